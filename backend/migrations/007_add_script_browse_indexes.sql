@@ -0,0 +1,10 @@
+-- Browse/listing indexes for the scripts table (Postgres variant).
+--
+-- The marketplace list endpoints filter by category and order by created_at
+-- (newest first); trending orders by downloads then rating, featured by rating
+-- then downloads. Without these every browse request is a full table scan.
+
+CREATE INDEX IF NOT EXISTS idx_scripts_category_created ON scripts(category, created_at);
+CREATE INDEX IF NOT EXISTS idx_scripts_created_at ON scripts(created_at);
+CREATE INDEX IF NOT EXISTS idx_scripts_downloads ON scripts(downloads, rating);
+CREATE INDEX IF NOT EXISTS idx_scripts_rating ON scripts(rating, downloads);
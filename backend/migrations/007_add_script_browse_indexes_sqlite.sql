@@ -0,0 +1,9 @@
+-- Browse/listing indexes for the scripts table (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 007_add_script_browse_indexes.sql for the Postgres twin and rationale.
+
+CREATE INDEX IF NOT EXISTS idx_scripts_category_created ON scripts(category, created_at);
+CREATE INDEX IF NOT EXISTS idx_scripts_created_at ON scripts(created_at);
+CREATE INDEX IF NOT EXISTS idx_scripts_downloads ON scripts(downloads, rating);
+CREATE INDEX IF NOT EXISTS idx_scripts_rating ON scripts(rating, downloads);
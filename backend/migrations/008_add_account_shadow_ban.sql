@@ -0,0 +1,7 @@
+-- Shadow-ban flag for accounts (Postgres variant).
+--
+-- Set by the admin shadow-ban endpoint. While non-NULL, the account's scripts
+-- and reviews are left out of every public listing, search and aggregate, but
+-- are still served by id.
+
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS shadow_banned_at TIMESTAMPTZ;
@@ -0,0 +1,6 @@
+-- Shadow-ban flag for accounts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migration). See 008_add_account_shadow_ban.sql for the Postgres twin.
+
+ALTER TABLE accounts ADD COLUMN shadow_banned_at TEXT;
@@ -0,0 +1,21 @@
+-- Author stats webhooks (Postgres variant).
+--
+-- One webhook endpoint per account. The background webhook job POSTs a daily
+-- stats snapshot and a one-off event whenever a script crosses a download
+-- milestone; `script_download_milestones` remembers which milestones have
+-- already been announced so each fires once per script.
+
+CREATE TABLE IF NOT EXISTS author_webhooks (
+    account_id TEXT PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS script_download_milestones (
+    script_id TEXT NOT NULL,
+    milestone BIGINT NOT NULL,
+    reached_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (script_id, milestone)
+);
@@ -0,0 +1,20 @@
+-- Author stats webhooks (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 009_create_author_webhooks.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS author_webhooks (
+    account_id TEXT PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS script_download_milestones (
+    script_id TEXT NOT NULL,
+    milestone INTEGER NOT NULL,
+    reached_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, milestone)
+);
@@ -0,0 +1,17 @@
+-- Search query log for curator analytics (Postgres variant).
+--
+-- One row per first-page search with a non-empty query. Only the normalised
+-- query text (trimmed, lowercased, whitespace collapsed, capped at 100
+-- characters), the category filter and the total result count are kept —
+-- nothing that identifies the searcher. Rows older than the retention period
+-- are deleted by the cleanup job.
+
+CREATE TABLE IF NOT EXISTS search_log (
+    id BIGSERIAL PRIMARY KEY,
+    query TEXT NOT NULL,
+    category TEXT,
+    result_count INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_log_created ON search_log(created_at);
@@ -0,0 +1,14 @@
+-- Search query log for curator analytics (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 010_create_search_log.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS search_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    query TEXT NOT NULL,
+    category TEXT,
+    result_count INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_log_created ON search_log(created_at);
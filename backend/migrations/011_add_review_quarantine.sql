@@ -0,0 +1,9 @@
+-- Review quarantine (Postgres variant).
+--
+-- Set when the review spam heuristics hold a review back for moderation
+-- (key bursts, comments copied across scripts, new accounts handing out
+-- 5-star ratings). While non-NULL the review is left out of listings and
+-- rating aggregates. A moderator either clears it or deletes the review.
+
+ALTER TABLE reviews ADD COLUMN IF NOT EXISTS quarantined_at TIMESTAMPTZ;
+ALTER TABLE reviews ADD COLUMN IF NOT EXISTS quarantine_reason TEXT;
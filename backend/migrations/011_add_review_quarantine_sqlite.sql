@@ -0,0 +1,7 @@
+-- Review quarantine (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migrations). See 011_add_review_quarantine.sql for the Postgres twin.
+
+ALTER TABLE reviews ADD COLUMN quarantined_at TEXT;
+ALTER TABLE reviews ADD COLUMN quarantine_reason TEXT;
@@ -0,0 +1,11 @@
+-- Weighted (Bayesian average) rating for scripts (Postgres variant).
+--
+-- `rating` stays the plain average shown to users. `weighted_rating` pulls
+-- it towards a prior of 3.0 stars worth 5 reviews, so a script with a single
+-- 5-star review no longer outranks established ones. Search sorting by
+-- rating, trending and featured order by it. 0 means unreviewed. Existing
+-- rows are backfilled by the application on startup.
+
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS weighted_rating DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_scripts_weighted_rating ON scripts(weighted_rating, downloads);
@@ -0,0 +1,9 @@
+-- Weighted (Bayesian average) rating for scripts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`, which also backfills
+-- reviewed rows. See 012_add_script_weighted_rating.sql for the Postgres
+-- twin.
+
+ALTER TABLE scripts ADD COLUMN weighted_rating REAL NOT NULL DEFAULT 0.0;
+
+CREATE INDEX IF NOT EXISTS idx_scripts_weighted_rating ON scripts(weighted_rating, downloads);
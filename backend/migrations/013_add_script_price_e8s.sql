@@ -0,0 +1,12 @@
+-- Integer script prices (Postgres variant).
+--
+-- `price_e8s` is the price in e8s (10^-8) of `currency` (ICP or ckBTC). The
+-- float `price` column stays for older clients and is kept equal to
+-- price_e8s / 10^8 by every write. Existing prices are converted once,
+-- rounding to the nearest e8.
+
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS price_e8s BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'ICP';
+
+UPDATE scripts SET price_e8s = ROUND(price * 100000000)::BIGINT
+WHERE price > 0 AND price_e8s = 0;
@@ -0,0 +1,11 @@
+-- Integer script prices (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migrations plus a one-off backfill). See 013_add_script_price_e8s.sql for
+-- the Postgres twin.
+
+ALTER TABLE scripts ADD COLUMN price_e8s INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE scripts ADD COLUMN currency TEXT NOT NULL DEFAULT 'ICP';
+
+UPDATE scripts SET price_e8s = CAST(ROUND(price * 100000000) AS INTEGER)
+WHERE price > 0 AND price_e8s = 0;
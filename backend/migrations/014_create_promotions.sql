@@ -0,0 +1,21 @@
+-- Script promotions (Postgres variant).
+--
+-- An owner-scheduled discount on one paid script, live during
+-- [starts_at, ends_at). `kind` is 'percent' (amount = whole percent, 1-100)
+-- or 'fixed' (amount = e8s of `currency`, the script's currency when the
+-- promotion was created). Windows of one script never overlap; the service
+-- checks that before inserting.
+
+CREATE TABLE IF NOT EXISTS promotions (
+    id VARCHAR(64) PRIMARY KEY,
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL,
+    amount BIGINT NOT NULL,
+    currency VARCHAR(16) NOT NULL,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_promotions_script_window
+    ON promotions(script_id, starts_at, ends_at);
@@ -0,0 +1,20 @@
+-- Script promotions (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 014_create_promotions.sql for the Postgres twin and column meanings.
+-- Times are RFC 3339 UTC text, compared as strings.
+
+CREATE TABLE IF NOT EXISTS promotions (
+    id TEXT PRIMARY KEY,
+    script_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    starts_at TEXT NOT NULL,
+    ends_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_promotions_script_window
+    ON promotions(script_id, starts_at, ends_at);
@@ -0,0 +1,35 @@
+-- Purchase disputes and refunds (Postgres variant).
+--
+-- A purchaser can dispute a `completed` purchase; an admin then either
+-- rejects the dispute (back to `completed`) or records a refund
+-- (`refunded`). A refund names the ledger block of the transfer that paid
+-- the money back; the backend checks that block on the ledger before
+-- accepting it, and the unique index stops one transfer from settling two
+-- purchases.
+--
+-- Allowed transitions: completed -> disputed, disputed -> completed,
+-- disputed -> refunded. `purchase_status_history` keeps one row per
+-- transition with the actor (`account:<id>` or `admin`).
+
+ALTER TABLE purchases ADD COLUMN IF NOT EXISTS dispute_reason TEXT;
+ALTER TABLE purchases ADD COLUMN IF NOT EXISTS disputed_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE purchases ADD COLUMN IF NOT EXISTS refunded_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE purchases ADD COLUMN IF NOT EXISTS refund_block_index BIGINT;
+ALTER TABLE purchases ADD COLUMN IF NOT EXISTS refund_amount_e8s BIGINT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_purchases_refund_block
+    ON purchases(refund_block_index) WHERE refund_block_index IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_purchases_status ON purchases(status, disputed_at);
+
+CREATE TABLE IF NOT EXISTS purchase_status_history (
+    id VARCHAR(64) PRIMARY KEY,
+    purchase_id VARCHAR(64) NOT NULL REFERENCES purchases(id),
+    from_status VARCHAR(32) NOT NULL,
+    to_status VARCHAR(32) NOT NULL,
+    actor VARCHAR(128) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_status_history_purchase
+    ON purchase_status_history(purchase_id, created_at);
@@ -0,0 +1,28 @@
+-- Purchase disputes and refunds (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (the ADD COLUMNs go
+-- through `apply_add_column_migration`, so re-runs are no-ops). See
+-- 015_add_purchase_disputes.sql for the Postgres twin and the state machine.
+
+ALTER TABLE purchases ADD COLUMN dispute_reason TEXT;
+ALTER TABLE purchases ADD COLUMN disputed_at TEXT;
+ALTER TABLE purchases ADD COLUMN refunded_at TEXT;
+ALTER TABLE purchases ADD COLUMN refund_block_index INTEGER;
+ALTER TABLE purchases ADD COLUMN refund_amount_e8s INTEGER;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_purchases_refund_block
+    ON purchases(refund_block_index) WHERE refund_block_index IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_purchases_status ON purchases(status, disputed_at);
+
+CREATE TABLE IF NOT EXISTS purchase_status_history (
+    id TEXT PRIMARY KEY,
+    purchase_id TEXT NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    note TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_purchase_status_history_purchase
+    ON purchase_status_history(purchase_id, created_at);
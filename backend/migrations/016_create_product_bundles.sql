@@ -0,0 +1,33 @@
+-- Bundle products (Postgres variant).
+--
+-- An author groups several of their own scripts and sells them together for
+-- one price (`price_e8s` of `currency`). Members live in
+-- product_bundle_scripts, ordered by `position`. Buying a bundle writes one
+-- purchases row per member script, each tagged with the bundle's id, so the
+-- usual per-script entitlement checks cover bundle buyers too.
+
+CREATE TABLE IF NOT EXISTS product_bundles (
+    id VARCHAR(64) PRIMARY KEY,
+    owner_account_id VARCHAR(64) NOT NULL REFERENCES accounts(id),
+    title VARCHAR(200) NOT NULL,
+    description TEXT NOT NULL,
+    price_e8s BIGINT NOT NULL,
+    currency VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_bundles_owner
+    ON product_bundles(owner_account_id);
+
+CREATE TABLE IF NOT EXISTS product_bundle_scripts (
+    bundle_id VARCHAR(64) NOT NULL REFERENCES product_bundles(id) ON DELETE CASCADE,
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (bundle_id, script_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_bundle_scripts_script
+    ON product_bundle_scripts(script_id);
+
+ALTER TABLE purchases ADD COLUMN IF NOT EXISTS bundle_id VARCHAR(64);
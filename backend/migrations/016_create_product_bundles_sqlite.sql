@@ -0,0 +1,34 @@
+-- Bundle products (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 016_create_product_bundles.sql for the Postgres twin and column meanings.
+-- The purchases column is added through `apply_add_column_migration`.
+
+CREATE TABLE IF NOT EXISTS product_bundles (
+    id TEXT PRIMARY KEY,
+    owner_account_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    price_e8s INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (owner_account_id) REFERENCES accounts(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_bundles_owner
+    ON product_bundles(owner_account_id);
+
+CREATE TABLE IF NOT EXISTS product_bundle_scripts (
+    bundle_id TEXT NOT NULL,
+    script_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (bundle_id, script_id),
+    FOREIGN KEY (bundle_id) REFERENCES product_bundles(id) ON DELETE CASCADE,
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_bundle_scripts_script
+    ON product_bundle_scripts(script_id);
+
+ALTER TABLE purchases ADD COLUMN bundle_id TEXT;
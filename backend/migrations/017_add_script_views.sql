@@ -0,0 +1,10 @@
+-- Script detail views (Postgres variant).
+--
+-- `views` counts `GET /api/v1/scripts/:id` reads, deduplicated per viewer
+-- (client IP and, when sent, public key) over a sliding window in the API
+-- process, so reloads and scrapers do not inflate it. Trending can rank by it
+-- (`?by=views`) separately from downloads.
+
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS views INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_scripts_views ON scripts(views, downloads);
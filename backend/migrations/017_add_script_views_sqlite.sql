@@ -0,0 +1,8 @@
+-- Script detail views (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migration). See 017_add_script_views.sql for the Postgres twin.
+
+ALTER TABLE scripts ADD COLUMN views INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_scripts_views ON scripts(views, downloads);
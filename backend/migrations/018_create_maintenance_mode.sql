@@ -0,0 +1,15 @@
+-- Read-only maintenance switch (Postgres variant).
+--
+-- Exactly one row (id = 1). While `enabled`, the API rejects writes with 503
+-- and `Retry-After: retry_after_secs`; reads keep working. Every instance
+-- polls this row, so flipping it once converges the whole fleet.
+
+CREATE TABLE IF NOT EXISTS maintenance_mode (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    message TEXT,
+    retry_after_secs INTEGER NOT NULL DEFAULT 300,
+    updated_at TIMESTAMP WITH TIME ZONE
+);
+
+INSERT INTO maintenance_mode (id) VALUES (1) ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,14 @@
+-- Read-only maintenance switch (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 018_create_maintenance_mode.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS maintenance_mode (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    enabled INTEGER NOT NULL DEFAULT 0,
+    message TEXT,
+    retry_after_secs INTEGER NOT NULL DEFAULT 300,
+    updated_at TEXT
+);
+
+INSERT OR IGNORE INTO maintenance_mode (id) VALUES (1);
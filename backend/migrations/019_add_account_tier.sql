@@ -0,0 +1,7 @@
+-- Plan tier for accounts (Postgres variant).
+--
+-- 'free' or 'pro'; set by the admin tier endpoint. The tier decides the
+-- account's quotas for private scripts, asset storage and API keys (see
+-- src/quotas.rs).
+
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS tier TEXT NOT NULL DEFAULT 'free';
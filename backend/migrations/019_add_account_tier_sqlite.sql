@@ -0,0 +1,6 @@
+-- Plan tier for accounts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migration). See 019_add_account_tier.sql for the Postgres twin.
+
+ALTER TABLE accounts ADD COLUMN tier TEXT NOT NULL DEFAULT 'free';
@@ -0,0 +1,10 @@
+-- Scheduled publication of drafts (Postgres variant).
+--
+-- Set through a signed script update. The scheduled publication job makes
+-- the draft public once `publish_at` has passed and clears the column;
+-- publishing by hand clears it too.
+
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_scripts_publish_at ON scripts(publish_at)
+    WHERE publish_at IS NOT NULL;
@@ -0,0 +1,11 @@
+-- Scheduled publication of drafts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migration). Values are UTC RFC 3339 with whole seconds and a `Z` suffix,
+-- so they compare correctly as text. See 020_add_script_publish_at.sql for
+-- the Postgres twin.
+
+ALTER TABLE scripts ADD COLUMN publish_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_scripts_publish_at ON scripts(publish_at)
+    WHERE publish_at IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Per-script release channels (Postgres variant).
+--
+-- The `stable` build of a script stays in `scripts`; this table holds the
+-- builds of the other channels (today only `beta`), one per script and
+-- channel, each with its own version. A push to a channel replaces its row.
+
+CREATE TABLE IF NOT EXISTS script_channel_releases (
+    script_id TEXT NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    channel TEXT NOT NULL,
+    version TEXT NOT NULL,
+    bundle TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (script_id, channel)
+);
@@ -0,0 +1,15 @@
+-- Per-script release channels (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 021_create_script_channel_releases.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_channel_releases (
+    script_id TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    version TEXT NOT NULL,
+    bundle TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, channel),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
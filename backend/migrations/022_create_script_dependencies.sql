@@ -0,0 +1,15 @@
+-- Dependencies between marketplace scripts (Postgres variant).
+--
+-- One row per script and required slug. `version_req` is a semver
+-- requirement matched against the `version` of the public scripts with that
+-- slug; it is checked when the script goes public, not when it is declared.
+
+CREATE TABLE IF NOT EXISTS script_dependencies (
+    script_id TEXT NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    dependency_slug TEXT NOT NULL,
+    version_req TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (script_id, dependency_slug)
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_dependencies_slug ON script_dependencies(dependency_slug);
@@ -0,0 +1,15 @@
+-- Dependencies between marketplace scripts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 022_create_script_dependencies.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_dependencies (
+    script_id TEXT NOT NULL,
+    dependency_slug TEXT NOT NULL,
+    version_req TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, dependency_slug),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_dependencies_slug ON script_dependencies(dependency_slug);
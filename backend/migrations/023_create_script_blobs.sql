@@ -0,0 +1,16 @@
+-- Content-addressed script bundles (Postgres variant).
+--
+-- Every distinct bundle is stored once, keyed by the hex SHA-256 of its
+-- text. `scripts` and `script_channel_releases` reference it through
+-- `bundle_sha256` and keep `bundle` empty; rows without a reference still
+-- carry their bundle inline until the startup backfill moves it here.
+
+CREATE TABLE IF NOT EXISTS script_blobs (
+    sha256 TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS bundle_sha256 TEXT;
+ALTER TABLE script_channel_releases ADD COLUMN IF NOT EXISTS bundle_sha256 TEXT;
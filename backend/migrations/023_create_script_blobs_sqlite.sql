@@ -0,0 +1,15 @@
+-- Content-addressed script bundles (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migrations), which then moves inline bundles into `script_blobs`. See
+-- 023_create_script_blobs.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_blobs (
+    sha256 TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+ALTER TABLE scripts ADD COLUMN bundle_sha256 TEXT;
+ALTER TABLE script_channel_releases ADD COLUMN bundle_sha256 TEXT;
@@ -0,0 +1,18 @@
+-- Key revocation list (Postgres variant).
+--
+-- One row per disabled account key, appended when the key is disabled (by
+-- its owner or an admin). `sequence` only ever grows, so a client that has
+-- seen sequence N fetches `?since=N` to catch up and can enforce the list
+-- offline in between. Keys disabled before this table existed are appended
+-- once at startup, oldest first.
+
+CREATE TABLE IF NOT EXISTS key_revocations (
+    sequence BIGSERIAL PRIMARY KEY,
+    key_id TEXT NOT NULL UNIQUE REFERENCES account_public_keys(id),
+    account_id TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    ic_principal TEXT NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_key_revocations_account ON key_revocations(account_id);
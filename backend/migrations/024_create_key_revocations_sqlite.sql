@@ -0,0 +1,17 @@
+-- Key revocation list (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 024_create_key_revocations.sql for the Postgres twin. AUTOINCREMENT keeps
+-- `sequence` from ever being reused.
+
+CREATE TABLE IF NOT EXISTS key_revocations (
+    sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+    key_id TEXT NOT NULL UNIQUE,
+    account_id TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    ic_principal TEXT NOT NULL,
+    revoked_at TEXT NOT NULL,
+    FOREIGN KEY (key_id) REFERENCES account_public_keys(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_key_revocations_account ON key_revocations(account_id);
@@ -0,0 +1,20 @@
+-- Public activity log behind account activity feeds (Postgres variant).
+--
+-- Appended when an account publishes a script, releases a new version of a
+-- public script or reviews a script. Reads filter out entries whose script
+-- is no longer public and reviews held for moderation, so nothing is deleted
+-- here when that happens. Scripts and reviews that predate the log are
+-- appended once at startup.
+
+CREATE TABLE IF NOT EXISTS account_activity (
+    id BIGSERIAL PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    script_id TEXT NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    version TEXT,
+    review_id TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_activity_account ON account_activity(account_id, id);
+CREATE INDEX IF NOT EXISTS idx_account_activity_script ON account_activity(script_id);
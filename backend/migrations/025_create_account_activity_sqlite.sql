@@ -0,0 +1,18 @@
+-- Public activity log behind account activity feeds (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 025_create_account_activity.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS account_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    script_id TEXT NOT NULL,
+    version TEXT,
+    review_id TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_activity_account ON account_activity(account_id, id);
+CREATE INDEX IF NOT EXISTS idx_account_activity_script ON account_activity(script_id);
@@ -0,0 +1,15 @@
+-- Author follows behind the followed-authors feed (Postgres variant).
+--
+-- One row per (follower, followed) pair. The feed joins this table to
+-- account_activity on followed_account_id, walking the activity log's
+-- (account_id, id) index once per followed author.
+
+CREATE TABLE IF NOT EXISTS follows (
+    follower_account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    followed_account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (follower_account_id, followed_account_id),
+    CHECK (follower_account_id <> followed_account_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_follows_followed ON follows(followed_account_id);
@@ -0,0 +1,16 @@
+-- Author follows behind the followed-authors feed (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 026_create_follows.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS follows (
+    follower_account_id TEXT NOT NULL,
+    followed_account_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (follower_account_id, followed_account_id),
+    CHECK (follower_account_id <> followed_account_id),
+    FOREIGN KEY (follower_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY (followed_account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_follows_followed ON follows(followed_account_id);
@@ -0,0 +1,7 @@
+-- Long-form script documentation (Postgres variant).
+--
+-- Markdown, set through a signed create or update and rendered at
+-- `GET /api/v1/scripts/:id/readme.html`. Each version row keeps the readme
+-- it was released with; NULL means the script has none.
+
+ALTER TABLE scripts ADD COLUMN IF NOT EXISTS readme TEXT;
@@ -0,0 +1,6 @@
+-- Long-form script documentation (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database` (idempotent column
+-- migration). See 027_add_script_readme.sql for the Postgres twin.
+
+ALTER TABLE scripts ADD COLUMN readme TEXT;
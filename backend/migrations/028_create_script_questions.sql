@@ -0,0 +1,21 @@
+-- Script Q&A (Postgres variant).
+--
+-- A question any account asks about a script, and the owner's answer. A
+-- question stays unanswered while `answer` is NULL; answering again replaces
+-- the answer and moves `answered_at`. The partial index serves the
+-- unanswered counts shown on the script detail and in the owner's feed.
+
+CREATE TABLE IF NOT EXISTS script_questions (
+    id VARCHAR(64) PRIMARY KEY,
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    question TEXT NOT NULL,
+    answer TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    answered_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_questions_script
+    ON script_questions(script_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_script_questions_unanswered
+    ON script_questions(script_id) WHERE answer IS NULL;
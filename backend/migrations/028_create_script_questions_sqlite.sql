@@ -0,0 +1,21 @@
+-- Script Q&A (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 028_create_script_questions.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_questions (
+    id TEXT PRIMARY KEY,
+    script_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    question TEXT NOT NULL,
+    answer TEXT,
+    created_at TEXT NOT NULL,
+    answered_at TEXT,
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_questions_script
+    ON script_questions(script_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_script_questions_unanswered
+    ON script_questions(script_id) WHERE answer IS NULL;
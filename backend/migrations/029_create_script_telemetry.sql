@@ -0,0 +1,16 @@
+-- Opt-in install/run telemetry, aggregated on arrival (Postgres variant).
+--
+-- One counter per (script, version, engine version, event, error class);
+-- `error_class` is '' for successes. Individual reports are never stored, so
+-- nothing here can be traced back to a user or device.
+
+CREATE TABLE IF NOT EXISTS script_telemetry (
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    version VARCHAR(64) NOT NULL,
+    engine_version VARCHAR(64) NOT NULL,
+    event VARCHAR(16) NOT NULL,
+    error_class VARCHAR(32) NOT NULL DEFAULT '',
+    count BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (script_id, version, engine_version, event, error_class)
+);
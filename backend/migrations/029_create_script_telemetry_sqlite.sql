@@ -0,0 +1,16 @@
+-- Opt-in install/run telemetry, aggregated on arrival (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 029_create_script_telemetry.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_telemetry (
+    script_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    engine_version TEXT NOT NULL,
+    event TEXT NOT NULL,
+    error_class TEXT NOT NULL DEFAULT '',
+    count INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, version, engine_version, event, error_class),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
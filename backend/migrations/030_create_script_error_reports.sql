@@ -0,0 +1,30 @@
+-- Script execution error reports and flagged versions (Postgres variant).
+--
+-- One row per distinct failure of a version, keyed by `stack_hash`, with
+-- the number of times it was reported. `script_version_flags` holds the
+-- versions whose run failure rate spiked; a version is flagged once.
+
+CREATE TABLE IF NOT EXISTS script_error_reports (
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    version VARCHAR(64) NOT NULL,
+    stack_hash CHAR(64) NOT NULL,
+    error_class VARCHAR(32) NOT NULL,
+    message TEXT NOT NULL,
+    stack TEXT NOT NULL,
+    engine_version VARCHAR(64) NOT NULL,
+    occurrences BIGINT NOT NULL DEFAULT 1,
+    first_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (script_id, version, stack_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_error_reports_last_seen
+    ON script_error_reports(script_id, last_seen_at);
+
+CREATE TABLE IF NOT EXISTS script_version_flags (
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    version VARCHAR(64) NOT NULL,
+    reason TEXT NOT NULL,
+    flagged_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (script_id, version)
+);
@@ -0,0 +1,31 @@
+-- Script execution error reports and flagged versions (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 030_create_script_error_reports.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_error_reports (
+    script_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    stack_hash TEXT NOT NULL,
+    error_class TEXT NOT NULL,
+    message TEXT NOT NULL,
+    stack TEXT NOT NULL,
+    engine_version TEXT NOT NULL,
+    occurrences INTEGER NOT NULL DEFAULT 1,
+    first_seen_at TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, version, stack_hash),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_script_error_reports_last_seen
+    ON script_error_reports(script_id, last_seen_at);
+
+CREATE TABLE IF NOT EXISTS script_version_flags (
+    script_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    flagged_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, version),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+-- Compatibility votes (Postgres variant).
+--
+-- "Works for me" or "broken" from one account for a script on one app
+-- version, optionally against one canister (`canister_id` is '' when the
+-- vote is about the app version alone). Voting again replaces the vote.
+
+CREATE TABLE IF NOT EXISTS compatibility_votes (
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    app_version VARCHAR(64) NOT NULL,
+    canister_id VARCHAR(64) NOT NULL DEFAULT '',
+    works BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (script_id, account_id, app_version, canister_id)
+);
@@ -0,0 +1,17 @@
+-- Compatibility votes (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 031_create_compatibility_votes.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS compatibility_votes (
+    script_id TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    app_version TEXT NOT NULL,
+    canister_id TEXT NOT NULL DEFAULT '',
+    works INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (script_id, account_id, app_version, canister_id),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,27 @@
+-- Moderation notes and strikes on accounts (Postgres variant).
+--
+-- Notes are free text for moderators. A strike counts toward the account's
+-- restriction until `revoked_at` is set; revoked strikes stay for the
+-- record. See `crate::moderation` for the thresholds.
+
+CREATE TABLE IF NOT EXISTS moderation_notes (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    note TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_notes_account
+    ON moderation_notes(account_id, created_at);
+
+CREATE TABLE IF NOT EXISTS account_strikes (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    revoke_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_strikes_active
+    ON account_strikes(account_id) WHERE revoked_at IS NULL;
@@ -0,0 +1,28 @@
+-- Moderation notes and strikes on accounts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 032_create_account_moderation.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS moderation_notes (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    note TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_notes_account
+    ON moderation_notes(account_id, created_at);
+
+CREATE TABLE IF NOT EXISTS account_strikes (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    revoked_at TEXT,
+    revoke_reason TEXT,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_strikes_active
+    ON account_strikes(account_id) WHERE revoked_at IS NULL;
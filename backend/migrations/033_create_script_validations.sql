@@ -0,0 +1,16 @@
+-- Re-validation results of public scripts (Postgres variant).
+--
+-- One row per script: the result of checking `version` under the engine's
+-- `rules_version`. `errors` holds one message per line, empty when the
+-- script passed; `failing_since` is when it started failing. A script is
+-- checked again once its version or the rules version changes. See
+-- `crate::revalidation`.
+
+CREATE TABLE IF NOT EXISTS script_validations (
+    script_id VARCHAR(64) PRIMARY KEY REFERENCES scripts(id) ON DELETE CASCADE,
+    version TEXT NOT NULL,
+    rules_version INTEGER NOT NULL,
+    errors TEXT NOT NULL,
+    failing_since TIMESTAMP WITH TIME ZONE,
+    checked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
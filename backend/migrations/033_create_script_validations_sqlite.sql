@@ -0,0 +1,14 @@
+-- Re-validation results of public scripts (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 033_create_script_validations.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS script_validations (
+    script_id TEXT PRIMARY KEY,
+    version TEXT NOT NULL,
+    rules_version INTEGER NOT NULL,
+    errors TEXT NOT NULL,
+    failing_since TEXT,
+    checked_at TEXT NOT NULL,
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,28 @@
+-- Search ranking experiment logs (Postgres variant).
+--
+-- `search_exposures` has one row per search ranked by an experiment: the
+-- variant served, a SHA-256 of the client id (never the id itself) and the
+-- script ids shown, one per line, starting at `result_offset`.
+-- `search_clicks` records the first click on each shown script. See
+-- `crate::search_experiments`.
+
+CREATE TABLE IF NOT EXISTS search_exposures (
+    id VARCHAR(64) PRIMARY KEY,
+    experiment TEXT NOT NULL,
+    variant TEXT NOT NULL,
+    client_hash TEXT NOT NULL,
+    result_ids TEXT NOT NULL,
+    result_offset INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_exposures_experiment
+    ON search_exposures(experiment, created_at);
+
+CREATE TABLE IF NOT EXISTS search_clicks (
+    exposure_id VARCHAR(64) NOT NULL REFERENCES search_exposures(id) ON DELETE CASCADE,
+    script_id VARCHAR(64) NOT NULL,
+    position INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (exposure_id, script_id)
+);
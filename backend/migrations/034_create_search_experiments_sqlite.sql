@@ -0,0 +1,26 @@
+-- Search ranking experiment logs (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 034_create_search_experiments.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS search_exposures (
+    id TEXT PRIMARY KEY,
+    experiment TEXT NOT NULL,
+    variant TEXT NOT NULL,
+    client_hash TEXT NOT NULL,
+    result_ids TEXT NOT NULL,
+    result_offset INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_exposures_experiment
+    ON search_exposures(experiment, created_at);
+
+CREATE TABLE IF NOT EXISTS search_clicks (
+    exposure_id TEXT NOT NULL,
+    script_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (exposure_id, script_id),
+    FOREIGN KEY (exposure_id) REFERENCES search_exposures(id) ON DELETE CASCADE
+);
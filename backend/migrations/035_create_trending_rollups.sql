@@ -0,0 +1,16 @@
+-- Per-category and per-tag trending lists (Postgres variant).
+--
+-- Rebuilt as a whole by the trending rollup job: for each signal
+-- (`downloads`, `views`), the top public scripts of every category
+-- (`scope = 'category'`) and every tag (`scope = 'tag'`), `rank` starting at
+-- 1. See `crate::trending`.
+
+CREATE TABLE IF NOT EXISTS trending_rollups (
+    scope TEXT NOT NULL,
+    scope_key TEXT NOT NULL,
+    signal TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    script_id VARCHAR(64) NOT NULL REFERENCES scripts(id) ON DELETE CASCADE,
+    computed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (scope, scope_key, signal, rank)
+);
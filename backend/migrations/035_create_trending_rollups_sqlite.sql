@@ -0,0 +1,15 @@
+-- Per-category and per-tag trending lists (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 035_create_trending_rollups.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS trending_rollups (
+    scope TEXT NOT NULL,
+    scope_key TEXT NOT NULL,
+    signal TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    script_id TEXT NOT NULL,
+    computed_at TEXT NOT NULL,
+    PRIMARY KEY (scope, scope_key, signal, rank),
+    FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+);
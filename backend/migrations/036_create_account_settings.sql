@@ -0,0 +1,13 @@
+-- Account-level preferences (Postgres variant).
+--
+-- At most one row per account; an account without one has the defaults.
+-- `muted_events` is a JSON array of webhook event names. See
+-- `crate::account_settings`.
+
+CREATE TABLE IF NOT EXISTS account_settings (
+    account_id VARCHAR(64) PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+    default_visibility TEXT NOT NULL DEFAULT 'public',
+    required_signature_scheme TEXT NOT NULL DEFAULT 'any',
+    muted_events TEXT NOT NULL DEFAULT '[]',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
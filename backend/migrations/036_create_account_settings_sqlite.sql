@@ -0,0 +1,13 @@
+-- Account-level preferences (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 036_create_account_settings.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS account_settings (
+    account_id TEXT PRIMARY KEY,
+    default_visibility TEXT NOT NULL DEFAULT 'public',
+    required_signature_scheme TEXT NOT NULL DEFAULT 'any',
+    muted_events TEXT NOT NULL DEFAULT '[]',
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
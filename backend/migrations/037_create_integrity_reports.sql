@@ -0,0 +1,17 @@
+-- Results of the data integrity checker (Postgres variant).
+--
+-- One row per run, scheduled or admin-triggered. `report` is the JSON
+-- report as `GET /api/v1/admin/integrity-reports` returns it. See
+-- `crate::integrity`.
+
+CREATE TABLE IF NOT EXISTS integrity_reports (
+    id VARCHAR(64) PRIMARY KEY,
+    trigger TEXT NOT NULL,
+    repaired BOOLEAN NOT NULL,
+    issue_count INTEGER NOT NULL,
+    report TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_integrity_reports_created
+    ON integrity_reports(created_at);
@@ -0,0 +1,16 @@
+-- Results of the data integrity checker (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 037_create_integrity_reports.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS integrity_reports (
+    id TEXT PRIMARY KEY,
+    trigger TEXT NOT NULL,
+    repaired INTEGER NOT NULL,
+    issue_count INTEGER NOT NULL,
+    report TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_integrity_reports_created
+    ON integrity_reports(created_at);
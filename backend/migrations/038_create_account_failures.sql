@@ -0,0 +1,18 @@
+-- Signed requests the signature gate rejected (Postgres variant).
+--
+-- Only requests whose key resolved to an account are kept, at most
+-- `MAX_RECORDED_FAILURES` per account, for
+-- `GET /api/v1/admin/accounts/:username/overview`. See
+-- `signature_gate::verify_signed_account_request`.
+
+CREATE TABLE IF NOT EXISTS account_failures (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_failures_account
+    ON account_failures(account_id, created_at);
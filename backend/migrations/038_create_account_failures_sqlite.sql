@@ -0,0 +1,17 @@
+-- Signed requests the signature gate rejected (SQLite variant).
+--
+-- Applied at startup by `db::initialize_database`. See
+-- 038_create_account_failures.sql for the Postgres twin.
+
+CREATE TABLE IF NOT EXISTS account_failures (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_failures_account
+    ON account_failures(account_id, created_at);
@@ -0,0 +1,97 @@
+#!/bin/bash
+# Check the deploy host and backend/.env before a production deploy.
+# Prints one line per check with the fix for anything that fails; exits 1 if
+# any check failed. Read-only: it never starts, stops or builds anything.
+set -u
+
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+YELLOW='\033[1;33m'
+NC='\033[0m'
+
+cd "$(dirname "$0")/.."
+
+failures=0
+pass() { echo -e "${GREEN}✓${NC} $1"; }
+fail() { echo -e "${RED}✗${NC} $1"; echo "    fix: $2"; failures=$((failures + 1)); }
+warn() { echo -e "${YELLOW}⚠${NC} $1"; echo "    hint: $2"; }
+
+# --- tools -------------------------------------------------------------------
+if docker info >/dev/null 2>&1; then
+    pass "Docker daemon reachable"
+else
+    fail "Docker daemon not reachable" "install Docker and start the daemon (docker info must succeed)"
+fi
+if docker compose version >/dev/null 2>&1; then
+    pass "Docker Compose v2 available"
+else
+    fail "Docker Compose v2 missing" "install the compose plugin: https://docs.docker.com/compose/install/"
+fi
+if command -v sqlite3 >/dev/null 2>&1; then
+    pass "sqlite3 CLI available (backups)"
+else
+    warn "sqlite3 CLI missing" "install sqlite3 to use just docker-prod-backup / docker-prod-restore"
+fi
+if [ -x ../target/release/icp-marketplace-api ]; then
+    pass "Release binary built (target/release/icp-marketplace-api)"
+else
+    fail "Release binary missing" "cargo build --release (the image copies the host-built binary)"
+fi
+
+# --- data dir ----------------------------------------------------------------
+if [ -d data ] && [ -w data ]; then
+    pass "backend/data exists and is writable"
+else
+    fail "backend/data missing or not writable" "mkdir -p backend/data && chmod 777 backend/data"
+fi
+
+# --- .env --------------------------------------------------------------------
+if [ ! -f .env ]; then
+    fail "backend/.env missing" "cp backend/.env.tunnel.example backend/.env and fill it in"
+else
+    pass "backend/.env present"
+    # shellcheck disable=SC1091
+    set -a; . ./.env; set +a
+
+    if [ -z "${TUNNEL_TOKEN:-}" ] || [ "$TUNNEL_TOKEN" = "your_tunnel_token_here" ]; then
+        fail "TUNNEL_TOKEN unset or placeholder" "copy the token from Zero Trust > Networks > Tunnels"
+    else
+        pass "TUNNEL_TOKEN set"
+    fi
+    if [ -z "${ADMIN_TOKEN:-}" ] || [ "$ADMIN_TOKEN" = "change-me-in-production" ]; then
+        fail "ADMIN_TOKEN unset or the public default" "set ADMIN_TOKEN=\$(openssl rand -hex 32)"
+    elif [ "${#ADMIN_TOKEN}" -lt 32 ]; then
+        warn "ADMIN_TOKEN is only ${#ADMIN_TOKEN} characters" "use at least 32 random characters"
+    else
+        pass "ADMIN_TOKEN set"
+    fi
+
+    rp_id="${WEBAUTHN_RP_ID:-icp-mp.kalaj.org}"
+    rp_origin="${WEBAUTHN_RP_ORIGIN:-https://icp-mp.kalaj.org}"
+    case "$rp_id" in
+        localhost|127.0.0.1)
+            fail "WEBAUTHN_RP_ID is $rp_id" "set WEBAUTHN_RP_ID to the public host (passkeys break otherwise)" ;;
+        *)
+            if [ "$rp_origin" != "https://$rp_id" ]; then
+                fail "WEBAUTHN_RP_ORIGIN ($rp_origin) does not match https://$rp_id" \
+                    "set WEBAUTHN_RP_ORIGIN=https://$rp_id (scheme + host, no port)"
+            else
+                pass "WebAuthn RP $rp_id / $rp_origin"
+            fi ;;
+    esac
+fi
+
+# --- compose -----------------------------------------------------------------
+if docker compose -f docker-compose.prod.yml config --quiet 2>/dev/null; then
+    pass "docker-compose.prod.yml resolves"
+else
+    fail "docker-compose.prod.yml does not resolve" "run: docker compose -f docker-compose.prod.yml config"
+fi
+
+echo
+if [ "$failures" -eq 0 ]; then
+    echo -e "${GREEN}Ready to deploy.${NC}"
+else
+    echo -e "${RED}$failures check(s) failed.${NC}"
+    exit 1
+fi
@@ -0,0 +1,78 @@
+#!/bin/bash
+# Lightweight uptime probe for the marketplace API.
+#
+# Usage: prod-monitor.sh [base_url] [interval_seconds] [max_failures]
+#
+# Every interval it probes /health, /marketplace-stats and a search, printing
+# one line per round with per-endpoint latency. After max_failures consecutive
+# failed rounds it POSTs a JSON alert to $MONITOR_WEBHOOK_URL (if set) and
+# exits 1. Ctrl+C prints p50/p95 latency for the session.
+set -u
+
+BASE_URL="${1:-https://icp-mp.kalaj.org}"
+INTERVAL="${2:-60}"
+MAX_FAILURES="${3:-3}"
+BASE_URL="${BASE_URL%/}"
+
+latencies=()
+consecutive=0
+
+# probe <name> <curl args...>  -> prints "name=XXXms" or "name=FAIL(code)"
+probe() {
+    local name=$1; shift
+    local out code secs
+    out=$(curl -s -o /dev/null -w '%{http_code} %{time_total}' --max-time 10 "$@") || out="000 0"
+    code=${out%% *}
+    secs=${out##* }
+    if [[ "$code" == 2* ]]; then
+        local ms
+        ms=$(awk -v s="$secs" 'BEGIN { printf "%d", s * 1000 }')
+        latencies+=("$ms")
+        echo -n "$name=${ms}ms "
+        return 0
+    fi
+    echo -n "$name=FAIL($code) "
+    return 1
+}
+
+summary() {
+    echo
+    if [ ${#latencies[@]} -eq 0 ]; then
+        echo "no successful probes"
+        exit 0
+    fi
+    sorted=$(printf '%s\n' "${latencies[@]}" | sort -n)
+    n=${#latencies[@]}
+    p50=$(echo "$sorted" | sed -n "$(( (n + 1) / 2 ))p")
+    p95=$(echo "$sorted" | sed -n "$(( (n * 95 + 99) / 100 ))p")
+    echo "probes=$n p50=${p50}ms p95=${p95}ms"
+    exit 0
+}
+trap summary INT TERM
+
+echo "==> Monitoring $BASE_URL every ${INTERVAL}s (alert after $MAX_FAILURES failed rounds)"
+while true; do
+    echo -n "$(date -u +%FT%TZ) "
+    ok=1
+    probe health "$BASE_URL/api/v1/health" || ok=0
+    probe stats "$BASE_URL/api/v1/marketplace-stats" || ok=0
+    probe search -X POST -H 'Content-Type: application/json' \
+        -d '{"query":"","limit":1}' "$BASE_URL/api/v1/scripts/search" || ok=0
+    echo
+
+    if [ "$ok" -eq 1 ]; then
+        consecutive=0
+    else
+        consecutive=$((consecutive + 1))
+        if [ "$consecutive" -ge "$MAX_FAILURES" ]; then
+            msg="$BASE_URL failed $consecutive consecutive health rounds"
+            echo "❌ $msg" >&2
+            if [ -n "${MONITOR_WEBHOOK_URL:-}" ]; then
+                curl -s --max-time 10 -X POST -H 'Content-Type: application/json' \
+                    -d "{\"text\":\"$msg\"}" "$MONITOR_WEBHOOK_URL" >/dev/null || true
+            fi
+            exit 1
+        fi
+    fi
+    sleep "$INTERVAL"
+done
@@ -59,6 +59,30 @@ fi
 echo -e "${GREEN}✓${NC} Found .env file"
 echo
 
+# Refuse to deploy with missing or placeholder secrets. The API would still
+# boot (it only prints a banner), but the admin routes would be guarded by a
+# publicly-known token and the tunnel would never connect.
+missing_secrets=$(
+    set -a
+    # shellcheck disable=SC1091
+    . ./.env
+    [ -z "${TUNNEL_TOKEN:-}" ] || [ "$TUNNEL_TOKEN" = "your_tunnel_token_here" ] && echo "TUNNEL_TOKEN"
+    [ -z "${ADMIN_TOKEN:-}" ] || [ "$ADMIN_TOKEN" = "change-me-in-production" ] && echo "ADMIN_TOKEN"
+    true
+)
+if [ -n "$missing_secrets" ]; then
+    echo -e "${RED}Error: required secrets are unset or still placeholders in .env:${NC}"
+    for name in $missing_secrets; do
+        echo "  - $name"
+    done
+    echo
+    echo "Generate an admin token with: openssl rand -hex 32"
+    exit 1
+fi
+
+echo -e "${GREEN}✓${NC} Required secrets present (TUNNEL_TOKEN, ADMIN_TOKEN)"
+echo
+
 # Ensure data directory exists and is writable
 echo -e "${YELLOW}Preparing data directory...${NC}"
 mkdir -p data
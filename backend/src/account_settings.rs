@@ -0,0 +1,124 @@
+//! Account-level preferences.
+//!
+//! An account without stored settings has the defaults of each field:
+//! - `default_visibility`: whether a script uploaded without `is_public` is
+//!   public or a private draft. Default `public`.
+//! - `required_signature_scheme`: `any` by default. Set to `ed25519` or
+//!   `secp256k1`, only keys of that scheme may sign for the account: the
+//!   signed account routes, signed GETs and script uploads, updates and
+//!   channel pushes refuse other keys with 403. The scheme is told from the
+//!   key, see [`SignatureScheme::of_key`].
+//! - `muted_events`: webhook events of [`WEBHOOK_EVENTS`] the account's
+//!   webhook is not sent.
+
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use serde::{Deserialize, Serialize};
+
+/// Every event a webhook can be sent; see `webhook_delivery`.
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    "downloads.milestone",
+    "script.published",
+    "script.validation_failed",
+    "stats.daily",
+];
+
+/// Message of the 403 for a key the account's scheme does not allow.
+pub const SCHEME_NOT_ALLOWED: &str = "This account requires another signature scheme";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultVisibility {
+    #[default]
+    Public,
+    Private,
+}
+
+impl DefaultVisibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Private => "private",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "public" => Some(Self::Public),
+            "private" => Some(Self::Private),
+            _ => None,
+        }
+    }
+
+    pub fn is_public(self) -> bool {
+        self == Self::Public
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    #[default]
+    Any,
+    Ed25519,
+    Secp256k1,
+}
+
+impl SignatureScheme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Ed25519 => "ed25519",
+            Self::Secp256k1 => "secp256k1",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "any" => Some(Self::Any),
+            "ed25519" => Some(Self::Ed25519),
+            "secp256k1" => Some(Self::Secp256k1),
+            _ => None,
+        }
+    }
+
+    /// The scheme of a base64 public key, by its length: 32 bytes for
+    /// Ed25519, 33 (compressed) or 65 (uncompressed) SEC1 bytes for
+    /// secp256k1. `None` for anything else.
+    pub fn of_key(public_key: &str) -> Option<Self> {
+        match B64.decode(public_key).ok()?.len() {
+            32 => Some(Self::Ed25519),
+            33 | 65 => Some(Self::Secp256k1),
+            _ => None,
+        }
+    }
+
+    /// Whether `public_key` may sign for an account requiring `self`.
+    pub fn allows(self, public_key: &str) -> bool {
+        self == Self::Any || Self::of_key(public_key) == Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheme_is_read_from_the_key_length() {
+        let ed25519 = B64.encode([1u8; 32]);
+        let secp256k1 = B64.encode([2u8; 33]);
+        assert_eq!(
+            SignatureScheme::of_key(&ed25519),
+            Some(SignatureScheme::Ed25519)
+        );
+        assert_eq!(
+            SignatureScheme::of_key(&secp256k1),
+            Some(SignatureScheme::Secp256k1)
+        );
+        assert_eq!(SignatureScheme::of_key("not base64!"), None);
+
+        assert!(SignatureScheme::Any.allows("anything"));
+        assert!(SignatureScheme::Ed25519.allows(&ed25519));
+        assert!(!SignatureScheme::Ed25519.allows(&secp256k1));
+        assert!(!SignatureScheme::Secp256k1.allows("not base64!"));
+    }
+}
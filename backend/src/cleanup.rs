@@ -8,8 +8,25 @@ use tokio_util::sync::CancellationToken;
 /// Recommendation: 90 days per design spec, but can be increased for POC/testing
 const AUDIT_RETENTION_DAYS: i32 = 90;
 
+/// How long a soft-deleted script stays in the table (recoverable by hand by
+/// clearing `deleted_at`) before the cleanup job removes it and its reviews.
+/// Scripts with purchases are never purged: buyers keep their entitlement row.
+const SOFT_DELETE_RETENTION_DAYS: i32 = 30;
+
+/// Search log rows older than this are dropped; the admin analytics look
+/// back at most a year.
+const SEARCH_LOG_RETENTION_DAYS: i32 = 365;
+
+/// Unreferenced bundle blobs younger than this are kept, so a blob stored
+/// by a write whose row is not inserted yet is never collected.
+const ORPHAN_BLOB_GRACE_DAYS: i32 = 1;
+
 /// Background job that cleans up old signature audit records
-/// Runs daily and removes records older than AUDIT_RETENTION_DAYS
+/// Runs daily and removes records older than AUDIT_RETENTION_DAYS, purges
+/// soft-deleted scripts past SOFT_DELETE_RETENTION_DAYS, trims the search log
+/// and search experiment exposures to SEARCH_LOG_RETENTION_DAYS, drops bundle blobs nothing references any
+/// more, then lets SQLite
+/// refresh its query-planner statistics (`PRAGMA optimize`).
 ///
 /// `shutdown` is observed every iteration: cancelling it makes the job exit
 /// cleanly instead of running forever. Returns immediately after spawning the
@@ -41,6 +58,30 @@ async fn cleanup_loop(pool: SqlitePool, shutdown: CancellationToken) {
                         tracing::error!("Signature audit cleanup failed: {}", e);
                     }
                 }
+
+                match purge_soft_deleted_scripts(&pool).await {
+                    Ok(0) => {}
+                    Ok(purged) => {
+                        tracing::info!("Purged {} soft-deleted scripts", purged);
+                    }
+                    Err(e) => {
+                        tracing::error!("Soft-deleted script purge failed: {}", e);
+                    }
+                }
+
+                if let Err(e) = purge_old_search_log(&pool).await {
+                    tracing::error!("Search log purge failed: {}", e);
+                }
+
+                match purge_orphan_blobs(&pool).await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!("Purged {} unreferenced bundle blobs", purged),
+                    Err(e) => tracing::error!("Bundle blob purge failed: {}", e),
+                }
+
+                if let Err(e) = sqlx::query("PRAGMA optimize").execute(&pool).await {
+                    tracing::warn!("PRAGMA optimize failed: {}", e);
+                }
             }
             _ = shutdown.cancelled() => {
                 tracing::info!("cleanup job stopped");
@@ -70,6 +111,92 @@ async fn cleanup_old_audit_records(pool: &SqlitePool) -> Result<u64, sqlx::Error
     Ok(result.rows_affected())
 }
 
+/// Hard-deletes scripts soft-deleted more than SOFT_DELETE_RETENTION_DAYS ago,
+/// together with their reviews, promotions, bundle memberships, channel builds
+/// and dependency declarations. Returns the number of scripts removed.
+async fn purge_soft_deleted_scripts(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    const EXPIRED: &str = r#"
+        SELECT id FROM scripts
+        WHERE deleted_at IS NOT NULL
+          AND datetime(deleted_at) < datetime('now', '-' || ? || ' days')
+          AND id NOT IN (SELECT script_id FROM purchases)
+    "#;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!(
+        "DELETE FROM reviews WHERE script_id IN ({})",
+        EXPIRED
+    ))
+    .bind(SOFT_DELETE_RETENTION_DAYS)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(&format!(
+        "DELETE FROM promotions WHERE script_id IN ({})",
+        EXPIRED
+    ))
+    .bind(SOFT_DELETE_RETENTION_DAYS)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(&format!(
+        "DELETE FROM product_bundle_scripts WHERE script_id IN ({})",
+        EXPIRED
+    ))
+    .bind(SOFT_DELETE_RETENTION_DAYS)
+    .execute(&mut *tx)
+    .await?;
+    for table in ["script_channel_releases", "script_dependencies"] {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE script_id IN ({})",
+            table, EXPIRED
+        ))
+        .bind(SOFT_DELETE_RETENTION_DAYS)
+        .execute(&mut *tx)
+        .await?;
+    }
+    let result = sqlx::query(&format!("DELETE FROM scripts WHERE id IN ({})", EXPIRED))
+        .bind(SOFT_DELETE_RETENTION_DAYS)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Deletes bundle blobs that no script or channel build references and that
+/// are older than ORPHAN_BLOB_GRACE_DAYS.
+async fn purge_orphan_blobs(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM script_blobs
+        WHERE datetime(created_at) < datetime('now', '-' || ? || ' days')
+          AND sha256 NOT IN (SELECT bundle_sha256 FROM scripts WHERE bundle_sha256 IS NOT NULL)
+          AND sha256 NOT IN (
+              SELECT bundle_sha256 FROM script_channel_releases WHERE bundle_sha256 IS NOT NULL
+          )
+        "#,
+    )
+    .bind(ORPHAN_BLOB_GRACE_DAYS)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Deletes search log rows and ranking experiment exposures (their clicks
+/// go with them) older than SEARCH_LOG_RETENTION_DAYS.
+async fn purge_old_search_log(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let mut purged = 0;
+    for table in ["search_log", "search_exposures"] {
+        let result = sqlx::query(&format!(
+            "DELETE FROM {table} WHERE datetime(created_at) < datetime('now', '-' || ? || ' days')"
+        ))
+        .bind(SEARCH_LOG_RETENTION_DAYS)
+        .execute(pool)
+        .await?;
+        purged += result.rows_affected();
+    }
+    Ok(purged)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,6 +344,96 @@ mod tests {
         assert_eq!(exists, 1);
     }
 
+    async fn insert_script(pool: &SqlitePool, id: &str, deleted_days_ago: Option<i64>) {
+        let deleted_at =
+            deleted_days_ago.map(|days| (Utc::now() - chrono::Duration::days(days)).to_rfc3339());
+        sqlx::query(
+            r#"
+            INSERT INTO scripts (id, slug, title, description, category, bundle, created_at, updated_at, deleted_at)
+            VALUES (?, ?, 'Title', 'Desc', 'utility', 'bundle', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', ?)
+            "#,
+        )
+        .bind(id)
+        .bind(id)
+        .bind(deleted_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_purge_soft_deleted_scripts_respects_retention_and_purchases() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "live", None).await;
+        insert_script(&pool, "recent", Some(5)).await;
+        insert_script(&pool, "expired", Some(31)).await;
+        insert_script(&pool, "expired-purchased", Some(31)).await;
+
+        sqlx::query(
+            r#"
+            INSERT INTO reviews (id, script_id, user_id, rating, created_at, updated_at)
+            VALUES ('r1', 'expired', 'u1', 5, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO purchases (id, account_id, script_id, usd_amount, status, paid_at, created_at)
+            VALUES ('p1', 'a1', 'expired-purchased', 1.0, 'completed', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(purge_soft_deleted_scripts(&pool).await.unwrap(), 1);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT id FROM scripts ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["expired-purchased", "live", "recent"]);
+        let reviews: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reviews")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(reviews, 0);
+    }
+
+    #[tokio::test]
+    async fn test_purge_orphan_blobs_keeps_referenced_and_fresh_blobs() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "live", None).await;
+        sqlx::query("UPDATE scripts SET bundle_sha256 = 'referenced' WHERE id = 'live'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let old = (Utc::now() - chrono::Duration::days(2)).to_rfc3339();
+        let now = Utc::now().to_rfc3339();
+        for (sha256, created_at) in [("referenced", &old), ("orphan", &old), ("fresh", &now)] {
+            sqlx::query(
+                "INSERT INTO script_blobs (sha256, content, size_bytes, created_at)
+                 VALUES (?, 'bundle', 6, ?)",
+            )
+            .bind(sha256)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(purge_orphan_blobs(&pool).await.unwrap(), 1);
+
+        let remaining: Vec<String> =
+            sqlx::query_scalar("SELECT sha256 FROM script_blobs ORDER BY sha256")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(remaining, vec!["fresh", "referenced"]);
+    }
+
     #[tokio::test]
     async fn test_cleanup_job_stops_on_cancellation() {
         // The cleanup job MUST observe a cancellation token and exit cleanly,
@@ -0,0 +1,57 @@
+//! Content-addressed storage of script bundles.
+//!
+//! A bundle is stored once in `script_blobs`, keyed by the hex SHA-256 of
+//! its text; `scripts` and `script_channel_releases` rows only reference it
+//! through `bundle_sha256`. Re-uploading an unchanged source as a new version,
+//! or pushing a beta build identical to stable, therefore stores nothing new.
+//! Reads resolve the reference in SQL, so `Script::bundle` is still the text.
+//!
+//! The download and source endpoints re-hash what they serve and refuse a
+//! bundle that no longer matches its key, and hand the hash to the client
+//! (`sha256` / `X-Bundle-SHA256`) so it can check the transfer as well.
+//! Rows written before blobs existed are moved over at startup; until then
+//! their bundle is inline and has no key to check against.
+
+use sha2::{Digest, Sha256};
+
+/// Response header carrying the SHA-256 of a served bundle.
+pub const BUNDLE_SHA256_HEADER: &str = "X-Bundle-SHA256";
+
+/// Hex SHA-256 of `content`: the key of its blob.
+pub fn sha256_hex(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// The hash of `content`, or `Err` with it when the content no longer
+/// matches the key it was stored under.
+pub fn verify(content: &str, stored_sha256: Option<&str>) -> Result<String, String> {
+    let actual = sha256_hex(content);
+    match stored_sha256 {
+        Some(expected) if expected != actual => Err(actual),
+        _ => Ok(actual),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_hex_matches_the_known_digest() {
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn verify_rejects_content_that_drifted_from_its_key() {
+        let key = sha256_hex("print('hi')");
+        assert_eq!(verify("print('hi')", Some(&key)), Ok(key.clone()));
+        assert!(verify("print('bye')", Some(&key)).is_err());
+        assert_eq!(verify("print('hi')", None), Ok(key));
+    }
+}
@@ -20,6 +20,10 @@
 //! - **Headers** — left at the Poem default (any), which already does the
 //!   right thing (echoes `Access-Control-Request-Headers` on preflight).
 //!
+//! [`build_embed_cors`] is the one exception: the embed route serves public
+//! marketplace cards to sites that are not known in advance, so it answers
+//! any origin, for `GET` only and without credentials.
+//!
 //! The helper is the single construction site — `main.rs` and the tests both
 //! go through it so the allow-list can never drift between them.
 
@@ -66,3 +70,10 @@ pub fn build_cors() -> Cors {
             Method::OPTIONS,
         ])
 }
+
+/// CORS for `GET /api/v1/scripts/:id/embed`, mounted outside [`build_cors`]:
+/// any origin may read a card, with no credentials and no other method.
+#[must_use]
+pub fn build_embed_cors() -> Cors {
+    Cors::new().allow_methods([Method::GET, Method::OPTIONS])
+}
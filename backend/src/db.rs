@@ -54,6 +54,14 @@ pub async fn initialize_database(pool: &SqlitePool) {
             "ALTER TABLE accounts ADD COLUMN website_url TEXT",
         ),
         ("bio", "ALTER TABLE accounts ADD COLUMN bio TEXT"),
+        (
+            "shadow_banned_at",
+            "ALTER TABLE accounts ADD COLUMN shadow_banned_at TEXT",
+        ),
+        (
+            "tier",
+            "ALTER TABLE accounts ADD COLUMN tier TEXT NOT NULL DEFAULT 'free'",
+        ),
     ];
 
     for (column_name, migration_sql) in account_migrations {
@@ -99,6 +107,50 @@ pub async fn initialize_database(pool: &SqlitePool) {
     .await
     .expect("Failed to create keys active index");
 
+    // -----------------------------------------------------------------------
+    // Key revocation list: one row per disabled key under an ever-growing
+    // sequence, which clients page through to enforce revocations offline.
+    // See migrations/024_create_key_revocations_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS key_revocations (
+            sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+            key_id TEXT NOT NULL UNIQUE,
+            account_id TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            ic_principal TEXT NOT NULL,
+            revoked_at TEXT NOT NULL,
+            FOREIGN KEY (key_id) REFERENCES account_public_keys(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create key_revocations table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_key_revocations_account ON key_revocations(account_id)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create key_revocations account index");
+
+    // Keys disabled before the list existed, oldest first.
+    sqlx::query(
+        r#"
+        INSERT INTO key_revocations (key_id, account_id, public_key, ic_principal, revoked_at)
+        SELECT id, account_id, public_key, ic_principal, COALESCE(disabled_at, added_at)
+        FROM account_public_keys
+        WHERE is_active = 0
+        ORDER BY COALESCE(disabled_at, added_at), id
+        ON CONFLICT (key_id) DO NOTHING
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to backfill key_revocations");
+
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS signature_audit (
@@ -231,12 +283,47 @@ pub async fn initialize_database(pool: &SqlitePool) {
             "deleted_at",
             "ALTER TABLE scripts ADD COLUMN deleted_at TEXT",
         ),
+        (
+            "weighted_rating",
+            "ALTER TABLE scripts ADD COLUMN weighted_rating REAL NOT NULL DEFAULT 0.0",
+        ),
+        (
+            "price_e8s",
+            "ALTER TABLE scripts ADD COLUMN price_e8s INTEGER NOT NULL DEFAULT 0",
+        ),
+        (
+            "currency",
+            "ALTER TABLE scripts ADD COLUMN currency TEXT NOT NULL DEFAULT 'ICP'",
+        ),
+        (
+            "views",
+            "ALTER TABLE scripts ADD COLUMN views INTEGER NOT NULL DEFAULT 0",
+        ),
+        (
+            "publish_at",
+            "ALTER TABLE scripts ADD COLUMN publish_at TEXT",
+        ),
+        (
+            "bundle_sha256",
+            "ALTER TABLE scripts ADD COLUMN bundle_sha256 TEXT",
+        ),
+        ("readme", "ALTER TABLE scripts ADD COLUMN readme TEXT"),
     ];
 
     for (column_name, migration_sql) in migrations {
         apply_add_column_migration(pool, "scripts", column_name, migration_sql).await;
     }
 
+    // Legacy rows only have the float `price` (whole ICP); derive e8s once.
+    // Afterwards every write keeps both in step (see `crate::pricing`).
+    sqlx::query(
+        "UPDATE scripts SET price_e8s = CAST(ROUND(price * 100000000) AS INTEGER)
+         WHERE price > 0 AND price_e8s = 0",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to backfill scripts.price_e8s");
+
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_scripts_slug ON scripts(slug)")
         .execute(pool)
         .await
@@ -249,6 +336,56 @@ pub async fn initialize_database(pool: &SqlitePool) {
     .await
     .expect("Failed to create scripts owner_account_id index");
 
+    // Browse/listing indexes (see migrations/007): category listings and the
+    // default newest-first feed order by created_at; trending and featured sort
+    // by downloads / views / weighted rating.
+    for (name, sql) in [
+        (
+            "category",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_category_created ON scripts(category, created_at)",
+        ),
+        (
+            "created_at",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_created_at ON scripts(created_at)",
+        ),
+        (
+            "downloads",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_downloads ON scripts(downloads, rating)",
+        ),
+        (
+            "views",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_views ON scripts(views, downloads)",
+        ),
+        (
+            "publish_at",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_publish_at ON scripts(publish_at) WHERE publish_at IS NOT NULL",
+        ),
+        (
+            "rating",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_rating ON scripts(rating, downloads)",
+        ),
+        (
+            "weighted_rating",
+            "CREATE INDEX IF NOT EXISTS idx_scripts_weighted_rating ON scripts(weighted_rating, downloads)",
+        ),
+    ] {
+        sqlx::query(sql)
+            .execute(pool)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create scripts {} index: {}", name, e));
+    }
+
+    // Scripts reviewed before `weighted_rating` existed get it computed once;
+    // from then on every review refreshes it.
+    match crate::repositories::ScriptRepository::new(pool.clone())
+        .backfill_weighted_ratings()
+        .await
+    {
+        Ok(0) => {}
+        Ok(n) => tracing::info!("Backfilled weighted_rating for {} scripts", n),
+        Err(e) => panic!("Failed to backfill scripts.weighted_rating: {}", e),
+    }
+
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS reviews (
@@ -291,6 +428,21 @@ pub async fn initialize_database(pool: &SqlitePool) {
     .await
     .expect("Failed to create reviews (script_id, user_id) unique index");
 
+    // Reviews held back by the spam heuristics until a moderator decides.
+    let review_migrations = [
+        (
+            "quarantined_at",
+            "ALTER TABLE reviews ADD COLUMN quarantined_at TEXT",
+        ),
+        (
+            "quarantine_reason",
+            "ALTER TABLE reviews ADD COLUMN quarantine_reason TEXT",
+        ),
+    ];
+    for (column_name, migration_sql) in review_migrations {
+        apply_add_column_migration(pool, "reviews", column_name, migration_sql).await;
+    }
+
     // Keypair Profiles System (separate from account profiles)
     sqlx::query(
         r#"
@@ -534,8 +686,13 @@ pub async fn initialize_database(pool: &SqlitePool) {
 
     // -----------------------------------------------------------------------
     // Purchases ledger — retained for historical data (all scripts are now
-    // free; no new rows are written). The table is never dropped (DB rule).
-    // See migrations/006_create_purchases_sqlite.sql.
+    // free). The table is never dropped (DB rule). Existing rows can still be
+    // disputed and refunded; every status change is recorded in
+    // `purchase_status_history`. A granted bundle product adds one row per
+    // member script, tagged with `bundle_id`.
+    // See migrations/006_create_purchases_sqlite.sql,
+    // migrations/015_add_purchase_disputes_sqlite.sql and
+    // migrations/016_create_product_bundles_sqlite.sql.
     // -----------------------------------------------------------------------
     sqlx::query(
         r#"
@@ -567,6 +724,747 @@ pub async fn initialize_database(pool: &SqlitePool) {
         .execute(pool)
         .await
         .expect("Failed to create purchases script_id index");
+
+    let purchase_migrations = [
+        (
+            "dispute_reason",
+            "ALTER TABLE purchases ADD COLUMN dispute_reason TEXT",
+        ),
+        (
+            "disputed_at",
+            "ALTER TABLE purchases ADD COLUMN disputed_at TEXT",
+        ),
+        (
+            "refunded_at",
+            "ALTER TABLE purchases ADD COLUMN refunded_at TEXT",
+        ),
+        (
+            "refund_block_index",
+            "ALTER TABLE purchases ADD COLUMN refund_block_index INTEGER",
+        ),
+        (
+            "refund_amount_e8s",
+            "ALTER TABLE purchases ADD COLUMN refund_amount_e8s INTEGER",
+        ),
+        (
+            "bundle_id",
+            "ALTER TABLE purchases ADD COLUMN bundle_id TEXT",
+        ),
+    ];
+    for (column_name, migration_sql) in purchase_migrations {
+        apply_add_column_migration(pool, "purchases", column_name, migration_sql).await;
+    }
+
+    // One ledger transfer can only ever pay back one purchase.
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_purchases_refund_block ON purchases(refund_block_index) WHERE refund_block_index IS NOT NULL",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create purchases refund_block_index index");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_purchases_status ON purchases(status, disputed_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create purchases status index");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS purchase_status_history (
+            id TEXT PRIMARY KEY,
+            purchase_id TEXT NOT NULL,
+            from_status TEXT NOT NULL,
+            to_status TEXT NOT NULL,
+            actor TEXT NOT NULL,
+            note TEXT,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create purchase_status_history table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_purchase_status_history_purchase ON purchase_status_history(purchase_id, created_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create purchase_status_history purchase_id index");
+
+    // -----------------------------------------------------------------------
+    // Author stats webhooks: one endpoint per account, plus the download
+    // milestones already announced per script so each fires once.
+    // See migrations/009_create_author_webhooks_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS author_webhooks (
+            account_id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            secret TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create author_webhooks table");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_download_milestones (
+            script_id TEXT NOT NULL,
+            milestone INTEGER NOT NULL,
+            reached_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, milestone)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_download_milestones table");
+
+    // -----------------------------------------------------------------------
+    // Promotions: owner-scheduled, time-boxed discounts on paid scripts.
+    // See migrations/014_create_promotions_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS promotions (
+            id TEXT PRIMARY KEY,
+            script_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            amount INTEGER NOT NULL,
+            currency TEXT NOT NULL,
+            starts_at TEXT NOT NULL,
+            ends_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create promotions table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_promotions_script_window ON promotions(script_id, starts_at, ends_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create promotions script/window index");
+
+    // -----------------------------------------------------------------------
+    // Bundle products: several scripts of one author sold for one price.
+    // See migrations/016_create_product_bundles_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS product_bundles (
+            id TEXT PRIMARY KEY,
+            owner_account_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            description TEXT NOT NULL,
+            price_e8s INTEGER NOT NULL,
+            currency TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (owner_account_id) REFERENCES accounts(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create product_bundles table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_product_bundles_owner ON product_bundles(owner_account_id)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create product_bundles owner index");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS product_bundle_scripts (
+            bundle_id TEXT NOT NULL,
+            script_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            PRIMARY KEY (bundle_id, script_id),
+            FOREIGN KEY (bundle_id) REFERENCES product_bundles(id) ON DELETE CASCADE,
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create product_bundle_scripts table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_product_bundle_scripts_script ON product_bundle_scripts(script_id)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create product_bundle_scripts script_id index");
+
+    // -----------------------------------------------------------------------
+    // Release channels other than stable (the scripts row itself). One build
+    // per script and channel, replaced on every push. See
+    // migrations/021_create_script_channel_releases_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_channel_releases (
+            script_id TEXT NOT NULL,
+            channel TEXT NOT NULL,
+            version TEXT NOT NULL,
+            bundle TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, channel),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_channel_releases table");
+
+    // -----------------------------------------------------------------------
+    // Content-addressed bundles: each distinct source stored once, keyed by
+    // its SHA-256, and referenced by scripts and channel builds. Inline
+    // bundles of older rows are moved over here. See
+    // migrations/023_create_script_blobs_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_blobs (
+            sha256 TEXT PRIMARY KEY,
+            content TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_blobs table");
+
+    apply_add_column_migration(
+        pool,
+        "script_channel_releases",
+        "bundle_sha256",
+        "ALTER TABLE script_channel_releases ADD COLUMN bundle_sha256 TEXT",
+    )
+    .await;
+
+    match crate::repositories::ScriptRepository::new(pool.clone())
+        .backfill_bundle_blobs(&chrono::Utc::now().to_rfc3339())
+        .await
+    {
+        Ok(0) => {}
+        Ok(n) => tracing::info!("Moved {} inline bundles into script_blobs", n),
+        Err(e) => panic!("Failed to backfill script_blobs: {}", e),
+    }
+
+    // -----------------------------------------------------------------------
+    // Declared dependencies on other scripts, by slug and semver requirement.
+    // See migrations/022_create_script_dependencies_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_dependencies (
+            script_id TEXT NOT NULL,
+            dependency_slug TEXT NOT NULL,
+            version_req TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, dependency_slug),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_dependencies table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_script_dependencies_slug ON script_dependencies(dependency_slug)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_dependencies slug index");
+
+    // -----------------------------------------------------------------------
+    // Account activity log: publications, version releases and reviews, read
+    // back as profile feeds. See migrations/025_create_account_activity_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS account_activity (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            script_id TEXT NOT NULL,
+            version TEXT,
+            review_id TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_activity table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_account_activity_account ON account_activity(account_id, id)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_activity account index");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_account_activity_script ON account_activity(script_id)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_activity script index");
+
+    match crate::repositories::ActivityRepository::new(pool.clone())
+        .backfill()
+        .await
+    {
+        Ok(0) => {}
+        Ok(n) => tracing::info!("Backfilled {} account activity entries", n),
+        Err(e) => panic!("Failed to backfill account_activity: {}", e),
+    }
+
+    // -----------------------------------------------------------------------
+    // Follows: who follows which author, read back as the followed-authors
+    // feed. See migrations/026_create_follows_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS follows (
+            follower_account_id TEXT NOT NULL,
+            followed_account_id TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (follower_account_id, followed_account_id),
+            CHECK (follower_account_id <> followed_account_id),
+            FOREIGN KEY (follower_account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+            FOREIGN KEY (followed_account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create follows table");
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_follows_followed ON follows(followed_account_id)")
+        .execute(pool)
+        .await
+        .expect("Failed to create follows followed index");
+
+    // -----------------------------------------------------------------------
+    // Script Q&A: questions about a script and the owner's answers.
+    // See migrations/028_create_script_questions_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_questions (
+            id TEXT PRIMARY KEY,
+            script_id TEXT NOT NULL,
+            account_id TEXT NOT NULL,
+            question TEXT NOT NULL,
+            answer TEXT,
+            created_at TEXT NOT NULL,
+            answered_at TEXT,
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_questions table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_script_questions_script ON script_questions(script_id, created_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_questions script index");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_script_questions_unanswered ON script_questions(script_id) WHERE answer IS NULL",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_questions unanswered index");
+
+    // -----------------------------------------------------------------------
+    // Install/run telemetry, stored only as counters.
+    // See migrations/029_create_script_telemetry_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_telemetry (
+            script_id TEXT NOT NULL,
+            version TEXT NOT NULL,
+            engine_version TEXT NOT NULL,
+            event TEXT NOT NULL,
+            error_class TEXT NOT NULL DEFAULT '',
+            count INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, version, engine_version, event, error_class),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_telemetry table");
+
+    // -----------------------------------------------------------------------
+    // Execution error reports, one row per distinct stack, and the versions
+    // flagged for a failure spike.
+    // See migrations/030_create_script_error_reports_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_error_reports (
+            script_id TEXT NOT NULL,
+            version TEXT NOT NULL,
+            stack_hash TEXT NOT NULL,
+            error_class TEXT NOT NULL,
+            message TEXT NOT NULL,
+            stack TEXT NOT NULL,
+            engine_version TEXT NOT NULL,
+            occurrences INTEGER NOT NULL DEFAULT 1,
+            first_seen_at TEXT NOT NULL,
+            last_seen_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, version, stack_hash),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_error_reports table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_script_error_reports_last_seen ON script_error_reports(script_id, last_seen_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_error_reports last_seen index");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_version_flags (
+            script_id TEXT NOT NULL,
+            version TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            flagged_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, version),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_version_flags table");
+
+    // -----------------------------------------------------------------------
+    // Compatibility votes: works / broken per account, app version and
+    // canister. See migrations/031_create_compatibility_votes_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS compatibility_votes (
+            script_id TEXT NOT NULL,
+            account_id TEXT NOT NULL,
+            app_version TEXT NOT NULL,
+            canister_id TEXT NOT NULL DEFAULT '',
+            works INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (script_id, account_id, app_version, canister_id),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create compatibility_votes table");
+
+    // -----------------------------------------------------------------------
+    // Moderation notes and strikes on accounts.
+    // See migrations/032_create_account_moderation_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS moderation_notes (
+            id TEXT PRIMARY KEY,
+            account_id TEXT NOT NULL,
+            note TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create moderation_notes table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_moderation_notes_account ON moderation_notes(account_id, created_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create moderation_notes account index");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS account_strikes (
+            id TEXT PRIMARY KEY,
+            account_id TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            revoked_at TEXT,
+            revoke_reason TEXT,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_strikes table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_account_strikes_active ON account_strikes(account_id) WHERE revoked_at IS NULL",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_strikes active index");
+
+    // -----------------------------------------------------------------------
+    // Latest re-validation result per public script.
+    // See migrations/033_create_script_validations_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS script_validations (
+            script_id TEXT PRIMARY KEY,
+            version TEXT NOT NULL,
+            rules_version INTEGER NOT NULL,
+            errors TEXT NOT NULL,
+            failing_since TEXT,
+            checked_at TEXT NOT NULL,
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create script_validations table");
+
+    // -----------------------------------------------------------------------
+    // Per-category and per-tag trending lists, rebuilt by the trending job.
+    // See migrations/035_create_trending_rollups_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS trending_rollups (
+            scope TEXT NOT NULL,
+            scope_key TEXT NOT NULL,
+            signal TEXT NOT NULL,
+            rank INTEGER NOT NULL,
+            script_id TEXT NOT NULL,
+            computed_at TEXT NOT NULL,
+            PRIMARY KEY (scope, scope_key, signal, rank),
+            FOREIGN KEY (script_id) REFERENCES scripts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create trending_rollups table");
+
+    // -----------------------------------------------------------------------
+    // Account-level preferences; no row means the defaults.
+    // See migrations/036_create_account_settings_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS account_settings (
+            account_id TEXT PRIMARY KEY,
+            default_visibility TEXT NOT NULL DEFAULT 'public',
+            required_signature_scheme TEXT NOT NULL DEFAULT 'any',
+            muted_events TEXT NOT NULL DEFAULT '[]',
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_settings table");
+
+    // -----------------------------------------------------------------------
+    // One report per run of the integrity checker.
+    // See migrations/037_create_integrity_reports_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS integrity_reports (
+            id TEXT PRIMARY KEY,
+            trigger TEXT NOT NULL,
+            repaired INTEGER NOT NULL,
+            issue_count INTEGER NOT NULL,
+            report TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create integrity_reports table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_integrity_reports_created ON integrity_reports(created_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create integrity_reports index");
+
+    // -----------------------------------------------------------------------
+    // Signed requests the signature gate rejected, for the admin account
+    // overview. See migrations/038_create_account_failures_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS account_failures (
+            id TEXT PRIMARY KEY,
+            account_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            status INTEGER NOT NULL,
+            reason TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_failures table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_account_failures_account ON account_failures(account_id, created_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create account_failures index");
+
+    // -----------------------------------------------------------------------
+    // Maintenance switch: a single row every instance polls, so toggling it
+    // on one converges all of them. See migrations/018_create_maintenance_mode_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS maintenance_mode (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            enabled INTEGER NOT NULL DEFAULT 0,
+            message TEXT,
+            retry_after_secs INTEGER NOT NULL DEFAULT 300,
+            updated_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create maintenance_mode table");
+
+    sqlx::query("INSERT OR IGNORE INTO maintenance_mode (id) VALUES (1)")
+        .execute(pool)
+        .await
+        .expect("Failed to seed maintenance_mode row");
+
+    // -----------------------------------------------------------------------
+    // Search log: normalised query text and result count only — no account,
+    // key or IP — for the admin search analytics. Pruned by the cleanup job.
+    // See migrations/010_create_search_log_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS search_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            query TEXT NOT NULL,
+            category TEXT,
+            result_count INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create search_log table");
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_search_log_created ON search_log(created_at)")
+        .execute(pool)
+        .await
+        .expect("Failed to create search_log created_at index");
+
+    // -----------------------------------------------------------------------
+    // Search ranking experiments: one exposure per ranked search, with the
+    // clicks on its results. Pruned with the search log by the cleanup job.
+    // See migrations/034_create_search_experiments_sqlite.sql.
+    // -----------------------------------------------------------------------
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS search_exposures (
+            id TEXT PRIMARY KEY,
+            experiment TEXT NOT NULL,
+            variant TEXT NOT NULL,
+            client_hash TEXT NOT NULL,
+            result_ids TEXT NOT NULL,
+            result_offset INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create search_exposures table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_search_exposures_experiment ON search_exposures(experiment, created_at)",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create search_exposures experiment index");
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS search_clicks (
+            exposure_id TEXT NOT NULL,
+            script_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (exposure_id, script_id),
+            FOREIGN KEY (exposure_id) REFERENCES search_exposures(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to create search_clicks table");
 }
 
 /// Applies an idempotent `ALTER TABLE … ADD COLUMN` migration, distinguishing
@@ -0,0 +1,171 @@
+//! Error reports from script executions.
+//!
+//! When a script fails in the app, the app sends what the engine knows about
+//! the failure (`JsExecError` and the stack frames it unwound) to
+//! `POST /api/v1/scripts/:id/error-reports`. Like telemetry, reports are
+//! anonymous and opt-in. Two reports with the same [`stack_hash`] are the
+//! same bug, so the server keeps one row per hash and version and counts
+//! occurrences.
+//!
+//! A version is flagged when its run failure rate, from the telemetry
+//! counters, spikes above the rest of the script (see [`is_spike`]). Owners
+//! see reports and flags at `GET /api/v1/scripts/:id/error-reports`.
+
+use semver::Version;
+use serde::Deserialize;
+
+use crate::content_store::sha256_hex;
+use crate::telemetry::ErrorClass;
+use crate::validation::{require_non_empty, FieldError, Validate};
+
+pub const MAX_MESSAGE_CHARS: usize = 2_000;
+pub const MAX_STACK_FRAMES: usize = 64;
+pub const MAX_FRAME_CHARS: usize = 500;
+
+/// Runs a version needs before its failure rate can count as a spike.
+pub const SPIKE_MIN_RUNS: i64 = 20;
+/// A failure rate is a spike from this rate on when the other versions
+/// have no failures…
+pub const SPIKE_MIN_RATE: f64 = 0.2;
+/// …and otherwise at this multiple of their rate.
+pub const SPIKE_FACTOR: f64 = 2.0;
+
+/// Body of `POST /api/v1/scripts/:id/error-reports`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorReport {
+    pub version: String,
+    pub engine_version: String,
+    pub error_class: ErrorClass,
+    pub message: String,
+    /// Innermost frame first.
+    #[serde(default)]
+    pub stack: Vec<String>,
+}
+
+impl Validate for ErrorReport {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        for (field, value) in [
+            ("version", &self.version),
+            ("engine_version", &self.engine_version),
+        ] {
+            if Version::parse(value).is_err() {
+                errors.push(FieldError::new(field, "must be a semver version"));
+            }
+        }
+        require_non_empty(&mut errors, "message", &self.message);
+        if self.message.chars().count() > MAX_MESSAGE_CHARS {
+            errors.push(FieldError::new(
+                "message",
+                format!("must be at most {MAX_MESSAGE_CHARS} characters"),
+            ));
+        }
+        if self.stack.len() > MAX_STACK_FRAMES {
+            errors.push(FieldError::new(
+                "stack",
+                format!("must have at most {MAX_STACK_FRAMES} frames"),
+            ));
+        }
+        if self
+            .stack
+            .iter()
+            .any(|frame| frame.chars().count() > MAX_FRAME_CHARS)
+        {
+            errors.push(FieldError::new(
+                "stack",
+                format!("frames must be at most {MAX_FRAME_CHARS} characters"),
+            ));
+        }
+        errors
+    }
+}
+
+impl ErrorReport {
+    /// The stack as stored: one trimmed frame per line.
+    pub fn stack_text(&self) -> String {
+        self.stack
+            .iter()
+            .map(|frame| frame.trim())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Identity of a failure: the error class and the trimmed frames. The
+/// message stays out, since it often carries values that differ per run.
+/// Without frames the message is all there is, so it is used instead.
+pub fn stack_hash(report: &ErrorReport) -> String {
+    let location = if report.stack.is_empty() {
+        report.message.trim().to_string()
+    } else {
+        report.stack_text()
+    };
+    sha256_hex(&format!("{}\n{location}", report.error_class.as_str()))
+}
+
+/// Whether a version failing `failures` of `runs` spikes above the other
+/// versions of the script, which failed `baseline_failures` of
+/// `baseline_runs`.
+pub fn is_spike(failures: i64, runs: i64, baseline_failures: i64, baseline_runs: i64) -> bool {
+    if runs < SPIKE_MIN_RUNS {
+        return false;
+    }
+    let rate = failures as f64 / runs as f64;
+    let baseline = if baseline_runs > 0 {
+        baseline_failures as f64 / baseline_runs as f64
+    } else {
+        0.0
+    };
+    rate >= SPIKE_MIN_RATE.max(baseline * SPIKE_FACTOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(message: &str, stack: &[&str]) -> ErrorReport {
+        ErrorReport {
+            version: "1.0.0".to_string(),
+            engine_version: "0.9.0".to_string(),
+            error_class: ErrorClass::Runtime,
+            message: message.to_string(),
+            stack: stack.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn same_stack_same_hash_whatever_the_message() {
+        let a = report(
+            "balance 12 too low",
+            &["at transfer (main.js:4:2)", "at run"],
+        );
+        let b = report(
+            "balance 7 too low",
+            &[" at transfer (main.js:4:2) ", "at run"],
+        );
+        let c = report("balance 7 too low", &["at other (main.js:9:1)"]);
+        assert_eq!(stack_hash(&a), stack_hash(&b));
+        assert_ne!(stack_hash(&a), stack_hash(&c));
+        assert_ne!(stack_hash(&report("x", &[])), stack_hash(&report("y", &[])));
+    }
+
+    #[test]
+    fn validation_bounds_the_report() {
+        assert!(report("boom", &["at f"]).validate().is_empty());
+        let mut bad = report(" ", &[]);
+        bad.version = "next".to_string();
+        bad.stack = vec!["f".to_string(); MAX_STACK_FRAMES + 1];
+        let fields: Vec<String> = bad.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["version", "message", "stack"]);
+    }
+
+    #[test]
+    fn spikes_need_volume_and_a_jump() {
+        assert!(!is_spike(10, 10, 0, 0));
+        assert!(is_spike(5, 20, 0, 0));
+        assert!(!is_spike(3, 20, 0, 0));
+        assert!(!is_spike(10, 40, 15, 100));
+        assert!(is_spike(12, 40, 15, 100));
+    }
+}
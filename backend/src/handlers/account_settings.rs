@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    web::{Data, Json},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::SignedIdentity,
+    models::{AccountSettingsUpdate, AppState},
+    responses::error_response,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{ValidJson, Validate},
+};
+
+// ============================================================================
+// Account settings handlers
+// ============================================================================
+//
+// Signature-gated like the webhook routes: the account is resolved
+// SERVER-SIDE from the signing key and bound into the payload
+// `{action, account_id, nonce, ts}` plus each field the update sets
+// (`default_visibility`, `required_signature_scheme`, `muted_events`).
+//
+// GET /api/v1/account-settings   (signed GET) → 200 {defaultVisibility, requiredSignatureScheme, mutedEvents, updatedAt}
+// PUT /api/v1/account-settings   (update)     → 200 same shape
+//
+// Fields absent from an update are kept. See `account_settings` for what
+// each one does.
+
+const SETTINGS_UPDATE_ACTION: &str = "account_settings:update";
+
+#[derive(Debug, serde::Deserialize)]
+struct AccountSettingsRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    #[serde(flatten)]
+    update: AccountSettingsUpdate,
+}
+
+impl Validate for AccountSettingsRequest {}
+
+/// The signed payload of an update: the auth binding plus the fields set.
+fn update_payload(
+    account_id: &str,
+    nonce: &str,
+    ts: i64,
+    update: &AccountSettingsUpdate,
+) -> serde_json::Value {
+    let mut payload = serde_json::json!({
+        "action": SETTINGS_UPDATE_ACTION,
+        "account_id": account_id,
+        "nonce": nonce,
+        "ts": ts,
+    });
+    if let Some(visibility) = update.default_visibility {
+        payload["default_visibility"] = visibility.as_str().into();
+    }
+    if let Some(scheme) = update.required_signature_scheme {
+        payload["required_signature_scheme"] = scheme.as_str().into();
+    }
+    if let Some(muted) = &update.muted_events {
+        payload["muted_events"] = muted.clone().into();
+    }
+    payload
+}
+
+/// `GET /api/v1/account-settings` — the caller's settings. A signed GET (see
+/// `middleware::signed_identity`).
+#[handler]
+pub async fn get_account_settings(
+    identity: SignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .account_service
+        .get_settings(&identity.account_id)
+        .await
+    {
+        Ok(settings) => {
+            Json(serde_json::json!({ "success": true, "data": settings })).into_response()
+        }
+        Err(e) => {
+            tracing::error!(account_id = %identity.account_id, "Failed to load account settings: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn update_account_settings(
+    ValidJson(req): ValidJson<AccountSettingsRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        SETTINGS_UPDATE_ACTION,
+        &SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        |resolved| update_payload(resolved, &req.nonce, req.timestamp, &req.update),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .account_service
+        .update_settings(&account_id, &req.update)
+        .await
+    {
+        Ok(settings) => {
+            Json(serde_json::json!({ "success": true, "data": settings })).into_response()
+        }
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "account settings update failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_settings::DefaultVisibility;
+
+    #[test]
+    fn payload_binds_only_the_fields_set() {
+        let update = AccountSettingsUpdate {
+            default_visibility: Some(DefaultVisibility::Private),
+            muted_events: Some(vec!["stats.daily".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            crate::auth::create_canonical_payload(&update_payload("acct", "n-1", 1700, &update)),
+            r#"{"account_id":"acct","action":"account_settings:update","default_visibility":"private","muted_events":["stats.daily"],"nonce":"n-1","ts":1700}"#
+        );
+    }
+}
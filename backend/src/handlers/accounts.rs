@@ -4,24 +4,25 @@ use poem::{
     error::ResponseError,
     handler,
     http::StatusCode,
-    web::{Data, Json, Path},
+    web::{Data, Json, Path, Query},
     IntoResponse, Response,
 };
 
 use crate::{
     models::{
-        AddPublicKeyRequest, AppState, RegisterAccountRequest, RemovePublicKeyRequest,
-        UpdateAccountRequest,
+        AddPublicKeyRequest, AppState, KeyRevocationsQuery, RegisterAccountRequest,
+        RemovePublicKeyRequest, ReviewsQuery, UpdateAccountRequest,
     },
     responses::error_response,
     services::error::AccountError,
+    validation::ValidJson,
 };
 
 // Account profiles Endpoints
 
 #[handler]
 pub async fn register_account(
-    Json(payload): Json<RegisterAccountRequest>,
+    ValidJson(payload): ValidJson<RegisterAccountRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state.account_service.register_account(payload).await {
@@ -62,6 +63,80 @@ pub async fn get_account(
     }
 }
 
+/// `GET /api/v1/accounts/:username/activity?limit=&offset=` — the account's
+/// public actions (publications, version releases, reviews), newest first.
+#[handler]
+pub async fn get_account_activity(
+    Path(username): Path<String>,
+    Query(params): Query<ReviewsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.map_or(20, i64::from);
+    let offset = params.offset.map_or(0, i64::from);
+    match state
+        .account_service
+        .activity(&username, limit, offset)
+        .await
+    {
+        Ok(Some(page)) => Json(serde_json::json!({
+            "success": true,
+            "data": page
+        }))
+        .into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Account not found"),
+        Err(e) => {
+            tracing::error!("Failed to load account activity: {}", e);
+            account_error_response(e)
+        }
+    }
+}
+
+/// `GET /api/v1/accounts/:username/keys/revoked` — the account's revoked
+/// keys, in revocation order, each with its global `sequence`.
+#[handler]
+pub async fn get_revoked_keys(
+    Path(username): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.account_service.revoked_keys(&username).await {
+        Ok(Some(revocations)) => Json(serde_json::json!({
+            "success": true,
+            "data": { "revocations": revocations }
+        }))
+        .into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Account not found"),
+        Err(e) => {
+            tracing::error!("Failed to list revoked keys: {}", e);
+            account_error_response(e)
+        }
+    }
+}
+
+/// `GET /api/v1/keys/revocations?since=&limit=` — the global key revocation
+/// list after sequence `since` (default 0), for clients that cache it and
+/// enforce it offline.
+#[handler]
+pub async fn get_key_revocations(
+    Query(params): Query<KeyRevocationsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .account_service
+        .key_revocations_since(params.since.unwrap_or(0), params.limit.unwrap_or(500))
+        .await
+    {
+        Ok(page) => Json(serde_json::json!({
+            "success": true,
+            "data": page
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list key revocations: {}", e);
+            account_error_response(e)
+        }
+    }
+}
+
 #[handler]
 pub async fn get_account_by_public_key(
     Path(public_key): Path<String>,
@@ -92,7 +167,7 @@ pub async fn get_account_by_public_key(
 #[handler]
 pub async fn update_account(
     Path(username): Path<String>,
-    Json(payload): Json<UpdateAccountRequest>,
+    ValidJson(payload): ValidJson<UpdateAccountRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state
@@ -118,7 +193,7 @@ pub async fn update_account(
 #[handler]
 pub async fn add_account_key(
     Path(username): Path<String>,
-    Json(payload): Json<AddPublicKeyRequest>,
+    ValidJson(payload): ValidJson<AddPublicKeyRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state
@@ -144,7 +219,7 @@ pub async fn add_account_key(
 #[handler]
 pub async fn remove_account_key(
     Path((username, key_id)): Path<(String, String)>,
-    Json(payload): Json<RemovePublicKeyRequest>,
+    ValidJson(payload): ValidJson<RemovePublicKeyRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state
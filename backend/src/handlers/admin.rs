@@ -4,15 +4,16 @@ use poem::{
     error::ResponseError,
     handler,
     http::StatusCode,
-    web::{Data, Json, Path},
+    web::{Data, Json, Path, Query},
     IntoResponse, Response,
 };
 
 use crate::{
     models::{self, AppState},
     responses::error_response,
-    services::error::AccountError,
+    services::error::{AccountError, ModerationError},
     startup_checks::is_development,
+    validation::ValidJson,
 };
 
 // Admin Account Operations
@@ -20,7 +21,7 @@ use crate::{
 #[handler]
 pub async fn admin_disable_key(
     Path((username, key_id)): Path<(String, String)>,
-    Json(payload): Json<models::AdminDisableKeyRequest>,
+    ValidJson(payload): ValidJson<models::AdminDisableKeyRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state
@@ -54,7 +55,7 @@ pub async fn admin_disable_key(
 #[handler]
 pub async fn admin_add_recovery_key(
     Path(username): Path<String>,
-    Json(payload): Json<models::AdminAddRecoveryKeyRequest>,
+    ValidJson(payload): ValidJson<models::AdminAddRecoveryKeyRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state
@@ -84,6 +85,562 @@ pub async fn admin_add_recovery_key(
     }
 }
 
+#[handler]
+pub async fn admin_account_overview(
+    Path(username): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .account_service
+        .admin_account_overview(&username)
+        .await
+    {
+        Ok(overview) => {
+            tracing::info!("Admin viewed account overview for {}", username);
+            Json(serde_json::json!({
+                "success": true,
+                "data": overview
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Admin account overview failed: {}", e);
+            account_error_response(e)
+        }
+    }
+}
+
+#[handler]
+pub async fn admin_shadow_ban(
+    Path(username): Path<String>,
+    ValidJson(payload): ValidJson<models::AdminShadowBanRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let result = match state
+        .account_service
+        .admin_set_shadow_ban(&username, payload.banned, &payload.reason)
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::warn!("Admin shadow-ban failed: {}", e);
+            return account_error_response(e);
+        }
+    };
+    // The flag is already committed; a failed re-aggregation only leaves
+    // stale stored ratings until the script's next review.
+    if let Err(e) = state
+        .review_service
+        .refresh_stats_for_reviewer(&result.account_id)
+        .await
+    {
+        tracing::error!(
+            "Failed to refresh ratings after shadow-ban of {}: {}",
+            username,
+            e
+        );
+    }
+    tracing::info!(
+        "Admin set shadow-ban={} for account {}: {}",
+        payload.banned,
+        username,
+        payload.reason
+    );
+    Json(serde_json::json!({
+        "success": true,
+        "data": result
+    }))
+    .into_response()
+}
+
+/// `POST /api/v1/admin/accounts/:username/tier` — moves an account to
+/// another plan tier and returns the account with its new quota usage.
+#[handler]
+pub async fn admin_set_tier(
+    Path(username): Path<String>,
+    ValidJson(payload): ValidJson<models::AdminSetTierRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .account_service
+        .admin_set_tier(&username, payload.tier, &payload.reason)
+        .await
+    {
+        Ok(account) => {
+            tracing::info!(
+                "Admin moved account {} to tier {}: {}",
+                username,
+                payload.tier.as_str(),
+                payload.reason
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": account
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Admin tier change failed: {}", e);
+            account_error_response(e)
+        }
+    }
+}
+
+/// `GET /api/v1/admin/accounts/:username/moderation` — notes, strikes and
+/// the restriction they put on the account.
+#[handler]
+pub async fn admin_moderation_status(
+    Path(username): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    moderation_response(state.moderation_service.status(&username).await)
+}
+
+/// `POST /api/v1/admin/accounts/:username/notes` — a note for the next
+/// moderator; never shown to the account.
+#[handler]
+pub async fn admin_add_moderation_note(
+    Path(username): Path<String>,
+    ValidJson(payload): ValidJson<models::AdminModerationNoteRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    moderation_response(
+        state
+            .moderation_service
+            .add_note(&username, &payload.note)
+            .await,
+    )
+}
+
+/// `POST /api/v1/admin/accounts/:username/strikes` — a strike takes effect
+/// at once; see [`crate::moderation`] for the thresholds.
+#[handler]
+pub async fn admin_add_strike(
+    Path(username): Path<String>,
+    ValidJson(payload): ValidJson<models::AdminStrikeRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    moderation_response(
+        state
+            .moderation_service
+            .add_strike(&username, &payload.reason)
+            .await,
+    )
+}
+
+#[handler]
+pub async fn admin_revoke_strike(
+    Path((username, strike_id)): Path<(String, String)>,
+    ValidJson(payload): ValidJson<models::AdminStrikeRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    moderation_response(
+        state
+            .moderation_service
+            .revoke_strike(&username, &strike_id, &payload.reason)
+            .await,
+    )
+}
+
+fn moderation_response(result: Result<models::ModerationStatus, ModerationError>) -> Response {
+    match result {
+        Ok(status) => Json(serde_json::json!({
+            "success": true,
+            "data": status
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!("Admin moderation failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// Lifts the script-write velocity limit (history, strikes and cooldown) for
+/// an account and all of its keys, e.g. after a legitimate bulk import.
+#[handler]
+pub async fn admin_reset_velocity(
+    Path(username): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account = match state.account_service.get_account(&username).await {
+        Ok(Some(account)) => account,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Account not found"),
+        Err(e) => {
+            tracing::warn!("Admin velocity reset failed: {}", e);
+            return account_error_response(e);
+        }
+    };
+    let keys: Vec<String> = account
+        .public_keys
+        .iter()
+        .map(|k| k.public_key.clone())
+        .collect();
+    state.script_service.clear_velocity(&account.id, &keys);
+    tracing::info!("Admin reset script write velocity for account {}", username);
+    Json(serde_json::json!({
+        "success": true,
+        "data": { "accountId": account.id, "keysCleared": keys.len() }
+    }))
+    .into_response()
+}
+
+/// `GET /api/v1/admin/reviews/quarantine?limit=50&offset=0` — reviews held
+/// back by the spam heuristics, oldest first.
+#[handler]
+pub async fn admin_list_quarantined_reviews(
+    Query(params): Query<models::ReviewsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+    match state.review_service.list_quarantined(limit, offset).await {
+        Ok(reviews) => Json(serde_json::json!({
+            "success": true,
+            "data": reviews
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list quarantined reviews: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list quarantined reviews",
+            )
+        }
+    }
+}
+
+/// `POST /api/v1/admin/reviews/:id/moderate` — publishes (`approve: true`)
+/// or deletes a quarantined review. Audited.
+#[handler]
+pub async fn admin_moderate_review(
+    Path(review_id): Path<String>,
+    ValidJson(payload): ValidJson<models::AdminModerateReviewRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .review_service
+        .admin_moderate_review(&review_id, payload.approve, &payload.reason)
+        .await
+    {
+        Ok(review) => {
+            tracing::info!(
+                "Admin {} quarantined review {} on script {}: {}",
+                if payload.approve {
+                    "approved"
+                } else {
+                    "rejected"
+                },
+                review_id,
+                review.script_id,
+                payload.reason
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": { "reviewId": review.id, "approved": payload.approve }
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Admin review moderation failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/admin/disputes` — the dispute resolution queue, oldest
+/// dispute first.
+#[handler]
+pub async fn admin_list_disputes(
+    Query(params): Query<models::ReviewsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+    match state.dispute_service.list_disputes(limit, offset).await {
+        Ok(purchases) => Json(serde_json::json!({
+            "success": true,
+            "data": purchases
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list disputes: {}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to list disputes")
+        }
+    }
+}
+
+/// `GET /api/v1/admin/purchases/:id/history` — every status change of a
+/// purchase, oldest first.
+#[handler]
+pub async fn admin_purchase_history(
+    Path(purchase_id): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.dispute_service.history(&purchase_id).await {
+        Ok(history) => Json(serde_json::json!({
+            "success": true,
+            "data": history
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load purchase history {}: {}", purchase_id, e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load purchase history",
+            )
+        }
+    }
+}
+
+/// `POST /api/v1/admin/disputes/:purchase_id/resolve` — records a
+/// ledger-verified refund or rejects the dispute. Audited.
+#[handler]
+pub async fn admin_resolve_dispute(
+    Path(purchase_id): Path<String>,
+    ValidJson(payload): ValidJson<models::AdminResolveDisputeRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .dispute_service
+        .admin_resolve_dispute(&purchase_id, &payload)
+        .await
+    {
+        Ok(purchase) => {
+            tracing::info!(
+                "Admin resolved dispute on purchase {} as {}: {}",
+                purchase_id,
+                purchase.status,
+                payload.reason
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": purchase
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Admin dispute resolution failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/admin/maintenance` — the current maintenance switch.
+#[handler]
+pub async fn admin_get_maintenance(Data(state): Data<&Arc<AppState>>) -> Response {
+    Json(serde_json::json!({
+        "success": true,
+        "data": state.maintenance_service.current().await
+    }))
+    .into_response()
+}
+
+/// `PUT /api/v1/admin/maintenance` — turns read-only maintenance mode on or
+/// off for every instance. Audited.
+#[handler]
+pub async fn admin_set_maintenance(
+    ValidJson(payload): ValidJson<models::AdminMaintenanceRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.maintenance_service.set(&payload).await {
+        Ok(maintenance) => {
+            tracing::warn!(
+                "Admin turned maintenance mode {}: {}",
+                if maintenance.enabled { "on" } else { "off" },
+                payload.reason
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": maintenance
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to set maintenance mode: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `POST /api/v1/admin/integrity-check` — runs the data integrity checks
+/// now and returns the report; with `repair` the issues are also repaired.
+/// Audited.
+#[handler]
+pub async fn admin_integrity_check(
+    ValidJson(payload): ValidJson<models::AdminIntegrityCheckRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.integrity_service.admin_check(&payload).await {
+        Ok(report) => {
+            tracing::info!(
+                "Admin ran the integrity check{}: {} issues ({})",
+                if payload.repair { " with repair" } else { "" },
+                report.issue_count,
+                payload.reason
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": report
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::error!("Admin integrity check failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/admin/integrity-reports?limit=10` — the latest integrity
+/// reports, scheduled and admin-triggered, newest first. `limit` is clamped
+/// to 1..=50.
+#[handler]
+pub async fn admin_integrity_reports(
+    Query(params): Query<models::IntegrityReportsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .integrity_service
+        .reports(params.limit.unwrap_or(10))
+        .await
+    {
+        Ok(reports) => Json(serde_json::json!({
+            "success": true,
+            "data": reports
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load integrity reports: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `POST /api/v1/admin/categories/merge` — moves every script in one of
+/// `from` to `into`. Audited.
+#[handler]
+pub async fn admin_merge_categories(
+    ValidJson(payload): ValidJson<models::AdminMergeCategoriesRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .script_service
+        .admin_merge_categories(&payload.from, &payload.into, &payload.reason)
+        .await
+    {
+        Ok(result) => {
+            tracing::info!(
+                "Admin merged categories {:?} into '{}' ({} scripts)",
+                payload.from,
+                payload.into,
+                result.scripts_updated
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": result
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Admin category merge failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `POST /api/v1/admin/tags/rename` — renames a tag on every script that
+/// carries it. Audited.
+#[handler]
+pub async fn admin_rename_tag(
+    ValidJson(payload): ValidJson<models::AdminRenameTagRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .script_service
+        .admin_rename_tag(&payload.from, &payload.to, &payload.reason)
+        .await
+    {
+        Ok(result) => {
+            tracing::info!(
+                "Admin renamed tag '{}' to '{}' ({} scripts)",
+                payload.from,
+                payload.to,
+                result.scripts_updated
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": result
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Admin tag rename failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/admin/search-analytics?days=30&limit=20` — what people search
+/// for and what they search for without finding anything. `days` is clamped
+/// to 1..=365 and `limit` to 1..=100.
+#[handler]
+pub async fn admin_search_analytics(
+    Query(params): Query<models::SearchAnalyticsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    match state.script_service.search_analytics(days, limit).await {
+        Ok(analytics) => Json(serde_json::json!({
+            "success": true,
+            "data": analytics
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to compute search analytics: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to compute search analytics",
+            )
+        }
+    }
+}
+
+/// `GET /api/v1/admin/search-experiments?experiment=q4&days=30` — exposures
+/// and click-through per variant of a search ranking experiment, the running
+/// one by default. `days` is clamped to 1..=365.
+#[handler]
+pub async fn admin_search_experiments(
+    Query(params): Query<models::RankingExperimentQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let days = params.days.unwrap_or(30).clamp(1, 365);
+    let experiment = params.experiment.as_deref().filter(|e| !e.is_empty());
+    match state
+        .script_service
+        .ranking_experiment_report(experiment, days)
+        .await
+    {
+        Ok(Some(report)) => Json(serde_json::json!({
+            "success": true,
+            "data": report
+        }))
+        .into_response(),
+        Ok(None) => error_response(
+            StatusCode::NOT_FOUND,
+            "No ranking experiment is running; name one with ?experiment=",
+        ),
+        Err(e) => {
+            tracing::error!("Failed to compute search experiment report: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to compute search experiment report",
+            )
+        }
+    }
+}
+
 /// Renders an [`AccountError`] for admin handlers. Same single source of
 /// truth for variant → status as the user-facing account handlers.
 fn account_error_response(e: AccountError) -> Response {
@@ -0,0 +1,394 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path, Query},
+    IntoResponse, Response,
+};
+
+use crate::{
+    models::{scripts_to_list_json, AppState, ReviewsQuery},
+    pricing::{is_supported_currency, Price},
+    responses::error_response,
+    services::{BundleListing, BundleUpdate, NewBundle},
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{require_non_empty, FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Bundle product handlers
+// ============================================================================
+//
+// GET    /api/v1/bundles                 (public)        → 200 {bundles, total, hasMore}
+// GET    /api/v1/bundles/:id             (public)        → 200 bundle / 404
+// GET    /api/v1/scripts/:id/bundles     (public)        → 200 [bundle]
+// POST   /api/v1/bundles                 (signed)        → 201 bundle
+// PUT    /api/v1/bundles/:id             (signed, owner) → 200 bundle
+// DELETE /api/v1/bundles/:id             (signed, owner) → 200 / 404
+//
+// Writes are signature-gated like the promotion routes. The canonical payload
+// is `{action, account_id, nonce, ts}` plus the body fields (`title,
+// description, price_e8s, currency, script_ids`; absent update fields sign as
+// `null`) and `bundle_id` for update and delete. Sending `script_ids` on an
+// update replaces the membership. Public bundle bodies carry the member
+// scripts (public, live ones only) and `separatePriceE8s`, what they cost
+// bought one by one.
+
+const BUNDLE_CREATE_ACTION: &str = "bundle:create";
+const BUNDLE_UPDATE_ACTION: &str = "bundle:update";
+const BUNDLE_DELETE_ACTION: &str = "bundle:delete";
+
+const MAX_TITLE_CHARS: usize = 200;
+const MAX_DESCRIPTION_CHARS: usize = 5000;
+
+#[derive(Debug, serde::Deserialize)]
+struct BundleCreateRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    title: String,
+    description: String,
+    price_e8s: i64,
+    currency: String,
+    script_ids: Vec<String>,
+}
+
+impl Validate for BundleCreateRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "title", &self.title);
+        check_text(&mut errors, Some(&self.title), Some(&self.description));
+        check_price(&mut errors, Some(self.price_e8s), Some(&self.currency));
+        errors
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BundleUpdateRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    title: Option<String>,
+    description: Option<String>,
+    price_e8s: Option<i64>,
+    currency: Option<String>,
+    script_ids: Option<Vec<String>>,
+}
+
+impl Validate for BundleUpdateRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if let Some(title) = &self.title {
+            require_non_empty(&mut errors, "title", title);
+        }
+        check_text(
+            &mut errors,
+            self.title.as_deref(),
+            self.description.as_deref(),
+        );
+        match (self.price_e8s, &self.currency) {
+            (Some(_), None) => errors.push(FieldError::new("currency", "required with price_e8s")),
+            (None, Some(_)) => errors.push(FieldError::new("price_e8s", "required with currency")),
+            _ => {}
+        }
+        check_price(&mut errors, self.price_e8s, self.currency.as_deref());
+        errors
+    }
+}
+
+/// Body for the delete route: the auth fields only.
+#[derive(Debug, serde::Deserialize)]
+struct BundleAuthRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+}
+
+impl Validate for BundleAuthRequest {}
+
+fn check_text(errors: &mut Vec<FieldError>, title: Option<&str>, description: Option<&str>) {
+    if title.is_some_and(|t| t.chars().count() > MAX_TITLE_CHARS) {
+        errors.push(FieldError::new(
+            "title",
+            format!("must be at most {MAX_TITLE_CHARS} characters"),
+        ));
+    }
+    if description.is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_CHARS) {
+        errors.push(FieldError::new(
+            "description",
+            format!("must be at most {MAX_DESCRIPTION_CHARS} characters"),
+        ));
+    }
+}
+
+fn check_price(errors: &mut Vec<FieldError>, price_e8s: Option<i64>, currency: Option<&str>) {
+    if price_e8s.is_some_and(|e8s| e8s < 0) {
+        errors.push(FieldError::new("price_e8s", "must not be negative"));
+    }
+    if currency.is_some_and(|code| !is_supported_currency(code)) {
+        errors.push(FieldError::new("currency", "unsupported currency"));
+    }
+}
+
+fn listing_json(listing: &BundleListing) -> serde_json::Value {
+    let mut value =
+        serde_json::to_value(&listing.bundle).expect("ProductBundle serializes to a JSON object");
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "scripts".to_string(),
+            scripts_to_list_json(&listing.scripts),
+        );
+        obj.insert(
+            "separatePriceE8s".to_string(),
+            listing.separate_price_e8s().into(),
+        );
+    }
+    value
+}
+
+async fn resolve_account(
+    state: &AppState,
+    action: &'static str,
+    auth_fields: SignedAuthFields<'_>,
+    extra: serde_json::Value,
+) -> Result<String, Response> {
+    let nonce = auth_fields.nonce;
+    let ts = auth_fields.timestamp;
+    verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        action,
+        &auth_fields,
+        |resolved| {
+            let mut payload = serde_json::json!({
+                "action": action,
+                "account_id": resolved,
+                "nonce": nonce,
+                "ts": ts,
+            });
+            if let (Some(payload), serde_json::Value::Object(extra)) =
+                (payload.as_object_mut(), extra)
+            {
+                payload.extend(extra);
+            }
+            payload
+        },
+    )
+    .await
+    .map_err(|r| error_response(r.status, r.message))
+}
+
+#[handler]
+pub async fn get_bundles(
+    Query(params): Query<ReviewsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    match state.bundle_service.list_bundles(limit, offset).await {
+        Ok((listings, total)) => Json(serde_json::json!({
+            "success": true,
+            "data": {
+                "bundles": listings.iter().map(listing_json).collect::<Vec<_>>(),
+                "total": total,
+                "hasMore": i64::from(offset + limit) < total
+            }
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list bundles: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn get_bundle(
+    Path(bundle_id): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.bundle_service.get_bundle(&bundle_id).await {
+        Ok(listing) => Json(serde_json::json!({
+            "success": true,
+            "data": listing_json(&listing)
+        }))
+        .into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
+
+#[handler]
+pub async fn get_script_bundles(
+    Path(script_id): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.bundle_service.bundles_for_script(&script_id).await {
+        Ok(bundles) => Json(serde_json::json!({
+            "success": true,
+            "data": bundles
+        }))
+        .into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
+
+#[handler]
+pub async fn bundle_create(
+    ValidJson(req): ValidJson<BundleCreateRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        BUNDLE_CREATE_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        serde_json::json!({
+            "title": req.title,
+            "description": req.description,
+            "price_e8s": req.price_e8s,
+            "currency": req.currency,
+            "script_ids": req.script_ids,
+        }),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    match state
+        .bundle_service
+        .create_bundle(
+            &account_id,
+            NewBundle {
+                title: &req.title,
+                description: &req.description,
+                price: Price::new(req.price_e8s, &req.currency),
+                script_ids: &req.script_ids,
+            },
+        )
+        .await
+    {
+        Ok(bundle) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "success": true,
+                "data": bundle
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "bundle create failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn bundle_update(
+    Path(bundle_id): Path<String>,
+    ValidJson(req): ValidJson<BundleUpdateRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        BUNDLE_UPDATE_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        serde_json::json!({
+            "bundle_id": bundle_id,
+            "title": req.title,
+            "description": req.description,
+            "price_e8s": req.price_e8s,
+            "currency": req.currency,
+            "script_ids": req.script_ids,
+        }),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    let price = req
+        .price_e8s
+        .zip(req.currency.as_deref())
+        .map(|(e8s, currency)| Price::new(e8s, currency));
+    match state
+        .bundle_service
+        .update_bundle(
+            &account_id,
+            &bundle_id,
+            BundleUpdate {
+                title: req.title.as_deref(),
+                description: req.description.as_deref(),
+                price,
+                script_ids: req.script_ids.as_deref(),
+            },
+        )
+        .await
+    {
+        Ok(bundle) => Json(serde_json::json!({
+            "success": true,
+            "data": bundle
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "bundle update failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn bundle_delete(
+    Path(bundle_id): Path<String>,
+    ValidJson(req): ValidJson<BundleAuthRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        BUNDLE_DELETE_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        serde_json::json!({ "bundle_id": bundle_id }),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    match state
+        .bundle_service
+        .delete_bundle(&account_id, &bundle_id)
+        .await
+    {
+        Ok(()) => Json(serde_json::json!({ "success": true })).into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use ic_agent::export::Principal;
+use poem::{
+    error::ResponseError,
+    handler,
+    web::{Data, Json, Path},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::OptionalSignedIdentity,
+    models::AppState,
+    responses::error_response,
+    services::COMPATIBILITY_VOTE_ACTION,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Compatibility vote handlers
+// ============================================================================
+//
+// One tap in the app: "works for me" or "broken" for the app version the
+// user runs, optionally against one of the script's canisters. Votes are
+// signature-gated like reviews, the voter resolved SERVER-SIDE from the
+// signing key:
+//
+// GET  /api/v1/scripts/:id/compatibility   → 200 CompatibilityBreakdown
+// POST /api/v1/scripts/:id/compatibility   → 200 CompatibilityBreakdown
+//      payload {action: "compatibility:vote", account_id, script_id,
+//               app_version, canister_id, works, nonce, ts}
+//
+// Listings and search results carry `compatibility_score` and
+// `compatibility_votes`.
+
+#[derive(Debug, serde::Deserialize)]
+struct CompatibilityVoteRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    app_version: String,
+    #[serde(default)]
+    canister_id: Option<String>,
+    works: bool,
+}
+
+impl Validate for CompatibilityVoteRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if semver::Version::parse(&self.app_version).is_err() {
+            errors.push(FieldError::new("app_version", "must be a semver version"));
+        }
+        if let Some(canister_id) = &self.canister_id {
+            if Principal::from_text(canister_id).is_err() {
+                errors.push(FieldError::new("canister_id", "must be a principal"));
+            }
+        }
+        errors
+    }
+}
+
+/// `GET /api/v1/scripts/:id/compatibility` — votes overall, per app version
+/// and per canister. A private script's votes need a signed GET by its
+/// owner.
+#[handler]
+pub async fn get_compatibility(
+    Path(script_id): Path<String>,
+    identity: OptionalSignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .compatibility_service
+        .breakdown(&script_id, identity.account_id())
+        .await
+    {
+        Ok(breakdown) => Json(serde_json::json!({
+            "success": true,
+            "data": breakdown
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to get compatibility of script {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn vote_compatibility(
+    Path(script_id): Path<String>,
+    ValidJson(req): ValidJson<CompatibilityVoteRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        COMPATIBILITY_VOTE_ACTION,
+        &SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        |resolved| {
+            serde_json::json!({
+                "action": COMPATIBILITY_VOTE_ACTION,
+                "account_id": resolved,
+                "script_id": script_id,
+                "app_version": req.app_version,
+                "canister_id": req.canister_id,
+                "works": req.works,
+                "nonce": req.nonce,
+                "ts": req.timestamp,
+            })
+        },
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .compatibility_service
+        .vote(
+            &account_id,
+            &script_id,
+            &req.app_version,
+            req.canister_id.as_deref(),
+            req.works,
+        )
+        .await
+    {
+        Ok(breakdown) => Json(serde_json::json!({
+            "success": true,
+            "data": breakdown
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "{} failed: {}", COMPATIBILITY_VOTE_ACTION, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
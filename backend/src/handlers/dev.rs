@@ -0,0 +1,162 @@
+use poem::{handler, http::StatusCode, web::Json, IntoResponse, Response};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    auth::{create_canonical_payload, verify_signature},
+    content_store::sha256_hex,
+    middleware::auth::{
+        build_canonical_update_payload, build_deletion_payload, build_publish_payload,
+        build_upload_payload,
+    },
+    models::{
+        CanonicalPayloadRequest, CreateScriptRequest, DeleteScriptRequest, UpdateScriptRequest,
+    },
+    responses::error_response,
+    startup_checks::is_development,
+    validation::ValidJson,
+};
+
+// ============================================================================
+// Development helpers
+// ============================================================================
+//
+// Answered only when the server runs in development; production gets 403.
+//
+// POST /api/dev/canonical-payload   → 200 {operation, canonicalJson, sha256, byteLength, signature}
+//      body {operation, script_id?, request, signature?, author_public_key?}
+//
+// The payload goes through the same builder the real route verifies with, so
+// a client whose signature is rejected can diff its own canonical string
+// against `canonicalJson`.
+
+fn parse_dto<T: DeserializeOwned>(request: &serde_json::Value) -> Result<T, Box<Response>> {
+    serde_json::from_value(request.clone()).map_err(|e| {
+        Box::new(error_response(
+            StatusCode::BAD_REQUEST,
+            &format!("request does not match the operation's body: {e}"),
+        ))
+    })
+}
+
+/// The payload the server would verify a signature over for `req`.
+fn build_payload(req: &CanonicalPayloadRequest) -> Result<serde_json::Value, Box<Response>> {
+    let script_id = req.script_id.as_deref().unwrap_or_default();
+    match req.operation.as_str() {
+        "upload" => build_upload_payload(&parse_dto::<CreateScriptRequest>(&req.request)?),
+        "update" => build_canonical_update_payload(
+            &parse_dto::<UpdateScriptRequest>(&req.request)?,
+            script_id,
+        ),
+        "publish" => {
+            build_publish_payload(&parse_dto::<UpdateScriptRequest>(&req.request)?, script_id)
+        }
+        "delete" => {
+            build_deletion_payload(&parse_dto::<DeleteScriptRequest>(&req.request)?, script_id)
+        }
+        _ => Ok(req.request.clone()),
+    }
+}
+
+/// Verification result of the signature carried by `req`, or `null` when it
+/// carries none.
+fn check_signature(req: &CanonicalPayloadRequest, canonical: &str) -> serde_json::Value {
+    let field = |explicit: &Option<String>, name: &str| {
+        explicit
+            .clone()
+            .or_else(|| req.request.get(name)?.as_str().map(str::to_string))
+    };
+    let (Some(signature), Some(public_key)) = (
+        field(&req.signature, "signature"),
+        field(&req.author_public_key, "author_public_key"),
+    ) else {
+        return serde_json::Value::Null;
+    };
+    match verify_signature(&signature, canonical.as_bytes(), &public_key) {
+        Ok(()) => serde_json::json!({ "valid": true, "error": null }),
+        Err(e) => serde_json::json!({ "valid": false, "error": e.to_string() }),
+    }
+}
+
+/// `POST /api/dev/canonical-payload` — the canonical JSON and SHA-256 the
+/// server would verify for a request, and whether its signature matches.
+#[handler]
+pub async fn canonical_payload(ValidJson(req): ValidJson<CanonicalPayloadRequest>) -> Response {
+    if !is_development() {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "Canonical payload builder only available in development",
+        );
+    }
+
+    let payload = match build_payload(&req) {
+        Ok(payload) => payload,
+        Err(response) => return *response,
+    };
+    let canonical = create_canonical_payload(&payload);
+    Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "operation": req.operation,
+            "canonicalJson": canonical,
+            "sha256": sha256_hex(&canonical),
+            "byteLength": canonical.len(),
+            "signature": check_signature(&req, &canonical),
+        }
+    }))
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn request(
+        operation: &str,
+        script_id: Option<&str>,
+        body: serde_json::Value,
+    ) -> CanonicalPayloadRequest {
+        CanonicalPayloadRequest {
+            operation: operation.to_string(),
+            script_id: script_id.map(str::to_string),
+            request: body,
+            signature: None,
+            author_public_key: None,
+        }
+    }
+
+    #[test]
+    fn upload_payload_matches_the_route_builder() {
+        let req = request(
+            "upload",
+            None,
+            serde_json::json!({
+                "slug": "s", "title": "T", "description": "D", "category": "DeFi",
+                "bundle": "b", "author_principal": "p", "tags": ["z", "a"],
+                "timestamp": "2026-01-01T00:00:00Z", "signature": "ignored",
+            }),
+        );
+        assert_eq!(
+            create_canonical_payload(&build_payload(&req).unwrap()),
+            r#"{"action":"upload","author_principal":"p","bundle":"b","category":"DeFi","description":"D","tags":["a","z"],"timestamp":"2026-01-01T00:00:00Z","title":"T","version":"1.0.0"}"#
+        );
+    }
+
+    #[test]
+    fn signature_is_checked_over_the_canonical_string() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut req = request(
+            "delete",
+            Some("script-1"),
+            serde_json::json!({ "author_principal": "p" }),
+        );
+        let canonical = create_canonical_payload(&build_payload(&req).unwrap());
+        req.author_public_key = Some(B64.encode(key.verifying_key().to_bytes()));
+        assert_eq!(check_signature(&req, &canonical), serde_json::Value::Null);
+
+        req.signature = Some(B64.encode(key.sign(canonical.as_bytes()).to_bytes()));
+        assert_eq!(check_signature(&req, &canonical)["valid"], true);
+        assert_eq!(check_signature(&req, "{}")["valid"], false);
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    web::{Data, Json, Path},
+    IntoResponse, Response,
+};
+
+use crate::{
+    models::AppState,
+    responses::error_response,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{require_non_empty, FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Purchase dispute handler
+// ============================================================================
+//
+// POST /api/v1/scripts/:id/dispute → 200 purchase (now `disputed`)
+//
+// Signature-gated like the webhook routes; the canonical payload is
+// `{action, account_id, script_id, reason, nonce, ts}`. Only the purchaser's
+// own `completed` purchase of the script can be disputed. Admins work the
+// queue under `/api/v1/admin/disputes`.
+
+const PURCHASE_DISPUTE_ACTION: &str = "purchase:dispute";
+
+const MAX_REASON_CHARS: usize = 2000;
+
+#[derive(Debug, serde::Deserialize)]
+struct DisputeRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    reason: String,
+}
+
+impl Validate for DisputeRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        if self.reason.chars().count() > MAX_REASON_CHARS {
+            errors.push(FieldError::new(
+                "reason",
+                format!("must be at most {MAX_REASON_CHARS} characters"),
+            ));
+        }
+        errors
+    }
+}
+
+#[handler]
+pub async fn dispute_purchase(
+    Path(script_id): Path<String>,
+    ValidJson(req): ValidJson<DisputeRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let nonce = req.nonce.clone();
+    let ts = req.timestamp;
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        PURCHASE_DISPUTE_ACTION,
+        &SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        |resolved| {
+            serde_json::json!({
+                "action": PURCHASE_DISPUTE_ACTION,
+                "account_id": resolved,
+                "script_id": script_id,
+                "reason": req.reason,
+                "nonce": nonce,
+                "ts": ts,
+            })
+        },
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .dispute_service
+        .open_dispute(&account_id, &script_id, &req.reason)
+        .await
+    {
+        Ok(purchase) => {
+            tracing::info!(
+                account_id = %account_id,
+                "Purchase {} of script {} disputed",
+                purchase.id,
+                script_id
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": purchase
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "purchase dispute failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
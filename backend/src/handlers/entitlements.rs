@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Query},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::SignedIdentity,
+    models::{AppState, ReviewsQuery},
+    responses::error_response,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Entitlement check handler
+// ============================================================================
+//
+// POST /api/v1/entitlements/check → 200 {checkedAt, entitlements: [...]}
+//
+// Lets the app decide locally whether the signed-in account may run a script
+// and cache the answer for offline use. Signature-gated so one account cannot
+// probe another's library; the canonical payload is
+// `{action, account_id, script_ids, nonce, ts}`. Each entry is
+// `{scriptId, owned, source}` with `source` one of `free`, `author`,
+// `purchase` or `null`.
+
+const ENTITLEMENT_CHECK_ACTION: &str = "entitlement:check";
+
+const MAX_SCRIPT_IDS: usize = 200;
+
+#[derive(Debug, serde::Deserialize)]
+struct EntitlementCheckRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    script_ids: Vec<String>,
+}
+
+impl Validate for EntitlementCheckRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self.script_ids.is_empty() {
+            errors.push(FieldError::new("script_ids", "must not be empty"));
+        }
+        if self.script_ids.len() > MAX_SCRIPT_IDS {
+            errors.push(FieldError::new(
+                "script_ids",
+                format!("must list at most {MAX_SCRIPT_IDS} scripts"),
+            ));
+        }
+        errors
+    }
+}
+
+#[handler]
+pub async fn check_entitlements(
+    ValidJson(req): ValidJson<EntitlementCheckRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let nonce = req.nonce.clone();
+    let ts = req.timestamp;
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        ENTITLEMENT_CHECK_ACTION,
+        &SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        |resolved| {
+            serde_json::json!({
+                "action": ENTITLEMENT_CHECK_ACTION,
+                "account_id": resolved,
+                "script_ids": req.script_ids,
+                "nonce": nonce,
+                "ts": ts,
+            })
+        },
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .entitlement_service
+        .check(&account_id, &req.script_ids)
+        .await
+    {
+        Ok(entitlements) => Json(serde_json::json!({
+            "success": true,
+            "data": {
+                "checkedAt": chrono::Utc::now().to_rfc3339(),
+                "entitlements": entitlements
+            }
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!(account_id = %account_id, "Entitlement check failed: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to check entitlements",
+            )
+        }
+    }
+}
+
+/// `GET /api/v1/purchases` — the signed-in account's purchase history,
+/// newest first. A signed GET (see `middleware::signed_identity`).
+#[handler]
+pub async fn get_purchases(
+    identity: SignedIdentity,
+    Query(params): Query<ReviewsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+    match state
+        .entitlement_service
+        .purchases(&identity.account_id, limit, offset)
+        .await
+    {
+        Ok(purchases) => Json(serde_json::json!({
+            "success": true,
+            "data": purchases
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!(account_id = %identity.account_id, "Failed to list purchases: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list purchases",
+            )
+        }
+    }
+}
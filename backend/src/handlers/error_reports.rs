@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path, Query},
+    IntoResponse, Response,
+};
+
+use crate::{
+    error_reports::ErrorReport,
+    middleware::SignedIdentity,
+    models::{AppState, ErrorReportsQuery},
+    responses::error_response,
+    validation::ValidJson,
+};
+
+// ============================================================================
+// Error report handlers
+// ============================================================================
+//
+// Reports are anonymous and unsigned like telemetry (see
+// `crate::error_reports`); only the owner reads them:
+//
+// POST /api/v1/scripts/:id/error-reports   → 202 {stackHash}
+//      body {version, engine_version, error_class, message, stack?}
+// GET  /api/v1/scripts/:id/error-reports   → 200 {reports, total, hasMore, flaggedVersions}
+//      (signed GET, owner; ?version=&limit=&offset=)
+
+/// `POST /api/v1/scripts/:id/error-reports` — stores one execution failure.
+#[handler]
+pub async fn post_error_report(
+    Path(script_id): Path<String>,
+    ValidJson(report): ValidJson<ErrorReport>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.error_report_service.record(&script_id, &report).await {
+        Ok(stack_hash) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({
+                "success": true,
+                "data": { "stackHash": stack_hash }
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to record error report for {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/scripts/:id/error-reports?version=&limit=&offset=` — one
+/// entry per distinct stack, most recently seen first.
+#[handler]
+pub async fn get_error_reports(
+    Path(script_id): Path<String>,
+    Query(params): Query<ErrorReportsQuery>,
+    identity: SignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    match state
+        .error_report_service
+        .list(
+            &identity.account_id,
+            &script_id,
+            params.version.as_deref(),
+            limit,
+            offset,
+        )
+        .await
+    {
+        Ok((reports, total, flags)) => Json(serde_json::json!({
+            "success": true,
+            "data": {
+                "reports": reports,
+                "total": total,
+                "hasMore": i64::from(offset + limit) < total,
+                "flaggedVersions": flags
+            }
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to get error reports of script {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
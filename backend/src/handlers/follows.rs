@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path, Query},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::SignedIdentity,
+    models::{AppState, FeedPage, ReviewsQuery},
+    responses::error_response,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{ValidJson, Validate},
+};
+
+// ============================================================================
+// Follow handlers
+// ============================================================================
+//
+// Signature-gated like the webhook routes: the follower is resolved
+// SERVER-SIDE from the signing key and bound into the payload
+// `{action, account_id, username, nonce, ts}`.
+//
+// POST   /api/v1/accounts/:username/follow   (follow)   → 200 {username, following, followers}
+// DELETE /api/v1/accounts/:username/follow   (unfollow) → 200 {username, following, followers}
+// GET    /api/v1/feed                        (signed GET) → 200 {entries, hasMore, unansweredQuestions}
+//
+// Both writes are idempotent. The feed lists the publications and version
+// releases of followed authors, newest first, in the activity feed shape,
+// plus the caller's own scripts with questions waiting for an answer.
+
+const FOLLOW_ACTION: &str = "account:follow";
+const UNFOLLOW_ACTION: &str = "account:unfollow";
+
+/// Body for both routes: the auth fields only.
+#[derive(Debug, serde::Deserialize)]
+struct FollowRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+}
+
+impl Validate for FollowRequest {}
+
+async fn set_following(
+    state: &AppState,
+    username: &str,
+    req: &FollowRequest,
+    follow: bool,
+) -> Response {
+    let action = if follow {
+        FOLLOW_ACTION
+    } else {
+        UNFOLLOW_ACTION
+    };
+    let auth_fields = SignedAuthFields {
+        signature: &req.signature,
+        author_public_key: &req.author_public_key,
+        author_principal: &req.author_principal,
+        timestamp: req.timestamp,
+        nonce: &req.nonce,
+    };
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        action,
+        &auth_fields,
+        |resolved| {
+            serde_json::json!({
+                "action": action,
+                "account_id": resolved,
+                "username": username,
+                "nonce": req.nonce,
+                "ts": req.timestamp,
+            })
+        },
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .account_service
+        .set_following(&account_id, username, follow)
+        .await
+    {
+        Ok(status) => Json(serde_json::json!({
+            "success": true,
+            "data": status
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "{} failed: {}", action, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn follow_account(
+    Path(username): Path<String>,
+    ValidJson(req): ValidJson<FollowRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    set_following(state, &username, &req, true).await
+}
+
+#[handler]
+pub async fn unfollow_account(
+    Path(username): Path<String>,
+    ValidJson(req): ValidJson<FollowRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    set_following(state, &username, &req, false).await
+}
+
+/// `GET /api/v1/feed?limit=&offset=` — recent publications and releases by
+/// the authors the caller follows, and the caller's unanswered questions. A
+/// signed GET (see `middleware::signed_identity`).
+#[handler]
+pub async fn get_feed(
+    identity: SignedIdentity,
+    Query(params): Query<ReviewsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.map_or(20, i64::from);
+    let offset = params.offset.map_or(0, i64::from);
+    let activity = match state
+        .account_service
+        .followed_feed(&identity.account_id, limit, offset)
+        .await
+    {
+        Ok(activity) => activity,
+        Err(e) => {
+            tracing::error!(account_id = %identity.account_id, "Failed to load feed: {}", e);
+            return error_response(e.status(), e.message());
+        }
+    };
+    match state
+        .question_service
+        .unanswered_for_owner(&identity.account_id)
+        .await
+    {
+        Ok(unanswered_questions) => Json(serde_json::json!({
+            "success": true,
+            "data": FeedPage {
+                activity,
+                unanswered_questions,
+            }
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!(account_id = %identity.account_id, "Failed to load unanswered questions: {}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load feed")
+        }
+    }
+}
@@ -1,15 +1,28 @@
-use poem::{handler, web::Json};
+use std::sync::Arc;
 
+use poem::{handler, web::Json, Request};
+
+use crate::models::AppState;
 use crate::startup_checks::Environment;
 
-/// Builds the canonical payload for script upload signature verification
+/// Liveness plus the maintenance switch. `status` is `"maintenance"` while
+/// writes are being refused; the endpoint itself keeps answering 200.
 #[handler]
-pub async fn health_check() -> Json<serde_json::Value> {
+pub async fn health_check(req: &Request) -> Json<serde_json::Value> {
+    // Read through the request so the handler still works on apps that
+    // carry no AppState (e.g. the CORS tests).
+    let maintenance = match req.data::<Arc<AppState>>() {
+        Some(state) => Some(state.maintenance_service.current().await),
+        None => None,
+    };
+    let in_maintenance = maintenance.as_ref().is_some_and(|m| m.enabled);
     Json(serde_json::json!({
         "success": true,
+        "status": if in_maintenance { "maintenance" } else { "ok" },
         "message": "ICP Marketplace API is running",
         // W7-014: single source of truth for the env label.
         "environment": Environment::current().as_str(),
+        "maintenance": maintenance,
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
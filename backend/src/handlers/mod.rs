@@ -4,22 +4,52 @@
 //! `#[handler]` functions live in their own file (`health.rs`, `scripts.rs`,
 //! …) and are re-exported here for the route table in `main`.
 
+pub mod account_settings;
 pub mod accounts;
 pub mod admin;
+pub mod bundles;
+pub mod compatibility;
+pub mod dev;
+pub mod disputes;
+pub mod entitlements;
+pub mod error_reports;
+pub mod follows;
 pub mod health;
 pub mod ic_proxy;
 pub mod passkey;
 pub mod payments;
+pub mod promotions;
+pub mod questions;
 pub mod recovery;
 pub mod reviews;
 pub mod scripts;
+pub mod telemetry;
 pub mod vault;
+pub mod webhooks;
 
+pub use account_settings::{get_account_settings, update_account_settings};
 pub use accounts::{
-    add_account_key, get_account, get_account_by_public_key, register_account, remove_account_key,
-    update_account,
+    add_account_key, get_account, get_account_activity, get_account_by_public_key,
+    get_key_revocations, get_revoked_keys, register_account, remove_account_key, update_account,
 };
-pub use admin::{admin_add_recovery_key, admin_disable_key, reset_database};
+pub use admin::{
+    admin_account_overview, admin_add_moderation_note, admin_add_recovery_key, admin_add_strike,
+    admin_disable_key, admin_get_maintenance, admin_integrity_check, admin_integrity_reports,
+    admin_list_disputes, admin_list_quarantined_reviews, admin_merge_categories,
+    admin_moderate_review, admin_moderation_status, admin_purchase_history, admin_rename_tag,
+    admin_reset_velocity, admin_resolve_dispute, admin_revoke_strike, admin_search_analytics,
+    admin_search_experiments, admin_set_maintenance, admin_set_tier, admin_shadow_ban,
+    reset_database,
+};
+pub use bundles::{
+    bundle_create, bundle_delete, bundle_update, get_bundle, get_bundles, get_script_bundles,
+};
+pub use compatibility::{get_compatibility, vote_compatibility};
+pub use dev::canonical_payload;
+pub use disputes::dispute_purchase;
+pub use entitlements::{check_entitlements, get_purchases};
+pub use error_reports::{get_error_reports, post_error_report};
+pub use follows::{follow_account, get_feed, unfollow_account};
 pub use health::{health_check, ping};
 // `ic_proxy` is both the module and the handler name; main.rs references it
 // fully-qualified as `handlers::ic_proxy::ic_proxy` to avoid the name clash.
@@ -27,13 +57,19 @@ pub use passkey::{
     passkey_authenticate_finish, passkey_authenticate_start, passkey_delete, passkey_list,
     passkey_register_finish, passkey_register_start,
 };
-pub use payments::download_script;
+pub use payments::{download_script, get_script_source, issue_source_url};
+pub use promotions::{promotion_create, promotion_delete, promotion_list};
+pub use questions::{answer_question, ask_question, get_questions};
 pub use recovery::{recovery_generate, recovery_status, recovery_verify};
 pub use reviews::{create_review, get_reviews};
 pub use scripts::{
-    create_script, delete_script, get_compatible_scripts, get_featured_scripts,
-    get_marketplace_stats, get_script, get_script_categories, get_script_preview, get_scripts,
-    get_scripts_by_category, get_scripts_count, get_trending_scripts, publish_script,
-    search_scripts, update_script,
+    compare_scripts, create_script, delete_script, get_compatible_scripts, get_featured_scripts,
+    get_marketplace_stats, get_pricing, get_script, get_script_categories, get_script_channels,
+    get_script_dependencies, get_script_embed, get_script_limits, get_script_preview,
+    get_script_readme_html, get_script_validation, get_scripts, get_scripts_by_category,
+    get_scripts_count, get_trending_scripts, preview_script_execution, publish_script,
+    record_search_click, search_scripts, update_script,
 };
+pub use telemetry::{get_script_reliability, post_telemetry};
 pub use vault::{vault_create, vault_get, vault_update};
+pub use webhooks::{webhook_delete, webhook_get, webhook_set};
@@ -13,6 +13,7 @@ use crate::{
     responses::error_response,
     services::{PasskeyAuthenticationFinish, PasskeyRegistrationFinish},
     signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{ValidJson, Validate},
 };
 
 // ============================================================================
@@ -52,9 +53,11 @@ struct PasskeyRegisterStartRequest {
     nonce: String,
 }
 
+impl Validate for PasskeyRegisterStartRequest {}
+
 #[handler]
 pub async fn passkey_register_start(
-    Json(req): Json<PasskeyRegisterStartRequest>,
+    ValidJson(req): ValidJson<PasskeyRegisterStartRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let account_repo = &state.script_service.account_repo;
@@ -100,7 +103,7 @@ pub async fn passkey_register_start(
 
 #[handler]
 pub async fn passkey_register_finish(
-    Json(req): Json<PasskeyRegistrationFinish>,
+    ValidJson(req): ValidJson<PasskeyRegistrationFinish>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state.passkey_service.finish_registration(req).await {
@@ -121,9 +124,11 @@ struct PasskeyAuthStartRequest {
     account_id: String,
 }
 
+impl Validate for PasskeyAuthStartRequest {}
+
 #[handler]
 pub async fn passkey_authenticate_start(
-    Json(req): Json<PasskeyAuthStartRequest>,
+    ValidJson(req): ValidJson<PasskeyAuthStartRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state
@@ -142,7 +147,7 @@ pub async fn passkey_authenticate_start(
 
 #[handler]
 pub async fn passkey_authenticate_finish(
-    Json(req): Json<PasskeyAuthenticationFinish>,
+    ValidJson(req): ValidJson<PasskeyAuthenticationFinish>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     match state.passkey_service.finish_authentication(req).await {
@@ -182,10 +187,12 @@ struct PasskeyDeleteRequest {
     nonce: String,
 }
 
+impl Validate for PasskeyDeleteRequest {}
+
 #[handler]
 pub async fn passkey_delete(
     Path(passkey_id): Path<String>,
-    Json(req): Json<PasskeyDeleteRequest>,
+    ValidJson(req): ValidJson<PasskeyDeleteRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let account_repo = &state.script_service.account_repo;
@@ -3,15 +3,18 @@ use std::sync::Arc;
 use poem::{
     handler,
     http::StatusCode,
-    web::{Data, Json, Path},
+    web::{Data, Json, Path, Query},
     IntoResponse, Response,
 };
+use serde::Deserialize;
 
 use crate::{
-    auth,
-    models::{AppState, DownloadRequest},
+    auth, content_store,
+    models::{AppState, ChannelQuery, DownloadRequest, Script},
+    release_channel::ReleaseChannel,
     repositories::SignatureAuditParams,
     responses::error_response,
+    validation::ValidJson,
 };
 
 /// Canonical signature payload for `POST /api/v1/scripts/:id/download`. The
@@ -23,20 +26,23 @@ fn build_download_payload(script_id: &str, timestamp: &str, nonce: &str) -> Stri
     format!("download:{script_id}:{timestamp}:{nonce}")
 }
 
-/// Authenticated bundle retrieval. `POST /api/v1/scripts/:id/download`.
-///
-/// All scripts are free — this endpoint exists for the download counter +
-/// signed-audit trail (replay prevention). Verifies an Ed25519 signature over
-/// `download:{script_id}:{timestamp}:{nonce}` with the public key in the body,
-/// resolves the owning account via the public-keys table, records the
-/// signature audit (single-use nonce), bumps the downloads counter, and
-/// returns the bundle.
-#[handler]
-pub async fn download_script(
-    Path(script_id): Path<String>,
-    Json(req): Json<DownloadRequest>,
-    Data(state): Data<&Arc<AppState>>,
-) -> Response {
+/// Canonical signature payload for `POST /api/v1/scripts/:id/source-url`.
+/// Same shape as the download payload under its own prefix, so a signed
+/// download can never be replayed as a URL request or vice versa.
+fn build_source_url_payload(script_id: &str, timestamp: &str, nonce: &str) -> String {
+    format!("source-url:{script_id}:{timestamp}:{nonce}")
+}
+
+/// Steps shared by every signed bundle fetch: resolve the account that owns
+/// `req.public_key`, verify the signature over `payload`, and check the
+/// timestamp/nonce for freshness. Returns `(account_id, timestamp_unix)`, or
+/// the error response to send.
+async fn verify_signed_fetch(
+    state: &AppState,
+    script_id: &str,
+    req: &DownloadRequest,
+    payload: &str,
+) -> Result<(String, i64), Response> {
     // 1. Resolve account_id from public_key FIRST. Unknown key → 401 (do not
     //    even attempt signature verify against an unbound key).
     let account_id = match state
@@ -48,54 +54,62 @@ pub async fn download_script(
         Ok(Some(key)) => key.account_id,
         Ok(None) => {
             tracing::warn!(
-                "Download rejected: public key not bound to any account (script={})",
+                "Signed fetch rejected: public key not bound to any account (script={})",
                 script_id
             );
-            return error_response(StatusCode::UNAUTHORIZED, "Unknown public key");
+            return Err(error_response(
+                StatusCode::UNAUTHORIZED,
+                "Unknown public key",
+            ));
         }
         Err(e) => {
             tracing::error!(
-                "Failed to lookup public key for download (script={}): {}",
+                "Failed to lookup public key for signed fetch (script={}): {}",
                 script_id,
                 e
             );
-            return error_response(
+            return Err(error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Failed to resolve account for download",
-            );
+            ));
         }
     };
 
     // 2. Verify Ed25519 signature over the canonical payload.
-    let payload = build_download_payload(&script_id, &req.timestamp, &req.nonce);
     if let Err(e) =
         auth::verify_ed25519_signature(&req.signature, payload.as_bytes(), &req.public_key)
     {
         tracing::warn!(
-            "Download rejected: signature verification failed (script={}, account={}): {}",
+            "Signed fetch rejected: signature verification failed (script={}, account={}): {}",
             script_id,
             account_id,
             e
         );
-        return error_response(StatusCode::UNAUTHORIZED, "Invalid signature");
+        return Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Invalid signature",
+        ));
     }
 
-    // 2b. Replay prevention: the signed `timestamp`+`nonce` MUST be
-    //     freshness-checked and single-use, exactly like every account
-    //     mutation in `account_service`. A captured signed download is
-    //     otherwise replayable verbatim. Mirrors the account_service pattern:
-    //     InvalidFormat (bad/out-of-range timestamp) → 400, InvalidSignature
-    //     (replayed nonce) → 401.
+    // 3. Replay prevention: the signed `timestamp`+`nonce` MUST be
+    //    freshness-checked and single-use, exactly like every account
+    //    mutation in `account_service`. A captured signed download is
+    //    otherwise replayable verbatim. Mirrors the account_service pattern:
+    //    InvalidFormat (bad/out-of-range timestamp) → 400, InvalidSignature
+    //    (replayed nonce) → 401.
     let timestamp_unix = match chrono::DateTime::parse_from_rfc3339(&req.timestamp) {
         Ok(dt) => dt.timestamp(),
         Err(e) => {
             tracing::warn!(
-                "Download rejected: unparseable timestamp (script={}, account={}): {}",
+                "Signed fetch rejected: unparseable timestamp (script={}, account={}): {}",
                 script_id,
                 account_id,
                 e
             );
-            return error_response(StatusCode::BAD_REQUEST, "Invalid timestamp format");
+            return Err(error_response(
+                StatusCode::BAD_REQUEST,
+                "Invalid timestamp format",
+            ));
         }
     };
     if let Err(e) = auth::validate_replay_prevention(&state.pool, timestamp_unix, &req.nonce).await
@@ -105,38 +119,50 @@ pub async fn download_script(
             _ => StatusCode::UNAUTHORIZED,
         };
         tracing::warn!(
-            "Download rejected: replay prevention failed (script={}, account={}): {}",
+            "Signed fetch rejected: replay prevention failed (script={}, account={}): {}",
             script_id,
             account_id,
             e
         );
-        return error_response(status, "Replay prevention failed");
+        return Err(error_response(status, "Replay prevention failed"));
     }
 
-    // 3. Load script.
-    let script = match state.script_service.get_script(&script_id).await {
-        Ok(Some(s)) => s,
-        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Script not found"),
+    Ok((account_id, timestamp_unix))
+}
+
+/// Loads the script a signed fetch targets (404 when missing).
+async fn load_script(state: &AppState, script_id: &str) -> Result<Script, Response> {
+    match state.script_service.get_script(script_id).await {
+        Ok(Some(s)) => Ok(s),
+        Ok(None) => Err(error_response(StatusCode::NOT_FOUND, "Script not found")),
         Err(e) => {
             tracing::error!("Failed to load script for download {}: {}", script_id, e);
-            return error_response(
+            Err(error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Failed to load script for download",
-            );
+            ))
         }
-    };
+    }
+}
 
-    // 4. Record the signature audit so the `(timestamp, nonce)` pair is
-    //    single-use within the 10-minute window — this is the WRITE side of
-    //    replay prevention (step 2b was the CHECK side). Security-relevant:
-    //    if we cannot record the nonce, we MUST NOT hand over the bundle,
-    //    because that request would then be replayable. (Unlike the counter
-    //    bump below, this is not best-effort.) Mirrors account_service.
-    //
-    //    W7-011: the DB UNIQUE constraint on `signature_audit.nonce` closes
-    //    the TOCTOU window between the CHECK (step 2b) and this WRITE. A
-    //    unique-violation = a concurrent download won the race with the same
-    //    nonce → replay → 401 (do NOT release the bundle).
+/// Records the signature audit so the `(timestamp, nonce)` pair is
+/// single-use within the 10-minute window — the WRITE side of replay
+/// prevention (`verify_signed_fetch` was the CHECK side). Security-relevant:
+/// if the nonce cannot be recorded, the caller MUST NOT release anything,
+/// because that request would then be replayable. Mirrors account_service.
+///
+/// W7-011: the DB UNIQUE constraint on `signature_audit.nonce` closes the
+/// TOCTOU window between the CHECK and this WRITE. A unique-violation = a
+/// concurrent request won the race with the same nonce → replay → 401.
+async fn record_fetch_audit(
+    state: &AppState,
+    script_id: &str,
+    account_id: &str,
+    action: &str,
+    req: &DownloadRequest,
+    payload: &str,
+    timestamp_unix: i64,
+) -> Result<(), Response> {
     let audit_id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
     match auth::classify_audit_write(
@@ -145,9 +171,9 @@ pub async fn download_script(
             .account_repo
             .record_signature_audit(SignatureAuditParams {
                 audit_id: &audit_id,
-                account_id: Some(&account_id),
-                action: "download_script",
-                payload: &payload,
+                account_id: Some(account_id),
+                action,
+                payload,
                 signature: &req.signature,
                 public_key: &req.public_key,
                 timestamp: timestamp_unix,
@@ -157,46 +183,280 @@ pub async fn download_script(
             })
             .await,
     ) {
-        Ok(auth::AuditOutcome::Ok) => {}
+        Ok(auth::AuditOutcome::Ok) => Ok(()),
         Ok(auth::AuditOutcome::Replay) => {
             tracing::warn!(
-                "Download rejected: nonce UNIQUE constraint fired — concurrent replay \
+                "{} rejected: nonce UNIQUE constraint fired — concurrent replay \
                  (script={}, account={})",
+                action,
                 script_id,
                 account_id
             );
-            return error_response(StatusCode::UNAUTHORIZED, "Replay prevention failed");
+            Err(error_response(
+                StatusCode::UNAUTHORIZED,
+                "Replay prevention failed",
+            ))
         }
         Err(e) => {
             tracing::error!(
-                "Failed to record download audit — refusing to release bundle (script={}, account={}): {}",
+                "Failed to record {} audit — refusing to release bundle (script={}, account={}): {}",
+                action,
                 script_id,
                 account_id,
                 e
             );
-            return error_response(
+            Err(error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Failed to record download audit",
-            );
+            ))
         }
     }
+}
 
-    // 5. Bump downloads counter. Best-effort: a counter failure does NOT
-    //    block the download — the entitlement decision is the
-    //    security-relevant part and already succeeded.
-    if let Err(e) = state.script_service.increment_downloads(&script_id).await {
+/// Bumps the downloads counter. Best-effort: a counter failure does NOT
+/// block the download — the entitlement decision is the security-relevant
+/// part and already succeeded.
+async fn bump_downloads(state: &AppState, script_id: &str) {
+    if let Err(e) = state.script_service.increment_downloads(script_id).await {
         tracing::warn!(
             "Download succeeded but failed to bump downloads counter for {}: {}",
             script_id,
             e
         );
     }
+}
+
+/// Authenticated bundle retrieval. `POST /api/v1/scripts/:id/download`.
+///
+/// All scripts are free — this endpoint exists for the download counter +
+/// signed-audit trail (replay prevention). Verifies an Ed25519 signature over
+/// `download:{script_id}:{timestamp}:{nonce}` with the public key in the body,
+/// resolves the owning account via the public-keys table, records the
+/// signature audit (single-use nonce), bumps the downloads counter, and
+/// returns the bundle.
+///
+/// `?channel=beta` returns the beta build instead when there is one. The
+/// channel is not signed: it only picks between builds of the same script.
+#[handler]
+pub async fn download_script(
+    Path(script_id): Path<String>,
+    Query(query): Query<ChannelQuery>,
+    ValidJson(req): ValidJson<DownloadRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let channel = match query.resolve() {
+        Ok(channel) => channel,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    let payload = build_download_payload(&script_id, &req.timestamp, &req.nonce);
+    let (account_id, timestamp_unix) =
+        match verify_signed_fetch(state, &script_id, &req, &payload).await {
+            Ok(verified) => verified,
+            Err(resp) => return resp,
+        };
+    let script = match load_script(state, &script_id).await {
+        Ok(script) => script,
+        Err(resp) => return resp,
+    };
+    // Same as `get_script`: a private script does not exist for anyone but
+    // its owner, whichever channel is asked for.
+    if !script.is_public && script.owner_account_id.as_deref() != Some(account_id.as_str()) {
+        return error_response(StatusCode::NOT_FOUND, "Script not found");
+    }
+    let release = match state
+        .script_service
+        .channel_release(&script_id, channel)
+        .await
+    {
+        Ok(release) => release,
+        Err(e) => {
+            tracing::error!(
+                "Failed to load {} build for download {}: {}",
+                channel.as_str(),
+                script_id,
+                e
+            );
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load script for download",
+            );
+        }
+    };
+    let (bundle, bundle_sha256, version, channel) = match release {
+        Some(release) => (
+            release.bundle,
+            release.bundle_sha256,
+            release.version,
+            release.channel,
+        ),
+        None => (
+            script.bundle,
+            script.bundle_sha256,
+            script.version,
+            ReleaseChannel::Stable.as_str().to_string(),
+        ),
+    };
+    let sha256 = match verified_sha256(&script_id, &bundle, bundle_sha256.as_deref()) {
+        Ok(sha256) => sha256,
+        Err(resp) => return resp,
+    };
+    if let Err(resp) = record_fetch_audit(
+        state,
+        &script_id,
+        &account_id,
+        "download_script",
+        &req,
+        &payload,
+        timestamp_unix,
+    )
+    .await
+    {
+        return resp;
+    }
+    bump_downloads(state, &script_id).await;
+
+    Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "bundle": bundle,
+            "sha256": sha256,
+            "version": version,
+            "channel": channel,
+        }
+    }))
+    .into_response()
+}
+
+/// The SHA-256 of a bundle about to be served, or a 500 when it no longer
+/// matches the blob key it was stored under (see [`crate::content_store`]).
+fn verified_sha256(
+    script_id: &str,
+    bundle: &str,
+    stored_sha256: Option<&str>,
+) -> Result<String, Response> {
+    content_store::verify(bundle, stored_sha256).map_err(|actual| {
+        tracing::error!(
+            "Bundle of script {} failed its integrity check: stored as {:?}, hashes to {}",
+            script_id,
+            stored_sha256,
+            actual
+        );
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Stored script failed its integrity check",
+        )
+    })
+}
+
+/// Issues a short-lived signed source URL.
+/// `POST /api/v1/scripts/:id/source-url`.
+///
+/// Authenticated exactly like `download_script` (same body, payload prefix
+/// `source-url:`). Public scripts are available to any account; a private
+/// script only to its owner, and is a 404 for everyone else as in
+/// `download_script`. Issuing counts as the download, so fetching the
+/// URL (possibly several times, e.g. a resumed transfer) does not.
+#[handler]
+pub async fn issue_source_url(
+    Path(script_id): Path<String>,
+    ValidJson(req): ValidJson<DownloadRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let payload = build_source_url_payload(&script_id, &req.timestamp, &req.nonce);
+    let (account_id, timestamp_unix) =
+        match verify_signed_fetch(state, &script_id, &req, &payload).await {
+            Ok(verified) => verified,
+            Err(resp) => return resp,
+        };
+    let script = match load_script(state, &script_id).await {
+        Ok(script) => script,
+        Err(resp) => return resp,
+    };
+    if !script.is_public && script.owner_account_id.as_deref() != Some(account_id.as_str()) {
+        tracing::warn!(
+            "Source URL rejected: private script not owned by caller (script={}, account={})",
+            script_id,
+            account_id
+        );
+        return error_response(StatusCode::NOT_FOUND, "Script not found");
+    }
+    if let Err(resp) = record_fetch_audit(
+        state,
+        &script_id,
+        &account_id,
+        "issue_source_url",
+        &req,
+        &payload,
+        timestamp_unix,
+    )
+    .await
+    {
+        return resp;
+    }
+    bump_downloads(state, &script_id).await;
 
+    let (token, expires_at) =
+        state
+            .source_urls
+            .issue(&script_id, &account_id, chrono::Utc::now().timestamp());
+    let expires_at = chrono::DateTime::from_timestamp(expires_at, 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default();
     Json(serde_json::json!({
         "success": true,
         "data": {
-            "bundle": script.bundle,
+            "url": format!("/api/v1/scripts/{script_id}/source?token={token}"),
+            "expiresAt": expires_at,
         }
     }))
     .into_response()
 }
+
+#[derive(Debug, Deserialize)]
+pub struct SourceQuery {
+    pub token: String,
+}
+
+/// Serves the raw bundle behind a signed source URL.
+/// `GET /api/v1/scripts/:id/source?token=…`.
+///
+/// The token is the only credential, so the response is `text/plain` with
+/// `Cache-Control: private, no-store`. Bad and expired tokens both get 403;
+/// the distinction is only logged. A private script whose owner changed
+/// since issuance is refused even while the token is still valid.
+#[handler]
+pub async fn get_script_source(
+    Path(script_id): Path<String>,
+    Query(query): Query<SourceQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id =
+        match state
+            .source_urls
+            .verify(&script_id, &query.token, chrono::Utc::now().timestamp())
+        {
+            Ok(account_id) => account_id,
+            Err(e) => {
+                tracing::warn!("Source URL rejected (script={}): {}", script_id, e);
+                return error_response(StatusCode::FORBIDDEN, "Invalid or expired source URL");
+            }
+        };
+    let script = match load_script(state, &script_id).await {
+        Ok(script) => script,
+        Err(resp) => return resp,
+    };
+    if !script.is_public && script.owner_account_id.as_deref() != Some(account_id.as_str()) {
+        return error_response(StatusCode::FORBIDDEN, "Invalid or expired source URL");
+    }
+    let sha256 = match verified_sha256(&script_id, &script.bundle, script.bundle_sha256.as_deref())
+    {
+        Ok(sha256) => sha256,
+        Err(resp) => return resp,
+    };
+
+    Response::builder()
+        .content_type("text/plain; charset=utf-8")
+        .header(poem::http::header::CACHE_CONTROL, "private, no-store")
+        .header(content_store::BUNDLE_SHA256_HEADER, sha256)
+        .body(script.bundle)
+}
@@ -0,0 +1,221 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::SignedIdentity,
+    models::AppState,
+    responses::error_response,
+    services::NewPromotion,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{require_non_empty, FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Script promotion handlers
+// ============================================================================
+//
+// Owner-only and signature-gated like the webhook routes. The canonical
+// payload is `{action, account_id, script_id, nonce, ts}`, plus
+// `kind, amount, starts_at, ends_at` for create and `promotion_id` for delete.
+//
+// POST   /api/v1/scripts/:id/promotions                 (create)     → 201 promotion
+// GET    /api/v1/scripts/:id/promotions                 (signed GET) → 200 [promotion]
+// DELETE /api/v1/scripts/:id/promotions/:promotion_id   (remove)     → 200 / 404
+//
+// A running promotion shows up in the script listings as
+// `discounted_price_e8s` + `promotion_ends_at`.
+
+const PROMOTION_CREATE_ACTION: &str = "promotion:create";
+const PROMOTION_DELETE_ACTION: &str = "promotion:delete";
+
+#[derive(Debug, serde::Deserialize)]
+struct PromotionCreateRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    kind: String,
+    amount: i64,
+    starts_at: String,
+    ends_at: String,
+}
+
+impl Validate for PromotionCreateRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "kind", &self.kind);
+        require_non_empty(&mut errors, "starts_at", &self.starts_at);
+        require_non_empty(&mut errors, "ends_at", &self.ends_at);
+        errors
+    }
+}
+
+/// Body for the delete route: the auth fields only.
+#[derive(Debug, serde::Deserialize)]
+struct PromotionAuthRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+}
+
+impl Validate for PromotionAuthRequest {}
+
+async fn resolve_account(
+    state: &AppState,
+    action: &'static str,
+    auth_fields: SignedAuthFields<'_>,
+    extra: serde_json::Value,
+) -> Result<String, Response> {
+    let nonce = auth_fields.nonce;
+    let ts = auth_fields.timestamp;
+    verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        action,
+        &auth_fields,
+        |resolved| {
+            let mut payload = serde_json::json!({
+                "action": action,
+                "account_id": resolved,
+                "nonce": nonce,
+                "ts": ts,
+            });
+            if let (Some(payload), serde_json::Value::Object(extra)) =
+                (payload.as_object_mut(), extra)
+            {
+                payload.extend(extra);
+            }
+            payload
+        },
+    )
+    .await
+    .map_err(|r| error_response(r.status, r.message))
+}
+
+#[handler]
+pub async fn promotion_create(
+    Path(script_id): Path<String>,
+    ValidJson(req): ValidJson<PromotionCreateRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        PROMOTION_CREATE_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        serde_json::json!({
+            "script_id": script_id,
+            "kind": req.kind,
+            "amount": req.amount,
+            "starts_at": req.starts_at,
+            "ends_at": req.ends_at,
+        }),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    match state
+        .promotion_service
+        .create_promotion(
+            &account_id,
+            &script_id,
+            NewPromotion {
+                kind: &req.kind,
+                amount: req.amount,
+                starts_at: &req.starts_at,
+                ends_at: &req.ends_at,
+            },
+        )
+        .await
+    {
+        Ok(promotion) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "success": true,
+                "data": promotion
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "promotion create failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/scripts/:id/promotions` — the script's promotions, for its
+/// owner. A signed GET (see `middleware::signed_identity`).
+#[handler]
+pub async fn promotion_list(
+    Path(script_id): Path<String>,
+    identity: SignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .promotion_service
+        .list_promotions(&identity.account_id, &script_id)
+        .await
+    {
+        Ok(promotions) => Json(serde_json::json!({
+            "success": true,
+            "data": promotions
+        }))
+        .into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
+
+#[handler]
+pub async fn promotion_delete(
+    Path((script_id, promotion_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<PromotionAuthRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        PROMOTION_DELETE_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        serde_json::json!({
+            "script_id": script_id,
+            "promotion_id": promotion_id,
+        }),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    match state
+        .promotion_service
+        .delete_promotion(&account_id, &script_id, &promotion_id)
+        .await
+    {
+        Ok(()) => Json(serde_json::json!({ "success": true })).into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path, Query},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::OptionalSignedIdentity,
+    models::{AppState, QuestionResponse, QuestionsQuery},
+    responses::error_response,
+    services::{QUESTION_ANSWER_ACTION, QUESTION_ASK_ACTION},
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{require_non_empty, FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Script Q&A handlers
+// ============================================================================
+//
+// Any account may ask; only the script owner answers. Both writes are
+// signature-gated, the account resolved SERVER-SIDE from the signing key and
+// bound into the payload together with the text, so neither the script nor
+// the wording can change after signing:
+//
+// GET  /api/v1/scripts/:id/questions                       → 200 {questions, total, hasMore}
+// POST /api/v1/scripts/:id/questions                       → 201 question
+//      payload {action: "question:ask", account_id, script_id, question, nonce, ts}
+// POST /api/v1/scripts/:id/questions/:question_id/answer   → 200 question
+//      payload {action: "question:answer", account_id, script_id, question_id, answer, nonce, ts}
+//
+// Unanswered questions are counted on the script detail
+// (`unansweredQuestions`) and listed per script in the owner's feed.
+
+#[derive(Debug, serde::Deserialize)]
+struct AskQuestionRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    question: String,
+}
+
+impl Validate for AskQuestionRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "question", &self.question);
+        errors
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AnswerQuestionRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    answer: String,
+}
+
+impl Validate for AnswerQuestionRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "answer", &self.answer);
+        errors
+    }
+}
+
+/// `GET /api/v1/scripts/:id/questions?limit=&offset=&unanswered=` — newest
+/// first. A private script's questions need a signed GET by its owner.
+#[handler]
+pub async fn get_questions(
+    Path(script_id): Path<String>,
+    Query(params): Query<QuestionsQuery>,
+    identity: OptionalSignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    match state
+        .question_service
+        .list(
+            &script_id,
+            identity.account_id(),
+            params.unanswered,
+            limit,
+            offset,
+        )
+        .await
+    {
+        Ok((questions, total)) => Json(serde_json::json!({
+            "success": true,
+            "data": {
+                "questions": questions.into_iter().map(QuestionResponse::from).collect::<Vec<_>>(),
+                "total": total,
+                "hasMore": i64::from(offset + limit) < total
+            }
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to get questions for script {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn ask_question(
+    Path(script_id): Path<String>,
+    ValidJson(req): ValidJson<AskQuestionRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        QUESTION_ASK_ACTION,
+        &SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        |resolved| {
+            serde_json::json!({
+                "action": QUESTION_ASK_ACTION,
+                "account_id": resolved,
+                "script_id": script_id,
+                "question": req.question,
+                "nonce": req.nonce,
+                "ts": req.timestamp,
+            })
+        },
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .question_service
+        .ask(&account_id, &script_id, &req.question)
+        .await
+    {
+        Ok(question) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "success": true,
+                "data": QuestionResponse::from(question)
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "{} failed: {}", QUESTION_ASK_ACTION, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+#[handler]
+pub async fn answer_question(
+    Path((script_id, question_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<AnswerQuestionRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        QUESTION_ANSWER_ACTION,
+        &SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        |resolved| {
+            serde_json::json!({
+                "action": QUESTION_ANSWER_ACTION,
+                "account_id": resolved,
+                "script_id": script_id,
+                "question_id": question_id,
+                "answer": req.answer,
+                "nonce": req.nonce,
+                "ts": req.timestamp,
+            })
+        },
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(r) => return error_response(r.status, r.message),
+    };
+
+    match state
+        .question_service
+        .answer(&account_id, &script_id, &question_id, &req.answer)
+        .await
+    {
+        Ok(question) => Json(serde_json::json!({
+            "success": true,
+            "data": QuestionResponse::from(question)
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "{} failed: {}", QUESTION_ANSWER_ACTION, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
     models::AppState,
     responses::error_response,
     signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{ValidJson, Validate},
 };
 
 // ============================================================================
@@ -48,9 +49,11 @@ struct RecoveryGenerateRequest {
     nonce: String,
 }
 
+impl Validate for RecoveryGenerateRequest {}
+
 #[handler]
 pub async fn recovery_generate(
-    Json(req): Json<RecoveryGenerateRequest>,
+    ValidJson(req): ValidJson<RecoveryGenerateRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let account_repo = &state.script_service.account_repo;
@@ -103,9 +106,11 @@ struct RecoveryVerifyRequest {
     code: String,
 }
 
+impl Validate for RecoveryVerifyRequest {}
+
 #[handler]
 pub async fn recovery_verify(
-    Json(req): Json<RecoveryVerifyRequest>,
+    ValidJson(req): ValidJson<RecoveryVerifyRequest>,
     Data(state): Data<&Arc<AppState>>,
     RealIp(ip): RealIp,
 ) -> Response {
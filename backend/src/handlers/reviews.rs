@@ -9,19 +9,21 @@ use poem::{
 };
 
 use crate::{
-    models::{AppState, CreateReviewRequest, ReviewsQuery},
+    middleware::OptionalSignedIdentity,
+    models::{AppState, CreateReviewRequest, ReviewResponse, ReviewsQuery},
     responses::error_response,
+    services::REVIEW_CREATE_ACTION,
     signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{FieldError, ValidJson, Validate},
 };
 
-/// Single source of truth for the signed review action name. The frontend
-/// mirrors this EXACT string inside the canonical payload.
-const REVIEW_CREATE_ACTION: &str = "review:create";
-
+/// `GET /api/v1/scripts/:id/reviews` — a page of the script's reviews. A
+/// shadow-banned reviewer who signs the GET still sees their own.
 #[handler]
 pub async fn get_reviews(
     Path(script_id): Path<String>,
     Query(params): Query<ReviewsQuery>,
+    identity: OptionalSignedIdentity,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let limit = params.limit.unwrap_or(20);
@@ -29,13 +31,13 @@ pub async fn get_reviews(
 
     match state
         .review_service
-        .get_reviews(&script_id, limit, offset)
+        .get_reviews(&script_id, identity.account_id(), limit, offset)
         .await
     {
         Ok((reviews, total)) => Json(serde_json::json!({
             "success": true,
             "data": {
-                "reviews": reviews,
+                "reviews": reviews.into_iter().map(ReviewResponse::from).collect::<Vec<_>>(),
                 "total": total,
                 "hasMore": (offset + limit) < total
             }
@@ -69,10 +71,20 @@ struct CreateReviewWireRequest {
     comment: Option<String>,
 }
 
+impl Validate for CreateReviewWireRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        if (1..=5).contains(&self.rating) {
+            Vec::new()
+        } else {
+            vec![FieldError::new("rating", "must be between 1 and 5")]
+        }
+    }
+}
+
 #[handler]
 pub async fn create_review(
     Path(script_id): Path<String>,
-    Json(req): Json<CreateReviewWireRequest>,
+    ValidJson(req): ValidJson<CreateReviewWireRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let account_repo = &state.script_service.account_repo;
@@ -110,6 +122,7 @@ pub async fn create_review(
         user_id,
         rating: req.rating,
         comment: req.comment,
+        public_key: Some(req.author_public_key),
     };
 
     match state
@@ -117,17 +130,36 @@ pub async fn create_review(
         .create_review(&script_id, review_req)
         .await
     {
-        Ok(review) => {
-            tracing::info!(
-                "Created review for script {} by user {}",
-                script_id,
-                review.user_id
-            );
+        Ok(submission) => {
+            let review = submission.review;
+            // A quarantined review is stored but hidden until moderated; the
+            // author gets 202 so the client can say so instead of showing it.
+            let status = match submission.quarantine_reason {
+                Some(reason) => {
+                    tracing::warn!(
+                        "Quarantined review {} for script {} by user {}: {}",
+                        review.id,
+                        script_id,
+                        review.user_id,
+                        reason.as_str()
+                    );
+                    StatusCode::ACCEPTED
+                }
+                None => {
+                    tracing::info!(
+                        "Created review for script {} by user {}",
+                        script_id,
+                        review.user_id
+                    );
+                    StatusCode::CREATED
+                }
+            };
             (
-                StatusCode::CREATED,
+                status,
                 Json(serde_json::json!({
                     "success": true,
-                    "data": review
+                    "data": ReviewResponse::from(review),
+                    "pendingModeration": submission.quarantine_reason.is_some()
                 })),
             )
                 .into_response()
@@ -4,38 +4,76 @@ use poem::{
     error::ResponseError,
     handler,
     http::StatusCode,
-    web::{Data, Json, Path, Query},
-    IntoResponse, Response,
+    web::{Data, Json, Path, Query, RealIp},
+    IntoResponse, Request, Response,
 };
 
 use crate::{
-    middleware,
+    middleware::{self, OptionalSignedIdentity},
     models::{
-        scripts_to_list_json, AppState, CreateScriptRequest, DeleteScriptRequest,
-        ScriptDetailResponse, ScriptsQuery, SearchRequest, UpdateScriptRequest,
+        attach_compatibility, attach_offers, scripts_to_list_json, AppState, ChannelQuery,
+        CompareQuery, CreateScriptRequest, DeleteScriptRequest, EmbedQuery, Script,
+        ScriptDetailResponse, ScriptsQuery, SearchClickRequest, SearchRequest, StatsQuery,
+        TrendingQuery, TrendingScope, UpdateScriptRequest,
     },
+    pricing, readme,
+    release_channel::ReleaseChannel,
     responses::error_response,
+    script_embed::{EmbedFormat, ScriptEmbed},
+    script_preview::{self, PreviewRequest},
     startup_checks::verify_script_ownership,
+    validation::ValidJson,
 };
 
+/// Browse-list JSON for `scripts` with running promotions and compatibility
+/// votes attached. A failed promotion lookup only drops the discount from
+/// the listing; the purchase price is checked separately.
+async fn listing_json(state: &AppState, scripts: &[Script]) -> serde_json::Value {
+    let mut list = scripts_to_list_json(scripts);
+    match state.promotion_service.offers_for(scripts).await {
+        Ok(offers) => attach_offers(&mut list, &offers),
+        Err(e) => tracing::warn!("Failed to load promotions for listing: {}", e),
+    }
+    match state.compatibility_service.summaries_for(scripts).await {
+        Ok(summaries) => attach_compatibility(&mut list, &summaries),
+        Err(e) => tracing::warn!("Failed to load compatibility for listing: {}", e),
+    }
+    list
+}
+
+/// `GET /api/v1/scripts` — public listing. `includePrivate=true` adds the
+/// caller's own private scripts and requires a signed request.
 #[handler]
 pub async fn get_scripts(
     Query(params): Query<ScriptsQuery>,
+    identity: OptionalSignedIdentity,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let limit = params.limit.unwrap_or(20);
     let offset = params.offset.unwrap_or(0);
-    let include_private = params.include_private.unwrap_or(false);
+    let private_of = if params.include_private.unwrap_or(false) {
+        match identity.account_id() {
+            Some(account_id) => Some(account_id),
+            None => {
+                return error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "Signed request required to include private scripts",
+                )
+            }
+        }
+    } else {
+        None
+    };
 
     match state
         .script_service
-        .get_scripts(limit, offset, params.category, include_private)
+        .get_scripts(limit, offset, params.category, private_of)
         .await
     {
         Ok((scripts, total)) => Json(serde_json::json!({
             "success": true,
             "data": {
-                "scripts": scripts_to_list_json(&scripts),
+                "scripts": listing_json(state, &scripts).await,
                 "total": total,
                 "hasMore": (offset + limit) < total as i32
             }
@@ -51,12 +89,45 @@ pub async fn get_scripts(
 /// `GET /api/v1/scripts/:id` — public script detail.
 ///
 /// All scripts are free — the full bundle is always included.
+/// Optional header the app sets to the signed-in public key, so a viewer is
+/// also recognised across networks. Unverified: it can only suppress views,
+/// never add any, because the IP is always checked too.
+const VIEWER_KEY_HEADER: &str = "x-viewer-public-key";
+
+/// Longest viewer key remembered; anything longer is ignored.
+const MAX_VIEWER_KEY_LEN: usize = 256;
+
+fn viewer_identities(req: &Request, ip: Option<std::net::IpAddr>) -> Vec<String> {
+    let ip = ip
+        .map(|addr| addr.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut viewers = vec![format!("ip:{ip}")];
+    if let Some(key) = req
+        .headers()
+        .get(VIEWER_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty() && k.len() <= MAX_VIEWER_KEY_LEN)
+    {
+        viewers.push(format!("key:{key}"));
+    }
+    viewers
+}
+
 #[handler]
 pub async fn get_script(
+    req: &Request,
     Path(script_id): Path<String>,
+    Query(query): Query<ChannelQuery>,
+    RealIp(ip): RealIp,
+    identity: OptionalSignedIdentity,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
-    let script = match state.script_service.get_script(&script_id).await {
+    let channel = match query.resolve() {
+        Ok(channel) => channel,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    let mut script = match state.script_service.get_script(&script_id).await {
         Ok(Some(script)) => script,
         Ok(None) => return error_response(StatusCode::NOT_FOUND, "Script not found"),
         Err(e) => {
@@ -64,8 +135,66 @@ pub async fn get_script(
             return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to get script");
         }
     };
+    // A private script exists only for its owner, who proves it by signing.
+    if !script.is_public
+        && script
+            .owner_account_id
+            .as_deref()
+            .is_none_or(|owner| Some(owner) != identity.account_id())
+    {
+        return error_response(StatusCode::NOT_FOUND, "Script not found");
+    }
+
+    // Best-effort, like the download counter: a failed bump never fails the read.
+    match state
+        .script_service
+        .record_view(&script.id, &viewer_identities(req, ip))
+        .await
+    {
+        Ok(true) => script.views += 1,
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Failed to count view of script {}: {}", script_id, e),
+    }
 
-    let detail = ScriptDetailResponse::from_script(script);
+    let offer = match state
+        .promotion_service
+        .offers_for(std::slice::from_ref(&script))
+        .await
+    {
+        Ok(mut offers) => offers.remove(&script.id),
+        Err(e) => {
+            tracing::warn!("Failed to load promotion for script {}: {}", script_id, e);
+            None
+        }
+    };
+    let release = match state
+        .script_service
+        .channel_release(&script.id, channel)
+        .await
+    {
+        Ok(release) => release,
+        Err(e) => {
+            tracing::error!(
+                "Failed to load {} build of {}: {}",
+                channel.as_str(),
+                script_id,
+                e
+            );
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to get script");
+        }
+    };
+    let mut detail = ScriptDetailResponse::from_script(script);
+    if let Some(release) = release {
+        detail = detail.with_release(release);
+    }
+    if let Some(offer) = offer {
+        detail.discounted_price_e8s = Some(offer.discounted_price_e8s);
+        detail.promotion_ends_at = Some(offer.promotion.ends_at);
+    }
+    match state.question_service.unanswered_count(&detail.id).await {
+        Ok(count) => detail.unanswered_questions = count,
+        Err(e) => tracing::warn!("Failed to count questions of script {}: {}", script_id, e),
+    }
 
     Json(serde_json::json!({
         "success": true,
@@ -74,16 +203,50 @@ pub async fn get_script(
     .into_response()
 }
 
+/// `GET /api/v1/scripts/compare?ids=a,b,c` — up to five public scripts side
+/// by side for the app's comparison view. Ids that match no public script are
+/// listed under `missing` rather than failing the request.
+#[handler]
+pub async fn compare_scripts(
+    Query(params): Query<CompareQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let ids: Vec<String> = params
+        .ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    match state.script_service.compare_scripts(&ids).await {
+        Ok((scripts, missing)) => Json(serde_json::json!({
+            "success": true,
+            "data": {
+                "scripts": scripts,
+                "missing": missing
+            }
+        }))
+        .into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
+
 /// Lightweight browse-time preview (UX-6). Returns a CAPPED excerpt of the
 /// source plus browse-relevant metadata instead of the full bundle, so the
 /// Script Details dialog stops downloading the whole script just to show 50
-/// lines. Public (no auth) — same reachability as `get_script` / `get_scripts`.
+/// lines. Same reachability as `get_script`: a private script is 404 except
+/// on a signed GET by its owner.
 #[handler]
 pub async fn get_script_preview(
     Path(script_id): Path<String>,
+    identity: OptionalSignedIdentity,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
-    match state.script_service.get_script_preview(&script_id).await {
+    match state
+        .script_service
+        .get_script_preview(&script_id, identity.account_id())
+        .await
+    {
         Ok(Some(preview)) => Json(serde_json::json!({
             "success": true,
             "data": preview
@@ -100,14 +263,101 @@ pub async fn get_script_preview(
     }
 }
 
+/// `GET /api/v1/scripts/:id/embed?format=` — the marketplace card of a
+/// public script for third-party sites (see `script_embed`). Not a view:
+/// cards are rendered by pages the viewer never opened the script from.
 #[handler]
-pub async fn get_scripts_count(Data(state): Data<&Arc<AppState>>) -> Response {
-    match state.script_service.get_scripts_count().await {
-        Ok(count) => Json(serde_json::json!({
+pub async fn get_script_embed(
+    Path(script_id): Path<String>,
+    Query(query): Query<EmbedQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let Some(format) = EmbedFormat::parse(query.format.as_deref()) else {
+        return error_response(StatusCode::NOT_IMPLEMENTED, "Unsupported embed format");
+    };
+    let card = match state.script_service.get_script(&script_id).await {
+        Ok(Some(script)) if script.is_public => ScriptEmbed::from_script(&script),
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "Script not found"),
+        Err(e) => {
+            tracing::error!("Failed to get script embed {}: {}", script_id, e);
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get script embed",
+            );
+        }
+    };
+    match format {
+        EmbedFormat::Json => Json(serde_json::json!({
             "success": true,
-            "data": { "count": count }
+            "data": card
         }))
         .into_response(),
+        EmbedFormat::OEmbed => Json(card.oembed()).into_response(),
+    }
+}
+
+/// `GET /api/v1/scripts/:id/readme.html` — the script's readme rendered to
+/// sanitized HTML (see `readme`). Private scripts follow `get_script`: only
+/// a signed GET by the owner sees them. Not a view.
+#[handler]
+pub async fn get_script_readme_html(
+    Path(script_id): Path<String>,
+    identity: OptionalSignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let script = match state.script_service.get_script(&script_id).await {
+        Ok(Some(script)) => script,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Script not found"),
+        Err(e) => {
+            tracing::error!("Failed to get readme of script {}: {}", script_id, e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to get readme");
+        }
+    };
+    if !script.is_public
+        && script
+            .owner_account_id
+            .as_deref()
+            .is_none_or(|owner| Some(owner) != identity.account_id())
+    {
+        return error_response(StatusCode::NOT_FOUND, "Script not found");
+    }
+    let Some(markdown) = script.readme.as_deref() else {
+        return error_response(StatusCode::NOT_FOUND, "Script has no readme");
+    };
+
+    Response::builder()
+        .content_type("text/html; charset=utf-8")
+        .header("Content-Security-Policy", readme::CONTENT_SECURITY_POLICY)
+        .header("X-Content-Type-Options", "nosniff")
+        .body(readme::render_html(markdown))
+}
+
+/// `GET /api/v1/scripts/count?locale=` — the number of public scripts;
+/// with `locale`, also `formatted.count` (see `locale_format`).
+#[handler]
+pub async fn get_scripts_count(
+    Query(query): Query<StatsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let locale = match query.resolve() {
+        Ok(locale) => locale,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    match state.script_service.get_scripts_count().await {
+        Ok(count) => {
+            let mut data = serde_json::json!({ "count": count });
+            if let Some(locale) = locale {
+                data["locale"] = locale.tag.into();
+                data["formatted"] = serde_json::json!({
+                    "count": locale.format_integer(count),
+                });
+            }
+            Json(serde_json::json!({
+                "success": true,
+                "data": data
+            }))
+            .into_response()
+        }
         Err(e) => {
             tracing::error!("Failed to get count: {}", e);
             error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to get count")
@@ -115,19 +365,46 @@ pub async fn get_scripts_count(Data(state): Data<&Arc<AppState>>) -> Response {
     }
 }
 
+/// `GET /api/v1/marketplace-stats?locale=` — raw totals; with `locale`,
+/// also `formatted` display strings for each of them (the rating to one
+/// decimal, the timestamp as a short UTC date and time).
 #[handler]
-pub async fn get_marketplace_stats(Data(state): Data<&Arc<AppState>>) -> Response {
-    match state.script_service.get_marketplace_stats().await {
-        Ok((scripts_count, total_downloads, avg_rating)) => Json(serde_json::json!({
-            "success": true,
-            "data": {
+pub async fn get_marketplace_stats(
+    Query(query): Query<StatsQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let locale = match query.resolve() {
+        Ok(locale) => locale,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    let stats = state.script_service.get_marketplace_stats().await;
+    let views = state.script_service.get_total_views().await;
+    match stats.and_then(|stats| views.map(|views| (stats, views))) {
+        Ok(((scripts_count, total_downloads, avg_rating), total_views)) => {
+            let now = chrono::Utc::now();
+            let mut data = serde_json::json!({
                 "totalScripts": scripts_count,
                 "totalDownloads": total_downloads,
+                "totalViews": total_views,
                 "averageRating": avg_rating,
-                "timestamp": chrono::Utc::now().to_rfc3339()
+                "timestamp": now.to_rfc3339()
+            });
+            if let Some(locale) = locale {
+                data["locale"] = locale.tag.into();
+                data["formatted"] = serde_json::json!({
+                    "totalScripts": locale.format_integer(scripts_count),
+                    "totalDownloads": locale.format_integer(total_downloads),
+                    "totalViews": locale.format_integer(total_views),
+                    "averageRating": locale.format_decimal(avg_rating, 1),
+                    "timestamp": locale.format_date_time(now),
+                });
             }
-        }))
-        .into_response(),
+            Json(serde_json::json!({
+                "success": true,
+                "data": data
+            }))
+            .into_response()
+        }
         Err(e) => {
             tracing::error!("Failed to get marketplace stats: {}", e);
             error_response(
@@ -138,9 +415,80 @@ pub async fn get_marketplace_stats(Data(state): Data<&Arc<AppState>>) -> Respons
     }
 }
 
+/// `GET /api/v1/limits` — the size/content limits create and update enforce,
+/// so clients can reject an oversized upload before signing it.
+#[handler]
+pub async fn get_script_limits(Data(state): Data<&Arc<AppState>>) -> Response {
+    Json(serde_json::json!({
+        "success": true,
+        "data": state.script_service.limits()
+    }))
+    .into_response()
+}
+
+/// `POST /api/v1/scripts/validate/execute` — runs `init` and one `view` of a
+/// bundle in the sandbox with mocked canister calls (see
+/// [`script_preview`]). A bundle that fails still answers 200; the
+/// preview's `errors` say at which step.
+#[handler]
+pub async fn preview_script_execution(
+    ValidJson(req): ValidJson<PreviewRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    if let Err(e) = state
+        .script_service
+        .limits()
+        .check(None, None, None, Some(&req.bundle), None)
+    {
+        return error_response(e.status(), e.message());
+    }
+    let Some(permit) = script_preview::try_reserve() else {
+        return error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many previews running, try again shortly",
+        );
+    };
+
+    // The permit moves with the run: a client that hangs up does not free
+    // the slot while the engine is still busy.
+    match tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        script_preview::run(&req)
+    })
+    .await
+    {
+        Ok(preview) => Json(serde_json::json!({
+            "success": true,
+            "data": preview
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Script preview failed to run: {}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to run preview")
+        }
+    }
+}
+
+/// `GET /api/v1/pricing` — the currencies scripts can be priced in and the
+/// configured display rates, so clients can show `price_e8s` as tokens and
+/// an approximate fiat amount.
+#[handler]
+pub async fn get_pricing() -> Response {
+    Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "e8sPerToken": pricing::E8S_PER_TOKEN,
+            "defaultCurrency": pricing::DEFAULT_CURRENCY,
+            "currencies": pricing::CURRENCIES,
+            "displayRates": pricing::display_rates(),
+        }
+    }))
+    .into_response()
+}
+
 #[handler]
 pub async fn create_script(
-    Json(req): Json<CreateScriptRequest>,
+    ValidJson(req): ValidJson<CreateScriptRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     // Verify authentication
@@ -185,7 +533,7 @@ pub async fn create_script(
 #[handler]
 pub async fn update_script(
     Path(script_id): Path<String>,
-    Json(req): Json<UpdateScriptRequest>,
+    ValidJson(req): ValidJson<UpdateScriptRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     // Verify authentication
@@ -201,6 +549,15 @@ pub async fn update_script(
         return response;
     }
 
+    let channel = req
+        .channel
+        .as_deref()
+        .and_then(ReleaseChannel::parse)
+        .unwrap_or_default();
+    if channel != ReleaseChannel::Stable {
+        return push_channel_release(state, &script_id, channel, &req).await;
+    }
+
     // Update script via service
     match state.script_service.update_script(&script_id, req).await {
         Ok(script) => {
@@ -220,18 +577,138 @@ pub async fn update_script(
         }
         Err(e) => {
             tracing::error!("Failed to update script {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// The `"channel": "beta"` branch of `update_script`: replaces the beta
+/// build and leaves the stable script as it is.
+async fn push_channel_release(
+    state: &AppState,
+    script_id: &str,
+    channel: ReleaseChannel,
+    req: &UpdateScriptRequest,
+) -> Response {
+    match state
+        .script_service
+        .push_channel_release(script_id, channel, req)
+        .await
+    {
+        Ok(release) => {
+            tracing::info!(
+                "Pushed {} build of script {} (version: {})",
+                release.channel,
+                script_id,
+                release.version
+            );
+            Json(serde_json::json!({
+                "success": true,
+                "data": {
+                    "id": release.script_id,
+                    "channel": release.channel,
+                    "version": release.version,
+                    "updated_at": release.updated_at
+                }
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to push {} build of script {}: {}",
+                channel.as_str(),
+                script_id,
+                e
+            );
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/scripts/:id/dependencies` — the script's dependency tree,
+/// resolved against today's public scripts, and the order to install it in.
+/// A private script is 404 unless its owner signs the GET, like `get_script`.
+#[handler]
+pub async fn get_script_dependencies(
+    Path(script_id): Path<String>,
+    identity: OptionalSignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .script_service
+        .resolve_dependencies(&script_id, identity.account_id())
+        .await
+    {
+        Ok(Some(tree)) => Json(serde_json::json!({
+            "success": true,
+            "data": tree
+        }))
+        .into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Script not found"),
+        Err(e) => {
+            tracing::error!("Failed to resolve dependencies of {}: {}", script_id, e);
             error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                &format!("Failed to update script: {}", e),
+                "Failed to resolve dependencies",
             )
         }
     }
 }
 
+/// `GET /api/v1/scripts/:id/channels` — the version and last push of each
+/// release channel the script has a build on, stable first. A private
+/// script is 404 unless its owner signs the GET, like `get_script`.
+#[handler]
+pub async fn get_script_channels(
+    Path(script_id): Path<String>,
+    identity: OptionalSignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .script_service
+        .list_channels(&script_id, identity.account_id())
+        .await
+    {
+        Ok(Some(channels)) => Json(serde_json::json!({
+            "success": true,
+            "data": channels
+        }))
+        .into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Script not found"),
+        Err(e) => {
+            tracing::error!("Failed to list channels of script {}: {}", script_id, e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list release channels",
+            )
+        }
+    }
+}
+
+/// `GET /api/v1/scripts/:id/validation` — the latest re-validation of a
+/// public script (see [`crate::revalidation`]).
+#[handler]
+pub async fn get_script_validation(
+    Path(script_id): Path<String>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.revalidation_service.status(&script_id).await {
+        Ok(validation) => Json(serde_json::json!({
+            "success": true,
+            "data": validation
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to load validation of script {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
 #[handler]
 pub async fn delete_script(
     Path(script_id): Path<String>,
-    Json(req): Json<DeleteScriptRequest>,
+    ValidJson(req): ValidJson<DeleteScriptRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     // Verify authentication
@@ -285,7 +762,7 @@ pub async fn delete_script(
 
 #[handler]
 pub async fn search_scripts(
-    Json(request): Json<SearchRequest>,
+    ValidJson(request): ValidJson<SearchRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     tracing::info!(
@@ -297,7 +774,7 @@ pub async fn search_scripts(
     );
 
     match state.script_service.search_scripts(&request).await {
-        Ok(result) => {
+        Ok((result, exposure)) => {
             let has_more = result.offset + (result.scripts.len() as i64) < result.total;
 
             tracing::info!(
@@ -311,11 +788,12 @@ pub async fn search_scripts(
             Json(serde_json::json!({
                 "success": true,
                 "data": {
-                    "scripts": scripts_to_list_json(&result.scripts),
+                    "scripts": listing_json(state, &result.scripts).await,
                     "total": result.total,
                     "hasMore": has_more,
                     "offset": result.offset,
-                    "limit": result.limit
+                    "limit": result.limit,
+                    "exposure": exposure
                 }
             }))
             .into_response()
@@ -327,6 +805,19 @@ pub async fn search_scripts(
     }
 }
 
+/// `POST /api/v1/scripts/search/clicks` — a click on a result of a search
+/// ranked by an experiment, reported with the search's `exposure.id`.
+#[handler]
+pub async fn record_search_click(
+    ValidJson(click): ValidJson<SearchClickRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.script_service.record_search_click(&click).await {
+        Ok(()) => Json(serde_json::json!({ "success": true })).into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
+
 /// `GET /api/v1/scripts/categories` — distinct, content-derived categories
 /// among public scripts. Fixes UXR-9: registered BEFORE `/scripts/:id` so the
 /// literal path `categories` is no longer shadowed by the `:id` capture (which
@@ -363,7 +854,7 @@ pub async fn get_scripts_by_category(
             tracing::debug!("Category '{}' has {} scripts", category, scripts.len());
             Json(serde_json::json!({
                 "success": true,
-                "data": scripts_to_list_json(&scripts)
+                "data": listing_json(state, &scripts).await
             }))
             .into_response()
         }
@@ -380,7 +871,7 @@ pub async fn get_scripts_by_category(
 #[handler]
 pub async fn publish_script(
     Path(script_id): Path<String>,
-    Json(req): Json<UpdateScriptRequest>,
+    ValidJson(req): ValidJson<UpdateScriptRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     // Verify authentication
@@ -409,20 +900,49 @@ pub async fn publish_script(
         }
         Err(e) => {
             tracing::error!("Failed to publish script {}: {}", script_id, e);
-            error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                &format!("Failed to publish script: {}", e),
-            )
+            error_response(e.status(), e.message())
         }
     }
 }
 
+/// `GET /api/v1/scripts/trending?by=downloads|views` (default `downloads`).
+/// `category` or `tag` narrows it to that category's or tag's rolled-up list;
+/// see [`crate::trending`].
 #[handler]
-pub async fn get_trending_scripts(Data(state): Data<&Arc<AppState>>) -> Response {
-    match state.script_service.get_trending(20).await {
+pub async fn get_trending_scripts(
+    Query(params): Query<TrendingQuery>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let signal = params.by.unwrap_or_default();
+    let category = params.category.as_deref().filter(|c| !c.is_empty());
+    let tag = params.tag.as_deref().filter(|t| !t.is_empty());
+    let trending = match (category, tag) {
+        (None, None) => state.script_service.get_trending(20, signal).await,
+        (Some(category), None) => {
+            let scope = TrendingScope::Category(category);
+            state
+                .script_service
+                .get_trending_in(scope, 20, signal)
+                .await
+        }
+        (None, Some(tag)) => {
+            let scope = TrendingScope::Tag(tag);
+            state
+                .script_service
+                .get_trending_in(scope, 20, signal)
+                .await
+        }
+        (Some(_), Some(_)) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "category and tag cannot be combined",
+            );
+        }
+    };
+    match trending {
         Ok(scripts) => Json(serde_json::json!({
             "success": true,
-            "data": scripts_to_list_json(&scripts)
+            "data": listing_json(state, &scripts).await
         }))
         .into_response(),
         Err(e) => {
@@ -440,7 +960,7 @@ pub async fn get_featured_scripts(Data(state): Data<&Arc<AppState>>) -> Response
     match state.script_service.get_featured(4.5, 10, 10).await {
         Ok(scripts) => Json(serde_json::json!({
             "success": true,
-            "data": scripts_to_list_json(&scripts)
+            "data": listing_json(state, &scripts).await
         }))
         .into_response(),
         Err(e) => {
@@ -462,7 +982,7 @@ pub async fn get_compatible_scripts(
     match state.script_service.get_compatible("all", 20).await {
         Ok(scripts) => Json(serde_json::json!({
             "success": true,
-            "data": scripts_to_list_json(&scripts)
+            "data": listing_json(state, &scripts).await
         }))
         .into_response(),
         Err(e) => {
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::SignedIdentity, models::AppState, responses::error_response,
+    telemetry::TelemetryReport, validation::ValidJson,
+};
+
+// ============================================================================
+// Telemetry handlers
+// ============================================================================
+//
+// Reports are anonymous and unsigned: the client only sends them when the
+// user opted in, and the body carries nothing that identifies either (see
+// `crate::telemetry`). Only the author reads the aggregates:
+//
+// POST /api/v1/telemetry                  → 202 {success}
+//      body {script_id, version, engine_version, event, success, error_class?}
+// GET  /api/v1/scripts/:id/reliability    → 200 [VersionReliability] (signed GET, owner)
+
+/// `POST /api/v1/telemetry` — counts one install or run report.
+#[handler]
+pub async fn post_telemetry(
+    ValidJson(report): ValidJson<TelemetryReport>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state.telemetry_service.record(&report).await {
+        Ok(()) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "success": true })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to record telemetry for {}: {}", report.script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/scripts/:id/reliability` — per-version install and run
+/// outcomes with reliability scores, newest version first.
+#[handler]
+pub async fn get_script_reliability(
+    Path(script_id): Path<String>,
+    identity: SignedIdentity,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    match state
+        .telemetry_service
+        .reliability(&identity.account_id, &script_id)
+        .await
+    {
+        Ok(versions) => Json(serde_json::json!({
+            "success": true,
+            "data": versions
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to get reliability of script {}: {}", script_id, e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
     models::AppState,
     responses::error_response,
     signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{ValidJson, Validate},
 };
 
 // ============================================================================
@@ -84,6 +85,8 @@ struct VaultBlobRequest {
     blob_nonce: String,     // base64 (AES-GCM nonce — renamed from `nonce`)
 }
 
+impl Validate for VaultBlobRequest {}
+
 /// Decodes a base64 field from a [`VaultBlobRequest`]. Returns the decoded
 /// bytes or a human-readable error string that the caller surfaces as a 400.
 fn decode_blob_field(field: &'static str, encoded: &str) -> Result<Vec<u8>, String> {
@@ -128,7 +131,7 @@ fn vault_auth_fields<'a>(req: &'a VaultBlobRequest) -> SignedAuthFields<'a> {
 
 #[handler]
 pub async fn vault_create(
-    Json(req): Json<VaultBlobRequest>,
+    ValidJson(req): ValidJson<VaultBlobRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let blob = match decode_blob_fields(&req) {
@@ -195,9 +198,11 @@ struct VaultGetRequest {
     nonce: String,
 }
 
+impl Validate for VaultGetRequest {}
+
 #[handler]
 pub async fn vault_get(
-    Json(req): Json<VaultGetRequest>,
+    ValidJson(req): ValidJson<VaultGetRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let account_repo = &state.script_service.account_repo;
@@ -247,7 +252,7 @@ pub async fn vault_get(
 
 #[handler]
 pub async fn vault_update(
-    Json(req): Json<VaultBlobRequest>,
+    ValidJson(req): ValidJson<VaultBlobRequest>,
     Data(state): Data<&Arc<AppState>>,
 ) -> Response {
     let blob = match decode_blob_fields(&req) {
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use poem::{
+    error::ResponseError,
+    handler,
+    web::{Data, Json},
+    IntoResponse, Response,
+};
+
+use crate::{
+    middleware::SignedIdentity,
+    models::AppState,
+    responses::error_response,
+    signature_gate::{verify_signed_account_request, SignedAuthFields},
+    validation::{require_non_empty, FieldError, ValidJson, Validate},
+};
+
+// ============================================================================
+// Author stats webhook handlers
+// ============================================================================
+//
+// Signature-gated like the vault routes: the account is resolved SERVER-SIDE
+// from the signing key and bound into the payload
+// `{action, account_id, nonce, ts}` (plus `url` for set).
+//
+// PUT    /api/v1/webhooks   (set)        → 200 {url, secret, createdAt, updatedAt}
+// GET    /api/v1/webhooks   (signed GET) → 200 {url, createdAt, updatedAt} / 404
+// DELETE /api/v1/webhooks   (remove)     → 200 / 404
+//
+// `secret` is returned only by set (each set rotates it). Receivers verify
+// `X-Webhook-Signature: sha256=<hex HMAC-SHA256(secret, raw body)>`; see
+// `webhook_delivery` for the events.
+
+const WEBHOOK_SET_ACTION: &str = "webhook:set";
+const WEBHOOK_DELETE_ACTION: &str = "webhook:delete";
+
+#[derive(Debug, serde::Deserialize)]
+struct WebhookSetRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+    url: String,
+}
+
+impl Validate for WebhookSetRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "url", &self.url);
+        errors
+    }
+}
+
+/// Body for the delete route: the auth fields only.
+#[derive(Debug, serde::Deserialize)]
+struct WebhookAuthRequest {
+    signature: String,
+    author_public_key: String,
+    author_principal: String,
+    timestamp: i64,
+    nonce: String,
+}
+
+impl Validate for WebhookAuthRequest {}
+
+async fn resolve_account(
+    state: &AppState,
+    action: &'static str,
+    auth_fields: SignedAuthFields<'_>,
+    url: Option<&str>,
+) -> Result<String, Response> {
+    let nonce = auth_fields.nonce;
+    let ts = auth_fields.timestamp;
+    verify_signed_account_request(
+        &state.script_service.account_repo,
+        &state.pool,
+        action,
+        &auth_fields,
+        |resolved| {
+            let mut payload = serde_json::json!({
+                "action": action,
+                "account_id": resolved,
+                "nonce": nonce,
+                "ts": ts,
+            });
+            if let Some(url) = url {
+                payload["url"] = serde_json::Value::from(url);
+            }
+            payload
+        },
+    )
+    .await
+    .map_err(|r| error_response(r.status, r.message))
+}
+
+#[handler]
+pub async fn webhook_set(
+    ValidJson(req): ValidJson<WebhookSetRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        WEBHOOK_SET_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        Some(&req.url),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    match state
+        .webhook_service
+        .set_webhook(&account_id, &req.url)
+        .await
+    {
+        Ok(hook) => Json(serde_json::json!({
+            "success": true,
+            "data": {
+                "url": hook.url,
+                "secret": hook.secret,
+                "createdAt": hook.created_at,
+                "updatedAt": hook.updated_at,
+            }
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::warn!(account_id = %account_id, "webhook set failed: {}", e);
+            error_response(e.status(), e.message())
+        }
+    }
+}
+
+/// `GET /api/v1/webhooks` — the caller's webhook. A signed GET (see
+/// `middleware::signed_identity`).
+#[handler]
+pub async fn webhook_get(identity: SignedIdentity, Data(state): Data<&Arc<AppState>>) -> Response {
+    match state
+        .webhook_service
+        .get_webhook(&identity.account_id)
+        .await
+    {
+        Ok(hook) => Json(serde_json::json!({
+            "success": true,
+            "data": hook
+        }))
+        .into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
+
+#[handler]
+pub async fn webhook_delete(
+    ValidJson(req): ValidJson<WebhookAuthRequest>,
+    Data(state): Data<&Arc<AppState>>,
+) -> Response {
+    let account_id = match resolve_account(
+        state,
+        WEBHOOK_DELETE_ACTION,
+        SignedAuthFields {
+            signature: &req.signature,
+            author_public_key: &req.author_public_key,
+            author_principal: &req.author_principal,
+            timestamp: req.timestamp,
+            nonce: &req.nonce,
+        },
+        None,
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    match state.webhook_service.delete_webhook(&account_id).await {
+        Ok(()) => Json(serde_json::json!({ "success": true })).into_response(),
+        Err(e) => error_response(e.status(), e.message()),
+    }
+}
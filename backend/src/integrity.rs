@@ -0,0 +1,140 @@
+//! Data integrity checks.
+//!
+//! Foreign keys keep most rows consistent, but not all of them: databases
+//! created before the keys existed, rows written with `foreign_keys` off,
+//! review authors (`reviews.user_id` has no key) and JSON columns slip
+//! through. The checker looks for:
+//!
+//! - orphaned reviews: on a script that no longer exists, or by an account
+//!   that no longer exists;
+//! - orphaned scripts: live scripts whose owner account no longer exists;
+//! - dangling tags: a tag list that is not JSON, or holds non-strings, blank
+//!   or duplicate tags;
+//! - mismatched aggregates: `rating`, `review_count` or `weighted_rating` of
+//!   a live script that its visible reviews do not add up to.
+//!
+//! Every [`CHECK_INTERVAL`] a job runs the checks and stores a report; an
+//! admin can run them at any time with `POST /api/v1/admin/integrity-check`,
+//! and `GET /api/v1/admin/integrity-reports` lists the latest reports. The
+//! job only reports unless [`AUTO_REPAIR_ENV`] is set to `true`. A repair
+//! deletes orphaned reviews, soft-deletes orphaned scripts (the cleanup job
+//! purges them after its retention), rewrites dangling tag lists and
+//! recomputes mismatched aggregates.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::env;
+use std::time::Duration;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::services::IntegrityService;
+
+/// Set to `true` to have the scheduled run repair what it finds.
+pub const AUTO_REPAIR_ENV: &str = "INTEGRITY_AUTO_REPAIR";
+
+/// How often the scheduled run happens.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Issues listed in a report; the counts cover all of them.
+pub const MAX_LISTED_ISSUES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    OrphanedReview,
+    OrphanedScript,
+    DanglingTags,
+    MismatchedAggregates,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Scheduled,
+    Admin,
+}
+
+impl Trigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+/// The repaired form of a stored tag list, or `None` when it is fine. A
+/// list that is not a JSON array is dropped (`Some(None)`); otherwise
+/// non-strings, blank tags and repeats are removed, keeping the order.
+pub fn clean_tags(raw: &str) -> Option<Option<Vec<String>>> {
+    let Ok(serde_json::Value::Array(values)) = serde_json::from_str(raw) else {
+        return Some(None);
+    };
+    let mut cleaned: Vec<String> = Vec::with_capacity(values.len());
+    for value in &values {
+        if let Some(tag) = value.as_str() {
+            if !tag.trim().is_empty() && !cleaned.iter().any(|t| t == tag) {
+                cleaned.push(tag.to_string());
+            }
+        }
+    }
+    (cleaned.len() != values.len()).then_some(Some(cleaned))
+}
+
+/// Whether the scheduled run should repair, from [`AUTO_REPAIR_ENV`].
+fn auto_repair() -> bool {
+    env::var(AUTO_REPAIR_ENV).is_ok_and(|v| v.trim().eq_ignore_ascii_case("true"))
+}
+
+/// Background job that runs the checks, the first time right away. Stops
+/// when `shutdown` is cancelled, like the other jobs.
+pub fn start_integrity_check_job(pool: SqlitePool, shutdown: CancellationToken) {
+    tracing::info!("Starting integrity check background job");
+    tokio::spawn(check_loop(pool, shutdown));
+}
+
+async fn check_loop(pool: SqlitePool, shutdown: CancellationToken) {
+    let service = IntegrityService::new(pool);
+    let repair = auto_repair();
+    let mut interval = time::interval(CHECK_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match service.run(Trigger::Scheduled, repair).await {
+                    Ok(report) if report.issue_count == 0 => {
+                        tracing::debug!("Integrity check found no issues");
+                    }
+                    Ok(report) => tracing::warn!(
+                        "Integrity check found {} issues{} (report {})",
+                        report.issue_count,
+                        if report.repaired { ", repaired" } else { "" },
+                        report.id
+                    ),
+                    Err(e) => tracing::error!("Integrity check failed: {}", e),
+                }
+            }
+            _ = shutdown.cancelled() => {
+                tracing::info!("integrity check job stopped");
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_lists_are_cleaned_in_order() {
+        assert_eq!(clean_tags(r#"["defi","nft"]"#), None);
+        assert_eq!(clean_tags("[]"), None);
+        assert_eq!(
+            clean_tags(r#"["defi", 3, " ", "nft", "defi"]"#),
+            Some(Some(vec!["defi".to_string(), "nft".to_string()]))
+        );
+        assert_eq!(clean_tags("defi,nft"), Some(None));
+        assert_eq!(clean_tags(r#"{"tag":"defi"}"#), Some(None));
+    }
+}
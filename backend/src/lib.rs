@@ -1,19 +1,84 @@
+pub mod account_settings;
 pub mod auth;
 pub mod cleanup;
+pub mod content_store;
 pub mod cors;
 pub mod crypto_util;
 pub mod db;
+pub mod error_reports;
 pub mod handlers;
+pub mod integrity;
+pub mod limits;
+pub mod locale_format;
+pub mod markdown;
 pub mod middleware;
 pub mod models;
+pub mod moderation;
+pub mod pricing;
+pub mod problem;
+pub mod quotas;
 pub mod rate_limit;
+pub mod readme;
+pub mod refund_ledger;
+pub mod release_channel;
 pub mod repositories;
 pub mod responses;
+pub mod revalidation;
+pub mod scheduled_publish;
+pub mod script_dependencies;
+pub mod script_embed;
 pub mod script_language;
+pub mod script_permissions;
+pub mod script_preview;
+pub mod search_experiments;
 pub mod services;
 pub mod signature_gate;
+pub mod signed_urls;
 pub mod startup_checks;
+pub mod telemetry;
+pub mod trending;
+pub mod validation;
 pub mod vault;
+pub mod webhook_delivery;
+
+/// Row fixtures shared by the service unit tests.
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+    use sqlx::SqlitePool;
+
+    /// Inserts script `id` owned by `owner`, creating the owner's account
+    /// if it does not exist yet.
+    pub(crate) async fn insert_script(
+        pool: &SqlitePool,
+        id: &str,
+        owner: &str,
+        price_e8s: i64,
+        is_public: bool,
+    ) {
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES (?1, ?1, ?1, '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(owner)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO scripts (id, slug, owner_account_id, title, description, category, bundle,
+                                  price_e8s, is_public, created_at, updated_at)
+             VALUES (?1, ?1, ?2, ?1, 'd', 'utility', 'b', ?3, ?4,
+                     '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .bind(id)
+        .bind(owner)
+        .bind(price_e8s)
+        .bind(is_public)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+}
 
 /// Test-only helpers for constructing an [`models::AppState`] over a given
 /// pool. Used by the integration tests under `backend/tests/` (which are
@@ -25,7 +90,10 @@ pub mod vault;
 pub mod test_support {
     use std::sync::Arc;
 
-    use crate::{models::AppState, rate_limit::SlidingWindowRateLimiter, services};
+    use crate::{
+        models::AppState, rate_limit::SlidingWindowRateLimiter, services,
+        signed_urls::SourceUrlSigner,
+    };
 
     /// Builds an `AppState` for integration tests.
     pub fn app_state_stub(
@@ -38,7 +106,21 @@ pub mod test_support {
             script_service: services::ScriptService::new(pool.clone()),
             review_service: services::ReviewService::new(pool.clone()),
             passkey_service,
+            webhook_service: services::WebhookService::new(pool.clone()),
+            promotion_service: services::PromotionService::new(pool.clone()),
+            dispute_service: services::DisputeService::new(pool.clone()),
+            bundle_service: services::BundleService::new(pool.clone()),
+            entitlement_service: services::EntitlementService::new(pool.clone()),
+            maintenance_service: services::MaintenanceService::new(pool.clone()),
+            question_service: services::QuestionService::new(pool.clone()),
+            telemetry_service: services::TelemetryService::new(pool.clone()),
+            error_report_service: services::ErrorReportService::new(pool.clone()),
+            compatibility_service: services::CompatibilityService::new(pool.clone()),
+            moderation_service: services::ModerationService::new(pool.clone()),
+            revalidation_service: services::RevalidationService::new(pool.clone()),
+            integrity_service: services::IntegrityService::new(pool.clone()),
             recovery_rate_limiter,
+            source_urls: SourceUrlSigner::new(b"test-source-url-secret".to_vec(), 300),
             pool,
         }
     }
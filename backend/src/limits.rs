@@ -0,0 +1,178 @@
+//! Script size and content limits.
+//!
+//! Enforced by [`crate::services::ScriptService`] on create and update, and
+//! published verbatim at `GET /api/v1/limits` so clients can pre-validate
+//! before signing an upload. Each limit can be overridden from the
+//! environment at boot (see [`ScriptLimits::from_env`]); unset or unparsable
+//! values fall back to the defaults.
+//!
+//! Screenshots are not part of the write API yet, so there is no limit for
+//! them here.
+
+use serde::Serialize;
+use std::env;
+
+use crate::services::ScriptError;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptLimits {
+    /// UTF-8 byte length of `bundle`.
+    pub max_bundle_bytes: usize,
+    /// Character counts, not bytes, so non-Latin titles are not penalised.
+    pub max_title_chars: usize,
+    pub max_description_chars: usize,
+    /// The markdown source of the readme, which is meant to be long.
+    pub max_readme_chars: usize,
+    pub max_tags: usize,
+    pub max_tag_chars: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_bundle_bytes: 512 * 1024,
+            max_title_chars: 100,
+            max_description_chars: 5_000,
+            max_readme_chars: 100_000,
+            max_tags: 10,
+            max_tag_chars: 32,
+        }
+    }
+}
+
+fn env_usize(name: &str, default: usize) -> usize {
+    match env::var(name) {
+        Ok(raw) => raw.trim().parse().unwrap_or_else(|_| {
+            tracing::warn!("{name}='{raw}' is not a non-negative integer; using {default}");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+impl ScriptLimits {
+    /// Defaults overridden by `SCRIPT_MAX_BUNDLE_BYTES`,
+    /// `SCRIPT_MAX_TITLE_CHARS`, `SCRIPT_MAX_DESCRIPTION_CHARS`,
+    /// `SCRIPT_MAX_README_CHARS`, `SCRIPT_MAX_TAGS` and
+    /// `SCRIPT_MAX_TAG_CHARS`.
+    pub fn from_env() -> Self {
+        let d = Self::default();
+        Self {
+            max_bundle_bytes: env_usize("SCRIPT_MAX_BUNDLE_BYTES", d.max_bundle_bytes),
+            max_title_chars: env_usize("SCRIPT_MAX_TITLE_CHARS", d.max_title_chars),
+            max_description_chars: env_usize(
+                "SCRIPT_MAX_DESCRIPTION_CHARS",
+                d.max_description_chars,
+            ),
+            max_readme_chars: env_usize("SCRIPT_MAX_README_CHARS", d.max_readme_chars),
+            max_tags: env_usize("SCRIPT_MAX_TAGS", d.max_tags),
+            max_tag_chars: env_usize("SCRIPT_MAX_TAG_CHARS", d.max_tag_chars),
+        }
+    }
+
+    /// Checks the fields a create or update request carries; `None` means
+    /// "not being changed" and is always accepted.
+    pub fn check(
+        &self,
+        title: Option<&str>,
+        description: Option<&str>,
+        readme: Option<&str>,
+        bundle: Option<&str>,
+        tags: Option<&[String]>,
+    ) -> Result<(), ScriptError> {
+        let too_long = |field: &str, max: usize, unit: &str| {
+            Err(ScriptError::BadRequest(format!(
+                "{field} exceeds the maximum of {max} {unit}"
+            )))
+        };
+        if let Some(title) = title {
+            if title.chars().count() > self.max_title_chars {
+                return too_long("title", self.max_title_chars, "characters");
+            }
+        }
+        if let Some(description) = description {
+            if description.chars().count() > self.max_description_chars {
+                return too_long("description", self.max_description_chars, "characters");
+            }
+        }
+        if let Some(readme) = readme {
+            if readme.chars().count() > self.max_readme_chars {
+                return too_long("readme", self.max_readme_chars, "characters");
+            }
+        }
+        if let Some(bundle) = bundle {
+            if bundle.len() > self.max_bundle_bytes {
+                return too_long("bundle", self.max_bundle_bytes, "bytes");
+            }
+        }
+        if let Some(tags) = tags {
+            if tags.len() > self.max_tags {
+                return Err(ScriptError::BadRequest(format!(
+                    "at most {} tags are allowed",
+                    self.max_tags
+                )));
+            }
+            if tags.iter().any(|t| t.chars().count() > self.max_tag_chars) {
+                return too_long("tag", self.max_tag_chars, "characters");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_values_at_the_limit() {
+        let limits = ScriptLimits::default();
+        let title = "t".repeat(limits.max_title_chars);
+        let tags = vec!["x".repeat(limits.max_tag_chars); limits.max_tags];
+        assert!(limits
+            .check(Some(&title), Some(""), Some(""), Some("b"), Some(&tags))
+            .is_ok());
+        assert!(limits.check(None, None, None, None, None).is_ok());
+    }
+
+    #[test]
+    fn rejects_each_limit_with_field_name() {
+        let limits = ScriptLimits {
+            max_bundle_bytes: 4,
+            max_title_chars: 3,
+            max_description_chars: 3,
+            max_readme_chars: 3,
+            max_tags: 1,
+            max_tag_chars: 2,
+        };
+        let rejected = |err: Result<(), ScriptError>, field: &str| {
+            let err = err.unwrap_err();
+            assert!(matches!(err, ScriptError::BadRequest(_)));
+            assert!(err.message().contains(field), "{}", err.message());
+        };
+        rejected(limits.check(Some("four"), None, None, None, None), "title");
+        rejected(
+            limits.check(None, Some("four"), None, None, None),
+            "description",
+        );
+        rejected(limits.check(None, None, Some("four"), None, None), "readme");
+        rejected(limits.check(None, None, None, Some("€€"), None), "bundle");
+        let two_tags = vec!["a".to_string(), "b".to_string()];
+        rejected(
+            limits.check(None, None, None, None, Some(&two_tags)),
+            "tags",
+        );
+        let long_tag = vec!["abc".to_string()];
+        rejected(limits.check(None, None, None, None, Some(&long_tag)), "tag");
+    }
+
+    #[test]
+    fn title_limit_counts_characters_not_bytes() {
+        let limits = ScriptLimits {
+            max_title_chars: 2,
+            ..ScriptLimits::default()
+        };
+        assert!(limits.check(Some("éé"), None, None, None, None).is_ok());
+    }
+}
@@ -0,0 +1,252 @@
+//! Locale-aware display strings for the stats endpoints.
+//!
+//! The Flutter and web clients each formatted the raw stats themselves and
+//! drifted apart (grouping separators, rating decimals, date order). With
+//! `?locale=` the server returns the display strings too, so both render the
+//! same text. Raw values stay in the response for anything machine-side.
+//!
+//! The rules are the CLDR data ICU formats with — decimal and grouping
+//! symbols, minimum grouping digits, and the short date/time patterns (here
+//! as chrono format strings) — for the locales the clients ship in. A tag is
+//! matched exactly, then by its language (`de-AT` → `de`); anything else is
+//! rejected rather than silently formatted as English.
+
+use chrono::{DateTime, Utc};
+
+/// Formatting rules of one locale.
+#[derive(Debug)]
+pub struct LocaleRules {
+    /// The tag the rules are filed under, echoed back as `locale`.
+    pub tag: &'static str,
+    decimal: &'static str,
+    group: &'static str,
+    /// Numbers below `10^(3 + min_grouping - 1)` are not grouped (`es`
+    /// writes `1234` but `12.345`).
+    min_grouping: u32,
+    date: &'static str,
+    time: &'static str,
+    /// Glue between date and time: `{date}{glue}{time}`.
+    date_time_glue: &'static str,
+}
+
+/// Narrow no-break space, used by CLDR as the `fr` grouping separator and
+/// before the English day period.
+const NNBSP: &str = "\u{202f}";
+
+pub const LOCALES: &[LocaleRules] = &[
+    LocaleRules {
+        tag: "en",
+        decimal: ".",
+        group: ",",
+        min_grouping: 1,
+        date: "%-m/%-d/%y",
+        time: "%-I:%M\u{202f}%p",
+        date_time_glue: ", ",
+    },
+    LocaleRules {
+        tag: "en-GB",
+        decimal: ".",
+        group: ",",
+        min_grouping: 1,
+        date: "%d/%m/%Y",
+        time: "%H:%M",
+        date_time_glue: ", ",
+    },
+    LocaleRules {
+        tag: "de",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        date: "%d.%m.%y",
+        time: "%H:%M",
+        date_time_glue: ", ",
+    },
+    LocaleRules {
+        tag: "fr",
+        decimal: ",",
+        group: NNBSP,
+        min_grouping: 1,
+        date: "%d/%m/%Y",
+        time: "%H:%M",
+        date_time_glue: " ",
+    },
+    LocaleRules {
+        tag: "es",
+        decimal: ",",
+        group: ".",
+        min_grouping: 2,
+        date: "%-d/%-m/%y",
+        time: "%-H:%M",
+        date_time_glue: ", ",
+    },
+    LocaleRules {
+        tag: "it",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        date: "%d/%m/%y",
+        time: "%H:%M",
+        date_time_glue: ", ",
+    },
+    LocaleRules {
+        tag: "pt",
+        decimal: ",",
+        group: ".",
+        min_grouping: 1,
+        date: "%d/%m/%Y",
+        time: "%H:%M",
+        date_time_glue: " ",
+    },
+    LocaleRules {
+        tag: "ja",
+        decimal: ".",
+        group: ",",
+        min_grouping: 1,
+        date: "%Y/%m/%d",
+        time: "%-H:%M",
+        date_time_glue: " ",
+    },
+    LocaleRules {
+        tag: "zh",
+        decimal: ".",
+        group: ",",
+        min_grouping: 1,
+        date: "%Y/%-m/%-d",
+        time: "%H:%M",
+        date_time_glue: " ",
+    },
+];
+
+/// The rules for a BCP 47 tag (`de-CH`, `en_GB`, case-insensitive): the
+/// exact tag if listed, else its language. `None` for malformed or
+/// unsupported tags.
+pub fn resolve(tag: &str) -> Option<&'static LocaleRules> {
+    let tag = tag.trim().replace('_', "-");
+    if tag.is_empty()
+        || tag.len() > 35
+        || !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return None;
+    }
+    let language = tag.split('-').next().unwrap_or_default();
+    LOCALES
+        .iter()
+        .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+        .or_else(|| {
+            LOCALES
+                .iter()
+                .find(|l| l.tag.eq_ignore_ascii_case(language))
+        })
+}
+
+/// The supported tags, for error messages.
+pub fn supported_tags() -> Vec<&'static str> {
+    LOCALES.iter().map(|l| l.tag).collect()
+}
+
+impl LocaleRules {
+    /// `digits` (ASCII, no sign) with the locale's grouping applied.
+    fn group_digits(&self, digits: &str) -> String {
+        if digits.len() < (3 + self.min_grouping) as usize {
+            return digits.to_string();
+        }
+        let mut out = String::with_capacity(digits.len() * 2);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push_str(self.group);
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    pub fn format_integer(&self, value: i64) -> String {
+        let sign = if value < 0 { "-" } else { "" };
+        format!(
+            "{sign}{}",
+            self.group_digits(&value.unsigned_abs().to_string())
+        )
+    }
+
+    /// `value` rounded to exactly `fraction_digits` decimals.
+    pub fn format_decimal(&self, value: f64, fraction_digits: usize) -> String {
+        let fixed = format!("{:.*}", fraction_digits, value.abs());
+        let (int_part, frac_part) = fixed.split_once('.').unwrap_or((&fixed, ""));
+        let sign = if value < 0.0 && fixed.chars().any(|c| ('1'..='9').contains(&c)) {
+            "-"
+        } else {
+            ""
+        };
+        let mut out = format!("{sign}{}", self.group_digits(int_part));
+        if !frac_part.is_empty() {
+            out.push_str(self.decimal);
+            out.push_str(frac_part);
+        }
+        out
+    }
+
+    /// Short date and time, in UTC.
+    pub fn format_date_time(&self, at: DateTime<Utc>) -> String {
+        format!(
+            "{}{}{}",
+            at.format(self.date),
+            self.date_time_glue,
+            at.format(self.time)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rules(tag: &str) -> &'static LocaleRules {
+        resolve(tag).expect("supported locale")
+    }
+
+    #[test]
+    fn resolves_exact_tags_then_languages() {
+        assert_eq!(rules("en-GB").tag, "en-GB");
+        assert_eq!(rules("en_gb").tag, "en-GB");
+        assert_eq!(rules("en-US").tag, "en");
+        assert_eq!(rules("de-AT").tag, "de");
+        assert_eq!(rules("pt-BR").tag, "pt");
+        assert!(resolve("xx").is_none());
+        assert!(resolve("").is_none());
+        assert!(resolve("de;DROP").is_none());
+    }
+
+    #[test]
+    fn integers_use_locale_grouping() {
+        assert_eq!(rules("en").format_integer(1_234_567), "1,234,567");
+        assert_eq!(rules("de").format_integer(1_234_567), "1.234.567");
+        assert_eq!(rules("fr").format_integer(1_234), "1\u{202f}234");
+        assert_eq!(rules("en").format_integer(999), "999");
+        assert_eq!(rules("en").format_integer(-1_000), "-1,000");
+    }
+
+    #[test]
+    fn spanish_does_not_group_four_digit_numbers() {
+        assert_eq!(rules("es").format_integer(1_234), "1234");
+        assert_eq!(rules("es").format_integer(12_345), "12.345");
+    }
+
+    #[test]
+    fn decimals_round_and_use_the_locale_separator() {
+        assert_eq!(rules("en").format_decimal(4.25, 1), "4.2");
+        assert_eq!(rules("de").format_decimal(4.26, 1), "4,3");
+        assert_eq!(rules("de").format_decimal(1234.5, 1), "1.234,5");
+        assert_eq!(rules("en").format_decimal(-0.01, 1), "0.0");
+        assert_eq!(rules("en").format_decimal(3.0, 0), "3");
+    }
+
+    #[test]
+    fn dates_follow_the_short_patterns() {
+        let at = Utc.with_ymd_and_hms(2026, 7, 4, 15, 4, 0).unwrap();
+        assert_eq!(rules("en").format_date_time(at), "7/4/26, 3:04\u{202f}PM");
+        assert_eq!(rules("en-GB").format_date_time(at), "04/07/2026, 15:04");
+        assert_eq!(rules("de").format_date_time(at), "04.07.26, 15:04");
+        assert_eq!(rules("ja").format_date_time(at), "2026/07/04 15:04");
+    }
+}
@@ -1,10 +1,22 @@
 use icp_marketplace_api::{
-    cleanup, cors, db, handlers, middleware,
+    cleanup, cors, db, handlers, integrity,
+    limits::ScriptLimits,
+    middleware::{self, RequestLimits},
     models::*,
-    services::{AccountService, PasskeyService, ReviewService, ScriptService},
+    rate_limit::{VelocityRules, ViewDeduper},
+    revalidation, scheduled_publish,
+    search_experiments::RankingExperiment,
+    services::{
+        AccountService, BundleService, CompatibilityService, DisputeService, EntitlementService,
+        ErrorReportService, IntegrityService, MaintenanceService, ModerationService,
+        PasskeyService, PromotionService, QuestionService, RevalidationService, ReviewService,
+        ScriptService, TelemetryService, WebhookService,
+    },
+    signed_urls::SourceUrlSigner,
     startup_checks::{
         warn_if_broken_prod_passkey_rp, warn_if_insecure_prod_admin_token, Environment,
     },
+    trending, webhook_delivery,
 };
 use poem::{delete, get, listener::TcpListener, post, EndpointExt, Route, Server};
 use sqlx::sqlite::SqlitePool;
@@ -88,8 +100,13 @@ async fn main() -> Result<(), std::io::Error> {
     db::initialize_database(&pool).await;
     tracing::info!("Database schema initialized successfully");
 
-    // Clone pool for background cleanup job before moving it to state
+    // Clone pool for the background jobs before moving it to state
     let cleanup_pool = pool.clone();
+    let webhook_pool = pool.clone();
+    let publish_pool = pool.clone();
+    let revalidation_pool = pool.clone();
+    let trending_pool = pool.clone();
+    let integrity_pool = pool.clone();
 
     // WebAuthn configuration
     let rp_id = env::var("WEBAUTHN_RP_ID").unwrap_or_else(|_| "localhost".to_string());
@@ -129,49 +146,123 @@ async fn main() -> Result<(), std::io::Error> {
         icp_marketplace_api::rate_limit::SlidingWindowRateLimiter::new(5, 15 * 60),
     );
 
+    // Per-route request budgets (413 / 408 with the standard envelope). Only
+    // GET/HEAD handlers are cut off at the deadline; writes run to completion.
+    // Script writes carry the bundle JSON-escaped, so they get twice the
+    // bundle limit plus headroom for the metadata; the IC proxy enforces its
+    // own body cap and upstream timeout.
+    let script_limits = ScriptLimits::from_env();
+    let script_write_limits = RequestLimits::new(
+        script_limits.max_bundle_bytes * 2 + 64 * 1024,
+        Duration::from_secs(30),
+    );
+    let vault_limits = RequestLimits::new(1024 * 1024, Duration::from_secs(15));
+    let default_limits = RequestLimits::new(64 * 1024, Duration::from_secs(10));
+
     let state = Arc::new(AppState {
         account_service: AccountService::new(pool.clone()),
-        script_service: ScriptService::new(pool.clone()),
+        script_service: ScriptService::with_limits(pool.clone(), script_limits)
+            .with_velocity_rules(VelocityRules::from_env())
+            .with_view_window(ViewDeduper::window_from_env())
+            .with_ranking_experiment(RankingExperiment::from_env()),
         review_service: ReviewService::new(pool.clone()),
         passkey_service,
+        webhook_service: WebhookService::new(pool.clone()),
+        promotion_service: PromotionService::new(pool.clone()),
+        dispute_service: DisputeService::new(pool.clone()),
+        bundle_service: BundleService::new(pool.clone()),
+        entitlement_service: EntitlementService::new(pool.clone()),
+        maintenance_service: MaintenanceService::new(pool.clone()),
+        question_service: QuestionService::new(pool.clone()),
+        telemetry_service: TelemetryService::new(pool.clone()),
+        error_report_service: ErrorReportService::new(pool.clone()),
+        compatibility_service: CompatibilityService::new(pool.clone()),
+        moderation_service: ModerationService::new(pool.clone()),
+        revalidation_service: RevalidationService::new(pool.clone()),
+        integrity_service: IntegrityService::new(pool.clone()),
         recovery_rate_limiter,
+        source_urls: SourceUrlSigner::from_env(),
         pool,
     });
 
     // ========================================================================
     // Route map — every public API route wired below, grouped by resource.
-    // Keep this in sync with the `.at(...)` chain. (Admin routes wear AdminAuth.)
+    // Keep this in sync with the `.at(...)` chain. (Admin routes wear AdminAuth.
+    // Every route except the IC proxy also wears a RequestLimits budget.)
     // ------------------------------------------------------------------------
     // Health & misc
     //   GET    /api/v1/health                         -> health_check
     //   GET    /api/v1/ping                           -> ping
-    //   GET    /api/v1/marketplace-stats              -> get_marketplace_stats
+    //   GET    /api/v1/marketplace-stats?locale=      -> get_marketplace_stats
+    //   GET    /api/v1/limits                         -> get_script_limits
+    //   GET    /api/v1/pricing                        -> get_pricing
     //   POST   /api/dev/reset-database                -> reset_database (dev only)
+    //   POST   /api/dev/canonical-payload             -> canonical_payload (dev only)
     // Scripts
-    //   GET    /api/v1/scripts                        -> get_scripts
+    //   GET    /api/v1/scripts                        -> get_scripts (includePrivate=true: signed GET)
     //   POST   /api/v1/scripts                        -> create_script
-    //   GET    /api/v1/scripts/count                  -> get_scripts_count
+    //   GET    /api/v1/scripts/count?locale=          -> get_scripts_count
     //   POST   /api/v1/scripts/search                 -> search_scripts
-    //   GET    /api/v1/scripts/trending               -> get_trending_scripts
+    //   POST   /api/v1/scripts/search/clicks          -> record_search_click
+    //   POST   /api/v1/scripts/validate/execute       -> preview_script_execution
+    //   GET    /api/v1/scripts/trending?by=&category=&tag= -> get_trending_scripts
     //   GET    /api/v1/scripts/featured               -> get_featured_scripts
     //   GET    /api/v1/scripts/compatible             -> get_compatible_scripts
+    //   GET    /api/v1/scripts/compare?ids=a,b,c      -> compare_scripts (BEFORE /:id)
     //   GET    /api/v1/scripts/category/:category     -> get_scripts_by_category
     //   GET    /api/v1/scripts/categories             -> get_script_categories (BEFORE /:id)
-    //   GET    /api/v1/scripts/:id                    -> get_script
-    //   PUT    /api/v1/scripts/:id                    -> update_script
+    //   GET    /api/v1/scripts/:id                    -> get_script (counts a deduplicated view; ?channel=beta; private: signed GET by owner)
+    //   PUT    /api/v1/scripts/:id                    -> update_script ("channel": "beta" pushes the beta build)
     //   DELETE /api/v1/scripts/:id                    -> delete_script
     //   POST   /api/v1/scripts/:id/publish            -> publish_script
-    //   GET    /api/v1/scripts/:id/preview            -> get_script_preview
-    //   GET    /api/v1/scripts/:id/reviews            -> get_reviews
+    //   GET    /api/v1/scripts/:id/preview            -> get_script_preview (private: signed GET by owner)
+    //   GET    /api/v1/scripts/:id/channels           -> get_script_channels (private: signed GET by owner)
+    //   GET    /api/v1/scripts/:id/validation         -> get_script_validation
+    //   GET    /api/v1/scripts/:id/dependencies       -> get_script_dependencies (private: signed GET by owner)
+    //   GET    /api/v1/scripts/:id/readme.html        -> get_script_readme_html (sanitized; private: signed GET by owner)
+    //   GET    /api/v1/scripts/:id/embed?format=      -> get_script_embed (any origin; outside the CORS allow-list)
+    //   GET    /api/v1/scripts/:id/reviews            -> get_reviews (signed GET: own reviews while shadow-banned)
     //   POST   /api/v1/scripts/:id/reviews            -> create_review
-    //   POST   /api/v1/scripts/:id/download           -> download_script (signed; audit + counter)
+    //   GET    /api/v1/scripts/:id/questions          -> get_questions (?unanswered=true; private: signed GET by owner)
+    //   POST   /api/v1/scripts/:id/questions          -> ask_question (signed)
+    //   POST   /api/v1/scripts/:id/questions/:question_id/answer -> answer_question (signed, owner)
+    //   GET    /api/v1/scripts/:id/reliability    -> get_script_reliability (signed GET, owner)
+    //   GET    /api/v1/scripts/:id/compatibility  -> get_compatibility (private: signed GET by owner)
+    //   POST   /api/v1/scripts/:id/compatibility  -> vote_compatibility (signed)
+    //   POST   /api/v1/scripts/:id/error-reports  -> post_error_report (202; deduplicated by stack)
+    //   GET    /api/v1/scripts/:id/error-reports  -> get_error_reports (signed GET, owner)
+    //   POST   /api/v1/scripts/:id/download           -> download_script (signed; audit + counter; ?channel=beta)
+    //   POST   /api/v1/scripts/:id/source-url         -> issue_source_url (signed; audit + counter)
+    //   GET    /api/v1/scripts/:id/source?token=      -> get_script_source (HMAC token)
+    //   POST   /api/v1/scripts/:id/promotions         -> promotion_create (signed, owner)
+    //   GET    /api/v1/scripts/:id/promotions         -> promotion_list (signed GET, owner)
+    //   DELETE /api/v1/scripts/:id/promotions/:promotion_id -> promotion_delete (signed, owner)
+    //   POST   /api/v1/scripts/:id/dispute            -> dispute_purchase (signed, purchaser)
+    //   GET    /api/v1/scripts/:id/bundles            -> get_script_bundles
+    // Bundle products
+    //   GET    /api/v1/bundles                        -> get_bundles
+    //   POST   /api/v1/bundles                        -> bundle_create (signed)
+    //   GET    /api/v1/bundles/:id                    -> get_bundle
+    //   PUT    /api/v1/bundles/:id                    -> bundle_update (signed, owner)
+    //   DELETE /api/v1/bundles/:id                    -> bundle_delete (signed, owner)
+    // Telemetry (opt-in, anonymous)
+    //   POST   /api/v1/telemetry                      -> post_telemetry (202; counted only)
+    // Entitlements
+    //   POST   /api/v1/entitlements/check             -> check_entitlements (signed)
+    //   GET    /api/v1/purchases                      -> get_purchases (signed GET)
     // Accounts
     //   POST   /api/v1/accounts                       -> register_account
     //   GET    /api/v1/accounts/:username             -> get_account
     //   PATCH  /api/v1/accounts/:username             -> update_account
     //   GET    /api/v1/accounts/by-public-key/:pubkey -> get_account_by_public_key
+    //   GET    /api/v1/accounts/:username/activity    -> get_account_activity
+    //   POST   /api/v1/accounts/:username/follow      -> follow_account (signed)
+    //   DELETE /api/v1/accounts/:username/follow      -> unfollow_account (signed)
+    //   GET    /api/v1/feed                           -> get_feed (signed GET)
     //   POST   /api/v1/accounts/:username/keys        -> add_account_key
+    //   GET    /api/v1/accounts/:username/keys/revoked -> get_revoked_keys (BEFORE /:key_id)
     //   DELETE /api/v1/accounts/:username/keys/:key_id-> remove_account_key
+    //   GET    /api/v1/keys/revocations?since=&limit= -> get_key_revocations
     // Passkeys
     // Passkeys (register/delete signature-gated; W7-13)
     //   POST   /api/v1/passkey/register/start         -> passkey_register_start (signed)
@@ -184,6 +275,13 @@ async fn main() -> Result<(), std::io::Error> {
     //   POST   /api/v1/vault          -> vault_create
     //   POST   /api/v1/vault/get      -> vault_get
     //   PUT    /api/v1/vault          -> vault_update
+    // Author stats webhooks (signature-gated)
+    //   PUT    /api/v1/webhooks                       -> webhook_set
+    //   GET    /api/v1/webhooks                       -> webhook_get (signed GET)
+    //   DELETE /api/v1/webhooks                       -> webhook_delete
+    // Account settings (signature-gated)
+    //   GET    /api/v1/account-settings               -> get_account_settings (signed GET)
+    //   PUT    /api/v1/account-settings               -> update_account_settings
     // Recovery codes (generate signature-gated; verify open + rate-limited; W7-14)
     //   POST   /api/v1/recovery/generate              -> recovery_generate (signed)
     //   POST   /api/v1/recovery/verify                -> recovery_verify (rate-limited)
@@ -191,134 +289,460 @@ async fn main() -> Result<(), std::io::Error> {
     // Admin (AdminAuth middleware)
     //   POST   /api/v1/admin/accounts/:username/keys/:key_id/disable -> admin_disable_key
     //   POST   /api/v1/admin/accounts/:username/recovery-key         -> admin_add_recovery_key
+    //   GET    /api/v1/admin/accounts/:username/overview             -> admin_account_overview
+    //   POST   /api/v1/admin/accounts/:username/velocity-reset       -> admin_reset_velocity
+    //   POST   /api/v1/admin/accounts/:username/shadow-ban           -> admin_shadow_ban
+    //   POST   /api/v1/admin/accounts/:username/tier                 -> admin_set_tier
+    //   GET    /api/v1/admin/accounts/:username/moderation           -> admin_moderation_status
+    //   POST   /api/v1/admin/accounts/:username/notes                -> admin_add_moderation_note
+    //   POST   /api/v1/admin/accounts/:username/strikes              -> admin_add_strike
+    //   POST   /api/v1/admin/accounts/:username/strikes/:strike_id/revoke -> admin_revoke_strike
+    //   GET    /api/v1/admin/search-analytics                        -> admin_search_analytics
+    //   GET    /api/v1/admin/search-experiments                      -> admin_search_experiments
+    //   POST   /api/v1/admin/categories/merge                        -> admin_merge_categories
+    //   POST   /api/v1/admin/tags/rename                             -> admin_rename_tag
+    //   GET    /api/v1/admin/reviews/quarantine                      -> admin_list_quarantined_reviews
+    //   POST   /api/v1/admin/reviews/:id/moderate                    -> admin_moderate_review
+    //   GET    /api/v1/admin/disputes                                -> admin_list_disputes
+    //   POST   /api/v1/admin/disputes/:purchase_id/resolve           -> admin_resolve_dispute
+    //   GET    /api/v1/admin/purchases/:id/history                   -> admin_purchase_history
+    //   GET    /api/v1/admin/maintenance                             -> admin_get_maintenance
+    //   PUT    /api/v1/admin/maintenance                             -> admin_set_maintenance
+    //   POST   /api/v1/admin/integrity-check                         -> admin_integrity_check
+    //   GET    /api/v1/admin/integrity-reports                       -> admin_integrity_reports
     // IC byte-relay CORS proxy (R-3b WU-1)
     //   GET|POST /api/v1/ic/*<rest>                 -> ic_proxy (forwards to ${IC_GATEWAY_HOST})
     // ========================================================================
     // Build app
     let app = Route::new()
-        .at("/api/v1/health", get(handlers::health_check))
-        .at("/api/v1/ping", get(handlers::ping))
+        .at(
+            "/api/v1/health",
+            get(handlers::health_check).with(default_limits),
+        )
+        .at("/api/v1/ping", get(handlers::ping).with(default_limits))
         .at(
             "/api/v1/scripts",
-            get(handlers::get_scripts).post(handlers::create_script),
+            get(handlers::get_scripts.with(default_limits))
+                .post(handlers::create_script.with(script_write_limits)),
+        )
+        .at(
+            "/api/v1/scripts/count",
+            get(handlers::get_scripts_count).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/search",
+            post(handlers::search_scripts).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/search/clicks",
+            post(handlers::record_search_click).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/validate/execute",
+            post(handlers::preview_script_execution).with(script_write_limits),
         )
-        .at("/api/v1/scripts/count", get(handlers::get_scripts_count))
-        .at("/api/v1/scripts/search", post(handlers::search_scripts))
         .at(
             "/api/v1/scripts/trending",
-            get(handlers::get_trending_scripts),
+            get(handlers::get_trending_scripts).with(default_limits),
         )
         .at(
             "/api/v1/scripts/featured",
-            get(handlers::get_featured_scripts),
+            get(handlers::get_featured_scripts).with(default_limits),
         )
         .at(
             "/api/v1/scripts/compatible",
-            get(handlers::get_compatible_scripts),
+            get(handlers::get_compatible_scripts).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/compare",
+            get(handlers::compare_scripts).with(default_limits),
         )
         .at(
             "/api/v1/scripts/category/:category",
-            get(handlers::get_scripts_by_category),
+            get(handlers::get_scripts_by_category).with(default_limits),
         )
         .at(
             "/api/v1/scripts/categories",
-            get(handlers::get_script_categories),
+            get(handlers::get_script_categories).with(default_limits),
         )
         .at(
             "/api/v1/scripts/:id",
-            get(handlers::get_script)
-                .put(handlers::update_script)
-                .delete(handlers::delete_script),
+            get(handlers::get_script.with(default_limits))
+                .put(handlers::update_script.with(script_write_limits))
+                .delete(handlers::delete_script.with(script_write_limits)),
         )
         .at(
             "/api/v1/scripts/:id/publish",
-            post(handlers::publish_script),
+            post(handlers::publish_script).with(default_limits),
         )
         .at(
             "/api/v1/scripts/:id/preview",
-            get(handlers::get_script_preview),
+            get(handlers::get_script_preview).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/channels",
+            get(handlers::get_script_channels).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/validation",
+            get(handlers::get_script_validation).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/dependencies",
+            get(handlers::get_script_dependencies).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/readme.html",
+            get(handlers::get_script_readme_html).with(default_limits),
         )
         .at(
             "/api/v1/scripts/:id/reviews",
-            get(handlers::get_reviews).post(handlers::create_review),
+            get(handlers::get_reviews)
+                .post(handlers::create_review)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/questions",
+            get(handlers::get_questions)
+                .post(handlers::ask_question)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/questions/:question_id/answer",
+            post(handlers::answer_question).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/reliability",
+            get(handlers::get_script_reliability).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/compatibility",
+            get(handlers::get_compatibility)
+                .post(handlers::vote_compatibility)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/error-reports",
+            get(handlers::get_error_reports)
+                .post(handlers::post_error_report)
+                .with(default_limits),
         )
         .at(
             "/api/v1/scripts/:id/download",
-            post(handlers::download_script),
+            post(handlers::download_script).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/source-url",
+            post(handlers::issue_source_url).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/source",
+            get(handlers::get_script_source).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/promotions",
+            get(handlers::promotion_list)
+                .post(handlers::promotion_create)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/promotions/:promotion_id",
+            delete(handlers::promotion_delete).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/dispute",
+            post(handlers::dispute_purchase).with(default_limits),
+        )
+        .at(
+            "/api/v1/scripts/:id/bundles",
+            get(handlers::get_script_bundles).with(default_limits),
+        )
+        // Bundle product endpoints
+        .at(
+            "/api/v1/bundles",
+            get(handlers::get_bundles)
+                .post(handlers::bundle_create)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/bundles/:id",
+            get(handlers::get_bundle)
+                .put(handlers::bundle_update)
+                .delete(handlers::bundle_delete)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/telemetry",
+            post(handlers::post_telemetry).with(default_limits),
+        )
+        .at(
+            "/api/v1/entitlements/check",
+            post(handlers::check_entitlements).with(default_limits),
+        )
+        .at(
+            "/api/v1/purchases",
+            get(handlers::get_purchases).with(default_limits),
         )
         // Account Profiles endpoints
-        .at("/api/v1/accounts", post(handlers::register_account))
+        .at(
+            "/api/v1/accounts",
+            post(handlers::register_account).with(default_limits),
+        )
         .at(
             "/api/v1/accounts/:username",
-            get(handlers::get_account).patch(handlers::update_account),
+            get(handlers::get_account)
+                .patch(handlers::update_account)
+                .with(default_limits),
         )
         .at(
             "/api/v1/accounts/by-public-key/:public_key",
-            get(handlers::get_account_by_public_key),
+            get(handlers::get_account_by_public_key).with(default_limits),
         )
         .at(
             "/api/v1/accounts/:username/keys",
-            post(handlers::add_account_key),
+            post(handlers::add_account_key).with(default_limits),
+        )
+        .at(
+            "/api/v1/accounts/:username/activity",
+            get(handlers::get_account_activity).with(default_limits),
+        )
+        .at(
+            "/api/v1/accounts/:username/follow",
+            post(handlers::follow_account)
+                .delete(handlers::unfollow_account)
+                .with(default_limits),
+        )
+        .at("/api/v1/feed", get(handlers::get_feed).with(default_limits))
+        .at(
+            "/api/v1/accounts/:username/keys/revoked",
+            get(handlers::get_revoked_keys).with(default_limits),
         )
         .at(
             "/api/v1/accounts/:username/keys/:key_id",
-            delete(handlers::remove_account_key),
+            delete(handlers::remove_account_key).with(default_limits),
+        )
+        .at(
+            "/api/v1/keys/revocations",
+            get(handlers::get_key_revocations).with(default_limits),
         )
         // Passkey Authentication endpoints
         .at(
             "/api/v1/passkey/register/start",
-            post(handlers::passkey_register_start),
+            post(handlers::passkey_register_start).with(default_limits),
         )
         .at(
             "/api/v1/passkey/register/finish",
-            post(handlers::passkey_register_finish),
+            post(handlers::passkey_register_finish).with(default_limits),
         )
         .at(
             "/api/v1/passkey/authenticate/start",
-            post(handlers::passkey_authenticate_start),
+            post(handlers::passkey_authenticate_start).with(default_limits),
         )
         .at(
             "/api/v1/passkey/authenticate/finish",
-            post(handlers::passkey_authenticate_finish),
+            post(handlers::passkey_authenticate_finish).with(default_limits),
         )
         .at(
             "/api/v1/passkey/list/:account_id",
-            get(handlers::passkey_list),
+            get(handlers::passkey_list).with(default_limits),
         )
         .at(
             "/api/v1/passkey/:passkey_id",
-            delete(handlers::passkey_delete),
+            delete(handlers::passkey_delete).with(default_limits),
         )
         // Vault endpoints (signature-gated; W7-12)
         .at(
             "/api/v1/vault",
-            post(handlers::vault_create).put(handlers::vault_update),
+            post(handlers::vault_create)
+                .put(handlers::vault_update)
+                .with(vault_limits),
+        )
+        .at(
+            "/api/v1/vault/get",
+            post(handlers::vault_get).with(vault_limits),
+        )
+        // Author stats webhook endpoints (signature-gated)
+        .at(
+            "/api/v1/webhooks",
+            get(handlers::webhook_get)
+                .put(handlers::webhook_set)
+                .delete(handlers::webhook_delete)
+                .with(default_limits),
+        )
+        // Account settings endpoints (signature-gated)
+        .at(
+            "/api/v1/account-settings",
+            get(handlers::get_account_settings)
+                .put(handlers::update_account_settings)
+                .with(default_limits),
         )
-        .at("/api/v1/vault/get", post(handlers::vault_get))
         // Recovery code endpoints
         .at(
             "/api/v1/recovery/generate",
-            post(handlers::recovery_generate),
+            post(handlers::recovery_generate).with(default_limits),
+        )
+        .at(
+            "/api/v1/recovery/verify",
+            post(handlers::recovery_verify).with(default_limits),
         )
-        .at("/api/v1/recovery/verify", post(handlers::recovery_verify))
         .at(
             "/api/v1/recovery/status/:account_id",
-            get(handlers::recovery_status),
+            get(handlers::recovery_status).with(default_limits),
         )
         // Admin Account endpoints (require admin authentication)
         .at(
             "/api/v1/admin/accounts/:username/keys/:key_id/disable",
-            post(handlers::admin_disable_key).with(middleware::AdminAuth),
+            post(handlers::admin_disable_key)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
         )
         .at(
             "/api/v1/admin/accounts/:username/recovery-key",
-            post(handlers::admin_add_recovery_key).with(middleware::AdminAuth),
+            post(handlers::admin_add_recovery_key)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/overview",
+            get(handlers::admin_account_overview)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/velocity-reset",
+            post(handlers::admin_reset_velocity)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/shadow-ban",
+            post(handlers::admin_shadow_ban)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/tier",
+            post(handlers::admin_set_tier)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/moderation",
+            get(handlers::admin_moderation_status)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/notes",
+            post(handlers::admin_add_moderation_note)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/strikes",
+            post(handlers::admin_add_strike)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/accounts/:username/strikes/:strike_id/revoke",
+            post(handlers::admin_revoke_strike)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/search-analytics",
+            get(handlers::admin_search_analytics)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/search-experiments",
+            get(handlers::admin_search_experiments)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/categories/merge",
+            post(handlers::admin_merge_categories)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/tags/rename",
+            post(handlers::admin_rename_tag)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/reviews/quarantine",
+            get(handlers::admin_list_quarantined_reviews)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/reviews/:id/moderate",
+            post(handlers::admin_moderate_review)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/disputes",
+            get(handlers::admin_list_disputes)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/disputes/:purchase_id/resolve",
+            post(handlers::admin_resolve_dispute)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/purchases/:id/history",
+            get(handlers::admin_purchase_history)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/maintenance",
+            get(handlers::admin_get_maintenance)
+                .put(handlers::admin_set_maintenance)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/integrity-check",
+            post(handlers::admin_integrity_check)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
+        )
+        .at(
+            "/api/v1/admin/integrity-reports",
+            get(handlers::admin_integrity_reports)
+                .with(middleware::AdminAuth)
+                .with(default_limits),
         )
         .at(
             "/api/v1/marketplace-stats",
-            get(handlers::get_marketplace_stats),
+            get(handlers::get_marketplace_stats).with(default_limits),
+        )
+        .at(
+            "/api/v1/limits",
+            get(handlers::get_script_limits).with(default_limits),
+        )
+        .at(
+            "/api/v1/pricing",
+            get(handlers::get_pricing).with(default_limits),
+        )
+        .at(
+            "/api/dev/reset-database",
+            post(handlers::reset_database).with(default_limits),
+        )
+        .at(
+            "/api/dev/canonical-payload",
+            post(handlers::canonical_payload).with(default_limits),
         )
-        .at("/api/dev/reset-database", post(handlers::reset_database))
         // R-3b WU-1: IC byte-relay CORS proxy. A protocol-blind catch-all that
         // forwards /api/v1/ic/*<rest> to ${IC_GATEWAY_HOST} (default ic0.app)
         // so the browser-side agent-js can reach IC boundary nodes (browsers
@@ -330,7 +754,27 @@ async fn main() -> Result<(), std::io::Error> {
             get(handlers::ic_proxy::ic_proxy).post(handlers::ic_proxy::ic_proxy),
         );
 
-    let app = app.with(cors::build_cors()).data(state);
+    // Maintenance mode turns every non-exempt write into a 503 (see
+    // middleware/maintenance.rs); it reads the switch from AppState.
+    // Embeddable cards are fetched by arbitrary canister-hosted sites, so
+    // their route wears its own open CORS instead of the allow-list. The
+    // nested app matches everything else.
+    let app = Route::new()
+        .at(
+            "/api/v1/scripts/:id/embed",
+            get(handlers::get_script_embed)
+                .with(default_limits)
+                .with(cors::build_embed_cors()),
+        )
+        .nest(
+            "/",
+            app.with(middleware::MaintenanceGuard)
+                .with(cors::build_cors()),
+        )
+        // Outermost, so it also sees CORS and maintenance rejections: stamps
+        // X-Trace-Id and serves problem+json errors to clients that ask.
+        .with(middleware::ProblemJson)
+        .data(state);
 
     // Start server
     let port = env::var("PORT").unwrap_or_else(|_| "58000".to_string());
@@ -383,6 +827,11 @@ async fn main() -> Result<(), std::io::Error> {
 
     // Start background cleanup job for signature audit
     cleanup::start_audit_cleanup_job(cleanup_pool, shutdown.clone());
+    webhook_delivery::start_webhook_delivery_job(webhook_pool, shutdown.clone());
+    scheduled_publish::start_scheduled_publish_job(publish_pool, shutdown.clone());
+    revalidation::start_revalidation_job(revalidation_pool, shutdown.clone());
+    trending::start_trending_rollup_job(trending_pool, shutdown.clone());
+    integrity::start_integrity_check_job(integrity_pool, shutdown.clone());
 
     // Close the std listener since we just needed it for the address
     drop(std_listener);
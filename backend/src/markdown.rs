@@ -0,0 +1,163 @@
+//! Markdown to safe HTML.
+//!
+//! Markdown written by users — script readmes, review comments, account
+//! bios — is rendered here with pulldown-cmark and the result cleaned by
+//! ammonia, so clients can insert it as HTML without sanitizing it
+//! themselves. Raw HTML inside the markdown goes through the same cleaner:
+//! allowed tags survive, everything else is stripped.
+//!
+//! One policy covers every [`Profile`]:
+//! - link and image URLs are relative or use one of [`URL_SCHEMES`];
+//! - every link gets [`LINK_REL`] as its `rel`;
+//! - no event handler, `style` or `class` attribute and no comments;
+//!   `<script>` and `<style>` are dropped along with their content.
+//!
+//! The profiles differ only in the tags they keep.
+
+use std::sync::OnceLock;
+
+use pulldown_cmark::{html, Options, Parser};
+
+/// Schemes a link or image may use; other absolute URLs are removed.
+pub const URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// `rel` of every link: user content must neither reach the opener nor pass
+/// on ranking.
+pub const LINK_REL: &str = "noopener noreferrer nofollow";
+
+/// Tags of [`Profile::Comment`]: inline formatting, links, lists, quotes and
+/// code.
+pub const COMMENT_TAGS: &[&str] = &[
+    "a",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "em",
+    "i",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "strong",
+    "ul",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Readmes: ammonia's default tag set (headings, tables, images, …) plus
+    /// task list checkboxes.
+    Document,
+    /// Review comments, questions and answers, and bios: [`COMMENT_TAGS`]
+    /// only.
+    Comment,
+}
+
+impl Profile {
+    fn options(self) -> Options {
+        match self {
+            Self::Document => {
+                Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+            }
+            Self::Comment => Options::ENABLE_STRIKETHROUGH,
+        }
+    }
+
+    fn cleaner(self) -> &'static ammonia::Builder<'static> {
+        static DOCUMENT: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+        static COMMENT: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+        match self {
+            Self::Document => DOCUMENT.get_or_init(|| {
+                let mut builder = policy();
+                builder
+                    .add_tags(["input"])
+                    .add_tag_attributes("input", ["checked", "disabled"])
+                    .add_tag_attribute_values("input", "type", ["checkbox"]);
+                builder
+            }),
+            Self::Comment => COMMENT.get_or_init(|| {
+                let mut builder = policy();
+                builder.tags(COMMENT_TAGS.iter().copied().collect());
+                builder
+            }),
+        }
+    }
+}
+
+/// The rules shared by every profile, over ammonia's default tag set.
+fn policy() -> ammonia::Builder<'static> {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .url_schemes(URL_SCHEMES.iter().copied().collect())
+        .link_rel(Some(LINK_REL))
+        .strip_comments(true);
+    builder
+}
+
+/// Renders `markdown` to an HTML fragment that is safe to insert into a
+/// page.
+pub fn render(markdown: &str, profile: Profile) -> String {
+    let mut rendered = String::with_capacity(markdown.len() + markdown.len() / 2);
+    html::push_html(&mut rendered, Parser::new_ext(markdown, profile.options()));
+    profile.cleaner().clean(&rendered).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_keeps_structure() {
+        let html = render(
+            "# Usage\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n- [x] done\n\n![logo](https://example.com/l.png)",
+            Profile::Document,
+        );
+        assert!(html.contains("<h1>Usage</h1>"), "{html}");
+        assert!(html.contains("<table>"), "{html}");
+        assert!(html.contains(r#"type="checkbox""#), "{html}");
+        assert!(
+            html.contains(r#"<img src="https://example.com/l.png" alt="logo">"#),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn comment_keeps_inline_formatting_only() {
+        let html = render(
+            "# Big\n\n**bold** and `code`\n\n![img](https://example.com/i.png)",
+            Profile::Comment,
+        );
+        assert!(!html.contains("<h1"), "{html}");
+        assert!(!html.contains("<img"), "{html}");
+        assert!(html.contains("<strong>bold</strong>"), "{html}");
+        assert!(html.contains("<code>code</code>"), "{html}");
+    }
+
+    #[test]
+    fn links_get_rel_and_lose_unsafe_schemes() {
+        let html = render(
+            "[ok](https://example.com) [bad](javascript:alert(1))",
+            Profile::Comment,
+        );
+        assert!(
+            html.contains(
+                r#"<a href="https://example.com" rel="noopener noreferrer nofollow">ok</a>"#
+            ),
+            "{html}"
+        );
+        assert!(!html.contains("javascript"), "{html}");
+    }
+
+    #[test]
+    fn raw_html_is_cleaned_not_trusted() {
+        let html = render(
+            "<script>alert(1)</script>\n\n<b onclick=\"x()\">hi</b>",
+            Profile::Document,
+        );
+        assert!(!html.contains("alert"), "{html}");
+        assert!(!html.contains("onclick"), "{html}");
+        assert!(html.contains("<b>hi</b>"), "{html}");
+    }
+}
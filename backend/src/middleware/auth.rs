@@ -2,7 +2,9 @@ use poem::{http::StatusCode, Response};
 
 use crate::auth::verify_operation_signature;
 use crate::models::{CreateScriptRequest, DeleteScriptRequest, UpdateScriptRequest};
+use crate::pricing::{DEFAULT_CURRENCY, PRICED_PAYLOAD_VERSION};
 use crate::responses::error_response;
+use crate::script_dependencies::signed_dependencies;
 
 /// Trait for requests that contain authentication information
 pub trait AuthenticatedRequest {
@@ -86,6 +88,19 @@ pub fn build_upload_payload(req: &CreateScriptRequest) -> Result<serde_json::Val
     if let Some(ref compatibility) = req.compatibility {
         payload["compatibility"] = serde_json::Value::String(compatibility.clone());
     }
+    if let Some(ref readme) = req.readme {
+        payload["readme"] = serde_json::Value::String(readme.clone());
+    }
+    if let Some(ref dependencies) = req.dependencies {
+        payload["dependencies"] = serde_json::json!(signed_dependencies(dependencies));
+    }
+    // v2 signs the price; an unpriced v2 upload signs the free default.
+    if req.payload_version == Some(PRICED_PAYLOAD_VERSION) {
+        payload["payload_version"] = serde_json::json!(PRICED_PAYLOAD_VERSION);
+        payload["price_e8s"] = serde_json::json!(req.price_e8s.unwrap_or(0));
+        payload["currency"] =
+            serde_json::json!(req.currency.as_deref().unwrap_or(DEFAULT_CURRENCY));
+    }
 
     Ok(payload)
 }
@@ -175,9 +190,12 @@ pub fn build_canonical_update_payload(
 
     insert_optional_string("title", &req.title, &mut payload);
     insert_optional_string("description", &req.description, &mut payload);
+    insert_optional_string("readme", &req.readme, &mut payload);
     insert_optional_string("category", &req.category, &mut payload);
     insert_optional_string("bundle", &req.bundle, &mut payload);
     insert_optional_string("version", &req.version, &mut payload);
+    insert_optional_string("publish_at", &req.publish_at, &mut payload);
+    insert_optional_string("channel", &req.channel, &mut payload);
 
     if let Some(tags) = &req.tags {
         let mut sorted_tags = tags.clone();
@@ -189,6 +207,13 @@ pub fn build_canonical_update_payload(
         payload.insert("tags".to_string(), serde_json::Value::Array(tag_values));
     }
 
+    if let Some(dependencies) = &req.dependencies {
+        payload.insert(
+            "dependencies".to_string(),
+            serde_json::json!(signed_dependencies(dependencies)),
+        );
+    }
+
     if let Some(price) = req.price {
         let number = serde_json::Number::from_f64(price).ok_or_else(|| {
             Box::new(error_response(
@@ -203,6 +228,17 @@ pub fn build_canonical_update_payload(
         payload.insert("is_public".to_string(), serde_json::Value::Bool(is_public));
     }
 
+    if req.payload_version == Some(PRICED_PAYLOAD_VERSION) {
+        payload.insert(
+            "payload_version".to_string(),
+            serde_json::json!(PRICED_PAYLOAD_VERSION),
+        );
+        if let Some(price_e8s) = req.price_e8s {
+            payload.insert("price_e8s".to_string(), serde_json::json!(price_e8s));
+        }
+        insert_optional_string("currency", &req.currency, &mut payload);
+    }
+
     Ok(serde_json::Value::Object(payload))
 }
 
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use poem::{
+    http::{header, Method, StatusCode},
+    web::Json,
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+
+use crate::models::AppState;
+
+/// Paths that keep accepting writes while maintenance mode is on: the admin
+/// API (so the switch can be turned back off), the IC proxy (it only relays
+/// to the IC and never touches the database) and search, which is a POST
+/// only because of its body.
+const EXEMPT_PREFIXES: &[&str] = &["/api/v1/admin/", "/api/v1/ic/", "/api/v1/scripts/search"];
+
+/// Rejects writes with 503 while maintenance mode is on. Reads are always
+/// served. The switch lives in the database (see
+/// [`crate::services::MaintenanceService`]) so every instance converges on it.
+///
+/// Needs `Arc<AppState>` as request data; without it every request passes.
+pub struct MaintenanceGuard;
+
+impl<E: Endpoint> Middleware<E> for MaintenanceGuard {
+    type Output = MaintenanceGuardEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        MaintenanceGuardEndpoint { ep }
+    }
+}
+
+pub struct MaintenanceGuardEndpoint<E> {
+    ep: E,
+}
+
+fn is_write(req: &Request) -> bool {
+    !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS)
+        && !EXEMPT_PREFIXES
+            .iter()
+            .any(|prefix| req.uri().path().starts_with(prefix))
+}
+
+impl<E: Endpoint> Endpoint for MaintenanceGuardEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if is_write(&req) {
+            if let Some(state) = req.data::<Arc<AppState>>() {
+                let maintenance = state.maintenance_service.current().await;
+                if maintenance.enabled {
+                    let message = maintenance.message.unwrap_or_else(|| {
+                        "The marketplace is in read-only maintenance mode".to_string()
+                    });
+                    let mut response = (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        Json(serde_json::json!({
+                            "success": false,
+                            "error": message,
+                            "retryAfterSecs": maintenance.retry_after_secs,
+                        })),
+                    )
+                        .into_response();
+                    response
+                        .headers_mut()
+                        .insert(header::RETRY_AFTER, maintenance.retry_after_secs.into());
+                    return Ok(response);
+                }
+            }
+        }
+        Ok(self.ep.call(req).await?.into_response())
+    }
+}
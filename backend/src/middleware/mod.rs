@@ -1,5 +1,13 @@
 pub mod admin_auth;
 pub mod auth;
+pub mod maintenance;
+pub mod problem_json;
+pub mod request_limits;
+pub mod signed_identity;
 
 pub use admin_auth::AdminAuth;
 pub use auth::{verify_request_auth, AuthenticatedRequest};
+pub use maintenance::MaintenanceGuard;
+pub use problem_json::ProblemJson;
+pub use request_limits::RequestLimits;
+pub use signed_identity::{OptionalSignedIdentity, SignedIdentity};
@@ -0,0 +1,154 @@
+use poem::{
+    http::{header, HeaderValue},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+
+use crate::problem::{problem_body, PROBLEM_JSON};
+
+/// Response header carrying the id of the request, on every response.
+pub const TRACE_ID_HEADER: &str = "X-Trace-Id";
+
+/// Tags every response with an `X-Trace-Id` (the caller's own, when it sends
+/// a well-formed one) and, for callers that accept
+/// `application/problem+json`, rewrites error responses into RFC 7807
+/// problem documents carrying the same id (see [`crate::problem`]).
+/// Everyone else keeps the `{"success":false,"error":…}` envelope.
+pub struct ProblemJson;
+
+impl<E: Endpoint> Middleware<E> for ProblemJson {
+    type Output = ProblemJsonEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ProblemJsonEndpoint { ep }
+    }
+}
+
+pub struct ProblemJsonEndpoint<E> {
+    ep: E,
+}
+
+/// A caller-supplied trace id is reused only if it is short and plain, so it
+/// can be echoed into headers and logs as-is.
+fn inbound_trace_id(req: &Request) -> Option<String> {
+    req.headers()
+        .get(TRACE_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= 64
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(str::to_string)
+}
+
+fn accepts_problem_json(req: &Request) -> bool {
+    req.headers()
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|media| {
+            media
+                .split(';')
+                .next()
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(PROBLEM_JSON))
+        })
+}
+
+impl<E: Endpoint> Endpoint for ProblemJsonEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let trace_id = inbound_trace_id(&req).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let problem = accepts_problem_json(&req);
+
+        let mut resp = match self.ep.call(req).await {
+            Ok(resp) => resp.into_response(),
+            Err(e) => e.into_response(),
+        };
+        if problem && (resp.status().is_client_error() || resp.status().is_server_error()) {
+            let body = resp.take_body().into_bytes().await.unwrap_or_default();
+            let document = problem_body(resp.status(), &body, &trace_id);
+            resp.set_body(document.to_string());
+            resp.headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+            resp.headers_mut().remove(header::CONTENT_LENGTH);
+        }
+        if let Ok(value) = HeaderValue::from_str(&trace_id) {
+            resp.headers_mut().insert(TRACE_ID_HEADER, value);
+        }
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::error_response;
+    use poem::{handler, http::StatusCode, test::TestClient, EndpointExt, Route};
+
+    #[handler]
+    fn missing() -> Response {
+        error_response(StatusCode::NOT_FOUND, "Script not found")
+    }
+
+    #[handler]
+    fn fine() -> &'static str {
+        "ok"
+    }
+
+    fn app() -> impl Endpoint {
+        Route::new()
+            .at("/missing", poem::get(missing))
+            .at("/fine", poem::get(fine))
+            .with(ProblemJson)
+    }
+
+    #[tokio::test]
+    async fn errors_keep_the_envelope_unless_problem_json_is_accepted() {
+        let client = TestClient::new(app());
+
+        let resp = client.get("/missing").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_content_type("application/json; charset=utf-8");
+        assert!(resp.0.headers().contains_key(TRACE_ID_HEADER));
+        resp.assert_json(serde_json::json!({"success": false, "error": "Script not found"}))
+            .await;
+
+        let resp = client
+            .get("/missing")
+            .header("Accept", "application/json, application/problem+json;q=0.9")
+            .header(TRACE_ID_HEADER, "trace-123")
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_content_type(PROBLEM_JSON);
+        resp.assert_header(TRACE_ID_HEADER, "trace-123");
+        let body: serde_json::Value = resp.json().await.value().deserialize();
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["detail"], "Script not found");
+        assert_eq!(body["traceId"], "trace-123");
+    }
+
+    #[tokio::test]
+    async fn successes_are_untouched_and_bad_trace_ids_are_replaced() {
+        let client = TestClient::new(app());
+        let resp = client
+            .get("/fine")
+            .header("Accept", PROBLEM_JSON)
+            .header(TRACE_ID_HEADER, "bad id\twith spaces")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        let trace_id = resp.0.headers()[TRACE_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_ne!(trace_id, "bad id\twith spaces");
+        assert!(uuid::Uuid::parse_str(&trace_id).is_ok());
+        resp.assert_text("ok").await;
+    }
+}
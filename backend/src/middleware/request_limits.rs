@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use poem::{
+    http::{Method, StatusCode},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+
+use crate::responses::error_response;
+
+/// Per-route request budget: a maximum body size and a deadline covering
+/// reading the body and, for reads (`GET`/`HEAD`), running the handler.
+///
+/// The body is read up front under the deadline, so a client trickling bytes
+/// (slowloris) gets 408 instead of pinning a task indefinitely, and an
+/// oversized body gets 413 after at most `max_body_bytes` have been buffered
+/// (immediately, when `Content-Length` already says so). Both use the
+/// standard `{"success":false,"error":…}` envelope.
+///
+/// Handlers of other methods run to completion once the body is in: they
+/// write in several statements, and dropping one midway would leave a
+/// partial write behind while telling the client nothing happened.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestLimits {
+    max_body_bytes: usize,
+    timeout: Duration,
+}
+
+impl RequestLimits {
+    pub const fn new(max_body_bytes: usize, timeout: Duration) -> Self {
+        Self {
+            max_body_bytes,
+            timeout,
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestLimits {
+    type Output = RequestLimitsEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestLimitsEndpoint { ep, limits: *self }
+    }
+}
+
+pub struct RequestLimitsEndpoint<E> {
+    ep: E,
+    limits: RequestLimits,
+}
+
+impl<E: Endpoint> RequestLimitsEndpoint<E> {
+    fn too_large(&self) -> Response {
+        error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            &format!(
+                "Request body exceeds the {} byte limit for this endpoint",
+                self.limits.max_body_bytes
+            ),
+        )
+    }
+
+    fn timed_out(&self, path: &str) -> Response {
+        tracing::warn!(
+            "Request to {} exceeded its {:?} deadline",
+            path,
+            self.limits.timeout
+        );
+        error_response(
+            StatusCode::REQUEST_TIMEOUT,
+            &format!(
+                "Request did not complete within {} seconds",
+                self.limits.timeout.as_secs()
+            ),
+        )
+    }
+
+    /// Buffers the body into `req`, or the error response to send instead.
+    async fn buffer(&self, req: &mut Request) -> std::result::Result<(), Response> {
+        match req
+            .take_body()
+            .into_bytes_limit(self.limits.max_body_bytes)
+            .await
+        {
+            Ok(body) => {
+                req.set_body(body);
+                Ok(())
+            }
+            Err(poem::error::ReadBodyError::PayloadTooLarge) => Err(self.too_large()),
+            Err(e) => Err(error_response(
+                StatusCode::BAD_REQUEST,
+                &format!("Failed to read request body: {e}"),
+            )),
+        }
+    }
+
+    async fn buffer_and_call(&self, mut req: Request) -> Result<Response> {
+        if let Err(resp) = self.buffer(&mut req).await {
+            return Ok(resp);
+        }
+        Ok(self.ep.call(req).await?.into_response())
+    }
+}
+
+impl<E: Endpoint> Endpoint for RequestLimitsEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let declared_len = req
+            .headers()
+            .get(poem::http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        if declared_len.is_some_and(|len| len > self.limits.max_body_bytes) {
+            return Ok(self.too_large());
+        }
+
+        let path = req.uri().path().to_string();
+        if matches!(*req.method(), Method::GET | Method::HEAD) {
+            return match tokio::time::timeout(self.limits.timeout, self.buffer_and_call(req)).await
+            {
+                Ok(result) => result,
+                Err(_) => Ok(self.timed_out(&path)),
+            };
+        }
+
+        match tokio::time::timeout(self.limits.timeout, self.buffer(&mut req)).await {
+            Ok(Ok(())) => Ok(self.ep.call(req).await?.into_response()),
+            Ok(Err(resp)) => Ok(resp),
+            Err(_) => Ok(self.timed_out(&path)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::{handler, test::TestClient, Body, EndpointExt, Route};
+
+    #[handler]
+    fn echo_len(body: Vec<u8>) -> String {
+        body.len().to_string()
+    }
+
+    #[handler]
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        "done"
+    }
+
+    /// Past the 100 ms deadline, but short enough to wait for.
+    #[handler]
+    async fn slow_write() -> &'static str {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        "done"
+    }
+
+    fn app() -> impl Endpoint {
+        let limits = RequestLimits::new(8, Duration::from_millis(100));
+        Route::new()
+            .at("/echo", poem::post(echo_len).with(limits))
+            .at("/slow", poem::get(slow).with(limits))
+            .at("/write", poem::post(slow_write).with(limits))
+    }
+
+    #[tokio::test]
+    async fn body_within_limit_reaches_handler() {
+        let client = TestClient::new(app());
+        let resp = client.post("/echo").body("12345678").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("8").await;
+    }
+
+    #[tokio::test]
+    async fn oversized_body_gets_413_envelope() {
+        let client = TestClient::new(app());
+        let resp = client.post("/echo").body("123456789").send().await;
+        resp.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+        let json = resp.json().await.value().deserialize::<serde_json::Value>();
+        assert_eq!(json["success"], false);
+    }
+
+    #[tokio::test]
+    async fn oversized_streamed_body_without_length_gets_413() {
+        let client = TestClient::new(app());
+        let resp = client
+            .post("/echo")
+            .body(Body::from_async_read(std::io::Cursor::new(vec![0u8; 10])))
+            .send()
+            .await;
+        resp.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn slow_handler_gets_408() {
+        let client = TestClient::new(app());
+        let resp = client.get("/slow").send().await;
+        resp.assert_status(StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn slow_write_runs_to_completion() {
+        let client = TestClient::new(app());
+        let resp = client.post("/write").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("done").await;
+    }
+}
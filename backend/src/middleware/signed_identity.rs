@@ -0,0 +1,195 @@
+//! Signed GET requests for reads that return private data (a private script,
+//! the caller's purchase history).
+//!
+//! A GET has no body to carry the usual signed JSON, so the signature travels
+//! in headers: the client signs `{METHOD}:{path_and_query}:{timestamp}` with
+//! one of its account keys and sends it as `X-Signature`, alongside
+//! `X-Public-Key` and `X-Timestamp` (unix seconds). Handlers declare
+//! [`SignedIdentity`] when a signature is required, or
+//! [`OptionalSignedIdentity`] when anonymous callers are served too (a
+//! signature that is sent must still verify).
+//!
+//! There is no nonce: a read changes nothing, so replaying one inside the
+//! timestamp window only returns the same data to whoever captured it.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use poem::{
+    http::{StatusCode, Uri},
+    FromRequest, Request, RequestBody,
+};
+
+use crate::{
+    account_settings::SCHEME_NOT_ALLOWED, auth, models::AppState,
+    repositories::AccountSettingsRepository, responses::error_response,
+};
+
+pub const PUBLIC_KEY_HEADER: &str = "X-Public-Key";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+
+/// How far `X-Timestamp` may be from the server clock, in seconds. Same
+/// window as the signed POSTs.
+pub const SIGNED_READ_WINDOW_SECS: i64 = 300;
+
+/// The exact string a client signs for a GET of `path_and_query`.
+pub fn signed_read_message(method: &str, path_and_query: &str, timestamp: i64) -> String {
+    format!("{method}:{path_and_query}:{timestamp}")
+}
+
+/// The account whose active key signed the request.
+#[derive(Debug, Clone)]
+pub struct SignedIdentity {
+    pub account_id: String,
+    pub public_key: String,
+}
+
+/// `Some` when the request was signed, `None` when it carries no
+/// `X-Signature` at all.
+#[derive(Debug, Clone)]
+pub struct OptionalSignedIdentity(pub Option<SignedIdentity>);
+
+impl OptionalSignedIdentity {
+    pub fn account_id(&self) -> Option<&str> {
+        self.0.as_ref().map(|identity| identity.account_id.as_str())
+    }
+}
+
+fn header<'r>(req: &'r Request, name: &str) -> Option<&'r str> {
+    req.headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+fn reject(status: StatusCode, message: &str) -> poem::Error {
+    poem::Error::from_response(error_response(status, message))
+}
+
+/// Whether `timestamp` is within [`SIGNED_READ_WINDOW_SECS`] of `now`.
+fn is_fresh(timestamp: i64, now: i64) -> bool {
+    (now - timestamp).abs() <= SIGNED_READ_WINDOW_SECS
+}
+
+async fn verify(req: &Request) -> poem::Result<Option<SignedIdentity>> {
+    let Some(signature) = header(req, SIGNATURE_HEADER) else {
+        return Ok(None);
+    };
+    let public_key = header(req, PUBLIC_KEY_HEADER)
+        .ok_or_else(|| reject(StatusCode::UNAUTHORIZED, "Missing X-Public-Key header"))?;
+    let timestamp = header(req, TIMESTAMP_HEADER)
+        .ok_or_else(|| reject(StatusCode::UNAUTHORIZED, "Missing X-Timestamp header"))?
+        .parse::<i64>()
+        .map_err(|_| reject(StatusCode::BAD_REQUEST, "Invalid X-Timestamp header"))?;
+    if !is_fresh(timestamp, Utc::now().timestamp()) {
+        return Err(reject(StatusCode::UNAUTHORIZED, "Signed request expired"));
+    }
+
+    let state = req.data::<Arc<AppState>>().ok_or_else(|| {
+        tracing::error!("Signed read: AppState missing from request data");
+        reject(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to verify request",
+        )
+    })?;
+    let key = match state
+        .script_service
+        .account_repo
+        .find_public_key_by_value(public_key)
+        .await
+    {
+        Ok(Some(key)) if key.is_active => key,
+        Ok(Some(_)) => {
+            tracing::warn!("Signed read rejected: public key is disabled");
+            return Err(reject(StatusCode::UNAUTHORIZED, "Public key is disabled"));
+        }
+        Ok(None) => {
+            tracing::warn!("Signed read rejected: public key not bound to any account");
+            return Err(reject(StatusCode::UNAUTHORIZED, "Unknown public key"));
+        }
+        Err(e) => {
+            tracing::error!("Signed read: key lookup failed: {}", e);
+            return Err(reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to resolve account",
+            ));
+        }
+    };
+
+    // The URI the server received: behind `nest` the routed one has lost its
+    // prefix. Requests built in-process (poem's `TestClient`) carry none.
+    let uri = match req.original_uri() {
+        uri if *uri == Uri::default() => req.uri(),
+        uri => uri,
+    };
+    let path_and_query = uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or_else(|| uri.path());
+    let message = signed_read_message(req.method().as_str(), path_and_query, timestamp);
+    if let Err(e) = auth::verify_signature(signature, message.as_bytes(), public_key) {
+        tracing::warn!(
+            account_id = %key.account_id,
+            "Signed read rejected: verification failed: {}",
+            e
+        );
+        return Err(reject(StatusCode::UNAUTHORIZED, "Invalid signature"));
+    }
+    let scheme = AccountSettingsRepository::new(state.pool.clone())
+        .required_scheme(&key.account_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Signed read: settings lookup failed: {}", e);
+            reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to resolve account",
+            )
+        })?;
+    if !scheme.allows(public_key) {
+        return Err(reject(StatusCode::FORBIDDEN, SCHEME_NOT_ALLOWED));
+    }
+
+    Ok(Some(SignedIdentity {
+        account_id: key.account_id,
+        public_key: key.public_key,
+    }))
+}
+
+impl<'a> FromRequest<'a> for SignedIdentity {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
+        verify(req)
+            .await?
+            .ok_or_else(|| reject(StatusCode::UNAUTHORIZED, "Signed request required"))
+    }
+}
+
+impl<'a> FromRequest<'a> for OptionalSignedIdentity {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> poem::Result<Self> {
+        verify(req).await.map(OptionalSignedIdentity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_covers_method_path_query_and_timestamp() {
+        assert_eq!(
+            signed_read_message("GET", "/api/v1/purchases?limit=5", 1_700_000_000),
+            "GET:/api/v1/purchases?limit=5:1700000000"
+        );
+    }
+
+    #[test]
+    fn timestamps_outside_the_window_are_stale() {
+        let now = 1_700_000_000;
+        assert!(is_fresh(now, now));
+        assert!(is_fresh(now - SIGNED_READ_WINDOW_SECS, now));
+        assert!(is_fresh(now + SIGNED_READ_WINDOW_SECS, now));
+        assert!(!is_fresh(now - SIGNED_READ_WINDOW_SECS - 1, now));
+        assert!(!is_fresh(now + SIGNED_READ_WINDOW_SECS + 1, now));
+    }
+}
@@ -8,9 +8,14 @@ pub struct Script {
     pub owner_account_id: Option<String>,
     pub title: String,
     pub description: String,
+    /// Long-form markdown documentation (see [`crate::readme`]).
+    pub readme: Option<String>,
     pub category: String,
     pub tags: Option<String>,
     pub bundle: String,
+    /// Key of the bundle in `script_blobs`; `None` for a row written before
+    /// blobs existed (see [`crate::content_store`]).
+    pub bundle_sha256: Option<String>,
     pub author_principal: Option<String>,
     pub author_public_key: Option<String>,
     pub upload_signature: Option<String>,
@@ -19,21 +24,94 @@ pub struct Script {
     pub screenshots: Option<String>,
     pub version: String,
     pub compatibility: Option<String>,
+    /// Legacy whole-token price, derived from `price_e8s`.
     pub price: f64,
+    pub price_e8s: i64,
+    pub currency: String,
     pub is_public: bool,
     pub downloads: i32,
+    /// Deduplicated detail-page views (see `rate_limit::ViewDeduper`).
+    pub views: i32,
     pub rating: f64,
+    /// Bayesian average of the ratings, used to rank scripts (see
+    /// `repositories::weighted_rating`).
+    pub weighted_rating: f64,
     pub review_count: i32,
     pub created_at: String,
     pub updated_at: String,
     pub deleted_at: Option<String>,
+    /// When a draft is scheduled to go public (see `scheduled_publish`).
+    pub publish_at: Option<String>,
     // Author info comes from JOIN with accounts table
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author_name: Option<String>,
 }
 
+/// A non-stable build of a script (see [`crate::release_channel`]).
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ChannelRelease {
+    pub script_id: String,
+    pub channel: String,
+    pub version: String,
+    pub bundle: String,
+    pub bundle_sha256: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// One line of `GET /api/v1/scripts/:id/channels`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelSummary {
+    pub channel: String,
+    pub version: String,
+    pub updated_at: String,
+}
+
+/// A dependency a script declares on another marketplace script: its `slug`
+/// and a semver requirement on its `version` (see
+/// [`crate::script_dependencies`]).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq)]
+pub struct ScriptDependency {
+    pub slug: String,
+    pub version: String,
+}
+
+/// The script a dependency resolved to.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ResolvedDependency {
+    pub id: String,
+    pub slug: String,
+    pub version: String,
+}
+
+/// One declared dependency in `GET /api/v1/scripts/:id/dependencies`, with
+/// its own dependencies beneath it. `resolved` is `None` when no public
+/// script meets the requirement any more.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyNode {
+    pub slug: String,
+    pub requirement: String,
+    pub resolved: Option<ResolvedDependency>,
+    pub dependencies: Vec<DependencyNode>,
+}
+
+/// Response of `GET /api/v1/scripts/:id/dependencies`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyTree {
+    pub id: String,
+    pub slug: String,
+    pub version: String,
+    pub dependencies: Vec<DependencyNode>,
+    /// Every resolved script of the tree once, dependencies before their
+    /// dependents: the order to install them in.
+    pub install: Vec<ResolvedDependency>,
+    /// Requirements anywhere in the tree that nothing meets.
+    pub unresolved: Vec<ScriptDependency>,
+}
+
 /// Browse-list serialization of `&[Script]` that OMITS the heavyweight
-/// `bundle` field from every item (IH-5, UXR-3).
+/// `bundle` and `readme` fields from every item (IH-5, UXR-3).
 ///
 /// The marketplace LIST endpoints (`/scripts`, `/scripts/featured`,
 /// `/scripts/trending`, `/scripts/category/:c`, `/scripts/compatible`,
@@ -47,7 +125,7 @@ pub struct Script {
 /// contract. The gate itself still lives in `GET /scripts/:id` via
 /// `ScriptDetailResponse::entitled` / `::locked`.
 ///
-/// Every non-`bundle` field is preserved verbatim — adding a column to
+/// Every other field is preserved verbatim — adding a column to
 /// `Script` flows through automatically, so this view can never drift from
 /// the model.
 pub fn scripts_to_list_json(scripts: &[Script]) -> serde_json::Value {
@@ -57,12 +135,65 @@ pub fn scripts_to_list_json(scripts: &[Script]) -> serde_json::Value {
         for item in arr.iter_mut() {
             if let Some(obj) = item.as_object_mut() {
                 obj.remove("bundle");
+                obj.remove("readme");
             }
         }
     }
     value
 }
 
+/// Adds `compatibility_score` and `compatibility_votes` to every item of a
+/// [`scripts_to_list_json`] list, from `summaries` (keyed by script id);
+/// a script without votes gets `null` and `0`.
+pub fn attach_compatibility(
+    list: &mut serde_json::Value,
+    summaries: &std::collections::HashMap<String, crate::services::CompatibilitySummary>,
+) {
+    let Some(arr) = list.as_array_mut() else {
+        return;
+    };
+    for obj in arr.iter_mut().filter_map(|item| item.as_object_mut()) {
+        let summary = obj
+            .get("id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| summaries.get(id));
+        obj.insert(
+            "compatibility_score".to_string(),
+            summary.and_then(|s| s.score).into(),
+        );
+        obj.insert(
+            "compatibility_votes".to_string(),
+            summary.map_or(0, |s| s.votes).into(),
+        );
+    }
+}
+
+/// Adds `discounted_price_e8s` and `promotion_ends_at` to every item of a
+/// [`scripts_to_list_json`] list: the values from `offers` (keyed by script
+/// id) when a promotion is running, `null` otherwise.
+pub fn attach_offers(
+    list: &mut serde_json::Value,
+    offers: &std::collections::HashMap<String, crate::services::Offer>,
+) {
+    let Some(arr) = list.as_array_mut() else {
+        return;
+    };
+    for obj in arr.iter_mut().filter_map(|item| item.as_object_mut()) {
+        let offer = obj
+            .get("id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| offers.get(id));
+        obj.insert(
+            "discounted_price_e8s".to_string(),
+            offer.map(|o| o.discounted_price_e8s).into(),
+        );
+        obj.insert(
+            "promotion_ends_at".to_string(),
+            offer.map(|o| o.promotion.ends_at.clone()).into(),
+        );
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct Review {
@@ -75,6 +206,208 @@ pub struct Review {
     pub updated_at: String,
 }
 
+/// A review as the API returns it: the stored fields plus `commentHtml`, the
+/// comment rendered by [`crate::markdown`] (`null` without a comment).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewResponse {
+    #[serde(flatten)]
+    pub review: Review,
+    pub comment_html: Option<String>,
+}
+
+impl From<Review> for ReviewResponse {
+    fn from(review: Review) -> Self {
+        let comment_html = review
+            .comment
+            .as_deref()
+            .map(|comment| crate::markdown::render(comment, crate::markdown::Profile::Comment));
+        Self {
+            review,
+            comment_html,
+        }
+    }
+}
+
+/// A question about a script and, once the owner has replied, its answer.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptQuestion {
+    pub id: String,
+    pub script_id: String,
+    pub account_id: String,
+    pub question: String,
+    pub answer: Option<String>,
+    pub created_at: String,
+    pub answered_at: Option<String>,
+}
+
+/// A question as the API returns it: the stored fields plus `questionHtml`
+/// and `answerHtml`, rendered like review comments.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionResponse {
+    #[serde(flatten)]
+    pub question: ScriptQuestion,
+    pub question_html: String,
+    pub answer_html: Option<String>,
+}
+
+impl From<ScriptQuestion> for QuestionResponse {
+    fn from(question: ScriptQuestion) -> Self {
+        let render = |text: &str| crate::markdown::render(text, crate::markdown::Profile::Comment);
+        Self {
+            question_html: render(&question.question),
+            answer_html: question.answer.as_deref().map(render),
+            question,
+        }
+    }
+}
+
+/// One of the caller's scripts with questions still waiting for an answer.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UnansweredQuestions {
+    pub script_id: String,
+    pub title: String,
+    pub count: i64,
+}
+
+/// Install and run telemetry of one version of a script, summed over engine
+/// versions, as shown to its author.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionReliability {
+    pub version: String,
+    pub installs: i64,
+    pub install_failures: i64,
+    pub runs: i64,
+    pub run_failures: i64,
+    /// Failures of either event by error class.
+    pub error_classes: std::collections::BTreeMap<String, i64>,
+    /// See [`crate::telemetry::reliability_score`]; installs and runs count
+    /// alike.
+    pub reliability_score: Option<f64>,
+}
+
+/// Votes for one app version or canister.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityTally {
+    /// The app version or canister id voted on.
+    pub target: String,
+    pub works: i64,
+    pub broken: i64,
+    pub score: Option<f64>,
+}
+
+/// `GET /api/v1/scripts/:id/compatibility`: every vote on the script, then
+/// the same votes per app version (newest first) and per canister (most
+/// voted first). Votes about the app version alone have no canister.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityBreakdown {
+    pub works: i64,
+    pub broken: i64,
+    /// Lower bound of the 95% Wilson interval of the "works" share.
+    pub score: Option<f64>,
+    pub by_app_version: Vec<CompatibilityTally>,
+    pub by_canister: Vec<CompatibilityTally>,
+}
+
+/// One distinct failure of a script version, as listed to its owner.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptErrorReport {
+    pub version: String,
+    pub stack_hash: String,
+    pub error_class: String,
+    /// The message of the first report with this stack.
+    pub message: String,
+    /// One frame per line, innermost first.
+    pub stack: String,
+    /// Engine version of the latest report.
+    pub engine_version: String,
+    pub occurrences: i64,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+}
+
+/// A script version flagged for a spike in its run failure rate.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionFlag {
+    pub version: String,
+    pub reason: String,
+    pub flagged_at: String,
+}
+
+/// The latest re-validation of a public script (see `crate::revalidation`).
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptValidation {
+    pub script_id: String,
+    /// The version that was checked.
+    pub version: String,
+    pub rules_version: i64,
+    pub valid: bool,
+    pub errors: Vec<String>,
+    /// When the script started failing; `None` while it passes.
+    pub failing_since: Option<String>,
+    pub checked_at: String,
+}
+
+/// What `GET /api/v1/scripts/trending` ranks by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendingSignal {
+    #[default]
+    Downloads,
+    Views,
+}
+
+impl TrendingSignal {
+    pub const ALL: [TrendingSignal; 2] = [TrendingSignal::Downloads, TrendingSignal::Views];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Downloads => "downloads",
+            Self::Views => "views",
+        }
+    }
+}
+
+/// A per-category or per-tag trending list; see [`crate::trending`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendingScope<'a> {
+    Category(&'a str),
+    Tag(&'a str),
+}
+
+impl TrendingScope<'_> {
+    /// `trending_rollups.scope`
+    pub fn kind(self) -> &'static str {
+        match self {
+            Self::Category(_) => "category",
+            Self::Tag(_) => "tag",
+        }
+    }
+
+    /// `trending_rollups.scope_key`
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Category(key) | Self::Tag(key) => key,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendingQuery {
+    pub by: Option<TrendingSignal>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ScriptsQuery {
     pub limit: Option<i32>,
@@ -90,6 +423,8 @@ pub struct CreateScriptRequest {
     pub slug: String,
     pub title: String,
     pub description: String,
+    /// Markdown documentation; signed when present.
+    pub readme: Option<String>,
     pub category: String,
     pub bundle: String,
     pub author_principal: Option<String>,
@@ -99,10 +434,17 @@ pub struct CreateScriptRequest {
     pub timestamp: Option<String>,
     pub version: Option<String>,
     pub price: Option<f64>,
+    /// Price in e8s of `currency`; requires `payload_version` 2 (see
+    /// `crate::pricing`).
+    pub price_e8s: Option<i64>,
+    pub currency: Option<String>,
+    pub payload_version: Option<u8>,
     pub is_public: Option<bool>,
     pub compatibility: Option<String>,
     pub tags: Option<Vec<String>>,
     pub action: Option<String>,
+    /// Scripts this one needs; must all resolve before it can be public.
+    pub dependencies: Option<Vec<ScriptDependency>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -110,10 +452,15 @@ pub struct CreateScriptRequest {
 pub struct UpdateScriptRequest {
     pub title: Option<String>,
     pub description: Option<String>,
+    /// Replaces the readme; `""` removes it.
+    pub readme: Option<String>,
     pub category: Option<String>,
     pub bundle: Option<String>,
     pub version: Option<String>,
     pub price: Option<f64>,
+    pub price_e8s: Option<i64>,
+    pub currency: Option<String>,
+    pub payload_version: Option<u8>,
     pub is_public: Option<bool>,
     pub tags: Option<Vec<String>>,
     pub signature: Option<String>,
@@ -122,6 +469,14 @@ pub struct UpdateScriptRequest {
     pub author_principal: Option<String>,
     pub author_public_key: Option<String>,
     pub action: Option<String>,
+    /// RFC 3339 time at which a draft goes public by itself; `""` cancels
+    /// the schedule. Signed like the other fields.
+    pub publish_at: Option<String>,
+    /// Release channel the pushed `bundle` and `version` go to; absent means
+    /// `stable`. A `beta` push carries nothing else.
+    pub channel: Option<String>,
+    /// Replaces the declared dependencies; `[]` removes them all.
+    pub dependencies: Option<Vec<ScriptDependency>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -134,6 +489,28 @@ pub struct DeleteScriptRequest {
     pub timestamp: Option<String>,
 }
 
+/// Operations `POST /api/dev/canonical-payload` builds payloads for. `raw`
+/// canonicalizes `request` as given, for the account-scoped actions whose
+/// payload the client assembles itself.
+pub const CANONICAL_PAYLOAD_OPERATIONS: &[&str] = &["upload", "update", "publish", "delete", "raw"];
+
+/// Body of `POST /api/dev/canonical-payload` (development only).
+#[derive(Debug, Deserialize)]
+pub struct CanonicalPayloadRequest {
+    /// One of [`CANONICAL_PAYLOAD_OPERATIONS`].
+    pub operation: String,
+    /// The path id of `update`, `publish` and `delete`.
+    pub script_id: Option<String>,
+    /// The request body as the client would send it, or the payload itself
+    /// for `raw`.
+    pub request: serde_json::Value,
+    /// Checked against the canonical payload when given with
+    /// `author_public_key`; defaults to the fields of the same name in
+    /// `request`.
+    pub signature: Option<String>,
+    pub author_public_key: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct SearchRequest {
     #[serde(rename = "query")]
@@ -151,6 +528,10 @@ pub struct SearchRequest {
     pub sort_order: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// Random per-install id that places the client in a ranking experiment
+    /// variant; see [`crate::search_experiments`].
+    #[serde(rename = "clientId")]
+    pub client_id: Option<String>,
 }
 
 #[derive(Debug)]
@@ -167,6 +548,24 @@ pub struct CreateReviewRequest {
     pub user_id: String,
     pub rating: i32,
     pub comment: Option<String>,
+    /// Key that signed the request, for the per-key burst check. Set by the
+    /// handler, never read from the body.
+    #[serde(skip)]
+    pub public_key: Option<String>,
+}
+
+/// A review held back by the spam heuristics, as listed for moderators.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedReview {
+    pub id: String,
+    pub script_id: String,
+    pub user_id: String,
+    pub rating: i32,
+    pub comment: Option<String>,
+    pub created_at: String,
+    pub quarantined_at: String,
+    pub quarantine_reason: String,
 }
 
 pub struct AppState {
@@ -175,9 +574,24 @@ pub struct AppState {
     pub script_service: crate::services::ScriptService,
     pub review_service: crate::services::ReviewService,
     pub passkey_service: crate::services::PasskeyService,
+    pub webhook_service: crate::services::WebhookService,
+    pub promotion_service: crate::services::PromotionService,
+    pub dispute_service: crate::services::DisputeService,
+    pub bundle_service: crate::services::BundleService,
+    pub entitlement_service: crate::services::EntitlementService,
+    pub maintenance_service: crate::services::MaintenanceService,
+    pub question_service: crate::services::QuestionService,
+    pub telemetry_service: crate::services::TelemetryService,
+    pub error_report_service: crate::services::ErrorReportService,
+    pub compatibility_service: crate::services::CompatibilityService,
+    pub moderation_service: crate::services::ModerationService,
+    pub revalidation_service: crate::services::RevalidationService,
+    pub integrity_service: crate::services::IntegrityService,
     /// Sliding-window throttle for the open `POST /recovery/verify` brute-force
     /// oracle (W7-14). Shared across all requests (process-local).
     pub recovery_rate_limiter: std::sync::Arc<crate::rate_limit::SlidingWindowRateLimiter>,
+    /// Mints and checks the tokens on `/api/v1/scripts/:id/source` URLs.
+    pub source_urls: crate::signed_urls::SourceUrlSigner,
 }
 
 #[derive(Debug, Deserialize)]
@@ -186,7 +600,24 @@ pub struct ReviewsQuery {
     pub offset: Option<i32>,
 }
 
-pub const SCRIPT_COLUMNS_WITH_ACCOUNT: &str = "scripts.id, scripts.slug, scripts.owner_account_id, scripts.title, scripts.description, scripts.category, scripts.tags, scripts.bundle, scripts.author_principal, scripts.author_public_key, scripts.upload_signature, scripts.canister_ids, scripts.icon_url, scripts.screenshots, scripts.version, scripts.compatibility, scripts.price, scripts.is_public, scripts.downloads, scripts.rating, scripts.review_count, scripts.created_at, scripts.updated_at, scripts.deleted_at, accounts.display_name as author_name";
+#[derive(Debug, Deserialize)]
+pub struct QuestionsQuery {
+    pub limit: Option<i32>,
+    pub offset: Option<i32>,
+    /// Only the questions still waiting for an answer.
+    #[serde(default)]
+    pub unanswered: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorReportsQuery {
+    pub limit: Option<i32>,
+    pub offset: Option<i32>,
+    /// Only the reports of this version.
+    pub version: Option<String>,
+}
+
+pub const SCRIPT_COLUMNS_WITH_ACCOUNT: &str = "scripts.id, scripts.slug, scripts.owner_account_id, scripts.title, scripts.description, scripts.readme, scripts.category, scripts.tags, CASE WHEN scripts.bundle_sha256 IS NULL THEN scripts.bundle ELSE (SELECT script_blobs.content FROM script_blobs WHERE script_blobs.sha256 = scripts.bundle_sha256) END as bundle, scripts.bundle_sha256, scripts.author_principal, scripts.author_public_key, scripts.upload_signature, scripts.canister_ids, scripts.icon_url, scripts.screenshots, scripts.version, scripts.compatibility, scripts.price, scripts.price_e8s, scripts.currency, scripts.is_public, scripts.downloads, scripts.views, scripts.rating, scripts.weighted_rating, scripts.review_count, scripts.created_at, scripts.updated_at, scripts.deleted_at, scripts.publish_at, accounts.display_name as author_name";
 
 /// Lightweight browse-time preview of a script (UX-6).
 ///
@@ -203,6 +634,8 @@ pub struct ScriptPreview {
     pub description: String,
     pub version: String,
     pub price: f64,
+    pub price_e8s: i64,
+    pub currency: String,
     /// Source language of the bundle. The runtime is TypeScript-on-QuickJS for
     /// every script (see AGENTS.md), so this is currently always "typescript".
     pub language: String,
@@ -216,6 +649,126 @@ pub struct ScriptPreview {
     pub total_lines: usize,
 }
 
+/// One column of `GET /api/v1/scripts/compare`: the fields a side-by-side
+/// view lines up, normalised across scripts.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptComparison {
+    pub id: String,
+    pub title: String,
+    pub author_name: Option<String>,
+    pub version: String,
+    pub language: String,
+    pub rating: f64,
+    pub weighted_rating: f64,
+    pub review_count: i32,
+    pub downloads: i32,
+    pub views: i32,
+    pub price_e8s: i64,
+    pub currency: String,
+    pub compatibility: Option<String>,
+    /// Declared `canister_ids` plus canister ids written in the bundle.
+    pub required_canisters: Vec<String>,
+    /// See [`crate::script_permissions::Permission`].
+    pub permissions: Vec<&'static str>,
+    pub updated_at: String,
+}
+
+impl ScriptComparison {
+    pub fn from_script(script: &Script) -> Self {
+        let mut required_canisters =
+            crate::script_permissions::referenced_canisters(&script.bundle);
+        if let Some(declared) = script.canister_ids.as_deref() {
+            // Stored as a JSON array; older rows hold a comma-separated list.
+            let declared: Vec<String> = serde_json::from_str(declared).unwrap_or_else(|_| {
+                declared
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            });
+            required_canisters.extend(declared);
+            required_canisters.sort();
+            required_canisters.dedup();
+        }
+        Self {
+            id: script.id.clone(),
+            title: script.title.clone(),
+            author_name: script.author_name.clone(),
+            version: script.version.clone(),
+            language: crate::script_language::ScriptLanguage::detect(&script.bundle)
+                .as_str()
+                .to_string(),
+            rating: script.rating,
+            weighted_rating: script.weighted_rating,
+            review_count: script.review_count,
+            downloads: script.downloads,
+            views: script.views,
+            price_e8s: script.price_e8s,
+            currency: script.currency.clone(),
+            compatibility: script.compatibility.clone(),
+            required_canisters,
+            permissions: crate::script_permissions::Permission::detect(&script.bundle)
+                .iter()
+                .map(|p| p.as_str())
+                .collect(),
+            updated_at: script.updated_at.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareQuery {
+    /// Comma-separated script ids.
+    pub ids: String,
+}
+
+/// `?channel=` of the script detail and download endpoints.
+#[derive(Debug, Deserialize, Default)]
+pub struct ChannelQuery {
+    pub channel: Option<String>,
+}
+
+impl ChannelQuery {
+    /// The requested channel, `stable` when absent; `Err` for unknown names.
+    pub fn resolve(&self) -> Result<crate::release_channel::ReleaseChannel, String> {
+        match self.channel.as_deref() {
+            None | Some("") => Ok(crate::release_channel::ReleaseChannel::Stable),
+            Some(channel) => crate::release_channel::ReleaseChannel::parse(channel)
+                .ok_or_else(|| format!("Unknown release channel '{channel}'")),
+        }
+    }
+}
+
+/// `?locale=` of the stats endpoints (see [`crate::locale_format`]).
+#[derive(Debug, Deserialize, Default)]
+pub struct StatsQuery {
+    pub locale: Option<String>,
+}
+
+impl StatsQuery {
+    /// The rules for the requested locale, `None` when absent; `Err` for
+    /// unsupported tags.
+    pub fn resolve(&self) -> Result<Option<&'static crate::locale_format::LocaleRules>, String> {
+        match self.locale.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(tag) => crate::locale_format::resolve(tag).map(Some).ok_or_else(|| {
+                format!(
+                    "Unsupported locale '{tag}' (supported: {})",
+                    crate::locale_format::supported_tags().join(", ")
+                )
+            }),
+        }
+    }
+}
+
+/// `?format=` of `GET /api/v1/scripts/:id/embed` (see [`crate::script_embed`]).
+#[derive(Debug, Deserialize, Default)]
+pub struct EmbedQuery {
+    pub format: Option<String>,
+}
+
 // ============================================================================
 // Download (all scripts are free — entitlement gate removed)
 // ============================================================================
@@ -224,6 +777,8 @@ pub struct ScriptPreview {
 /// Ed25519 over the canonical string `download:{script_id}:{timestamp}:{nonce}`
 /// (built in `entitlement::resolve_download`), verified with
 /// `auth::verify_ed25519_signature`. Field names are snake_case on the wire.
+/// `POST /api/v1/scripts/:id/source-url` takes the same body, signed over
+/// `source-url:{script_id}:{timestamp}:{nonce}`.
 #[derive(Debug, Deserialize)]
 pub struct DownloadRequest {
     pub public_key: String,
@@ -245,6 +800,7 @@ pub struct ScriptDetailResponse {
     pub owner_account_id: Option<String>,
     pub title: String,
     pub description: String,
+    pub readme: Option<String>,
     pub category: String,
     pub tags: Option<String>,
     pub bundle: String,
@@ -261,14 +817,27 @@ pub struct ScriptDetailResponse {
     pub version: String,
     pub compatibility: Option<String>,
     pub price: f64,
+    pub price_e8s: i64,
+    pub currency: String,
+    /// Price under the promotion running now, if any; `price_e8s` stays the
+    /// original price.
+    pub discounted_price_e8s: Option<i64>,
+    pub promotion_ends_at: Option<String>,
     pub is_public: bool,
     pub downloads: i32,
+    pub views: i32,
     pub rating: f64,
     pub review_count: i32,
     pub created_at: String,
     pub updated_at: String,
     pub deleted_at: Option<String>,
+    pub publish_at: Option<String>,
     pub author_name: Option<String>,
+    /// Questions about the script the owner has not answered yet.
+    pub unanswered_questions: i64,
+    /// Channel whose build `bundle` and `version` are: the requested one, or
+    /// `stable` when the script has no build on it.
+    pub channel: crate::release_channel::ReleaseChannel,
 }
 
 impl ScriptDetailResponse {
@@ -284,6 +853,7 @@ impl ScriptDetailResponse {
             owner_account_id: script.owner_account_id,
             title: script.title,
             description: script.description,
+            readme: script.readme,
             category: script.category,
             tags: script.tags,
             bundle: script.bundle,
@@ -297,15 +867,36 @@ impl ScriptDetailResponse {
             version: script.version,
             compatibility: script.compatibility,
             price: script.price,
+            price_e8s: script.price_e8s,
+            currency: script.currency,
+            discounted_price_e8s: None,
+            promotion_ends_at: None,
             is_public: script.is_public,
             downloads: script.downloads,
+            views: script.views,
             rating: script.rating,
             review_count: script.review_count,
             created_at: script.created_at,
             updated_at: script.updated_at,
             deleted_at: script.deleted_at,
+            publish_at: script.publish_at,
             author_name: script.author_name,
+            unanswered_questions: 0,
+            channel: crate::release_channel::ReleaseChannel::Stable,
+        }
+    }
+
+    /// Swaps in a channel build for the stable bundle and version.
+    pub fn with_release(mut self, release: ChannelRelease) -> Self {
+        self.language = crate::script_language::ScriptLanguage::detect(&release.bundle)
+            .as_str()
+            .to_string();
+        self.bundle = release.bundle;
+        self.version = release.version;
+        if let Some(channel) = crate::release_channel::ReleaseChannel::parse(&release.channel) {
+            self.channel = channel;
         }
+        self
     }
 }
 
@@ -322,6 +913,8 @@ pub struct Account {
     pub contact_discord: Option<String>,
     pub website_url: Option<String>,
     pub bio: Option<String>,
+    /// Plan tier, see [`crate::quotas::AccountTier`].
+    pub tier: String,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -405,6 +998,101 @@ pub struct UpdateAccountRequest {
     pub signature: String,
 }
 
+/// An entry of the key revocation list. `sequence` grows with every
+/// revocation and is never reused.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRevocation {
+    pub sequence: i64,
+    pub key_id: String,
+    pub account_id: String,
+    pub public_key: String,
+    pub ic_principal: String,
+    pub revoked_at: String,
+}
+
+/// A page of the global revocation list after `since`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRevocationPage {
+    pub revocations: Vec<KeyRevocation>,
+    /// Highest sequence handed out so far; pass the last entry's sequence as
+    /// the next `since` until it is reached.
+    pub latest_sequence: i64,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeyRevocationsQuery {
+    pub since: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// A public action logged on an account's activity feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    /// A script went public for the first time.
+    ScriptPublished,
+    /// A public script got a new version.
+    VersionReleased,
+    /// The account reviewed a script.
+    ReviewPosted,
+}
+
+impl ActivityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ScriptPublished => "script_published",
+            Self::VersionReleased => "version_released",
+            Self::ReviewPosted => "review_posted",
+        }
+    }
+}
+
+/// One entry of an activity feed. `version` is set for publications and
+/// releases; `reviewId`, `rating` and `comment` for reviews.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    pub id: i64,
+    pub account_id: String,
+    pub kind: String,
+    pub script_id: String,
+    pub script_slug: String,
+    pub script_title: String,
+    pub version: Option<String>,
+    pub review_id: Option<String>,
+    pub rating: Option<i32>,
+    pub comment: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPage {
+    pub entries: Vec<ActivityEntry>,
+    pub has_more: bool,
+}
+
+/// `GET /api/v1/feed`: the followed authors' activity, plus the caller's own
+/// scripts with unanswered questions.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedPage {
+    #[serde(flatten)]
+    pub activity: ActivityPage,
+    pub unanswered_questions: Vec<UnansweredQuestions>,
+}
+
+/// Where the caller stands with an author after a follow or unfollow.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowStatus {
+    pub username: String,
+    pub following: bool,
+    pub followers: i64,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountPublicKeyResponse {
@@ -437,10 +1125,15 @@ pub struct AccountResponse {
     pub website_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bio: Option<String>,
+    /// `bio` rendered by [`crate::markdown`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bio_html: Option<String>,
     pub created_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<String>,
     pub public_keys: Vec<AccountPublicKeyResponse>,
+    pub tier: crate::quotas::AccountTier,
+    pub quota: crate::quotas::QuotaUsage,
 }
 
 // Admin operation request models
@@ -457,28 +1150,459 @@ pub struct AdminAddRecoveryKeyRequest {
     pub reason: String,
 }
 
-#[derive(Debug, Serialize)]
+/// Body of the admin shadow-ban endpoint; `banned: false` lifts the ban.
+#[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AdminKeyResponse {
-    pub id: String,
-    pub public_key: String,
-    pub ic_principal: String,
-    pub is_active: bool,
-    pub disabled_at: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub disabled_by_admin: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub added_by_admin: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub added_at: Option<String>,
+pub struct AdminShadowBanRequest {
+    pub banned: bool,
+    pub reason: String,
 }
 
-// Implement AuthenticatedRequest trait for request types
-use crate::middleware::AuthenticatedRequest;
+/// Body of `POST /api/v1/admin/accounts/:username/tier`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminSetTierRequest {
+    pub tier: crate::quotas::AccountTier,
+    pub reason: String,
+}
 
-impl AuthenticatedRequest for CreateScriptRequest {
-    fn signature(&self) -> Option<&str> {
-        self.signature.as_deref()
+/// Body of `POST /api/v1/admin/accounts/:username/notes`.
+#[derive(Debug, Deserialize)]
+pub struct AdminModerationNoteRequest {
+    pub note: String,
+}
+
+/// Body of `POST /api/v1/admin/accounts/:username/strikes` and of
+/// `POST /api/v1/admin/accounts/:username/strikes/:strike_id/revoke`.
+#[derive(Debug, Deserialize)]
+pub struct AdminStrikeRequest {
+    pub reason: String,
+}
+
+/// A moderator's note on an account.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationNote {
+    pub id: String,
+    pub note: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStrike {
+    pub id: String,
+    pub reason: String,
+    pub created_at: String,
+    /// Set once an admin revoked the strike; it no longer counts.
+    pub revoked_at: Option<String>,
+    pub revoke_reason: Option<String>,
+}
+
+/// An account's moderation record, returned by every moderation endpoint.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationStatus {
+    pub account_id: String,
+    pub username: String,
+    pub active_strikes: i64,
+    pub restriction: crate::moderation::Restriction,
+    pub strikes: Vec<AccountStrike>,
+    pub notes: Vec<ModerationNote>,
+}
+
+/// An account's preferences; see [`crate::account_settings`].
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSettings {
+    pub default_visibility: crate::account_settings::DefaultVisibility,
+    pub required_signature_scheme: crate::account_settings::SignatureScheme,
+    pub muted_events: Vec<String>,
+    /// `None` while the account has the defaults.
+    pub updated_at: Option<String>,
+}
+
+/// The fields `PUT /api/v1/account-settings` changes; absent ones are kept.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccountSettingsUpdate {
+    pub default_visibility: Option<crate::account_settings::DefaultVisibility>,
+    pub required_signature_scheme: Option<crate::account_settings::SignatureScheme>,
+    pub muted_events: Option<Vec<String>>,
+}
+
+/// Body of `POST /api/v1/admin/reviews/:id/moderate`: `approve` publishes
+/// the quarantined review, otherwise it is deleted.
+#[derive(Debug, Deserialize)]
+pub struct AdminModerateReviewRequest {
+    pub approve: bool,
+    pub reason: String,
+}
+
+/// Body of `POST /api/v1/admin/categories/merge`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminMergeCategoriesRequest {
+    pub from: Vec<String>,
+    pub into: String,
+    pub reason: String,
+}
+
+/// The read-only maintenance switch, as stored and as served by
+/// `GET /api/v1/health` and `GET /api/v1/admin/maintenance`.
+#[derive(Debug, Clone, Serialize, FromRow, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceState {
+    pub enabled: bool,
+    pub message: Option<String>,
+    /// Sent as `Retry-After` on rejected writes.
+    pub retry_after_secs: i64,
+    pub updated_at: Option<String>,
+}
+
+/// Body of `PUT /api/v1/admin/maintenance`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminMaintenanceRequest {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub retry_after_secs: Option<i64>,
+    pub reason: String,
+}
+
+/// Body of `POST /api/v1/admin/tags/rename`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRenameTagRequest {
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminTaxonomyResponse {
+    pub scripts_updated: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminShadowBanResponse {
+    pub account_id: String,
+    pub username: String,
+    pub shadow_banned_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminKeyResponse {
+    pub id: String,
+    pub public_key: String,
+    pub ic_principal: String,
+    pub is_active: bool,
+    pub disabled_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_by_admin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added_by_admin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added_at: Option<String>,
+}
+
+/// One signed (or admin) operation from `signature_audit`, without the
+/// payload/signature bodies.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOperation {
+    pub id: String,
+    pub action: String,
+    pub public_key: String,
+    pub is_admin_action: bool,
+    pub created_at: String,
+}
+
+/// A signed request from one of the account's keys that the signature gate
+/// rejected. `reason` is the detail the caller was not told.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountFailure {
+    pub id: String,
+    pub action: String,
+    pub status: i64,
+    pub reason: String,
+    pub created_at: String,
+}
+
+/// Script metadata row for support views; includes private and soft-deleted
+/// scripts, never the bundle.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountScriptSummary {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub version: String,
+    pub is_public: bool,
+    pub downloads: i32,
+    pub created_at: String,
+    pub updated_at: String,
+    pub deleted_at: Option<String>,
+}
+
+/// `GET /api/v1/admin/accounts/:username/overview`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccountOverview {
+    pub account: AccountResponse,
+    pub scripts: Vec<AccountScriptSummary>,
+    pub recent_operations: Vec<AccountOperation>,
+    pub recent_failures: Vec<AccountFailure>,
+}
+
+/// One row of `GET /api/v1/admin/search-analytics`.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQueryStat {
+    pub query: String,
+    pub searches: i64,
+    pub avg_results: i64,
+    pub last_searched_at: String,
+}
+
+/// `GET /api/v1/admin/search-analytics`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchAnalytics {
+    pub since: String,
+    pub total_searches: i64,
+    pub zero_result_searches: i64,
+    pub top_queries: Vec<SearchQueryStat>,
+    pub top_zero_result_queries: Vec<SearchQueryStat>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchAnalyticsQuery {
+    pub days: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// The exposure a search ranked by an experiment was logged as, returned
+/// with its results; clicks on them are reported against `id`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchExposure {
+    pub id: String,
+    pub experiment: String,
+    pub variant: String,
+}
+
+/// `POST /api/v1/scripts/search/clicks`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchClickRequest {
+    pub exposure_id: String,
+    pub script_id: String,
+}
+
+/// One variant of `GET /api/v1/admin/search-experiments`. `ctr` is the
+/// share of exposures with at least one click.
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RankingVariantStats {
+    pub variant: String,
+    pub exposures: i64,
+    pub clients: i64,
+    pub clicked_exposures: i64,
+    pub clicks: i64,
+    pub ctr: f64,
+    /// Mean 1-based position of the first clicked result.
+    pub mean_first_click_position: Option<f64>,
+}
+
+/// `GET /api/v1/admin/search-experiments`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankingExperimentReport {
+    pub experiment: String,
+    /// Whether this is the experiment searches are ranked by now.
+    pub running: bool,
+    pub since: String,
+    pub variants: Vec<RankingVariantStats>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RankingExperimentQuery {
+    pub experiment: Option<String>,
+    pub days: Option<i64>,
+}
+
+// Data integrity
+
+/// One inconsistency the integrity checker found; see [`crate::integrity`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub kind: crate::integrity::IssueKind,
+    /// The review id for an orphaned review, the script id otherwise.
+    pub id: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityCounts {
+    pub orphaned_reviews: i64,
+    pub orphaned_scripts: i64,
+    pub dangling_tags: i64,
+    pub mismatched_aggregates: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub id: String,
+    /// `scheduled` or `admin`.
+    pub trigger: String,
+    /// Whether the issues were repaired in the same run.
+    pub repaired: bool,
+    pub issue_count: i64,
+    pub counts: IntegrityCounts,
+    /// The first [`crate::integrity::MAX_LISTED_ISSUES`] issues.
+    pub issues: Vec<IntegrityIssue>,
+    pub created_at: String,
+}
+
+/// Body of `POST /api/v1/admin/integrity-check`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminIntegrityCheckRequest {
+    /// Repair what is found; otherwise only report it.
+    #[serde(default)]
+    pub repair: bool,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IntegrityReportsQuery {
+    pub limit: Option<i64>,
+}
+
+// Author stats webhooks
+
+/// An author's registered stats webhook. The signing secret is only ever
+/// returned when it is (re)generated, never on reads.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorWebhook {
+    pub account_id: String,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Per-script numbers carried by webhook payloads.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorScriptStats {
+    pub id: String,
+    pub title: String,
+    pub downloads: i32,
+    pub views: i32,
+    pub rating: f64,
+    pub review_count: i32,
+}
+
+// Promotions
+
+/// A time-boxed discount on one script, set by its owner. `kind` is
+/// `percent` (`amount` = whole percent) or `fixed` (`amount` = e8s of
+/// `currency`). Times are RFC 3339 UTC; the window is `[starts_at, ends_at)`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Promotion {
+    pub id: String,
+    pub script_id: String,
+    pub kind: String,
+    pub amount: i64,
+    pub currency: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub created_at: String,
+}
+
+// Bundle products
+
+/// Several scripts of one author sold together for a single price. Not to be
+/// confused with a script's `bundle`, which is its source code. Members are
+/// kept in `product_bundle_scripts`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductBundle {
+    pub id: String,
+    pub owner_account_id: String,
+    pub title: String,
+    pub description: String,
+    pub price_e8s: i64,
+    pub currency: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Purchases, disputes and refunds
+
+/// A row of the (historical) purchases ledger, with its dispute and refund
+/// state. `status` is `completed`, `disputed` or `refunded`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Purchase {
+    pub id: String,
+    pub account_id: String,
+    pub script_id: String,
+    pub usd_amount: f64,
+    pub currency: String,
+    pub status: String,
+    pub paid_at: String,
+    pub dispute_reason: Option<String>,
+    pub disputed_at: Option<String>,
+    pub refunded_at: Option<String>,
+    pub refund_block_index: Option<i64>,
+    pub refund_amount_e8s: Option<i64>,
+    /// Set when the entitlement came with a bundle product.
+    pub bundle_id: Option<String>,
+}
+
+/// One transition of a purchase's status. `actor` is `account:<id>` for the
+/// purchaser or `admin`.
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseStatusChange {
+    pub id: String,
+    pub purchase_id: String,
+    pub from_status: String,
+    pub to_status: String,
+    pub actor: String,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Body of `POST /api/v1/admin/disputes/:purchase_id/resolve`. A refund
+/// names the ledger block of the transfer back to the buyer; rejecting the
+/// dispute returns the purchase to `completed`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminResolveDisputeRequest {
+    pub refund: bool,
+    pub refund_block_index: Option<u64>,
+    pub reason: String,
+}
+
+// Implement AuthenticatedRequest trait for request types
+use crate::middleware::AuthenticatedRequest;
+
+impl AuthenticatedRequest for CreateScriptRequest {
+    fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
     }
 
     fn author_principal(&self) -> Option<&str> {
@@ -518,6 +1642,311 @@ impl AuthenticatedRequest for DeleteScriptRequest {
     }
 }
 
+// Request body rules enforced by `ValidJson` (see crate::validation)
+use crate::pricing::validate_price_fields;
+use crate::validation::{require_non_empty, require_non_negative, FieldError, Validate};
+
+impl Validate for CreateScriptRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "slug", &self.slug);
+        require_non_empty(&mut errors, "title", &self.title);
+        require_non_empty(&mut errors, "category", &self.category);
+        require_non_empty(&mut errors, "bundle", &self.bundle);
+        require_non_negative(&mut errors, "price", self.price);
+        validate_price_fields(
+            &mut errors,
+            self.payload_version,
+            self.price,
+            self.price_e8s,
+            self.currency.as_deref(),
+        );
+        if let Some(dependencies) = &self.dependencies {
+            validate_dependencies(&mut errors, dependencies, Some(&self.slug));
+        }
+        errors
+    }
+}
+
+impl Validate for UpdateScriptRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        let present = [
+            ("title", &self.title),
+            ("category", &self.category),
+            ("bundle", &self.bundle),
+        ];
+        for (field, value) in present {
+            if let Some(value) = value {
+                require_non_empty(&mut errors, field, value);
+            }
+        }
+        require_non_negative(&mut errors, "price", self.price);
+        validate_price_fields(
+            &mut errors,
+            self.payload_version,
+            self.price,
+            self.price_e8s,
+            self.currency.as_deref(),
+        );
+        if let Some(channel) = self.channel.as_deref() {
+            match crate::release_channel::ReleaseChannel::parse(channel) {
+                None => errors.push(FieldError::new("channel", "must be stable or beta")),
+                Some(crate::release_channel::ReleaseChannel::Beta) => {
+                    validate_channel_push(&mut errors, self)
+                }
+                Some(crate::release_channel::ReleaseChannel::Stable) => {}
+            }
+        }
+        if let Some(dependencies) = &self.dependencies {
+            validate_dependencies(&mut errors, dependencies, None);
+        }
+        errors
+    }
+}
+
+impl Validate for CanonicalPayloadRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if !CANONICAL_PAYLOAD_OPERATIONS.contains(&self.operation.as_str()) {
+            errors.push(FieldError::new(
+                "operation",
+                format!("must be one of {}", CANONICAL_PAYLOAD_OPERATIONS.join(", ")),
+            ));
+        }
+        if matches!(self.operation.as_str(), "update" | "publish" | "delete") {
+            require_non_empty(
+                &mut errors,
+                "script_id",
+                self.script_id.as_deref().unwrap_or_default(),
+            );
+        }
+        if !self.request.is_object() {
+            errors.push(FieldError::new("request", "must be an object"));
+        }
+        errors
+    }
+}
+
+/// A channel push replaces the channel's build and nothing else; metadata,
+/// price and visibility are shared by all channels and change on stable.
+fn validate_channel_push(errors: &mut Vec<FieldError>, req: &UpdateScriptRequest) {
+    if req.bundle.is_none() {
+        errors.push(FieldError::new("bundle", "is required for a channel push"));
+    }
+    match req.version.as_deref() {
+        Some(version) => require_non_empty(errors, "version", version),
+        None => errors.push(FieldError::new("version", "is required for a channel push")),
+    }
+    let shared = [
+        ("title", req.title.is_some()),
+        ("description", req.description.is_some()),
+        ("readme", req.readme.is_some()),
+        ("category", req.category.is_some()),
+        ("price", req.price.is_some() || req.price_e8s.is_some()),
+        ("is_public", req.is_public.is_some()),
+        ("tags", req.tags.is_some()),
+        ("publish_at", req.publish_at.is_some()),
+        ("dependencies", req.dependencies.is_some()),
+    ];
+    for (field, present) in shared {
+        if present {
+            errors.push(FieldError::new(field, "cannot be set on a channel push"));
+        }
+    }
+}
+
+/// Each dependency names a slug once, other than the script's own, with a
+/// semver requirement; resolvability is checked when the script goes public.
+fn validate_dependencies(
+    errors: &mut Vec<FieldError>,
+    dependencies: &[ScriptDependency],
+    own_slug: Option<&str>,
+) {
+    use crate::script_dependencies::{parse_requirement, MAX_DEPENDENCIES};
+
+    if dependencies.len() > MAX_DEPENDENCIES {
+        errors.push(FieldError::new(
+            "dependencies",
+            format!("must have at most {MAX_DEPENDENCIES} entries"),
+        ));
+    }
+    for (i, dependency) in dependencies.iter().enumerate() {
+        let slug = dependency.slug.trim();
+        require_non_empty(errors, &format!("dependencies[{i}].slug"), slug);
+        if own_slug.is_some_and(|own| own.trim() == slug) {
+            errors.push(FieldError::new(
+                format!("dependencies[{i}].slug"),
+                "cannot be the script itself",
+            ));
+        }
+        if dependencies[..i].iter().any(|d| d.slug.trim() == slug) {
+            errors.push(FieldError::new(
+                format!("dependencies[{i}].slug"),
+                "is declared more than once",
+            ));
+        }
+        if let Err(message) = parse_requirement(&dependency.version) {
+            errors.push(FieldError::new(
+                format!("dependencies[{i}].version"),
+                message,
+            ));
+        }
+    }
+}
+
+impl Validate for AdminDisableKeyRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminAddRecoveryKeyRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "publicKey", &self.public_key);
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminShadowBanRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminSetTierRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminModerationNoteRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "note", &self.note);
+        errors
+    }
+}
+
+impl Validate for AdminStrikeRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminModerateReviewRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminResolveDisputeRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        match (self.refund, self.refund_block_index) {
+            (true, None) => errors.push(FieldError::new(
+                "refundBlockIndex",
+                "required when refunding",
+            )),
+            (false, Some(_)) => errors.push(FieldError::new(
+                "refundBlockIndex",
+                "only allowed when refunding",
+            )),
+            _ => {}
+        }
+        errors
+    }
+}
+
+impl Validate for AdminMergeCategoriesRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self.from.is_empty() {
+            errors.push(FieldError::new("from", "must list at least one category"));
+        }
+        for (i, category) in self.from.iter().enumerate() {
+            require_non_empty(&mut errors, &format!("from[{i}]"), category);
+        }
+        require_non_empty(&mut errors, "into", &self.into);
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminIntegrityCheckRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminMaintenanceRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self
+            .retry_after_secs
+            .is_some_and(|secs| !(1..=86_400).contains(&secs))
+        {
+            errors.push(FieldError::new(
+                "retryAfterSecs",
+                "must be between 1 and 86400",
+            ));
+        }
+        if self
+            .message
+            .as_deref()
+            .is_some_and(|m| m.chars().count() > 500)
+        {
+            errors.push(FieldError::new("message", "must be at most 500 characters"));
+        }
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+impl Validate for AdminRenameTagRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "from", &self.from);
+        require_non_empty(&mut errors, "to", &self.to);
+        require_non_empty(&mut errors, "reason", &self.reason);
+        errors
+    }
+}
+
+// Validated in the services (username format, key encoding, signatures) or
+// in the repository (search paging), where the messages already exist.
+impl Validate for RegisterAccountRequest {}
+impl Validate for UpdateAccountRequest {}
+impl Validate for AddPublicKeyRequest {}
+impl Validate for RemovePublicKeyRequest {}
+impl Validate for DeleteScriptRequest {}
+impl Validate for DownloadRequest {}
+impl Validate for SearchRequest {}
+
+impl Validate for SearchClickRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "exposureId", &self.exposure_id);
+        require_non_empty(&mut errors, "scriptId", &self.script_id);
+        errors
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -528,9 +1957,11 @@ mod tests {
         "owner_account_id",
         "title",
         "description",
+        "readme",
         "category",
         "tags",
         "bundle",
+        "bundle_sha256",
         "author_principal",
         "author_public_key",
         "upload_signature",
@@ -540,13 +1971,18 @@ mod tests {
         "version",
         "compatibility",
         "price",
+        "price_e8s",
+        "currency",
         "is_public",
         "downloads",
+        "views",
         "rating",
+        "weighted_rating",
         "review_count",
         "created_at",
         "updated_at",
         "deleted_at",
+        "publish_at",
         "author_name",
     ];
 
@@ -0,0 +1,78 @@
+//! Moderation notes, strikes and the restrictions strikes bring.
+//!
+//! Admins keep notes on an account (context for the next moderator, never
+//! shown to the account) and hand out strikes with a reason. Strikes count
+//! until an admin revokes them, and the count alone decides the account's
+//! [`Restriction`]: no manual switch to forget to turn off.
+//!
+//! Every write path checks the restriction of the account it acts for:
+//! script uploads, updates, channel pushes and publishing against
+//! [`Restriction::allows_uploads`], reviews, questions and compatibility
+//! votes against [`Restriction::allows_community_writes`].
+
+use serde::Serialize;
+
+/// Active strikes from which the account cannot upload or change scripts.
+pub const UPLOAD_FREEZE_STRIKES: i64 = 3;
+/// Active strikes from which the account cannot write anything.
+pub const WRITE_FREEZE_STRIKES: i64 = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Restriction {
+    #[default]
+    None,
+    /// No script uploads, updates, channel pushes or publishing.
+    UploadFrozen,
+    /// Upload freeze, and no reviews, questions or votes either.
+    WriteFrozen,
+}
+
+impl Restriction {
+    pub fn for_strikes(active_strikes: i64) -> Self {
+        if active_strikes >= WRITE_FREEZE_STRIKES {
+            Self::WriteFrozen
+        } else if active_strikes >= UPLOAD_FREEZE_STRIKES {
+            Self::UploadFrozen
+        } else {
+            Self::None
+        }
+    }
+
+    pub fn allows_uploads(self) -> bool {
+        self == Self::None
+    }
+
+    pub fn allows_community_writes(self) -> bool {
+        self != Self::WriteFrozen
+    }
+}
+
+/// Message of the 403 a restricted account gets.
+pub fn restricted_message(restriction: Restriction) -> &'static str {
+    match restriction {
+        Restriction::None => "",
+        Restriction::UploadFrozen => {
+            "Script uploads are frozen for this account after moderation strikes"
+        }
+        Restriction::WriteFrozen => "This account is frozen after moderation strikes",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_escalate() {
+        assert_eq!(Restriction::for_strikes(0), Restriction::None);
+        assert_eq!(
+            Restriction::for_strikes(UPLOAD_FREEZE_STRIKES - 1),
+            Restriction::None
+        );
+        let upload = Restriction::for_strikes(UPLOAD_FREEZE_STRIKES);
+        assert!(!upload.allows_uploads() && upload.allows_community_writes());
+        let write = Restriction::for_strikes(WRITE_FREEZE_STRIKES + 2);
+        assert!(!write.allows_uploads() && !write.allows_community_writes());
+    }
+}
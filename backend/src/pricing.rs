@@ -0,0 +1,337 @@
+//! Script prices: an integer amount in e8s (10^-8 of a token) plus the code
+//! of the token it is denominated in.
+//!
+//! The legacy `price` field was a bare `f64` number of tokens, so the amount
+//! a buyer signed for and the amount the backend stored could differ in the
+//! last digit. Prices are now stored as `price_e8s` + `currency`; `price` is
+//! still accepted (rounded to the nearest e8) and still returned, derived
+//! from `price_e8s`, for older clients.
+//!
+//! Signed uploads and updates that use the new fields must declare
+//! `payload_version: 2`, which puts `price_e8s` and `currency` into the
+//! canonical payload (see `middleware::auth`). Version 1 payloads cannot
+//! carry them, so an unsigned price can never ride along a v1 signature.
+
+use serde::Serialize;
+use std::env;
+use std::sync::OnceLock;
+
+use crate::validation::FieldError;
+
+/// e8s per whole token.
+pub const E8S_PER_TOKEN: i64 = 100_000_000;
+
+pub const DEFAULT_CURRENCY: &str = "ICP";
+
+/// Canonical payload version that signs `price_e8s` and `currency`.
+pub const PRICED_PAYLOAD_VERSION: u8 = 2;
+
+/// A token scripts can be priced in. Every supported token has 8 decimals,
+/// so `price_e8s` is always its smallest unit.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Currency {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub decimals: u32,
+}
+
+pub const CURRENCIES: &[Currency] = &[
+    Currency {
+        code: "ICP",
+        name: "Internet Computer",
+        decimals: 8,
+    },
+    Currency {
+        code: "ckBTC",
+        name: "Chain-key Bitcoin",
+        decimals: 8,
+    },
+];
+
+pub fn is_supported_currency(code: &str) -> bool {
+    CURRENCIES.iter().any(|c| c.code == code)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Price {
+    pub e8s: i64,
+    pub currency: String,
+}
+
+impl Price {
+    pub fn new(e8s: i64, currency: &str) -> Self {
+        Self {
+            e8s,
+            currency: currency.to_string(),
+        }
+    }
+
+    pub fn free() -> Self {
+        Self::new(0, DEFAULT_CURRENCY)
+    }
+
+    /// The amount in whole tokens, for the legacy `price` field.
+    pub fn legacy_amount(&self) -> f64 {
+        self.e8s as f64 / E8S_PER_TOKEN as f64
+    }
+}
+
+/// Converts a legacy whole-token price to e8s, rounding to the nearest e8.
+/// `None` for negative, non-finite or out-of-range values.
+pub fn e8s_from_legacy(price: f64) -> Option<i64> {
+    let e8s = (price * E8S_PER_TOKEN as f64).round();
+    (e8s.is_finite() && e8s >= 0.0 && e8s <= i64::MAX as f64).then_some(e8s as i64)
+}
+
+/// Field-level checks for the price fields of a script create or update
+/// body. `payload_version` is the declared canonical payload version
+/// (absent means 1).
+pub fn validate_price_fields(
+    errors: &mut Vec<FieldError>,
+    payload_version: Option<u8>,
+    legacy_price: Option<f64>,
+    price_e8s: Option<i64>,
+    currency: Option<&str>,
+) {
+    match payload_version.unwrap_or(1) {
+        1 => {
+            if price_e8s.is_some() {
+                errors.push(FieldError::new("price_e8s", "requires payload_version 2"));
+            }
+            if currency.is_some() {
+                errors.push(FieldError::new("currency", "requires payload_version 2"));
+            }
+        }
+        PRICED_PAYLOAD_VERSION => {
+            if legacy_price.is_some() {
+                errors.push(FieldError::new(
+                    "price",
+                    "not allowed with payload_version 2; use price_e8s",
+                ));
+            }
+            // A price always names its currency, so an update can never
+            // silently re-denominate the stored amount.
+            match (price_e8s, currency) {
+                (Some(_), None) => {
+                    errors.push(FieldError::new("currency", "required with price_e8s"))
+                }
+                (None, Some(_)) => {
+                    errors.push(FieldError::new("price_e8s", "required with currency"))
+                }
+                _ => {}
+            }
+        }
+        _ => errors.push(FieldError::new("payload_version", "must be 1 or 2")),
+    }
+    if legacy_price.is_some_and(|p| p >= 0.0 && e8s_from_legacy(p).is_none()) {
+        errors.push(FieldError::new("price", "out of range"));
+    }
+    if price_e8s.is_some_and(|e8s| e8s < 0) {
+        errors.push(FieldError::new("price_e8s", "must not be negative"));
+    }
+    if currency.is_some_and(|code| !is_supported_currency(code)) {
+        errors.push(FieldError::new("currency", "unsupported currency"));
+    }
+}
+
+/// The price a validated request sets, if any.
+pub fn resolve_price(
+    legacy_price: Option<f64>,
+    price_e8s: Option<i64>,
+    currency: Option<&str>,
+) -> Option<Price> {
+    let e8s = price_e8s.or_else(|| legacy_price.and_then(e8s_from_legacy))?;
+    Some(Price::new(e8s, currency.unwrap_or(DEFAULT_CURRENCY)))
+}
+
+/// How a promotion lowers a script's price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscountKind {
+    /// `amount` is a whole percentage, 1–100.
+    Percent,
+    /// `amount` is in e8s of the promotion's currency.
+    Fixed,
+}
+
+impl DiscountKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "percent" => Some(Self::Percent),
+            "fixed" => Some(Self::Fixed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Percent => "percent",
+            Self::Fixed => "fixed",
+        }
+    }
+}
+
+/// `price_e8s` after the discount, never below zero. Percentages round in
+/// the buyer's favour.
+pub fn discounted_e8s(price_e8s: i64, kind: DiscountKind, amount: i64) -> i64 {
+    let discounted = match kind {
+        DiscountKind::Percent => {
+            let kept = 100 - amount.clamp(0, 100);
+            (i128::from(price_e8s) * i128::from(kept) / 100) as i64
+        }
+        DiscountKind::Fixed => price_e8s.saturating_sub(amount.max(0)),
+    };
+    discounted.max(0)
+}
+
+/// Conversion of one token into a display currency, e.g. 1 ICP = 7.25 USD.
+/// Only used to show approximate prices; payments are always in e8s.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayRate {
+    pub currency: String,
+    pub display: String,
+    pub rate: f64,
+}
+
+/// Display rates from `PRICE_DISPLAY_RATES`, parsed once per process.
+pub fn display_rates() -> &'static [DisplayRate] {
+    static RATES: OnceLock<Vec<DisplayRate>> = OnceLock::new();
+    RATES.get_or_init(|| {
+        env::var("PRICE_DISPLAY_RATES")
+            .map(|raw| parse_display_rates(&raw))
+            .unwrap_or_default()
+    })
+}
+
+/// Parses `ICP/USD=7.25,ckBTC/USD=65000`. Malformed entries and unsupported
+/// tokens are skipped with a warning.
+fn parse_display_rates(raw: &str) -> Vec<DisplayRate> {
+    let mut rates = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parsed = entry.split_once('=').and_then(|(pair, rate)| {
+            let (currency, display) = pair.trim().split_once('/')?;
+            let rate: f64 = rate.trim().parse().ok()?;
+            let currency = currency.trim();
+            let display = display.trim();
+            (is_supported_currency(currency) && !display.is_empty() && rate > 0.0).then(|| {
+                DisplayRate {
+                    currency: currency.to_string(),
+                    display: display.to_ascii_uppercase(),
+                    rate,
+                }
+            })
+        });
+        match parsed {
+            Some(rate) => rates.push(rate),
+            None => tracing::warn!("Ignoring malformed PRICE_DISPLAY_RATES entry '{entry}'"),
+        }
+    }
+    rates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors_for(
+        payload_version: Option<u8>,
+        legacy_price: Option<f64>,
+        price_e8s: Option<i64>,
+        currency: Option<&str>,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        validate_price_fields(
+            &mut errors,
+            payload_version,
+            legacy_price,
+            price_e8s,
+            currency,
+        );
+        errors.into_iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn legacy_prices_round_to_the_nearest_e8() {
+        assert_eq!(e8s_from_legacy(9.99), Some(999_000_000));
+        assert_eq!(e8s_from_legacy(0.1 + 0.2), Some(30_000_000));
+        assert_eq!(e8s_from_legacy(0.000_000_004), Some(0));
+        assert_eq!(e8s_from_legacy(-1.0), None);
+        assert_eq!(e8s_from_legacy(f64::NAN), None);
+        assert_eq!(Price::new(999_000_000, "ICP").legacy_amount(), 9.99);
+    }
+
+    #[test]
+    fn new_price_fields_require_payload_version_2() {
+        assert!(errors_for(None, Some(1.5), None, None).is_empty());
+        assert_eq!(
+            errors_for(None, None, Some(150_000_000), Some("ICP")),
+            ["price_e8s", "currency"]
+        );
+        assert!(errors_for(Some(2), None, Some(150_000_000), Some("ckBTC")).is_empty());
+        assert_eq!(errors_for(Some(2), Some(1.5), None, None), ["price"]);
+        assert_eq!(errors_for(Some(3), None, None, None), ["payload_version"]);
+    }
+
+    #[test]
+    fn price_fields_are_range_and_currency_checked() {
+        assert_eq!(
+            errors_for(Some(2), None, Some(-1), Some("ICP")),
+            ["price_e8s"]
+        );
+        assert_eq!(
+            errors_for(Some(2), None, Some(1), Some("DOGE")),
+            ["currency"]
+        );
+    }
+
+    #[test]
+    fn v2_price_and_currency_come_together() {
+        assert_eq!(errors_for(Some(2), None, Some(1), None), ["currency"]);
+        assert_eq!(errors_for(Some(2), None, None, Some("ICP")), ["price_e8s"]);
+        assert!(errors_for(Some(2), None, None, None).is_empty());
+    }
+
+    #[test]
+    fn resolve_price_prefers_e8s_and_defaults_currency() {
+        assert_eq!(resolve_price(None, None, None), None);
+        assert_eq!(
+            resolve_price(Some(2.5), None, None),
+            Some(Price::new(250_000_000, "ICP"))
+        );
+        assert_eq!(
+            resolve_price(None, Some(42), Some("ckBTC")),
+            Some(Price::new(42, "ckBTC"))
+        );
+    }
+
+    #[test]
+    fn discounts_never_go_below_zero() {
+        assert_eq!(discounted_e8s(999, DiscountKind::Percent, 50), 499);
+        assert_eq!(discounted_e8s(1_000, DiscountKind::Percent, 100), 0);
+        assert_eq!(discounted_e8s(1_000, DiscountKind::Fixed, 250), 750);
+        assert_eq!(discounted_e8s(1_000, DiscountKind::Fixed, 5_000), 0);
+        assert_eq!(DiscountKind::parse("percent"), Some(DiscountKind::Percent));
+        assert_eq!(DiscountKind::parse("bogo"), None);
+    }
+
+    #[test]
+    fn display_rates_skip_malformed_entries() {
+        let rates = parse_display_rates("ICP/usd=7.25, ckBTC/USD=65000,DOGE/USD=1,ICP/EUR=x,junk");
+        assert_eq!(
+            rates,
+            [
+                DisplayRate {
+                    currency: "ICP".into(),
+                    display: "USD".into(),
+                    rate: 7.25
+                },
+                DisplayRate {
+                    currency: "ckBTC".into(),
+                    display: "USD".into(),
+                    rate: 65000.0
+                },
+            ]
+        );
+    }
+}
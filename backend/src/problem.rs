@@ -0,0 +1,189 @@
+//! RFC 7807 problem details for error responses.
+//!
+//! Errors keep the `{"success":false,"error":"…"}` envelope by default;
+//! clients that send `Accept: application/problem+json` get the same error
+//! as an `application/problem+json` document instead (see
+//! [`crate::middleware::ProblemJson`]):
+//!
+//! ```json
+//! {"type":"about:blank","title":"Not Found","status":404,
+//!  "detail":"Script not found","code":"not_found","traceId":"…"}
+//! ```
+//!
+//! `code` is a stable machine-readable [`ErrorCode`], so clients can branch
+//! without matching on `detail`, whose wording may change. Any other member
+//! of the legacy body (`errors` of a validation failure, `retryAfterSecs`
+//! of maintenance mode) is carried over as an extension member.
+
+use poem::http::StatusCode;
+use serde_json::{Map, Value};
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// The stable error codes of the API, one per kind of failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    BadRequest,
+    ValidationFailed,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    Gone,
+    PayloadTooLarge,
+    Timeout,
+    RateLimited,
+    NotImplemented,
+    Maintenance,
+    Internal,
+}
+
+impl ErrorCode {
+    /// The code of an error response with `status`; `validation` when the
+    /// body lists per-field errors.
+    pub fn classify(status: StatusCode, validation: bool) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY if validation => {
+                Self::ValidationFailed
+            }
+            StatusCode::UNPROCESSABLE_ENTITY => Self::ValidationFailed,
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::FORBIDDEN => Self::Forbidden,
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::CONFLICT => Self::Conflict,
+            StatusCode::GONE => Self::Gone,
+            StatusCode::PAYLOAD_TOO_LARGE => Self::PayloadTooLarge,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => Self::Timeout,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+            StatusCode::NOT_IMPLEMENTED => Self::NotImplemented,
+            StatusCode::SERVICE_UNAVAILABLE => Self::Maintenance,
+            s if s.is_client_error() => Self::BadRequest,
+            _ => Self::Internal,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BadRequest => "bad_request",
+            Self::ValidationFailed => "validation_failed",
+            Self::Unauthorized => "unauthorized",
+            Self::Forbidden => "forbidden",
+            Self::NotFound => "not_found",
+            Self::Conflict => "conflict",
+            Self::Gone => "gone",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::Timeout => "timeout",
+            Self::RateLimited => "rate_limited",
+            Self::NotImplemented => "not_implemented",
+            Self::Maintenance => "maintenance",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+/// The problem document for an error response with `status` and `body`:
+/// the legacy JSON envelope, or the plain-text body of a framework error.
+pub fn problem_body(status: StatusCode, body: &[u8], trace_id: &str) -> Value {
+    let (detail, mut extensions) = match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Object(mut envelope)) => {
+            envelope.remove("success");
+            let detail = match envelope.remove("error") {
+                Some(Value::String(message)) => Some(message),
+                _ => None,
+            };
+            (detail, envelope)
+        }
+        _ => {
+            let text = String::from_utf8_lossy(body).trim().to_string();
+            ((!text.is_empty()).then_some(text), Map::new())
+        }
+    };
+    let validation = extensions.get("errors").is_some_and(Value::is_array);
+
+    let mut problem = Map::new();
+    problem.insert("type".into(), "about:blank".into());
+    problem.insert(
+        "title".into(),
+        status.canonical_reason().unwrap_or("Error").into(),
+    );
+    problem.insert("status".into(), status.as_u16().into());
+    if let Some(detail) = detail {
+        problem.insert("detail".into(), detail.into());
+    }
+    problem.insert(
+        "code".into(),
+        ErrorCode::classify(status, validation).as_str().into(),
+    );
+    problem.insert("traceId".into(), trace_id.into());
+    for key in ["type", "title", "status", "detail", "code", "traceId"] {
+        extensions.remove(key);
+    }
+    problem.extend(extensions);
+    Value::Object(problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn envelope_becomes_a_problem_document() {
+        let body = json!({"success": false, "error": "Script not found"}).to_string();
+        assert_eq!(
+            problem_body(StatusCode::NOT_FOUND, body.as_bytes(), "t-1"),
+            json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": 404,
+                "detail": "Script not found",
+                "code": "not_found",
+                "traceId": "t-1",
+            })
+        );
+    }
+
+    #[test]
+    fn extra_members_are_kept_and_field_errors_classify_as_validation() {
+        let body = json!({
+            "success": false,
+            "error": "Invalid request body",
+            "errors": [{"field": "title", "error": "is required"}],
+        })
+        .to_string();
+        let problem = problem_body(StatusCode::BAD_REQUEST, body.as_bytes(), "t-2");
+        assert_eq!(problem["code"], "validation_failed");
+        assert_eq!(problem["errors"][0]["field"], "title");
+        assert!(problem.get("success").is_none());
+    }
+
+    #[test]
+    fn plain_text_bodies_become_the_detail() {
+        let problem = problem_body(StatusCode::FORBIDDEN, b"origin not allowed", "t-3");
+        assert_eq!(problem["detail"], "origin not allowed");
+        assert_eq!(problem["code"], "forbidden");
+        assert!(problem_body(StatusCode::BAD_GATEWAY, b"", "t-4")
+            .get("detail")
+            .is_none());
+    }
+
+    #[test]
+    fn statuses_map_to_stable_codes() {
+        assert_eq!(
+            ErrorCode::classify(StatusCode::TOO_MANY_REQUESTS, false),
+            ErrorCode::RateLimited
+        );
+        assert_eq!(
+            ErrorCode::classify(StatusCode::BAD_REQUEST, false),
+            ErrorCode::BadRequest
+        );
+        assert_eq!(
+            ErrorCode::classify(StatusCode::METHOD_NOT_ALLOWED, false),
+            ErrorCode::BadRequest
+        );
+        assert_eq!(
+            ErrorCode::classify(StatusCode::BAD_GATEWAY, false),
+            ErrorCode::Internal
+        );
+    }
+}
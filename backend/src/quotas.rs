@@ -0,0 +1,132 @@
+//! Account plan tiers and the quotas each tier grants.
+//!
+//! Every account is on the `free` tier until an admin moves it (see
+//! `POST /api/v1/admin/accounts/:username/tier`). The quotas are enforced by
+//! the services: [`crate::services::ScriptService`] checks private scripts
+//! and asset storage on create and update, and
+//! [`crate::services::AccountService`] checks API keys when one is added.
+//! Asset storage is the UTF-8 byte size of the bundles of the account's live
+//! scripts; soft-deleted scripts do not count.
+//!
+//! A downgrade never deletes anything. An account over its new quota keeps
+//! what it has, but cannot grow further until it is back under.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountTier {
+    #[default]
+    Free,
+    Pro,
+}
+
+impl AccountTier {
+    /// Unknown values read back as `Free`, the most restrictive tier.
+    pub fn parse(tier: &str) -> Self {
+        match tier {
+            "pro" => Self::Pro,
+            _ => Self::Free,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Free => "free",
+            Self::Pro => "pro",
+        }
+    }
+
+    pub fn quotas(self) -> TierQuotas {
+        match self {
+            Self::Free => TierQuotas {
+                max_private_scripts: 3,
+                max_asset_bytes: 5 * 1024 * 1024,
+                max_api_keys: 10,
+            },
+            Self::Pro => TierQuotas {
+                max_private_scripts: 100,
+                max_asset_bytes: 200 * 1024 * 1024,
+                max_api_keys: 25,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierQuotas {
+    pub max_private_scripts: i64,
+    pub max_asset_bytes: i64,
+    /// Active and disabled keys alike; disabling a key does not free a slot.
+    pub max_api_keys: i64,
+}
+
+/// How much of one quota is in use.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct QuotaMeter {
+    pub used: i64,
+    pub limit: i64,
+}
+
+impl QuotaMeter {
+    /// Whether `used + extra` stays within the limit. Shrinking is always
+    /// allowed, even for an account already over its quota.
+    pub fn allows(&self, extra: i64) -> bool {
+        extra <= 0 || self.used + extra <= self.limit
+    }
+}
+
+/// Quota usage reported in the account payload.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaUsage {
+    pub private_scripts: QuotaMeter,
+    pub asset_bytes: QuotaMeter,
+    pub api_keys: QuotaMeter,
+}
+
+impl QuotaUsage {
+    pub fn new(tier: AccountTier, private_scripts: i64, asset_bytes: i64, api_keys: i64) -> Self {
+        let quotas = tier.quotas();
+        Self {
+            private_scripts: QuotaMeter {
+                used: private_scripts,
+                limit: quotas.max_private_scripts,
+            },
+            asset_bytes: QuotaMeter {
+                used: asset_bytes,
+                limit: quotas.max_asset_bytes,
+            },
+            api_keys: QuotaMeter {
+                used: api_keys,
+                limit: quotas.max_api_keys,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_tiers_read_as_free() {
+        assert_eq!(AccountTier::parse("pro"), AccountTier::Pro);
+        assert_eq!(AccountTier::parse("enterprise"), AccountTier::Free);
+        assert_eq!(
+            AccountTier::parse(AccountTier::Pro.as_str()),
+            AccountTier::Pro
+        );
+    }
+
+    #[test]
+    fn meters_allow_growth_up_to_the_limit_and_any_shrink() {
+        let meter = QuotaMeter { used: 3, limit: 5 };
+        assert!(meter.allows(2));
+        assert!(!meter.allows(3));
+        let over = QuotaMeter { used: 7, limit: 5 };
+        assert!(!over.allows(1));
+        assert!(over.allows(0));
+        assert!(over.allows(-4));
+    }
+}
@@ -9,8 +9,14 @@
 //! In-memory (not DB-backed): a restart resets the counters, which is
 //! acceptable for an online brute-force throttle (the Argon2id KDF remains the
 //! primary bound). Process-local (sufficient for a single-node deployment).
+//!
+//! [`VelocityGuard`] applies the same in-memory model to script uploads and
+//! updates, adding an escalating cooldown for callers that keep hitting it.
+//! [`ViewDeduper`] uses it to decide which script detail views are counted.
 
 use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -67,10 +73,346 @@ impl SlidingWindowRateLimiter {
     }
 }
 
+/// Which write a [`VelocityGuard`] check is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VelocityAction {
+    Create,
+    Update,
+}
+
+/// Limits applied by [`VelocityGuard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VelocityRules {
+    pub max_creates_per_hour: usize,
+    pub max_updates_per_minute: usize,
+    /// Cooldown after the first violation; doubles with each further one.
+    pub base_cooldown_secs: i64,
+    /// Ceiling for the doubling. A subject that stays clean for this long
+    /// after its last cooldown starts again from `base_cooldown_secs`.
+    pub max_cooldown_secs: i64,
+}
+
+impl Default for VelocityRules {
+    fn default() -> Self {
+        Self {
+            max_creates_per_hour: 10,
+            max_updates_per_minute: 20,
+            base_cooldown_secs: 60,
+            max_cooldown_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+impl VelocityRules {
+    /// Defaults overridden by `SCRIPT_MAX_CREATES_PER_HOUR` and
+    /// `SCRIPT_MAX_UPDATES_PER_MINUTE`.
+    pub fn from_env() -> Self {
+        let parse = |name: &str, default: usize| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        let d = Self::default();
+        Self {
+            max_creates_per_hour: parse("SCRIPT_MAX_CREATES_PER_HOUR", d.max_creates_per_hour),
+            max_updates_per_minute: parse(
+                "SCRIPT_MAX_UPDATES_PER_MINUTE",
+                d.max_updates_per_minute,
+            ),
+            ..d
+        }
+    }
+}
+
+#[derive(Default)]
+struct VelocityState {
+    creates: Vec<i64>,
+    updates: Vec<i64>,
+    strikes: u32,
+    cooldown_until: i64,
+}
+
+impl VelocityState {
+    /// Whether forgetting the subject changes nothing: both windows are
+    /// empty and the strikes would reset on its next check anyway.
+    fn is_idle(&self, rules: &VelocityRules, now: i64) -> bool {
+        self.creates.iter().all(|t| *t <= now - CREATE_WINDOW_SECS)
+            && self.updates.iter().all(|t| *t <= now - UPDATE_WINDOW_SECS)
+            && now - self.cooldown_until > rules.max_cooldown_secs
+    }
+}
+
+const CREATE_WINDOW_SECS: i64 = 60 * 60;
+const UPDATE_WINDOW_SECS: i64 = 60;
+
+/// How often [`VelocityGuard`] drops idle subjects.
+const VELOCITY_SWEEP_INTERVAL_SECS: i64 = 10 * 60;
+
+/// Per-subject write velocity limiter for scripts. A subject is an opaque
+/// string; the script service passes one per account and one per signing key
+/// so neither rotating keys nor spreading across accounts escapes the limit.
+/// Idle subjects are dropped every [`VELOCITY_SWEEP_INTERVAL_SECS`] so the
+/// map only holds callers that wrote recently.
+pub struct VelocityGuard {
+    rules: VelocityRules,
+    subjects: Mutex<HashMap<String, VelocityState>>,
+    next_sweep: AtomicI64,
+}
+
+impl VelocityGuard {
+    pub fn new(rules: VelocityRules) -> Self {
+        Self {
+            rules,
+            subjects: Mutex::new(HashMap::new()),
+            next_sweep: AtomicI64::new(0),
+        }
+    }
+
+    /// Admits the write and records it against every subject, or returns the
+    /// number of seconds until the caller may retry. A rejected attempt
+    /// records nothing but starts (or extends) the subject's cooldown.
+    pub fn check(&self, subjects: &[String], action: VelocityAction) -> Result<(), i64> {
+        self.check_at(subjects, action, SlidingWindowRateLimiter::now())
+    }
+
+    fn check_at(&self, subjects: &[String], action: VelocityAction, now: i64) -> Result<(), i64> {
+        let (max, window) = match action {
+            VelocityAction::Create => (self.rules.max_creates_per_hour, CREATE_WINDOW_SECS),
+            VelocityAction::Update => (self.rules.max_updates_per_minute, UPDATE_WINDOW_SECS),
+        };
+        let mut map = self.subjects.lock().expect("velocity mutex poisoned");
+        // Read and written under the lock, so relaxed ordering is enough.
+        if now >= self.next_sweep.load(Ordering::Relaxed) {
+            map.retain(|_, state| !state.is_idle(&self.rules, now));
+            self.next_sweep
+                .store(now + VELOCITY_SWEEP_INTERVAL_SECS, Ordering::Relaxed);
+        }
+
+        for subject in subjects {
+            let state = map.entry(subject.clone()).or_default();
+            if now < state.cooldown_until {
+                return Err(state.cooldown_until - now);
+            }
+            let history = match action {
+                VelocityAction::Create => &mut state.creates,
+                VelocityAction::Update => &mut state.updates,
+            };
+            history.retain(|t| *t > now - window);
+            if history.len() >= max {
+                if now - state.cooldown_until > self.rules.max_cooldown_secs {
+                    state.strikes = 0;
+                }
+                state.strikes += 1;
+                let cooldown = self
+                    .rules
+                    .base_cooldown_secs
+                    .saturating_mul(1 << (state.strikes - 1).min(30))
+                    .min(self.rules.max_cooldown_secs);
+                state.cooldown_until = now + cooldown;
+                return Err(cooldown);
+            }
+        }
+
+        for subject in subjects {
+            let state = map.entry(subject.clone()).or_default();
+            match action {
+                VelocityAction::Create => state.creates.push(now),
+                VelocityAction::Update => state.updates.push(now),
+            }
+        }
+        Ok(())
+    }
+
+    /// Admin override: forgets history, strikes and any active cooldown.
+    pub fn clear(&self, subject: &str) {
+        let mut map = self.subjects.lock().expect("velocity mutex poisoned");
+        map.remove(subject);
+    }
+}
+
+/// Repeat views of a script by one viewer within this many seconds count once.
+pub const DEFAULT_VIEW_WINDOW_SECS: i64 = 30 * 60;
+
+/// (script, viewer) pairs remembered at most. Past this, after evicting
+/// expired pairs, new views are not counted rather than growing the map.
+const MAX_TRACKED_VIEWS: usize = 100_000;
+
+/// Decides which script views are counted. A viewer is a set of identities
+/// (the client IP, plus the public key when the app sends one); a view counts
+/// only if NONE of them viewed the script within the window. Every view
+/// refreshes the window, so a client polling the page never counts again, and
+/// rotating keys from one IP (or one key across IPs) does not help either.
+pub struct ViewDeduper {
+    last_seen: Mutex<HashMap<String, i64>>,
+    window_secs: i64,
+}
+
+impl ViewDeduper {
+    pub fn new(window_secs: i64) -> Self {
+        Self {
+            last_seen: Mutex::new(HashMap::new()),
+            window_secs,
+        }
+    }
+
+    /// `VIEW_DEDUP_WINDOW_SECS`, or [`DEFAULT_VIEW_WINDOW_SECS`].
+    pub fn window_from_env() -> i64 {
+        env::var("VIEW_DEDUP_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|secs: &i64| *secs > 0)
+            .unwrap_or(DEFAULT_VIEW_WINDOW_SECS)
+    }
+
+    /// Records the view and returns whether it should be counted.
+    pub fn should_count(&self, script_id: &str, viewers: &[String]) -> bool {
+        self.should_count_at(script_id, viewers, SlidingWindowRateLimiter::now())
+    }
+
+    fn should_count_at(&self, script_id: &str, viewers: &[String], now: i64) -> bool {
+        let cutoff = now - self.window_secs;
+        let mut map = self.last_seen.lock().expect("view-dedup mutex poisoned");
+        let keys: Vec<String> = viewers
+            .iter()
+            .map(|viewer| format!("{script_id}|{viewer}"))
+            .collect();
+        let fresh = keys
+            .iter()
+            .all(|key| !matches!(map.get(key), Some(seen) if *seen > cutoff));
+        if map.len() + keys.len() > MAX_TRACKED_VIEWS {
+            map.retain(|_, seen| *seen > cutoff);
+            if map.len() + keys.len() > MAX_TRACKED_VIEWS {
+                return false;
+            }
+        }
+        for key in keys {
+            map.insert(key, now);
+        }
+        fresh && !viewers.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn rules() -> VelocityRules {
+        VelocityRules {
+            max_creates_per_hour: 2,
+            max_updates_per_minute: 1,
+            base_cooldown_secs: 60,
+            max_cooldown_secs: 600,
+        }
+    }
+
+    #[test]
+    fn velocity_blocks_over_limit_with_doubling_cooldown() {
+        let guard = VelocityGuard::new(rules());
+        let subjects = vec!["account:a".to_string()];
+        let t = 1_000_000;
+        assert!(guard.check_at(&subjects, VelocityAction::Create, t).is_ok());
+        assert!(guard.check_at(&subjects, VelocityAction::Create, t).is_ok());
+        assert_eq!(
+            guard.check_at(&subjects, VelocityAction::Create, t),
+            Err(60)
+        );
+        // Still cooling down: reports the remaining time, no new strike.
+        assert_eq!(
+            guard.check_at(&subjects, VelocityAction::Create, t + 10),
+            Err(50)
+        );
+        // Cooldown over but the hourly window is still full: second strike.
+        assert_eq!(
+            guard.check_at(&subjects, VelocityAction::Create, t + 60),
+            Err(120)
+        );
+        // Window expired: allowed again.
+        assert!(guard
+            .check_at(&subjects, VelocityAction::Create, t + 3601)
+            .is_ok());
+    }
+
+    #[test]
+    fn velocity_cooldown_is_capped_and_strikes_decay() {
+        let guard = VelocityGuard::new(rules());
+        let subjects = vec!["key:k".to_string()];
+        let mut t = 0;
+        assert!(guard.check_at(&subjects, VelocityAction::Update, t).is_ok());
+        let mut last = 0;
+        for _ in 0..6 {
+            last = guard
+                .check_at(&subjects, VelocityAction::Update, t)
+                .unwrap_err();
+            t += last;
+            // Keep the per-minute window full.
+            guard
+                .subjects
+                .lock()
+                .unwrap()
+                .get_mut("key:k")
+                .unwrap()
+                .updates = vec![t];
+        }
+        assert_eq!(last, 600, "cooldown must stop doubling at the cap");
+
+        t += 601 + 60;
+        assert!(guard.check_at(&subjects, VelocityAction::Update, t).is_ok());
+        assert_eq!(
+            guard.check_at(&subjects, VelocityAction::Update, t),
+            Err(60),
+            "a long clean stretch resets the escalation"
+        );
+    }
+
+    #[test]
+    fn velocity_any_subject_over_limit_blocks_and_clear_overrides() {
+        let guard = VelocityGuard::new(rules());
+        let t = 0;
+        let first = vec!["account:a".to_string(), "key:1".to_string()];
+        let rotated = vec!["account:a".to_string(), "key:2".to_string()];
+        assert!(guard.check_at(&first, VelocityAction::Update, t).is_ok());
+        assert!(
+            guard.check_at(&rotated, VelocityAction::Update, t).is_err(),
+            "a fresh key on the same account must not reset the limit"
+        );
+        guard.clear("account:a");
+        assert!(guard.check_at(&rotated, VelocityAction::Update, t).is_ok());
+    }
+
+    #[test]
+    fn velocity_forgets_idle_subjects() {
+        let guard = VelocityGuard::new(rules());
+        let quiet = vec!["account:quiet".to_string()];
+        let striking = vec!["account:striking".to_string()];
+        let t = 1_000_000;
+        let tracked = |subject: &str| guard.subjects.lock().unwrap().contains_key(subject);
+        assert!(guard.check_at(&quiet, VelocityAction::Update, t).is_ok());
+        assert!(guard.check_at(&striking, VelocityAction::Update, t).is_ok());
+        assert!(guard
+            .check_at(&striking, VelocityAction::Update, t)
+            .is_err());
+
+        // Both windows have passed; the strike has not decayed yet.
+        let later = t + VELOCITY_SWEEP_INTERVAL_SECS;
+        assert!(guard.check_at(&[], VelocityAction::Update, later).is_ok());
+        assert!(!tracked("account:quiet"));
+        assert!(tracked("account:striking"));
+
+        // Not swept again until the interval has passed.
+        assert!(guard
+            .check_at(&quiet, VelocityAction::Update, later)
+            .is_ok());
+        assert!(guard
+            .check_at(&[], VelocityAction::Update, later + 120)
+            .is_ok());
+        assert!(tracked("account:quiet"));
+        let next = later + VELOCITY_SWEEP_INTERVAL_SECS;
+        assert!(guard.check_at(&[], VelocityAction::Update, next).is_ok());
+        assert!(!tracked("account:quiet"));
+        assert!(!tracked("account:striking"));
+    }
+
     #[test]
     fn allows_until_limit_then_blocks() {
         let limiter = SlidingWindowRateLimiter::new(3, 900);
@@ -108,4 +450,29 @@ mod tests {
             "a different caller must not inherit the limit"
         );
     }
+
+    fn viewers(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn repeat_views_within_the_window_count_once() {
+        let views = ViewDeduper::new(60);
+        let ip = viewers(&["ip:1.2.3.4"]);
+        assert!(views.should_count_at("s1", &ip, 1000));
+        assert!(!views.should_count_at("s1", &ip, 1030));
+        // Each view slides the window, so steady polling never counts again.
+        assert!(!views.should_count_at("s1", &ip, 1080));
+        assert!(views.should_count_at("s1", &ip, 1200));
+        assert!(views.should_count_at("s2", &ip, 1200));
+    }
+
+    #[test]
+    fn any_seen_identity_suppresses_the_view() {
+        let views = ViewDeduper::new(60);
+        assert!(views.should_count_at("s1", &viewers(&["ip:a", "key:k1"]), 1000));
+        assert!(!views.should_count_at("s1", &viewers(&["ip:a", "key:k2"]), 1001));
+        assert!(!views.should_count_at("s1", &viewers(&["ip:b", "key:k1"]), 1002));
+        assert!(views.should_count_at("s1", &viewers(&["ip:c"]), 1003));
+    }
 }
@@ -0,0 +1,22 @@
+//! Script readmes.
+//!
+//! The readme is the long-form markdown documentation of a script, next to
+//! the one-paragraph `description` and limited separately
+//! (`maxReadmeChars`). It lives on the script row, so every version published
+//! under a slug keeps the readme it was released with, and it is signed with
+//! the rest of the upload or update payload.
+//!
+//! `GET /api/v1/scripts/:id/readme.html` serves it rendered by
+//! [`render_html`], under the shared sanitization policy of
+//! [`crate::markdown`].
+
+use crate::markdown::{self, Profile};
+
+/// `Content-Security-Policy` of the rendered page: no scripts, styles or
+/// frames even if something slipped through, and images only over HTTPS.
+pub const CONTENT_SECURITY_POLICY: &str = "default-src 'none'; img-src https:";
+
+/// Renders a readme to a sanitized HTML fragment.
+pub fn render_html(readme: &str) -> String {
+    markdown::render(readme, Profile::Document)
+}
@@ -0,0 +1,192 @@
+//! Ledger lookups behind refund recording.
+//!
+//! An admin settles a disputed purchase by naming the ledger block of the
+//! transfer that paid the buyer back. Before the purchase is marked
+//! `refunded` the backend reads that block from the ledger, checks that it
+//! paid one of the buyer's accounts at least the purchase amount, and records
+//! the transferred amount, so a refund can never be recorded for money that
+//! did not move to the buyer.
+//!
+//! [`IcpLedger`] queries the ICP ledger's `query_blocks` anonymously through
+//! `icp_core::canister_client`. Blocks that have already been moved to an
+//! archive canister are reported as [`LedgerError::Archived`]; refunds are
+//! expected to be recorded shortly after the transfer.
+
+use std::env;
+
+use icp_core::canister_client::{call_anonymous, MethodKind};
+
+/// Mainnet ICP ledger.
+pub const ICP_LEDGER_CANISTER_ID: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+
+/// The ledger account identifier (default subaccount, hex) of a textual
+/// principal, comparable to [`LedgerTransfer::to`]. `None` when `principal`
+/// is not a valid principal.
+pub fn ledger_account_of(principal: &str) -> Option<String> {
+    icp_core::principal::parse_principal_text(principal)
+        .ok()
+        .map(|p| icp_core::principal::account_identifier(&p, None))
+}
+
+/// A token transfer read back from the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerTransfer {
+    pub amount_e8s: u64,
+    /// Hex account identifier of the recipient.
+    pub to: String,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum LedgerError {
+    #[error("block {0} has been archived and cannot be verified")]
+    Archived(u64),
+    #[error("ledger call failed: {0}")]
+    Call(String),
+    #[error("unexpected ledger response: {0}")]
+    Decode(String),
+}
+
+// async_trait marks the boxed future `#[must_use]` on top of the `Result`.
+#[allow(clippy::double_must_use)]
+#[async_trait::async_trait]
+pub trait RefundLedger: Send + Sync {
+    /// The transfer recorded at `block_index`, or `None` when the block does
+    /// not exist yet or is not a transfer (mint, burn, approve).
+    async fn transfer_at(&self, block_index: u64) -> Result<Option<LedgerTransfer>, LedgerError>;
+}
+
+pub struct IcpLedger {
+    canister_id: String,
+    host: String,
+}
+
+impl IcpLedger {
+    /// Ledger from `REFUND_LEDGER_CANISTER_ID` (default: the ICP ledger),
+    /// reached through `IC_GATEWAY_HOST` like the IC proxy.
+    pub fn from_env() -> Self {
+        Self {
+            canister_id: env::var("REFUND_LEDGER_CANISTER_ID")
+                .unwrap_or_else(|_| ICP_LEDGER_CANISTER_ID.to_string()),
+            host: env::var("IC_GATEWAY_HOST")
+                .unwrap_or_else(|_| icp_core::DEFAULT_IC_GATEWAY.to_string()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RefundLedger for IcpLedger {
+    async fn transfer_at(&self, block_index: u64) -> Result<Option<LedgerTransfer>, LedgerError> {
+        let canister_id = self.canister_id.clone();
+        let host = self.host.clone();
+        let arg = format!("(record {{ start = {block_index} : nat64; length = 1 : nat64 }})");
+        // `call_anonymous` blocks on icp_core's own runtime.
+        let raw = tokio::task::spawn_blocking(move || {
+            call_anonymous(
+                &canister_id,
+                "query_blocks",
+                MethodKind::Query,
+                &arg,
+                Some(&host),
+            )
+        })
+        .await
+        .map_err(|e| LedgerError::Call(e.to_string()))?
+        .map_err(|e| LedgerError::Call(e.to_string()))?;
+        let response: serde_json::Value =
+            serde_json::from_str(&raw).map_err(|e| LedgerError::Decode(e.to_string()))?;
+        parse_query_blocks(&response["result"], block_index)
+    }
+}
+
+/// Reads the single block of a `query_blocks(start = block_index, length = 1)`
+/// response, as JSON-decoded by `icp_core` (nat64 values are strings, blobs
+/// are byte arrays).
+fn parse_query_blocks(
+    result: &serde_json::Value,
+    block_index: u64,
+) -> Result<Option<LedgerTransfer>, LedgerError> {
+    let blocks = result["blocks"]
+        .as_array()
+        .ok_or_else(|| LedgerError::Decode("missing blocks".to_string()))?;
+    let Some(block) = blocks.first() else {
+        let archived = result["archived_blocks"]
+            .as_array()
+            .is_some_and(|ranges| !ranges.is_empty());
+        return if archived {
+            Err(LedgerError::Archived(block_index))
+        } else {
+            Ok(None)
+        };
+    };
+    let Some(transfer) = block["transaction"]["operation"].get("Transfer") else {
+        return Ok(None);
+    };
+    let amount_e8s = transfer["amount"]["e8s"]
+        .as_str()
+        .and_then(|e8s| e8s.parse().ok())
+        .ok_or_else(|| LedgerError::Decode("missing transfer amount".to_string()))?;
+    let to = transfer["to"]
+        .as_array()
+        .ok_or_else(|| LedgerError::Decode("missing transfer recipient".to_string()))?
+        .iter()
+        .map(|b| b.as_u64().map(|b| format!("{b:02x}")))
+        .collect::<Option<String>>()
+        .ok_or_else(|| LedgerError::Decode("malformed transfer recipient".to_string()))?;
+    Ok(Some(LedgerTransfer { amount_e8s, to }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_transfer_of_a_block() {
+        let result = json!({
+            "chain_length": "101",
+            "first_block_index": "100",
+            "archived_blocks": [],
+            "blocks": [{
+                "transaction": {
+                    "memo": "0",
+                    "operation": { "Transfer": {
+                        "from": [1, 2],
+                        "to": [171, 205, 1],
+                        "amount": { "e8s": "150000000" },
+                        "fee": { "e8s": "10000" },
+                    }},
+                },
+            }],
+        });
+        assert_eq!(
+            parse_query_blocks(&result, 100),
+            Ok(Some(LedgerTransfer {
+                amount_e8s: 150_000_000,
+                to: "abcd01".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn non_transfers_and_missing_blocks_are_none() {
+        let mint = json!({
+            "archived_blocks": [],
+            "blocks": [{ "transaction": { "operation": { "Mint": {} } } }],
+        });
+        assert_eq!(parse_query_blocks(&mint, 5), Ok(None));
+        let future = json!({ "archived_blocks": [], "blocks": [] });
+        assert_eq!(parse_query_blocks(&future, 5), Ok(None));
+    }
+
+    #[test]
+    fn archived_blocks_are_reported() {
+        let archived = json!({
+            "archived_blocks": [{ "start": "0", "length": "1" }],
+            "blocks": [],
+        });
+        assert_eq!(
+            parse_query_blocks(&archived, 0),
+            Err(LedgerError::Archived(0))
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Per-script release channels.
+//!
+//! `stable` is the script row itself: every existing read, the listings and
+//! the search index only ever see it. An author can additionally push a
+//! `beta` build (`PUT /api/v1/scripts/:id` with `"channel": "beta"`), which
+//! carries its own bundle and version and lives in `script_channel_releases`.
+//! Readers opt in with `?channel=beta`; a script without a beta build serves
+//! its stable build instead, and says so in the response's `channel` field.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl ReleaseChannel {
+    /// Strict, unlike [`crate::quotas::AccountTier::parse`]: a typo must not
+    /// silently land a beta build on stable.
+    pub fn parse(channel: &str) -> Option<Self> {
+        match channel {
+            "stable" => Some(Self::Stable),
+            "beta" => Some(Self::Beta),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trips_and_rejects_unknown_channels() {
+        for channel in [ReleaseChannel::Stable, ReleaseChannel::Beta] {
+            assert_eq!(ReleaseChannel::parse(channel.as_str()), Some(channel));
+        }
+        assert_eq!(ReleaseChannel::parse("canary"), None);
+        assert_eq!(ReleaseChannel::parse("Beta"), None);
+    }
+}
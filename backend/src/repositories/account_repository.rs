@@ -1,6 +1,13 @@
-use crate::models::{Account, AccountPublicKey};
+use crate::models::{Account, AccountFailure, AccountOperation, AccountPublicKey, KeyRevocation};
 use sqlx::SqlitePool;
 
+const KEY_REVOCATION_COLUMNS: &str =
+    "sequence, key_id, account_id, public_key, ic_principal, revoked_at";
+
+/// Rejected requests kept per account; older ones are dropped as new ones
+/// arrive.
+pub const MAX_RECORDED_FAILURES: i64 = 50;
+
 pub struct SignatureAuditParams<'a> {
     pub audit_id: &'a str,
     pub account_id: Option<&'a str>,
@@ -169,11 +176,108 @@ impl AccountRepository {
         Ok(())
     }
 
+    /// Most recent audit entries for an account, newest first
+    pub async fn recent_operations(
+        &self,
+        account_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AccountOperation>, sqlx::Error> {
+        sqlx::query_as::<_, AccountOperation>(
+            r#"
+            SELECT id, action, public_key, is_admin_action, created_at
+            FROM signature_audit
+            WHERE account_id = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(account_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Stores a rejected request, keeping only the account's latest
+    /// [`MAX_RECORDED_FAILURES`].
+    pub async fn record_failure(
+        &self,
+        account_id: &str,
+        action: &str,
+        status: u16,
+        reason: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO account_failures (id, account_id, action, status, reason, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(account_id)
+        .bind(action)
+        .bind(i64::from(status))
+        .bind(reason)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM account_failures
+             WHERE account_id = ?1 AND id NOT IN (
+                 SELECT id FROM account_failures WHERE account_id = ?1
+                 ORDER BY created_at DESC, rowid DESC LIMIT ?2
+             )",
+        )
+        .bind(account_id)
+        .bind(MAX_RECORDED_FAILURES)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Most recent rejected requests for an account, newest first
+    pub async fn recent_failures(
+        &self,
+        account_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AccountFailure>, sqlx::Error> {
+        sqlx::query_as::<_, AccountFailure>(
+            "SELECT id, action, status, reason, created_at FROM account_failures
+             WHERE account_id = ?1
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?2",
+        )
+        .bind(account_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Number of audited `action` requests signed by `public_key` since
+    /// `since` (RFC 3339)
+    pub async fn count_signed_actions_since(
+        &self,
+        action: &str,
+        public_key: &str,
+        since: &str,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM signature_audit
+            WHERE action = ? AND public_key = ? AND created_at >= ?
+            "#,
+        )
+        .bind(action)
+        .bind(public_key)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+    }
+
     /// Finds account by username
     pub async fn find_by_username(&self, username: &str) -> Result<Option<Account>, sqlx::Error> {
         let account = sqlx::query_as::<_, Account>(
             r#"
-            SELECT id, username, display_name, contact_email, contact_telegram, contact_twitter, contact_discord, website_url, bio, created_at, updated_at
+            SELECT id, username, display_name, contact_email, contact_telegram, contact_twitter, contact_discord, website_url, bio, tier, created_at, updated_at
             FROM accounts
             WHERE username = ?
             "#,
@@ -189,7 +293,7 @@ impl AccountRepository {
     pub async fn find_by_id(&self, account_id: &str) -> Result<Option<Account>, sqlx::Error> {
         let account = sqlx::query_as::<_, Account>(
             r#"
-            SELECT id, username, display_name, contact_email, contact_telegram, contact_twitter, contact_discord, website_url, bio, created_at, updated_at
+            SELECT id, username, display_name, contact_email, contact_telegram, contact_twitter, contact_discord, website_url, bio, tier, created_at, updated_at
             FROM accounts
             WHERE id = ?
             "#,
@@ -291,13 +395,15 @@ impl AccountRepository {
         Ok(key)
     }
 
-    /// Disables a public key (soft delete)
+    /// Disables a public key (soft delete) and appends it to the key
+    /// revocation list. Disabling it again leaves its list entry alone.
     pub async fn disable_key(
         &self,
         key_id: &str,
         disabled_by_key_id: &str,
         now: &str,
     ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
         sqlx::query(
             r#"
             UPDATE account_public_keys
@@ -308,9 +414,86 @@ impl AccountRepository {
         .bind(now)
         .bind(disabled_by_key_id)
         .bind(key_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
+        sqlx::query(
+            r#"
+            INSERT INTO key_revocations (key_id, account_id, public_key, ic_principal, revoked_at)
+            SELECT id, account_id, public_key, ic_principal, ?2
+            FROM account_public_keys WHERE id = ?1
+            ON CONFLICT (key_id) DO NOTHING
+            "#,
+        )
+        .bind(key_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// The account's revoked keys, in revocation order.
+    pub async fn find_revocations_by_account(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<KeyRevocation>, sqlx::Error> {
+        sqlx::query_as::<_, KeyRevocation>(&format!(
+            "SELECT {KEY_REVOCATION_COLUMNS} FROM key_revocations
+             WHERE account_id = ?1 ORDER BY sequence"
+        ))
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Up to `limit` revocations with a sequence above `since`, in order.
+    pub async fn find_revocations_since(
+        &self,
+        since: i64,
+        limit: i64,
+    ) -> Result<Vec<KeyRevocation>, sqlx::Error> {
+        sqlx::query_as::<_, KeyRevocation>(&format!(
+            "SELECT {KEY_REVOCATION_COLUMNS} FROM key_revocations
+             WHERE sequence > ?1 ORDER BY sequence LIMIT ?2"
+        ))
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The highest revocation sequence handed out so far, 0 when none.
+    pub async fn latest_revocation_sequence(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COALESCE(MAX(sequence), 0) FROM key_revocations")
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Sets (`Some(timestamp)`) or lifts (`None`) an account's shadow-ban.
+    pub async fn set_shadow_banned_at(
+        &self,
+        account_id: &str,
+        shadow_banned_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE accounts SET shadow_banned_at = ? WHERE id = ?")
+            .bind(shadow_banned_at)
+            .bind(account_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Moves an account to `tier` (see [`crate::quotas::AccountTier`]).
+    pub async fn set_tier(&self, account_id: &str, tier: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE accounts SET tier = ? WHERE id = ?")
+            .bind(tier)
+            .bind(account_id)
+            .execute(&self.pool)
+            .await?;
+
         Ok(())
     }
 }
@@ -0,0 +1,69 @@
+use crate::account_settings::{DefaultVisibility, SignatureScheme};
+use crate::models::AccountSettings;
+use sqlx::SqlitePool;
+
+pub struct AccountSettingsRepository {
+    pool: SqlitePool,
+}
+
+impl AccountSettingsRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// The stored settings, `None` when the account has the defaults.
+    pub async fn find(&self, account_id: &str) -> Result<Option<AccountSettings>, sqlx::Error> {
+        let row: Option<(String, String, String, String)> = sqlx::query_as(
+            "SELECT default_visibility, required_signature_scheme, muted_events, updated_at
+             FROM account_settings WHERE account_id = ?1",
+        )
+        .bind(account_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(
+            row.map(|(visibility, scheme, muted, updated_at)| AccountSettings {
+                default_visibility: DefaultVisibility::parse(&visibility).unwrap_or_default(),
+                required_signature_scheme: SignatureScheme::parse(&scheme).unwrap_or_default(),
+                muted_events: serde_json::from_str(&muted).unwrap_or_default(),
+                updated_at: Some(updated_at),
+            }),
+        )
+    }
+
+    /// The scheme keys signing for the account must use.
+    pub async fn required_scheme(&self, account_id: &str) -> Result<SignatureScheme, sqlx::Error> {
+        Ok(self
+            .find(account_id)
+            .await?
+            .map(|settings| settings.required_signature_scheme)
+            .unwrap_or_default())
+    }
+
+    pub async fn upsert(
+        &self,
+        account_id: &str,
+        settings: &AccountSettings,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        let muted = serde_json::to_string(&settings.muted_events)
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+        sqlx::query(
+            "INSERT INTO account_settings
+                 (account_id, default_visibility, required_signature_scheme, muted_events, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(account_id) DO UPDATE SET
+                 default_visibility = excluded.default_visibility,
+                 required_signature_scheme = excluded.required_signature_scheme,
+                 muted_events = excluded.muted_events,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(account_id)
+        .bind(settings.default_visibility.as_str())
+        .bind(settings.required_signature_scheme.as_str())
+        .bind(muted)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use crate::models::{ActivityEntry, ActivityKind};
+use sqlx::SqlitePool;
+
+/// Columns of [`ActivityEntry`] over `account_activity AS a`, joined to the
+/// script and (for reviews) the review it is about.
+const ACTIVITY_SELECT: &str = "SELECT a.id, a.account_id, a.kind, a.script_id,
+        s.slug AS script_slug, s.title AS script_title, a.version, a.review_id,
+        r.rating, r.comment, a.created_at
+    FROM account_activity AS a
+    JOIN scripts AS s ON s.id = a.script_id
+    LEFT JOIN reviews AS r ON r.id = a.review_id";
+
+/// What of the log is public: entries about live public scripts, reviews
+/// that are not held for moderation, and nothing by shadow-banned accounts.
+const ACTIVITY_VISIBLE: &str = "s.deleted_at IS NULL AND s.is_public = 1
+    AND (a.review_id IS NULL OR (r.id IS NOT NULL AND r.quarantined_at IS NULL))
+    AND NOT EXISTS (SELECT 1 FROM accounts AS banned
+                    WHERE banned.id = a.account_id AND banned.shadow_banned_at IS NOT NULL)";
+
+pub struct ActivityRepository {
+    pool: SqlitePool,
+}
+
+impl ActivityRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        account_id: &str,
+        kind: ActivityKind,
+        script_id: &str,
+        version: Option<&str>,
+        review_id: Option<&str>,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO account_activity (account_id, kind, script_id, version, review_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(account_id)
+        .bind(kind.as_str())
+        .bind(script_id)
+        .bind(version)
+        .bind(review_id)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The account's public activity, newest first.
+    pub async fn find_by_account(
+        &self,
+        account_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ActivityEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ActivityEntry>(&format!(
+            "{ACTIVITY_SELECT} WHERE a.account_id = ?1 AND {ACTIVITY_VISIBLE}
+             ORDER BY a.id DESC LIMIT ?2 OFFSET ?3"
+        ))
+        .bind(account_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Script publications and version releases by the authors
+    /// `follower_account_id` follows, newest first.
+    pub async fn find_followed_by(
+        &self,
+        follower_account_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ActivityEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ActivityEntry>(&format!(
+            "{ACTIVITY_SELECT}
+             JOIN follows AS f ON f.followed_account_id = a.account_id
+             WHERE f.follower_account_id = ?1 AND a.kind IN (?2, ?3) AND {ACTIVITY_VISIBLE}
+             ORDER BY a.id DESC LIMIT ?4 OFFSET ?5"
+        ))
+        .bind(follower_account_id)
+        .bind(ActivityKind::ScriptPublished.as_str())
+        .bind(ActivityKind::VersionReleased.as_str())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Logs the public scripts and reviews that predate the activity log,
+    /// oldest first, so profiles do not start out empty. Returns how many
+    /// entries it added; run at startup, a no-op once caught up.
+    pub async fn backfill(&self) -> Result<u64, sqlx::Error> {
+        let scripts = sqlx::query(
+            "INSERT INTO account_activity (account_id, kind, script_id, version, created_at)
+             SELECT owner_account_id,
+                    CASE WHEN EXISTS (SELECT 1 FROM scripts AS older
+                                      WHERE older.slug = scripts.slug
+                                        AND older.created_at < scripts.created_at)
+                         THEN ?2 ELSE ?1 END,
+                    id, version, created_at
+             FROM scripts
+             WHERE owner_account_id IS NOT NULL AND is_public = 1 AND deleted_at IS NULL
+               AND NOT EXISTS (SELECT 1 FROM account_activity AS a
+                               WHERE a.script_id = scripts.id AND a.review_id IS NULL)
+             ORDER BY created_at, id",
+        )
+        .bind(ActivityKind::ScriptPublished.as_str())
+        .bind(ActivityKind::VersionReleased.as_str())
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        let reviews = sqlx::query(
+            "INSERT INTO account_activity (account_id, kind, script_id, review_id, created_at)
+             SELECT user_id, ?1, script_id, id, created_at
+             FROM reviews
+             WHERE NOT EXISTS (SELECT 1 FROM account_activity AS a
+                               WHERE a.script_id = reviews.script_id AND a.review_id = reviews.id)
+             ORDER BY created_at, id",
+        )
+        .bind(ActivityKind::ReviewPosted.as_str())
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(scripts + reviews)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::models::ProductBundle;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+const BUNDLE_COLUMNS: &str =
+    "id, owner_account_id, title, description, price_e8s, currency, created_at, updated_at";
+
+pub struct BundleRepository {
+    pool: SqlitePool,
+}
+
+impl BundleRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts the bundle and its members, in `script_ids` order.
+    pub async fn create(
+        &self,
+        bundle: &ProductBundle,
+        script_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO product_bundles
+                 (id, owner_account_id, title, description, price_e8s, currency, created_at,
+                  updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
+        .bind(&bundle.id)
+        .bind(&bundle.owner_account_id)
+        .bind(&bundle.title)
+        .bind(&bundle.description)
+        .bind(bundle.price_e8s)
+        .bind(&bundle.currency)
+        .bind(&bundle.created_at)
+        .bind(&bundle.updated_at)
+        .execute(&mut *tx)
+        .await?;
+        insert_members(&mut tx, &bundle.id, script_ids).await?;
+        tx.commit().await
+    }
+
+    /// Saves the bundle's fields and, when `script_ids` is given, replaces
+    /// its members.
+    pub async fn update(
+        &self,
+        bundle: &ProductBundle,
+        script_ids: Option<&[String]>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE product_bundles
+             SET title = ?1, description = ?2, price_e8s = ?3, currency = ?4, updated_at = ?5
+             WHERE id = ?6",
+        )
+        .bind(&bundle.title)
+        .bind(&bundle.description)
+        .bind(bundle.price_e8s)
+        .bind(&bundle.currency)
+        .bind(&bundle.updated_at)
+        .bind(&bundle.id)
+        .execute(&mut *tx)
+        .await?;
+        if let Some(script_ids) = script_ids {
+            sqlx::query("DELETE FROM product_bundle_scripts WHERE bundle_id = ?1")
+                .bind(&bundle.id)
+                .execute(&mut *tx)
+                .await?;
+            insert_members(&mut tx, &bundle.id, script_ids).await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM product_bundle_scripts WHERE bundle_id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let removed = sqlx::query("DELETE FROM product_bundles WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok(removed > 0)
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<ProductBundle>, sqlx::Error> {
+        sqlx::query_as::<_, ProductBundle>(&format!(
+            "SELECT {BUNDLE_COLUMNS} FROM product_bundles WHERE id = ?1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Newest bundles first.
+    pub async fn find_all(
+        &self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<ProductBundle>, sqlx::Error> {
+        sqlx::query_as::<_, ProductBundle>(&format!(
+            "SELECT {BUNDLE_COLUMNS} FROM product_bundles
+             ORDER BY created_at DESC, id LIMIT ?1 OFFSET ?2"
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn count(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM product_bundles")
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Bundles that include the script, newest first.
+    pub async fn find_containing(
+        &self,
+        script_id: &str,
+    ) -> Result<Vec<ProductBundle>, sqlx::Error> {
+        sqlx::query_as::<_, ProductBundle>(&format!(
+            "SELECT {BUNDLE_COLUMNS} FROM product_bundles
+             WHERE id IN (SELECT bundle_id FROM product_bundle_scripts WHERE script_id = ?1)
+             ORDER BY created_at DESC, id"
+        ))
+        .bind(script_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Member script ids in bundle order.
+    pub async fn script_ids(&self, bundle_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT script_id FROM product_bundle_scripts WHERE bundle_id = ?1 ORDER BY position",
+        )
+        .bind(bundle_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+async fn insert_members(
+    tx: &mut Transaction<'_, Sqlite>,
+    bundle_id: &str,
+    script_ids: &[String],
+) -> Result<(), sqlx::Error> {
+    for (position, script_id) in script_ids.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO product_bundle_scripts (bundle_id, script_id, position)
+             VALUES (?1, ?2, ?3)",
+        )
+        .bind(bundle_id)
+        .bind(script_id)
+        .bind(position as i64)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
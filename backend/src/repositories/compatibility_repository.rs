@@ -0,0 +1,84 @@
+use sqlx::SqlitePool;
+
+/// Leaves votes by shadow-banned accounts out of every tally, like their
+/// reviews.
+const NOT_SHADOW_BANNED: &str = "NOT EXISTS (SELECT 1 FROM accounts AS banned WHERE banned.id = compatibility_votes.account_id AND banned.shadow_banned_at IS NOT NULL)";
+
+pub struct CompatibilityRepository {
+    pool: SqlitePool,
+}
+
+impl CompatibilityRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Records the account's vote, replacing an earlier one for the same
+    /// app version and canister. `canister_id` is `""` for none.
+    pub async fn upsert_vote(
+        &self,
+        script_id: &str,
+        account_id: &str,
+        app_version: &str,
+        canister_id: &str,
+        works: bool,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO compatibility_votes
+                 (script_id, account_id, app_version, canister_id, works, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(script_id, account_id, app_version, canister_id)
+             DO UPDATE SET works = excluded.works, updated_at = excluded.updated_at",
+        )
+        .bind(script_id)
+        .bind(account_id)
+        .bind(app_version)
+        .bind(canister_id)
+        .bind(works)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The script's votes as `(app_version, canister_id, works, broken)`.
+    pub async fn tallies(
+        &self,
+        script_id: &str,
+    ) -> Result<Vec<(String, String, i64, i64)>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            "SELECT app_version, canister_id,
+                    SUM(CASE WHEN works THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN works THEN 0 ELSE 1 END)
+             FROM compatibility_votes
+             WHERE script_id = ?1 AND {NOT_SHADOW_BANNED}
+             GROUP BY app_version, canister_id"
+        ))
+        .bind(script_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// `(script_id, works, votes)` of each listed script with votes.
+    pub async fn totals_for(
+        &self,
+        script_ids: &[&str],
+    ) -> Result<Vec<(String, i64, i64)>, sqlx::Error> {
+        if script_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; script_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT script_id, SUM(CASE WHEN works THEN 1 ELSE 0 END), COUNT(*)
+             FROM compatibility_votes
+             WHERE script_id IN ({placeholders}) AND {NOT_SHADOW_BANNED}
+             GROUP BY script_id"
+        );
+        let mut query = sqlx::query_as(&sql);
+        for id in script_ids {
+            query = query.bind(*id);
+        }
+        query.fetch_all(&self.pool).await
+    }
+}
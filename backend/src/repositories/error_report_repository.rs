@@ -0,0 +1,122 @@
+use sqlx::SqlitePool;
+
+use crate::models::{ScriptErrorReport, VersionFlag};
+
+const REPORT_COLUMNS: &str = "version, stack_hash, error_class, message, stack, engine_version,
+     occurrences, first_seen_at, last_seen_at";
+
+pub struct ErrorReportRepository {
+    pool: SqlitePool,
+}
+
+/// A report about to be stored; `stack` is one frame per line.
+pub struct NewErrorReport<'a> {
+    pub script_id: &'a str,
+    pub version: &'a str,
+    pub stack_hash: &'a str,
+    pub error_class: &'a str,
+    pub message: &'a str,
+    pub stack: &'a str,
+    pub engine_version: &'a str,
+}
+
+impl ErrorReportRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores a report, or counts one more occurrence of a known stack.
+    pub async fn record(&self, report: &NewErrorReport<'_>, now: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO script_error_reports
+                 (script_id, version, stack_hash, error_class, message, stack, engine_version,
+                  occurrences, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8, ?8)
+             ON CONFLICT(script_id, version, stack_hash) DO UPDATE SET
+                 occurrences = occurrences + 1,
+                 engine_version = excluded.engine_version,
+                 last_seen_at = excluded.last_seen_at",
+        )
+        .bind(report.script_id)
+        .bind(report.version)
+        .bind(report.stack_hash)
+        .bind(report.error_class)
+        .bind(report.message)
+        .bind(report.stack)
+        .bind(report.engine_version)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The script's reports, most recently seen first, optionally of one
+    /// version.
+    pub async fn find_by_script(
+        &self,
+        script_id: &str,
+        version: Option<&str>,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<ScriptErrorReport>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            "SELECT {REPORT_COLUMNS} FROM script_error_reports
+             WHERE script_id = ?1 AND (?2 IS NULL OR version = ?2)
+             ORDER BY last_seen_at DESC, occurrences DESC
+             LIMIT ?3 OFFSET ?4"
+        ))
+        .bind(script_id)
+        .bind(version)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn count_by_script(
+        &self,
+        script_id: &str,
+        version: Option<&str>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM script_error_reports
+             WHERE script_id = ?1 AND (?2 IS NULL OR version = ?2)",
+        )
+        .bind(script_id)
+        .bind(version)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Flags a version; `false` if it already was.
+    pub async fn flag_version(
+        &self,
+        script_id: &str,
+        version: &str,
+        reason: &str,
+        now: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO script_version_flags (script_id, version, reason, flagged_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(script_id, version) DO NOTHING",
+        )
+        .bind(script_id)
+        .bind(version)
+        .bind(reason)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn flags_by_script(&self, script_id: &str) -> Result<Vec<VersionFlag>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT version, reason, flagged_at FROM script_version_flags
+             WHERE script_id = ?1 ORDER BY flagged_at DESC",
+        )
+        .bind(script_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,59 @@
+use sqlx::SqlitePool;
+
+pub struct FollowRepository {
+    pool: SqlitePool,
+}
+
+impl FollowRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Returns whether a new follow was recorded (`false` when `follower`
+    /// already follows `followed`).
+    pub async fn follow(
+        &self,
+        follower_account_id: &str,
+        followed_account_id: &str,
+        now: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO follows (follower_account_id, followed_account_id, created_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(follower_account_id, followed_account_id) DO NOTHING",
+        )
+        .bind(follower_account_id)
+        .bind(followed_account_id)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns whether a follow was removed.
+    pub async fn unfollow(
+        &self,
+        follower_account_id: &str,
+        followed_account_id: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM follows WHERE follower_account_id = ?1 AND followed_account_id = ?2",
+        )
+        .bind(follower_account_id)
+        .bind(followed_account_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// How many accounts follow `account_id`, and how many it follows.
+    pub async fn counts(&self, account_id: &str) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM follows WHERE followed_account_id = ?1),
+                    (SELECT COUNT(*) FROM follows WHERE follower_account_id = ?1)",
+        )
+        .bind(account_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,135 @@
+use super::review_repository::{NOT_QUARANTINED, NOT_SHADOW_BANNED};
+use crate::models::IntegrityReport;
+use sqlx::SqlitePool;
+
+/// A live script's stored rating aggregates next to what its visible
+/// reviews add up to.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ScriptAggregates {
+    pub id: String,
+    pub review_count: i32,
+    pub rating: f64,
+    pub weighted_rating: f64,
+    pub visible_count: i32,
+    pub visible_average: f64,
+}
+
+pub struct IntegrityRepository {
+    pool: SqlitePool,
+}
+
+impl IntegrityRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Reviews whose script or author is gone: `(id, script_id, detail)`.
+    pub async fn orphaned_reviews(&self) -> Result<Vec<(String, String, String)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT reviews.id, reviews.script_id,
+                    CASE WHEN scripts.id IS NULL
+                         THEN 'script ' || reviews.script_id || ' does not exist'
+                         ELSE 'author ' || reviews.user_id || ' does not exist' END
+             FROM reviews LEFT JOIN scripts ON scripts.id = reviews.script_id
+             WHERE scripts.id IS NULL
+                OR NOT EXISTS (SELECT 1 FROM accounts WHERE accounts.id = reviews.user_id)
+             ORDER BY reviews.id",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Live scripts whose owner account is gone: `(id, owner_account_id)`.
+    pub async fn orphaned_scripts(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT id, owner_account_id FROM scripts
+             WHERE deleted_at IS NULL AND owner_account_id IS NOT NULL
+               AND NOT EXISTS (SELECT 1 FROM accounts WHERE accounts.id = scripts.owner_account_id)
+             ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The stored tag list of every live script that has one.
+    pub async fn script_tags(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT id, tags FROM scripts
+             WHERE deleted_at IS NULL AND tags IS NOT NULL
+             ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Stored and recomputed aggregates of every live script, counting the
+    /// reviews `ReviewRepository::refresh_script_stats` counts.
+    pub async fn script_aggregates(&self) -> Result<Vec<ScriptAggregates>, sqlx::Error> {
+        let sql = format!(
+            "WITH visible AS (
+                 SELECT script_id, COUNT(*) AS n, AVG(rating) AS average FROM reviews
+                 WHERE {NOT_SHADOW_BANNED} AND {NOT_QUARANTINED}
+                 GROUP BY script_id
+             )
+             SELECT scripts.id, scripts.review_count, scripts.rating, scripts.weighted_rating,
+                    COALESCE(visible.n, 0) AS visible_count,
+                    COALESCE(visible.average, 0.0) AS visible_average
+             FROM scripts LEFT JOIN visible ON visible.script_id = scripts.id
+             WHERE scripts.deleted_at IS NULL
+             ORDER BY scripts.id"
+        );
+        sqlx::query_as::<_, ScriptAggregates>(&sql)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn delete_review(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM reviews WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Stores a script's tag list; `None` clears it.
+    pub async fn set_tags(&self, script_id: &str, tags: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scripts SET tags = ?1 WHERE id = ?2")
+            .bind(tags)
+            .bind(script_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn save_report(&self, report: &IntegrityReport) -> Result<(), sqlx::Error> {
+        let json = serde_json::to_string(report).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+        sqlx::query(
+            "INSERT INTO integrity_reports (id, trigger, repaired, issue_count, report, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(&report.id)
+        .bind(&report.trigger)
+        .bind(report.repaired)
+        .bind(report.issue_count)
+        .bind(json)
+        .bind(&report.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The latest `limit` reports, newest first.
+    pub async fn recent_reports(&self, limit: i64) -> Result<Vec<IntegrityReport>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT report FROM integrity_reports ORDER BY created_at DESC, id DESC LIMIT ?1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|(json,)| {
+                serde_json::from_str(&json).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,35 @@
+use crate::models::MaintenanceState;
+use sqlx::SqlitePool;
+
+pub struct MaintenanceRepository {
+    pool: SqlitePool,
+}
+
+impl MaintenanceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get(&self) -> Result<MaintenanceState, sqlx::Error> {
+        sqlx::query_as::<_, MaintenanceState>(
+            "SELECT enabled, message, retry_after_secs, updated_at FROM maintenance_mode WHERE id = 1",
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn set(&self, state: &MaintenanceState) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE maintenance_mode
+             SET enabled = ?1, message = ?2, retry_after_secs = ?3, updated_at = ?4
+             WHERE id = 1",
+        )
+        .bind(state.enabled)
+        .bind(&state.message)
+        .bind(state.retry_after_secs)
+        .bind(&state.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
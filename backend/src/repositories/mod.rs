@@ -1,11 +1,46 @@
 mod account_repository;
+mod account_settings_repository;
+mod activity_repository;
+mod bundle_repository;
+mod compatibility_repository;
+mod error_report_repository;
+mod follow_repository;
+mod integrity_repository;
+mod maintenance_repository;
+mod moderation_repository;
 mod passkey_repository;
+mod promotion_repository;
+mod purchase_repository;
+mod question_repository;
 mod review_repository;
 mod script_repository;
+mod search_experiment_repository;
+mod search_log_repository;
+mod telemetry_repository;
+mod validation_repository;
+mod webhook_repository;
 
 pub use account_repository::{
     AccountRepository, CreateAccountParams, SignatureAuditParams, UpdateAccountParams,
+    MAX_RECORDED_FAILURES,
 };
+pub use account_settings_repository::AccountSettingsRepository;
+pub use activity_repository::ActivityRepository;
+pub use bundle_repository::BundleRepository;
+pub use compatibility_repository::CompatibilityRepository;
+pub use error_report_repository::{ErrorReportRepository, NewErrorReport};
+pub use follow_repository::FollowRepository;
+pub use integrity_repository::{IntegrityRepository, ScriptAggregates};
+pub use maintenance_repository::MaintenanceRepository;
+pub use moderation_repository::ModerationRepository;
 pub use passkey_repository::PasskeyRepository;
+pub use promotion_repository::PromotionRepository;
+pub use purchase_repository::{PurchaseRepository, StatusUpdate, Transition};
+pub use question_repository::QuestionRepository;
 pub use review_repository::ReviewRepository;
-pub use script_repository::ScriptRepository;
+pub use script_repository::{weighted_rating, ScriptRepository};
+pub use search_experiment_repository::SearchExperimentRepository;
+pub use search_log_repository::SearchLogRepository;
+pub use telemetry_repository::TelemetryRepository;
+pub use validation_repository::ValidationRepository;
+pub use webhook_repository::WebhookRepository;
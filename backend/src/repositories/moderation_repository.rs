@@ -0,0 +1,114 @@
+use sqlx::SqlitePool;
+
+use crate::models::{AccountStrike, ModerationNote};
+use crate::moderation::Restriction;
+
+pub struct ModerationRepository {
+    pool: SqlitePool,
+}
+
+impl ModerationRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn add_note(
+        &self,
+        id: &str,
+        account_id: &str,
+        note: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO moderation_notes (id, account_id, note, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(id)
+        .bind(account_id)
+        .bind(note)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn add_strike(
+        &self,
+        id: &str,
+        account_id: &str,
+        reason: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO account_strikes (id, account_id, reason, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(id)
+        .bind(account_id)
+        .bind(reason)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Revokes an active strike of the account; `false` if there is none
+    /// with that id.
+    pub async fn revoke_strike(
+        &self,
+        account_id: &str,
+        strike_id: &str,
+        reason: &str,
+        now: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE account_strikes SET revoked_at = ?1, revoke_reason = ?2
+             WHERE id = ?3 AND account_id = ?4 AND revoked_at IS NULL",
+        )
+        .bind(now)
+        .bind(reason)
+        .bind(strike_id)
+        .bind(account_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Newest first.
+    pub async fn notes(&self, account_id: &str) -> Result<Vec<ModerationNote>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT id, note, created_at FROM moderation_notes
+             WHERE account_id = ?1 ORDER BY created_at DESC",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Newest first, revoked ones included.
+    pub async fn strikes(&self, account_id: &str) -> Result<Vec<AccountStrike>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT id, reason, created_at, revoked_at, revoke_reason FROM account_strikes
+             WHERE account_id = ?1 ORDER BY created_at DESC",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn active_strikes(&self, account_id: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM account_strikes WHERE account_id = ?1 AND revoked_at IS NULL",
+        )
+        .bind(account_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The restriction the account's active strikes put on it.
+    pub async fn restriction(&self, account_id: &str) -> Result<Restriction, sqlx::Error> {
+        Ok(Restriction::for_strikes(
+            self.active_strikes(account_id).await?,
+        ))
+    }
+}
@@ -0,0 +1,93 @@
+use crate::models::Promotion;
+use sqlx::SqlitePool;
+
+const PROMOTION_COLUMNS: &str =
+    "id, script_id, kind, amount, currency, starts_at, ends_at, created_at";
+
+pub struct PromotionRepository {
+    pool: SqlitePool,
+}
+
+impl PromotionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, promotion: &Promotion) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO promotions (id, script_id, kind, amount, currency, starts_at, ends_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
+        .bind(&promotion.id)
+        .bind(&promotion.script_id)
+        .bind(&promotion.kind)
+        .bind(promotion.amount)
+        .bind(&promotion.currency)
+        .bind(&promotion.starts_at)
+        .bind(&promotion.ends_at)
+        .bind(&promotion.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Every promotion of the script, past ones included, oldest first.
+    pub async fn find_by_script(&self, script_id: &str) -> Result<Vec<Promotion>, sqlx::Error> {
+        sqlx::query_as::<_, Promotion>(&format!(
+            "SELECT {PROMOTION_COLUMNS} FROM promotions WHERE script_id = ?1 ORDER BY starts_at, id"
+        ))
+        .bind(script_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Promotions of the script whose window intersects `[starts_at, ends_at)`.
+    pub async fn count_overlapping(
+        &self,
+        script_id: &str,
+        starts_at: &str,
+        ends_at: &str,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM promotions
+             WHERE script_id = ?1 AND starts_at < ?3 AND ends_at > ?2",
+        )
+        .bind(script_id)
+        .bind(starts_at)
+        .bind(ends_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The promotions running at `now` for any of `script_ids`. Windows never
+    /// overlap, so there is at most one per script.
+    pub async fn find_running(
+        &self,
+        script_ids: &[&str],
+        now: &str,
+    ) -> Result<Vec<Promotion>, sqlx::Error> {
+        if script_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; script_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT {PROMOTION_COLUMNS} FROM promotions
+             WHERE starts_at <= ? AND ends_at > ? AND script_id IN ({placeholders})"
+        );
+        let mut query = sqlx::query_as::<_, Promotion>(&sql).bind(now).bind(now);
+        for id in script_ids {
+            query = query.bind(*id);
+        }
+        query.fetch_all(&self.pool).await
+    }
+
+    /// Returns whether a promotion was removed.
+    pub async fn delete(&self, script_id: &str, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM promotions WHERE id = ?1 AND script_id = ?2")
+            .bind(id)
+            .bind(script_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,219 @@
+use crate::models::{Purchase, PurchaseStatusChange};
+use sqlx::SqlitePool;
+
+const PURCHASE_COLUMNS: &str = "id, account_id, script_id, usd_amount, currency, status, paid_at, \
+     dispute_reason, disputed_at, refunded_at, refund_block_index, refund_amount_e8s, bundle_id";
+
+/// Column changes that go with a status transition.
+pub enum StatusUpdate<'a> {
+    Dispute { reason: &'a str },
+    Reopen,
+    Refund { block_index: i64, amount_e8s: i64 },
+}
+
+/// A status change to apply only if the purchase is still in `from`.
+pub struct Transition<'a> {
+    pub purchase_id: &'a str,
+    pub from: &'a str,
+    pub to: &'a str,
+    pub update: StatusUpdate<'a>,
+    pub actor: &'a str,
+    pub note: Option<&'a str>,
+    pub now: &'a str,
+}
+
+pub struct PurchaseRepository {
+    pool: SqlitePool,
+}
+
+impl PurchaseRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Purchase>, sqlx::Error> {
+        sqlx::query_as::<_, Purchase>(&format!(
+            "SELECT {PURCHASE_COLUMNS} FROM purchases WHERE id = ?1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_account_and_script(
+        &self,
+        account_id: &str,
+        script_id: &str,
+    ) -> Result<Option<Purchase>, sqlx::Error> {
+        sqlx::query_as::<_, Purchase>(&format!(
+            "SELECT {PURCHASE_COLUMNS} FROM purchases WHERE account_id = ?1 AND script_id = ?2"
+        ))
+        .bind(account_id)
+        .bind(script_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// The account's purchases of any of `script_ids`, whatever their status.
+    pub async fn find_by_account_and_scripts(
+        &self,
+        account_id: &str,
+        script_ids: &[String],
+    ) -> Result<Vec<Purchase>, sqlx::Error> {
+        if script_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; script_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT {PURCHASE_COLUMNS} FROM purchases
+             WHERE account_id = ? AND script_id IN ({placeholders})"
+        );
+        let mut query = sqlx::query_as::<_, Purchase>(&sql).bind(account_id);
+        for id in script_ids {
+            query = query.bind(id);
+        }
+        query.fetch_all(&self.pool).await
+    }
+
+    /// The account's purchases, newest first, whatever their status.
+    pub async fn find_by_account(
+        &self,
+        account_id: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<Purchase>, sqlx::Error> {
+        sqlx::query_as::<_, Purchase>(&format!(
+            "SELECT {PURCHASE_COLUMNS} FROM purchases WHERE account_id = ?1
+             ORDER BY paid_at DESC, id LIMIT ?2 OFFSET ?3"
+        ))
+        .bind(account_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Purchases in `status`, longest-disputed first.
+    pub async fn find_by_status(
+        &self,
+        status: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<Purchase>, sqlx::Error> {
+        sqlx::query_as::<_, Purchase>(&format!(
+            "SELECT {PURCHASE_COLUMNS} FROM purchases WHERE status = ?1
+             ORDER BY disputed_at, id LIMIT ?2 OFFSET ?3"
+        ))
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Entitles `account_id` to each of `script_ids` as part of bundle
+    /// `bundle_id`. Scripts the account already holds a purchase of are left
+    /// alone. Returns the number of new entitlements.
+    pub async fn grant_bundle(
+        &self,
+        account_id: &str,
+        bundle_id: &str,
+        currency: &str,
+        script_ids: &[String],
+        now: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut granted = 0;
+        for script_id in script_ids {
+            granted += sqlx::query(
+                "INSERT INTO purchases
+                     (id, account_id, script_id, usd_amount, currency, status, paid_at,
+                      created_at, bundle_id)
+                 VALUES (?1, ?2, ?3, 0, ?4, 'completed', ?5, ?5, ?6)
+                 ON CONFLICT (account_id, script_id) DO NOTHING",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(account_id)
+            .bind(script_id)
+            .bind(currency)
+            .bind(now)
+            .bind(bundle_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(granted)
+    }
+
+    pub async fn history(
+        &self,
+        purchase_id: &str,
+    ) -> Result<Vec<PurchaseStatusChange>, sqlx::Error> {
+        sqlx::query_as::<_, PurchaseStatusChange>(
+            "SELECT id, purchase_id, from_status, to_status, actor, note, created_at
+             FROM purchase_status_history WHERE purchase_id = ?1 ORDER BY created_at, id",
+        )
+        .bind(purchase_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Applies the transition and appends it to `purchase_status_history` in
+    /// one transaction. Returns `false`, changing nothing, when the purchase
+    /// is no longer in `transition.from`.
+    pub async fn transition(&self, transition: Transition<'_>) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let update = match transition.update {
+            StatusUpdate::Dispute { reason } => sqlx::query(
+                "UPDATE purchases SET status = ?1, dispute_reason = ?2, disputed_at = ?3
+                 WHERE id = ?4 AND status = ?5",
+            )
+            .bind(transition.to)
+            .bind(reason)
+            .bind(transition.now),
+            StatusUpdate::Reopen => sqlx::query(
+                "UPDATE purchases SET status = ?1, dispute_reason = NULL, disputed_at = NULL
+                 WHERE id = ?2 AND status = ?3",
+            )
+            .bind(transition.to),
+            StatusUpdate::Refund {
+                block_index,
+                amount_e8s,
+            } => sqlx::query(
+                "UPDATE purchases SET status = ?1, refund_block_index = ?2, refund_amount_e8s = ?3,
+                        refunded_at = ?4
+                 WHERE id = ?5 AND status = ?6",
+            )
+            .bind(transition.to)
+            .bind(block_index)
+            .bind(amount_e8s)
+            .bind(transition.now),
+        };
+        let updated = update
+            .bind(transition.purchase_id)
+            .bind(transition.from)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if updated == 0 {
+            return Ok(false);
+        }
+        sqlx::query(
+            "INSERT INTO purchase_status_history
+                 (id, purchase_id, from_status, to_status, actor, note, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(transition.purchase_id)
+        .bind(transition.from)
+        .bind(transition.to)
+        .bind(transition.actor)
+        .bind(transition.note)
+        .bind(transition.now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,132 @@
+use crate::models::{ScriptQuestion, UnansweredQuestions};
+use sqlx::SqlitePool;
+
+const QUESTION_COLUMNS: &str = "script_questions.id, script_questions.script_id, script_questions.account_id, script_questions.question, script_questions.answer, script_questions.created_at, script_questions.answered_at";
+
+/// Hides questions asked by a shadow-banned account from listings and
+/// counts, like their reviews.
+const NOT_SHADOW_BANNED: &str = "NOT EXISTS (SELECT 1 FROM accounts AS banned WHERE banned.id = script_questions.account_id AND banned.shadow_banned_at IS NOT NULL)";
+
+pub struct QuestionRepository {
+    pool: SqlitePool,
+}
+
+impl QuestionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        id: &str,
+        script_id: &str,
+        account_id: &str,
+        question: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO script_questions (id, script_id, account_id, question, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(id)
+        .bind(script_id)
+        .bind(account_id)
+        .bind(question)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(
+        &self,
+        script_id: &str,
+        id: &str,
+    ) -> Result<Option<ScriptQuestion>, sqlx::Error> {
+        sqlx::query_as::<_, ScriptQuestion>(&format!(
+            "SELECT {QUESTION_COLUMNS} FROM script_questions WHERE id = ?1 AND script_id = ?2"
+        ))
+        .bind(id)
+        .bind(script_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Stores `answer`, replacing an earlier one. Returns whether the
+    /// question exists.
+    pub async fn answer(
+        &self,
+        script_id: &str,
+        id: &str,
+        answer: &str,
+        now: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE script_questions SET answer = ?3, answered_at = ?4
+             WHERE id = ?1 AND script_id = ?2",
+        )
+        .bind(id)
+        .bind(script_id)
+        .bind(answer)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Questions about the script, newest first; only the unanswered ones
+    /// with `unanswered_only`.
+    pub async fn find_by_script(
+        &self,
+        script_id: &str,
+        unanswered_only: bool,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<ScriptQuestion>, sqlx::Error> {
+        sqlx::query_as::<_, ScriptQuestion>(&format!(
+            "SELECT {QUESTION_COLUMNS} FROM script_questions
+             WHERE script_id = ?1 AND (?2 = 0 OR answer IS NULL) AND {NOT_SHADOW_BANNED}
+             ORDER BY created_at DESC, id LIMIT ?3 OFFSET ?4"
+        ))
+        .bind(script_id)
+        .bind(unanswered_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn count_by_script(
+        &self,
+        script_id: &str,
+        unanswered_only: bool,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM script_questions
+             WHERE script_id = ?1 AND (?2 = 0 OR answer IS NULL) AND {NOT_SHADOW_BANNED}"
+        ))
+        .bind(script_id)
+        .bind(unanswered_only)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The live scripts owned by `account_id` that have unanswered
+    /// questions, the one waiting longest first.
+    pub async fn unanswered_by_owner(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<UnansweredQuestions>, sqlx::Error> {
+        sqlx::query_as::<_, UnansweredQuestions>(&format!(
+            "SELECT scripts.id AS script_id, scripts.title, COUNT(*) AS count
+             FROM script_questions JOIN scripts ON scripts.id = script_questions.script_id
+             WHERE scripts.owner_account_id = ?1 AND scripts.deleted_at IS NULL
+               AND script_questions.answer IS NULL AND {NOT_SHADOW_BANNED}
+             GROUP BY scripts.id, scripts.title
+             ORDER BY MIN(script_questions.created_at), scripts.id"
+        ))
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
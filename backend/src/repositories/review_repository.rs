@@ -1,6 +1,17 @@
-use crate::models::Review;
+use super::script_repository::{RATING_PRIOR_MEAN, RATING_PRIOR_WEIGHT};
+use crate::models::{QuarantinedReview, Review};
 use sqlx::SqlitePool;
 
+/// Hides reviews written by a shadow-banned account from listings and
+/// rating aggregates. The per-user duplicate check ignores it, so a
+/// shadow-banned reviewer still cannot post twice, and listings show the
+/// reviewer their own reviews so the ban stays unnoticed.
+pub(super) const NOT_SHADOW_BANNED: &str = "NOT EXISTS (SELECT 1 FROM accounts AS banned WHERE banned.id = reviews.user_id AND banned.shadow_banned_at IS NOT NULL)";
+
+/// Hides reviews the spam heuristics held back until a moderator releases
+/// them.
+pub(super) const NOT_QUARANTINED: &str = "reviews.quarantined_at IS NULL";
+
 pub struct ReviewRepository {
     pool: SqlitePool,
 }
@@ -10,26 +21,45 @@ impl ReviewRepository {
         Self { pool }
     }
 
+    /// The visible reviews of a script, newest first, plus `viewer`'s own
+    /// even while they are shadow-banned.
     pub async fn find_by_script(
         &self,
         script_id: &str,
+        viewer: Option<&str>,
         limit: i32,
         offset: i32,
     ) -> Result<Vec<Review>, sqlx::Error> {
-        sqlx::query_as::<_, Review>(
+        let sql = format!(
             "SELECT id, script_id, user_id, rating, comment, created_at, updated_at
-             FROM reviews WHERE script_id = ?1 ORDER BY created_at DESC LIMIT ?2 OFFSET ?3",
-        )
-        .bind(script_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&self.pool)
-        .await
+             FROM reviews
+             WHERE script_id = ?1 AND ({NOT_SHADOW_BANNED} OR reviews.user_id = ?2)
+               AND {NOT_QUARANTINED}
+             ORDER BY created_at DESC LIMIT ?3 OFFSET ?4"
+        );
+        sqlx::query_as::<_, Review>(&sql)
+            .bind(script_id)
+            .bind(viewer)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
     }
 
-    pub async fn count_by_script(&self, script_id: &str) -> Result<i32, sqlx::Error> {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reviews WHERE script_id = ?1")
+    /// Counts what [`Self::find_by_script`] lists.
+    pub async fn count_by_script(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+    ) -> Result<i32, sqlx::Error> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM reviews
+             WHERE script_id = ?1 AND ({NOT_SHADOW_BANNED} OR reviews.user_id = ?2)
+               AND {NOT_QUARANTINED}"
+        );
+        let count: i64 = sqlx::query_scalar(&sql)
             .bind(script_id)
+            .bind(viewer)
             .fetch_one(&self.pool)
             .await?;
         Ok(count as i32)
@@ -47,6 +77,13 @@ impl ReviewRepository {
             .await
     }
 
+    pub async fn script_ids_reviewed_by(&self, user_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT script_id FROM reviews WHERE user_id = ?1")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     pub async fn create(
         &self,
         id: &str,
@@ -55,10 +92,51 @@ impl ReviewRepository {
         rating: i32,
         comment: Option<&str>,
         timestamp: &str,
+    ) -> Result<(), sqlx::Error> {
+        self.insert(id, script_id, user_id, rating, comment, timestamp, None)
+            .await
+    }
+
+    /// Like [`Self::create`], but the review starts out quarantined: stored,
+    /// but hidden until a moderator releases it.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_quarantined(
+        &self,
+        id: &str,
+        script_id: &str,
+        user_id: &str,
+        rating: i32,
+        comment: Option<&str>,
+        timestamp: &str,
+        reason: &str,
+    ) -> Result<(), sqlx::Error> {
+        self.insert(
+            id,
+            script_id,
+            user_id,
+            rating,
+            comment,
+            timestamp,
+            Some(reason),
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert(
+        &self,
+        id: &str,
+        script_id: &str,
+        user_id: &str,
+        rating: i32,
+        comment: Option<&str>,
+        timestamp: &str,
+        quarantine_reason: Option<&str>,
     ) -> Result<(), sqlx::Error> {
         sqlx::query(
-            "INSERT INTO reviews (id, script_id, user_id, rating, comment, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO reviews (id, script_id, user_id, rating, comment, created_at, updated_at,
+                                  quarantined_at, quarantine_reason)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, CASE WHEN ?7 IS NULL THEN NULL ELSE ?6 END, ?7)",
         )
         .bind(id)
         .bind(script_id)
@@ -66,16 +144,136 @@ impl ReviewRepository {
         .bind(rating)
         .bind(comment)
         .bind(timestamp)
-        .bind(timestamp)
+        .bind(quarantine_reason)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    /// Reviews on scripts other than `script_id` whose trimmed, lowercased
+    /// comment equals `normalized_comment`. Quarantined reviews count too.
+    pub async fn count_same_comment_elsewhere(
+        &self,
+        script_id: &str,
+        normalized_comment: &str,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM reviews
+             WHERE script_id != ?1 AND comment IS NOT NULL AND lower(trim(comment)) = ?2",
+        )
+        .bind(script_id)
+        .bind(normalized_comment)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// 5-star reviews by `user_id` if its account was created at or after
+    /// `created_since`; `None` for older accounts and for user ids without
+    /// an account row.
+    pub async fn count_five_star_if_account_created_since(
+        &self,
+        user_id: &str,
+        created_since: &str,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM reviews WHERE user_id = accounts.id AND rating = 5)
+             FROM accounts WHERE id = ?1 AND created_at >= ?2",
+        )
+        .bind(user_id)
+        .bind(created_since)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Quarantined reviews, oldest first.
+    pub async fn find_quarantined(
+        &self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<QuarantinedReview>, sqlx::Error> {
+        sqlx::query_as::<_, QuarantinedReview>(
+            "SELECT id, script_id, user_id, rating, comment, created_at, quarantined_at,
+                    quarantine_reason
+             FROM reviews WHERE quarantined_at IS NOT NULL
+             ORDER BY quarantined_at ASC LIMIT ?1 OFFSET ?2",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_quarantined_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<QuarantinedReview>, sqlx::Error> {
+        sqlx::query_as::<_, QuarantinedReview>(
+            "SELECT id, script_id, user_id, rating, comment, created_at, quarantined_at,
+                    quarantine_reason
+             FROM reviews WHERE id = ?1 AND quarantined_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Makes a quarantined review public. Returns whether it was quarantined.
+    pub async fn release_from_quarantine(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE reviews SET quarantined_at = NULL, quarantine_reason = NULL
+             WHERE id = ?1 AND quarantined_at IS NOT NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns whether a review was removed.
+    pub async fn delete(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM reviews WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn get_average_rating(&self, script_id: &str) -> Result<Option<f64>, sqlx::Error> {
-        sqlx::query_scalar("SELECT AVG(rating) FROM reviews WHERE script_id = ?1")
+        let sql = format!(
+            "SELECT AVG(rating) FROM reviews
+             WHERE script_id = ?1 AND {NOT_SHADOW_BANNED} AND {NOT_QUARANTINED}"
+        );
+        sqlx::query_scalar(&sql)
             .bind(script_id)
             .fetch_one(&self.pool)
             .await
     }
+
+    /// Recomputes `rating`, `review_count` and `weighted_rating` of a script
+    /// from its visible reviews in ONE statement. Reading the aggregates and
+    /// writing them back in separate queries let two concurrent reviews
+    /// interleave and leave the older aggregate behind; a single UPDATE reads
+    /// and writes under one lock, so whichever refresh runs last sees every
+    /// committed review. The weighting is [`super::weighted_rating`] in SQL.
+    pub async fn refresh_script_stats(&self, script_id: &str) -> Result<(), sqlx::Error> {
+        let sql = format!(
+            "WITH agg AS (
+                 SELECT COUNT(*) AS n, COALESCE(AVG(rating), 0.0) AS average FROM reviews
+                 WHERE script_id = ?1 AND {NOT_SHADOW_BANNED} AND {NOT_QUARANTINED}
+             )
+             UPDATE scripts SET
+                 rating = (SELECT average FROM agg),
+                 review_count = (SELECT n FROM agg),
+                 weighted_rating = (SELECT CASE WHEN n = 0 THEN 0.0
+                                        ELSE (n * average + ?2 * ?3) / (n + ?2) END FROM agg)
+             WHERE id = ?1"
+        );
+        sqlx::query(&sql)
+            .bind(script_id)
+            .bind(RATING_PRIOR_WEIGHT)
+            .bind(RATING_PRIOR_MEAN)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 }
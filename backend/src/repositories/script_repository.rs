@@ -1,6 +1,50 @@
-use crate::models::{Script, SearchRequest, SearchResultPayload, SCRIPT_COLUMNS_WITH_ACCOUNT};
+use crate::content_store::sha256_hex;
+use crate::models::{
+    AccountScriptSummary, ChannelRelease, Script, ScriptDependency, SearchRequest,
+    SearchResultPayload, TrendingScope, TrendingSignal, SCRIPT_COLUMNS_WITH_ACCOUNT,
+};
+use crate::pricing::Price;
+use crate::search_experiments::Ranking;
 use sqlx::SqlitePool;
 
+/// Excludes scripts owned by a shadow-banned account. Appended to every
+/// public listing, search and aggregate; lookups by id or slug and the
+/// owner's own views deliberately skip it, so a shadow-banned author still
+/// sees their scripts.
+const NOT_SHADOW_BANNED: &str = "NOT EXISTS (SELECT 1 FROM accounts AS banned WHERE banned.id = scripts.owner_account_id AND banned.shadow_banned_at IS NOT NULL)";
+
+/// Prior of the Bayesian average behind `scripts.weighted_rating`: every
+/// script starts as if it had `RATING_PRIOR_WEIGHT` reviews of
+/// `RATING_PRIOR_MEAN` stars, so a handful of reviews only nudges it.
+pub const RATING_PRIOR_MEAN: f64 = 3.0;
+pub const RATING_PRIOR_WEIGHT: f64 = 5.0;
+
+/// Bayesian average of `review_count` reviews averaging `average` stars.
+/// Unreviewed scripts get 0.0, like their raw rating, so they sort last.
+pub fn weighted_rating(average: f64, review_count: i32) -> f64 {
+    if review_count <= 0 {
+        return 0.0;
+    }
+    let count = f64::from(review_count);
+    (count * average + RATING_PRIOR_WEIGHT * RATING_PRIOR_MEAN) / (count + RATING_PRIOR_WEIGHT)
+}
+
+/// `ORDER BY` of the trending lists, global and rolled up alike.
+fn trending_order(signal: TrendingSignal) -> &'static str {
+    match signal {
+        TrendingSignal::Downloads => "scripts.downloads DESC, scripts.weighted_rating DESC",
+        TrendingSignal::Views => "scripts.views DESC, scripts.downloads DESC",
+    }
+}
+
+/// Columns of [`ChannelRelease`], the bundle read through its blob like
+/// `SCRIPT_COLUMNS_WITH_ACCOUNT` does for scripts.
+const CHANNEL_RELEASE_COLUMNS: &str = "script_id, channel, version,
+    CASE WHEN bundle_sha256 IS NULL THEN bundle
+         ELSE (SELECT content FROM script_blobs
+               WHERE sha256 = script_channel_releases.bundle_sha256) END AS bundle,
+    bundle_sha256, created_at, updated_at";
+
 pub struct ScriptRepository {
     pool: SqlitePool,
 }
@@ -21,12 +65,31 @@ impl ScriptRepository {
             .await
     }
 
+    /// The live scripts among `ids`, in no particular order.
+    pub async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<Script>, sqlx::Error> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let sql = format!(
+            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.id IN ({}) AND scripts.deleted_at IS NULL",
+            SCRIPT_COLUMNS_WITH_ACCOUNT, placeholders
+        );
+        let mut query = sqlx::query_as::<_, Script>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        query.fetch_all(&self.pool).await
+    }
+
+    /// Live public scripts, newest first. With `private_of`, that account's
+    /// own private scripts (and its shadow-banned ones) are listed as well.
     pub async fn find_all(
         &self,
         limit: i32,
         offset: i32,
         category: Option<String>,
-        include_private: bool,
+        private_of: Option<&str>,
     ) -> Result<Vec<Script>, sqlx::Error> {
         // W7-1 (security): `category` is bound as a SQL parameter, NEVER
         // string-interpolated. The previous `format!(" AND category = '{}'",
@@ -41,10 +104,12 @@ impl ScriptRepository {
             ""
         };
 
-        let privacy_filter = if include_private {
-            ""
+        let privacy_filter = if private_of.is_some() {
+            format!(
+                " AND ((is_public = 1 AND {NOT_SHADOW_BANNED}) OR scripts.owner_account_id = ?)"
+            )
         } else {
-            " AND is_public = 1"
+            format!(" AND is_public = 1 AND {NOT_SHADOW_BANNED}")
         };
 
         let sql = format!(
@@ -56,17 +121,58 @@ impl ScriptRepository {
         if let Some(cat) = category {
             query = query.bind(cat);
         }
+        if let Some(account_id) = private_of {
+            query = query.bind(account_id);
+        }
         query.fetch_all(&self.pool).await
     }
 
-    pub async fn count_public(&self) -> Result<i64, sqlx::Error> {
-        sqlx::query_scalar(
-            "SELECT COUNT(*) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL",
+    /// Every script owned by an account, including private and soft-deleted
+    /// ones, newest first. Metadata only (no bundle).
+    pub async fn summaries_by_owner(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<AccountScriptSummary>, sqlx::Error> {
+        sqlx::query_as::<_, AccountScriptSummary>(
+            "SELECT id, slug, title, version, is_public, downloads, created_at, updated_at, deleted_at FROM scripts WHERE owner_account_id = ?1 ORDER BY created_at DESC",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// What an owner's live scripts count against their quotas: the number
+    /// of private scripts and the total bundle size in bytes, channel builds
+    /// included. A bundle counts once per script or build that references
+    /// it, even when the blob itself is shared.
+    pub async fn owner_usage(&self, account_id: &str) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as(
+            "SELECT COALESCE(SUM(CASE WHEN is_public = 0 THEN 1 ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN bundle_sha256 IS NULL
+                                      THEN LENGTH(CAST(bundle AS BLOB))
+                                      ELSE (SELECT size_bytes FROM script_blobs
+                                            WHERE sha256 = scripts.bundle_sha256) END), 0)
+                      + (SELECT COALESCE(SUM(CASE WHEN r.bundle_sha256 IS NULL
+                                                  THEN LENGTH(CAST(r.bundle AS BLOB))
+                                                  ELSE (SELECT size_bytes FROM script_blobs
+                                                        WHERE sha256 = r.bundle_sha256) END), 0)
+                         FROM script_channel_releases AS r
+                         JOIN scripts AS s ON s.id = r.script_id
+                         WHERE s.owner_account_id = ?1 AND s.deleted_at IS NULL)
+             FROM scripts WHERE owner_account_id = ?1 AND deleted_at IS NULL",
         )
+        .bind(account_id)
         .fetch_one(&self.pool)
         .await
     }
 
+    pub async fn count_public(&self) -> Result<i64, sqlx::Error> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL AND {NOT_SHADOW_BANNED}"
+        );
+        sqlx::query_scalar(&sql).fetch_one(&self.pool).await
+    }
+
     pub async fn count_by_id(&self, id: &str) -> Result<i64, sqlx::Error> {
         sqlx::query_scalar("SELECT COUNT(*) FROM scripts WHERE id = ?1 AND deleted_at IS NULL")
             .bind(id)
@@ -88,19 +194,21 @@ impl ScriptRepository {
         author_public_key: Option<&str>,
         upload_signature: Option<&str>,
         version: &str,
-        price: f64,
+        price: &Price,
         is_public: bool,
         compatibility: Option<&str>,
         tags_json: Option<&str>,
         timestamp: &str,
     ) -> Result<(), sqlx::Error> {
+        let bundle_sha256 = self.put_blob(bundle, timestamp).await?;
         sqlx::query(
             r#"
             INSERT INTO scripts (
-                id, slug, owner_account_id, title, description, category, bundle,
+                id, slug, owner_account_id, title, description, category, bundle, bundle_sha256,
                 author_principal, author_public_key, upload_signature, version, price,
-                is_public, compatibility, tags, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                is_public, compatibility, tags, created_at, updated_at, price_e8s, currency
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, '', ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                      ?17, ?18, ?19)
             "#,
         )
         .bind(id)
@@ -109,17 +217,19 @@ impl ScriptRepository {
         .bind(title)
         .bind(description)
         .bind(category)
-        .bind(bundle)
+        .bind(bundle_sha256)
         .bind(author_principal)
         .bind(author_public_key)
         .bind(upload_signature)
         .bind(version)
-        .bind(price)
+        .bind(price.legacy_amount())
         .bind(is_public)
         .bind(compatibility)
         .bind(tags_json)
         .bind(timestamp)
         .bind(timestamp)
+        .bind(price.e8s)
+        .bind(&price.currency)
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -134,11 +244,15 @@ impl ScriptRepository {
         category: Option<&str>,
         bundle: Option<&str>,
         version: Option<&str>,
-        price: Option<f64>,
+        price: Option<&Price>,
         is_public: Option<bool>,
         tags_json: Option<&str>,
         updated_at: &str,
     ) -> Result<(), sqlx::Error> {
+        let bundle_sha256 = match bundle {
+            Some(bundle) => Some(self.put_blob(bundle, updated_at).await?),
+            None => None,
+        };
         let mut updates = vec!["updated_at = ?"];
         let mut query_str = String::from("UPDATE scripts SET ");
 
@@ -151,17 +265,20 @@ impl ScriptRepository {
         if category.is_some() {
             updates.push("category = ?");
         }
-        if bundle.is_some() {
-            updates.push("bundle = ?");
+        if bundle_sha256.is_some() {
+            updates.push("bundle = '', bundle_sha256 = ?");
         }
         if version.is_some() {
             updates.push("version = ?");
         }
         if price.is_some() {
-            updates.push("price = ?");
+            updates.push("price = ?, price_e8s = ?, currency = ?");
         }
-        if is_public.is_some() {
-            updates.push("is_public = ?");
+        match is_public {
+            // Going public by hand cancels any scheduled publication.
+            Some(true) => updates.push("is_public = ?, publish_at = NULL"),
+            Some(false) => updates.push("is_public = ?"),
+            None => {}
         }
         if tags_json.is_some() {
             updates.push("tags = ?");
@@ -181,14 +298,17 @@ impl ScriptRepository {
         if let Some(c) = category {
             query = query.bind(c);
         }
-        if let Some(l) = bundle {
-            query = query.bind(l);
+        if let Some(sha256) = bundle_sha256 {
+            query = query.bind(sha256);
         }
         if let Some(v) = version {
             query = query.bind(v);
         }
         if let Some(p) = price {
-            query = query.bind(p);
+            query = query
+                .bind(p.legacy_amount())
+                .bind(p.e8s)
+                .bind(p.currency.as_str());
         }
         if let Some(pub_status) = is_public {
             query = query.bind(pub_status);
@@ -219,40 +339,341 @@ impl ScriptRepository {
     }
 
     pub async fn publish(&self, id: &str, updated_at: &str) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE scripts SET is_public = 1, updated_at = ?1 WHERE id = ?2")
-            .bind(updated_at)
+        sqlx::query(
+            "UPDATE scripts SET is_public = 1, publish_at = NULL, updated_at = ?1 WHERE id = ?2",
+        )
+        .bind(updated_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Schedules (`Some`) or cancels (`None`) the publication of a draft.
+    pub async fn set_publish_at(
+        &self,
+        id: &str,
+        publish_at: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scripts SET publish_at = ?1 WHERE id = ?2")
+            .bind(publish_at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Sets the readme of one script row; `None` removes it.
+    pub async fn set_readme(&self, id: &str, readme: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scripts SET readme = ?1 WHERE id = ?2")
+            .bind(readme)
             .bind(id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
+    pub async fn find_channel_release(
+        &self,
+        script_id: &str,
+        channel: &str,
+    ) -> Result<Option<ChannelRelease>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {CHANNEL_RELEASE_COLUMNS}
+             FROM script_channel_releases WHERE script_id = ?1 AND channel = ?2"
+        );
+        sqlx::query_as::<_, ChannelRelease>(&sql)
+            .bind(script_id)
+            .bind(channel)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_channel_releases(
+        &self,
+        script_id: &str,
+    ) -> Result<Vec<ChannelRelease>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {CHANNEL_RELEASE_COLUMNS}
+             FROM script_channel_releases WHERE script_id = ?1 ORDER BY channel"
+        );
+        sqlx::query_as::<_, ChannelRelease>(&sql)
+            .bind(script_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Replaces the build of `channel`, creating it on the first push.
+    pub async fn upsert_channel_release(
+        &self,
+        script_id: &str,
+        channel: &str,
+        version: &str,
+        bundle: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        let bundle_sha256 = self.put_blob(bundle, now).await?;
+        sqlx::query(
+            "INSERT INTO script_channel_releases
+                 (script_id, channel, version, bundle, bundle_sha256, created_at, updated_at)
+             VALUES (?1, ?2, ?3, '', ?4, ?5, ?5)
+             ON CONFLICT (script_id, channel)
+             DO UPDATE SET version = excluded.version, bundle = '',
+                           bundle_sha256 = excluded.bundle_sha256,
+                           updated_at = excluded.updated_at",
+        )
+        .bind(script_id)
+        .bind(channel)
+        .bind(version)
+        .bind(bundle_sha256)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Stores `bundle` in `script_blobs` unless an identical one is already
+    /// there, and returns its key.
+    async fn put_blob(&self, bundle: &str, now: &str) -> Result<String, sqlx::Error> {
+        let sha256 = sha256_hex(bundle);
+        sqlx::query(
+            "INSERT INTO script_blobs (sha256, content, size_bytes, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (sha256) DO NOTHING",
+        )
+        .bind(&sha256)
+        .bind(bundle)
+        .bind(bundle.len() as i64)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(sha256)
+    }
+
+    /// Moves bundles still stored inline, in scripts and channel builds, into
+    /// `script_blobs`. Returns the number of rows moved.
+    pub async fn backfill_bundle_blobs(&self, now: &str) -> Result<u64, sqlx::Error> {
+        let mut moved = 0;
+        for table in ["scripts", "script_channel_releases"] {
+            let rows: Vec<(i64, String)> = sqlx::query_as(&format!(
+                "SELECT rowid, bundle FROM {table} WHERE bundle_sha256 IS NULL"
+            ))
+            .fetch_all(&self.pool)
+            .await?;
+            for (rowid, bundle) in &rows {
+                let sha256 = self.put_blob(bundle, now).await?;
+                sqlx::query(&format!(
+                    "UPDATE {table} SET bundle = '', bundle_sha256 = ?1 WHERE rowid = ?2"
+                ))
+                .bind(sha256)
+                .bind(rowid)
+                .execute(&self.pool)
+                .await?;
+            }
+            moved += rows.len() as u64;
+        }
+        Ok(moved)
+    }
+
+    pub async fn find_dependencies(
+        &self,
+        script_id: &str,
+    ) -> Result<Vec<ScriptDependency>, sqlx::Error> {
+        sqlx::query_as::<_, ScriptDependency>(
+            "SELECT dependency_slug AS slug, version_req AS version
+             FROM script_dependencies WHERE script_id = ?1 ORDER BY dependency_slug",
+        )
+        .bind(script_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Replaces the declared dependencies of a script.
+    pub async fn set_dependencies(
+        &self,
+        script_id: &str,
+        dependencies: &[ScriptDependency],
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM script_dependencies WHERE script_id = ?1")
+            .bind(script_id)
+            .execute(&mut *tx)
+            .await?;
+        for dependency in dependencies {
+            sqlx::query(
+                "INSERT INTO script_dependencies (script_id, dependency_slug, version_req, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(script_id)
+            .bind(dependency.slug.trim())
+            .bind(dependency.version.trim())
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Ids of live drafts whose `publish_at` is at or before `now`.
+    pub async fn find_due_for_publication(&self, now: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT id FROM scripts
+             WHERE publish_at IS NOT NULL AND publish_at <= ?1
+               AND is_public = 0 AND deleted_at IS NULL
+             ORDER BY publish_at",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Publishes a draft that is due at `now`. Returns `false` when it is
+    /// no longer due, e.g. because another instance got there first.
+    pub async fn publish_scheduled(
+        &self,
+        id: &str,
+        now: &str,
+        updated_at: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let published = sqlx::query(
+            "UPDATE scripts SET is_public = 1, publish_at = NULL, updated_at = ?1
+             WHERE id = ?2 AND publish_at IS NOT NULL AND publish_at <= ?3
+               AND is_public = 0 AND deleted_at IS NULL",
+        )
+        .bind(updated_at)
+        .bind(id)
+        .bind(now)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        Ok(published > 0)
+    }
+
     pub async fn update_stats(
         &self,
         script_id: &str,
         rating: f64,
         review_count: i32,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE scripts SET rating = ?1, review_count = ?2 WHERE id = ?3")
-            .bind(rating)
-            .bind(review_count)
+        sqlx::query(
+            "UPDATE scripts SET rating = ?1, review_count = ?2, weighted_rating = ?3 WHERE id = ?4",
+        )
+        .bind(rating)
+        .bind(review_count)
+        .bind(weighted_rating(rating, review_count))
+        .bind(script_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Fills `weighted_rating` for reviewed scripts stored before the column
+    /// existed. Returns the number of scripts updated.
+    pub async fn backfill_weighted_ratings(&self) -> Result<u64, sqlx::Error> {
+        let rows: Vec<(String, f64, i32)> = sqlx::query_as(
+            "SELECT id, rating, review_count FROM scripts
+             WHERE review_count > 0 AND weighted_rating = 0",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        for (id, rating, review_count) in &rows {
+            sqlx::query("UPDATE scripts SET weighted_rating = ?1 WHERE id = ?2")
+                .bind(weighted_rating(*rating, *review_count))
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(rows.len() as u64)
+    }
+
+    pub async fn increment_downloads(&self, script_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scripts SET downloads = downloads + 1 WHERE id = ?1")
             .bind(script_id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
-    pub async fn increment_downloads(&self, script_id: &str) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE scripts SET downloads = downloads + 1 WHERE id = ?1")
+    pub async fn increment_views(&self, script_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scripts SET views = views + 1 WHERE id = ?1")
             .bind(script_id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
+    /// Moves every script (soft-deleted ones included, so a restore cannot
+    /// bring an old category back) from any of `from` into `into`, in one
+    /// statement. `updated_at` is left alone: this is curation, not an
+    /// author edit. Returns the number of scripts changed.
+    pub async fn merge_categories(&self, from: &[String], into: &str) -> Result<u64, sqlx::Error> {
+        let placeholders = vec!["?"; from.len()].join(", ");
+        let sql = format!("UPDATE scripts SET category = ? WHERE category IN ({placeholders})");
+        let mut query = sqlx::query(&sql).bind(into);
+        for category in from {
+            query = query.bind(category);
+        }
+        Ok(query.execute(&self.pool).await?.rows_affected())
+    }
+
+    /// Replaces tag `from` with `to` in every script's JSON tag list inside
+    /// one transaction, dropping the duplicate when a script already has
+    /// `to`. Same scope and `updated_at` rule as [`Self::merge_categories`].
+    /// Returns the number of scripts changed.
+    pub async fn rename_tag(&self, from: &str, to: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, tags FROM scripts
+             WHERE json_valid(tags)
+               AND EXISTS (SELECT 1 FROM json_each(scripts.tags) WHERE value = ?1)",
+        )
+        .bind(from)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut changed = 0;
+        for (id, tags_json) in rows {
+            let Ok(tags) = serde_json::from_str::<Vec<String>>(&tags_json) else {
+                tracing::warn!(
+                    "Skipping tag rename for script {}: tags are not a string list",
+                    id
+                );
+                continue;
+            };
+            let mut renamed: Vec<String> = Vec::with_capacity(tags.len());
+            for tag in tags {
+                let tag = if tag == from { to.to_string() } else { tag };
+                if !renamed.contains(&tag) {
+                    renamed.push(tag);
+                }
+            }
+            let renamed_json =
+                serde_json::to_string(&renamed).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+            sqlx::query("UPDATE scripts SET tags = ?1 WHERE id = ?2")
+                .bind(renamed_json)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+            changed += 1;
+        }
+        tx.commit().await?;
+        Ok(changed)
+    }
+
     pub async fn search(
         &self,
         request: &SearchRequest,
+    ) -> Result<SearchResultPayload, (poem::http::StatusCode, String)> {
+        self.search_ranked(request, None).await
+    }
+
+    /// [`Self::search`], ordered by `ranking` instead of the request's
+    /// `sortBy` and `order` when one is given.
+    pub async fn search_ranked(
+        &self,
+        request: &SearchRequest,
+        ranking: Option<Ranking>,
     ) -> Result<SearchResultPayload, (poem::http::StatusCode, String)> {
         use poem::http::StatusCode;
 
@@ -279,9 +700,10 @@ impl ScriptRepository {
         let sort_field = request.sort_by.as_deref().unwrap_or("createdAt");
         let sort_column = match sort_field {
             "createdAt" => "created_at",
-            "rating" => "rating",
+            "rating" => "weighted_rating",
             "downloads" => "downloads",
-            "price" => "price",
+            "views" => "views",
+            "price" => "price_e8s",
             "title" => "title",
             _ => {
                 return Err((
@@ -314,6 +736,7 @@ impl ScriptRepository {
 
         conditions.push("is_public = ?".to_string());
         condition_binds.push(BindValue::Text("1".to_string()));
+        conditions.push(NOT_SHADOW_BANNED.to_string());
 
         if let Some(query) = request
             .query
@@ -369,9 +792,13 @@ impl ScriptRepository {
             )
         })?;
 
+        let order_by = match ranking {
+            Some(ranking) => ranking.order_by().to_string(),
+            None => format!("scripts.{} {}", sort_column, sort_order),
+        };
         let search_sql = format!(
-            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.deleted_at IS NULL AND ({}) ORDER BY scripts.{} {} LIMIT {} OFFSET {}",
-            SCRIPT_COLUMNS_WITH_ACCOUNT, where_clause, sort_column, sort_order, limit, offset
+            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.deleted_at IS NULL AND ({}) ORDER BY {} LIMIT {} OFFSET {}",
+            SCRIPT_COLUMNS_WITH_ACCOUNT, where_clause, order_by, limit, offset
         );
 
         let mut query = sqlx::query_as::<_, Script>(&search_sql);
@@ -404,8 +831,8 @@ impl ScriptRepository {
         limit: i32,
     ) -> Result<Vec<Script>, sqlx::Error> {
         let sql = format!(
-            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.category = ?1 AND scripts.is_public = 1 AND scripts.deleted_at IS NULL ORDER BY scripts.created_at DESC LIMIT ?2",
-            SCRIPT_COLUMNS_WITH_ACCOUNT
+            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.category = ?1 AND scripts.is_public = 1 AND scripts.deleted_at IS NULL AND {} ORDER BY scripts.created_at DESC LIMIT ?2",
+            SCRIPT_COLUMNS_WITH_ACCOUNT, NOT_SHADOW_BANNED
         );
         sqlx::query_as::<_, Script>(&sql)
             .bind(category)
@@ -419,19 +846,24 @@ impl ScriptRepository {
     /// endpoint (single source, vs a hardcoded client list). Ordered
     /// alphabetically for stable UX.
     pub async fn distinct_categories(&self) -> Result<Vec<String>, sqlx::Error> {
-        sqlx::query_scalar(
+        let sql = format!(
             "SELECT DISTINCT category FROM scripts \
-             WHERE is_public = 1 AND deleted_at IS NULL AND category != '' \
-             ORDER BY category",
-        )
-        .fetch_all(&self.pool)
-        .await
+             WHERE is_public = 1 AND deleted_at IS NULL AND category != '' AND {NOT_SHADOW_BANNED} \
+             ORDER BY category"
+        );
+        sqlx::query_scalar(&sql).fetch_all(&self.pool).await
     }
 
-    pub async fn get_trending(&self, limit: i32) -> Result<Vec<Script>, sqlx::Error> {
+    pub async fn get_trending(
+        &self,
+        limit: i32,
+        signal: TrendingSignal,
+    ) -> Result<Vec<Script>, sqlx::Error> {
         let sql = format!(
-            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.is_public = 1 AND scripts.deleted_at IS NULL ORDER BY scripts.downloads DESC, rating DESC LIMIT ?1",
-            SCRIPT_COLUMNS_WITH_ACCOUNT
+            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.is_public = 1 AND scripts.deleted_at IS NULL AND {} ORDER BY {} LIMIT ?1",
+            SCRIPT_COLUMNS_WITH_ACCOUNT,
+            NOT_SHADOW_BANNED,
+            trending_order(signal)
         );
         sqlx::query_as::<_, Script>(&sql)
             .bind(limit)
@@ -439,6 +871,86 @@ impl ScriptRepository {
             .await
     }
 
+    /// The trending list of `scope` as of the last rollup. Scripts made
+    /// private, deleted or shadow-banned since are left out.
+    pub async fn get_trending_in(
+        &self,
+        scope: TrendingScope<'_>,
+        limit: i32,
+        signal: TrendingSignal,
+    ) -> Result<Vec<Script>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM trending_rollups AS r
+             JOIN scripts ON scripts.id = r.script_id
+             LEFT JOIN accounts ON scripts.owner_account_id = accounts.id
+             WHERE r.scope = ?1 AND r.scope_key = ?2 AND r.signal = ?3
+               AND scripts.is_public = 1 AND scripts.deleted_at IS NULL AND {}
+             ORDER BY r.rank LIMIT ?4",
+            SCRIPT_COLUMNS_WITH_ACCOUNT, NOT_SHADOW_BANNED
+        );
+        sqlx::query_as::<_, Script>(&sql)
+            .bind(scope.kind())
+            .bind(scope.key())
+            .bind(signal.as_str())
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Replaces every per-category and per-tag trending list with the top
+    /// `size` public scripts of each, for every signal, in one transaction.
+    /// Returns the number of rows written.
+    pub async fn rebuild_trending_rollups(&self, size: i64, now: &str) -> Result<u64, sqlx::Error> {
+        // `json_each` fails on malformed JSON, so such tags are read as none.
+        let scoped = [
+            (
+                "category",
+                "SELECT scripts.id AS script_id, scripts.category AS scope_key FROM scripts
+                 WHERE scripts.category != ''",
+            ),
+            (
+                "tag",
+                "SELECT DISTINCT scripts.id AS script_id, t.value AS scope_key
+                 FROM scripts,
+                      json_each(CASE WHEN json_valid(scripts.tags) THEN scripts.tags ELSE '[]' END) AS t
+                 WHERE t.type = 'text' AND t.value != ''",
+            ),
+        ];
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM trending_rollups")
+            .execute(&mut *tx)
+            .await?;
+        let mut written = 0;
+        for signal in TrendingSignal::ALL {
+            for (scope, members) in scoped {
+                let sql = format!(
+                    "INSERT INTO trending_rollups (scope, scope_key, signal, rank, script_id, computed_at)
+                     SELECT ?1, scope_key, ?2, rank, script_id, ?3 FROM (
+                         SELECT m.script_id, m.scope_key,
+                                ROW_NUMBER() OVER (PARTITION BY m.scope_key ORDER BY {}) AS rank
+                         FROM ({}) AS m
+                         JOIN scripts ON scripts.id = m.script_id
+                         WHERE scripts.is_public = 1 AND scripts.deleted_at IS NULL AND {}
+                     )
+                     WHERE rank <= ?4",
+                    trending_order(signal),
+                    members,
+                    NOT_SHADOW_BANNED
+                );
+                written += sqlx::query(&sql)
+                    .bind(scope)
+                    .bind(signal.as_str())
+                    .bind(now)
+                    .bind(size)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            }
+        }
+        tx.commit().await?;
+        Ok(written)
+    }
+
     pub async fn get_featured(
         &self,
         min_rating: f64,
@@ -446,8 +958,8 @@ impl ScriptRepository {
         limit: i32,
     ) -> Result<Vec<Script>, sqlx::Error> {
         let sql = format!(
-            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.is_public = 1 AND scripts.rating >= ?1 AND scripts.downloads >= ?2 AND scripts.deleted_at IS NULL ORDER BY scripts.rating DESC, scripts.downloads DESC LIMIT ?3",
-            SCRIPT_COLUMNS_WITH_ACCOUNT
+            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.is_public = 1 AND scripts.rating >= ?1 AND scripts.downloads >= ?2 AND scripts.deleted_at IS NULL AND {} ORDER BY scripts.weighted_rating DESC, scripts.downloads DESC LIMIT ?3",
+            SCRIPT_COLUMNS_WITH_ACCOUNT, NOT_SHADOW_BANNED
         );
         sqlx::query_as::<_, Script>(&sql)
             .bind(min_rating)
@@ -463,8 +975,8 @@ impl ScriptRepository {
         limit: i32,
     ) -> Result<Vec<Script>, sqlx::Error> {
         let sql = format!(
-            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.is_public = 1 AND (scripts.compatibility IS NULL OR scripts.compatibility LIKE ?1) AND scripts.deleted_at IS NULL ORDER BY scripts.created_at DESC LIMIT ?2",
-            SCRIPT_COLUMNS_WITH_ACCOUNT
+            "SELECT {} FROM scripts LEFT JOIN accounts ON scripts.owner_account_id = accounts.id WHERE scripts.is_public = 1 AND (scripts.compatibility IS NULL OR scripts.compatibility LIKE ?1) AND scripts.deleted_at IS NULL AND {} ORDER BY scripts.created_at DESC LIMIT ?2",
+            SCRIPT_COLUMNS_WITH_ACCOUNT, NOT_SHADOW_BANNED
         );
         let pattern = format!("%{}%", compatibility);
         sqlx::query_as::<_, Script>(&sql)
@@ -474,22 +986,30 @@ impl ScriptRepository {
             .await
     }
 
+    pub async fn total_views(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COALESCE(SUM(views), 0) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL AND {NOT_SHADOW_BANNED}"
+        ))
+        .fetch_one(&self.pool)
+        .await
+    }
+
     pub async fn get_marketplace_stats(&self) -> Result<(i64, i64, f64), sqlx::Error> {
-        let scripts_count: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL",
-        )
+        let scripts_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL AND {NOT_SHADOW_BANNED}"
+        ))
         .fetch_one(&self.pool)
         .await?;
 
-        let total_downloads: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(downloads), 0) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL",
-        )
+        let total_downloads: i64 = sqlx::query_scalar(&format!(
+            "SELECT COALESCE(SUM(downloads), 0) FROM scripts WHERE is_public = 1 AND deleted_at IS NULL AND {NOT_SHADOW_BANNED}"
+        ))
         .fetch_one(&self.pool)
         .await?;
 
-        let avg_rating: Option<f64> = sqlx::query_scalar(
-            "SELECT AVG(rating) FROM scripts WHERE is_public = 1 AND rating > 0 AND deleted_at IS NULL",
-        )
+        let avg_rating: Option<f64> = sqlx::query_scalar(&format!(
+            "SELECT AVG(rating) FROM scripts WHERE is_public = 1 AND rating > 0 AND deleted_at IS NULL AND {NOT_SHADOW_BANNED}"
+        ))
         .fetch_one(&self.pool)
         .await?;
 
@@ -0,0 +1,114 @@
+use crate::models::RankingVariantStats;
+use sqlx::SqlitePool;
+
+/// Exposures and clicks of search ranking experiments; see
+/// [`crate::search_experiments`].
+pub struct SearchExperimentRepository {
+    pool: SqlitePool,
+}
+
+impl SearchExperimentRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_exposure(
+        &self,
+        id: &str,
+        experiment: &str,
+        variant: &str,
+        client_hash: &str,
+        result_ids: &[String],
+        result_offset: i64,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO search_exposures
+                 (id, experiment, variant, client_hash, result_ids, result_offset, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(id)
+        .bind(experiment)
+        .bind(variant)
+        .bind(client_hash)
+        .bind(result_ids.join("\n"))
+        .bind(result_offset)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The script ids an exposure showed and the offset of the first one.
+    pub async fn find_results(
+        &self,
+        exposure_id: &str,
+    ) -> Result<Option<(Vec<String>, i64)>, sqlx::Error> {
+        let row: Option<(String, i64)> =
+            sqlx::query_as("SELECT result_ids, result_offset FROM search_exposures WHERE id = ?1")
+                .bind(exposure_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(|(ids, offset)| {
+            let ids = ids
+                .lines()
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+            (ids, offset)
+        }))
+    }
+
+    /// Records a click at 1-based `position`; repeated clicks on the same
+    /// result of an exposure count once.
+    pub async fn record_click(
+        &self,
+        exposure_id: &str,
+        script_id: &str,
+        position: i64,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO search_clicks (exposure_id, script_id, position, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(exposure_id)
+        .bind(script_id)
+        .bind(position)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Exposures and clicks of each variant of `experiment` since `since`.
+    pub async fn variant_stats(
+        &self,
+        experiment: &str,
+        since: &str,
+    ) -> Result<Vec<RankingVariantStats>, sqlx::Error> {
+        sqlx::query_as::<_, RankingVariantStats>(
+            "SELECT e.variant,
+                    COUNT(*) AS exposures,
+                    COUNT(DISTINCT e.client_hash) AS clients,
+                    COALESCE(SUM(c.clicks > 0), 0) AS clicked_exposures,
+                    COALESCE(SUM(c.clicks), 0) AS clicks,
+                    CAST(COALESCE(SUM(c.clicks > 0), 0) AS REAL) / COUNT(*) AS ctr,
+                    AVG(c.first_position) AS mean_first_click_position
+             FROM search_exposures e
+             LEFT JOIN (
+                 SELECT exposure_id, COUNT(*) AS clicks, MIN(position) AS first_position
+                 FROM search_clicks
+                 GROUP BY exposure_id
+             ) c ON c.exposure_id = e.id
+             WHERE e.experiment = ?1 AND datetime(e.created_at) >= datetime(?2)
+             GROUP BY e.variant
+             ORDER BY e.variant",
+        )
+        .bind(experiment)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
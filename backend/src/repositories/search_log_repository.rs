@@ -0,0 +1,68 @@
+use crate::models::SearchQueryStat;
+use sqlx::SqlitePool;
+
+pub struct SearchLogRepository {
+    pool: SqlitePool,
+}
+
+impl SearchLogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        query: &str,
+        category: Option<&str>,
+        result_count: i64,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO search_log (query, category, result_count, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(query)
+        .bind(category)
+        .bind(result_count)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// `(total searches, searches with zero results)` since `since`.
+    pub async fn totals_since(&self, since: &str) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as(
+            "SELECT COUNT(*), COALESCE(SUM(result_count = 0), 0) FROM search_log
+             WHERE datetime(created_at) >= datetime(?1)",
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Most frequent queries since `since`. With `zero_results_only`, only
+    /// searches that found nothing are counted.
+    pub async fn top_queries(
+        &self,
+        since: &str,
+        zero_results_only: bool,
+        limit: i64,
+    ) -> Result<Vec<SearchQueryStat>, sqlx::Error> {
+        sqlx::query_as::<_, SearchQueryStat>(
+            "SELECT query, COUNT(*) AS searches,
+                    CAST(ROUND(AVG(result_count)) AS INTEGER) AS avg_results,
+                    MAX(created_at) AS last_searched_at
+             FROM search_log
+             WHERE datetime(created_at) >= datetime(?1) AND (?2 = 0 OR result_count = 0)
+             GROUP BY query
+             ORDER BY searches DESC, last_searched_at DESC
+             LIMIT ?3",
+        )
+        .bind(since)
+        .bind(zero_results_only)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
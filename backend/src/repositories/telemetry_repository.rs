@@ -0,0 +1,55 @@
+use sqlx::SqlitePool;
+
+pub struct TelemetryRepository {
+    pool: SqlitePool,
+}
+
+impl TelemetryRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds one report to its counter. `error_class` is `""` for a success.
+    pub async fn increment(
+        &self,
+        script_id: &str,
+        version: &str,
+        engine_version: &str,
+        event: &str,
+        error_class: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO script_telemetry
+                 (script_id, version, engine_version, event, error_class, count, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6)
+             ON CONFLICT(script_id, version, engine_version, event, error_class)
+             DO UPDATE SET count = count + 1, updated_at = excluded.updated_at",
+        )
+        .bind(script_id)
+        .bind(version)
+        .bind(engine_version)
+        .bind(event)
+        .bind(error_class)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The script's counters summed over engine versions, as
+    /// `(version, event, error_class, count)`.
+    pub async fn counts_by_version(
+        &self,
+        script_id: &str,
+    ) -> Result<Vec<(String, String, String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT version, event, error_class, SUM(count) FROM script_telemetry
+             WHERE script_id = ?1
+             GROUP BY version, event, error_class",
+        )
+        .bind(script_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
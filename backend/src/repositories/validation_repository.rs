@@ -0,0 +1,83 @@
+use crate::models::{Script, ScriptValidation, SCRIPT_COLUMNS_WITH_ACCOUNT};
+use sqlx::SqlitePool;
+
+type ValidationRow = (String, String, i64, String, Option<String>, String);
+
+pub struct ValidationRepository {
+    pool: SqlitePool,
+}
+
+impl ValidationRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Public scripts never checked, or last checked at another version or
+    /// under other rules, least recently updated first.
+    pub async fn due(&self, rules_version: i64, limit: i64) -> Result<Vec<Script>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {SCRIPT_COLUMNS_WITH_ACCOUNT} FROM scripts
+             LEFT JOIN accounts ON scripts.owner_account_id = accounts.id
+             LEFT JOIN script_validations AS checked ON checked.script_id = scripts.id
+             WHERE scripts.is_public = 1 AND scripts.deleted_at IS NULL
+               AND (checked.script_id IS NULL
+                    OR checked.rules_version <> ?1
+                    OR checked.version <> scripts.version)
+             ORDER BY scripts.updated_at
+             LIMIT ?2"
+        );
+        sqlx::query_as::<_, Script>(&sql)
+            .bind(rules_version)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn find(&self, script_id: &str) -> Result<Option<ScriptValidation>, sqlx::Error> {
+        let row: Option<ValidationRow> = sqlx::query_as(
+            "SELECT script_id, version, rules_version, errors, failing_since, checked_at
+             FROM script_validations WHERE script_id = ?1",
+        )
+        .bind(script_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(
+            |(script_id, version, rules_version, errors, failing_since, checked_at)| {
+                let errors: Vec<String> = errors.lines().map(str::to_string).collect();
+                ScriptValidation {
+                    script_id,
+                    version,
+                    rules_version,
+                    valid: errors.is_empty(),
+                    errors,
+                    failing_since,
+                    checked_at,
+                }
+            },
+        ))
+    }
+
+    /// Replaces the script's result.
+    pub async fn save(&self, validation: &ScriptValidation) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO script_validations
+                 (script_id, version, rules_version, errors, failing_since, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(script_id) DO UPDATE SET
+                 version = excluded.version,
+                 rules_version = excluded.rules_version,
+                 errors = excluded.errors,
+                 failing_since = excluded.failing_since,
+                 checked_at = excluded.checked_at",
+        )
+        .bind(&validation.script_id)
+        .bind(&validation.version)
+        .bind(validation.rules_version)
+        .bind(validation.errors.join("\n"))
+        .bind(&validation.failing_since)
+        .bind(&validation.checked_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
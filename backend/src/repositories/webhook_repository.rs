@@ -0,0 +1,103 @@
+use crate::models::{AuthorScriptStats, AuthorWebhook};
+use sqlx::SqlitePool;
+
+pub struct WebhookRepository {
+    pool: SqlitePool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts or replaces the account's webhook, keeping `created_at` on
+    /// replacement.
+    pub async fn upsert(
+        &self,
+        account_id: &str,
+        url: &str,
+        secret: &str,
+        now: &str,
+    ) -> Result<AuthorWebhook, sqlx::Error> {
+        sqlx::query_as::<_, AuthorWebhook>(
+            "INSERT INTO author_webhooks (account_id, url, secret, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(account_id) DO UPDATE SET
+                url = excluded.url, secret = excluded.secret, updated_at = excluded.updated_at
+             RETURNING account_id, url, secret, created_at, updated_at",
+        )
+        .bind(account_id)
+        .bind(url)
+        .bind(secret)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_account(
+        &self,
+        account_id: &str,
+    ) -> Result<Option<AuthorWebhook>, sqlx::Error> {
+        sqlx::query_as::<_, AuthorWebhook>(
+            "SELECT account_id, url, secret, created_at, updated_at
+             FROM author_webhooks WHERE account_id = ?1",
+        )
+        .bind(account_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Returns whether a webhook was removed.
+    pub async fn delete(&self, account_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM author_webhooks WHERE account_id = ?1")
+            .bind(account_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<AuthorWebhook>, sqlx::Error> {
+        sqlx::query_as::<_, AuthorWebhook>(
+            "SELECT account_id, url, secret, created_at, updated_at
+             FROM author_webhooks ORDER BY account_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Live (not soft-deleted) scripts owned by the account, private ones
+    /// included: the payload goes to the author only.
+    pub async fn script_stats_for_owner(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<AuthorScriptStats>, sqlx::Error> {
+        sqlx::query_as::<_, AuthorScriptStats>(
+            "SELECT id, title, downloads, views, rating, review_count FROM scripts
+             WHERE owner_account_id = ?1 AND deleted_at IS NULL
+             ORDER BY downloads DESC, id",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Marks `milestone` as announced for the script. Returns `false` when
+    /// it already was.
+    pub async fn record_milestone(
+        &self,
+        script_id: &str,
+        milestone: i64,
+        now: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO script_download_milestones (script_id, milestone, reached_at)
+             VALUES (?1, ?2, ?3) ON CONFLICT(script_id, milestone) DO NOTHING",
+        )
+        .bind(script_id)
+        .bind(milestone)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
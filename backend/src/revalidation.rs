@@ -0,0 +1,69 @@
+//! Re-validation of published scripts.
+//!
+//! The engine's validation rules carry a version
+//! (`icp_core::js_engine::static_analysis::RULES_VERSION`). A background job
+//! checks every public script whose latest result was taken at another
+//! version of the script or under other rules — so all of them after the
+//! rules version is bumped — and records the result, readable at
+//! `GET /api/v1/scripts/:id/validation`. A script that starts failing is
+//! flagged (`failing_since`) and its owner gets a `script.validation_failed`
+//! webhook, instead of clients finding out when it breaks.
+
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::services::{RevalidationService, WebhookService};
+use crate::webhook_delivery;
+
+/// How often due scripts are looked for.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Scripts checked per run, so a rules bump is worked through over several
+/// runs instead of tying up the blocking pool at once.
+const BATCH_SIZE: i64 = 25;
+
+/// Background job that re-validates due public scripts and notifies the
+/// owners of those that started failing. Stops when `shutdown` is
+/// cancelled, like the other jobs.
+pub fn start_revalidation_job(pool: SqlitePool, shutdown: CancellationToken) {
+    tracing::info!("Starting script re-validation background job");
+    tokio::spawn(revalidation_loop(pool, shutdown));
+}
+
+async fn revalidation_loop(pool: SqlitePool, shutdown: CancellationToken) {
+    let service = RevalidationService::new(pool.clone());
+    let webhooks = WebhookService::new(pool);
+    // Results are recorded either way; without a client the failures are
+    // only logged.
+    let client = webhook_delivery::http_client()
+        .map_err(|e| tracing::error!("Re-validation webhooks disabled: {}", e))
+        .ok();
+    let mut interval = time::interval(CHECK_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let failing = match service.revalidate_due(BATCH_SIZE).await {
+                    Ok(failing) => failing,
+                    Err(e) => {
+                        tracing::error!("Script re-validation failed: {}", e);
+                        continue;
+                    }
+                };
+                let Some(client) = &client else { continue };
+                match webhooks.validation_failed_events(&failing).await {
+                    Ok(events) => webhook_delivery::deliver_all(client, events).await,
+                    Err(e) => {
+                        tracing::error!("Failed to load script.validation_failed webhooks: {}", e)
+                    }
+                }
+            }
+            _ = shutdown.cancelled() => {
+                tracing::info!("script re-validation job stopped");
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::services::{ScriptService, WebhookService};
+use crate::webhook_delivery;
+
+/// How often due drafts are looked for; a scheduled script goes public at
+/// most this long after its `publish_at`.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Background job that publishes drafts whose `publish_at` has passed and
+/// sends their owners a `script.published` webhook.
+///
+/// Every instance runs it; the publish itself is a conditional update, so a
+/// script is published, and announced, by exactly one of them. Stops when
+/// `shutdown` is cancelled, like the other jobs.
+pub fn start_scheduled_publish_job(pool: SqlitePool, shutdown: CancellationToken) {
+    tracing::info!("Starting scheduled publication background job");
+    tokio::spawn(publish_loop(pool, shutdown));
+}
+
+async fn publish_loop(pool: SqlitePool, shutdown: CancellationToken) {
+    let scripts = ScriptService::new(pool.clone());
+    let webhooks = WebhookService::new(pool);
+    // Publishing does not depend on webhooks; without a client the events
+    // are only logged.
+    let client = webhook_delivery::http_client()
+        .map_err(|e| tracing::error!("Scheduled publication webhooks disabled: {}", e))
+        .ok();
+    let mut interval = time::interval(CHECK_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let published = match scripts.publish_due().await {
+                    Ok(published) => published,
+                    Err(e) => {
+                        tracing::error!("Scheduled publication failed: {}", e);
+                        continue;
+                    }
+                };
+                for script in &published {
+                    tracing::info!("Published scheduled script {} ({})", script.id, script.slug);
+                }
+                let Some(client) = &client else { continue };
+                match webhooks.published_events(&published).await {
+                    Ok(events) => webhook_delivery::deliver_all(client, events).await,
+                    Err(e) => tracing::error!("Failed to load script.published webhooks: {}", e),
+                }
+            }
+            _ = shutdown.cancelled() => {
+                tracing::info!("scheduled publication job stopped");
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Dependencies between marketplace scripts.
+//!
+//! A script declares the other scripts it needs as `{ "slug", "version" }`
+//! pairs on create or update, where `version` is a semver requirement
+//! (`^1.2`, `~0.4.1`, `>=2, <3`, `*`). Drafts may declare anything; a script
+//! only goes public (create, update, publish or a scheduled publication) when
+//! every requirement is met by a public script with that slug. The highest
+//! matching version wins, and `GET /api/v1/scripts/:id/dependencies` walks the
+//! same resolution transitively so the app can install the whole tree.
+
+use std::collections::HashMap;
+
+use semver::{Version, VersionReq};
+
+use crate::models::{DependencyNode, DependencyTree, ResolvedDependency, Script, ScriptDependency};
+
+/// Most dependencies one script may declare.
+pub const MAX_DEPENDENCIES: usize = 20;
+
+/// How deep the resolved tree is walked; deeper chains are cut off.
+pub const MAX_DEPENDENCY_DEPTH: usize = 8;
+
+/// The requirement of a declared dependency; `Err` says why it is not one.
+pub fn parse_requirement(raw: &str) -> Result<VersionReq, String> {
+    VersionReq::parse(raw.trim()).map_err(|e| format!("is not a semver requirement: {e}"))
+}
+
+/// The public script among `candidates` with the highest version that meets
+/// `requirement`. Versions that are not semver never match.
+pub fn best_match<'a>(requirement: &str, candidates: &'a [Script]) -> Option<&'a Script> {
+    let requirement = parse_requirement(requirement).ok()?;
+    candidates
+        .iter()
+        .filter(|script| script.is_public)
+        .filter_map(|script| {
+            Version::parse(script.version.trim())
+                .ok()
+                .filter(|version| requirement.matches(version))
+                .map(|version| (version, script))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, script)| script)
+}
+
+/// `slug@version` of each dependency, sorted; the form they are signed in.
+pub fn signed_dependencies(dependencies: &[ScriptDependency]) -> Vec<String> {
+    let mut signed: Vec<String> = dependencies
+        .iter()
+        .map(|dependency| format!("{}@{}", dependency.slug, dependency.version))
+        .collect();
+    signed.sort();
+    signed
+}
+
+/// The declared dependencies reachable from one script and what each
+/// requirement resolved to, as loaded by
+/// `ScriptService::resolve_dependencies`.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// Declared dependencies of each loaded script, by id. Scripts past
+    /// [`MAX_DEPENDENCY_DEPTH`] are not loaded and show no dependencies.
+    pub declared: HashMap<String, Vec<ScriptDependency>>,
+    /// What each `(slug, requirement)` resolved to.
+    pub resolved: HashMap<(String, String), Option<ResolvedDependency>>,
+}
+
+impl DependencyGraph {
+    pub fn tree(&self, root: &Script) -> DependencyTree {
+        let mut path = vec![root.id.clone()];
+        let mut install = Vec::new();
+        let mut unresolved = Vec::new();
+        let dependencies = self.nodes(&mut path, &mut install, &mut unresolved);
+        DependencyTree {
+            id: root.id.clone(),
+            slug: root.slug.clone(),
+            version: root.version.clone(),
+            dependencies,
+            install,
+            unresolved,
+        }
+    }
+
+    /// The dependencies of the last script on `path`. A script already on
+    /// the path closes a cycle and is listed without its dependencies.
+    fn nodes(
+        &self,
+        path: &mut Vec<String>,
+        install: &mut Vec<ResolvedDependency>,
+        unresolved: &mut Vec<ScriptDependency>,
+    ) -> Vec<DependencyNode> {
+        let Some(declared) = path.last().and_then(|id| self.declared.get(id)) else {
+            return Vec::new();
+        };
+        let mut nodes = Vec::with_capacity(declared.len());
+        for dependency in declared {
+            let resolved = self
+                .resolved
+                .get(&(dependency.slug.clone(), dependency.version.clone()))
+                .cloned()
+                .flatten();
+            let dependencies = match &resolved {
+                Some(resolved) if !path.contains(&resolved.id) => {
+                    path.push(resolved.id.clone());
+                    let children = self.nodes(path, install, unresolved);
+                    path.pop();
+                    if !install.contains(resolved) {
+                        install.push(resolved.clone());
+                    }
+                    children
+                }
+                Some(_) => Vec::new(),
+                None => {
+                    if !unresolved.contains(dependency) {
+                        unresolved.push(dependency.clone());
+                    }
+                    Vec::new()
+                }
+            };
+            nodes.push(DependencyNode {
+                slug: dependency.slug.clone(),
+                requirement: dependency.version.clone(),
+                resolved,
+                dependencies,
+            });
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(id: &str, version: &str, is_public: bool) -> Script {
+        Script {
+            id: id.to_string(),
+            slug: "dep".to_string(),
+            owner_account_id: None,
+            title: String::new(),
+            description: String::new(),
+            readme: None,
+            category: String::new(),
+            tags: None,
+            bundle: String::new(),
+            bundle_sha256: None,
+            author_principal: None,
+            author_public_key: None,
+            upload_signature: None,
+            canister_ids: None,
+            icon_url: None,
+            screenshots: None,
+            version: version.to_string(),
+            compatibility: None,
+            price: 0.0,
+            price_e8s: 0,
+            currency: "ICP".to_string(),
+            is_public,
+            downloads: 0,
+            views: 0,
+            rating: 0.0,
+            weighted_rating: 0.0,
+            review_count: 0,
+            created_at: String::new(),
+            updated_at: String::new(),
+            deleted_at: None,
+            publish_at: None,
+            author_name: None,
+        }
+    }
+
+    #[test]
+    fn best_match_picks_the_highest_public_version_in_range() {
+        let candidates = [
+            script("a", "1.2.0", true),
+            script("b", "1.9.3", true),
+            script("c", "1.10.0", false),
+            script("d", "2.0.0", true),
+            script("e", "not-semver", true),
+        ];
+        assert_eq!(best_match("^1.2", &candidates).unwrap().id, "b");
+        assert_eq!(best_match(">=2, <3", &candidates).unwrap().id, "d");
+        assert!(best_match("^3", &candidates).is_none());
+        assert!(best_match("not a range", &candidates).is_none());
+    }
+
+    #[test]
+    fn tree_installs_dependencies_first_and_stops_at_cycles() {
+        let dependency = |slug: &str| ScriptDependency {
+            slug: slug.to_string(),
+            version: "^1".to_string(),
+        };
+        let resolved = |id: &str| ResolvedDependency {
+            id: id.to_string(),
+            slug: id.to_string(),
+            version: "1.0.0".to_string(),
+        };
+        // root -> a -> b -> a, root -> missing
+        let mut graph = DependencyGraph::default();
+        graph.declared.insert(
+            "root".to_string(),
+            vec![dependency("a"), dependency("missing")],
+        );
+        graph
+            .declared
+            .insert("a".to_string(), vec![dependency("b")]);
+        graph
+            .declared
+            .insert("b".to_string(), vec![dependency("a")]);
+        for id in ["a", "b"] {
+            graph
+                .resolved
+                .insert((id.to_string(), "^1".to_string()), Some(resolved(id)));
+        }
+        graph
+            .resolved
+            .insert(("missing".to_string(), "^1".to_string()), None);
+
+        let tree = graph.tree(&script("root", "1.0.0", true));
+        let install: Vec<_> = tree.install.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(install, ["b", "a"]);
+        assert_eq!(tree.unresolved, [dependency("missing")]);
+        let b = &tree.dependencies[0].dependencies[0];
+        assert_eq!(b.dependencies.len(), 1);
+        assert!(b.dependencies[0].dependencies.is_empty());
+    }
+
+    #[test]
+    fn parse_requirement_rejects_garbage() {
+        assert!(parse_requirement("^1.2").is_ok());
+        assert!(parse_requirement("*").is_ok());
+        assert!(parse_requirement("one point two").is_err());
+    }
+
+    #[test]
+    fn signed_dependencies_are_sorted() {
+        let dependency = |slug: &str, version: &str| ScriptDependency {
+            slug: slug.to_string(),
+            version: version.to_string(),
+        };
+        assert_eq!(
+            signed_dependencies(&[dependency("zeta", "^1"), dependency("alpha", "~2.1")]),
+            ["alpha@~2.1", "zeta@^1"]
+        );
+    }
+}
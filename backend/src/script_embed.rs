@@ -0,0 +1,160 @@
+//! Embeddable marketplace cards.
+//!
+//! `GET /api/v1/scripts/:id/embed` gives canister-hosted websites what they
+//! need to render a card for a public script: title, author, rating and an
+//! install link that opens the script in the app. `?format=oembed` returns
+//! the same card as a bare oEmbed 1.0 `link` document, for consumers that
+//! speak oEmbed rather than the marketplace envelope.
+//!
+//! The route sits outside the global CORS allow-list (see
+//! [`crate::cors::build_embed_cors`]): the sites embedding cards are not
+//! known in advance, and the card holds nothing a listing does not.
+
+use serde::Serialize;
+
+use crate::models::Script;
+
+/// URL scheme the app registers for deep links (`DeepLinkService` in the
+/// Flutter app).
+pub const INSTALL_LINK_SCHEME: &str = "icpautorun";
+
+/// `provider_name` of the oEmbed document.
+pub const PROVIDER_NAME: &str = "ICP Autorun Marketplace";
+
+/// The deep link that opens `script_id` in the app, ready to install.
+pub fn install_link(script_id: &str) -> String {
+    format!("{INSTALL_LINK_SCHEME}://script/{script_id}")
+}
+
+/// `?format=` of the embed endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedFormat {
+    Json,
+    OEmbed,
+}
+
+impl EmbedFormat {
+    /// `json` when absent; `None` for anything else, which oEmbed answers
+    /// with 501.
+    pub fn parse(format: Option<&str>) -> Option<Self> {
+        match format {
+            None | Some("") | Some("json") => Some(Self::Json),
+            Some("oembed") => Some(Self::OEmbed),
+            _ => None,
+        }
+    }
+}
+
+/// The card of one public script.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptEmbed {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub author: Option<String>,
+    pub rating: f64,
+    pub review_count: i32,
+    pub downloads: i32,
+    pub version: String,
+    pub price_e8s: i64,
+    pub currency: String,
+    pub icon_url: Option<String>,
+    pub install_url: String,
+}
+
+/// An oEmbed 1.0 document of type `link`. Field names follow the spec, so
+/// they stay snake_case.
+#[derive(Debug, Clone, Serialize)]
+pub struct OEmbed {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub version: &'static str,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+    pub provider_name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    /// Extension fields, allowed by the spec.
+    pub rating: f64,
+    pub install_url: String,
+}
+
+impl ScriptEmbed {
+    pub fn from_script(script: &Script) -> Self {
+        Self {
+            id: script.id.clone(),
+            title: script.title.clone(),
+            description: script.description.clone(),
+            author: script.author_name.clone(),
+            rating: script.rating,
+            review_count: script.review_count,
+            downloads: script.downloads,
+            version: script.version.clone(),
+            price_e8s: script.price_e8s,
+            currency: script.currency.clone(),
+            icon_url: script.icon_url.clone(),
+            install_url: install_link(&script.id),
+        }
+    }
+
+    pub fn oembed(&self) -> OEmbed {
+        OEmbed {
+            kind: "link",
+            version: "1.0",
+            title: self.title.clone(),
+            author_name: self.author.clone(),
+            provider_name: PROVIDER_NAME,
+            thumbnail_url: self.icon_url.clone(),
+            rating: self.rating,
+            install_url: self.install_url.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_link_matches_the_app_deep_link() {
+        assert_eq!(install_link("abc-123"), "icpautorun://script/abc-123");
+    }
+
+    #[test]
+    fn format_defaults_to_json_and_rejects_unknown_formats() {
+        assert_eq!(EmbedFormat::parse(None), Some(EmbedFormat::Json));
+        assert_eq!(EmbedFormat::parse(Some("json")), Some(EmbedFormat::Json));
+        assert_eq!(
+            EmbedFormat::parse(Some("oembed")),
+            Some(EmbedFormat::OEmbed)
+        );
+        assert_eq!(EmbedFormat::parse(Some("xml")), None);
+    }
+
+    #[test]
+    fn oembed_is_a_link_document_without_empty_optional_fields() {
+        let card = ScriptEmbed {
+            id: "s-1".to_string(),
+            title: "Balance checker".to_string(),
+            description: "Checks balances".to_string(),
+            author: None,
+            rating: 4.5,
+            review_count: 2,
+            downloads: 10,
+            version: "1.0.0".to_string(),
+            price_e8s: 0,
+            currency: "ICP".to_string(),
+            icon_url: None,
+            install_url: install_link("s-1"),
+        };
+        let doc = serde_json::to_value(card.oembed()).unwrap();
+        assert_eq!(doc["type"], "link");
+        assert_eq!(doc["version"], "1.0");
+        assert_eq!(doc["title"], "Balance checker");
+        assert_eq!(doc["install_url"], "icpautorun://script/s-1");
+        assert!(doc.get("author_name").is_none());
+        assert!(doc.get("thumbnail_url").is_none());
+    }
+}
@@ -0,0 +1,98 @@
+//! What a script bundle asks of the host, read from its source.
+//!
+//! Scripts carry no declared manifest, so — like [`crate::script_language`] —
+//! this inspects the bundle text. A permission is reported when the bundle
+//! names one of the host functions the runtime installs for it (the names in
+//! `packages/marketplace-sdk/src/host.ts`). A name that only appears in a
+//! comment is reported too: over-disclosing is the safe direction.
+//!
+//! Canisters are found as canister-id literals (`xxxxx-xxxxx-xxxxx-xxxxx-cai`)
+//! that parse as principals. Ids built at run time are not seen.
+
+use ic_agent::export::Principal;
+
+/// A host capability a script uses. Serialized as [`Permission::as_str`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    /// `icp_call` / `icp_batch`: query or update calls to canisters.
+    CanisterCalls,
+    /// `icp_fetch`: HTTP requests.
+    Network,
+    /// `icp_setTimeout`: deferred work.
+    Timers,
+}
+
+const MARKERS: &[(Permission, &[&str])] = &[
+    (Permission::CanisterCalls, &["icp_call", "icp_batch"]),
+    (Permission::Network, &["icp_fetch"]),
+    (Permission::Timers, &["icp_setTimeout"]),
+];
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::CanisterCalls => "canister_calls",
+            Permission::Network => "network",
+            Permission::Timers => "timers",
+        }
+    }
+
+    /// The permissions `bundle` uses, in declaration order.
+    pub fn detect(bundle: &str) -> Vec<Permission> {
+        MARKERS
+            .iter()
+            .filter(|(_, names)| names.iter().any(|name| bundle.contains(name)))
+            .map(|(permission, _)| *permission)
+            .collect()
+    }
+}
+
+/// Canister ids written literally in `bundle`, sorted and deduplicated.
+pub fn referenced_canisters(bundle: &str) -> Vec<String> {
+    let mut ids: Vec<String> = bundle
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .filter(|token| token.len() == 27 && token.ends_with("-cai"))
+        .filter(|token| Principal::from_text(token).is_ok())
+        .map(str::to_string)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLE: &str = r#"(() => {
+  const LEDGER = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+  function update(msg, state) {
+    if (msg.type === "load") {
+      return { state, effects: [icp_call({ canisterId: LEDGER, method: "symbol" })] };
+    }
+    icp_setTimeout(() => {}, 10);
+    return { state, effects: [] };
+  }
+  globalThis.update = update;
+})();"#;
+
+    #[test]
+    fn detects_host_functions_in_use() {
+        assert_eq!(
+            Permission::detect(BUNDLE),
+            vec![Permission::CanisterCalls, Permission::Timers]
+        );
+        assert!(Permission::detect("globalThis.view = () => ({});").is_empty());
+    }
+
+    #[test]
+    fn finds_canister_id_literals_only() {
+        let bundle = format!(
+            "{BUNDLE}\n// also ryjl3-tyaaa-aaaaa-aaaba-cai and zzzzz-zzzzz-zzzzz-zzzzz-cai"
+        );
+        assert_eq!(
+            referenced_canisters(&bundle),
+            vec!["ryjl3-tyaaa-aaaaa-aaaba-cai".to_string()]
+        );
+    }
+}
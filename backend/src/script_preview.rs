@@ -0,0 +1,357 @@
+//! Preview runs of script bundles.
+//!
+//! `POST /api/v1/scripts/validate/execute` runs a bundle the way the app does
+//! when it is opened — `init`, then one `view` — so the marketplace can show
+//! a live preview before install. Each step runs in icp_core's QuickJS
+//! sandbox: no IO, a memory cap and a time budget of [`STEP_BUDGET_MS`].
+//!
+//! Canister calls are never made. Every `icp_call` or `icp_batch` effect
+//! returned by `init` is answered through `update` with an `effect/result`
+//! message, as the app's host would: `{ok: true, data}` with the caller's
+//! mock for the effect id, or `{ok: false}` when there is none. Effects that
+//! `update` returns in turn are not followed.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+
+use crate::validation::{require_non_empty, FieldError, Validate};
+
+/// Time budget of each engine step, in milliseconds.
+pub const STEP_BUDGET_MS: u64 = 250;
+
+/// Previews running at once; more are refused rather than queued.
+pub const MAX_CONCURRENT_PREVIEWS: usize = 4;
+
+/// Effects of `init` answered with a mock; the rest are listed only.
+pub const MAX_MOCKED_EFFECTS: usize = 16;
+
+/// `error` of the `effect/result` for a call effect without a mock.
+pub const UNMOCKED_CALL_ERROR: &str = "canister calls are not made in preview";
+
+static RUNNING: Semaphore = Semaphore::const_new(MAX_CONCURRENT_PREVIEWS);
+
+/// A slot to run one preview in, or `None` when all are taken.
+pub fn try_reserve() -> Option<tokio::sync::SemaphorePermit<'static>> {
+    RUNNING.try_acquire().ok()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreviewRequest {
+    pub bundle: String,
+    /// Passed to `init` as its argument.
+    #[serde(default)]
+    pub arg: Option<Value>,
+    /// The `data` delivered for each call effect, by effect id.
+    #[serde(default)]
+    pub mocks: HashMap<String, Value>,
+}
+
+impl Validate for PreviewRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "bundle", &self.bundle);
+        errors
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewStage {
+    /// Static checks, before anything runs.
+    Validate,
+    Init,
+    /// Delivering a call effect's result.
+    Update,
+    View,
+}
+
+impl PreviewStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Validate => "validate",
+            Self::Init => "init",
+            Self::Update => "update",
+            Self::View => "view",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewError {
+    pub stage: PreviewStage,
+    pub message: String,
+    /// The effect whose result `update` failed on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effect_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewEffect {
+    pub id: String,
+    pub kind: String,
+    /// Whether a mock answered it; `false` when it was answered with a
+    /// failure or, not being a canister call, not answered at all.
+    pub mocked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Preview {
+    pub ok: bool,
+    /// What `view` rendered, `null` when a step failed.
+    pub ui: Option<Value>,
+    /// The state `view` was given.
+    pub state: Option<Value>,
+    pub effects: Vec<PreviewEffect>,
+    pub errors: Vec<PreviewError>,
+    pub warnings: Vec<String>,
+}
+
+/// The engine entry points a preview goes through. Each returns the
+/// engine's JSON envelope: `{ok: true, ...}` or `{ok: false, error}`.
+trait Engine {
+    fn init(&self, bundle: &str, arg: Option<&str>) -> String;
+    fn update(&self, bundle: &str, msg: &str, state: &str) -> String;
+    fn view(&self, bundle: &str, state: &str) -> String;
+}
+
+struct QuickJs;
+
+impl Engine for QuickJs {
+    fn init(&self, bundle: &str, arg: Option<&str>) -> String {
+        icp_core::js_engine::js_app_init(bundle, arg, STEP_BUDGET_MS)
+    }
+
+    fn update(&self, bundle: &str, msg: &str, state: &str) -> String {
+        icp_core::js_engine::js_app_update(bundle, msg, state, STEP_BUDGET_MS)
+    }
+
+    fn view(&self, bundle: &str, state: &str) -> String {
+        icp_core::js_engine::js_app_view(bundle, state, STEP_BUDGET_MS)
+    }
+}
+
+/// Runs `req` in the sandbox. Blocks for up to a few step budgets, so call it
+/// off the async runtime.
+pub fn run(req: &PreviewRequest) -> Preview {
+    let checks = icp_core::js_engine::static_analysis::run_static_stages(&req.bundle, None);
+    let mut preview = Preview {
+        ok: false,
+        ui: None,
+        state: None,
+        effects: Vec::new(),
+        errors: Vec::new(),
+        warnings: checks.warnings,
+    };
+    if !checks.is_valid {
+        preview.errors = checks
+            .syntax_errors
+            .into_iter()
+            .map(|message| PreviewError {
+                stage: PreviewStage::Validate,
+                message,
+                effect_id: None,
+            })
+            .collect();
+        return preview;
+    }
+    run_steps(&QuickJs, req, preview)
+}
+
+fn run_steps(engine: &impl Engine, req: &PreviewRequest, mut preview: Preview) -> Preview {
+    let fail = |preview: &mut Preview, stage, message, effect_id| {
+        preview.errors.push(PreviewError {
+            stage,
+            message,
+            effect_id,
+        });
+    };
+
+    let arg = req.arg.as_ref().map(Value::to_string);
+    let init = match envelope(&engine.init(&req.bundle, arg.as_deref())) {
+        Ok(init) => init,
+        Err(message) => {
+            fail(&mut preview, PreviewStage::Init, message, None);
+            return preview;
+        }
+    };
+    let mut state = init.get("state").cloned().unwrap_or(Value::Null);
+
+    let effects = init
+        .get("effects")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for (i, effect) in effects.iter().enumerate() {
+        let kind = effect
+            .get("kind")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let id = effect.get("id").and_then(Value::as_str).unwrap_or(kind);
+        let answered = matches!(kind, "icp_call" | "icp_batch") && i < MAX_MOCKED_EFFECTS;
+        let mock = req.mocks.get(id).filter(|_| answered);
+        preview.effects.push(PreviewEffect {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            mocked: mock.is_some(),
+        });
+        if !answered {
+            continue;
+        }
+        let msg = match mock {
+            Some(data) => {
+                serde_json::json!({ "type": "effect/result", "id": id, "ok": true, "data": data })
+            }
+            None => serde_json::json!({
+                "type": "effect/result", "id": id, "ok": false, "error": UNMOCKED_CALL_ERROR,
+            }),
+        };
+        match envelope(&engine.update(&req.bundle, &msg.to_string(), &state.to_string())) {
+            Ok(updated) => state = updated.get("state").cloned().unwrap_or(Value::Null),
+            Err(message) => {
+                fail(
+                    &mut preview,
+                    PreviewStage::Update,
+                    message,
+                    Some(id.to_string()),
+                );
+                return preview;
+            }
+        }
+    }
+
+    match envelope(&engine.view(&req.bundle, &state.to_string())) {
+        Ok(view) => {
+            preview.ui = Some(view.get("ui").cloned().unwrap_or(Value::Null));
+            preview.ok = true;
+        }
+        Err(message) => fail(&mut preview, PreviewStage::View, message, None),
+    }
+    preview.state = Some(state);
+    preview
+}
+
+/// The body of a successful engine envelope, or its error.
+fn envelope(raw: &str) -> Result<Value, String> {
+    let value: Value =
+        serde_json::from_str(raw).map_err(|e| format!("invalid engine output: {e}"))?;
+    if value.get("ok").and_then(Value::as_bool) == Some(true) {
+        Ok(value)
+    } else {
+        Err(value
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown engine error")
+            .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Plays back canned envelopes and records the messages `update` got.
+    struct Scripted {
+        init: Value,
+        view: Value,
+        updates: RefCell<Vec<Value>>,
+    }
+
+    impl Engine for Scripted {
+        fn init(&self, _: &str, _: Option<&str>) -> String {
+            self.init.to_string()
+        }
+
+        fn update(&self, _: &str, msg: &str, state: &str) -> String {
+            let msg: Value = serde_json::from_str(msg).unwrap();
+            self.updates.borrow_mut().push(msg.clone());
+            let mut state: Value = serde_json::from_str(state).unwrap();
+            state["last"] = msg;
+            serde_json::json!({ "ok": true, "state": state, "effects": [] }).to_string()
+        }
+
+        fn view(&self, _: &str, _: &str) -> String {
+            self.view.to_string()
+        }
+    }
+
+    fn request(mocks: &[(&str, Value)]) -> PreviewRequest {
+        PreviewRequest {
+            bundle: "bundle".to_string(),
+            arg: None,
+            mocks: mocks
+                .iter()
+                .map(|(id, data)| (id.to_string(), data.clone()))
+                .collect(),
+        }
+    }
+
+    fn empty() -> Preview {
+        Preview {
+            ok: false,
+            ui: None,
+            state: None,
+            effects: Vec::new(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn call_effects_get_mocked_results_before_the_view() {
+        let engine = Scripted {
+            init: serde_json::json!({ "ok": true, "state": { "n": 1 }, "effects": [
+                { "kind": "icp_call", "id": "balance" },
+                { "kind": "icp_batch", "id": "load" },
+            ]}),
+            view: serde_json::json!({ "ok": true, "ui": { "type": "text" } }),
+            updates: RefCell::new(Vec::new()),
+        };
+        let preview = run_steps(
+            &engine,
+            &request(&[("balance", serde_json::json!({ "ok": true, "result": 5 }))]),
+            empty(),
+        );
+
+        assert!(preview.ok, "{preview:?}");
+        assert_eq!(preview.ui, Some(serde_json::json!({ "type": "text" })));
+        let updates = engine.updates.borrow();
+        assert_eq!(updates[0]["ok"], true);
+        assert_eq!(updates[0]["data"]["result"], 5);
+        assert_eq!(updates[1]["ok"], false);
+        assert_eq!(updates[1]["error"], UNMOCKED_CALL_ERROR);
+        assert_eq!(preview.state.unwrap()["last"]["id"], "load");
+        assert_eq!(
+            preview.effects.iter().map(|e| e.mocked).collect::<Vec<_>>(),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn a_failing_step_is_reported_with_its_stage() {
+        let engine = Scripted {
+            init: serde_json::json!({ "ok": true, "state": {}, "effects": [] }),
+            view: serde_json::json!({ "ok": false, "error": "execution timeout" }),
+            updates: RefCell::new(Vec::new()),
+        };
+        let preview = run_steps(&engine, &request(&[]), empty());
+
+        assert!(!preview.ok);
+        assert_eq!(preview.ui, None);
+        assert_eq!(
+            preview.errors,
+            [PreviewError {
+                stage: PreviewStage::View,
+                message: "execution timeout".to_string(),
+                effect_id: None,
+            }]
+        );
+    }
+}
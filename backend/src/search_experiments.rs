@@ -0,0 +1,185 @@
+//! Ranking experiments for script search.
+//!
+//! Operators A/B test a relevance change by running a named experiment over
+//! the default ranking, i.e. searches that set neither `sortBy` nor `order`.
+//! `SEARCH_RANKING_EXPERIMENT` names the experiment and weights its variants:
+//!
+//! ```text
+//! SEARCH_RANKING_EXPERIMENT=quality-2026-10:control=50,quality=50
+//! ```
+//!
+//! A client — the `clientId` of the search, a random per-install id — lands
+//! in one variant, picked by hashing the id with the experiment name: it sees
+//! the same ranking on every search, and a new experiment reshuffles
+//! everyone. Searches without a client id get the control ranking and are
+//! not logged.
+//!
+//! Each search ranked by an experiment logs an exposure (variant, a hash of
+//! the client id, the script ids shown) and returns its id, which the client
+//! reports clicks against. `GET /api/v1/admin/search-experiments` gives the
+//! click-through rate of each variant.
+
+use std::env;
+
+use sha2::{Digest, Sha256};
+
+/// The environment variable holding the running experiment, if any.
+pub const EXPERIMENT_ENV: &str = "SEARCH_RANKING_EXPERIMENT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ranking {
+    /// Newest first: the ranking searches get without an experiment.
+    Control,
+    /// Most downloaded first.
+    Popular,
+    /// Highest weighted rating first, then most downloaded.
+    Quality,
+}
+
+impl Ranking {
+    pub const ALL: [Ranking; 3] = [Ranking::Control, Ranking::Popular, Ranking::Quality];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Control => "control",
+            Self::Popular => "popular",
+            Self::Quality => "quality",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == name)
+    }
+
+    /// `ORDER BY` terms over `scripts`; ties go to the newest script.
+    pub fn order_by(self) -> &'static str {
+        match self {
+            Self::Control => "scripts.created_at DESC",
+            Self::Popular => "scripts.downloads DESC, scripts.created_at DESC",
+            Self::Quality => {
+                "scripts.weighted_rating DESC, scripts.downloads DESC, scripts.created_at DESC"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankingExperiment {
+    pub name: String,
+    variants: Vec<(Ranking, u64)>,
+}
+
+impl RankingExperiment {
+    /// Parses `name:variant=weight,...`. Weights are relative; a variant
+    /// with weight 0 is listed but gets no clients.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, variants) = spec
+            .split_once(':')
+            .ok_or("expected <name>:<variant>=<weight>,...")?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("experiment name is empty".to_string());
+        }
+        let mut parsed: Vec<(Ranking, u64)> = Vec::new();
+        for entry in variants.split(',') {
+            let (variant, weight) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected <variant>=<weight>, got {:?}", entry.trim()))?;
+            let variant = Ranking::parse(variant.trim())
+                .ok_or_else(|| format!("unknown ranking variant {:?}", variant.trim()))?;
+            let weight = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid weight {:?}", weight.trim()))?;
+            if parsed.iter().any(|(v, _)| *v == variant) {
+                return Err(format!("variant {} listed twice", variant.as_str()));
+            }
+            parsed.push((variant, weight));
+        }
+        if parsed.iter().map(|(_, w)| w).sum::<u64>() == 0 {
+            return Err("weights add up to zero".to_string());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            variants: parsed,
+        })
+    }
+
+    /// The experiment in [`EXPERIMENT_ENV`]. An invalid one is logged and
+    /// ignored, so a typo cannot take search down.
+    pub fn from_env() -> Option<Self> {
+        let spec = env::var(EXPERIMENT_ENV).ok()?;
+        if spec.trim().is_empty() {
+            return None;
+        }
+        match Self::parse(&spec) {
+            Ok(experiment) => {
+                tracing::info!("Search ranking experiment {:?} is running", experiment.name);
+                Some(experiment)
+            }
+            Err(e) => {
+                tracing::error!("Ignoring {}: {}", EXPERIMENT_ENV, e);
+                None
+            }
+        }
+    }
+
+    /// The variant `client_id` is in.
+    pub fn assign(&self, client_id: &str) -> Ranking {
+        let digest = Sha256::digest(format!("{}\n{}", self.name, client_id).as_bytes());
+        let total: u64 = self.variants.iter().map(|(_, w)| w).sum();
+        let mut bucket = u64::from_be_bytes(digest[..8].try_into().unwrap()) % total;
+        for &(variant, weight) in &self.variants {
+            if bucket < weight {
+                return variant;
+            }
+            bucket -= weight;
+        }
+        unreachable!("bucket is below the total weight")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_weighted_variants() {
+        let experiment = RankingExperiment::parse("q4: control=90, quality=10").unwrap();
+        assert_eq!(experiment.name, "q4");
+        assert_eq!(
+            experiment.variants,
+            [(Ranking::Control, 90), (Ranking::Quality, 10)]
+        );
+        for bad in [
+            "control=50",
+            ":control=50",
+            "q4:control",
+            "q4:best=50",
+            "q4:control=-1",
+            "q4:control=0",
+            "q4:control=1,control=1",
+        ] {
+            assert!(RankingExperiment::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn assignment_is_sticky_and_follows_the_weights() {
+        let experiment = RankingExperiment::parse("q4:control=50,popular=0,quality=50").unwrap();
+        let assigned: Vec<Ranking> = (0..1000)
+            .map(|i| experiment.assign(&format!("client-{i}")))
+            .collect();
+        assert_eq!(experiment.assign("client-7"), assigned[7]);
+        assert!(!assigned.contains(&Ranking::Popular));
+        let control = assigned.iter().filter(|r| **r == Ranking::Control).count();
+        assert!((400..600).contains(&control), "{control}");
+
+        // Another experiment splits the same clients differently.
+        let next = RankingExperiment::parse("q5:control=50,quality=50").unwrap();
+        assert!((0..1000)
+            .map(|i| next.assign(&format!("client-{i}")))
+            .zip(&assigned)
+            .any(|(a, b)| a != *b));
+    }
+}
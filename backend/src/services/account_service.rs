@@ -1,13 +1,19 @@
+use crate::account_settings::{SignatureScheme, WEBHOOK_EVENTS};
 use crate::auth::{
     create_canonical_payload, derive_ic_principal, is_audit_replay_error,
     validate_replay_prevention, validate_username, verify_signature, AuthError,
 };
+use crate::markdown::{self, Profile};
 use crate::models::{
-    AccountPublicKeyResponse, AccountResponse, AddPublicKeyRequest, RegisterAccountRequest,
-    RemovePublicKeyRequest, UpdateAccountRequest,
+    Account, AccountPublicKeyResponse, AccountResponse, AccountSettings, AccountSettingsUpdate,
+    ActivityPage, AddPublicKeyRequest, AdminAccountOverview, FollowStatus, KeyRevocation,
+    KeyRevocationPage, RegisterAccountRequest, RemovePublicKeyRequest, UpdateAccountRequest,
 };
+use crate::quotas::{AccountTier, QuotaUsage};
 use crate::repositories::{
-    AccountRepository, CreateAccountParams, SignatureAuditParams, UpdateAccountParams,
+    AccountRepository, AccountSettingsRepository, ActivityRepository, CreateAccountParams,
+    FollowRepository, ScriptRepository, SignatureAuditParams, UpdateAccountParams,
+    MAX_RECORDED_FAILURES,
 };
 use crate::services::error::AccountError;
 use chrono::Utc;
@@ -37,6 +43,17 @@ fn signature_err(e: AuthError) -> AccountError {
     AccountError::Unauthorized(format!("Signature verification failed: {e}"))
 }
 
+/// A bio is markdown, rendered like review comments.
+fn render_bio(bio: &str) -> String {
+    markdown::render(bio, Profile::Comment)
+}
+
+/// Most entries one page of an activity feed returns.
+pub const MAX_ACTIVITY_PER_PAGE: i64 = 100;
+
+/// Most entries one page of the global key revocation list returns.
+pub const MAX_REVOCATIONS_PER_PAGE: i64 = 1000;
+
 /// Maps a `record_signature_audit` DB error to a typed [`AccountError`].
 ///
 /// A UNIQUE-violation on the `signature_audit.nonce` constraint is a
@@ -54,6 +71,9 @@ fn account_audit_error(e: sqlx::Error) -> AccountError {
     }
 }
 
+/// How many audit entries the admin account overview returns.
+const ADMIN_OVERVIEW_OPERATIONS: i64 = 50;
+
 pub struct AccountService {
     repo: AccountRepository,
     pool: SqlitePool,
@@ -67,6 +87,89 @@ impl AccountService {
         }
     }
 
+    /// The account's quota usage under `tier`.
+    async fn quota_usage(
+        &self,
+        account_id: &str,
+        tier: AccountTier,
+    ) -> Result<QuotaUsage, AccountError> {
+        let (private_scripts, asset_bytes) = ScriptRepository::new(self.pool.clone())
+            .owner_usage(account_id)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+        let api_keys = self
+            .repo
+            .count_all_keys(account_id)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+        Ok(QuotaUsage::new(
+            tier,
+            private_scripts,
+            asset_bytes,
+            api_keys,
+        ))
+    }
+
+    /// Rejects adding a key once the account holds as many as its tier
+    /// allows.
+    async fn check_key_quota(&self, account: &Account) -> Result<(), AccountError> {
+        let quota = self
+            .quota_usage(&account.id, AccountTier::parse(&account.tier))
+            .await?
+            .api_keys;
+        if !quota.allows(1) {
+            return Err(AccountError::Conflict(format!(
+                "Maximum number of keys ({}) reached for this account",
+                quota.limit
+            )));
+        }
+        Ok(())
+    }
+
+    /// Builds the account payload: profile, every key and quota usage.
+    async fn account_response(&self, account: Account) -> Result<AccountResponse, AccountError> {
+        let keys = self
+            .repo
+            .get_account_keys(&account.id)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+
+        let public_keys = keys
+            .into_iter()
+            .map(|k| AccountPublicKeyResponse {
+                id: k.id,
+                public_key: k.public_key,
+                ic_principal: k.ic_principal,
+                added_at: k.added_at,
+                is_active: k.is_active,
+                disabled_at: k.disabled_at,
+                disabled_by_key_id: k.disabled_by_key_id,
+            })
+            .collect();
+
+        let tier = AccountTier::parse(&account.tier);
+        let quota = self.quota_usage(&account.id, tier).await?;
+
+        let bio_html = account.bio.as_deref().map(render_bio);
+        Ok(AccountResponse {
+            id: account.id,
+            username: account.username,
+            display_name: account.display_name,
+            contact_email: account.contact_email,
+            contact_telegram: account.contact_telegram,
+            contact_twitter: account.contact_twitter,
+            contact_discord: account.contact_discord,
+            website_url: account.website_url,
+            bio: account.bio,
+            bio_html,
+            created_at: account.created_at,
+            updated_at: Some(account.updated_at),
+            public_keys,
+            tier,
+            quota,
+        })
+    }
+
     /// Registers a new account with the first public key
     pub async fn register_account(
         &self,
@@ -172,6 +275,8 @@ impl AccountService {
             .map_err(account_audit_error)?;
 
         // 10. Return created account
+        let quota = self.quota_usage(&account_id, AccountTier::Free).await?;
+        let bio_html = req.bio.as_deref().map(render_bio);
         Ok(AccountResponse {
             id: account_id,
             username: normalized_username,
@@ -182,6 +287,7 @@ impl AccountService {
             contact_discord: req.contact_discord,
             website_url: req.website_url,
             bio: req.bio,
+            bio_html,
             created_at: now.clone(),
             updated_at: Some(now.clone()),
             public_keys: vec![AccountPublicKeyResponse {
@@ -193,6 +299,8 @@ impl AccountService {
                 disabled_at: None,
                 disabled_by_key_id: None,
             }],
+            tier: AccountTier::Free,
+            quota,
         })
     }
 
@@ -212,45 +320,10 @@ impl AccountService {
             .await
             .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
 
-        let account = match account {
-            Some(acc) => acc,
-            None => return Ok(None),
-        };
-
-        // Get all public keys for account
-        let keys = self
-            .repo
-            .get_account_keys(&account.id)
-            .await
-            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
-
-        let public_keys = keys
-            .into_iter()
-            .map(|k| AccountPublicKeyResponse {
-                id: k.id,
-                public_key: k.public_key,
-                ic_principal: k.ic_principal,
-                added_at: k.added_at,
-                is_active: k.is_active,
-                disabled_at: k.disabled_at,
-                disabled_by_key_id: k.disabled_by_key_id,
-            })
-            .collect();
-
-        Ok(Some(AccountResponse {
-            id: account.id,
-            username: account.username,
-            display_name: account.display_name,
-            contact_email: account.contact_email,
-            contact_telegram: account.contact_telegram,
-            contact_twitter: account.contact_twitter,
-            contact_discord: account.contact_discord,
-            website_url: account.website_url,
-            bio: account.bio,
-            created_at: account.created_at,
-            updated_at: Some(account.updated_at),
-            public_keys,
-        }))
+        match account {
+            Some(account) => self.account_response(account).await.map(Some),
+            None => Ok(None),
+        }
     }
 
     /// Gets account by public key with all public keys
@@ -283,40 +356,208 @@ impl AccountService {
                 AccountError::Internal("Account not found for public key".to_string())
             })?;
 
-        // Get all public keys for account
-        let keys = self
+        self.account_response(account).await.map(Some)
+    }
+
+    /// A page of the public activity of `username`, newest first. `Ok(None)`
+    /// when the account does not exist.
+    pub async fn activity(
+        &self,
+        username: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Option<ActivityPage>, AccountError> {
+        let db_err = |e: sqlx::Error| AccountError::Internal(format!("Database error: {e}"));
+        let normalized_username = validate_username(username)
+            .map_err(|e| AccountError::BadRequest(format!("Invalid username: {e}")))?;
+        let Some(account) = self
             .repo
-            .get_account_keys(&account.id)
+            .find_by_username(&normalized_username)
+            .await
+            .map_err(db_err)?
+        else {
+            return Ok(None);
+        };
+        let limit = limit.clamp(1, MAX_ACTIVITY_PER_PAGE);
+        let mut entries = ActivityRepository::new(self.pool.clone())
+            .find_by_account(&account.id, limit + 1, offset.max(0))
+            .await
+            .map_err(db_err)?;
+        let has_more = entries.len() as i64 > limit;
+        entries.truncate(limit as usize);
+        Ok(Some(ActivityPage { entries, has_more }))
+    }
+
+    /// Makes `follower_account_id` follow (or, with `follow == false`,
+    /// unfollow) `username`. Idempotent either way.
+    pub async fn set_following(
+        &self,
+        follower_account_id: &str,
+        username: &str,
+        follow: bool,
+    ) -> Result<FollowStatus, AccountError> {
+        let db_err = |e: sqlx::Error| AccountError::Internal(format!("Database error: {e}"));
+        let normalized_username = validate_username(username)
+            .map_err(|e| AccountError::BadRequest(format!("Invalid username: {e}")))?;
+        let author = self
+            .repo
+            .find_by_username(&normalized_username)
+            .await
+            .map_err(db_err)?
+            .ok_or_else(|| AccountError::NotFound("Account not found".to_string()))?;
+        if author.id == follower_account_id {
+            return Err(AccountError::BadRequest(
+                "Cannot follow your own account".to_string(),
+            ));
+        }
+
+        let follows = FollowRepository::new(self.pool.clone());
+        if follow {
+            follows
+                .follow(follower_account_id, &author.id, &Utc::now().to_rfc3339())
+                .await
+                .map_err(db_err)?;
+        } else {
+            follows
+                .unfollow(follower_account_id, &author.id)
+                .await
+                .map_err(db_err)?;
+        }
+        let (followers, _) = follows.counts(&author.id).await.map_err(db_err)?;
+        Ok(FollowStatus {
+            username: author.username,
+            following: follow,
+            followers,
+        })
+    }
+
+    /// A page of recent publications and releases by the authors
+    /// `account_id` follows, newest first.
+    pub async fn followed_feed(
+        &self,
+        account_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<ActivityPage, AccountError> {
+        let limit = limit.clamp(1, MAX_ACTIVITY_PER_PAGE);
+        let mut entries = ActivityRepository::new(self.pool.clone())
+            .find_followed_by(account_id, limit + 1, offset.max(0))
             .await
             .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+        let has_more = entries.len() as i64 > limit;
+        entries.truncate(limit as usize);
+        Ok(ActivityPage { entries, has_more })
+    }
 
-        let public_keys = keys
-            .into_iter()
-            .map(|k| AccountPublicKeyResponse {
-                id: k.id,
-                public_key: k.public_key,
-                ic_principal: k.ic_principal,
-                added_at: k.added_at,
-                is_active: k.is_active,
-                disabled_at: k.disabled_at,
-                disabled_by_key_id: k.disabled_by_key_id,
-            })
-            .collect();
+    /// The settings of an account; the defaults when none are stored.
+    pub async fn get_settings(&self, account_id: &str) -> Result<AccountSettings, AccountError> {
+        Ok(AccountSettingsRepository::new(self.pool.clone())
+            .find(account_id)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?
+            .unwrap_or_default())
+    }
 
-        Ok(Some(AccountResponse {
-            id: account.id,
-            username: account.username,
-            display_name: account.display_name,
-            contact_email: account.contact_email,
-            contact_telegram: account.contact_telegram,
-            contact_twitter: account.contact_twitter,
-            contact_discord: account.contact_discord,
-            website_url: account.website_url,
-            bio: account.bio,
-            created_at: account.created_at,
-            updated_at: Some(account.updated_at),
-            public_keys,
-        }))
+    /// Changes the fields present in `update`. Muted events must be webhook
+    /// events, and a required scheme other than `any` needs an active key of
+    /// that scheme, so the account cannot lock itself out.
+    pub async fn update_settings(
+        &self,
+        account_id: &str,
+        update: &AccountSettingsUpdate,
+    ) -> Result<AccountSettings, AccountError> {
+        let db_err = |e: sqlx::Error| AccountError::Internal(format!("Database error: {e}"));
+        let mut settings = self.get_settings(account_id).await?;
+        if let Some(visibility) = update.default_visibility {
+            settings.default_visibility = visibility;
+        }
+        if let Some(muted) = &update.muted_events {
+            if let Some(unknown) = muted.iter().find(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+                return Err(AccountError::BadRequest(format!(
+                    "Unknown webhook event: {unknown}"
+                )));
+            }
+            let mut muted = muted.clone();
+            muted.sort();
+            muted.dedup();
+            settings.muted_events = muted;
+        }
+        if let Some(scheme) = update.required_signature_scheme {
+            if scheme != SignatureScheme::Any {
+                let keys = self
+                    .repo
+                    .get_account_keys(account_id)
+                    .await
+                    .map_err(db_err)?;
+                if !keys
+                    .iter()
+                    .any(|k| k.is_active && scheme.allows(&k.public_key))
+                {
+                    return Err(AccountError::BadRequest(format!(
+                        "No active {} key on this account",
+                        scheme.as_str()
+                    )));
+                }
+            }
+            settings.required_signature_scheme = scheme;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        AccountSettingsRepository::new(self.pool.clone())
+            .upsert(account_id, &settings, &now)
+            .await
+            .map_err(db_err)?;
+        settings.updated_at = Some(now);
+        Ok(settings)
+    }
+
+    /// The revoked keys of `username`, in revocation order. `Ok(None)` when
+    /// the account does not exist.
+    pub async fn revoked_keys(
+        &self,
+        username: &str,
+    ) -> Result<Option<Vec<KeyRevocation>>, AccountError> {
+        let normalized_username = validate_username(username)
+            .map_err(|e| AccountError::BadRequest(format!("Invalid username: {e}")))?;
+        let Some(account) = self
+            .repo
+            .find_by_username(&normalized_username)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?
+        else {
+            return Ok(None);
+        };
+        self.repo
+            .find_revocations_by_account(&account.id)
+            .await
+            .map(Some)
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))
+    }
+
+    /// The global revocation list after sequence `since`, at most `limit`
+    /// (capped at [`MAX_REVOCATIONS_PER_PAGE`]) entries.
+    pub async fn key_revocations_since(
+        &self,
+        since: i64,
+        limit: i64,
+    ) -> Result<KeyRevocationPage, AccountError> {
+        let db_err = |e: sqlx::Error| AccountError::Internal(format!("Database error: {e}"));
+        let latest_sequence = self
+            .repo
+            .latest_revocation_sequence()
+            .await
+            .map_err(db_err)?;
+        let revocations = self
+            .repo
+            .find_revocations_since(since.max(0), limit.clamp(1, MAX_REVOCATIONS_PER_PAGE))
+            .await
+            .map_err(db_err)?;
+        let last_seen = revocations.last().map_or(since, |r| r.sequence);
+        Ok(KeyRevocationPage {
+            has_more: last_seen < latest_sequence,
+            latest_sequence: latest_sequence.max(last_seen),
+            revocations,
+        })
     }
 
     /// Updates account profile information
@@ -518,18 +759,8 @@ impl AccountService {
             ));
         }
 
-        // 7. Check account has < 10 keys (max limit)
-        let total_keys = self
-            .repo
-            .count_all_keys(&account.id)
-            .await
-            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
-
-        if total_keys >= 10 {
-            return Err(AccountError::Conflict(
-                "Maximum number of keys (10) reached for this account".to_string(),
-            ));
-        }
+        // 7. Check account is below its tier's key quota
+        self.check_key_quota(&account).await?;
 
         // 8. Derive IC principal from new public key
         let ic_principal = derive_ic_principal(&req.new_public_key)
@@ -826,20 +1057,10 @@ impl AccountService {
             ));
         }
 
-        // 3. Check account has < 10 keys (max limit)
-        let total_keys = self
-            .repo
-            .count_all_keys(&account.id)
-            .await
-            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
-
-        if total_keys >= 10 {
-            // State conflict (TD-2: was 400 under the old admin heuristic;
-            // user add_public_key already returned 409 — now consistent).
-            return Err(AccountError::Conflict(
-                "Maximum number of keys (10) reached for this account".to_string(),
-            ));
-        }
+        // 3. Check account is below its tier's key quota. Over quota is a
+        // state conflict (TD-2: was 400 under the old admin heuristic; user
+        // add_public_key already returned 409 — now consistent).
+        self.check_key_quota(&account).await?;
 
         // 4. Derive IC principal from new public key
         let ic_principal = derive_ic_principal(public_key)
@@ -892,6 +1113,158 @@ impl AccountService {
             added_at: Some(now),
         })
     }
+
+    /// Admin: sets or lifts an account's shadow-ban. The account keeps
+    /// working normally, but its scripts and reviews disappear from public
+    /// listings, search and aggregates (see the repository filters). Audited
+    /// like the other admin actions.
+    pub async fn admin_set_shadow_ban(
+        &self,
+        username: &str,
+        banned: bool,
+        reason: &str,
+    ) -> Result<crate::models::AdminShadowBanResponse, AccountError> {
+        let normalized_username = validate_username(username)
+            .map_err(|e| AccountError::BadRequest(format!("Invalid username: {e}")))?;
+
+        let account = self
+            .repo
+            .find_by_username(&normalized_username)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?
+            .ok_or_else(|| AccountError::NotFound("Account not found".to_string()))?;
+
+        let now = Utc::now().to_rfc3339();
+        let shadow_banned_at = banned.then(|| now.clone());
+        self.repo
+            .set_shadow_banned_at(&account.id, shadow_banned_at.as_deref())
+            .await
+            .map_err(|e| AccountError::Internal(format!("Failed to update shadow-ban: {e}")))?;
+
+        let action = if banned {
+            "admin_shadow_ban"
+        } else {
+            "admin_lift_shadow_ban"
+        };
+        let payload = serde_json::json!({
+            "action": action,
+            "reason": reason,
+            "username": normalized_username,
+        });
+        self.repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: Some(&account.id),
+                action,
+                payload: &create_canonical_payload(&payload),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: Utc::now().timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now,
+            })
+            .await
+            .map_err(account_audit_error)?;
+
+        Ok(crate::models::AdminShadowBanResponse {
+            account_id: account.id,
+            username: account.username,
+            shadow_banned_at,
+        })
+    }
+
+    /// Admin: moves an account to another plan tier. Nothing is removed on a
+    /// downgrade; see [`crate::quotas`]. Audited as `admin_set_tier`.
+    pub async fn admin_set_tier(
+        &self,
+        username: &str,
+        tier: AccountTier,
+        reason: &str,
+    ) -> Result<AccountResponse, AccountError> {
+        let normalized_username = validate_username(username)
+            .map_err(|e| AccountError::BadRequest(format!("Invalid username: {e}")))?;
+
+        let mut account = self
+            .repo
+            .find_by_username(&normalized_username)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?
+            .ok_or_else(|| AccountError::NotFound("Account not found".to_string()))?;
+
+        self.repo
+            .set_tier(&account.id, tier.as_str())
+            .await
+            .map_err(|e| AccountError::Internal(format!("Failed to update tier: {e}")))?;
+
+        let now = Utc::now().to_rfc3339();
+        let payload = serde_json::json!({
+            "action": "admin_set_tier",
+            "fromTier": account.tier,
+            "reason": reason,
+            "tier": tier.as_str(),
+            "username": normalized_username,
+        });
+        self.repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: Some(&account.id),
+                action: "admin_set_tier",
+                payload: &create_canonical_payload(&payload),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: Utc::now().timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now,
+            })
+            .await
+            .map_err(account_audit_error)?;
+
+        account.tier = tier.as_str().to_string();
+        self.account_response(account).await
+    }
+
+    /// Admin: support overview of one account — profile and keys (as
+    /// [`Self::get_account`]), every script it owns including private and
+    /// soft-deleted ones, and its most recent audited operations.
+    ///
+    /// Failed requests are not persisted anywhere, so there is no error
+    /// history here; the operation timestamps are the anchor for grepping the
+    /// server logs.
+    pub async fn admin_account_overview(
+        &self,
+        username: &str,
+    ) -> Result<AdminAccountOverview, AccountError> {
+        let account = self
+            .get_account(username)
+            .await?
+            .ok_or_else(|| AccountError::NotFound("Account not found".to_string()))?;
+
+        let scripts = ScriptRepository::new(self.pool.clone())
+            .summaries_by_owner(&account.id)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+
+        let recent_operations = self
+            .repo
+            .recent_operations(&account.id, ADMIN_OVERVIEW_OPERATIONS)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+
+        let recent_failures = self
+            .repo
+            .recent_failures(&account.id, MAX_RECORDED_FAILURES)
+            .await
+            .map_err(|e| AccountError::Internal(format!("Database error: {e}")))?;
+
+        Ok(AdminAccountOverview {
+            account,
+            scripts,
+            recent_operations,
+            recent_failures,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1229,6 +1602,103 @@ mod tests {
         assert_eq!(account.public_keys[0].public_key, ctx.public_key);
     }
 
+    #[tokio::test]
+    async fn test_admin_account_overview() {
+        let ctx = TestContext::new().await;
+        let account = test_register_account(
+            &ctx.service,
+            "alice",
+            &ctx.signing_key,
+            &ctx.public_key,
+            ctx.timestamp,
+        )
+        .await;
+        for (id, deleted_at) in [("s-live", None), ("s-gone", Some("2026-01-02T00:00:00Z"))] {
+            sqlx::query(
+                r#"INSERT INTO scripts (id, slug, owner_account_id, title, description, category, bundle, is_public, created_at, updated_at, deleted_at)
+                   VALUES (?, ?, ?, 'T', 'D', 'utility', 'b', 0, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', ?)"#,
+            )
+            .bind(id)
+            .bind(id)
+            .bind(&account.id)
+            .bind(deleted_at)
+            .execute(&ctx.service.pool)
+            .await
+            .unwrap();
+        }
+
+        let overview = ctx.service.admin_account_overview("alice").await.unwrap();
+        assert_eq!(overview.account.public_keys.len(), 1);
+        assert_eq!(overview.scripts.len(), 2);
+        assert!(overview.scripts.iter().any(|s| s.deleted_at.is_some()));
+        assert_eq!(overview.recent_operations.len(), 1);
+        assert_eq!(overview.recent_operations[0].action, "register_account");
+        assert!(overview.recent_failures.is_empty());
+
+        ctx.service
+            .repo
+            .record_failure(
+                &account.id,
+                "vault:create",
+                401,
+                "bad signature",
+                "2026-01-03T00:00:00Z",
+            )
+            .await
+            .unwrap();
+        let overview = ctx.service.admin_account_overview("alice").await.unwrap();
+        assert_eq!(overview.recent_failures.len(), 1);
+        assert_eq!(overview.recent_failures[0].reason, "bad signature");
+
+        let missing = ctx.service.admin_account_overview("nobody").await;
+        assert!(matches!(missing, Err(AccountError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_admin_set_tier_reports_quota() {
+        let ctx = TestContext::new().await;
+        let account = test_register_account(
+            &ctx.service,
+            "carol",
+            &ctx.signing_key,
+            &ctx.public_key,
+            ctx.timestamp,
+        )
+        .await;
+        assert_eq!(account.tier, AccountTier::Free);
+        assert_eq!(account.quota.api_keys.used, 1);
+        sqlx::query(
+            r#"INSERT INTO scripts (id, slug, owner_account_id, title, description, category, bundle, is_public, created_at, updated_at)
+               VALUES ('s-1', 's-1', ?, 'T', 'D', 'utility', 'héllo', 0, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')"#,
+        )
+        .bind(&account.id)
+        .execute(&ctx.service.pool)
+        .await
+        .unwrap();
+
+        let upgraded = ctx
+            .service
+            .admin_set_tier("carol", AccountTier::Pro, "paid plan")
+            .await
+            .unwrap();
+        assert_eq!(upgraded.tier, AccountTier::Pro);
+        assert_eq!(upgraded.quota.private_scripts.used, 1);
+        assert_eq!(upgraded.quota.asset_bytes.used, 6);
+        assert_eq!(
+            upgraded.quota.api_keys.limit,
+            AccountTier::Pro.quotas().max_api_keys
+        );
+
+        let reread = ctx.service.get_account("carol").await.unwrap().unwrap();
+        assert_eq!(reread.tier, AccountTier::Pro);
+
+        let missing = ctx
+            .service
+            .admin_set_tier("nobody", AccountTier::Pro, "paid plan")
+            .await;
+        assert!(matches!(missing, Err(AccountError::NotFound(_))));
+    }
+
     #[tokio::test]
     async fn test_get_account_not_found() {
         let ctx = TestContext::new().await;
@@ -1572,6 +2042,104 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_disabled_keys_are_listed_once_in_sequence() {
+        let ctx = TestContext::new().await;
+        test_register_account(
+            &ctx.service,
+            "ivy",
+            &ctx.signing_key,
+            &ctx.public_key,
+            ctx.timestamp,
+        )
+        .await;
+        let mut added = Vec::new();
+        for _ in 0..2 {
+            let (_, public_key) = create_test_keypair();
+            let add_req = create_add_key_request(
+                "ivy",
+                &public_key,
+                &ctx.signing_key,
+                &ctx.public_key,
+                ctx.timestamp,
+            );
+            added.push(ctx.service.add_public_key("ivy", add_req).await.unwrap().id);
+        }
+
+        for key_id in [&added[1], &added[0], &added[1]] {
+            ctx.service
+                .admin_disable_key("ivy", key_id, "compromised")
+                .await
+                .unwrap();
+        }
+
+        let revoked = ctx.service.revoked_keys("ivy").await.unwrap().unwrap();
+        let ids: Vec<&str> = revoked.iter().map(|r| r.key_id.as_str()).collect();
+        assert_eq!(ids, vec![added[1].as_str(), added[0].as_str()]);
+        assert!(revoked[0].sequence < revoked[1].sequence);
+        assert!(ctx.service.revoked_keys("nobody").await.unwrap().is_none());
+
+        let first = ctx.service.key_revocations_since(0, 1).await.unwrap();
+        assert_eq!(first.revocations.len(), 1);
+        assert!(first.has_more);
+        assert_eq!(first.latest_sequence, revoked[1].sequence);
+        let rest = ctx
+            .service
+            .key_revocations_since(first.revocations[0].sequence, 10)
+            .await
+            .unwrap();
+        assert_eq!(rest.revocations[0].key_id, added[0]);
+        assert!(!rest.has_more);
+    }
+
+    #[tokio::test]
+    async fn test_follow_and_unfollow_are_idempotent() {
+        let ctx = TestContext::new().await;
+        let fan = test_register_account(
+            &ctx.service,
+            "fan",
+            &ctx.signing_key,
+            &ctx.public_key,
+            ctx.timestamp,
+        )
+        .await;
+        let (author_key, author_public_key) = create_test_keypair();
+        test_register_account(
+            &ctx.service,
+            "author",
+            &author_key,
+            &author_public_key,
+            ctx.timestamp,
+        )
+        .await;
+
+        for _ in 0..2 {
+            let status = ctx
+                .service
+                .set_following(&fan.id, "author", true)
+                .await
+                .unwrap();
+            assert!(status.following);
+            assert_eq!(status.followers, 1);
+        }
+        let status = ctx
+            .service
+            .set_following(&fan.id, "author", false)
+            .await
+            .unwrap();
+        assert!(!status.following);
+        assert_eq!(status.followers, 0);
+
+        assert!(matches!(
+            ctx.service.set_following(&fan.id, "fan", true).await,
+            Err(AccountError::BadRequest(_))
+        ));
+        assert!(matches!(
+            ctx.service.set_following(&fan.id, "nobody", true).await,
+            Err(AccountError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_admin_disable_key_account_not_found() {
         let ctx = TestContext::new().await;
@@ -1744,6 +2312,7 @@ mod tests {
         assert_eq!(account.display_name, "Updated Name");
         assert_eq!(account.contact_email, Some("test@example.com".to_string()));
         assert_eq!(account.bio, Some("New bio".to_string()));
+        assert_eq!(account.bio_html.as_deref(), Some("<p>New bio</p>\n"));
     }
 
     #[tokio::test]
@@ -1967,6 +2536,73 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("replay attack"));
     }
 
+    #[tokio::test]
+    async fn test_update_settings_validates_events_and_scheme() {
+        use crate::account_settings::DefaultVisibility;
+        let ctx = TestContext::new().await;
+        let account = test_register_account(
+            &ctx.service,
+            "settings",
+            &ctx.signing_key,
+            &ctx.public_key,
+            ctx.timestamp,
+        )
+        .await;
+        assert_eq!(
+            ctx.service.get_settings(&account.id).await.unwrap(),
+            AccountSettings::default()
+        );
+
+        let settings = ctx
+            .service
+            .update_settings(
+                &account.id,
+                &AccountSettingsUpdate {
+                    default_visibility: Some(DefaultVisibility::Private),
+                    required_signature_scheme: Some(SignatureScheme::Ed25519),
+                    muted_events: Some(vec![
+                        "stats.daily".to_string(),
+                        "script.published".to_string(),
+                        "stats.daily".to_string(),
+                    ]),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(settings.default_visibility, DefaultVisibility::Private);
+        assert_eq!(settings.muted_events, ["script.published", "stats.daily"]);
+        assert!(settings.updated_at.is_some());
+
+        // The only key is Ed25519: requiring secp256k1 would lock the account out.
+        let result = ctx
+            .service
+            .update_settings(
+                &account.id,
+                &AccountSettingsUpdate {
+                    required_signature_scheme: Some(SignatureScheme::Secp256k1),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AccountError::BadRequest(_))));
+        let result = ctx
+            .service
+            .update_settings(
+                &account.id,
+                &AccountSettingsUpdate {
+                    muted_events: Some(vec!["script.deleted".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AccountError::BadRequest(_))));
+
+        // Partial updates keep the other fields.
+        let stored = ctx.service.get_settings(&account.id).await.unwrap();
+        assert_eq!(stored.required_signature_scheme, SignatureScheme::Ed25519);
+        assert_eq!(stored.muted_events, ["script.published", "stats.daily"]);
+    }
+
     #[tokio::test]
     async fn test_admin_add_recovery_key_max_keys_exceeded() {
         let ctx = TestContext::new().await;
@@ -0,0 +1,428 @@
+use std::collections::HashSet;
+
+use crate::models::{ProductBundle, Script};
+use crate::pricing::Price;
+use crate::repositories::{BundleRepository, PurchaseRepository, ScriptRepository};
+use crate::services::error::BundleError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// A bundle needs at least two scripts to be one.
+pub const MIN_BUNDLE_SCRIPTS: usize = 2;
+pub const MAX_BUNDLE_SCRIPTS: usize = 20;
+
+/// An author's request to sell scripts together.
+#[derive(Debug)]
+pub struct NewBundle<'a> {
+    pub title: &'a str,
+    pub description: &'a str,
+    pub price: Price,
+    pub script_ids: &'a [String],
+}
+
+/// Changes to a bundle; `None` keeps the current value. `script_ids`
+/// replaces the whole membership.
+#[derive(Debug, Default)]
+pub struct BundleUpdate<'a> {
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub price: Option<Price>,
+    pub script_ids: Option<&'a [String]>,
+}
+
+/// A bundle with the member scripts a buyer currently gets: public, not
+/// deleted, in bundle order.
+#[derive(Debug)]
+pub struct BundleListing {
+    pub bundle: ProductBundle,
+    pub scripts: Vec<Script>,
+}
+
+impl BundleListing {
+    /// What the members cost bought one by one, counting only those priced
+    /// in the bundle's currency.
+    pub fn separate_price_e8s(&self) -> i64 {
+        self.scripts
+            .iter()
+            .filter(|s| s.currency == self.bundle.currency)
+            .map(|s| s.price_e8s)
+            .sum()
+    }
+}
+
+pub struct BundleService {
+    repo: BundleRepository,
+    scripts: ScriptRepository,
+    purchases: PurchaseRepository,
+}
+
+impl BundleService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: BundleRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool.clone()),
+            purchases: PurchaseRepository::new(pool),
+        }
+    }
+
+    pub async fn create_bundle(
+        &self,
+        account_id: &str,
+        new: NewBundle<'_>,
+    ) -> Result<ProductBundle, BundleError> {
+        self.check_members(account_id, new.script_ids).await?;
+        let now = Utc::now().to_rfc3339();
+        let bundle = ProductBundle {
+            id: uuid::Uuid::new_v4().to_string(),
+            owner_account_id: account_id.to_string(),
+            title: new.title.trim().to_string(),
+            description: new.description.trim().to_string(),
+            price_e8s: new.price.e8s,
+            currency: new.price.currency,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        self.repo
+            .create(&bundle, new.script_ids)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to save bundle: {e}")))?;
+        Ok(bundle)
+    }
+
+    pub async fn update_bundle(
+        &self,
+        account_id: &str,
+        bundle_id: &str,
+        update: BundleUpdate<'_>,
+    ) -> Result<ProductBundle, BundleError> {
+        let mut bundle = self.owned_bundle(account_id, bundle_id).await?;
+        if let Some(script_ids) = update.script_ids {
+            self.check_members(account_id, script_ids).await?;
+        }
+        if let Some(title) = update.title {
+            bundle.title = title.trim().to_string();
+        }
+        if let Some(description) = update.description {
+            bundle.description = description.trim().to_string();
+        }
+        if let Some(price) = update.price {
+            bundle.price_e8s = price.e8s;
+            bundle.currency = price.currency;
+        }
+        bundle.updated_at = Utc::now().to_rfc3339();
+        self.repo
+            .update(&bundle, update.script_ids)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to save bundle: {e}")))?;
+        Ok(bundle)
+    }
+
+    /// Removes the bundle. Entitlements already granted through it stay.
+    pub async fn delete_bundle(
+        &self,
+        account_id: &str,
+        bundle_id: &str,
+    ) -> Result<(), BundleError> {
+        self.owned_bundle(account_id, bundle_id).await?;
+        self.repo
+            .delete(bundle_id)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to delete bundle: {e}")))?;
+        Ok(())
+    }
+
+    pub async fn list_bundles(
+        &self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<BundleListing>, i64), BundleError> {
+        let bundles = self
+            .repo
+            .find_all(limit, offset)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to load bundles: {e}")))?;
+        let total = self
+            .repo
+            .count()
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to count bundles: {e}")))?;
+        let mut listings = Vec::with_capacity(bundles.len());
+        for bundle in bundles {
+            listings.push(self.listing(bundle).await?);
+        }
+        Ok((listings, total))
+    }
+
+    pub async fn get_bundle(&self, bundle_id: &str) -> Result<BundleListing, BundleError> {
+        let bundle = self.find_bundle(bundle_id).await?;
+        self.listing(bundle).await
+    }
+
+    /// Bundles a public script is sold in, for its detail page.
+    pub async fn bundles_for_script(
+        &self,
+        script_id: &str,
+    ) -> Result<Vec<ProductBundle>, BundleError> {
+        let script = self.load_script(script_id).await?;
+        if !script.is_some_and(|s| s.is_public) {
+            return Err(BundleError::NotFound("Script not found".to_string()));
+        }
+        self.repo
+            .find_containing(script_id)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to load bundles: {e}")))
+    }
+
+    /// Entitles `account_id` to every script the bundle currently lists.
+    /// Called once a payment for the bundle's price has been verified.
+    /// Returns the number of scripts newly entitled.
+    pub async fn grant_entitlements(
+        &self,
+        account_id: &str,
+        bundle_id: &str,
+    ) -> Result<u64, BundleError> {
+        let listing = self.get_bundle(bundle_id).await?;
+        let script_ids: Vec<String> = listing.scripts.into_iter().map(|s| s.id).collect();
+        self.purchases
+            .grant_bundle(
+                account_id,
+                &listing.bundle.id,
+                &listing.bundle.currency,
+                &script_ids,
+                &Utc::now().to_rfc3339(),
+            )
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to grant bundle: {e}")))
+    }
+
+    async fn listing(&self, bundle: ProductBundle) -> Result<BundleListing, BundleError> {
+        let ids =
+            self.repo.script_ids(&bundle.id).await.map_err(|e| {
+                BundleError::Internal(format!("Failed to load bundle scripts: {e}"))
+            })?;
+        let mut scripts = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(script) = self.load_script(&id).await?.filter(|s| s.is_public) {
+                scripts.push(script);
+            }
+        }
+        Ok(BundleListing { bundle, scripts })
+    }
+
+    /// Members must be distinct live scripts owned by the bundle's author.
+    async fn check_members(
+        &self,
+        account_id: &str,
+        script_ids: &[String],
+    ) -> Result<(), BundleError> {
+        if !(MIN_BUNDLE_SCRIPTS..=MAX_BUNDLE_SCRIPTS).contains(&script_ids.len()) {
+            return Err(BundleError::BadRequest(format!(
+                "A bundle holds between {MIN_BUNDLE_SCRIPTS} and {MAX_BUNDLE_SCRIPTS} scripts"
+            )));
+        }
+        let distinct: HashSet<&String> = script_ids.iter().collect();
+        if distinct.len() != script_ids.len() {
+            return Err(BundleError::BadRequest(
+                "A script can appear in a bundle only once".to_string(),
+            ));
+        }
+        for id in script_ids {
+            let script = self
+                .load_script(id)
+                .await?
+                .ok_or_else(|| BundleError::BadRequest(format!("Script {id} not found")))?;
+            if script.owner_account_id.as_deref() != Some(account_id) {
+                return Err(BundleError::Forbidden(
+                    "Only your own scripts can be bundled".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn find_bundle(&self, bundle_id: &str) -> Result<ProductBundle, BundleError> {
+        self.repo
+            .find_by_id(bundle_id)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to load bundle: {e}")))?
+            .ok_or_else(|| BundleError::NotFound("Bundle not found".to_string()))
+    }
+
+    async fn owned_bundle(
+        &self,
+        account_id: &str,
+        bundle_id: &str,
+    ) -> Result<ProductBundle, BundleError> {
+        let bundle = self.find_bundle(bundle_id).await?;
+        if bundle.owner_account_id != account_id {
+            return Err(BundleError::Forbidden(
+                "Only the bundle owner can change it".to_string(),
+            ));
+        }
+        Ok(bundle)
+    }
+
+    async fn load_script(&self, script_id: &str) -> Result<Option<Script>, BundleError> {
+        self.scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| BundleError::Internal(format!("Failed to load script: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::insert_script;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn new_bundle(script_ids: &[String]) -> NewBundle<'_> {
+        NewBundle {
+            title: "Starter pack",
+            description: "Everything to get going",
+            price: Price::new(150, "ICP"),
+            script_ids,
+        }
+    }
+
+    #[tokio::test]
+    async fn bundle_lists_members_in_order_with_their_separate_price() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "a", "owner", 100, true).await;
+        insert_script(&pool, "b", "owner", 80, true).await;
+        let service = BundleService::new(pool);
+
+        let members = ids(&["b", "a"]);
+        let bundle = service
+            .create_bundle("owner", new_bundle(&members))
+            .await
+            .unwrap();
+
+        let listing = service.get_bundle(&bundle.id).await.unwrap();
+        let order: Vec<&str> = listing.scripts.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(order, ["b", "a"]);
+        assert_eq!(listing.separate_price_e8s(), 180);
+        assert_eq!(service.bundles_for_script("a").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn only_own_distinct_scripts_can_be_bundled() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "a", "owner", 100, true).await;
+        insert_script(&pool, "b", "owner", 80, true).await;
+        insert_script(&pool, "theirs", "other", 80, true).await;
+        let service = BundleService::new(pool);
+
+        let foreign = ids(&["a", "theirs"]);
+        let err = service
+            .create_bundle("owner", new_bundle(&foreign))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BundleError::Forbidden(_)));
+
+        let repeated = ids(&["a", "a"]);
+        let err = service
+            .create_bundle("owner", new_bundle(&repeated))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BundleError::BadRequest(_)));
+
+        let single = ids(&["a"]);
+        let err = service
+            .create_bundle("owner", new_bundle(&single))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BundleError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn membership_is_replaced_by_the_owner_only() {
+        let pool = setup_test_db().await;
+        for id in ["a", "b", "c"] {
+            insert_script(&pool, id, "owner", 100, true).await;
+        }
+        let service = BundleService::new(pool);
+        let members = ids(&["a", "b"]);
+        let bundle = service
+            .create_bundle("owner", new_bundle(&members))
+            .await
+            .unwrap();
+
+        let replacement = ids(&["c", "a"]);
+        let err = service
+            .update_bundle(
+                "other",
+                &bundle.id,
+                BundleUpdate {
+                    script_ids: Some(&replacement),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BundleError::Forbidden(_)));
+
+        service
+            .update_bundle(
+                "owner",
+                &bundle.id,
+                BundleUpdate {
+                    script_ids: Some(&replacement),
+                    price: Some(Price::new(120, "ICP")),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let listing = service.get_bundle(&bundle.id).await.unwrap();
+        let order: Vec<&str> = listing.scripts.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(order, ["c", "a"]);
+        assert_eq!(listing.bundle.price_e8s, 120);
+    }
+
+    #[tokio::test]
+    async fn granting_a_bundle_entitles_each_member_once() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "a", "owner", 100, true).await;
+        insert_script(&pool, "b", "owner", 80, true).await;
+        let purchases = PurchaseRepository::new(pool.clone());
+        let service = BundleService::new(pool);
+        let members = ids(&["a", "b"]);
+        let bundle = service
+            .create_bundle("owner", new_bundle(&members))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service
+                .grant_entitlements("buyer", &bundle.id)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            service
+                .grant_entitlements("buyer", &bundle.id)
+                .await
+                .unwrap(),
+            0
+        );
+        let entitlement = purchases
+            .find_by_account_and_script("buyer", "b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entitlement.status, "completed");
+        assert_eq!(entitlement.bundle_id.as_deref(), Some(bundle.id.as_str()));
+    }
+}
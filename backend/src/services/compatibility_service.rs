@@ -0,0 +1,284 @@
+use crate::models::{CompatibilityBreakdown, CompatibilityTally, Script};
+use crate::moderation::restricted_message;
+use crate::repositories::{CompatibilityRepository, ModerationRepository, ScriptRepository};
+use crate::services::error::CompatibilityError;
+use crate::telemetry::reliability_score;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+
+/// Signed action name of a compatibility vote, mirrored by the clients
+/// inside the canonical payload.
+pub const COMPATIBILITY_VOTE_ACTION: &str = "compatibility:vote";
+
+/// A script's compatibility in listings: the share of "works" votes,
+/// scored like telemetry reliability (the Wilson lower bound), so a few
+/// votes cannot make a script look safe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompatibilitySummary {
+    pub score: Option<f64>,
+    pub votes: i64,
+}
+
+pub struct CompatibilityService {
+    repo: CompatibilityRepository,
+    scripts: ScriptRepository,
+    moderation: ModerationRepository,
+}
+
+impl CompatibilityService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: CompatibilityRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool.clone()),
+            moderation: ModerationRepository::new(pool),
+        }
+    }
+
+    /// Records `account_id`'s vote on a script it can see, replacing its
+    /// earlier vote for the same app version and canister.
+    pub async fn vote(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        app_version: &str,
+        canister_id: Option<&str>,
+        works: bool,
+    ) -> Result<CompatibilityBreakdown, CompatibilityError> {
+        self.visible_script(script_id, Some(account_id)).await?;
+        let restriction = self.moderation.restriction(account_id).await.map_err(|e| {
+            CompatibilityError::Internal(format!("Failed to check restrictions: {e}"))
+        })?;
+        if !restriction.allows_community_writes() {
+            return Err(CompatibilityError::Forbidden(
+                restricted_message(restriction).to_string(),
+            ));
+        }
+        self.repo
+            .upsert_vote(
+                script_id,
+                account_id,
+                app_version,
+                canister_id.unwrap_or(""),
+                works,
+                &Utc::now().to_rfc3339(),
+            )
+            .await
+            .map_err(|e| CompatibilityError::Internal(format!("Failed to save vote: {e}")))?;
+        self.breakdown(script_id, Some(account_id)).await
+    }
+
+    /// The script's votes overall, per app version and per canister.
+    pub async fn breakdown(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+    ) -> Result<CompatibilityBreakdown, CompatibilityError> {
+        self.visible_script(script_id, viewer).await?;
+        let tallies = self
+            .repo
+            .tallies(script_id)
+            .await
+            .map_err(|e| CompatibilityError::Internal(format!("Failed to load votes: {e}")))?;
+
+        let mut by_app_version: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        let mut by_canister: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+        let (mut works, mut broken) = (0, 0);
+        for (app_version, canister_id, w, b) in tallies {
+            works += w;
+            broken += b;
+            let entry = by_app_version.entry(app_version).or_default();
+            entry.0 += w;
+            entry.1 += b;
+            if !canister_id.is_empty() {
+                let entry = by_canister.entry(canister_id).or_default();
+                entry.0 += w;
+                entry.1 += b;
+            }
+        }
+
+        let mut by_app_version: Vec<CompatibilityTally> =
+            by_app_version.into_iter().map(tally).collect();
+        by_app_version.sort_by(|a, b| {
+            match (
+                semver::Version::parse(&a.target),
+                semver::Version::parse(&b.target),
+            ) {
+                (Ok(a), Ok(b)) => b.cmp(&a),
+                _ => b.target.cmp(&a.target),
+            }
+        });
+        let mut by_canister: Vec<CompatibilityTally> = by_canister.into_iter().map(tally).collect();
+        by_canister.sort_by_key(|t| std::cmp::Reverse(t.works + t.broken));
+
+        Ok(CompatibilityBreakdown {
+            works,
+            broken,
+            score: reliability_score(works, works + broken),
+            by_app_version,
+            by_canister,
+        })
+    }
+
+    /// Summaries of the scripts with votes, keyed by script id, for
+    /// listings and search results.
+    pub async fn summaries_for(
+        &self,
+        scripts: &[Script],
+    ) -> Result<HashMap<String, CompatibilitySummary>, sqlx::Error> {
+        let ids: Vec<&str> = scripts.iter().map(|s| s.id.as_str()).collect();
+        Ok(self
+            .repo
+            .totals_for(&ids)
+            .await?
+            .into_iter()
+            .map(|(script_id, works, votes)| {
+                (
+                    script_id,
+                    CompatibilitySummary {
+                        score: reliability_score(works, votes),
+                        votes,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// The script, if it is public or owned by `viewer`.
+    async fn visible_script(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+    ) -> Result<Script, CompatibilityError> {
+        let not_found = || CompatibilityError::NotFound("Script not found".to_string());
+        let script = self
+            .scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| CompatibilityError::Internal(format!("Failed to load script: {e}")))?
+            .ok_or_else(not_found)?;
+        if !script.is_public
+            && script
+                .owner_account_id
+                .as_deref()
+                .is_none_or(|owner| Some(owner) != viewer)
+        {
+            return Err(not_found());
+        }
+        Ok(script)
+    }
+}
+
+fn tally((target, (works, broken)): (String, (i64, i64))) -> CompatibilityTally {
+    CompatibilityTally {
+        target,
+        works,
+        broken,
+        score: reliability_score(works, works + broken),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const LEDGER: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+
+    async fn setup() -> (SqlitePool, CompatibilityService) {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        for id in ["owner", "a", "b", "c"] {
+            insert_account(&pool, id).await;
+        }
+        for (id, is_public) in [("s", true), ("draft", false)] {
+            sqlx::query(
+                "INSERT INTO scripts (id, slug, owner_account_id, title, description, category,
+                                      bundle, is_public, created_at, updated_at)
+                 VALUES (?1, ?1, 'owner', ?1, 'd', 'utility', 'b', ?2,
+                         '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+            )
+            .bind(id)
+            .bind(is_public)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let service = CompatibilityService::new(pool.clone());
+        (pool, service)
+    }
+
+    async fn insert_account(pool: &SqlitePool, id: &str) {
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES (?1, ?1, ?1, '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn votes_tally_per_app_version_and_canister() {
+        let (pool, service) = setup().await;
+        service
+            .vote("a", "s", "1.2.0", Some(LEDGER), true)
+            .await
+            .unwrap();
+        service.vote("b", "s", "1.2.0", None, true).await.unwrap();
+        service
+            .vote("c", "s", "1.10.0", Some(LEDGER), true)
+            .await
+            .unwrap();
+        // Changing one's mind replaces the vote.
+        let breakdown = service
+            .vote("c", "s", "1.10.0", Some(LEDGER), false)
+            .await
+            .unwrap();
+
+        assert_eq!((breakdown.works, breakdown.broken), (2, 1));
+        assert_eq!(
+            breakdown
+                .by_app_version
+                .iter()
+                .map(|t| (t.target.as_str(), t.works, t.broken))
+                .collect::<Vec<_>>(),
+            [("1.10.0", 0, 1), ("1.2.0", 2, 0)]
+        );
+        assert_eq!(breakdown.by_canister.len(), 1);
+        assert_eq!(
+            (
+                breakdown.by_canister[0].works,
+                breakdown.by_canister[0].broken
+            ),
+            (1, 1)
+        );
+
+        let scripts = ScriptRepository::new(pool)
+            .find_by_ids(&["s".to_string()])
+            .await
+            .unwrap();
+        let summaries = service.summaries_for(&scripts).await.unwrap();
+        assert_eq!(summaries["s"].votes, 3);
+        assert_eq!(summaries["s"].score, breakdown.score);
+    }
+
+    #[tokio::test]
+    async fn private_scripts_take_votes_from_their_owner_only() {
+        let (_pool, service) = setup().await;
+        assert!(matches!(
+            service.vote("a", "draft", "1.0.0", None, true).await,
+            Err(CompatibilityError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.breakdown("draft", None).await,
+            Err(CompatibilityError::NotFound(_))
+        ));
+        let breakdown = service
+            .vote("owner", "draft", "1.0.0", None, true)
+            .await
+            .unwrap();
+        assert_eq!(breakdown.works, 1);
+    }
+}
@@ -0,0 +1,502 @@
+use std::sync::Arc;
+
+use crate::auth::create_canonical_payload;
+use crate::models::{AdminResolveDisputeRequest, Purchase, PurchaseStatusChange};
+use crate::refund_ledger::{ledger_account_of, IcpLedger, LedgerError, RefundLedger};
+use crate::repositories::{
+    AccountRepository, PurchaseRepository, SignatureAuditParams, StatusUpdate, Transition,
+};
+use crate::services::error::DisputeError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// Where a purchase is in the dispute workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseStatus {
+    Completed,
+    Disputed,
+    Refunded,
+}
+
+impl PurchaseStatus {
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "completed" => Some(Self::Completed),
+            "disputed" => Some(Self::Disputed),
+            "refunded" => Some(Self::Refunded),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Disputed => "disputed",
+            Self::Refunded => "refunded",
+        }
+    }
+
+    /// Whether a purchase in this state still entitles the buyer. A dispute
+    /// does not revoke access until it ends in a refund.
+    pub fn grants_access(self) -> bool {
+        matches!(self, Self::Completed | Self::Disputed)
+    }
+
+    /// The state machine: a completed purchase can be disputed, and a
+    /// dispute ends either back in `completed` (rejected) or in `refunded`,
+    /// which is final.
+    pub fn can_become(self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (Self::Completed, Self::Disputed)
+                | (Self::Disputed, Self::Completed)
+                | (Self::Disputed, Self::Refunded)
+        )
+    }
+}
+
+pub struct DisputeService {
+    purchases: PurchaseRepository,
+    account_repo: AccountRepository,
+    ledger: Arc<dyn RefundLedger>,
+}
+
+impl DisputeService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self::with_ledger(pool, Arc::new(IcpLedger::from_env()))
+    }
+
+    pub fn with_ledger(pool: SqlitePool, ledger: Arc<dyn RefundLedger>) -> Self {
+        Self {
+            purchases: PurchaseRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool),
+            ledger,
+        }
+    }
+
+    /// Opens a dispute on the caller's purchase of `script_id`.
+    pub async fn open_dispute(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        reason: &str,
+    ) -> Result<Purchase, DisputeError> {
+        let purchase = self
+            .purchases
+            .find_by_account_and_script(account_id, script_id)
+            .await
+            .map_err(|e| DisputeError::Internal(format!("Failed to load purchase: {e}")))?
+            .ok_or_else(|| DisputeError::NotFound("No purchase of this script".to_string()))?;
+        let actor = format!("account:{account_id}");
+        self.transition(
+            &purchase,
+            PurchaseStatus::Disputed,
+            StatusUpdate::Dispute { reason },
+            &actor,
+            Some(reason),
+        )
+        .await
+    }
+
+    /// Disputed purchases, oldest dispute first.
+    pub async fn list_disputes(
+        &self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<Purchase>, sqlx::Error> {
+        self.purchases
+            .find_by_status(PurchaseStatus::Disputed.as_str(), limit, offset)
+            .await
+    }
+
+    pub async fn history(
+        &self,
+        purchase_id: &str,
+    ) -> Result<Vec<PurchaseStatusChange>, sqlx::Error> {
+        self.purchases.history(purchase_id).await
+    }
+
+    /// Settles a dispute. A refund is only recorded once the named ledger
+    /// block is confirmed to be a transfer to the buyer covering the
+    /// purchase; its amount is stored with the purchase. Audited.
+    pub async fn admin_resolve_dispute(
+        &self,
+        purchase_id: &str,
+        req: &AdminResolveDisputeRequest,
+    ) -> Result<Purchase, DisputeError> {
+        let purchase = self
+            .purchases
+            .find_by_id(purchase_id)
+            .await
+            .map_err(|e| DisputeError::Internal(format!("Failed to load purchase: {e}")))?
+            .ok_or_else(|| DisputeError::NotFound("Purchase not found".to_string()))?;
+        if PurchaseStatus::parse(&purchase.status) != Some(PurchaseStatus::Disputed) {
+            return Err(DisputeError::Conflict(
+                "Only disputed purchases can be resolved".to_string(),
+            ));
+        }
+
+        let (next, update) = match req.refund_block_index.filter(|_| req.refund) {
+            Some(block_index) => {
+                let amount_e8s = self.verify_refund(&purchase, block_index).await?;
+                (
+                    PurchaseStatus::Refunded,
+                    StatusUpdate::Refund {
+                        block_index: block_index as i64,
+                        amount_e8s,
+                    },
+                )
+            }
+            None => (PurchaseStatus::Completed, StatusUpdate::Reopen),
+        };
+        let resolved = self
+            .transition(&purchase, next, update, "admin", Some(&req.reason))
+            .await?;
+
+        let now = Utc::now();
+        self.account_repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: None,
+                action: "admin_resolve_dispute",
+                payload: &create_canonical_payload(&serde_json::json!({
+                    "purchase_id": purchase_id,
+                    "account_id": resolved.account_id,
+                    "script_id": resolved.script_id,
+                    "refund": req.refund,
+                    "refund_block_index": resolved.refund_block_index,
+                    "refund_amount_e8s": resolved.refund_amount_e8s,
+                    "reason": req.reason,
+                })),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: now.timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now.to_rfc3339(),
+            })
+            .await
+            .map_err(|e| DisputeError::Internal(format!("Failed to record audit: {e}")))?;
+        Ok(resolved)
+    }
+
+    /// The amount of the transfer at `block_index`, in e8s. The transfer
+    /// must pay the default account of one of the buyer's keys at least what
+    /// the purchase cost.
+    async fn verify_refund(
+        &self,
+        purchase: &Purchase,
+        block_index: u64,
+    ) -> Result<i64, DisputeError> {
+        if i64::try_from(block_index).is_err() {
+            return Err(DisputeError::BadRequest(
+                "refundBlockIndex is out of range".to_string(),
+            ));
+        }
+        if !purchase.currency.eq_ignore_ascii_case("ICP") {
+            return Err(DisputeError::BadRequest(format!(
+                "A {} purchase cannot be refunded against the ICP ledger",
+                purchase.currency
+            )));
+        }
+        let transfer = match self.ledger.transfer_at(block_index).await {
+            Ok(Some(transfer)) if transfer.amount_e8s > 0 => transfer,
+            Ok(_) => {
+                return Err(DisputeError::BadRequest(format!(
+                    "Ledger block {block_index} is not a transfer"
+                )))
+            }
+            Err(e @ LedgerError::Archived(_)) => {
+                return Err(DisputeError::BadRequest(e.to_string()))
+            }
+            Err(e) => {
+                tracing::error!("Refund verification failed for block {block_index}: {e}");
+                return Err(DisputeError::BadGateway(
+                    "Could not verify the refund on the ledger".to_string(),
+                ));
+            }
+        };
+
+        let keys = self
+            .account_repo
+            .get_account_keys(&purchase.account_id)
+            .await
+            .map_err(|e| DisputeError::Internal(format!("Failed to load buyer keys: {e}")))?;
+        if !keys
+            .iter()
+            .filter_map(|key| ledger_account_of(&key.ic_principal))
+            .any(|account| account == transfer.to)
+        {
+            return Err(DisputeError::BadRequest(format!(
+                "Ledger block {block_index} did not pay the buyer"
+            )));
+        }
+        // `usd_amount` is in the purchase's `currency`, ICP here.
+        let price_e8s = (purchase.usd_amount * 100_000_000.0).round() as u64;
+        if transfer.amount_e8s < price_e8s {
+            return Err(DisputeError::BadRequest(format!(
+                "Ledger block {block_index} refunds {} e8s of a {price_e8s} e8s purchase",
+                transfer.amount_e8s
+            )));
+        }
+        i64::try_from(transfer.amount_e8s)
+            .map_err(|_| DisputeError::BadRequest("Refund amount is out of range".to_string()))
+    }
+
+    async fn transition(
+        &self,
+        purchase: &Purchase,
+        next: PurchaseStatus,
+        update: StatusUpdate<'_>,
+        actor: &str,
+        note: Option<&str>,
+    ) -> Result<Purchase, DisputeError> {
+        let current = PurchaseStatus::parse(&purchase.status);
+        if !current.is_some_and(|c| c.can_become(next)) {
+            return Err(DisputeError::Conflict(format!(
+                "A {} purchase cannot become {}",
+                purchase.status,
+                next.as_str()
+            )));
+        }
+        let now = Utc::now().to_rfc3339();
+        let applied = self
+            .purchases
+            .transition(Transition {
+                purchase_id: &purchase.id,
+                from: &purchase.status,
+                to: next.as_str(),
+                update,
+                actor,
+                note,
+                now: &now,
+            })
+            .await
+            .map_err(|e| match e.as_database_error() {
+                Some(db_err) if db_err.is_unique_violation() => DisputeError::Conflict(
+                    "That ledger block already settled another refund".to_string(),
+                ),
+                _ => DisputeError::Internal(format!("Failed to update purchase: {e}")),
+            })?;
+        if !applied {
+            return Err(DisputeError::Conflict(
+                "The purchase changed status concurrently; reload and retry".to_string(),
+            ));
+        }
+        self.purchases
+            .find_by_id(&purchase.id)
+            .await
+            .map_err(|e| DisputeError::Internal(format!("Failed to load purchase: {e}")))?
+            .ok_or_else(|| DisputeError::NotFound("Purchase not found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::refund_ledger::LedgerTransfer;
+    use base64::Engine;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::collections::HashMap;
+
+    /// Ledger holding only the given transfers.
+    struct FakeLedger(HashMap<u64, LedgerTransfer>);
+
+    #[async_trait::async_trait]
+    impl RefundLedger for FakeLedger {
+        async fn transfer_at(
+            &self,
+            block_index: u64,
+        ) -> Result<Option<LedgerTransfer>, LedgerError> {
+            Ok(self.0.get(&block_index).cloned())
+        }
+    }
+
+    /// The principal of a fixed Ed25519 key, one per `seed`.
+    fn principal(seed: u8) -> String {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+        let public_key =
+            base64::engine::general_purpose::STANDARD.encode(key.verifying_key().as_bytes());
+        crate::auth::derive_ic_principal(&public_key).unwrap()
+    }
+
+    fn transfer(amount_e8s: u64, to_seed: u8) -> LedgerTransfer {
+        LedgerTransfer {
+            amount_e8s,
+            to: ledger_account_of(&principal(to_seed)).unwrap(),
+        }
+    }
+
+    /// Purchases `p1` and `p2` by `buyer` of 1 ICP each. On the ledger, block
+    /// 42 pays `buyer` 1 ICP, block 43 pays `other` 1 ICP, block 44 pays
+    /// `buyer` half of it and block 45 pays a stranger.
+    async fn setup() -> (SqlitePool, DisputeService) {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        for (seed, account) in [(1, "buyer"), (2, "other")] {
+            sqlx::query(
+                "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+                 VALUES (?1, ?1, ?1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            )
+            .bind(account)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO account_public_keys (id, account_id, public_key, ic_principal, added_at)
+                 VALUES (?1, ?1, ?1, ?2, '2026-01-01T00:00:00Z')",
+            )
+            .bind(account)
+            .bind(principal(seed))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        for (id, script) in [("p1", "s1"), ("p2", "s2")] {
+            sqlx::query(
+                "INSERT INTO purchases (id, account_id, script_id, usd_amount, currency, status, paid_at, created_at)
+                 VALUES (?1, 'buyer', ?2, 1.0, 'ICP', 'completed', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            )
+            .bind(id)
+            .bind(script)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let ledger = FakeLedger(HashMap::from([
+            (42, transfer(100_000_000, 1)),
+            (43, transfer(100_000_000, 2)),
+            (44, transfer(50_000_000, 1)),
+            (45, transfer(100_000_000, 3)),
+        ]));
+        let service = DisputeService::with_ledger(pool.clone(), Arc::new(ledger));
+        (pool, service)
+    }
+
+    fn resolve(refund_block_index: Option<u64>) -> AdminResolveDisputeRequest {
+        AdminResolveDisputeRequest {
+            refund: refund_block_index.is_some(),
+            refund_block_index,
+            reason: "checked".to_string(),
+        }
+    }
+
+    #[test]
+    fn state_machine_allows_only_the_dispute_paths() {
+        use PurchaseStatus::*;
+        assert!(Completed.can_become(Disputed));
+        assert!(Disputed.can_become(Refunded));
+        assert!(Disputed.can_become(Completed));
+        assert!(!Completed.can_become(Refunded));
+        assert!(!Refunded.can_become(Disputed));
+        assert!(!Refunded.can_become(Completed));
+        assert!(Disputed.grants_access());
+        assert!(!Refunded.grants_access());
+    }
+
+    #[tokio::test]
+    async fn dispute_then_verified_refund_is_recorded_with_history() {
+        let (_pool, service) = setup().await;
+        let disputed = service
+            .open_dispute("buyer", "s1", "does not run")
+            .await
+            .unwrap();
+        assert_eq!(disputed.status, "disputed");
+        assert_eq!(service.list_disputes(10, 0).await.unwrap().len(), 1);
+
+        let refunded = service
+            .admin_resolve_dispute("p1", &resolve(Some(42)))
+            .await
+            .unwrap();
+        assert_eq!(refunded.status, "refunded");
+        assert_eq!(refunded.refund_amount_e8s, Some(100_000_000));
+
+        let history = service.history("p1").await.unwrap();
+        let steps: Vec<_> = history
+            .iter()
+            .map(|h| {
+                (
+                    h.from_status.as_str(),
+                    h.to_status.as_str(),
+                    h.actor.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("completed", "disputed", "account:buyer"),
+                ("disputed", "refunded", "admin"),
+            ]
+        );
+        assert!(matches!(
+            service.open_dispute("buyer", "s1", "again").await,
+            Err(DisputeError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn refunds_need_a_real_unused_ledger_transfer() {
+        let (_pool, service) = setup().await;
+        service.open_dispute("buyer", "s1", "x").await.unwrap();
+        service.open_dispute("buyer", "s2", "x").await.unwrap();
+
+        for block in [7, 43, 44, 45] {
+            assert!(
+                matches!(
+                    service
+                        .admin_resolve_dispute("p1", &resolve(Some(block)))
+                        .await,
+                    Err(DisputeError::BadRequest(_))
+                ),
+                "block {block} must not settle p1"
+            );
+        }
+        service
+            .admin_resolve_dispute("p1", &resolve(Some(42)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            service
+                .admin_resolve_dispute("p2", &resolve(Some(42)))
+                .await,
+            Err(DisputeError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejected_dispute_returns_to_completed() {
+        let (_pool, service) = setup().await;
+        assert!(matches!(
+            service.admin_resolve_dispute("p1", &resolve(None)).await,
+            Err(DisputeError::Conflict(_))
+        ));
+        service.open_dispute("buyer", "s1", "x").await.unwrap();
+        let reopened = service
+            .admin_resolve_dispute("p1", &resolve(None))
+            .await
+            .unwrap();
+        assert_eq!(reopened.status, "completed");
+        assert_eq!(reopened.dispute_reason, None);
+        assert!(matches!(
+            service.open_dispute("nobody", "s1", "x").await,
+            Err(DisputeError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn purchases_in_other_currencies_cannot_be_refunded_on_the_ledger() {
+        let (pool, service) = setup().await;
+        sqlx::query("UPDATE purchases SET currency = 'USD' WHERE id = 'p1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        service.open_dispute("buyer", "s1", "x").await.unwrap();
+        assert!(matches!(
+            service
+                .admin_resolve_dispute("p1", &resolve(Some(42)))
+                .await,
+            Err(DisputeError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use crate::repositories::{PurchaseRepository, ScriptRepository};
+use crate::services::PurchaseStatus;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+/// Why an account may run a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntitlementSource {
+    /// The script costs nothing.
+    Free,
+    /// The account owns the script.
+    Author,
+    /// The account bought it, alone or in a bundle, and was not refunded.
+    Purchase,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entitlement {
+    pub script_id: String,
+    pub owned: bool,
+    pub source: Option<EntitlementSource>,
+}
+
+pub struct EntitlementService {
+    scripts: ScriptRepository,
+    purchases: PurchaseRepository,
+}
+
+impl EntitlementService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            scripts: ScriptRepository::new(pool.clone()),
+            purchases: PurchaseRepository::new(pool),
+        }
+    }
+
+    /// The account's purchase history, newest first, refunded and disputed
+    /// purchases included.
+    pub async fn purchases(
+        &self,
+        account_id: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<crate::models::Purchase>, sqlx::Error> {
+        self.purchases
+            .find_by_account(account_id, limit, offset)
+            .await
+    }
+
+    /// One entry per distinct id of `script_ids`, in request order. A
+    /// purchase still counts after the script is deleted; an unknown script
+    /// is simply not owned.
+    pub async fn check(
+        &self,
+        account_id: &str,
+        script_ids: &[String],
+    ) -> Result<Vec<Entitlement>, sqlx::Error> {
+        let mut ids: Vec<String> = Vec::with_capacity(script_ids.len());
+        for id in script_ids {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+        let scripts: HashMap<String, _> = self
+            .scripts
+            .find_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|s| (s.id.clone(), s))
+            .collect();
+        let purchased: Vec<String> = self
+            .purchases
+            .find_by_account_and_scripts(account_id, &ids)
+            .await?
+            .into_iter()
+            .filter(|p| PurchaseStatus::parse(&p.status).is_some_and(|s| s.grants_access()))
+            .map(|p| p.script_id)
+            .collect();
+
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                let script = scripts.get(&id);
+                let source =
+                    if script.is_some_and(|s| s.owner_account_id.as_deref() == Some(account_id)) {
+                        Some(EntitlementSource::Author)
+                    } else if purchased.contains(&id) {
+                        Some(EntitlementSource::Purchase)
+                    } else if script.is_some_and(|s| s.price_e8s == 0) {
+                        Some(EntitlementSource::Free)
+                    } else {
+                        None
+                    };
+                Entitlement {
+                    owned: source.is_some(),
+                    script_id: id,
+                    source,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::insert_script;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    async fn insert_purchase(pool: &SqlitePool, account_id: &str, script_id: &str, status: &str) {
+        sqlx::query(
+            "INSERT INTO purchases (id, account_id, script_id, usd_amount, status, paid_at, created_at)
+             VALUES (?1, ?2, ?3, 1.0, ?4, '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .bind(format!("{account_id}-{script_id}"))
+        .bind(account_id)
+        .bind(script_id)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn purchase_history_lists_only_the_callers_purchases() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "bought", "author", 500, true).await;
+        insert_script(&pool, "refunded", "author", 500, true).await;
+        insert_purchase(&pool, "buyer", "bought", "completed").await;
+        insert_purchase(&pool, "buyer", "refunded", "refunded").await;
+        insert_purchase(&pool, "other", "bought", "completed").await;
+        let service = EntitlementService::new(pool);
+
+        let history = service.purchases("buyer", 10, 0).await.unwrap();
+        let mut scripts: Vec<&str> = history.iter().map(|p| p.script_id.as_str()).collect();
+        scripts.sort();
+        assert_eq!(scripts, vec!["bought", "refunded"]);
+        assert!(history.iter().all(|p| p.account_id == "buyer"));
+        assert_eq!(service.purchases("buyer", 1, 1).await.unwrap().len(), 1);
+    }
+
+    fn sources(entitlements: &[Entitlement]) -> Vec<(&str, Option<EntitlementSource>)> {
+        entitlements
+            .iter()
+            .map(|e| (e.script_id.as_str(), e.source))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reports_each_script_once_in_request_order() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "free", "author", 0, true).await;
+        insert_script(&pool, "mine", "buyer", 500, true).await;
+        insert_script(&pool, "bought", "author", 500, true).await;
+        insert_script(&pool, "disputed", "author", 500, true).await;
+        insert_script(&pool, "refunded", "author", 500, true).await;
+        insert_script(&pool, "paid", "author", 500, true).await;
+        insert_purchase(&pool, "buyer", "bought", "completed").await;
+        insert_purchase(&pool, "buyer", "disputed", "disputed").await;
+        insert_purchase(&pool, "buyer", "refunded", "refunded").await;
+        let service = EntitlementService::new(pool);
+
+        let ids: Vec<String> = [
+            "paid", "free", "mine", "bought", "disputed", "refunded", "missing", "free",
+        ]
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+        let entitlements = service.check("buyer", &ids).await.unwrap();
+
+        use EntitlementSource::*;
+        assert_eq!(
+            sources(&entitlements),
+            vec![
+                ("paid", None),
+                ("free", Some(Free)),
+                ("mine", Some(Author)),
+                ("bought", Some(Purchase)),
+                ("disputed", Some(Purchase)),
+                ("refunded", None),
+                ("missing", None),
+            ]
+        );
+        assert!(entitlements[1].owned);
+        assert!(!entitlements[0].owned);
+    }
+
+    #[tokio::test]
+    async fn purchases_outlive_deleted_scripts() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "gone", "author", 500, true).await;
+        insert_purchase(&pool, "buyer", "gone", "completed").await;
+        sqlx::query("UPDATE scripts SET deleted_at = '2025-02-01T00:00:00Z' WHERE id = 'gone'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = EntitlementService::new(pool);
+
+        let entitlements = service.check("buyer", &["gone".to_string()]).await.unwrap();
+        assert_eq!(entitlements[0].source, Some(EntitlementSource::Purchase));
+    }
+}
@@ -125,6 +125,7 @@ service_error! {
         Conflict => CONFLICT,
         BadRequest => BAD_REQUEST,
         Unauthorized => UNAUTHORIZED,
+        TooManyRequests => TOO_MANY_REQUESTS,
         Internal => INTERNAL_SERVER_ERROR,
     }
 }
@@ -133,12 +134,59 @@ service_error! {
     /// Errors emitted by [`super::ReviewService`] for `create_review`.
     ReviewError {
         NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
         Conflict => CONFLICT,
         BadRequest => BAD_REQUEST,
         Internal => INTERNAL_SERVER_ERROR,
     }
 }
 
+service_error! {
+    /// Errors emitted by [`super::QuestionService`] for script Q&A.
+    QuestionError {
+        NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
+        BadRequest => BAD_REQUEST,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::ModerationService`].
+    ModerationError {
+        NotFound => NOT_FOUND,
+        BadRequest => BAD_REQUEST,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::CompatibilityService`].
+    CompatibilityError {
+        NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::ErrorReportService`].
+    ErrorReportError {
+        NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::TelemetryService`].
+    TelemetryError {
+        NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
 service_error! {
     /// Errors emitted by [`super::PasskeyService`] (passkey registration /
     /// authentication, vault opaque-blob store, recovery codes). The vault
@@ -153,6 +201,74 @@ service_error! {
     }
 }
 
+service_error! {
+    /// Errors emitted by [`super::WebhookService`] when managing an author's
+    /// stats webhook.
+    WebhookError {
+        NotFound => NOT_FOUND,
+        BadRequest => BAD_REQUEST,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::PromotionService`] when an owner manages a
+    /// script's promotions.
+    PromotionError {
+        NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
+        Conflict => CONFLICT,
+        BadRequest => BAD_REQUEST,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::BundleService`] for bundle products.
+    BundleError {
+        NotFound => NOT_FOUND,
+        Forbidden => FORBIDDEN,
+        BadRequest => BAD_REQUEST,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::DisputeService`] for purchase disputes and
+    /// refunds. `BadGateway` means the ledger could not be asked.
+    DisputeError {
+        NotFound => NOT_FOUND,
+        Conflict => CONFLICT,
+        BadRequest => BAD_REQUEST,
+        BadGateway => BAD_GATEWAY,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::RevalidationService`] when a script's
+    /// validation result is read.
+    RevalidationError {
+        NotFound => NOT_FOUND,
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::MaintenanceService`] when an admin toggles
+    /// maintenance mode.
+    MaintenanceError {
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
+service_error! {
+    /// Errors emitted by [`super::IntegrityService`] for the admin routes.
+    IntegrityError {
+        Internal => INTERNAL_SERVER_ERROR,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,6 +361,16 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn script_too_many_requests_maps_429() {
+        assert_wire(
+            ScriptError::TooManyRequests("Too many script writes; retry in 60 seconds".into()),
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many script writes; retry in 60 seconds",
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn script_internal_maps_500() {
         assert_wire(
@@ -0,0 +1,280 @@
+use crate::error_reports::{self, ErrorReport};
+use crate::models::{Script, ScriptErrorReport, VersionFlag};
+use crate::repositories::{
+    ErrorReportRepository, NewErrorReport, ScriptRepository, TelemetryRepository,
+};
+use crate::services::error::ErrorReportError;
+use crate::telemetry::TelemetryEvent;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct ErrorReportService {
+    repo: ErrorReportRepository,
+    telemetry: TelemetryRepository,
+    scripts: ScriptRepository,
+}
+
+impl ErrorReportService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: ErrorReportRepository::new(pool.clone()),
+            telemetry: TelemetryRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool),
+        }
+    }
+
+    /// Stores a validated report against a public script, then flags its
+    /// version if the run failure rate spiked. Returns the stack hash.
+    pub async fn record(
+        &self,
+        script_id: &str,
+        report: &ErrorReport,
+    ) -> Result<String, ErrorReportError> {
+        let script = self.script(script_id).await?;
+        if !script.is_public {
+            return Err(ErrorReportError::NotFound("Script not found".to_string()));
+        }
+        let stack_hash = error_reports::stack_hash(report);
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .record(
+                &NewErrorReport {
+                    script_id,
+                    version: &report.version,
+                    stack_hash: &stack_hash,
+                    error_class: report.error_class.as_str(),
+                    message: &report.message,
+                    stack: &report.stack_text(),
+                    engine_version: &report.engine_version,
+                },
+                &now,
+            )
+            .await
+            .map_err(|e| ErrorReportError::Internal(format!("Failed to save error report: {e}")))?;
+
+        // The report is stored either way; a failed check only delays the
+        // flag to the next report.
+        if let Err(e) = self.flag_if_spiking(script_id, &report.version, &now).await {
+            tracing::warn!(
+                "Failed to check error rate of {} {}: {}",
+                script_id,
+                report.version,
+                e
+            );
+        }
+        Ok(stack_hash)
+    }
+
+    /// A page of the reports of a script owned by `account_id`, how many
+    /// there are in all, and its flagged versions.
+    pub async fn list(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        version: Option<&str>,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<ScriptErrorReport>, i64, Vec<VersionFlag>), ErrorReportError> {
+        let script = self.script(script_id).await?;
+        if script.owner_account_id.as_deref() != Some(account_id) {
+            return Err(ErrorReportError::Forbidden(
+                "Only the script owner can read its error reports".to_string(),
+            ));
+        }
+        let db_err = |e: sqlx::Error| {
+            ErrorReportError::Internal(format!("Failed to load error reports: {e}"))
+        };
+        let reports = self
+            .repo
+            .find_by_script(script_id, version, limit, offset)
+            .await
+            .map_err(db_err)?;
+        let total = self
+            .repo
+            .count_by_script(script_id, version)
+            .await
+            .map_err(db_err)?;
+        let flags = self.repo.flags_by_script(script_id).await.map_err(db_err)?;
+        Ok((reports, total, flags))
+    }
+
+    /// Compares the version's run failure rate with that of the script's
+    /// other versions, from the telemetry counters.
+    async fn flag_if_spiking(
+        &self,
+        script_id: &str,
+        version: &str,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        let (mut runs, mut failures, mut baseline_runs, mut baseline_failures) = (0, 0, 0, 0);
+        for (counted_version, event, error_class, count) in
+            self.telemetry.counts_by_version(script_id).await?
+        {
+            if event != TelemetryEvent::Run.as_str() {
+                continue;
+            }
+            let failed = if error_class.is_empty() { 0 } else { count };
+            if counted_version == version {
+                runs += count;
+                failures += failed;
+            } else {
+                baseline_runs += count;
+                baseline_failures += failed;
+            }
+        }
+        if !error_reports::is_spike(failures, runs, baseline_failures, baseline_runs) {
+            return Ok(());
+        }
+        let percent = |failures: i64, runs: i64| {
+            if runs == 0 {
+                0.0
+            } else {
+                100.0 * failures as f64 / runs as f64
+            }
+        };
+        let reason = format!(
+            "{:.0}% of {runs} runs failed, against {:.0}% for the other versions",
+            percent(failures, runs),
+            percent(baseline_failures, baseline_runs),
+        );
+        if self
+            .repo
+            .flag_version(script_id, version, &reason, now)
+            .await?
+        {
+            tracing::warn!("Flagged {} {}: {}", script_id, version, reason);
+        }
+        Ok(())
+    }
+
+    async fn script(&self, script_id: &str) -> Result<Script, ErrorReportError> {
+        self.scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| ErrorReportError::Internal(format!("Failed to load script: {e}")))?
+            .ok_or_else(|| ErrorReportError::NotFound("Script not found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::ErrorClass;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup() -> (SqlitePool, ErrorReportService) {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('owner', 'owner', 'owner', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO scripts (id, slug, owner_account_id, title, description, category, bundle,
+                                  is_public, created_at, updated_at)
+             VALUES ('s', 's', 'owner', 's', 'd', 'utility', 'b', 1,
+                     '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let service = ErrorReportService::new(pool.clone());
+        (pool, service)
+    }
+
+    fn report(version: &str, message: &str, frame: &str) -> ErrorReport {
+        ErrorReport {
+            version: version.to_string(),
+            engine_version: "0.9.0".to_string(),
+            error_class: ErrorClass::Runtime,
+            message: message.to_string(),
+            stack: vec![frame.to_string()],
+        }
+    }
+
+    async fn runs(pool: &SqlitePool, version: &str, successes: i64, failures: i64) {
+        let telemetry = TelemetryRepository::new(pool.clone());
+        for (error_class, n) in [("", successes), ("runtime", failures)] {
+            for _ in 0..n {
+                telemetry
+                    .increment(
+                        "s",
+                        version,
+                        "0.9.0",
+                        "run",
+                        error_class,
+                        "2025-01-01T00:00:00Z",
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn same_stack_is_counted_not_duplicated() {
+        let (_pool, service) = setup().await;
+        let first = service
+            .record("s", &report("1.0.0", "x is 1", "at main (a.js:1:1)"))
+            .await
+            .unwrap();
+        let again = service
+            .record("s", &report("1.0.0", "x is 2", "at main (a.js:1:1)"))
+            .await
+            .unwrap();
+        assert_eq!(first, again);
+        service
+            .record("s", &report("1.0.0", "x is 2", "at other (a.js:9:1)"))
+            .await
+            .unwrap();
+
+        let (reports, total, flags) = service.list("owner", "s", None, 20, 0).await.unwrap();
+        assert_eq!((reports.len(), total), (2, 2));
+        let counted = reports.iter().find(|r| r.stack_hash == first).unwrap();
+        assert_eq!(
+            (counted.occurrences, counted.message.as_str()),
+            (2, "x is 1")
+        );
+        assert!(flags.is_empty());
+        assert!(matches!(
+            service.list("someone", "s", None, 20, 0).await,
+            Err(ErrorReportError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn failure_spike_flags_the_version_once() {
+        let (pool, service) = setup().await;
+        runs(&pool, "1.0.0", 95, 5).await;
+        runs(&pool, "1.1.0", 12, 8).await;
+
+        service
+            .record("s", &report("1.0.0", "boom", "at f"))
+            .await
+            .unwrap();
+        service
+            .record("s", &report("1.1.0", "boom", "at f"))
+            .await
+            .unwrap();
+        service
+            .record("s", &report("1.1.0", "boom", "at g"))
+            .await
+            .unwrap();
+
+        let (reports, _, flags) = service
+            .list("owner", "s", Some("1.1.0"), 20, 0)
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(flags.len(), 1);
+        assert_eq!(flags[0].version, "1.1.0");
+        assert!(
+            flags[0].reason.starts_with("40% of 20 runs"),
+            "{}",
+            flags[0].reason
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::BTreeSet;
+
+use crate::auth::create_canonical_payload;
+use crate::integrity::{clean_tags, IssueKind, Trigger, MAX_LISTED_ISSUES};
+use crate::models::{AdminIntegrityCheckRequest, IntegrityCounts, IntegrityIssue, IntegrityReport};
+use crate::repositories::{
+    weighted_rating, AccountRepository, IntegrityRepository, ReviewRepository, ScriptAggregates,
+    ScriptRepository, SignatureAuditParams,
+};
+use crate::services::error::IntegrityError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// Most reports `GET /api/v1/admin/integrity-reports` returns.
+pub const MAX_REPORTS_PER_PAGE: i64 = 50;
+
+/// Stored and recomputed ratings closer than this are equal.
+const RATING_TOLERANCE: f64 = 1e-6;
+
+pub struct IntegrityService {
+    repo: IntegrityRepository,
+    scripts: ScriptRepository,
+    reviews: ReviewRepository,
+    account_repo: AccountRepository,
+}
+
+impl IntegrityService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: IntegrityRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool.clone()),
+            reviews: ReviewRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool),
+        }
+    }
+
+    /// Runs every check, repairs the issues found when `repair` is set, and
+    /// stores the report. See [`crate::integrity`].
+    pub async fn run(
+        &self,
+        trigger: Trigger,
+        repair: bool,
+    ) -> Result<IntegrityReport, sqlx::Error> {
+        let now = Utc::now().to_rfc3339();
+        let orphaned_reviews = self.repo.orphaned_reviews().await?;
+        let orphaned_scripts = self.repo.orphaned_scripts().await?;
+        let dangling_tags: Vec<(String, Option<Vec<String>>)> = self
+            .repo
+            .script_tags()
+            .await?
+            .into_iter()
+            .filter_map(|(id, raw)| clean_tags(&raw).map(|cleaned| (id, cleaned)))
+            .collect();
+        let mismatched: Vec<ScriptAggregates> = self
+            .repo
+            .script_aggregates()
+            .await?
+            .into_iter()
+            .filter(is_mismatched)
+            .collect();
+
+        let counts = IntegrityCounts {
+            orphaned_reviews: orphaned_reviews.len() as i64,
+            orphaned_scripts: orphaned_scripts.len() as i64,
+            dangling_tags: dangling_tags.len() as i64,
+            mismatched_aggregates: mismatched.len() as i64,
+        };
+        let issue = |kind, id: &str, detail: String| IntegrityIssue {
+            kind,
+            id: id.to_string(),
+            detail,
+        };
+        let mut issues: Vec<IntegrityIssue> = orphaned_reviews
+            .iter()
+            .map(|(id, _, detail)| issue(IssueKind::OrphanedReview, id, detail.clone()))
+            .chain(orphaned_scripts.iter().map(|(id, owner)| {
+                issue(
+                    IssueKind::OrphanedScript,
+                    id,
+                    format!("owner {owner} does not exist"),
+                )
+            }))
+            .chain(dangling_tags.iter().map(|(id, cleaned)| {
+                let detail = match cleaned {
+                    Some(tags) => format!("tags would become {tags:?}"),
+                    None => "tags are not a JSON list".to_string(),
+                };
+                issue(IssueKind::DanglingTags, id, detail)
+            }))
+            .chain(mismatched.iter().map(|a| {
+                issue(
+                    IssueKind::MismatchedAggregates,
+                    &a.id,
+                    format!(
+                        "stored {} reviews averaging {:.2}, visible reviews: {} averaging {:.2}",
+                        a.review_count, a.rating, a.visible_count, a.visible_average
+                    ),
+                )
+            }))
+            .collect();
+        let issue_count = issues.len() as i64;
+
+        if repair {
+            // Scripts whose aggregates change with the repair.
+            let mut refresh: BTreeSet<&str> = BTreeSet::new();
+            for (id, script_id, _) in &orphaned_reviews {
+                self.repo.delete_review(id).await?;
+                refresh.insert(script_id);
+            }
+            for (id, _) in &orphaned_scripts {
+                self.scripts.delete(id, &now).await?;
+            }
+            for (id, cleaned) in &dangling_tags {
+                let json = cleaned
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+                self.repo.set_tags(id, json.as_deref()).await?;
+            }
+            refresh.extend(mismatched.iter().map(|a| a.id.as_str()));
+            for script_id in refresh {
+                self.reviews.refresh_script_stats(script_id).await?;
+            }
+        }
+
+        issues.truncate(MAX_LISTED_ISSUES);
+        let report = IntegrityReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            trigger: trigger.as_str().to_string(),
+            repaired: repair && issue_count > 0,
+            issue_count,
+            counts,
+            issues,
+            created_at: now,
+        };
+        self.repo.save_report(&report).await?;
+        Ok(report)
+    }
+
+    /// Admin: runs the checks now. Audited as `admin_integrity_check`.
+    pub async fn admin_check(
+        &self,
+        req: &AdminIntegrityCheckRequest,
+    ) -> Result<IntegrityReport, IntegrityError> {
+        let report = self
+            .run(Trigger::Admin, req.repair)
+            .await
+            .map_err(|e| IntegrityError::Internal(format!("Integrity check failed: {e}")))?;
+
+        let now = Utc::now();
+        self.account_repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: None,
+                action: "admin_integrity_check",
+                payload: &create_canonical_payload(&serde_json::json!({
+                    "issue_count": report.issue_count,
+                    "reason": req.reason,
+                    "repair": req.repair,
+                    "report_id": report.id,
+                })),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: now.timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now.to_rfc3339(),
+            })
+            .await
+            .map_err(|e| IntegrityError::Internal(format!("Failed to record audit: {e}")))?;
+        Ok(report)
+    }
+
+    /// The latest reports, newest first.
+    pub async fn reports(&self, limit: i64) -> Result<Vec<IntegrityReport>, IntegrityError> {
+        self.repo
+            .recent_reports(limit.clamp(1, MAX_REPORTS_PER_PAGE))
+            .await
+            .map_err(|e| IntegrityError::Internal(format!("Failed to load reports: {e}")))
+    }
+}
+
+fn is_mismatched(a: &ScriptAggregates) -> bool {
+    a.review_count != a.visible_count
+        || (a.rating - a.visible_average).abs() > RATING_TOLERANCE
+        || (a.weighted_rating - weighted_rating(a.visible_average, a.visible_count)).abs()
+            > RATING_TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// A database with one issue of each kind; foreign keys are off while it
+    /// is filled so the orphans can be written.
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        let mut conn = pool.acquire().await.unwrap();
+        for sql in [
+            "PRAGMA foreign_keys = OFF",
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('owner', 'owner', 'owner', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z'),
+                    ('reader', 'reader', 'reader', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            "INSERT INTO scripts (id, slug, owner_account_id, title, description, category,
+                                  tags, bundle, created_at, updated_at)
+             VALUES ('kept', 'kept', 'owner', 'kept', 'd', 'utility', '[\"defi\",\"defi\"]', 'b',
+                     '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z'),
+                    ('abandoned', 'abandoned', 'gone', 'abandoned', 'd', 'utility', NULL, 'b',
+                     '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            "INSERT INTO reviews (id, script_id, user_id, rating, created_at, updated_at)
+             VALUES ('fine', 'kept', 'reader', 4, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z'),
+                    ('ghost', 'kept', 'gone', 1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z'),
+                    ('stray', 'missing', 'reader', 5, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            "PRAGMA foreign_keys = ON",
+        ] {
+            sqlx::query(sql).execute(&mut *conn).await.unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn finds_each_kind_and_repairs_them() {
+        let pool = setup().await;
+        let service = IntegrityService::new(pool.clone());
+
+        let report = service.run(Trigger::Scheduled, false).await.unwrap();
+        assert_eq!(
+            report.counts,
+            IntegrityCounts {
+                orphaned_reviews: 2,
+                orphaned_scripts: 1,
+                dangling_tags: 1,
+                mismatched_aggregates: 1,
+            }
+        );
+        assert_eq!(report.issue_count, 5);
+        assert!(!report.repaired);
+        // Reporting changes nothing.
+        assert_eq!(
+            service.run(Trigger::Scheduled, false).await.unwrap().counts,
+            report.counts
+        );
+
+        let repaired = service.run(Trigger::Admin, true).await.unwrap();
+        assert!(repaired.repaired);
+        assert_eq!(repaired.trigger, "admin");
+        let kept = ScriptRepository::new(pool.clone())
+            .find_by_id("kept")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.tags.as_deref(), Some(r#"["defi"]"#));
+        assert_eq!(kept.review_count, 1);
+        assert_eq!(kept.rating, 4.0);
+        let abandoned: Option<String> =
+            sqlx::query_scalar("SELECT deleted_at FROM scripts WHERE id = 'abandoned'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(abandoned.is_some());
+
+        let clean = service.run(Trigger::Scheduled, true).await.unwrap();
+        assert_eq!(clean.issue_count, 0);
+        assert!(!clean.repaired);
+        let reports = service.reports(10).await.unwrap();
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports[0].id, clean.id);
+        assert_eq!(reports[1], repaired);
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::auth::create_canonical_payload;
+use crate::models::{AdminMaintenanceRequest, MaintenanceState};
+use crate::repositories::{AccountRepository, MaintenanceRepository, SignatureAuditParams};
+use crate::services::error::MaintenanceError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// How long an instance trusts its cached copy of the switch. Toggling it on
+/// one instance reaches every other instance within this window.
+const STATE_TTL: Duration = Duration::from_secs(5);
+
+const DEFAULT_RETRY_AFTER_SECS: i64 = 300;
+
+pub struct MaintenanceService {
+    repo: MaintenanceRepository,
+    account_repo: AccountRepository,
+    cached: Mutex<Option<(Instant, MaintenanceState)>>,
+}
+
+impl MaintenanceService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: MaintenanceRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool),
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The current switch, read through a short-lived cache because every
+    /// write request asks. A failed read fails open: the API stays writable
+    /// rather than going read-only because the database hiccupped.
+    pub async fn current(&self) -> MaintenanceState {
+        if let Some((fetched_at, state)) = self.cached.lock().unwrap().as_ref() {
+            if fetched_at.elapsed() < STATE_TTL {
+                return state.clone();
+            }
+        }
+        match self.repo.get().await {
+            Ok(state) => {
+                *self.cached.lock().unwrap() = Some((Instant::now(), state.clone()));
+                state
+            }
+            Err(e) => {
+                tracing::error!("Failed to read maintenance state: {}", e);
+                MaintenanceState {
+                    enabled: false,
+                    message: None,
+                    retry_after_secs: DEFAULT_RETRY_AFTER_SECS,
+                    updated_at: None,
+                }
+            }
+        }
+    }
+
+    /// Turns maintenance mode on or off. Audited.
+    pub async fn set(
+        &self,
+        req: &AdminMaintenanceRequest,
+    ) -> Result<MaintenanceState, MaintenanceError> {
+        let now = Utc::now();
+        let message = req
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(str::to_string);
+        let state = MaintenanceState {
+            enabled: req.enabled,
+            message,
+            retry_after_secs: req.retry_after_secs.unwrap_or(DEFAULT_RETRY_AFTER_SECS),
+            updated_at: Some(now.to_rfc3339()),
+        };
+        self.repo
+            .set(&state)
+            .await
+            .map_err(|e| MaintenanceError::Internal(format!("Failed to save state: {e}")))?;
+        *self.cached.lock().unwrap() = Some((Instant::now(), state.clone()));
+
+        self.account_repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: None,
+                action: "admin_set_maintenance",
+                payload: &create_canonical_payload(&serde_json::json!({
+                    "enabled": state.enabled,
+                    "message": state.message,
+                    "retry_after_secs": state.retry_after_secs,
+                    "reason": req.reason,
+                })),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: now.timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now.to_rfc3339(),
+            })
+            .await
+            .map_err(|e| MaintenanceError::Internal(format!("Failed to record audit: {e}")))?;
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn starts_disabled_and_persists_toggles() {
+        let pool = setup_test_db().await;
+        let service = MaintenanceService::new(pool.clone());
+        assert!(!service.current().await.enabled);
+
+        let state = service
+            .set(&AdminMaintenanceRequest {
+                enabled: true,
+                message: Some("  Migrating  ".to_string()),
+                retry_after_secs: Some(120),
+                reason: "schema migration".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(state.message.as_deref(), Some("Migrating"));
+
+        // A second instance sees the switch through the database.
+        let other = MaintenanceService::new(pool);
+        let seen = other.current().await;
+        assert!(seen.enabled);
+        assert_eq!(seen.retry_after_secs, 120);
+    }
+}
@@ -1,16 +1,49 @@
 mod account_service;
+mod bundle_service;
+mod compatibility_service;
+mod dispute_service;
+mod entitlement_service;
 pub mod error;
+mod error_report_service;
+mod integrity_service;
+mod maintenance_service;
+mod moderation_service;
 mod passkey_service;
+mod promotion_service;
+mod question_service;
+mod revalidation_service;
 mod review_service;
 mod script_service;
+mod telemetry_service;
+mod webhook_service;
 
 pub use account_service::AccountService;
-pub use error::{AccountError, PasskeyError, ReviewError, ScriptError};
+pub use bundle_service::{BundleListing, BundleService, BundleUpdate, NewBundle};
+pub use compatibility_service::{
+    CompatibilityService, CompatibilitySummary, COMPATIBILITY_VOTE_ACTION,
+};
+pub use dispute_service::{DisputeService, PurchaseStatus};
+pub use entitlement_service::{Entitlement, EntitlementService, EntitlementSource};
+pub use error::{
+    AccountError, BundleError, CompatibilityError, DisputeError, ErrorReportError, IntegrityError,
+    MaintenanceError, ModerationError, PasskeyError, PromotionError, QuestionError,
+    RevalidationError, ReviewError, ScriptError, TelemetryError, WebhookError,
+};
+pub use error_report_service::ErrorReportService;
+pub use integrity_service::IntegrityService;
+pub use maintenance_service::MaintenanceService;
+pub use moderation_service::ModerationService;
 #[allow(unused_imports)]
 pub use passkey_service::{
     PasskeyAuthenticationFinish, PasskeyAuthenticationStart, PasskeyInfo,
     PasskeyRegistrationFinish, PasskeyRegistrationStart, PasskeyService, RecoveryCodesResponse,
     VaultData,
 };
-pub use review_service::ReviewService;
+pub use promotion_service::{NewPromotion, Offer, PromotionService};
+pub use question_service::{QuestionService, QUESTION_ANSWER_ACTION, QUESTION_ASK_ACTION};
+pub use revalidation_service::RevalidationService;
+pub use review_service::{ReviewService, REVIEW_CREATE_ACTION};
 pub use script_service::ScriptService;
+pub use telemetry_service::TelemetryService;
+pub(crate) use webhook_service::{is_public_ip, validate_webhook_url};
+pub use webhook_service::{WebhookDelivery, WebhookService};
@@ -0,0 +1,229 @@
+use crate::auth::{create_canonical_payload, validate_username};
+use crate::models::{AccountStrike, ModerationNote, ModerationStatus};
+use crate::repositories::{AccountRepository, ModerationRepository, SignatureAuditParams};
+use crate::services::error::ModerationError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// Admin moderation of accounts: notes, strikes and the restriction they
+/// bring (see [`crate::moderation`]). Every change is audited like the other
+/// admin actions.
+pub struct ModerationService {
+    repo: ModerationRepository,
+    accounts: AccountRepository,
+}
+
+impl ModerationService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: ModerationRepository::new(pool.clone()),
+            accounts: AccountRepository::new(pool),
+        }
+    }
+
+    pub async fn status(&self, username: &str) -> Result<ModerationStatus, ModerationError> {
+        let (account_id, username) = self.account(username).await?;
+        self.status_of(account_id, username).await
+    }
+
+    pub async fn add_note(
+        &self,
+        username: &str,
+        note: &str,
+    ) -> Result<ModerationStatus, ModerationError> {
+        let (account_id, username) = self.account(username).await?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .add_note(&id, &account_id, note, &now)
+            .await
+            .map_err(|e| ModerationError::Internal(format!("Failed to save note: {e}")))?;
+        self.audit(
+            &account_id,
+            "admin_moderation_note",
+            serde_json::json!({ "noteId": id, "username": username }),
+            &now,
+        )
+        .await?;
+        self.status_of(account_id, username).await
+    }
+
+    /// Gives the account a strike. Reaching a threshold restricts it at
+    /// once.
+    pub async fn add_strike(
+        &self,
+        username: &str,
+        reason: &str,
+    ) -> Result<ModerationStatus, ModerationError> {
+        let (account_id, username) = self.account(username).await?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .add_strike(&id, &account_id, reason, &now)
+            .await
+            .map_err(|e| ModerationError::Internal(format!("Failed to save strike: {e}")))?;
+        self.audit(
+            &account_id,
+            "admin_strike",
+            serde_json::json!({ "reason": reason, "strikeId": id, "username": username }),
+            &now,
+        )
+        .await?;
+        let status = self.status_of(account_id, username).await?;
+        tracing::info!(
+            "Strike for {} ({} active, restriction {:?}): {}",
+            status.username,
+            status.active_strikes,
+            status.restriction,
+            reason
+        );
+        Ok(status)
+    }
+
+    /// Revokes an active strike; the restriction follows the new count.
+    pub async fn revoke_strike(
+        &self,
+        username: &str,
+        strike_id: &str,
+        reason: &str,
+    ) -> Result<ModerationStatus, ModerationError> {
+        let (account_id, username) = self.account(username).await?;
+        let now = Utc::now().to_rfc3339();
+        if !self
+            .repo
+            .revoke_strike(&account_id, strike_id, reason, &now)
+            .await
+            .map_err(|e| ModerationError::Internal(format!("Failed to revoke strike: {e}")))?
+        {
+            return Err(ModerationError::NotFound(
+                "No active strike with that id".to_string(),
+            ));
+        }
+        self.audit(
+            &account_id,
+            "admin_revoke_strike",
+            serde_json::json!({ "reason": reason, "strikeId": strike_id, "username": username }),
+            &now,
+        )
+        .await?;
+        self.status_of(account_id, username).await
+    }
+
+    async fn account(&self, username: &str) -> Result<(String, String), ModerationError> {
+        let username = validate_username(username)
+            .map_err(|e| ModerationError::BadRequest(format!("Invalid username: {e}")))?;
+        let account = self
+            .accounts
+            .find_by_username(&username)
+            .await
+            .map_err(|e| ModerationError::Internal(format!("Database error: {e}")))?
+            .ok_or_else(|| ModerationError::NotFound("Account not found".to_string()))?;
+        Ok((account.id, account.username))
+    }
+
+    async fn status_of(
+        &self,
+        account_id: String,
+        username: String,
+    ) -> Result<ModerationStatus, ModerationError> {
+        let db_err = |e: sqlx::Error| ModerationError::Internal(format!("Database error: {e}"));
+        let strikes: Vec<AccountStrike> = self.repo.strikes(&account_id).await.map_err(db_err)?;
+        let notes: Vec<ModerationNote> = self.repo.notes(&account_id).await.map_err(db_err)?;
+        let active_strikes = strikes.iter().filter(|s| s.revoked_at.is_none()).count() as i64;
+        Ok(ModerationStatus {
+            account_id,
+            username,
+            active_strikes,
+            restriction: crate::moderation::Restriction::for_strikes(active_strikes),
+            strikes,
+            notes,
+        })
+    }
+
+    async fn audit(
+        &self,
+        account_id: &str,
+        action: &str,
+        payload: serde_json::Value,
+        now: &str,
+    ) -> Result<(), ModerationError> {
+        let mut payload = payload;
+        payload["action"] = action.into();
+        self.accounts
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: Some(account_id),
+                action,
+                payload: &create_canonical_payload(&payload),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: Utc::now().timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now,
+            })
+            .await
+            .map_err(|e| ModerationError::Internal(format!("Failed to record audit: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moderation::{Restriction, UPLOAD_FREEZE_STRIKES};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup() -> ModerationService {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('acc-1', 'mallory', 'Mallory', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        ModerationService::new(pool)
+    }
+
+    #[tokio::test]
+    async fn strikes_restrict_until_revoked() {
+        let service = setup().await;
+        service
+            .add_note("mallory", "Reported twice for copied scripts")
+            .await
+            .unwrap();
+        let mut status = service.status("mallory").await.unwrap();
+        for n in 0..UPLOAD_FREEZE_STRIKES {
+            assert_eq!(status.restriction, Restriction::None);
+            status = service
+                .add_strike("mallory", &format!("spam {n}"))
+                .await
+                .unwrap();
+        }
+        assert_eq!(status.restriction, Restriction::UploadFrozen);
+        assert_eq!(status.notes.len(), 1);
+
+        let strike_id = status.strikes[0].id.clone();
+        let status = service
+            .revoke_strike("mallory", &strike_id, "appeal upheld")
+            .await
+            .unwrap();
+        assert_eq!(status.active_strikes, UPLOAD_FREEZE_STRIKES - 1);
+        assert_eq!(status.restriction, Restriction::None);
+        assert_eq!(status.strikes.len() as i64, UPLOAD_FREEZE_STRIKES);
+        assert!(matches!(
+            service.revoke_strike("mallory", &strike_id, "again").await,
+            Err(ModerationError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_accounts_are_not_found() {
+        let service = setup().await;
+        assert!(matches!(
+            service.add_strike("nobody", "spam").await,
+            Err(ModerationError::NotFound(_))
+        ));
+    }
+}
@@ -42,6 +42,8 @@ pub struct PasskeyRegistrationFinish {
     pub device_type: Option<String>, // "platform" or "cross-platform"
 }
 
+impl crate::validation::Validate for PasskeyRegistrationFinish {}
+
 #[derive(Debug, Serialize)]
 pub struct PasskeyAuthenticationStart {
     pub challenge_id: String,
@@ -57,6 +59,8 @@ pub struct PasskeyAuthenticationFinish {
     pub credential: PublicKeyCredential,
 }
 
+impl crate::validation::Validate for PasskeyAuthenticationFinish {}
+
 #[derive(Debug, Serialize)]
 pub struct PasskeyInfo {
     pub id: String,
@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+
+use crate::models::{Promotion, Script};
+use crate::pricing::{discounted_e8s, DiscountKind, Price};
+use crate::repositories::{PromotionRepository, ScriptRepository};
+use crate::services::error::PromotionError;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+
+/// Longest a single promotion may run. Anything longer is a price change.
+pub const MAX_PROMOTION_DAYS: i64 = 90;
+
+/// An owner's request to discount a script. Times are RFC 3339.
+#[derive(Debug)]
+pub struct NewPromotion<'a> {
+    pub kind: &'a str,
+    pub amount: i64,
+    pub starts_at: &'a str,
+    pub ends_at: &'a str,
+}
+
+/// A promotion running right now and what it does to the script's price.
+#[derive(Debug, Clone)]
+pub struct Offer {
+    pub promotion: Promotion,
+    pub discounted_price_e8s: i64,
+}
+
+pub struct PromotionService {
+    repo: PromotionRepository,
+    scripts: ScriptRepository,
+}
+
+impl PromotionService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: PromotionRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool),
+        }
+    }
+
+    /// Schedules a promotion on a paid script owned by `account_id`. Windows
+    /// of one script may not overlap, so at most one promotion runs at a time.
+    /// A fixed discount is denominated in the script's current currency.
+    pub async fn create_promotion(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        new: NewPromotion<'_>,
+    ) -> Result<Promotion, PromotionError> {
+        let script = self.owned_script(account_id, script_id).await?;
+        if script.price_e8s == 0 {
+            return Err(PromotionError::BadRequest(
+                "Free scripts cannot be promoted".to_string(),
+            ));
+        }
+        let kind = DiscountKind::parse(new.kind).ok_or_else(|| {
+            PromotionError::BadRequest("kind must be 'percent' or 'fixed'".to_string())
+        })?;
+        match kind {
+            DiscountKind::Percent if !(1..=100).contains(&new.amount) => {
+                return Err(PromotionError::BadRequest(
+                    "A percent discount must be between 1 and 100".to_string(),
+                ));
+            }
+            DiscountKind::Fixed if new.amount <= 0 => {
+                return Err(PromotionError::BadRequest(
+                    "A fixed discount must be positive".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        let now = Utc::now();
+        let starts_at = parse_time("starts_at", new.starts_at)?;
+        let ends_at = parse_time("ends_at", new.ends_at)?;
+        if ends_at <= starts_at {
+            return Err(PromotionError::BadRequest(
+                "ends_at must be after starts_at".to_string(),
+            ));
+        }
+        if ends_at <= now {
+            return Err(PromotionError::BadRequest(
+                "ends_at must be in the future".to_string(),
+            ));
+        }
+        if ends_at - starts_at > Duration::days(MAX_PROMOTION_DAYS) {
+            return Err(PromotionError::BadRequest(format!(
+                "A promotion may run for at most {MAX_PROMOTION_DAYS} days"
+            )));
+        }
+
+        let promotion = Promotion {
+            id: uuid::Uuid::new_v4().to_string(),
+            script_id: script.id,
+            kind: kind.as_str().to_string(),
+            amount: new.amount,
+            currency: script.currency,
+            starts_at: starts_at.to_rfc3339(),
+            ends_at: ends_at.to_rfc3339(),
+            created_at: now.to_rfc3339(),
+        };
+        let overlapping = self
+            .repo
+            .count_overlapping(script_id, &promotion.starts_at, &promotion.ends_at)
+            .await
+            .map_err(|e| PromotionError::Internal(format!("Failed to check promotions: {e}")))?;
+        if overlapping > 0 {
+            return Err(PromotionError::Conflict(
+                "Another promotion of this script overlaps that window".to_string(),
+            ));
+        }
+        self.repo
+            .create(&promotion)
+            .await
+            .map_err(|e| PromotionError::Internal(format!("Failed to save promotion: {e}")))?;
+        Ok(promotion)
+    }
+
+    pub async fn list_promotions(
+        &self,
+        account_id: &str,
+        script_id: &str,
+    ) -> Result<Vec<Promotion>, PromotionError> {
+        self.owned_script(account_id, script_id).await?;
+        self.repo
+            .find_by_script(script_id)
+            .await
+            .map_err(|e| PromotionError::Internal(format!("Failed to load promotions: {e}")))
+    }
+
+    pub async fn delete_promotion(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        promotion_id: &str,
+    ) -> Result<(), PromotionError> {
+        self.owned_script(account_id, script_id).await?;
+        let removed = self
+            .repo
+            .delete(script_id, promotion_id)
+            .await
+            .map_err(|e| PromotionError::Internal(format!("Failed to delete promotion: {e}")))?;
+        if !removed {
+            return Err(PromotionError::NotFound("Promotion not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Running offers for `scripts`, keyed by script id, for listing
+    /// responses.
+    pub async fn offers_for(
+        &self,
+        scripts: &[Script],
+    ) -> Result<HashMap<String, Offer>, sqlx::Error> {
+        let ids: Vec<&str> = scripts
+            .iter()
+            .filter(|s| s.price_e8s > 0)
+            .map(|s| s.id.as_str())
+            .collect();
+        let running = self
+            .repo
+            .find_running(&ids, &Utc::now().to_rfc3339())
+            .await?;
+        let mut offers = HashMap::new();
+        for promotion in running {
+            let Some(script) = scripts.iter().find(|s| s.id == promotion.script_id) else {
+                continue;
+            };
+            if let Some(discounted) = apply(&promotion, script) {
+                offers.insert(
+                    promotion.script_id.clone(),
+                    Offer {
+                        promotion,
+                        discounted_price_e8s: discounted,
+                    },
+                );
+            }
+        }
+        Ok(offers)
+    }
+
+    /// The price a purchase of `script` must be verified against right now:
+    /// the listed price, lowered by the running promotion if there is one.
+    pub async fn effective_price(
+        &self,
+        script: &Script,
+    ) -> Result<(Price, Option<Promotion>), sqlx::Error> {
+        let mut offers = self.offers_for(std::slice::from_ref(script)).await?;
+        Ok(match offers.remove(&script.id) {
+            Some(offer) => (
+                Price::new(offer.discounted_price_e8s, &script.currency),
+                Some(offer.promotion),
+            ),
+            None => (Price::new(script.price_e8s, &script.currency), None),
+        })
+    }
+
+    async fn owned_script(
+        &self,
+        account_id: &str,
+        script_id: &str,
+    ) -> Result<Script, PromotionError> {
+        let script = self
+            .scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| PromotionError::Internal(format!("Failed to load script: {e}")))?
+            .ok_or_else(|| PromotionError::NotFound("Script not found".to_string()))?;
+        if script.owner_account_id.as_deref() != Some(account_id) {
+            return Err(PromotionError::Forbidden(
+                "Only the script owner can manage its promotions".to_string(),
+            ));
+        }
+        Ok(script)
+    }
+}
+
+/// The discounted price, or `None` when the promotion no longer applies: a
+/// fixed discount set before the owner switched the script's currency.
+fn apply(promotion: &Promotion, script: &Script) -> Option<i64> {
+    let kind = DiscountKind::parse(&promotion.kind)?;
+    if kind == DiscountKind::Fixed && promotion.currency != script.currency {
+        return None;
+    }
+    Some(discounted_e8s(script.price_e8s, kind, promotion.amount))
+}
+
+fn parse_time(field: &str, value: &str) -> Result<DateTime<Utc>, PromotionError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| PromotionError::BadRequest(format!("{field} must be an RFC 3339 timestamp")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::insert_script;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    fn window(from_hours: i64, to_hours: i64) -> (String, String) {
+        let now = Utc::now();
+        (
+            (now + Duration::hours(from_hours)).to_rfc3339(),
+            (now + Duration::hours(to_hours)).to_rfc3339(),
+        )
+    }
+
+    fn percent<'a>(amount: i64, window: &'a (String, String)) -> NewPromotion<'a> {
+        NewPromotion {
+            kind: "percent",
+            amount,
+            starts_at: &window.0,
+            ends_at: &window.1,
+        }
+    }
+
+    #[tokio::test]
+    async fn running_promotion_lowers_the_effective_price() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "paid", "owner", 1_000_000_000, true).await;
+        let scripts = ScriptRepository::new(pool.clone());
+        let service = PromotionService::new(pool);
+
+        let now = window(-1, 24);
+        service
+            .create_promotion("owner", "paid", percent(25, &now))
+            .await
+            .unwrap();
+
+        let script = scripts.find_by_id("paid").await.unwrap().unwrap();
+        let (price, promotion) = service.effective_price(&script).await.unwrap();
+        assert_eq!(price, Price::new(750_000_000, "ICP"));
+        assert!(promotion.is_some());
+        let offers = service.offers_for(&[script]).await.unwrap();
+        assert_eq!(offers["paid"].discounted_price_e8s, 750_000_000);
+    }
+
+    #[tokio::test]
+    async fn scheduled_promotion_does_not_apply_yet() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "paid", "owner", 500, true).await;
+        let scripts = ScriptRepository::new(pool.clone());
+        let service = PromotionService::new(pool);
+
+        let later = window(24, 48);
+        service
+            .create_promotion("owner", "paid", percent(50, &later))
+            .await
+            .unwrap();
+
+        let script = scripts.find_by_id("paid").await.unwrap().unwrap();
+        let (price, promotion) = service.effective_price(&script).await.unwrap();
+        assert_eq!(price.e8s, 500);
+        assert!(promotion.is_none());
+    }
+
+    #[tokio::test]
+    async fn overlapping_windows_conflict() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "paid", "owner", 500, true).await;
+        let service = PromotionService::new(pool);
+
+        service
+            .create_promotion("owner", "paid", percent(10, &window(0, 10)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            service
+                .create_promotion("owner", "paid", percent(20, &window(5, 15)))
+                .await,
+            Err(PromotionError::Conflict(_))
+        ));
+        service
+            .create_promotion("owner", "paid", percent(20, &window(10, 15)))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn only_owners_of_paid_scripts_may_promote() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "paid", "owner", 500, true).await;
+        insert_script(&pool, "free", "owner", 0, true).await;
+        let service = PromotionService::new(pool);
+        let w = window(0, 1);
+
+        assert!(matches!(
+            service
+                .create_promotion("intruder", "paid", percent(10, &w))
+                .await,
+            Err(PromotionError::Forbidden(_))
+        ));
+        assert!(matches!(
+            service
+                .create_promotion("owner", "free", percent(10, &w))
+                .await,
+            Err(PromotionError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .create_promotion("owner", "paid", percent(101, &w))
+                .await,
+            Err(PromotionError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .create_promotion("owner", "paid", percent(10, &window(0, 24 * 91)))
+                .await,
+            Err(PromotionError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_removes_only_the_scripts_own_promotion() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "a", "owner", 500, true).await;
+        insert_script(&pool, "b", "owner", 500, true).await;
+        let service = PromotionService::new(pool);
+
+        let promo = service
+            .create_promotion("owner", "a", percent(10, &window(0, 1)))
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.delete_promotion("owner", "b", &promo.id).await,
+            Err(PromotionError::NotFound(_))
+        ));
+        service
+            .delete_promotion("owner", "a", &promo.id)
+            .await
+            .unwrap();
+        assert!(service
+            .list_promotions("owner", "a")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::models::{Script, ScriptQuestion, UnansweredQuestions};
+use crate::moderation::restricted_message;
+use crate::repositories::{ModerationRepository, QuestionRepository, ScriptRepository};
+use crate::services::error::QuestionError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// Signed action names of the Q&A routes, mirrored by the clients inside the
+/// canonical payload.
+pub const QUESTION_ASK_ACTION: &str = "question:ask";
+pub const QUESTION_ANSWER_ACTION: &str = "question:answer";
+
+/// Markdown source length limits, in characters.
+pub const MAX_QUESTION_CHARS: usize = 2_000;
+pub const MAX_ANSWER_CHARS: usize = 10_000;
+
+pub struct QuestionService {
+    repo: QuestionRepository,
+    scripts: ScriptRepository,
+    moderation: ModerationRepository,
+}
+
+impl QuestionService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: QuestionRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool.clone()),
+            moderation: ModerationRepository::new(pool),
+        }
+    }
+
+    /// Records a question by `account_id` about a script it can see.
+    pub async fn ask(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        question: &str,
+    ) -> Result<ScriptQuestion, QuestionError> {
+        self.visible_script(script_id, Some(account_id)).await?;
+        self.check_writes_allowed(account_id).await?;
+        check_text("question", question, MAX_QUESTION_CHARS)?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .create(&id, script_id, account_id, question, &now)
+            .await
+            .map_err(|e| QuestionError::Internal(format!("Failed to save question: {e}")))?;
+        Ok(ScriptQuestion {
+            id,
+            script_id: script_id.to_string(),
+            account_id: account_id.to_string(),
+            question: question.to_string(),
+            answer: None,
+            created_at: now,
+            answered_at: None,
+        })
+    }
+
+    /// Answers a question about a script owned by `account_id`. Answering
+    /// again replaces the answer.
+    pub async fn answer(
+        &self,
+        account_id: &str,
+        script_id: &str,
+        question_id: &str,
+        answer: &str,
+    ) -> Result<ScriptQuestion, QuestionError> {
+        let script = self.visible_script(script_id, Some(account_id)).await?;
+        if script.owner_account_id.as_deref() != Some(account_id) {
+            return Err(QuestionError::Forbidden(
+                "Only the script owner can answer its questions".to_string(),
+            ));
+        }
+        self.check_writes_allowed(account_id).await?;
+        check_text("answer", answer, MAX_ANSWER_CHARS)?;
+
+        let db_err =
+            |e: sqlx::Error| QuestionError::Internal(format!("Failed to save answer: {e}"));
+        let now = Utc::now().to_rfc3339();
+        if !self
+            .repo
+            .answer(script_id, question_id, answer, &now)
+            .await
+            .map_err(db_err)?
+        {
+            return Err(QuestionError::NotFound("Question not found".to_string()));
+        }
+        self.repo
+            .find_by_id(script_id, question_id)
+            .await
+            .map_err(db_err)?
+            .ok_or_else(|| QuestionError::NotFound("Question not found".to_string()))
+    }
+
+    /// A page of the script's questions, newest first, and how many there
+    /// are in all. A private script's questions are listed only to its
+    /// owner (`viewer`).
+    pub async fn list(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+        unanswered_only: bool,
+        limit: i32,
+        offset: i32,
+    ) -> Result<(Vec<ScriptQuestion>, i64), QuestionError> {
+        self.visible_script(script_id, viewer).await?;
+        let db_err =
+            |e: sqlx::Error| QuestionError::Internal(format!("Failed to load questions: {e}"));
+        let questions = self
+            .repo
+            .find_by_script(script_id, unanswered_only, limit, offset)
+            .await
+            .map_err(db_err)?;
+        let total = self
+            .repo
+            .count_by_script(script_id, unanswered_only)
+            .await
+            .map_err(db_err)?;
+        Ok((questions, total))
+    }
+
+    /// How many questions about the script wait for an answer.
+    pub async fn unanswered_count(&self, script_id: &str) -> Result<i64, sqlx::Error> {
+        self.repo.count_by_script(script_id, true).await
+    }
+
+    /// The scripts of `account_id` with unanswered questions, for its feed.
+    pub async fn unanswered_for_owner(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<UnansweredQuestions>, sqlx::Error> {
+        self.repo.unanswered_by_owner(account_id).await
+    }
+
+    /// 403 while moderation strikes freeze the account's writes.
+    async fn check_writes_allowed(&self, account_id: &str) -> Result<(), QuestionError> {
+        let restriction =
+            self.moderation.restriction(account_id).await.map_err(|e| {
+                QuestionError::Internal(format!("Failed to check restrictions: {e}"))
+            })?;
+        if !restriction.allows_community_writes() {
+            return Err(QuestionError::Forbidden(
+                restricted_message(restriction).to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The script, if it is public or owned by `viewer`; a private script
+    /// does not exist for anyone else.
+    async fn visible_script(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+    ) -> Result<Script, QuestionError> {
+        let not_found = || QuestionError::NotFound("Script not found".to_string());
+        let script = self
+            .scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| QuestionError::Internal(format!("Failed to load script: {e}")))?
+            .ok_or_else(not_found)?;
+        if !script.is_public
+            && script
+                .owner_account_id
+                .as_deref()
+                .is_none_or(|owner| Some(owner) != viewer)
+        {
+            return Err(not_found());
+        }
+        Ok(script)
+    }
+}
+
+fn check_text(field: &str, text: &str, max_chars: usize) -> Result<(), QuestionError> {
+    if text.trim().is_empty() {
+        return Err(QuestionError::BadRequest(format!(
+            "{field} must not be empty"
+        )));
+    }
+    if text.chars().count() > max_chars {
+        return Err(QuestionError::BadRequest(format!(
+            "{field} must be at most {max_chars} characters"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::insert_script;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    async fn insert_account(pool: &SqlitePool, id: &str) {
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES (?1, ?1, ?1, '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn answering_clears_the_unanswered_counts() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "s", "owner", 0, true).await;
+        insert_account(&pool, "asker").await;
+        let service = QuestionService::new(pool);
+
+        let first = service
+            .ask("asker", "s", "Does it work on mainnet?")
+            .await
+            .unwrap();
+        service
+            .ask("asker", "s", "Any **rate limits**?")
+            .await
+            .unwrap();
+        assert_eq!(service.unanswered_count("s").await.unwrap(), 2);
+        let waiting = service.unanswered_for_owner("owner").await.unwrap();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!((waiting[0].script_id.as_str(), waiting[0].count), ("s", 2));
+
+        let answered = service
+            .answer("owner", "s", &first.id, "Yes, since 1.2.")
+            .await
+            .unwrap();
+        assert_eq!(answered.answer.as_deref(), Some("Yes, since 1.2."));
+        assert!(answered.answered_at.is_some());
+        assert_eq!(service.unanswered_count("s").await.unwrap(), 1);
+
+        let (all, total) = service.list("s", None, false, 20, 0).await.unwrap();
+        assert_eq!((all.len(), total), (2, 2));
+        let (open, total) = service.list("s", None, true, 20, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(open[0].question, "Any **rate limits**?");
+        assert!(service
+            .unanswered_for_owner("asker")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_answers() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "s", "owner", 0, true).await;
+        insert_script(&pool, "other", "owner", 0, true).await;
+        insert_account(&pool, "asker").await;
+        let service = QuestionService::new(pool);
+
+        let question = service.ask("asker", "s", "How?").await.unwrap();
+        assert!(matches!(
+            service
+                .answer("asker", "s", &question.id, "Like this")
+                .await,
+            Err(QuestionError::Forbidden(_))
+        ));
+        assert!(matches!(
+            service
+                .answer("owner", "other", &question.id, "Like this")
+                .await,
+            Err(QuestionError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.answer("owner", "s", &question.id, "  ").await,
+            Err(QuestionError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn private_scripts_take_questions_from_their_owner_only() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "draft", "owner", 0, false).await;
+        insert_account(&pool, "asker").await;
+        let service = QuestionService::new(pool);
+
+        assert!(matches!(
+            service.ask("asker", "draft", "Hello?").await,
+            Err(QuestionError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.list("draft", None, false, 20, 0).await,
+            Err(QuestionError::NotFound(_))
+        ));
+        service.ask("owner", "draft", "Note to self").await.unwrap();
+        let (questions, _) = service
+            .list("draft", Some("owner"), false, 20, 0)
+            .await
+            .unwrap();
+        assert_eq!(questions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn overlong_questions_are_rejected() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "s", "owner", 0, true).await;
+        insert_account(&pool, "asker").await;
+        let service = QuestionService::new(pool);
+
+        let long = "?".repeat(MAX_QUESTION_CHARS + 1);
+        assert!(matches!(
+            service.ask("asker", "s", &long).await,
+            Err(QuestionError::BadRequest(_))
+        ));
+    }
+}
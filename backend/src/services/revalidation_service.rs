@@ -0,0 +1,201 @@
+use crate::models::{Script, ScriptValidation};
+use crate::repositories::{ScriptRepository, ValidationRepository};
+use crate::script_preview::{self, PreviewRequest};
+use crate::services::error::RevalidationError;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+/// Checks a bundle, returning one message per problem.
+pub type BundleCheck = fn(&str) -> Vec<String>;
+
+/// The engine's checks: its static validation stages (UI node schema
+/// included), then `init` and one `view` in the sandbox without any mocked
+/// call results, as the app first shows the script.
+pub fn check_bundle(bundle: &str) -> Vec<String> {
+    let preview = script_preview::run(&PreviewRequest {
+        bundle: bundle.to_string(),
+        arg: None,
+        mocks: Default::default(),
+    });
+    preview
+        .errors
+        .into_iter()
+        .map(|e| format!("{}: {}", e.stage.as_str(), e.message.replace('\n', " ")))
+        .collect()
+}
+
+pub struct RevalidationService {
+    repo: ValidationRepository,
+    scripts: ScriptRepository,
+    rules_version: i64,
+    check: BundleCheck,
+}
+
+impl RevalidationService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: ValidationRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool),
+            rules_version: i64::from(icp_core::js_engine::static_analysis::RULES_VERSION),
+            check: check_bundle,
+        }
+    }
+
+    /// Validates under `rules_version` with `check` instead of the engine's.
+    #[cfg(test)]
+    fn with_rules(mut self, rules_version: i64, check: BundleCheck) -> Self {
+        self.rules_version = rules_version;
+        self.check = check;
+        self
+    }
+
+    /// Checks up to `limit` public scripts that are due (see
+    /// [`ValidationRepository::due`]) and returns those that started
+    /// failing, with their new result.
+    pub async fn revalidate_due(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(Script, ScriptValidation)>, sqlx::Error> {
+        let mut newly_failing = Vec::new();
+        for script in self.repo.due(self.rules_version, limit).await? {
+            let check = self.check;
+            let bundle = script.bundle.clone();
+            let errors = match tokio::task::spawn_blocking(move || check(&bundle)).await {
+                Ok(errors) => errors,
+                Err(e) => {
+                    tracing::error!("Validation of script {} failed to run: {}", script.id, e);
+                    continue;
+                }
+            };
+            let previous = self.repo.find(&script.id).await?;
+            let now = Utc::now().to_rfc3339();
+            let was_failing = previous.as_ref().is_some_and(|p| !p.valid);
+            let failing_since = match (&previous, errors.is_empty()) {
+                (_, true) => None,
+                (Some(previous), false) if was_failing => previous.failing_since.clone(),
+                (_, false) => Some(now.clone()),
+            };
+            let validation = ScriptValidation {
+                script_id: script.id.clone(),
+                version: script.version.clone(),
+                rules_version: self.rules_version,
+                valid: errors.is_empty(),
+                errors,
+                failing_since,
+                checked_at: now,
+            };
+            self.repo.save(&validation).await?;
+            if !validation.valid && !was_failing {
+                tracing::warn!(
+                    "Script {} {} fails validation rules v{}: {}",
+                    script.id,
+                    script.version,
+                    self.rules_version,
+                    validation.errors.join("; ")
+                );
+                newly_failing.push((script, validation));
+            }
+        }
+        Ok(newly_failing)
+    }
+
+    /// The latest result of a public script.
+    pub async fn status(&self, script_id: &str) -> Result<ScriptValidation, RevalidationError> {
+        let not_found = || RevalidationError::NotFound("Script not validated yet".to_string());
+        let script = self
+            .scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| RevalidationError::Internal(format!("Failed to load script: {e}")))?
+            .ok_or_else(|| RevalidationError::NotFound("Script not found".to_string()))?;
+        if !script.is_public {
+            return Err(RevalidationError::NotFound("Script not found".to_string()));
+        }
+        self.repo
+            .find(script_id)
+            .await
+            .map_err(|e| RevalidationError::Internal(format!("Failed to load validation: {e}")))?
+            .ok_or_else(not_found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('owner', 'owner', 'owner', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (id, bundle, is_public) in [
+            ("good", "ok", 1),
+            ("bad", "broken", 1),
+            ("draft", "broken", 0),
+        ] {
+            sqlx::query(
+                "INSERT INTO scripts (id, slug, owner_account_id, title, description, category,
+                                      bundle, is_public, created_at, updated_at)
+                 VALUES (?1, ?1, 'owner', ?1, 'd', 'utility', ?2, ?3,
+                         '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+            )
+            .bind(id)
+            .bind(bundle)
+            .bind(is_public)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        pool
+    }
+
+    fn rejects_broken(bundle: &str) -> Vec<String> {
+        if bundle == "broken" {
+            vec!["view: UI node missing type".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn accepts_all(_: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[tokio::test]
+    async fn only_scripts_that_start_failing_are_reported() {
+        let pool = setup().await;
+        let v1 = RevalidationService::new(pool.clone()).with_rules(1, accepts_all);
+        assert!(v1.revalidate_due(10).await.unwrap().is_empty());
+        assert!(v1.status("good").await.unwrap().valid);
+        assert!(matches!(
+            v1.status("draft").await,
+            Err(RevalidationError::NotFound(_))
+        ));
+
+        // A rules bump makes every public script due again.
+        let v2 = RevalidationService::new(pool.clone()).with_rules(2, rejects_broken);
+        let failing = v2.revalidate_due(10).await.unwrap();
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].0.id, "bad");
+        let bad = v2.status("bad").await.unwrap();
+        assert!(!bad.valid);
+        assert_eq!(bad.rules_version, 2);
+        assert_eq!(bad.errors, ["view: UI node missing type"]);
+        assert!(bad.failing_since.is_some());
+        assert!(v2.revalidate_due(10).await.unwrap().is_empty());
+
+        // Still failing under newer rules: not reported again.
+        let v3 = RevalidationService::new(pool).with_rules(3, rejects_broken);
+        assert!(v3.revalidate_due(10).await.unwrap().is_empty());
+        assert_eq!(
+            v3.status("bad").await.unwrap().failing_since,
+            bad.failing_since
+        );
+    }
+}
@@ -1,27 +1,107 @@
-use crate::models::{CreateReviewRequest, Review};
-use crate::repositories::{ReviewRepository, ScriptRepository};
+use crate::auth::create_canonical_payload;
+use crate::models::{ActivityKind, CreateReviewRequest, QuarantinedReview, Review};
+use crate::moderation::restricted_message;
+use crate::repositories::{
+    AccountRepository, ActivityRepository, ModerationRepository, ReviewRepository,
+    ScriptRepository, SignatureAuditParams,
+};
 use crate::services::error::ReviewError;
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use sqlx::SqlitePool;
 
+/// Single source of truth for the signed review action name. The frontend
+/// mirrors this EXACT string inside the canonical payload, and the burst
+/// check counts audit rows recorded under it.
+pub const REVIEW_CREATE_ACTION: &str = "review:create";
+
+/// Why a review was quarantined. Stored in `reviews.quarantine_reason`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuarantineReason {
+    /// Too many reviews signed by one key in a short window.
+    KeyBurst,
+    /// The same comment was already posted on another script.
+    DuplicateComment,
+    /// A fresh account handing out 5-star ratings in bulk.
+    NewAccountFiveStar,
+}
+
+impl QuarantineReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::KeyBurst => "key_burst",
+            Self::DuplicateComment => "duplicate_comment",
+            Self::NewAccountFiveStar => "new_account_five_star",
+        }
+    }
+}
+
+/// Thresholds for the review spam heuristics. A review that trips any of
+/// them is stored quarantined instead of published.
+#[derive(Debug, Clone)]
+pub struct SpamRules {
+    /// Signed review requests allowed per key within `burst_window`.
+    pub burst_max_reviews: i64,
+    pub burst_window: Duration,
+    /// Shorter comments ("great!", "works") are legitimately repeated and
+    /// are never treated as duplicates.
+    pub duplicate_min_chars: usize,
+    /// Accounts younger than this count as new.
+    pub new_account_age: Duration,
+    /// 5-star reviews a new account may post before further ones are held.
+    pub new_account_max_five_star: i64,
+}
+
+impl Default for SpamRules {
+    fn default() -> Self {
+        Self {
+            burst_max_reviews: 3,
+            burst_window: Duration::minutes(10),
+            duplicate_min_chars: 20,
+            new_account_age: Duration::hours(24),
+            new_account_max_five_star: 2,
+        }
+    }
+}
+
+/// Outcome of [`ReviewService::create_review`]: the stored review and, when
+/// it was held for moderation, why.
+#[derive(Debug)]
+pub struct ReviewSubmission {
+    pub review: Review,
+    pub quarantine_reason: Option<QuarantineReason>,
+}
+
 pub struct ReviewService {
     review_repo: ReviewRepository,
     script_repo: ScriptRepository,
+    account_repo: AccountRepository,
+    activity: ActivityRepository,
+    moderation: ModerationRepository,
+    spam_rules: SpamRules,
 }
 
 impl ReviewService {
     pub fn new(pool: SqlitePool) -> Self {
         Self {
             review_repo: ReviewRepository::new(pool.clone()),
-            script_repo: ScriptRepository::new(pool),
+            script_repo: ScriptRepository::new(pool.clone()),
+            account_repo: AccountRepository::new(pool.clone()),
+            activity: ActivityRepository::new(pool.clone()),
+            moderation: ModerationRepository::new(pool),
+            spam_rules: SpamRules::default(),
         }
     }
 
+    pub fn with_spam_rules(mut self, spam_rules: SpamRules) -> Self {
+        self.spam_rules = spam_rules;
+        self
+    }
+
     pub async fn create_review(
         &self,
         script_id: &str,
         req: CreateReviewRequest,
-    ) -> Result<Review, ReviewError> {
+    ) -> Result<ReviewSubmission, ReviewError> {
         // Verify script exists
         let script_count = self
             .script_repo
@@ -33,6 +113,17 @@ impl ReviewService {
             return Err(ReviewError::NotFound("Script not found".to_string()));
         }
 
+        let restriction = self
+            .moderation
+            .restriction(&req.user_id)
+            .await
+            .map_err(|e| ReviewError::Internal(format!("Failed to check restrictions: {e}")))?;
+        if !restriction.allows_community_writes() {
+            return Err(ReviewError::Forbidden(
+                restricted_message(restriction).to_string(),
+            ));
+        }
+
         // Check if user already reviewed
         let existing_count = self
             .review_repo
@@ -53,22 +144,43 @@ impl ReviewService {
             ));
         }
 
+        let quarantine_reason = self
+            .spam_check(script_id, &req)
+            .await
+            .map_err(|e| ReviewError::Internal(format!("Failed to run spam checks: {e}")))?;
+
         // Create review
         let review_id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
 
-        if let Err(e) = self
-            .review_repo
-            .create(
-                &review_id,
-                script_id,
-                &req.user_id,
-                req.rating,
-                req.comment.as_deref(),
-                &now,
-            )
-            .await
-        {
+        let created = match quarantine_reason {
+            None => {
+                self.review_repo
+                    .create(
+                        &review_id,
+                        script_id,
+                        &req.user_id,
+                        req.rating,
+                        req.comment.as_deref(),
+                        &now,
+                    )
+                    .await
+            }
+            Some(reason) => {
+                self.review_repo
+                    .create_quarantined(
+                        &review_id,
+                        script_id,
+                        &req.user_id,
+                        req.rating,
+                        req.comment.as_deref(),
+                        &now,
+                        reason.as_str(),
+                    )
+                    .await
+            }
+        };
+        if let Err(e) = created {
             // W7-16: the UNIQUE(script_id, user_id) index makes the dup-check
             // race-free. A UNIQUE violation here means a concurrent review from
             // the same user won the race past the COUNT(*) guard above — map it
@@ -85,49 +197,201 @@ impl ReviewService {
             )));
         }
 
-        // Update script stats
-        let avg_rating = self
-            .review_repo
-            .get_average_rating(script_id)
+        // A quarantined review is not part of the aggregates yet.
+        if quarantine_reason.is_none() {
+            self.refresh_script_stats(script_id).await?;
+        }
+        // Best-effort; the feed hides the entry while the review is held.
+        if let Err(e) = self
+            .activity
+            .record(
+                &req.user_id,
+                ActivityKind::ReviewPosted,
+                script_id,
+                None,
+                Some(&review_id),
+                &now,
+            )
             .await
-            .map_err(|e| ReviewError::Internal(format!("Failed to calculate avg rating: {e}")))?
-            .unwrap_or(0.0);
+        {
+            tracing::warn!(
+                "Failed to log review {} on the activity feed: {}",
+                review_id,
+                e
+            );
+        }
 
-        let review_count = self
-            .review_repo
-            .count_by_script(script_id)
+        Ok(ReviewSubmission {
+            review: Review {
+                id: review_id,
+                script_id: script_id.to_string(),
+                user_id: req.user_id,
+                rating: req.rating,
+                comment: req.comment,
+                created_at: now.clone(),
+                updated_at: now,
+            },
+            quarantine_reason,
+        })
+    }
+
+    /// Runs the spam heuristics against a review about to be stored and
+    /// returns the first one it trips.
+    async fn spam_check(
+        &self,
+        script_id: &str,
+        req: &CreateReviewRequest,
+    ) -> Result<Option<QuarantineReason>, sqlx::Error> {
+        let rules = &self.spam_rules;
+        let now = Utc::now();
+
+        // The signature gate audits the request before it gets here, so the
+        // count already includes this review.
+        if let Some(public_key) = req.public_key.as_deref() {
+            let since = (now - rules.burst_window).to_rfc3339();
+            let recent = self
+                .account_repo
+                .count_signed_actions_since(REVIEW_CREATE_ACTION, public_key, &since)
+                .await?;
+            if recent > rules.burst_max_reviews {
+                return Ok(Some(QuarantineReason::KeyBurst));
+            }
+        }
+
+        if let Some(comment) = req.comment.as_deref() {
+            let normalized = comment.trim().to_lowercase();
+            if normalized.chars().count() >= rules.duplicate_min_chars
+                && self
+                    .review_repo
+                    .count_same_comment_elsewhere(script_id, &normalized)
+                    .await?
+                    > 0
+            {
+                return Ok(Some(QuarantineReason::DuplicateComment));
+            }
+        }
+
+        if req.rating == 5 {
+            let created_since = (now - rules.new_account_age).to_rfc3339();
+            let five_stars = self
+                .review_repo
+                .count_five_star_if_account_created_since(&req.user_id, &created_since)
+                .await?;
+            if five_stars.is_some_and(|n| n >= rules.new_account_max_five_star) {
+                return Ok(Some(QuarantineReason::NewAccountFiveStar));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Recomputes the stored rating and review count of a script from its
+    /// (publicly visible) reviews.
+    async fn refresh_script_stats(&self, script_id: &str) -> Result<(), ReviewError> {
+        self.review_repo
+            .refresh_script_stats(script_id)
             .await
-            .map_err(|e| ReviewError::Internal(format!("Failed to count reviews: {e}")))?;
+            .map_err(|e| ReviewError::Internal(format!("Failed to update script stats: {e}")))
+    }
 
-        self.script_repo
-            .update_stats(script_id, avg_rating, review_count)
+    /// Re-aggregates every script a user has reviewed. Called when the user's
+    /// shadow-ban changes so their ratings drop out of (or back into) the
+    /// stored averages immediately.
+    pub async fn refresh_stats_for_reviewer(&self, user_id: &str) -> Result<(), ReviewError> {
+        let script_ids = self
+            .review_repo
+            .script_ids_reviewed_by(user_id)
             .await
-            .map_err(|e| ReviewError::Internal(format!("Failed to update script stats: {e}")))?;
-
-        Ok(Review {
-            id: review_id,
-            script_id: script_id.to_string(),
-            user_id: req.user_id,
-            rating: req.rating,
-            comment: req.comment,
-            created_at: now.clone(),
-            updated_at: now,
-        })
+            .map_err(|e| ReviewError::Internal(format!("Failed to list reviews: {e}")))?;
+        for script_id in script_ids {
+            self.refresh_script_stats(&script_id).await?;
+        }
+        Ok(())
     }
 
+    /// A page of a script's reviews and their total. `viewer` also sees
+    /// their own reviews while shadow-banned; the rating aggregates never
+    /// count them.
     pub async fn get_reviews(
         &self,
         script_id: &str,
+        viewer: Option<&str>,
         limit: i32,
         offset: i32,
     ) -> Result<(Vec<Review>, i32), sqlx::Error> {
         let reviews = self
             .review_repo
-            .find_by_script(script_id, limit, offset)
+            .find_by_script(script_id, viewer, limit, offset)
             .await?;
-        let total = self.review_repo.count_by_script(script_id).await?;
+        let total = self.review_repo.count_by_script(script_id, viewer).await?;
         Ok((reviews, total))
     }
+
+    /// The moderation queue, oldest first.
+    pub async fn list_quarantined(
+        &self,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<QuarantinedReview>, sqlx::Error> {
+        self.review_repo.find_quarantined(limit, offset).await
+    }
+
+    /// Publishes (`approve`) or deletes a quarantined review. Audited.
+    pub async fn admin_moderate_review(
+        &self,
+        review_id: &str,
+        approve: bool,
+        reason: &str,
+    ) -> Result<QuarantinedReview, ReviewError> {
+        let review = self
+            .review_repo
+            .find_quarantined_by_id(review_id)
+            .await
+            .map_err(|e| ReviewError::Internal(format!("Failed to load review: {e}")))?
+            .ok_or_else(|| {
+                ReviewError::NotFound("No quarantined review with that id".to_string())
+            })?;
+
+        let applied = if approve {
+            self.review_repo.release_from_quarantine(review_id).await
+        } else {
+            self.review_repo.delete(review_id).await
+        }
+        .map_err(|e| ReviewError::Internal(format!("Failed to moderate review: {e}")))?;
+        if !applied {
+            // Another moderator got there first.
+            return Err(ReviewError::NotFound(
+                "No quarantined review with that id".to_string(),
+            ));
+        }
+        if approve {
+            self.refresh_script_stats(&review.script_id).await?;
+        }
+
+        let now = Utc::now();
+        self.account_repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: None,
+                action: "admin_moderate_review",
+                payload: &create_canonical_payload(&serde_json::json!({
+                    "review_id": review_id,
+                    "script_id": review.script_id,
+                    "approve": approve,
+                    "quarantine_reason": review.quarantine_reason,
+                    "reason": reason,
+                })),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: now.timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now.to_rfc3339(),
+            })
+            .await
+            .map_err(|e| ReviewError::Internal(format!("Failed to record audit: {e}")))?;
+        Ok(review)
+    }
 }
 
 #[cfg(test)]
@@ -149,6 +413,7 @@ mod tests {
             slug: "test-script".to_string(),
             title: "Test Script".to_string(),
             description: "Test Description".to_string(),
+            readme: None,
             category: "utility".to_string(),
             bundle: "print('hello')".to_string(),
             author_principal: None,
@@ -158,10 +423,14 @@ mod tests {
             timestamp: None,
             version: None,
             price: None,
+            price_e8s: None,
+            currency: None,
+            payload_version: None,
             is_public: None,
             compatibility: None,
             tags: None,
             action: None,
+            dependencies: None,
         };
         script_service.create_script(req).await.unwrap().id
     }
@@ -171,6 +440,7 @@ mod tests {
             user_id: user_id.to_string(),
             rating,
             comment: Some("Great script!".to_string()),
+            public_key: None,
         }
     }
 
@@ -184,7 +454,9 @@ mod tests {
         let result = service.create_review(&script_id, req).await;
 
         assert!(result.is_ok());
-        let review = result.unwrap();
+        let submission = result.unwrap();
+        assert!(submission.quarantine_reason.is_none());
+        let review = submission.review;
         assert_eq!(review.script_id, script_id);
         assert_eq!(review.user_id, "user-1");
         assert_eq!(review.rating, 5);
@@ -235,6 +507,7 @@ mod tests {
                 user_id: format!("user-{}", rating),
                 rating,
                 comment: None,
+                public_key: None,
             };
             let result = service.create_review(&script_id, req).await;
             assert!(result.is_ok(), "Rating {} should be valid", rating);
@@ -338,11 +611,12 @@ mod tests {
             user_id: "user-1".to_string(),
             rating: 4,
             comment: None,
+            public_key: None,
         };
         let result = service.create_review(&script_id, req).await;
 
         assert!(result.is_ok());
-        let review = result.unwrap();
+        let review = result.unwrap().review;
         assert_eq!(review.comment, None);
     }
 
@@ -359,12 +633,12 @@ mod tests {
         }
 
         // Get first 3 reviews
-        let (reviews, total) = service.get_reviews(&script_id, 3, 0).await.unwrap();
+        let (reviews, total) = service.get_reviews(&script_id, None, 3, 0).await.unwrap();
         assert_eq!(reviews.len(), 3);
         assert_eq!(total, 5);
 
         // Get next 3 reviews (should only get 2)
-        let (reviews, _) = service.get_reviews(&script_id, 3, 3).await.unwrap();
+        let (reviews, _) = service.get_reviews(&script_id, None, 3, 3).await.unwrap();
         assert_eq!(reviews.len(), 2);
     }
 
@@ -374,7 +648,7 @@ mod tests {
         let service = ReviewService::new(pool.clone());
         let script_id = create_test_script(&pool).await;
 
-        let (reviews, total) = service.get_reviews(&script_id, 10, 0).await.unwrap();
+        let (reviews, total) = service.get_reviews(&script_id, None, 10, 0).await.unwrap();
         assert_eq!(reviews.len(), 0);
         assert_eq!(total, 0);
     }
@@ -400,13 +674,237 @@ mod tests {
         service.create_review(&script_id_2, req3).await.unwrap();
 
         // Get reviews for script 1
-        let (reviews, total) = service.get_reviews(&script_id_1, 10, 0).await.unwrap();
+        let (reviews, total) = service
+            .get_reviews(&script_id_1, None, 10, 0)
+            .await
+            .unwrap();
         assert_eq!(reviews.len(), 2);
         assert_eq!(total, 2);
 
         // Get reviews for script 2
-        let (reviews, total) = service.get_reviews(&script_id_2, 10, 0).await.unwrap();
+        let (reviews, total) = service
+            .get_reviews(&script_id_2, None, 10, 0)
+            .await
+            .unwrap();
         assert_eq!(reviews.len(), 1);
         assert_eq!(total, 1);
     }
+
+    async fn insert_account(pool: &SqlitePool, id: &str, created_at: &str) {
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES (?1, ?1, ?1, ?2, ?2)",
+        )
+        .bind(id)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn audit_review_request(pool: &SqlitePool, public_key: &str) {
+        let now = Utc::now();
+        AccountRepository::new(pool.clone())
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: None,
+                action: REVIEW_CREATE_ACTION,
+                payload: "{}",
+                signature: "sig",
+                public_key,
+                timestamp: now.timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: false,
+                now: &now.to_rfc3339(),
+            })
+            .await
+            .unwrap();
+    }
+
+    fn review_request(user_id: &str, rating: i32, comment: Option<&str>) -> CreateReviewRequest {
+        CreateReviewRequest {
+            user_id: user_id.to_string(),
+            rating,
+            comment: comment.map(str::to_string),
+            public_key: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_key_burst_is_quarantined() {
+        let pool = setup_test_db().await;
+        let service = ReviewService::new(pool.clone());
+        let mut scripts = Vec::new();
+        for _ in 0..4 {
+            scripts.push(create_test_script(&pool).await);
+        }
+
+        for (i, script_id) in scripts.iter().enumerate() {
+            audit_review_request(&pool, "burst-key").await;
+            let mut req = review_request(&format!("user-{i}"), 4, None);
+            req.public_key = Some("burst-key".to_string());
+            let submission = service.create_review(script_id, req).await.unwrap();
+            let expected = (i == 3).then_some(QuarantineReason::KeyBurst);
+            assert_eq!(submission.quarantine_reason, expected, "review {i}");
+        }
+
+        let (reviews, total) = service.get_reviews(&scripts[3], None, 10, 0).await.unwrap();
+        assert!(reviews.is_empty());
+        assert_eq!(total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_comment_copied_across_scripts_is_quarantined() {
+        let pool = setup_test_db().await;
+        let service = ReviewService::new(pool.clone());
+        let first = create_test_script(&pool).await;
+        let second = create_test_script(&pool).await;
+        let comment = "Best script ever, go download it now!";
+
+        let submission = service
+            .create_review(&first, review_request("user-1", 4, Some(comment)))
+            .await
+            .unwrap();
+        assert!(submission.quarantine_reason.is_none());
+
+        let copied = format!("  {}  ", comment.to_uppercase());
+        let submission = service
+            .create_review(&second, review_request("user-2", 4, Some(&copied)))
+            .await
+            .unwrap();
+        assert_eq!(
+            submission.quarantine_reason,
+            Some(QuarantineReason::DuplicateComment)
+        );
+
+        // Short stock phrases are fine to repeat.
+        let third = create_test_script(&pool).await;
+        let submission = service
+            .create_review(&third, review_request("user-3", 4, Some("Works great")))
+            .await
+            .unwrap();
+        assert!(submission.quarantine_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_new_account_five_star_spree_is_quarantined() {
+        let pool = setup_test_db().await;
+        let service = ReviewService::new(pool.clone());
+        insert_account(&pool, "fresh", &Utc::now().to_rfc3339()).await;
+        insert_account(&pool, "veteran", "2025-01-01T00:00:00+00:00").await;
+
+        for i in 0..3 {
+            let script_id = create_test_script(&pool).await;
+            let fresh = service
+                .create_review(&script_id, review_request("fresh", 5, None))
+                .await
+                .unwrap();
+            let expected = (i == 2).then_some(QuarantineReason::NewAccountFiveStar);
+            assert_eq!(fresh.quarantine_reason, expected, "review {i}");
+
+            let veteran = service
+                .create_review(&script_id, review_request("veteran", 5, None))
+                .await
+                .unwrap();
+            assert!(veteran.quarantine_reason.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moderation_publishes_or_deletes_quarantined_reviews() {
+        let pool = setup_test_db().await;
+        let rules = SpamRules {
+            new_account_max_five_star: 0,
+            ..SpamRules::default()
+        };
+        let service = ReviewService::new(pool.clone()).with_spam_rules(rules);
+        let script_service = ScriptService::new(pool.clone());
+        insert_account(&pool, "fresh-1", &Utc::now().to_rfc3339()).await;
+        insert_account(&pool, "fresh-2", &Utc::now().to_rfc3339()).await;
+        let script_id = create_test_script(&pool).await;
+
+        let kept = service
+            .create_review(&script_id, review_request("fresh-1", 5, None))
+            .await
+            .unwrap()
+            .review;
+        let dropped = service
+            .create_review(&script_id, review_request("fresh-2", 5, None))
+            .await
+            .unwrap()
+            .review;
+        assert_eq!(service.list_quarantined(10, 0).await.unwrap().len(), 2);
+
+        service
+            .admin_moderate_review(&kept.id, true, "looks genuine")
+            .await
+            .unwrap();
+        service
+            .admin_moderate_review(&dropped.id, false, "sock puppet")
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.admin_moderate_review(&kept.id, true, "again").await,
+            Err(ReviewError::NotFound(_))
+        ));
+
+        assert!(service.list_quarantined(10, 0).await.unwrap().is_empty());
+        let (reviews, total) = service.get_reviews(&script_id, None, 10, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(reviews[0].id, kept.id);
+        let script = script_service
+            .get_script(&script_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(script.review_count, 1);
+        assert_eq!(script.rating, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_banned_reviewer_still_sees_own_review() {
+        let pool = setup_test_db().await;
+        let service = ReviewService::new(pool.clone());
+        let script_service = ScriptService::new(pool.clone());
+        insert_account(&pool, "banned", "2025-01-01T00:00:00Z").await;
+        insert_account(&pool, "other", "2025-01-01T00:00:00Z").await;
+        let script_id = create_test_script(&pool).await;
+        let own = service
+            .create_review(&script_id, review_request("banned", 1, None))
+            .await
+            .unwrap()
+            .review;
+        service
+            .create_review(&script_id, review_request("other", 4, None))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE accounts SET shadow_banned_at = ?1 WHERE id = 'banned'")
+            .bind(Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+        service.refresh_stats_for_reviewer("banned").await.unwrap();
+
+        for viewer in [None, Some("other")] {
+            let (reviews, total) = service
+                .get_reviews(&script_id, viewer, 10, 0)
+                .await
+                .unwrap();
+            assert_eq!(total, 1);
+            assert!(reviews.iter().all(|r| r.id != own.id));
+        }
+        let (reviews, total) = service
+            .get_reviews(&script_id, Some("banned"), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        assert!(reviews.iter().any(|r| r.id == own.id));
+        let script = script_service
+            .get_script(&script_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(script.review_count, 1);
+        assert_eq!(script.rating, 4.0);
+    }
 }
@@ -1,9 +1,30 @@
-use crate::models::{CreateScriptRequest, Script, ScriptPreview, UpdateScriptRequest};
-use crate::repositories::{AccountRepository, ScriptRepository};
+use crate::account_settings::{DefaultVisibility, SCHEME_NOT_ALLOWED};
+use crate::auth::create_canonical_payload;
+use crate::limits::ScriptLimits;
+use crate::models::{
+    ActivityKind, AdminTaxonomyResponse, ChannelRelease, ChannelSummary, CreateScriptRequest,
+    DependencyTree, RankingExperimentReport, ResolvedDependency, Script, ScriptComparison,
+    ScriptDependency, ScriptPreview, SearchAnalytics, SearchClickRequest, SearchExposure,
+    TrendingScope, TrendingSignal, UpdateScriptRequest,
+};
+use crate::moderation::restricted_message;
+use crate::pricing::{resolve_price, Price};
+use crate::quotas::{AccountTier, QuotaUsage};
+use crate::rate_limit::{
+    VelocityAction, VelocityGuard, VelocityRules, ViewDeduper, DEFAULT_VIEW_WINDOW_SECS,
+};
+use crate::release_channel::ReleaseChannel;
+use crate::repositories::{
+    AccountRepository, AccountSettingsRepository, ActivityRepository, ModerationRepository,
+    ScriptRepository, SearchExperimentRepository, SearchLogRepository, SignatureAuditParams,
+};
+use crate::script_dependencies::{best_match, DependencyGraph, MAX_DEPENDENCY_DEPTH};
 use crate::script_language::ScriptLanguage;
+use crate::search_experiments::{Ranking, RankingExperiment};
 use crate::services::error::ScriptError;
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use sqlx::SqlitePool;
+use std::collections::VecDeque;
 
 /// Maximum preview lines for a FREE script. Matches the prior client-side
 /// `take(50)` so the preview UX is unchanged for free scripts (which the user
@@ -16,25 +37,356 @@ pub const FREE_PREVIEW_LINES: usize = 50;
 /// for. NEVER raise this to the full bundle length for paid scripts.
 pub const PAID_PREVIEW_LINES: usize = 20;
 
+/// Most scripts `GET /api/v1/scripts/compare` lines up at once.
+pub const MAX_COMPARED_SCRIPTS: usize = 5;
+
+/// How far ahead a draft can be scheduled for publication.
+const MAX_SCHEDULE_AHEAD_DAYS: i64 = 365;
+
+/// Logged search queries are cut to this many characters.
+const MAX_LOGGED_QUERY_CHARS: usize = 100;
+
 pub struct ScriptService {
     repo: ScriptRepository,
     pub account_repo: AccountRepository,
+    settings: AccountSettingsRepository,
+    search_log: SearchLogRepository,
+    experiments: SearchExperimentRepository,
+    activity: ActivityRepository,
+    moderation: ModerationRepository,
+    limits: ScriptLimits,
+    velocity: VelocityGuard,
+    views: ViewDeduper,
+    ranking_experiment: Option<RankingExperiment>,
 }
 
 impl ScriptService {
     pub fn new(pool: SqlitePool) -> Self {
+        Self::with_limits(pool, ScriptLimits::default())
+    }
+
+    pub fn with_limits(pool: SqlitePool, limits: ScriptLimits) -> Self {
         Self {
             repo: ScriptRepository::new(pool.clone()),
-            account_repo: AccountRepository::new(pool),
+            account_repo: AccountRepository::new(pool.clone()),
+            settings: AccountSettingsRepository::new(pool.clone()),
+            search_log: SearchLogRepository::new(pool.clone()),
+            experiments: SearchExperimentRepository::new(pool.clone()),
+            activity: ActivityRepository::new(pool.clone()),
+            moderation: ModerationRepository::new(pool),
+            limits,
+            velocity: VelocityGuard::new(VelocityRules::default()),
+            views: ViewDeduper::new(DEFAULT_VIEW_WINDOW_SECS),
+            ranking_experiment: None,
+        }
+    }
+
+    pub fn with_velocity_rules(mut self, rules: VelocityRules) -> Self {
+        self.velocity = VelocityGuard::new(rules);
+        self
+    }
+
+    pub fn with_view_window(mut self, window_secs: i64) -> Self {
+        self.views = ViewDeduper::new(window_secs);
+        self
+    }
+
+    pub fn with_ranking_experiment(mut self, experiment: Option<RankingExperiment>) -> Self {
+        self.ranking_experiment = experiment;
+        self
+    }
+
+    pub fn limits(&self) -> &ScriptLimits {
+        &self.limits
+    }
+
+    /// Rejects the write with 429 when the account or the signing key is over
+    /// its create/update velocity or still cooling down from a violation.
+    fn check_velocity(
+        &self,
+        account_id: Option<&str>,
+        public_key: Option<&str>,
+        action: VelocityAction,
+    ) -> Result<(), ScriptError> {
+        let subjects = velocity_subjects(account_id, public_key);
+        self.velocity
+            .check(&subjects, action)
+            .map_err(|retry_after| {
+                tracing::warn!("Script write velocity exceeded for {:?}", subjects);
+                ScriptError::TooManyRequests(format!(
+                    "Too many script writes; retry in {retry_after} seconds"
+                ))
+            })
+    }
+
+    /// Rejects the write with 403 while moderation strikes freeze the
+    /// account's uploads; see [`crate::moderation`].
+    async fn check_uploads_allowed(&self, account_id: &str) -> Result<(), ScriptError> {
+        let restriction = self
+            .moderation
+            .restriction(account_id)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to check restrictions: {e}")))?;
+        if !restriction.allows_uploads() {
+            return Err(ScriptError::Forbidden(
+                restricted_message(restriction).to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rejects the write with 403 when the account requires a signature
+    /// scheme `public_key` is not of; see [`crate::account_settings`].
+    async fn check_signature_scheme(
+        &self,
+        account_id: &str,
+        public_key: Option<&str>,
+    ) -> Result<(), ScriptError> {
+        let scheme = self
+            .settings
+            .required_scheme(account_id)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to load account settings: {e}")))?;
+        if !scheme.allows(public_key.unwrap_or_default()) {
+            return Err(ScriptError::Forbidden(SCHEME_NOT_ALLOWED.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Rejects the write with 403 when it would take the owner past the
+    /// private-script or asset-storage quota of their tier. The deltas are
+    /// what the write adds; shrinking is always allowed.
+    async fn check_quota(
+        &self,
+        account_id: &str,
+        private_scripts: i64,
+        asset_bytes: i64,
+    ) -> Result<(), ScriptError> {
+        let lookup_err =
+            |e: sqlx::Error| ScriptError::Internal(format!("Failed to check quota: {e}"));
+        let tier = self
+            .account_repo
+            .find_by_id(account_id)
+            .await
+            .map_err(lookup_err)?
+            .map(|account| AccountTier::parse(&account.tier))
+            .unwrap_or_default();
+        let (used_private, used_bytes) = self
+            .repo
+            .owner_usage(account_id)
+            .await
+            .map_err(lookup_err)?;
+        let usage = QuotaUsage::new(tier, used_private, used_bytes, 0);
+        if !usage.private_scripts.allows(private_scripts) {
+            return Err(ScriptError::Forbidden(format!(
+                "The {} tier allows at most {} private scripts",
+                tier.as_str(),
+                usage.private_scripts.limit
+            )));
+        }
+        if !usage.asset_bytes.allows(asset_bytes) {
+            return Err(ScriptError::Forbidden(format!(
+                "The {} tier allows at most {} bytes of script storage ({} in use)",
+                tier.as_str(),
+                usage.asset_bytes.limit,
+                usage.asset_bytes.used
+            )));
         }
+        Ok(())
+    }
+
+    /// The declared dependencies that no public script meets.
+    async fn unresolved_dependencies(
+        &self,
+        dependencies: &[ScriptDependency],
+    ) -> Result<Vec<ScriptDependency>, sqlx::Error> {
+        let mut unresolved = Vec::new();
+        for dependency in dependencies {
+            let candidates = self.repo.find_by_slug(dependency.slug.trim()).await?;
+            if best_match(&dependency.version, &candidates).is_none() {
+                unresolved.push(dependency.clone());
+            }
+        }
+        Ok(unresolved)
+    }
+
+    /// Rejects with 400 a script going public while a dependency has no
+    /// public script that meets it.
+    async fn check_dependencies_resolve(
+        &self,
+        dependencies: &[ScriptDependency],
+    ) -> Result<(), ScriptError> {
+        let unresolved = self
+            .unresolved_dependencies(dependencies)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to resolve dependencies: {e}")))?;
+        match unresolved.first() {
+            None => Ok(()),
+            Some(dependency) => Err(ScriptError::BadRequest(format!(
+                "Dependency '{}' {} matches no public script version",
+                dependency.slug.trim(),
+                dependency.version.trim()
+            ))),
+        }
+    }
+
+    /// Logs `kind` on the owner's activity feed. Best-effort: a failed entry
+    /// never fails the change it records.
+    async fn log_activity(&self, script: &Script, kind: ActivityKind) {
+        let Some(owner) = script.owner_account_id.as_deref() else {
+            return;
+        };
+        if let Err(e) = self
+            .activity
+            .record(
+                owner,
+                kind,
+                &script.id,
+                Some(&script.version),
+                None,
+                &Utc::now().to_rfc3339(),
+            )
+            .await
+        {
+            tracing::warn!(
+                "Failed to log {} of script {}: {}",
+                kind.as_str(),
+                script.id,
+                e
+            );
+        }
+    }
+
+    /// Admin override: lifts the velocity history and cooldowns of an account
+    /// and each of its keys.
+    pub fn clear_velocity(&self, account_id: &str, public_keys: &[String]) {
+        let mut subjects = velocity_subjects(Some(account_id), None);
+        for key in public_keys {
+            subjects.extend(velocity_subjects(None, Some(key)));
+        }
+        for subject in &subjects {
+            self.velocity.clear(subject);
+        }
+    }
+
+    /// Admin: folds the categories in `from` into `into` across all scripts.
+    /// Audited as `admin_merge_categories`.
+    pub async fn admin_merge_categories(
+        &self,
+        from: &[String],
+        into: &str,
+        reason: &str,
+    ) -> Result<AdminTaxonomyResponse, ScriptError> {
+        let into = into.trim();
+        let mut sources: Vec<String> = Vec::new();
+        for category in from.iter().map(|c| c.trim()) {
+            if category != into && !sources.iter().any(|s| s == category) {
+                sources.push(category.to_string());
+            }
+        }
+        if sources.is_empty() {
+            return Err(ScriptError::BadRequest(
+                "from must name at least one category other than into".to_string(),
+            ));
+        }
+
+        let scripts_updated = self
+            .repo
+            .merge_categories(&sources, into)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to merge categories: {e}")))?;
+        self.record_admin_audit(
+            "admin_merge_categories",
+            serde_json::json!({
+                "action": "admin_merge_categories",
+                "from": sources,
+                "into": into,
+                "reason": reason,
+                "scriptsUpdated": scripts_updated,
+            }),
+        )
+        .await?;
+        Ok(AdminTaxonomyResponse { scripts_updated })
+    }
+
+    /// Admin: renames tag `from` to `to` across all scripts. Audited as
+    /// `admin_rename_tag`.
+    pub async fn admin_rename_tag(
+        &self,
+        from: &str,
+        to: &str,
+        reason: &str,
+    ) -> Result<AdminTaxonomyResponse, ScriptError> {
+        let (from, to) = (from.trim(), to.trim());
+        if from == to {
+            return Err(ScriptError::BadRequest(
+                "from and to must differ".to_string(),
+            ));
+        }
+        if to.chars().count() > self.limits.max_tag_chars {
+            return Err(ScriptError::BadRequest(format!(
+                "tag exceeds the maximum of {} characters",
+                self.limits.max_tag_chars
+            )));
+        }
+
+        let scripts_updated = self
+            .repo
+            .rename_tag(from, to)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to rename tag: {e}")))?;
+        self.record_admin_audit(
+            "admin_rename_tag",
+            serde_json::json!({
+                "action": "admin_rename_tag",
+                "from": from,
+                "to": to,
+                "reason": reason,
+                "scriptsUpdated": scripts_updated,
+            }),
+        )
+        .await?;
+        Ok(AdminTaxonomyResponse { scripts_updated })
+    }
+
+    /// Records an admin action that is not tied to one account.
+    async fn record_admin_audit(
+        &self,
+        action: &str,
+        payload: serde_json::Value,
+    ) -> Result<(), ScriptError> {
+        let now = Utc::now();
+        self.account_repo
+            .record_signature_audit(SignatureAuditParams {
+                audit_id: &uuid::Uuid::new_v4().to_string(),
+                account_id: None,
+                action,
+                payload: &create_canonical_payload(&payload),
+                signature: "admin-action",
+                public_key: "admin",
+                timestamp: now.timestamp(),
+                nonce: &uuid::Uuid::new_v4().to_string(),
+                is_admin_action: true,
+                now: &now.to_rfc3339(),
+            })
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to record audit: {e}")))
     }
 
     pub async fn create_script(&self, req: CreateScriptRequest) -> Result<Script, ScriptError> {
+        self.limits.check(
+            Some(&req.title),
+            Some(&req.description),
+            req.readme.as_deref(),
+            Some(&req.bundle),
+            req.tags.as_deref(),
+        )?;
+
         let script_id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
         let version = req.version.as_deref().unwrap_or("1.0.0");
-        let price = req.price.unwrap_or(0.0);
-        let is_public = resolve_script_visibility(req.is_public);
+        let price = resolve_price(req.price, req.price_e8s, req.currency.as_deref())
+            .unwrap_or_else(Price::free);
         let tags_json = req.tags.map(|tags| {
             serde_json::to_string(&tags).unwrap_or_else(|e| {
                 tracing::warn!("Failed to serialize script tags: {e}");
@@ -60,6 +412,20 @@ impl ScriptService {
         } else {
             None
         };
+        let default_visibility = match owner_account_id.as_deref() {
+            Some(owner) => {
+                self.settings
+                    .find(owner)
+                    .await
+                    .map_err(|e| {
+                        ScriptError::Internal(format!("Failed to load account settings: {e}"))
+                    })?
+                    .unwrap_or_default()
+                    .default_visibility
+            }
+            None => DefaultVisibility::default(),
+        };
+        let is_public = resolve_script_visibility(req.is_public, default_visibility);
 
         // Check slug ownership if script with this slug already exists
         let existing_scripts =
@@ -77,6 +443,26 @@ impl ScriptService {
             }
         }
 
+        if let Some(owner) = owner_account_id.as_deref() {
+            self.check_uploads_allowed(owner).await?;
+            self.check_signature_scheme(owner, req.author_public_key.as_deref())
+                .await?;
+        }
+        self.check_velocity(
+            owner_account_id.as_deref(),
+            req.author_public_key.as_deref(),
+            VelocityAction::Create,
+        )?;
+
+        if let Some(owner) = owner_account_id.as_deref() {
+            self.check_quota(owner, i64::from(!is_public), req.bundle.len() as i64)
+                .await?;
+        }
+        if is_public {
+            self.check_dependencies_resolve(req.dependencies.as_deref().unwrap_or_default())
+                .await?;
+        }
+
         self.repo
             .create(
                 &script_id,
@@ -90,7 +476,7 @@ impl ScriptService {
                 req.author_public_key.as_deref(),
                 req.signature.as_deref(),
                 version,
-                price,
+                &price,
                 is_public,
                 req.compatibility.as_deref(),
                 tags_json.as_deref(),
@@ -98,20 +484,130 @@ impl ScriptService {
             )
             .await
             .map_err(|e| ScriptError::Internal(format!("Failed to create script: {e}")))?;
+        if let Some(dependencies) = req.dependencies.as_deref() {
+            self.repo
+                .set_dependencies(&script_id, dependencies, &now)
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to save dependencies: {e}")))?;
+        }
+        if let Some(readme) = req.readme.as_deref().filter(|readme| !readme.is_empty()) {
+            self.repo
+                .set_readme(&script_id, Some(readme))
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to save readme: {e}")))?;
+        }
 
-        self.repo
+        let script = self
+            .repo
             .find_by_id(&script_id)
             .await
             .map_err(|e| ScriptError::Internal(format!("Failed to retrieve created script: {e}")))?
-            .ok_or_else(|| ScriptError::Internal("Script created but not found".to_string()))
+            .ok_or_else(|| ScriptError::Internal("Script created but not found".to_string()))?;
+        if script.is_public {
+            // A new row under a taken slug is the next version of that script.
+            let kind = if existing_scripts.is_empty() {
+                ActivityKind::ScriptPublished
+            } else {
+                ActivityKind::VersionReleased
+            };
+            self.log_activity(&script, kind).await;
+        }
+        Ok(script)
     }
 
     pub async fn update_script(
         &self,
         script_id: &str,
         req: UpdateScriptRequest,
-    ) -> Result<Script, sqlx::Error> {
+    ) -> Result<Script, ScriptError> {
+        self.limits.check(
+            req.title.as_deref(),
+            req.description.as_deref(),
+            req.readme.as_deref(),
+            req.bundle.as_deref(),
+            req.tags.as_deref(),
+        )?;
+
+        let owner_account_id = match req.author_public_key.as_deref() {
+            Some(public_key) => self
+                .account_repo
+                .find_public_key_by_value(public_key)
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to lookup account: {e}")))?
+                .map(|key| key.account_id),
+            None => None,
+        };
+        if let Some(signer) = owner_account_id.as_deref() {
+            self.check_uploads_allowed(signer).await?;
+            self.check_signature_scheme(signer, req.author_public_key.as_deref())
+                .await?;
+        }
+        self.check_velocity(
+            owner_account_id.as_deref(),
+            req.author_public_key.as_deref(),
+            VelocityAction::Update,
+        )?;
+
+        // Quotas are charged to the script's owner, whoever signed the update.
+        let existing = self
+            .repo
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to update script: {e}")))?;
+        if let Some(existing) = &existing {
+            if let Some(owner) = existing.owner_account_id.as_deref() {
+                let newly_private = existing.is_public && req.is_public == Some(false);
+                let added_bytes = req.bundle.as_ref().map_or(0, |bundle| {
+                    bundle.len() as i64 - existing.bundle.len() as i64
+                });
+                self.check_quota(owner, i64::from(newly_private), added_bytes)
+                    .await?;
+            }
+        }
+
+        // Dependencies are checked when they change on a public script and
+        // when the update makes the script public.
+        if let Some(existing) = &existing {
+            if let Some(dependencies) = req.dependencies.as_deref() {
+                if dependencies.iter().any(|d| d.slug.trim() == existing.slug) {
+                    return Err(ScriptError::BadRequest(
+                        "A script cannot depend on itself".to_string(),
+                    ));
+                }
+            }
+            let goes_public = !existing.is_public && req.is_public == Some(true);
+            let stays_public = existing.is_public && req.is_public != Some(false);
+            if goes_public || (stays_public && req.dependencies.is_some()) {
+                let dependencies = match req.dependencies.clone() {
+                    Some(dependencies) => dependencies,
+                    None => self.repo.find_dependencies(script_id).await.map_err(|e| {
+                        ScriptError::Internal(format!("Failed to load dependencies: {e}"))
+                    })?,
+                };
+                self.check_dependencies_resolve(&dependencies).await?;
+            }
+        }
+
+        // `Some(None)` cancels the schedule.
+        let schedule = match req.publish_at.as_deref() {
+            None => None,
+            Some("") => Some(None),
+            Some(raw) => {
+                let stays_draft = !req
+                    .is_public
+                    .or(existing.as_ref().map(|script| script.is_public))
+                    .unwrap_or(false);
+                if !stays_draft {
+                    return Err(ScriptError::BadRequest(
+                        "publish_at can only be set on a draft".to_string(),
+                    ));
+                }
+                Some(Some(parse_publish_at(raw, Utc::now())?))
+            }
+        };
+
         let now = Utc::now().to_rfc3339();
+        let price = resolve_price(req.price, req.price_e8s, req.currency.as_deref());
         let tags_json = req.tags.map(|tags| {
             serde_json::to_string(&tags).unwrap_or_else(|e| {
                 tracing::warn!("Failed to serialize script tags: {e}");
@@ -127,17 +623,213 @@ impl ScriptService {
                 req.category.as_deref(),
                 req.bundle.as_deref(),
                 req.version.as_deref(),
-                req.price,
+                price.as_ref(),
                 req.is_public,
                 tags_json.as_deref(),
                 &now,
             )
-            .await?;
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to update script: {e}")))?;
 
-        self.repo
+        if let Some(publish_at) = schedule {
+            self.repo
+                .set_publish_at(script_id, publish_at.as_deref())
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to schedule script: {e}")))?;
+        }
+        if let (Some(_), Some(dependencies)) = (&existing, req.dependencies.as_deref()) {
+            self.repo
+                .set_dependencies(script_id, dependencies, &now)
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to save dependencies: {e}")))?;
+        }
+        if let (Some(_), Some(readme)) = (&existing, req.readme.as_deref()) {
+            self.repo
+                .set_readme(script_id, Some(readme).filter(|readme| !readme.is_empty()))
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to save readme: {e}")))?;
+        }
+
+        let script = self
+            .repo
             .find_by_id(script_id)
-            .await?
-            .ok_or_else(|| sqlx::Error::RowNotFound)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to update script: {e}")))?
+            .ok_or_else(|| ScriptError::NotFound(format!("Script {script_id} not found")))?;
+        if let Some(existing) = existing.filter(|_| script.is_public) {
+            if !existing.is_public {
+                self.log_activity(&script, ActivityKind::ScriptPublished)
+                    .await;
+            } else if existing.version != script.version {
+                self.log_activity(&script, ActivityKind::VersionReleased)
+                    .await;
+            }
+        }
+        Ok(script)
+    }
+
+    /// Replaces the build of a non-stable channel with the pushed bundle and
+    /// version. The request has already been validated as a channel push
+    /// (bundle and version only); the stable script row is left untouched.
+    pub async fn push_channel_release(
+        &self,
+        script_id: &str,
+        channel: ReleaseChannel,
+        req: &UpdateScriptRequest,
+    ) -> Result<ChannelRelease, ScriptError> {
+        let (Some(bundle), Some(version)) = (req.bundle.as_deref(), req.version.as_deref()) else {
+            return Err(ScriptError::BadRequest(
+                "A channel push needs a bundle and a version".to_string(),
+            ));
+        };
+        if channel == ReleaseChannel::Stable {
+            return Err(ScriptError::BadRequest(
+                "Stable builds are pushed without a channel".to_string(),
+            ));
+        }
+        self.limits.check(None, None, None, Some(bundle), None)?;
+
+        let owner_account_id = match req.author_public_key.as_deref() {
+            Some(public_key) => self
+                .account_repo
+                .find_public_key_by_value(public_key)
+                .await
+                .map_err(|e| ScriptError::Internal(format!("Failed to lookup account: {e}")))?
+                .map(|key| key.account_id),
+            None => None,
+        };
+        if let Some(signer) = owner_account_id.as_deref() {
+            self.check_uploads_allowed(signer).await?;
+            self.check_signature_scheme(signer, req.author_public_key.as_deref())
+                .await?;
+        }
+        self.check_velocity(
+            owner_account_id.as_deref(),
+            req.author_public_key.as_deref(),
+            VelocityAction::Update,
+        )?;
+
+        let push_err =
+            |e: sqlx::Error| ScriptError::Internal(format!("Failed to push release: {e}"));
+        let script = self
+            .repo
+            .find_by_id(script_id)
+            .await
+            .map_err(push_err)?
+            .ok_or_else(|| ScriptError::NotFound(format!("Script {script_id} not found")))?;
+        if let Some(owner) = script.owner_account_id.as_deref() {
+            let previous = self
+                .repo
+                .find_channel_release(script_id, channel.as_str())
+                .await
+                .map_err(push_err)?;
+            let added_bytes =
+                bundle.len() as i64 - previous.map_or(0, |release| release.bundle.len() as i64);
+            self.check_quota(owner, 0, added_bytes).await?;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .upsert_channel_release(script_id, channel.as_str(), version, bundle, &now)
+            .await
+            .map_err(push_err)?;
+        self.repo
+            .find_channel_release(script_id, channel.as_str())
+            .await
+            .map_err(push_err)?
+            .ok_or_else(|| ScriptError::Internal("Release pushed but not found".to_string()))
+    }
+
+    /// The build of `channel`, if the script has one. Stable builds are the
+    /// script row itself, so asking for stable always yields `None`.
+    pub async fn channel_release(
+        &self,
+        script_id: &str,
+        channel: ReleaseChannel,
+    ) -> Result<Option<ChannelRelease>, sqlx::Error> {
+        if channel == ReleaseChannel::Stable {
+            return Ok(None);
+        }
+        self.repo
+            .find_channel_release(script_id, channel.as_str())
+            .await
+    }
+
+    /// Version and last push of every channel the script has a build on,
+    /// stable first. `None` when the script does not exist, or is private
+    /// and `viewer` does not own it.
+    pub async fn list_channels(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+    ) -> Result<Option<Vec<ChannelSummary>>, sqlx::Error> {
+        let Some(script) = self.repo.find_by_id(script_id).await? else {
+            return Ok(None);
+        };
+        if !is_visible_to(&script, viewer) {
+            return Ok(None);
+        }
+        let releases = self.repo.find_channel_releases(script_id).await?;
+        let stable = ChannelSummary {
+            channel: ReleaseChannel::Stable.as_str().to_string(),
+            version: script.version,
+            updated_at: script.updated_at,
+        };
+        Ok(Some(
+            std::iter::once(stable)
+                .chain(releases.into_iter().map(|release| ChannelSummary {
+                    channel: release.channel,
+                    version: release.version,
+                    updated_at: release.updated_at,
+                }))
+                .collect(),
+        ))
+    }
+
+    /// The dependency tree of a script, each requirement resolved to the
+    /// highest public version that meets it, plus the order to install the
+    /// resolved scripts in. `None` when the script does not exist, or is
+    /// private and `viewer` does not own it.
+    pub async fn resolve_dependencies(
+        &self,
+        script_id: &str,
+        viewer: Option<&str>,
+    ) -> Result<Option<DependencyTree>, sqlx::Error> {
+        let Some(script) = self.repo.find_by_id(script_id).await? else {
+            return Ok(None);
+        };
+        if !is_visible_to(&script, viewer) {
+            return Ok(None);
+        }
+        let mut graph = DependencyGraph::default();
+        let mut queue = VecDeque::from([(script.id.clone(), 0)]);
+        while let Some((id, depth)) = queue.pop_front() {
+            if depth > MAX_DEPENDENCY_DEPTH || graph.declared.contains_key(&id) {
+                continue;
+            }
+            let dependencies = self.repo.find_dependencies(&id).await?;
+            for dependency in &dependencies {
+                let key = (dependency.slug.clone(), dependency.version.clone());
+                if graph.resolved.contains_key(&key) {
+                    continue;
+                }
+                // Only public builds resolve, so a private script never shows
+                // up in the tree, not even for its owner: it stays unresolved.
+                let candidates = self.repo.find_by_slug(&dependency.slug).await?;
+                let resolved =
+                    best_match(&dependency.version, &candidates).map(|found| ResolvedDependency {
+                        id: found.id.clone(),
+                        slug: found.slug.clone(),
+                        version: found.version.clone(),
+                    });
+                if let Some(resolved) = &resolved {
+                    queue.push_back((resolved.id.clone(), depth + 1));
+                }
+                graph.resolved.insert(key, resolved);
+            }
+            graph.declared.insert(id, dependencies);
+        }
+        Ok(Some(graph.tree(&script)))
     }
 
     pub async fn delete_script(&self, script_id: &str) -> Result<(), sqlx::Error> {
@@ -145,14 +837,79 @@ impl ScriptService {
         self.repo.delete(script_id, &now).await
     }
 
-    pub async fn publish_script(&self, script_id: &str) -> Result<Script, sqlx::Error> {
-        let now = Utc::now().to_rfc3339();
-        self.repo.publish(script_id, &now).await?;
+    /// Makes a script public; 400 while one of its dependencies does not
+    /// resolve.
+    pub async fn publish_script(&self, script_id: &str) -> Result<Script, ScriptError> {
+        let publish_err =
+            |e: sqlx::Error| ScriptError::Internal(format!("Failed to publish script: {e}"));
+        let dependencies = self
+            .repo
+            .find_dependencies(script_id)
+            .await
+            .map_err(publish_err)?;
+        let existing = self.repo.find_by_id(script_id).await.map_err(publish_err)?;
+        if let Some(owner) = existing
+            .as_ref()
+            .and_then(|script| script.owner_account_id.as_deref())
+        {
+            self.check_uploads_allowed(owner).await?;
+        }
+        self.check_dependencies_resolve(&dependencies).await?;
+        let was_public = existing.is_some_and(|script| script.is_public);
 
+        let now = Utc::now().to_rfc3339();
         self.repo
+            .publish(script_id, &now)
+            .await
+            .map_err(publish_err)?;
+
+        let script = self
+            .repo
             .find_by_id(script_id)
-            .await?
-            .ok_or_else(|| sqlx::Error::RowNotFound)
+            .await
+            .map_err(publish_err)?
+            .ok_or_else(|| ScriptError::NotFound(format!("Script {script_id} not found")))?;
+        if !was_public {
+            self.log_activity(&script, ActivityKind::ScriptPublished)
+                .await;
+        }
+        Ok(script)
+    }
+
+    /// Publishes every draft whose `publish_at` has passed and returns the
+    /// scripts this call published. Run periodically by
+    /// [`crate::scheduled_publish`]. A draft whose dependencies do not
+    /// resolve stays scheduled and is retried on the next run.
+    pub async fn publish_due(&self) -> Result<Vec<Script>, sqlx::Error> {
+        let now = Utc::now();
+        let due_at = now.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let updated_at = now.to_rfc3339();
+        let mut published = Vec::new();
+        for id in self.repo.find_due_for_publication(&due_at).await? {
+            let dependencies = self.repo.find_dependencies(&id).await?;
+            let unresolved = self.unresolved_dependencies(&dependencies).await?;
+            if !unresolved.is_empty() {
+                tracing::warn!(
+                    "Holding scheduled script {}: unresolved dependencies {:?}",
+                    id,
+                    unresolved
+                );
+                continue;
+            }
+            if !self
+                .repo
+                .publish_scheduled(&id, &due_at, &updated_at)
+                .await?
+            {
+                continue;
+            }
+            if let Some(script) = self.repo.find_by_id(&id).await? {
+                self.log_activity(&script, ActivityKind::ScriptPublished)
+                    .await;
+                published.push(script);
+            }
+        }
+        Ok(published)
     }
 
     pub async fn get_script(&self, script_id: &str) -> Result<Option<Script>, sqlx::Error> {
@@ -168,16 +925,54 @@ impl ScriptService {
     pub async fn get_script_preview(
         &self,
         script_id: &str,
+        viewer: Option<&str>,
     ) -> Result<Option<ScriptPreview>, sqlx::Error> {
         let script = match self.repo.find_by_id(script_id).await? {
             Some(s) => s,
             None => return Ok(None),
         };
+        if !is_visible_to(&script, viewer) {
+            return Ok(None);
+        }
         Ok(Some(Self::build_preview(&script)))
     }
 
+    /// Side-by-side view of up to [`MAX_COMPARED_SCRIPTS`] public scripts, in
+    /// `ids` order (duplicates dropped), plus the ids that matched no public
+    /// script.
+    pub async fn compare_scripts(
+        &self,
+        ids: &[String],
+    ) -> Result<(Vec<ScriptComparison>, Vec<String>), ScriptError> {
+        let mut unique: Vec<String> = Vec::with_capacity(ids.len());
+        for id in ids {
+            if !unique.contains(id) {
+                unique.push(id.clone());
+            }
+        }
+        if unique.is_empty() || unique.len() > MAX_COMPARED_SCRIPTS {
+            return Err(ScriptError::BadRequest(format!(
+                "Compare between 1 and {MAX_COMPARED_SCRIPTS} scripts"
+            )));
+        }
+        let scripts = self
+            .repo
+            .find_by_ids(&unique)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to load scripts: {e}")))?;
+        let mut compared = Vec::with_capacity(unique.len());
+        let mut missing = Vec::new();
+        for id in unique {
+            match scripts.iter().find(|s| s.id == id && s.is_public) {
+                Some(script) => compared.push(ScriptComparison::from_script(script)),
+                None => missing.push(id),
+            }
+        }
+        Ok((compared, missing))
+    }
+
     fn build_preview(script: &Script) -> ScriptPreview {
-        let cap = if script.price > 0.0 {
+        let cap = if script.price_e8s > 0 {
             PAID_PREVIEW_LINES
         } else {
             FREE_PREVIEW_LINES
@@ -195,6 +990,8 @@ impl ScriptService {
             description: script.description.clone(),
             version: script.version.clone(),
             price: script.price,
+            price_e8s: script.price_e8s,
+            currency: script.currency.clone(),
             // UXR5-2: detected from the bundle CONTENT, not hardcoded. The
             // detector is the single source of truth — see `script_language`.
             language: ScriptLanguage::detect(&script.bundle).as_str().to_string(),
@@ -214,21 +1011,168 @@ impl ScriptService {
         limit: i32,
         offset: i32,
         category: Option<String>,
-        include_private: bool,
+        private_of: Option<&str>,
     ) -> Result<(Vec<Script>, i64), sqlx::Error> {
         let scripts = self
             .repo
-            .find_all(limit, offset, category, include_private)
+            .find_all(limit, offset, category, private_of)
             .await?;
         let total = self.repo.count_public().await?;
         Ok((scripts, total))
     }
 
+    /// Runs a search, ranked by the running experiment when the request
+    /// carries a client id and leaves the ranking to the server (no `sortBy`
+    /// or `order`). Returns the exposure such a search was logged as.
     pub async fn search_scripts(
         &self,
         request: &crate::models::SearchRequest,
-    ) -> Result<crate::models::SearchResultPayload, (poem::http::StatusCode, String)> {
-        self.repo.search(request).await
+    ) -> Result<
+        (crate::models::SearchResultPayload, Option<SearchExposure>),
+        (poem::http::StatusCode, String),
+    > {
+        let assignment = match (&self.ranking_experiment, request.client_id.as_deref()) {
+            (Some(experiment), Some(client_id))
+                if !client_id.trim().is_empty()
+                    && request.sort_by.is_none()
+                    && request.sort_order.is_none() =>
+            {
+                Some((experiment, experiment.assign(client_id), client_id))
+            }
+            _ => None,
+        };
+        let result = self
+            .repo
+            .search_ranked(request, assignment.map(|(_, ranking, _)| ranking))
+            .await?;
+        // Only first pages are logged, so paging through results does not
+        // count as repeated searches. Logging is best-effort.
+        if result.offset == 0 {
+            if let Some(query) = request.query.as_deref().and_then(normalize_search_query) {
+                let now = Utc::now().to_rfc3339();
+                if let Err(e) = self
+                    .search_log
+                    .record(&query, request.category.as_deref(), result.total, &now)
+                    .await
+                {
+                    tracing::warn!("Failed to log search query: {}", e);
+                }
+            }
+        }
+        let exposure = match assignment {
+            Some((experiment, ranking, client_id)) => {
+                self.log_exposure(experiment, ranking, client_id, &result)
+                    .await
+            }
+            None => None,
+        };
+        Ok((result, exposure))
+    }
+
+    /// Best-effort, like the search log: a search is not failed because its
+    /// exposure could not be written.
+    async fn log_exposure(
+        &self,
+        experiment: &RankingExperiment,
+        ranking: Ranking,
+        client_id: &str,
+        result: &crate::models::SearchResultPayload,
+    ) -> Option<SearchExposure> {
+        let exposure = SearchExposure {
+            id: uuid::Uuid::new_v4().to_string(),
+            experiment: experiment.name.clone(),
+            variant: ranking.as_str().to_string(),
+        };
+        let result_ids: Vec<String> = result.scripts.iter().map(|s| s.id.clone()).collect();
+        let now = Utc::now().to_rfc3339();
+        match self
+            .experiments
+            .record_exposure(
+                &exposure.id,
+                &exposure.experiment,
+                &exposure.variant,
+                &crate::content_store::sha256_hex(client_id),
+                &result_ids,
+                result.offset,
+                &now,
+            )
+            .await
+        {
+            Ok(()) => Some(exposure),
+            Err(e) => {
+                tracing::warn!("Failed to log search exposure: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Records a click on one of the results an exposure showed.
+    pub async fn record_search_click(&self, click: &SearchClickRequest) -> Result<(), ScriptError> {
+        let internal =
+            |e: sqlx::Error| ScriptError::Internal(format!("Failed to record click: {e}"));
+        let (result_ids, offset) = self
+            .experiments
+            .find_results(&click.exposure_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| ScriptError::NotFound("Search exposure not found".to_string()))?;
+        let index = result_ids
+            .iter()
+            .position(|id| *id == click.script_id)
+            .ok_or_else(|| {
+                ScriptError::BadRequest(
+                    "Script was not among the results of this search".to_string(),
+                )
+            })?;
+        let now = Utc::now().to_rfc3339();
+        self.experiments
+            .record_click(
+                &click.exposure_id,
+                &click.script_id,
+                offset + index as i64 + 1,
+                &now,
+            )
+            .await
+            .map_err(internal)
+    }
+
+    /// Per-variant click-through of `experiment` (the running one when
+    /// `None`) over the last `days` days; `None` when no experiment is named
+    /// or running.
+    pub async fn ranking_experiment_report(
+        &self,
+        experiment: Option<&str>,
+        days: i64,
+    ) -> Result<Option<RankingExperimentReport>, sqlx::Error> {
+        let running = self.ranking_experiment.as_ref().map(|e| e.name.as_str());
+        let Some(name) = experiment.or(running) else {
+            return Ok(None);
+        };
+        let since = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        Ok(Some(RankingExperimentReport {
+            variants: self.experiments.variant_stats(name, &since).await?,
+            experiment: name.to_string(),
+            running: running == Some(name),
+            since,
+        }))
+    }
+
+    /// Search totals plus the most frequent and most frequent zero-result
+    /// queries over the last `days` days.
+    pub async fn search_analytics(
+        &self,
+        days: i64,
+        limit: i64,
+    ) -> Result<SearchAnalytics, sqlx::Error> {
+        let since = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        let (total_searches, zero_result_searches) = self.search_log.totals_since(&since).await?;
+        Ok(SearchAnalytics {
+            top_queries: self.search_log.top_queries(&since, false, limit).await?,
+            top_zero_result_queries: self.search_log.top_queries(&since, true, limit).await?,
+            since,
+            total_searches,
+            zero_result_searches,
+        })
     }
 
     pub async fn get_scripts_by_category(
@@ -245,8 +1189,29 @@ impl ScriptService {
         self.repo.distinct_categories().await
     }
 
-    pub async fn get_trending(&self, limit: i32) -> Result<Vec<Script>, sqlx::Error> {
-        self.repo.get_trending(limit).await
+    pub async fn get_trending(
+        &self,
+        limit: i32,
+        signal: TrendingSignal,
+    ) -> Result<Vec<Script>, sqlx::Error> {
+        self.repo.get_trending(limit, signal).await
+    }
+
+    pub async fn get_trending_in(
+        &self,
+        scope: TrendingScope<'_>,
+        limit: i32,
+        signal: TrendingSignal,
+    ) -> Result<Vec<Script>, sqlx::Error> {
+        self.repo.get_trending_in(scope, limit, signal).await
+    }
+
+    /// Recomputes the per-category and per-tag trending lists.
+    pub async fn refresh_trending_rollups(&self) -> Result<u64, sqlx::Error> {
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .rebuild_trending_rollups(crate::trending::ROLLUP_SIZE, &now)
+            .await
     }
 
     pub async fn get_featured(
@@ -272,6 +1237,10 @@ impl ScriptService {
         self.repo.get_marketplace_stats().await
     }
 
+    pub async fn get_total_views(&self) -> Result<i64, sqlx::Error> {
+        self.repo.total_views().await
+    }
+
     pub async fn get_scripts_count(&self) -> Result<i64, sqlx::Error> {
         self.repo.count_public().await
     }
@@ -282,10 +1251,83 @@ impl ScriptService {
             .await
             .map_err(|e| ScriptError::Internal(format!("Failed to increment downloads: {e}")))
     }
+
+    /// Counts a detail view by `viewers` (see [`ViewDeduper`]). Returns
+    /// whether it was counted.
+    pub async fn record_view(
+        &self,
+        script_id: &str,
+        viewers: &[String],
+    ) -> Result<bool, ScriptError> {
+        if !self.views.should_count(script_id, viewers) {
+            return Ok(false);
+        }
+        self.repo
+            .increment_views(script_id)
+            .await
+            .map_err(|e| ScriptError::Internal(format!("Failed to increment views: {e}")))?;
+        Ok(true)
+    }
+}
+
+fn velocity_subjects(account_id: Option<&str>, public_key: Option<&str>) -> Vec<String> {
+    account_id
+        .map(|id| format!("account:{id}"))
+        .into_iter()
+        .chain(public_key.map(|key| format!("key:{key}")))
+        .collect()
+}
+
+/// Lowercases, trims and collapses whitespace so "Token  Swap" and
+/// "token swap" aggregate together. Empty queries (plain browsing) are not
+/// logged.
+fn normalize_search_query(query: &str) -> Option<String> {
+    let normalized = query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if normalized.is_empty() {
+        return None;
+    }
+    Some(normalized.chars().take(MAX_LOGGED_QUERY_CHARS).collect())
+}
+
+/// Parses a requested publication time into the stored form: UTC, whole
+/// seconds, `Z` suffix, so that stored values compare correctly as text.
+fn parse_publish_at(raw: &str, now: DateTime<Utc>) -> Result<String, ScriptError> {
+    let at = DateTime::parse_from_rfc3339(raw)
+        .map_err(|_| {
+            ScriptError::BadRequest("publish_at must be an RFC 3339 timestamp".to_string())
+        })?
+        .with_timezone(&Utc);
+    if at <= now {
+        return Err(ScriptError::BadRequest(
+            "publish_at must be in the future".to_string(),
+        ));
+    }
+    if at > now + chrono::Duration::days(MAX_SCHEDULE_AHEAD_DAYS) {
+        return Err(ScriptError::BadRequest(format!(
+            "publish_at must be within {MAX_SCHEDULE_AHEAD_DAYS} days"
+        )));
+    }
+    Ok(at.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Whether `viewer` may see the script: a private script exists only for
+/// its owner.
+fn is_visible_to(script: &Script, viewer: Option<&str>) -> bool {
+    script.is_public
+        || script
+            .owner_account_id
+            .as_deref()
+            .is_some_and(|owner| Some(owner) == viewer)
 }
 
-fn resolve_script_visibility(is_public: Option<bool>) -> bool {
-    is_public.unwrap_or(true)
+/// The visibility of a new script: `is_public` when the upload sets it,
+/// otherwise the owner's default.
+fn resolve_script_visibility(is_public: Option<bool>, default: DefaultVisibility) -> bool {
+    is_public.unwrap_or(default.is_public())
 }
 
 #[cfg(test)]
@@ -304,6 +1346,7 @@ mod tests {
             slug: "test-script".to_string(),
             title: "Test Script".to_string(),
             description: "Test Description".to_string(),
+            readme: None,
             category: "utility".to_string(),
             bundle: "print('hello')".to_string(),
             author_principal: Some("test-principal".to_string()),
@@ -313,22 +1356,181 @@ mod tests {
             timestamp: None,
             version: None,
             price: None,
+            price_e8s: None,
+            currency: None,
+            payload_version: None,
             is_public: None,
             compatibility: None,
             tags: None,
             action: None,
+            dependencies: None,
+        }
+    }
+
+    #[test]
+    fn search_queries_are_normalized_before_logging() {
+        assert_eq!(
+            normalize_search_query("  Token \t Swap "),
+            Some("token swap".to_string())
+        );
+        assert_eq!(normalize_search_query("   "), None);
+        let long = "x".repeat(MAX_LOGGED_QUERY_CHARS + 10);
+        assert_eq!(
+            normalize_search_query(&long).map(|q| q.len()),
+            Some(MAX_LOGGED_QUERY_CHARS)
+        );
+    }
+
+    #[tokio::test]
+    async fn search_analytics_groups_logged_queries() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+        service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+
+        let search = |query: &str, offset: i64| crate::models::SearchRequest {
+            query: Some(query.to_string()),
+            offset: Some(offset),
+            ..Default::default()
+        };
+        for (query, offset) in [
+            ("Test", 0),
+            ("test ", 0),
+            ("test", 20),
+            ("ledger", 0),
+            ("", 0),
+        ] {
+            service
+                .search_scripts(&search(query, offset))
+                .await
+                .unwrap();
+        }
+
+        let analytics = service.search_analytics(30, 10).await.unwrap();
+        assert_eq!(analytics.total_searches, 3);
+        assert_eq!(analytics.zero_result_searches, 1);
+        assert_eq!(analytics.top_queries[0].query, "test");
+        assert_eq!(analytics.top_queries[0].searches, 2);
+        assert_eq!(analytics.top_queries[0].avg_results, 1);
+        assert_eq!(analytics.top_zero_result_queries.len(), 1);
+        assert_eq!(analytics.top_zero_result_queries[0].query, "ledger");
+    }
+
+    #[tokio::test]
+    async fn ranking_experiment_logs_exposures_and_clicks() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool.clone())
+            .with_ranking_experiment(Some(RankingExperiment::parse("q4:quality=1").unwrap()));
+        let mut ids = Vec::new();
+        for slug in ["older-but-better", "newer"] {
+            let mut req = create_test_script_request();
+            req.slug = slug.to_string();
+            ids.push(service.create_script(req).await.unwrap().id);
         }
+        for (id, rating, created_at) in [
+            (&ids[0], 4.5, "2025-01-01T00:00:00Z"),
+            (&ids[1], 2.0, "2025-06-01T00:00:00Z"),
+        ] {
+            sqlx::query("UPDATE scripts SET weighted_rating = ?1, created_at = ?2 WHERE id = ?3")
+                .bind(rating)
+                .bind(created_at)
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let search =
+            |client_id: Option<&str>, sort_by: Option<&str>| crate::models::SearchRequest {
+                client_id: client_id.map(str::to_string),
+                sort_by: sort_by.map(str::to_string),
+                ..Default::default()
+            };
+        let (result, exposure) = service.search_scripts(&search(None, None)).await.unwrap();
+        assert!(exposure.is_none());
+        assert_eq!(result.scripts[0].id, ids[1], "control ranks newest first");
+        let (_, exposure) = service
+            .search_scripts(&search(Some("install-1"), Some("title")))
+            .await
+            .unwrap();
+        assert!(exposure.is_none(), "explicit sorts are not experimented on");
+
+        let (result, exposure) = service
+            .search_scripts(&search(Some("install-1"), None))
+            .await
+            .unwrap();
+        let exposure = exposure.unwrap();
+        assert_eq!(exposure.variant, "quality");
+        assert_eq!(result.scripts[0].id, ids[0]);
+        service
+            .search_scripts(&search(Some("install-2"), None))
+            .await
+            .unwrap();
+
+        let click = |script_id: &str| SearchClickRequest {
+            exposure_id: exposure.id.clone(),
+            script_id: script_id.to_string(),
+        };
+        service.record_search_click(&click(&ids[1])).await.unwrap();
+        service.record_search_click(&click(&ids[1])).await.unwrap();
+        assert!(matches!(
+            service.record_search_click(&click("elsewhere")).await,
+            Err(ScriptError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service
+                .record_search_click(&SearchClickRequest {
+                    exposure_id: "unknown".to_string(),
+                    script_id: ids[0].clone(),
+                })
+                .await,
+            Err(ScriptError::NotFound(_))
+        ));
+
+        let report = service
+            .ranking_experiment_report(None, 30)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(report.running);
+        let quality = &report.variants[0];
+        assert_eq!(quality.variant, "quality");
+        assert_eq!(
+            (
+                quality.exposures,
+                quality.clients,
+                quality.clicked_exposures,
+                quality.clicks
+            ),
+            (2, 2, 1, 1)
+        );
+        assert_eq!(quality.ctr, 0.5);
+        assert_eq!(quality.mean_first_click_position, Some(2.0));
+        assert!(ScriptService::new(pool)
+            .ranking_experiment_report(None, 30)
+            .await
+            .unwrap()
+            .is_none());
     }
 
     #[test]
     fn resolve_visibility_defaults_to_public() {
-        assert!(resolve_script_visibility(None));
+        assert!(resolve_script_visibility(None, DefaultVisibility::Public));
+        assert!(!resolve_script_visibility(None, DefaultVisibility::Private));
     }
 
     #[test]
     fn resolve_visibility_preserves_private_flag() {
-        assert!(!resolve_script_visibility(Some(false)));
-        assert!(resolve_script_visibility(Some(true)));
+        assert!(!resolve_script_visibility(
+            Some(false),
+            DefaultVisibility::Public
+        ));
+        assert!(resolve_script_visibility(
+            Some(true),
+            DefaultVisibility::Private
+        ));
     }
 
     #[tokio::test]
@@ -372,11 +1574,56 @@ mod tests {
         let script = result.unwrap();
         assert_eq!(script.version, "2.0.0");
         assert_eq!(script.price, 9.99);
+        assert_eq!(script.price_e8s, 999_000_000);
+        assert_eq!(script.currency, "ICP");
         assert!(!script.is_public); // Private script
         assert!(script.tags.is_some());
         assert_eq!(script.compatibility, Some("v1.0".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_e8s_price_round_trips_and_updates_legacy_price() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+        let mut req = create_test_script_request();
+        req.payload_version = Some(2);
+        req.price_e8s = Some(150_000_000);
+        req.currency = Some("ckBTC".to_string());
+
+        let script = service.create_script(req).await.unwrap();
+        assert_eq!(script.price_e8s, 150_000_000);
+        assert_eq!(script.currency, "ckBTC");
+        assert_eq!(script.price, 1.5);
+
+        let update_req = UpdateScriptRequest {
+            title: None,
+            description: None,
+            readme: None,
+            category: None,
+            bundle: None,
+            version: None,
+            price: None,
+            price_e8s: Some(1),
+            currency: Some("ICP".to_string()),
+            payload_version: Some(2),
+            is_public: None,
+            tags: None,
+            signature: None,
+            timestamp: None,
+            script_id: None,
+            author_principal: None,
+            author_public_key: None,
+            action: None,
+            publish_at: None,
+            channel: None,
+            dependencies: None,
+        };
+        let updated = service.update_script(&script.id, update_req).await.unwrap();
+        assert_eq!(updated.price_e8s, 1);
+        assert_eq!(updated.currency, "ICP");
+        assert_eq!(updated.price, 0.000_000_01);
+    }
+
     #[tokio::test]
     async fn test_update_script_partial_update() {
         let pool = setup_test_db().await;
@@ -390,10 +1637,14 @@ mod tests {
         let update_req = UpdateScriptRequest {
             title: Some("Updated Title".to_string()),
             description: Some("Updated Description".to_string()),
+            readme: None,
             category: None,
             bundle: None,
             version: None,
             price: None,
+            price_e8s: None,
+            currency: None,
+            payload_version: None,
             is_public: None,
             tags: None,
             signature: None,
@@ -402,6 +1653,9 @@ mod tests {
             author_principal: None,
             author_public_key: None,
             action: None,
+            publish_at: None,
+            channel: None,
+            dependencies: None,
         };
 
         let result = service.update_script(&created.id, update_req).await;
@@ -422,10 +1676,14 @@ mod tests {
         let update_req = UpdateScriptRequest {
             title: Some("Updated Title".to_string()),
             description: None,
+            readme: None,
             category: None,
             bundle: None,
             version: None,
             price: None,
+            price_e8s: None,
+            currency: None,
+            payload_version: None,
             is_public: None,
             tags: None,
             signature: None,
@@ -434,11 +1692,14 @@ mod tests {
             author_principal: None,
             author_public_key: None,
             action: None,
+            publish_at: None,
+            channel: None,
+            dependencies: None,
         };
 
         let result = service.update_script("nonexistent-id", update_req).await;
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), sqlx::Error::RowNotFound));
+        assert!(matches!(result.unwrap_err(), ScriptError::NotFound(_)));
     }
 
     #[tokio::test]
@@ -459,6 +1720,323 @@ mod tests {
         assert!(get_result.is_none());
     }
 
+    fn schedule_request(publish_at: &str) -> UpdateScriptRequest {
+        UpdateScriptRequest {
+            title: None,
+            description: None,
+            readme: None,
+            category: None,
+            bundle: None,
+            version: None,
+            price: None,
+            price_e8s: None,
+            currency: None,
+            payload_version: None,
+            is_public: None,
+            tags: None,
+            signature: None,
+            timestamp: None,
+            script_id: None,
+            author_principal: None,
+            author_public_key: None,
+            action: None,
+            publish_at: Some(publish_at.to_string()),
+            channel: None,
+            dependencies: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_draft_is_published_once_due() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool.clone());
+        let mut create_req = create_test_script_request();
+        create_req.is_public = Some(false);
+        let draft = service.create_script(create_req).await.unwrap();
+
+        let at =
+            (Utc::now() + chrono::Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        let scheduled = service
+            .update_script(&draft.id, schedule_request(&at))
+            .await
+            .unwrap();
+        assert_eq!(scheduled.publish_at.as_deref(), Some(at.as_str()));
+        assert!(service.publish_due().await.unwrap().is_empty());
+
+        sqlx::query("UPDATE scripts SET publish_at = '2020-01-01T00:00:00Z' WHERE id = ?1")
+            .bind(&draft.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let published = service.publish_due().await.unwrap();
+        assert_eq!(published.len(), 1);
+        assert!(published[0].is_public);
+        assert!(published[0].publish_at.is_none());
+        assert!(service.publish_due().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_publish_at_requires_a_future_time_on_a_draft() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+        let public = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+        let at = (Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        let err = service
+            .update_script(&public.id, schedule_request(&at))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScriptError::BadRequest(_)));
+
+        let mut create_req = create_test_script_request();
+        create_req.is_public = Some(false);
+        let draft = service.create_script(create_req).await.unwrap();
+        for bad in ["2020-01-01T00:00:00Z", "tomorrow"] {
+            let err = service
+                .update_script(&draft.id, schedule_request(bad))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ScriptError::BadRequest(_)));
+        }
+
+        // An empty value cancels the schedule.
+        service
+            .update_script(&draft.id, schedule_request(&at))
+            .await
+            .unwrap();
+        let cancelled = service
+            .update_script(&draft.id, schedule_request(""))
+            .await
+            .unwrap();
+        assert!(cancelled.publish_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_identical_bundles_share_one_blob() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool.clone());
+        let first = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+        let mut second = create_test_script_request();
+        second.version = Some("1.0.1".to_string());
+        let second = service.create_script(second).await.unwrap();
+
+        let key = crate::content_store::sha256_hex("print('hello')");
+        assert_eq!(first.bundle, "print('hello')");
+        assert_eq!(first.bundle_sha256.as_deref(), Some(key.as_str()));
+        assert_eq!(second.bundle_sha256, first.bundle_sha256);
+        let blobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM script_blobs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(blobs, 1);
+    }
+
+    #[tokio::test]
+    async fn test_readme_is_kept_per_version_and_cleared_by_empty_update() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+        let mut first = create_test_script_request();
+        first.readme = Some("# v1\n\nUsage.".to_string());
+        let first = service.create_script(first).await.unwrap();
+        let mut second = create_test_script_request();
+        second.version = Some("1.1.0".to_string());
+        second.readme = Some("# v1.1".to_string());
+        let second = service.create_script(second).await.unwrap();
+        assert_eq!(first.readme.as_deref(), Some("# v1\n\nUsage."));
+        assert_eq!(second.readme.as_deref(), Some("# v1.1"));
+
+        let mut clear = schedule_request("");
+        clear.publish_at = None;
+        clear.readme = Some(String::new());
+        let cleared = service.update_script(&second.id, clear).await.unwrap();
+        assert_eq!(cleared.readme, None);
+        let first = service.get_script(&first.id).await.unwrap().unwrap();
+        assert_eq!(first.readme.as_deref(), Some("# v1\n\nUsage."));
+    }
+
+    #[tokio::test]
+    async fn test_beta_push_is_tracked_apart_from_stable() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+        let script = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+        assert!(service
+            .channel_release(&script.id, ReleaseChannel::Beta)
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut push = schedule_request("");
+        push.publish_at = None;
+        push.channel = Some("beta".to_string());
+        push.bundle = Some("print('beta')".to_string());
+        push.version = Some("2.0.0-beta.1".to_string());
+        let release = service
+            .push_channel_release(&script.id, ReleaseChannel::Beta, &push)
+            .await
+            .unwrap();
+        assert_eq!(release.version, "2.0.0-beta.1");
+
+        push.version = Some("2.0.0-beta.2".to_string());
+        service
+            .push_channel_release(&script.id, ReleaseChannel::Beta, &push)
+            .await
+            .unwrap();
+        let stable = service.get_script(&script.id).await.unwrap().unwrap();
+        assert_eq!(stable.version, script.version);
+        assert_eq!(stable.bundle, script.bundle);
+
+        let channels = service
+            .list_channels(&script.id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        let versions: Vec<_> = channels
+            .iter()
+            .map(|c| (c.channel.as_str(), c.version.as_str()))
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                ("stable", script.version.as_str()),
+                ("beta", "2.0.0-beta.2")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_private_channels_are_hidden_from_non_owners() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool.clone());
+        let mut req = create_test_script_request();
+        req.is_public = Some(false);
+        let script = service.create_script(req).await.unwrap();
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('owner', 'owner', 'owner', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE scripts SET owner_account_id = 'owner' WHERE id = ?1")
+            .bind(&script.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for viewer in [None, Some("someone-else")] {
+            assert!(service
+                .list_channels(&script.id, viewer)
+                .await
+                .unwrap()
+                .is_none());
+        }
+        let channels = service
+            .list_channels(&script.id, Some("owner"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(channels.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_must_resolve_to_go_public() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+        let mut library = create_test_script_request();
+        library.slug = "json-lib".to_string();
+        library.version = Some("1.4.0".to_string());
+        let library = service.create_script(library).await.unwrap();
+
+        let mut app = create_test_script_request();
+        app.is_public = Some(false);
+        app.dependencies = Some(vec![ScriptDependency {
+            slug: "json-lib".to_string(),
+            version: "^2".to_string(),
+        }]);
+        let draft = service.create_script(app).await.unwrap();
+        let err = service.publish_script(&draft.id).await.unwrap_err();
+        assert!(matches!(err, ScriptError::BadRequest(_)));
+
+        let mut retarget = schedule_request("");
+        retarget.publish_at = None;
+        retarget.dependencies = Some(vec![ScriptDependency {
+            slug: "json-lib".to_string(),
+            version: "^1.2".to_string(),
+        }]);
+        service.update_script(&draft.id, retarget).await.unwrap();
+        assert!(service.publish_script(&draft.id).await.unwrap().is_public);
+
+        let tree = service
+            .resolve_dependencies(&draft.id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(tree.unresolved.is_empty());
+        assert_eq!(
+            tree.install,
+            [ResolvedDependency {
+                id: library.id,
+                slug: "json-lib".to_string(),
+                version: "1.4.0".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_private_scripts_stay_out_of_dependency_trees() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool.clone());
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('owner', 'owner', 'owner', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut library = create_test_script_request();
+        library.slug = "secret-lib".to_string();
+        library.version = Some("1.0.0".to_string());
+        library.is_public = Some(false);
+        let library = service.create_script(library).await.unwrap();
+        let mut app = create_test_script_request();
+        app.is_public = Some(false);
+        app.dependencies = Some(vec![ScriptDependency {
+            slug: "secret-lib".to_string(),
+            version: "^1".to_string(),
+        }]);
+        let app = service.create_script(app).await.unwrap();
+        for id in [&library.id, &app.id] {
+            sqlx::query("UPDATE scripts SET owner_account_id = 'owner' WHERE id = ?1")
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        for viewer in [None, Some("someone-else")] {
+            assert!(service
+                .resolve_dependencies(&app.id, viewer)
+                .await
+                .unwrap()
+                .is_none());
+        }
+        let tree = service
+            .resolve_dependencies(&app.id, Some("owner"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(tree.install.is_empty());
+        assert_eq!(tree.unresolved.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_publish_script_makes_public() {
         let pool = setup_test_db().await;
@@ -485,7 +2063,7 @@ mod tests {
 
         let result = service.publish_script("nonexistent-id").await;
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), sqlx::Error::RowNotFound));
+        assert!(matches!(result.unwrap_err(), ScriptError::NotFound(_)));
     }
 
     #[tokio::test]
@@ -542,19 +2120,19 @@ mod tests {
         }
 
         // Get first 2
-        let (scripts, total) = service.get_scripts(2, 0, None, false).await.unwrap();
+        let (scripts, total) = service.get_scripts(2, 0, None, None).await.unwrap();
         assert_eq!(scripts.len(), 2);
         assert_eq!(total, 3);
 
         // Get next 2 (should only get 1)
-        let (scripts, _) = service.get_scripts(2, 2, None, false).await.unwrap();
+        let (scripts, _) = service.get_scripts(2, 2, None, None).await.unwrap();
         assert_eq!(scripts.len(), 1);
     }
 
     #[tokio::test]
     async fn test_get_scripts_filters_private() {
         let pool = setup_test_db().await;
-        let service = ScriptService::new(pool);
+        let service = ScriptService::new(pool.clone());
 
         // Create 1 public and 1 private script
         let mut req1 = create_test_script_request();
@@ -563,14 +2141,36 @@ mod tests {
 
         let mut req2 = create_test_script_request();
         req2.is_public = Some(false);
-        service.create_script(req2).await.unwrap();
+        let private = service.create_script(req2).await.unwrap();
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('owner', 'owner', 'owner', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE scripts SET owner_account_id = 'owner' WHERE id = ?1")
+            .bind(&private.id)
+            .execute(&pool)
+            .await
+            .unwrap();
 
         // Get scripts without including private
-        let (scripts, _) = service.get_scripts(10, 0, None, false).await.unwrap();
+        let (scripts, _) = service.get_scripts(10, 0, None, None).await.unwrap();
         assert_eq!(scripts.len(), 1); // Only public script
 
-        // Get scripts including private
-        let (scripts, _) = service.get_scripts(10, 0, None, true).await.unwrap();
+        // Someone else's private scripts stay hidden
+        let (scripts, _) = service
+            .get_scripts(10, 0, None, Some("stranger"))
+            .await
+            .unwrap();
+        assert_eq!(scripts.len(), 1);
+
+        // The owner sees their private script too
+        let (scripts, _) = service
+            .get_scripts(10, 0, None, Some("owner"))
+            .await
+            .unwrap();
         assert_eq!(scripts.len(), 2); // Both scripts
     }
 
@@ -667,7 +2267,7 @@ mod tests {
         let created = service.create_script(req).await.unwrap();
 
         let preview = service
-            .get_script_preview(&created.id)
+            .get_script_preview(&created.id, None)
             .await
             .unwrap()
             .expect("free script must return a preview");
@@ -699,7 +2299,7 @@ mod tests {
         let created = service.create_script(req).await.unwrap();
 
         let preview = service
-            .get_script_preview(&created.id)
+            .get_script_preview(&created.id, None)
             .await
             .unwrap()
             .expect("free script must return a preview");
@@ -733,7 +2333,7 @@ mod tests {
         let created = service.create_script(req).await.unwrap();
 
         let preview = service
-            .get_script_preview(&created.id)
+            .get_script_preview(&created.id, None)
             .await
             .unwrap()
             .expect("paid script must return a (capped) preview");
@@ -763,10 +2363,299 @@ mod tests {
         let pool = setup_test_db().await;
         let service = ScriptService::new(pool);
 
-        let result = service.get_script_preview("nonexistent-id").await.unwrap();
+        let result = service
+            .get_script_preview("nonexistent-id", None)
+            .await
+            .unwrap();
         assert!(
             result.is_none(),
             "unknown id must resolve to None so the handler maps it to 404"
         );
     }
+
+    #[tokio::test]
+    async fn test_compare_scripts_keeps_request_order_and_reports_missing() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool);
+
+        let mut req = create_test_script_request();
+        req.bundle = "icp_call({ canisterId: \"ryjl3-tyaaa-aaaaa-aaaba-cai\" })".to_string();
+        let first = service.create_script(req).await.unwrap();
+        let mut req = create_test_script_request();
+        req.slug = "second-script".to_string();
+        req.is_public = Some(false);
+        let private = service.create_script(req).await.unwrap();
+
+        let ids = vec![
+            "nope".to_string(),
+            first.id.clone(),
+            private.id.clone(),
+            first.id.clone(),
+        ];
+        let (compared, missing) = service.compare_scripts(&ids).await.unwrap();
+        assert_eq!(compared.len(), 1);
+        assert_eq!(compared[0].id, first.id);
+        assert_eq!(compared[0].permissions, vec!["canister_calls"]);
+        assert_eq!(
+            compared[0].required_canisters,
+            vec!["ryjl3-tyaaa-aaaaa-aaaba-cai"]
+        );
+        assert_eq!(missing, vec!["nope".to_string(), private.id]);
+
+        let too_many: Vec<String> = (0..=MAX_COMPARED_SCRIPTS).map(|i| i.to_string()).collect();
+        assert!(matches!(
+            service.compare_scripts(&too_many).await,
+            Err(ScriptError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_configured_limits_reject_oversized_create_and_update() {
+        let pool = setup_test_db().await;
+        let limits = ScriptLimits {
+            max_bundle_bytes: 64,
+            ..ScriptLimits::default()
+        };
+        let service = ScriptService::with_limits(pool, limits.clone());
+        assert_eq!(service.limits(), &limits);
+
+        let mut oversized = create_test_script_request();
+        oversized.bundle = "x".repeat(65);
+        let err = service.create_script(oversized).await.unwrap_err();
+        assert!(matches!(err, ScriptError::BadRequest(_)));
+
+        let created = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+        let update_req = UpdateScriptRequest {
+            title: None,
+            description: None,
+            readme: None,
+            category: None,
+            bundle: Some("x".repeat(65)),
+            version: None,
+            price: None,
+            price_e8s: None,
+            currency: None,
+            payload_version: None,
+            is_public: None,
+            tags: None,
+            signature: None,
+            timestamp: None,
+            script_id: None,
+            author_principal: None,
+            author_public_key: None,
+            action: None,
+            publish_at: None,
+            channel: None,
+            dependencies: None,
+        };
+        let err = service
+            .update_script(&created.id, update_req)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScriptError::BadRequest(_)));
+        let unchanged = service.get_script(&created.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.bundle, "print('hello')");
+    }
+
+    #[tokio::test]
+    async fn test_create_velocity_returns_429_until_admin_clears_it() {
+        let pool = setup_test_db().await;
+        let service = ScriptService::new(pool).with_velocity_rules(VelocityRules {
+            max_creates_per_hour: 1,
+            ..VelocityRules::default()
+        });
+
+        service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+        let err = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScriptError::TooManyRequests(_)));
+
+        service.clear_velocity("unused-account", &["test-public-key".to_string()]);
+        assert!(service
+            .create_script(create_test_script_request())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_private_script_quota_follows_tier() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('acct', 'acct', 'acct', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO account_public_keys (id, account_id, public_key, ic_principal, added_at)
+             VALUES ('key', 'acct', 'test-public-key', 'principal', '2026-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let service = ScriptService::new(pool.clone());
+        let private = || {
+            let mut req = create_test_script_request();
+            req.slug = uuid::Uuid::new_v4().to_string();
+            req.is_public = Some(false);
+            req
+        };
+
+        for _ in 0..AccountTier::Free.quotas().max_private_scripts {
+            service.create_script(private()).await.unwrap();
+        }
+        let err = service.create_script(private()).await.unwrap_err();
+        assert!(matches!(err, ScriptError::Forbidden(_)));
+
+        // Public scripts are not limited, but cannot be flipped private.
+        let mut public = private();
+        public.is_public = Some(true);
+        let public = service.create_script(public).await.unwrap();
+        let err = service
+            .update_script(
+                &public.id,
+                UpdateScriptRequest {
+                    title: None,
+                    description: None,
+                    readme: None,
+                    category: None,
+                    bundle: None,
+                    version: None,
+                    price: None,
+                    price_e8s: None,
+                    currency: None,
+                    payload_version: None,
+                    is_public: Some(false),
+                    tags: None,
+                    signature: None,
+                    timestamp: None,
+                    script_id: None,
+                    author_principal: None,
+                    author_public_key: None,
+                    action: None,
+                    publish_at: None,
+                    channel: None,
+                    dependencies: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScriptError::Forbidden(_)));
+
+        sqlx::query("UPDATE accounts SET tier = 'pro' WHERE id = 'acct'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(service.create_script(private()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_account_settings_apply_to_uploads() {
+        use crate::account_settings::SignatureScheme;
+        use crate::models::AccountSettings;
+        let pool = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('acct', 'acct', 'acct', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO account_public_keys (id, account_id, public_key, ic_principal, added_at)
+             VALUES ('key', 'acct', 'test-public-key', 'principal', '2026-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let settings = AccountSettingsRepository::new(pool.clone());
+        let mut stored = AccountSettings {
+            default_visibility: DefaultVisibility::Private,
+            ..Default::default()
+        };
+        settings
+            .upsert("acct", &stored, "2026-01-01T00:00:00Z")
+            .await
+            .unwrap();
+        let service = ScriptService::new(pool);
+
+        let draft = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+        assert!(!draft.is_public);
+        let mut public = create_test_script_request();
+        public.slug = "explicitly-public".to_string();
+        public.is_public = Some(true);
+        assert!(service.create_script(public).await.unwrap().is_public);
+
+        // `test-public-key` is not an Ed25519 key.
+        stored.required_signature_scheme = SignatureScheme::Ed25519;
+        settings
+            .upsert("acct", &stored, "2026-01-01T00:00:00Z")
+            .await
+            .unwrap();
+        let mut req = create_test_script_request();
+        req.slug = "wrong-scheme".to_string();
+        let err = service.create_script(req).await.unwrap_err();
+        assert!(matches!(err, ScriptError::Forbidden(ref m) if m == SCHEME_NOT_ALLOWED));
+    }
+
+    #[tokio::test]
+    async fn test_strikes_freeze_uploads() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES ('acct', 'acct', 'acct', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO account_public_keys (id, account_id, public_key, ic_principal, added_at)
+             VALUES ('key', 'acct', 'test-public-key', 'principal', '2026-01-01T00:00:00Z')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let service = ScriptService::new(pool.clone());
+        let script = service
+            .create_script(create_test_script_request())
+            .await
+            .unwrap();
+
+        let moderation = ModerationRepository::new(pool.clone());
+        for n in 0..crate::moderation::UPLOAD_FREEZE_STRIKES {
+            moderation
+                .add_strike(
+                    &format!("strike-{n}"),
+                    "acct",
+                    "spam",
+                    "2026-01-01T00:00:00Z",
+                )
+                .await
+                .unwrap();
+        }
+        let mut next = create_test_script_request();
+        next.slug = "another".to_string();
+        let err = service.create_script(next).await.unwrap_err();
+        assert!(matches!(err, ScriptError::Forbidden(_)));
+        let err = service.publish_script(&script.id).await.unwrap_err();
+        assert!(matches!(err, ScriptError::Forbidden(_)));
+
+        moderation
+            .revoke_strike("acct", "strike-0", "appeal", "2026-01-02T00:00:00Z")
+            .await
+            .unwrap();
+        assert!(service.publish_script(&script.id).await.is_ok());
+    }
 }
@@ -0,0 +1,211 @@
+use crate::models::{Script, VersionReliability};
+use crate::repositories::{ScriptRepository, TelemetryRepository};
+use crate::services::error::TelemetryError;
+use crate::telemetry::{self, TelemetryEvent, TelemetryReport};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+pub struct TelemetryService {
+    repo: TelemetryRepository,
+    scripts: ScriptRepository,
+}
+
+impl TelemetryService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: TelemetryRepository::new(pool.clone()),
+            scripts: ScriptRepository::new(pool),
+        }
+    }
+
+    /// Counts a validated report against its public script.
+    pub async fn record(&self, report: &TelemetryReport) -> Result<(), TelemetryError> {
+        let script = self.script(&report.script_id).await?;
+        if !script.is_public {
+            return Err(TelemetryError::NotFound("Script not found".to_string()));
+        }
+        let error_class = report.error_class.map_or("", |class| class.as_str());
+        self.repo
+            .increment(
+                &script.id,
+                &report.version,
+                &report.engine_version,
+                report.event.as_str(),
+                error_class,
+                &Utc::now().to_rfc3339(),
+            )
+            .await
+            .map_err(|e| TelemetryError::Internal(format!("Failed to record telemetry: {e}")))
+    }
+
+    /// Per-version reliability of a script owned by `account_id`, newest
+    /// version first.
+    pub async fn reliability(
+        &self,
+        account_id: &str,
+        script_id: &str,
+    ) -> Result<Vec<VersionReliability>, TelemetryError> {
+        let script = self.script(script_id).await?;
+        if script.owner_account_id.as_deref() != Some(account_id) {
+            return Err(TelemetryError::Forbidden(
+                "Only the script owner can read its telemetry".to_string(),
+            ));
+        }
+        let counts = self
+            .repo
+            .counts_by_version(script_id)
+            .await
+            .map_err(|e| TelemetryError::Internal(format!("Failed to load telemetry: {e}")))?;
+
+        let mut versions: BTreeMap<String, VersionReliability> = BTreeMap::new();
+        for (version, event, error_class, count) in counts {
+            let entry = versions
+                .entry(version.clone())
+                .or_insert_with(|| VersionReliability {
+                    version,
+                    ..Default::default()
+                });
+            let failed = !error_class.is_empty();
+            if event == TelemetryEvent::Install.as_str() {
+                entry.installs += count;
+                entry.install_failures += if failed { count } else { 0 };
+            } else {
+                entry.runs += count;
+                entry.run_failures += if failed { count } else { 0 };
+            }
+            if failed {
+                *entry.error_classes.entry(error_class).or_default() += count;
+            }
+        }
+
+        let mut versions: Vec<VersionReliability> = versions
+            .into_values()
+            .map(|mut v| {
+                let total = v.installs + v.runs;
+                v.reliability_score = telemetry::reliability_score(
+                    total - v.install_failures - v.run_failures,
+                    total,
+                );
+                v
+            })
+            .collect();
+        versions.sort_by(|a, b| {
+            match (
+                semver::Version::parse(&a.version),
+                semver::Version::parse(&b.version),
+            ) {
+                (Ok(a), Ok(b)) => b.cmp(&a),
+                _ => b.version.cmp(&a.version),
+            }
+        });
+        Ok(versions)
+    }
+
+    async fn script(&self, script_id: &str) -> Result<Script, TelemetryError> {
+        self.scripts
+            .find_by_id(script_id)
+            .await
+            .map_err(|e| TelemetryError::Internal(format!("Failed to load script: {e}")))?
+            .ok_or_else(|| TelemetryError::NotFound("Script not found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::ErrorClass;
+    use crate::test_fixtures::insert_script;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    fn report(
+        version: &str,
+        event: TelemetryEvent,
+        error_class: Option<ErrorClass>,
+    ) -> TelemetryReport {
+        TelemetryReport {
+            script_id: "s".to_string(),
+            version: version.to_string(),
+            engine_version: "0.9.0".to_string(),
+            event,
+            success: error_class.is_none(),
+            error_class,
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_aggregate_per_version() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "s", "owner", 0, true).await;
+        let service = TelemetryService::new(pool);
+
+        for _ in 0..3 {
+            service
+                .record(&report("1.2.0", TelemetryEvent::Run, None))
+                .await
+                .unwrap();
+        }
+        let mut other_engine = report("1.2.0", TelemetryEvent::Run, Some(ErrorClass::Timeout));
+        other_engine.engine_version = "1.0.0".to_string();
+        service.record(&other_engine).await.unwrap();
+        service
+            .record(&report("1.2.0", TelemetryEvent::Install, None))
+            .await
+            .unwrap();
+        service
+            .record(&report(
+                "1.10.0",
+                TelemetryEvent::Install,
+                Some(ErrorClass::Download),
+            ))
+            .await
+            .unwrap();
+
+        let versions = service.reliability("owner", "s").await.unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|v| v.version.as_str())
+                .collect::<Vec<_>>(),
+            ["1.10.0", "1.2.0"]
+        );
+        let v12 = &versions[1];
+        assert_eq!((v12.installs, v12.install_failures), (1, 0));
+        assert_eq!((v12.runs, v12.run_failures), (4, 1));
+        assert_eq!(v12.error_classes.get("timeout"), Some(&1));
+        assert!(v12.reliability_score.unwrap() > 0.0);
+        assert_eq!(versions[0].reliability_score, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn only_public_scripts_report_and_only_owners_read() {
+        let pool = setup_test_db().await;
+        insert_script(&pool, "s", "owner", 0, true).await;
+        insert_script(&pool, "draft", "owner", 0, false).await;
+        let service = TelemetryService::new(pool);
+
+        let mut draft = report("1.0.0", TelemetryEvent::Run, None);
+        draft.script_id = "draft".to_string();
+        assert!(matches!(
+            service.record(&draft).await,
+            Err(TelemetryError::NotFound(_))
+        ));
+        let mut missing = report("1.0.0", TelemetryEvent::Run, None);
+        missing.script_id = "missing".to_string();
+        assert!(matches!(
+            service.record(&missing).await,
+            Err(TelemetryError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.reliability("someone", "s").await,
+            Err(TelemetryError::Forbidden(_))
+        ));
+        assert!(service.reliability("owner", "s").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,523 @@
+use crate::models::{AuthorWebhook, Script, ScriptValidation};
+use crate::repositories::{AccountSettingsRepository, WebhookRepository};
+use crate::services::error::WebhookError;
+use crate::startup_checks::is_development;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use std::net::IpAddr;
+
+/// Download counts that trigger a one-off `downloads.milestone` event.
+pub const DOWNLOAD_MILESTONES: &[i64] = &[100, 1_000, 10_000, 100_000, 1_000_000];
+
+const MAX_URL_LEN: usize = 2048;
+
+/// One webhook POST the background job should make: `body` is sent verbatim
+/// and signed with `secret` (see [`WebhookService::signature_header`]).
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub account_id: String,
+    pub url: String,
+    pub secret: String,
+    pub event: &'static str,
+    pub body: serde_json::Value,
+}
+
+pub struct WebhookService {
+    repo: WebhookRepository,
+    settings: AccountSettingsRepository,
+}
+
+impl WebhookService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            repo: WebhookRepository::new(pool.clone()),
+            settings: AccountSettingsRepository::new(pool),
+        }
+    }
+
+    /// Registers (or replaces) the account's webhook and returns it together
+    /// with a freshly generated signing secret. Re-registering rotates the
+    /// secret.
+    pub async fn set_webhook(
+        &self,
+        account_id: &str,
+        url: &str,
+    ) -> Result<AuthorWebhook, WebhookError> {
+        check_webhook_target(url, is_development()).await?;
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let now = Utc::now().to_rfc3339();
+        self.repo
+            .upsert(account_id, url, &URL_SAFE_NO_PAD.encode(secret), &now)
+            .await
+            .map_err(|e| WebhookError::Internal(format!("Failed to save webhook: {e}")))
+    }
+
+    pub async fn get_webhook(&self, account_id: &str) -> Result<AuthorWebhook, WebhookError> {
+        self.repo
+            .find_by_account(account_id)
+            .await
+            .map_err(|e| WebhookError::Internal(format!("Failed to load webhook: {e}")))?
+            .ok_or_else(|| WebhookError::NotFound("No webhook registered".to_string()))
+    }
+
+    pub async fn delete_webhook(&self, account_id: &str) -> Result<(), WebhookError> {
+        let removed = self
+            .repo
+            .delete(account_id)
+            .await
+            .map_err(|e| WebhookError::Internal(format!("Failed to delete webhook: {e}")))?;
+        if !removed {
+            return Err(WebhookError::NotFound("No webhook registered".to_string()));
+        }
+        Ok(())
+    }
+
+    /// One `stats.daily` delivery per registered webhook, listing every live
+    /// script the author owns.
+    pub async fn daily_snapshots(&self) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let sent_at = Utc::now().to_rfc3339();
+        let mut deliveries = Vec::new();
+        for hook in self.repo.find_all().await? {
+            let scripts = self.repo.script_stats_for_owner(&hook.account_id).await?;
+            let body = serde_json::json!({
+                "event": "stats.daily",
+                "accountId": hook.account_id,
+                "sentAt": sent_at,
+                "scripts": scripts,
+            });
+            deliveries.push(delivery(hook, "stats.daily", body));
+        }
+        self.unmuted(deliveries).await
+    }
+
+    /// Records newly crossed download milestones for scripts whose authors
+    /// have a webhook and returns a `downloads.milestone` delivery for each.
+    /// A script that jumped several milestones since the last run gets a
+    /// single event for the highest one; the lower ones are recorded
+    /// silently so they never fire later.
+    pub async fn milestone_events(&self) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let now = Utc::now().to_rfc3339();
+        let mut deliveries = Vec::new();
+        for hook in self.repo.find_all().await? {
+            for script in self.repo.script_stats_for_owner(&hook.account_id).await? {
+                let mut newest = None;
+                for &milestone in DOWNLOAD_MILESTONES {
+                    if i64::from(script.downloads) < milestone {
+                        break;
+                    }
+                    if self
+                        .repo
+                        .record_milestone(&script.id, milestone, &now)
+                        .await?
+                    {
+                        newest = Some(milestone);
+                    }
+                }
+                if let Some(milestone) = newest {
+                    let body = serde_json::json!({
+                        "event": "downloads.milestone",
+                        "accountId": hook.account_id,
+                        "sentAt": now,
+                        "scriptId": script.id,
+                        "title": script.title,
+                        "milestone": milestone,
+                        "downloads": script.downloads,
+                    });
+                    deliveries.push(delivery(hook.clone(), "downloads.milestone", body));
+                }
+            }
+        }
+        self.unmuted(deliveries).await
+    }
+
+    /// A `script.published` delivery for each script (published by its
+    /// schedule) whose owner has a webhook.
+    pub async fn published_events(
+        &self,
+        scripts: &[Script],
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let sent_at = Utc::now().to_rfc3339();
+        let mut deliveries = Vec::new();
+        for script in scripts {
+            let Some(owner) = script.owner_account_id.as_deref() else {
+                continue;
+            };
+            let Some(hook) = self.repo.find_by_account(owner).await? else {
+                continue;
+            };
+            let body = serde_json::json!({
+                "event": "script.published",
+                "accountId": hook.account_id,
+                "sentAt": sent_at,
+                "scriptId": script.id,
+                "slug": script.slug,
+                "title": script.title,
+                "version": script.version,
+            });
+            deliveries.push(delivery(hook, "script.published", body));
+        }
+        self.unmuted(deliveries).await
+    }
+
+    /// A `script.validation_failed` delivery for each script that started
+    /// failing re-validation and whose owner has a webhook.
+    pub async fn validation_failed_events(
+        &self,
+        failing: &[(Script, ScriptValidation)],
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let sent_at = Utc::now().to_rfc3339();
+        let mut deliveries = Vec::new();
+        for (script, validation) in failing {
+            let Some(owner) = script.owner_account_id.as_deref() else {
+                continue;
+            };
+            let Some(hook) = self.repo.find_by_account(owner).await? else {
+                continue;
+            };
+            let body = serde_json::json!({
+                "event": "script.validation_failed",
+                "accountId": hook.account_id,
+                "sentAt": sent_at,
+                "scriptId": script.id,
+                "slug": script.slug,
+                "title": script.title,
+                "version": validation.version,
+                "rulesVersion": validation.rules_version,
+                "errors": validation.errors,
+            });
+            deliveries.push(delivery(hook, "script.validation_failed", body));
+        }
+        self.unmuted(deliveries).await
+    }
+
+    /// Drops the deliveries of events their account muted in its settings.
+    async fn unmuted(
+        &self,
+        deliveries: Vec<WebhookDelivery>,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let mut kept = Vec::with_capacity(deliveries.len());
+        for delivery in deliveries {
+            let muted = self
+                .settings
+                .find(&delivery.account_id)
+                .await?
+                .is_some_and(|s| s.muted_events.iter().any(|e| e == delivery.event));
+            if !muted {
+                kept.push(delivery);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Value of the `X-Webhook-Signature` header for `body`:
+    /// `sha256=<hex HMAC-SHA256(secret, body)>`, the scheme GitHub and Stripe
+    /// receivers already know how to check.
+    pub fn signature_header(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC-SHA256 accepts keys of any length");
+        mac.update(body);
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        format!("sha256={hex}")
+    }
+}
+
+fn delivery(hook: AuthorWebhook, event: &'static str, body: serde_json::Value) -> WebhookDelivery {
+    WebhookDelivery {
+        account_id: hook.account_id,
+        url: hook.url,
+        secret: hook.secret,
+        event,
+        body,
+    }
+}
+
+/// Whether webhooks may be delivered to `ip`. Loopback, private, link-local
+/// (which holds the cloud metadata endpoints), shared, multicast and
+/// unspecified addresses reach the backend's own network, not an author's
+/// receiver.
+pub(crate) fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_multicast()
+                || v4.is_broadcast()
+                || v4.is_unspecified()
+                || a == 0
+                // 100.64.0.0/10, carrier-grade NAT.
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_multicast()
+                    || v6.is_unspecified()
+                    // fc00::/7 unique local, fe80::/10 link-local.
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// The address of a URL host that is an IP literal (`[…]` for IPv6).
+fn ip_literal(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// HTTPS to a public address only. `allow_local` (development) also lets
+/// plain HTTP to localhost and non-public addresses through. Only IP-literal
+/// hosts are checked here; hostnames are checked once resolved (see
+/// [`check_webhook_target`] and the delivery client).
+pub(crate) fn validate_webhook_url(
+    url: &str,
+    allow_local: bool,
+) -> Result<reqwest::Url, WebhookError> {
+    if url.len() > MAX_URL_LEN {
+        return Err(WebhookError::BadRequest(format!(
+            "url exceeds the maximum of {MAX_URL_LEN} characters"
+        )));
+    }
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| WebhookError::BadRequest(format!("url is not a valid URL: {e}")))?;
+    let Some(host) = parsed.host_str() else {
+        return Err(WebhookError::BadRequest("url has no host".to_string()));
+    };
+    let local = matches!(host, "localhost" | "127.0.0.1" | "[::1]");
+    let literal = ip_literal(host);
+    match parsed.scheme() {
+        "https" => {}
+        "http" if local && allow_local => {}
+        _ => return Err(WebhookError::BadRequest("url must use https".to_string())),
+    }
+    if !allow_local && literal.is_some_and(|ip| !is_public_ip(ip)) {
+        return Err(WebhookError::BadRequest(
+            "url must point to a public address".to_string(),
+        ));
+    }
+    Ok(parsed)
+}
+
+/// [`validate_webhook_url`], plus every address the host resolves to must be
+/// public (unless `allow_local`). Deliveries check again when they connect.
+async fn check_webhook_target(url: &str, allow_local: bool) -> Result<(), WebhookError> {
+    let parsed = validate_webhook_url(url, allow_local)?;
+    let host = parsed.host_str().unwrap_or_default();
+    if allow_local || ip_literal(host).is_some() {
+        return Ok(());
+    }
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<_> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| WebhookError::BadRequest(format!("url host does not resolve: {e}")))?
+        .collect();
+    if addrs.is_empty() || addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(WebhookError::BadRequest(
+            "url must point to a public address".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new().connect(":memory:").await.unwrap();
+        crate::db::initialize_database(&pool).await;
+        pool
+    }
+
+    async fn insert_account(pool: &SqlitePool, id: &str) {
+        sqlx::query(
+            "INSERT INTO accounts (id, username, display_name, created_at, updated_at)
+             VALUES (?1, ?1, ?1, '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn insert_script(pool: &SqlitePool, id: &str, owner: &str, downloads: i32) {
+        sqlx::query(
+            "INSERT INTO scripts (id, slug, owner_account_id, title, description, category, bundle,
+                                  downloads, created_at, updated_at)
+             VALUES (?1, ?1, ?2, ?1, 'd', 'utility', 'b', ?3,
+                     '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z')",
+        )
+        .bind(id)
+        .bind(owner)
+        .bind(downloads)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn webhook_urls_must_be_https_to_public_addresses() {
+        assert!(validate_webhook_url("https://example.com/hook", false).is_ok());
+        assert!(validate_webhook_url("https://203.0.113.10/hook", false).is_ok());
+        for url in [
+            "http://example.com/hook",
+            "not a url",
+            "http://localhost:8080/hook",
+            "https://127.0.0.1/hook",
+            "https://[::1]/hook",
+            "https://10.0.0.5/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[fd00:ec2::254]/hook",
+            "https://[::ffff:192.168.1.1]/hook",
+        ] {
+            assert!(
+                matches!(
+                    validate_webhook_url(url, false),
+                    Err(WebhookError::BadRequest(_))
+                ),
+                "{url} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn local_targets_need_development() {
+        assert!(validate_webhook_url("http://localhost:8080/hook", true).is_ok());
+        assert!(validate_webhook_url("https://10.0.0.5/hook", true).is_ok());
+        assert!(validate_webhook_url("http://10.0.0.5/hook", true).is_err());
+    }
+
+    #[tokio::test]
+    async fn hostnames_resolving_to_private_addresses_are_rejected() {
+        let result = check_webhook_target("https://localhost:8443/hook", false).await;
+        assert!(
+            matches!(&result, Err(WebhookError::BadRequest(m)) if m.contains("public address")),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn signature_header_is_hex_hmac() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            WebhookService::signature_header("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn set_rotates_secret_and_delete_removes() {
+        let pool = setup_test_db().await;
+        insert_account(&pool, "acct-1").await;
+        let service = WebhookService::new(pool);
+
+        let first = service
+            .set_webhook("acct-1", "https://203.0.113.10/a")
+            .await
+            .unwrap();
+        let second = service
+            .set_webhook("acct-1", "https://203.0.113.10/b")
+            .await
+            .unwrap();
+        assert_ne!(first.secret, second.secret);
+        assert_eq!(second.created_at, first.created_at);
+
+        let stored = service.get_webhook("acct-1").await.unwrap();
+        assert_eq!(stored.url, "https://203.0.113.10/b");
+        service.delete_webhook("acct-1").await.unwrap();
+        assert!(matches!(
+            service.get_webhook("acct-1").await,
+            Err(WebhookError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn milestones_fire_once_for_the_highest_crossed() {
+        let pool = setup_test_db().await;
+        insert_account(&pool, "acct-1").await;
+        insert_script(&pool, "popular", "acct-1", 1_500).await;
+        insert_script(&pool, "quiet", "acct-1", 5).await;
+        let service = WebhookService::new(pool.clone());
+        service
+            .set_webhook("acct-1", "https://203.0.113.10/hook")
+            .await
+            .unwrap();
+
+        let events = service.milestone_events().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].body["scriptId"], "popular");
+        assert_eq!(events[0].body["milestone"], 1_000);
+
+        assert!(service.milestone_events().await.unwrap().is_empty());
+
+        sqlx::query("UPDATE scripts SET downloads = 10000 WHERE id = 'popular'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let events = service.milestone_events().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].body["milestone"], 10_000);
+    }
+
+    #[tokio::test]
+    async fn daily_snapshot_lists_the_authors_scripts() {
+        let pool = setup_test_db().await;
+        insert_account(&pool, "acct-1").await;
+        insert_account(&pool, "acct-2").await;
+        insert_script(&pool, "mine", "acct-1", 3).await;
+        insert_script(&pool, "theirs", "acct-2", 7).await;
+        let service = WebhookService::new(pool);
+        service
+            .set_webhook("acct-1", "https://203.0.113.10/hook")
+            .await
+            .unwrap();
+
+        let snapshots = service.daily_snapshots().await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        let scripts = snapshots[0].body["scripts"].as_array().unwrap();
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0]["id"], "mine");
+        assert_eq!(scripts[0]["downloads"], 3);
+    }
+
+    #[tokio::test]
+    async fn muted_events_are_not_delivered() {
+        let pool = setup_test_db().await;
+        insert_account(&pool, "acct-1").await;
+        insert_script(&pool, "popular", "acct-1", 150).await;
+        let service = WebhookService::new(pool.clone());
+        service
+            .set_webhook("acct-1", "https://203.0.113.10/hook")
+            .await
+            .unwrap();
+        let settings = crate::models::AccountSettings {
+            muted_events: vec!["downloads.milestone".to_string()],
+            ..Default::default()
+        };
+        AccountSettingsRepository::new(pool)
+            .upsert("acct-1", &settings, "2026-01-01T00:00:00Z")
+            .await
+            .unwrap();
+
+        assert!(service.milestone_events().await.unwrap().is_empty());
+        assert_eq!(service.daily_snapshots().await.unwrap().len(), 1);
+    }
+}
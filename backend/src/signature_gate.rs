@@ -22,12 +22,13 @@ use poem::http::StatusCode;
 use sqlx::SqlitePool;
 
 use crate::{
+    account_settings::SCHEME_NOT_ALLOWED,
     auth::{self, AuthError},
-    repositories::{AccountRepository, SignatureAuditParams},
+    repositories::{AccountRepository, AccountSettingsRepository, SignatureAuditParams},
 };
 
-/// The signature + identity fields every signed request carries. Mirrors the
-/// [`crate::models::EntitlementRequest`] shape (snake_case on the wire):
+/// The signature + identity fields every signed request carries (snake_case
+/// on the wire):
 /// `{signature, author_public_key, author_principal, timestamp, nonce}`.
 pub struct SignedAuthFields<'a> {
     pub signature: &'a str,
@@ -55,11 +56,15 @@ pub struct AuthGateRejection {
 /// Steps (mirrors the signed download endpoint):
 /// 1. Resolve `account_id` from `find_public_key_by_value` (unknown key → 401).
 /// 2. Build the canonical payload via `build_payload(&resolved_account_id)` and
-///    verify the Ed25519/secp256k1 signature over it (mismatch → 401).
+///    verify the Ed25519/secp256k1 signature over it (mismatch → 401), then
+///    check the key against the account's required signature scheme (→ 403).
 /// 3. `validate_replay_prevention` — timestamp window + single-use nonce.
 /// 4. `record_signature_audit` — fail-closed (an unrecorded request is
 ///    replayable, so we refuse to proceed).
 ///
+/// Rejections in steps 2-4 are also stored against the resolved account
+/// with their detailed reason, for the admin account overview.
+///
 /// `build_payload` receives the resolved `account_id` and returns the canonical
 /// JSON the caller signed. Including the resolved `account_id` inside the
 /// payload is what binds authorship (see the module-level doc).
@@ -108,10 +113,45 @@ pub async fn verify_signed_account_request(
             account_id = %account_id,
             "Signature gate: verification failed: {e}"
         );
-        return Err(AuthGateRejection {
-            status: StatusCode::UNAUTHORIZED,
-            message: "Invalid signature",
-        });
+        return Err(reject(
+            account_repo,
+            &account_id,
+            action,
+            StatusCode::UNAUTHORIZED,
+            "Invalid signature",
+            format!("signature verification failed: {e}"),
+        )
+        .await);
+    }
+    match AccountSettingsRepository::new(pool.clone())
+        .required_scheme(&account_id)
+        .await
+    {
+        Ok(scheme) if scheme.allows(auth_fields.author_public_key) => {}
+        Ok(scheme) => {
+            tracing::warn!(
+                action,
+                account_id = %account_id,
+                "Signature gate: account requires {} keys",
+                scheme.as_str()
+            );
+            return Err(reject(
+                account_repo,
+                &account_id,
+                action,
+                StatusCode::FORBIDDEN,
+                SCHEME_NOT_ALLOWED,
+                format!("account requires {} keys", scheme.as_str()),
+            )
+            .await);
+        }
+        Err(e) => {
+            tracing::error!(action, "Signature gate: settings lookup failed: {e}");
+            return Err(AuthGateRejection {
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+                message: "Failed to resolve account",
+            });
+        }
     }
 
     // 3. Replay prevention (timestamp window + single-use nonce).
@@ -127,10 +167,15 @@ pub async fn verify_signed_account_request(
             account_id = %account_id,
             "Signature gate: replay prevention failed: {e}"
         );
-        return Err(AuthGateRejection {
+        return Err(reject(
+            account_repo,
+            &account_id,
+            action,
             status,
-            message: "Replay prevention failed",
-        });
+            "Replay prevention failed",
+            format!("replay prevention failed: {e}"),
+        )
+        .await);
     }
 
     // 4. Record the audit (fail-closed: a replayable request is refused).
@@ -164,10 +209,15 @@ pub async fn verify_signed_account_request(
                 account_id = %account_id,
                 "Signature gate: nonce UNIQUE constraint fired (concurrent replay)"
             );
-            return Err(AuthGateRejection {
-                status: StatusCode::UNAUTHORIZED,
-                message: "Replay prevention failed",
-            });
+            return Err(reject(
+                account_repo,
+                &account_id,
+                action,
+                StatusCode::UNAUTHORIZED,
+                "Replay prevention failed",
+                "nonce reused by a concurrent request".to_string(),
+            )
+            .await);
         }
         Err(e) => {
             tracing::error!(
@@ -184,3 +234,28 @@ pub async fn verify_signed_account_request(
 
     Ok(account_id)
 }
+
+/// The rejection of a request whose key resolved to `account_id`, after
+/// storing `reason` for the admin account overview. Best-effort: a failed
+/// write is logged and the request rejected all the same.
+async fn reject(
+    account_repo: &AccountRepository,
+    account_id: &str,
+    action: &'static str,
+    status: StatusCode,
+    message: &'static str,
+    reason: String,
+) -> AuthGateRejection {
+    let now = chrono::Utc::now().to_rfc3339();
+    if let Err(e) = account_repo
+        .record_failure(account_id, action, status.as_u16(), &reason, &now)
+        .await
+    {
+        tracing::error!(
+            action,
+            account_id = %account_id,
+            "Signature gate: failed to record rejection: {e}"
+        );
+    }
+    AuthGateRejection { status, message }
+}
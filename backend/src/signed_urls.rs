@@ -0,0 +1,182 @@
+//! Short-lived HMAC-signed source URLs.
+//!
+//! `POST /api/v1/scripts/:id/source-url` (Ed25519-signed, like download)
+//! mints a token; `GET /api/v1/scripts/:id/source?token=…` serves the raw
+//! bundle to whoever holds it until it expires. This lets the app hand a
+//! plain URL to a downloader or webview without signing every fetch.
+//!
+//! Token format: `{expires_unix}.{account_id}.{mac}`, where `mac` is
+//! unpadded URL-safe base64 of HMAC-SHA256 over
+//! `source:{script_id}:{account_id}:{expires_unix}`. The script id is bound
+//! by the MAC but not carried in the token, so a token cannot be moved to a
+//! different script's URL.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::env;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const DEFAULT_TTL_SECS: i64 = 300;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SourceTokenError {
+    #[error("malformed token")]
+    Malformed,
+    #[error("token expired")]
+    Expired,
+    #[error("bad token signature")]
+    BadSignature,
+}
+
+#[derive(Clone)]
+pub struct SourceUrlSigner {
+    key: Vec<u8>,
+    ttl_secs: i64,
+}
+
+impl SourceUrlSigner {
+    pub fn new(key: Vec<u8>, ttl_secs: i64) -> Self {
+        Self { key, ttl_secs }
+    }
+
+    /// Signer keyed by `SOURCE_URL_SECRET`, with `SOURCE_URL_TTL_SECS`
+    /// (default 300). Without a secret a random per-process key is used, so
+    /// outstanding URLs stop working on restart and across replicas.
+    pub fn from_env() -> Self {
+        let key = match env::var("SOURCE_URL_SECRET") {
+            Ok(secret) if !secret.trim().is_empty() => secret.into_bytes(),
+            _ => {
+                tracing::warn!(
+                    "SOURCE_URL_SECRET not set; signed source URLs use a per-process key"
+                );
+                Self::random_key()
+            }
+        };
+        let ttl_secs = match env::var("SOURCE_URL_TTL_SECS") {
+            Ok(raw) => match raw.trim().parse::<i64>() {
+                Ok(ttl) if ttl > 0 => ttl,
+                _ => {
+                    tracing::warn!(
+                        "SOURCE_URL_TTL_SECS='{raw}' is not a positive integer; using {DEFAULT_TTL_SECS}"
+                    );
+                    DEFAULT_TTL_SECS
+                }
+            },
+            Err(_) => DEFAULT_TTL_SECS,
+        };
+        Self::new(key, ttl_secs)
+    }
+
+    fn random_key() -> Vec<u8> {
+        let mut key = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        key
+    }
+
+    fn mac(&self, script_id: &str, account_id: &str, expires_at: i64) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.key).expect("HMAC-SHA256 accepts keys of any length");
+        mac.update(format!("source:{script_id}:{account_id}:{expires_at}").as_bytes());
+        mac
+    }
+
+    /// Returns `(token, expires_unix)` for `account_id` to fetch `script_id`.
+    pub fn issue(&self, script_id: &str, account_id: &str, now: i64) -> (String, i64) {
+        let expires_at = now + self.ttl_secs;
+        let tag = self
+            .mac(script_id, account_id, expires_at)
+            .finalize()
+            .into_bytes();
+        let token = format!("{expires_at}.{account_id}.{}", URL_SAFE_NO_PAD.encode(tag));
+        (token, expires_at)
+    }
+
+    /// Checks `token` against `script_id` and returns the account it was
+    /// issued to. The MAC is compared in constant time.
+    pub fn verify(
+        &self,
+        script_id: &str,
+        token: &str,
+        now: i64,
+    ) -> Result<String, SourceTokenError> {
+        let mut parts = token.splitn(3, '.');
+        let (Some(expires_at), Some(account_id), Some(tag)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(SourceTokenError::Malformed);
+        };
+        let expires_at: i64 = expires_at
+            .parse()
+            .map_err(|_| SourceTokenError::Malformed)?;
+        let tag = URL_SAFE_NO_PAD
+            .decode(tag)
+            .map_err(|_| SourceTokenError::Malformed)?;
+
+        self.mac(script_id, account_id, expires_at)
+            .verify_slice(&tag)
+            .map_err(|_| SourceTokenError::BadSignature)?;
+        if now >= expires_at {
+            return Err(SourceTokenError::Expired);
+        }
+        Ok(account_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer() -> SourceUrlSigner {
+        SourceUrlSigner::new(b"test-secret".to_vec(), 60)
+    }
+
+    #[test]
+    fn issued_token_verifies_until_expiry() {
+        let (token, expires_at) = signer().issue("script-1", "acct-1", 1_000);
+        assert_eq!(expires_at, 1_060);
+        assert_eq!(
+            signer().verify("script-1", &token, 1_059),
+            Ok("acct-1".to_string())
+        );
+        assert_eq!(
+            signer().verify("script-1", &token, 1_060),
+            Err(SourceTokenError::Expired)
+        );
+    }
+
+    #[test]
+    fn token_is_bound_to_script_and_key() {
+        let (token, _) = signer().issue("script-1", "acct-1", 1_000);
+        assert_eq!(
+            signer().verify("script-2", &token, 1_000),
+            Err(SourceTokenError::BadSignature)
+        );
+        let other = SourceUrlSigner::new(b"other-secret".to_vec(), 60);
+        assert_eq!(
+            other.verify("script-1", &token, 1_000),
+            Err(SourceTokenError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn tampered_fields_are_rejected() {
+        let (token, _) = signer().issue("script-1", "acct-1", 1_000);
+        let extended = token.replacen("1060", "9999", 1);
+        assert_eq!(
+            signer().verify("script-1", &extended, 1_000),
+            Err(SourceTokenError::BadSignature)
+        );
+        let reassigned = token.replacen("acct-1", "acct-2", 1);
+        assert_eq!(
+            signer().verify("script-1", &reassigned, 1_000),
+            Err(SourceTokenError::BadSignature)
+        );
+        assert_eq!(
+            signer().verify("script-1", "garbage", 1_000),
+            Err(SourceTokenError::Malformed)
+        );
+    }
+}
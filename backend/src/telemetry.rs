@@ -0,0 +1,199 @@
+//! Opt-in install and run telemetry.
+//!
+//! When the user has opted in, the app reports whether installing or running
+//! a script succeeded and, when it failed, the class of the error. A report
+//! names the script, its version and the app's engine version, nothing about
+//! the user or device, and the server keeps no more than that:
+//! `POST /api/v1/telemetry` folds each report into a counter keyed by
+//! (script, version, engine version, event, error class), so only aggregates
+//! are ever stored.
+//!
+//! Authors read the counters per version, with a [`reliability_score`], at
+//! `GET /api/v1/scripts/:id/reliability`.
+
+use semver::Version;
+use serde::Deserialize;
+
+use crate::validation::{require_non_empty, FieldError, Validate};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    Install,
+    Run,
+}
+
+impl TelemetryEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Install => "install",
+            Self::Run => "run",
+        }
+    }
+}
+
+/// Why an install or run failed, coarse enough to carry no script data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// The bundle did not parse.
+    Syntax,
+    /// The script threw.
+    Runtime,
+    /// The engine stopped it for running too long.
+    Timeout,
+    /// The engine stopped it for using too much memory or too many steps.
+    ResourceLimit,
+    /// A canister call was rejected or failed.
+    CanisterCall,
+    /// The script needed a permission the user did not grant.
+    Permission,
+    /// Downloading the bundle or one of its dependencies failed.
+    Download,
+    Other,
+}
+
+impl ErrorClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Syntax => "syntax",
+            Self::Runtime => "runtime",
+            Self::Timeout => "timeout",
+            Self::ResourceLimit => "resource_limit",
+            Self::CanisterCall => "canister_call",
+            Self::Permission => "permission",
+            Self::Download => "download",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Body of `POST /api/v1/telemetry`. Unknown fields are refused, so nothing
+/// identifying can ride along.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TelemetryReport {
+    pub script_id: String,
+    /// The script version that was installed or run.
+    pub version: String,
+    /// Version of the app's script engine.
+    pub engine_version: String,
+    pub event: TelemetryEvent,
+    pub success: bool,
+    /// Required on failure, refused on success.
+    pub error_class: Option<ErrorClass>,
+}
+
+/// Longest `script_id` accepted; ids are UUIDs.
+const MAX_SCRIPT_ID_CHARS: usize = 64;
+
+fn require_semver(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if Version::parse(value).is_err() {
+        errors.push(FieldError::new(field, "must be a semver version"));
+    }
+}
+
+impl Validate for TelemetryReport {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        require_non_empty(&mut errors, "script_id", &self.script_id);
+        if self.script_id.chars().count() > MAX_SCRIPT_ID_CHARS {
+            errors.push(FieldError::new(
+                "script_id",
+                format!("must be at most {MAX_SCRIPT_ID_CHARS} characters"),
+            ));
+        }
+        require_semver(&mut errors, "version", &self.version);
+        require_semver(&mut errors, "engine_version", &self.engine_version);
+        match (self.success, self.error_class) {
+            (true, Some(_)) => errors.push(FieldError::new(
+                "error_class",
+                "must be absent when success is true",
+            )),
+            (false, None) => errors.push(FieldError::new(
+                "error_class",
+                "is required when success is false",
+            )),
+            _ => {}
+        }
+        errors
+    }
+}
+
+/// The lower bound of the 95% Wilson score interval of the success rate:
+/// a version needs many reports, not just a few lucky ones, to score high.
+/// `None` without reports.
+pub fn reliability_score(successes: i64, total: i64) -> Option<f64> {
+    if total <= 0 {
+        return None;
+    }
+    const Z: f64 = 1.96;
+    let n = total as f64;
+    let p = successes.clamp(0, total) as f64 / n;
+    let z2 = Z * Z;
+    let centre = p + z2 / (2.0 * n);
+    let margin = Z * ((p * (1.0 - p) + z2 / (4.0 * n)) / n).sqrt();
+    Some(((centre - margin) / (1.0 + z2 / n)).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(json: serde_json::Value) -> Result<TelemetryReport, serde_json::Error> {
+        serde_json::from_value(json)
+    }
+
+    fn fields(report: &TelemetryReport) -> Vec<String> {
+        report.validate().into_iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn accepts_success_and_classified_failure() {
+        let ok = report(serde_json::json!({
+            "script_id": "s1", "version": "1.2.0", "engine_version": "0.9.1",
+            "event": "install", "success": true
+        }))
+        .unwrap();
+        assert!(fields(&ok).is_empty());
+        let failed = report(serde_json::json!({
+            "script_id": "s1", "version": "1.2.0", "engine_version": "0.9.1",
+            "event": "run", "success": false, "error_class": "canister_call"
+        }))
+        .unwrap();
+        assert!(fields(&failed).is_empty());
+        assert_eq!(failed.error_class, Some(ErrorClass::CanisterCall));
+    }
+
+    #[test]
+    fn schema_is_strict() {
+        assert!(report(serde_json::json!({
+            "script_id": "s1", "version": "1.2.0", "engine_version": "0.9.1",
+            "event": "run", "success": true, "principal": "aaaaa-aa"
+        }))
+        .is_err());
+        assert!(report(serde_json::json!({
+            "script_id": "s1", "version": "1.2.0", "engine_version": "0.9.1",
+            "event": "run", "success": false, "error_class": "stack trace here"
+        }))
+        .is_err());
+
+        let bad = report(serde_json::json!({
+            "script_id": "s1", "version": "latest", "engine_version": "0.9",
+            "event": "run", "success": false
+        }))
+        .unwrap();
+        assert_eq!(fields(&bad), ["version", "engine_version", "error_class"]);
+    }
+
+    #[test]
+    fn score_rewards_volume_as_well_as_rate() {
+        assert_eq!(reliability_score(0, 0), None);
+        let few = reliability_score(3, 3).unwrap();
+        let many = reliability_score(300, 300).unwrap();
+        assert!(few < many && many < 1.0, "{few} {many}");
+        let flaky = reliability_score(150, 300).unwrap();
+        assert!((0.44..0.5).contains(&flaky), "{flaky}");
+        assert_eq!(reliability_score(0, 10), Some(0.0));
+    }
+}
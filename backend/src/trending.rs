@@ -0,0 +1,51 @@
+//! Per-category and per-tag trending lists.
+//!
+//! `GET /api/v1/scripts/trending` ranks all public scripts live. With
+//! `?category=` or `?tag=` it reads a list this job rolls up instead: every
+//! [`REFRESH_INTERVAL`] the top [`ROLLUP_SIZE`] public scripts of each
+//! category and each tag, for each signal, are written to
+//! `trending_rollups`, so a category page costs one indexed read however
+//! many scripts there are. A scoped list is at most one interval old; a
+//! script that stops being listable drops out of it at once.
+
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::services::ScriptService;
+
+/// Scripts kept per category or tag and signal.
+pub const ROLLUP_SIZE: i64 = 20;
+
+/// How often the lists are rebuilt.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Background job that rebuilds the trending rollups, the first time right
+/// away. Every instance runs it; a rebuild replaces the lists in one
+/// transaction, so readers never see them half-written. Stops when
+/// `shutdown` is cancelled, like the other jobs.
+pub fn start_trending_rollup_job(pool: SqlitePool, shutdown: CancellationToken) {
+    tracing::info!("Starting trending rollup background job");
+    tokio::spawn(rollup_loop(pool, shutdown));
+}
+
+async fn rollup_loop(pool: SqlitePool, shutdown: CancellationToken) {
+    let scripts = ScriptService::new(pool);
+    let mut interval = time::interval(REFRESH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match scripts.refresh_trending_rollups().await {
+                    Ok(rows) => tracing::debug!("Rebuilt trending rollups: {} entries", rows),
+                    Err(e) => tracing::error!("Trending rollup failed: {}", e),
+                }
+            }
+            _ = shutdown.cancelled() => {
+                tracing::info!("trending rollup job stopped");
+                return;
+            }
+        }
+    }
+}
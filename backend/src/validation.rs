@@ -0,0 +1,222 @@
+//! Request-body validation with field-level errors.
+//!
+//! Every JSON endpoint extracts its body with [`ValidJson`] instead of
+//! `poem::web::Json`. Both a body that fails to deserialize and one that
+//! deserializes but breaks a DTO rule ([`Validate`]) are answered with the
+//! same envelope:
+//!
+//! ```json
+//! { "success": false, "error": "Invalid request body",
+//!   "errors": [ { "field": "title", "error": "must not be empty" } ] }
+//! ```
+//!
+//! `error` stays a plain string so clients that only read it keep working.
+//! Rules that need configuration or the database (size limits, ownership,
+//! username format) stay in the services; DTO rules are the static checks a
+//! client can get right before sending.
+
+use poem::{
+    error::ParseJsonError, http::StatusCode, web::Json, FromRequest, IntoResponse, Request,
+    RequestBody, Response,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub error: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            error: error.into(),
+        }
+    }
+}
+
+/// Static rules for a request DTO. The default accepts everything, so a DTO
+/// with no rules of its own is a one-line `impl Validate for X {}`.
+pub trait Validate {
+    fn validate(&self) -> Vec<FieldError> {
+        Vec::new()
+    }
+}
+
+/// Pushes a `must not be empty` error when `value` is blank.
+pub fn require_non_empty(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(FieldError::new(field, "must not be empty"));
+    }
+}
+
+/// Pushes a `must not be negative` error for a negative (or NaN) number.
+pub fn require_non_negative(errors: &mut Vec<FieldError>, field: &str, value: Option<f64>) {
+    if let Some(value) = value {
+        if value.is_nan() || value < 0.0 {
+            errors.push(FieldError::new(field, "must not be negative"));
+        }
+    }
+}
+
+pub fn validation_error_response(status: StatusCode, errors: &[FieldError]) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "success": false,
+            "error": "Invalid request body",
+            "errors": errors,
+        })),
+    )
+        .into_response()
+}
+
+/// Maps a serde_json message to a field error. serde names the field for
+/// missing and unknown fields only; type errors are reported against `body`
+/// with serde's own wording, minus the line/column suffix.
+fn parse_failure(message: &str) -> FieldError {
+    let backticked = |prefix: &str| {
+        message
+            .strip_prefix(prefix)?
+            .strip_prefix('`')?
+            .split('`')
+            .next()
+            .map(str::to_string)
+    };
+    if let Some(field) = backticked("missing field ") {
+        return FieldError::new(field, "is required");
+    }
+    if let Some(field) = backticked("unknown field ") {
+        return FieldError::new(field, "is not allowed");
+    }
+    let message = message
+        .rsplit_once(" at line ")
+        .map_or(message, |(head, _)| head);
+    FieldError::new("body", message)
+}
+
+/// `Json<T>` plus [`Validate`]; see the module docs for the error shape.
+pub struct ValidJson<T>(pub T);
+
+impl<'a, T: DeserializeOwned + Validate + Send> FromRequest<'a> for ValidJson<T> {
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> poem::Result<Self> {
+        let Json(value) = Json::<T>::from_request(req, body).await.map_err(|e| {
+            let status = e.status();
+            let error = match e.downcast_ref::<ParseJsonError>() {
+                Some(ParseJsonError::Parse(parse)) => parse_failure(&parse.to_string()),
+                _ => FieldError::new("body", e.to_string()),
+            };
+            poem::Error::from_response(validation_error_response(status, &[error]))
+        })?;
+
+        let errors = value.validate();
+        if !errors.is_empty() {
+            return Err(poem::Error::from_response(validation_error_response(
+                StatusCode::BAD_REQUEST,
+                &errors,
+            )));
+        }
+        Ok(ValidJson(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::{handler, test::TestClient, Route};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Body {
+        title: String,
+        price: Option<f64>,
+    }
+
+    impl Validate for Body {
+        fn validate(&self) -> Vec<FieldError> {
+            let mut errors = Vec::new();
+            require_non_empty(&mut errors, "title", &self.title);
+            require_non_negative(&mut errors, "price", self.price);
+            errors
+        }
+    }
+
+    #[handler]
+    fn echo(ValidJson(body): ValidJson<Body>) -> String {
+        body.title
+    }
+
+    async fn post(json: &str) -> (StatusCode, serde_json::Value) {
+        let client = TestClient::new(Route::new().at("/", poem::post(echo)));
+        let resp = client
+            .post("/")
+            .content_type("application/json")
+            .body(json.to_string())
+            .send()
+            .await;
+        let status = resp.0.status();
+        let body = resp
+            .0
+            .into_body()
+            .into_json::<serde_json::Value>()
+            .await
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[test]
+    fn parse_failure_names_missing_and_unknown_fields() {
+        assert_eq!(
+            parse_failure("missing field `title` at line 1 column 2"),
+            FieldError::new("title", "is required")
+        );
+        assert_eq!(
+            parse_failure("unknown field `x`, expected `title` at line 1 column 4"),
+            FieldError::new("x", "is not allowed")
+        );
+        assert_eq!(
+            parse_failure("invalid type: integer `5`, expected a string at line 1 column 11"),
+            FieldError::new("body", "invalid type: integer `5`, expected a string")
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_body_passes_through() {
+        let client = TestClient::new(Route::new().at("/", poem::post(echo)));
+        client
+            .post("/")
+            .content_type("application/json")
+            .body(r#"{"title":"ok"}"#)
+            .send()
+            .await
+            .assert_text("ok")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn rule_violations_are_listed_per_field() {
+        let (status, body) = post(r#"{"title":" ","price":-1}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error"], "Invalid request body");
+        assert_eq!(
+            body["errors"],
+            serde_json::json!([
+                {"field": "title", "error": "must not be empty"},
+                {"field": "price", "error": "must not be negative"},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn deserialization_failure_uses_the_same_envelope() {
+        let (status, body) = post(r#"{"price":1}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["errors"],
+            serde_json::json!([{"field": "title", "error": "is required"}])
+        );
+    }
+}
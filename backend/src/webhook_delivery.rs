@@ -0,0 +1,158 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use sqlx::SqlitePool;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use crate::services::{is_public_ip, validate_webhook_url, WebhookDelivery, WebhookService};
+use crate::startup_checks::is_development;
+
+/// How often download milestones are checked. Downloads are only counted,
+/// not timestamped, so a milestone event can lag the download that crossed
+/// it by up to this long.
+const MILESTONE_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Background job that delivers author stats webhooks: a `stats.daily`
+/// snapshot once a day and `downloads.milestone` events as they happen.
+///
+/// Delivery is a single best-effort POST per event; failures are logged and
+/// not retried (the next daily snapshot carries the current numbers anyway).
+/// Stops when `shutdown` is cancelled, like the cleanup job.
+pub fn start_webhook_delivery_job(pool: SqlitePool, shutdown: CancellationToken) {
+    tracing::info!("Starting author webhook delivery background job");
+    tokio::spawn(delivery_loop(pool, shutdown));
+}
+
+/// Resolves hostnames to their public addresses only, so a receiver whose
+/// name points (or is re-pointed after registration) into the backend's
+/// network is never connected to.
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} has no public address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// The HTTP client webhook deliveries are made with. Redirects are not
+/// followed, and outside development only public addresses are connected to.
+pub(crate) fn http_client() -> reqwest::Result<reqwest::Client> {
+    let builder = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(Policy::none());
+    if is_development() {
+        builder.build()
+    } else {
+        builder.dns_resolver(Arc::new(PublicOnlyResolver)).build()
+    }
+}
+
+async fn delivery_loop(pool: SqlitePool, shutdown: CancellationToken) {
+    let service = WebhookService::new(pool);
+    let client = match http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(
+                "Webhook delivery disabled: failed to build HTTP client: {}",
+                e
+            );
+            return;
+        }
+    };
+    let mut milestones = time::interval(MILESTONE_CHECK_INTERVAL);
+    let mut snapshots = time::interval(SNAPSHOT_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = milestones.tick() => {
+                match service.milestone_events().await {
+                    Ok(events) => deliver_all(&client, events).await,
+                    Err(e) => tracing::error!("Webhook milestone check failed: {}", e),
+                }
+            }
+            _ = snapshots.tick() => {
+                match service.daily_snapshots().await {
+                    Ok(events) => deliver_all(&client, events).await,
+                    Err(e) => tracing::error!("Webhook daily snapshot failed: {}", e),
+                }
+            }
+            _ = shutdown.cancelled() => {
+                tracing::info!("webhook delivery job stopped");
+                return;
+            }
+        }
+    }
+}
+
+pub(crate) async fn deliver_all(client: &reqwest::Client, deliveries: Vec<WebhookDelivery>) {
+    for delivery in deliveries {
+        deliver(client, &delivery).await;
+    }
+}
+
+async fn deliver(client: &reqwest::Client, delivery: &WebhookDelivery) {
+    // The resolver does not see IP-literal hosts; check those here.
+    if let Err(e) = validate_webhook_url(&delivery.url, is_development()) {
+        tracing::warn!(
+            account_id = %delivery.account_id,
+            "{} webhook not delivered: {}",
+            delivery.event,
+            e
+        );
+        return;
+    }
+    let body = delivery.body.to_string();
+    let result = client
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Event", delivery.event)
+        .header(
+            "X-Webhook-Signature",
+            WebhookService::signature_header(&delivery.secret, body.as_bytes()),
+        )
+        .body(body)
+        .send()
+        .await;
+    match result {
+        Ok(resp) if resp.status().is_success() => {
+            tracing::debug!(
+                account_id = %delivery.account_id,
+                "Delivered {} webhook",
+                delivery.event
+            );
+        }
+        Ok(resp) => {
+            tracing::warn!(
+                account_id = %delivery.account_id,
+                "{} webhook rejected by receiver: HTTP {}",
+                delivery.event,
+                resp.status()
+            );
+        }
+        Err(e) => {
+            tracing::warn!(
+                account_id = %delivery.account_id,
+                "{} webhook delivery failed: {}",
+                delivery.event,
+                e
+            );
+        }
+    }
+}
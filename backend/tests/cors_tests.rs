@@ -23,7 +23,7 @@
 //! route table and tests can never drift. The production origin is read from
 //! `CORS_ALLOWED_ORIGIN` (default: `DEFAULT_PROD_ORIGIN`).
 
-use icp_marketplace_api::cors::{build_cors, DEFAULT_PROD_ORIGIN};
+use icp_marketplace_api::cors::{build_cors, build_embed_cors, DEFAULT_PROD_ORIGIN};
 use icp_marketplace_api::handlers::health_check;
 use poem::http::{Method, StatusCode};
 use poem::test::TestClient;
@@ -165,3 +165,48 @@ async fn preflight_does_not_advertise_trace_or_connect() {
         );
     }
 }
+
+#[tokio::test]
+async fn embed_route_answers_any_origin_but_nothing_else_does() {
+    // Same layout as `main`: the embed route wears the open embed CORS and
+    // every other route is nested behind the allow-list.
+    let app = Route::new()
+        .at(
+            "/api/v1/scripts/:id/embed",
+            get(health_check).with(build_embed_cors()),
+        )
+        .nest("/", build_app());
+    let client = TestClient::new(app);
+    let site = "https://abcde-aaaaa-aaaaa-aaaaa-cai.icp0.io";
+
+    let resp = client
+        .get("/api/v1/scripts/s-1/embed")
+        .header("Origin", site)
+        .send()
+        .await;
+    resp.assert_status(StatusCode::OK);
+    let header = resp
+        .0
+        .headers()
+        .get("access-control-allow-origin")
+        .expect("embed route MUST answer any origin");
+    assert!(
+        matches!(header.to_str().unwrap(), "*") || header.to_str().unwrap() == site,
+        "embed route must allow {site:?} (got {header:?})"
+    );
+
+    let resp = client
+        .request(Method::OPTIONS, "/api/v1/scripts/s-1/embed")
+        .header("Origin", site)
+        .header("Access-Control-Request-Method", "POST")
+        .send()
+        .await;
+    assert_ne!(resp.0.status(), StatusCode::OK, "embed route is GET-only");
+
+    client
+        .get("/api/v1/health")
+        .header("Origin", site)
+        .send()
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,243 @@
+//! Privacy of signed downloads: `POST /scripts/:id/download` hides a private
+//! script from everyone but its owner with the same 404 as `GET /scripts/:id`,
+//! on both channels. `POST /scripts/:id/source-url` and, for signed GETs,
+//! `GET /scripts/:id/preview` do the same.
+
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use icp_marketplace_api::{
+    db::initialize_database,
+    handlers::{download_script, get_script_preview, issue_source_url},
+    middleware::signed_identity::{
+        signed_read_message, PUBLIC_KEY_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    },
+    models::AppState,
+    rate_limit::SlidingWindowRateLimiter,
+    repositories::AccountRepository,
+    services::PasskeyService,
+};
+use poem::{get, post, test::TestClient, EndpointExt, Route};
+use rand::rngs::OsRng;
+use sqlx::sqlite::SqlitePoolOptions;
+use std::sync::Arc;
+
+const NOW: &str = "2026-07-14T00:00:00Z";
+const SCRIPT_ID: &str = "private-script";
+
+struct RealKey {
+    signing: SigningKey,
+    public_key_b64: String,
+    principal: String,
+}
+
+impl RealKey {
+    fn generate() -> Self {
+        let signing = SigningKey::generate(&mut OsRng);
+        let public_key_b64 =
+            base64::engine::general_purpose::STANDARD.encode(signing.verifying_key().as_bytes());
+        let principal = icp_marketplace_api::auth::derive_ic_principal(&public_key_b64).unwrap();
+        Self {
+            signing,
+            public_key_b64,
+            principal,
+        }
+    }
+
+    /// A download body signed over `download:{script_id}:{timestamp}:{nonce}`.
+    fn download_body(&self, script_id: &str) -> serde_json::Value {
+        self.signed_fetch_body("download", script_id)
+    }
+
+    /// A signed fetch body over `{prefix}:{script_id}:{timestamp}:{nonce}`.
+    fn signed_fetch_body(&self, prefix: &str, script_id: &str) -> serde_json::Value {
+        let timestamp = chrono::Utc::now().to_rfc3339();
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let payload = format!("{prefix}:{script_id}:{timestamp}:{nonce}");
+        let sig = self.signing.sign(payload.as_bytes());
+        serde_json::json!({
+            "public_key": self.public_key_b64,
+            "signature": base64::engine::general_purpose::STANDARD.encode(sig.to_bytes()),
+            "timestamp": timestamp,
+            "nonce": nonce,
+        })
+    }
+
+    /// The signature of a signed GET of `path_and_query` at `timestamp`.
+    fn sign_get(&self, path_and_query: &str, timestamp: i64) -> String {
+        let message = signed_read_message("GET", path_and_query, timestamp);
+        base64::engine::general_purpose::STANDARD
+            .encode(self.signing.sign(message.as_bytes()).to_bytes())
+    }
+}
+
+async fn setup() -> Arc<AppState> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("pool");
+    initialize_database(&pool).await;
+
+    let passkey_service =
+        PasskeyService::new(pool.clone(), "localhost", "http://localhost:58000").unwrap();
+    Arc::new(icp_marketplace_api::test_support::app_state_stub(
+        pool,
+        passkey_service,
+        Arc::new(SlidingWindowRateLimiter::new(5, 15 * 60)),
+    ))
+}
+
+/// Creates an account named `name` and binds `key` to it.
+async fn seed_account(state: &AppState, name: &str, key: &RealKey) {
+    let repo = AccountRepository::new(state.pool.clone());
+    repo.create_account(icp_marketplace_api::repositories::CreateAccountParams {
+        account_id: name,
+        username: name,
+        display_name: name,
+        contact_email: None,
+        contact_telegram: None,
+        contact_twitter: None,
+        contact_discord: None,
+        website_url: None,
+        bio: None,
+        now: NOW,
+    })
+    .await
+    .unwrap();
+    repo.add_public_key(
+        &format!("key-{name}"),
+        name,
+        &key.public_key_b64,
+        &key.principal,
+        NOW,
+    )
+    .await
+    .unwrap();
+}
+
+/// A private script of `owner`.
+async fn seed_private_script(state: &AppState) {
+    sqlx::query(
+        r#"INSERT INTO scripts (id, slug, owner_account_id, title, description, category, bundle, version, price, is_public, downloads, rating, review_count, created_at, updated_at)
+           VALUES (?1, 'slug', 'owner', 'T', 'D', 'c', 'b', '1.0.0', 0.0, 0, 0, 0.0, 0, ?2, ?2)"#,
+    )
+    .bind(SCRIPT_ID)
+    .bind(NOW)
+    .execute(&state.pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn private_script_download_is_404_for_non_owner_and_200_for_owner() {
+    let state = setup().await;
+    let owner = RealKey::generate();
+    let stranger = RealKey::generate();
+    seed_account(&state, "owner", &owner).await;
+    seed_account(&state, "stranger", &stranger).await;
+    seed_private_script(&state).await;
+
+    let app = Route::new()
+        .at("/scripts/:id/download", post(download_script))
+        .data(state.clone());
+    let client = TestClient::new(app);
+
+    for path in [
+        format!("/scripts/{SCRIPT_ID}/download"),
+        format!("/scripts/{SCRIPT_ID}/download?channel=beta"),
+    ] {
+        let resp = client
+            .post(&path)
+            .body_json(&stranger.download_body(SCRIPT_ID))
+            .send()
+            .await;
+        resp.assert_status(poem::http::StatusCode::NOT_FOUND);
+        let body: serde_json::Value = resp.0.into_body().into_json().await.unwrap();
+        assert_eq!(body["error"], "Script not found");
+    }
+    let downloads: i64 = sqlx::query_scalar("SELECT downloads FROM scripts WHERE id = ?1")
+        .bind(SCRIPT_ID)
+        .fetch_one(&state.pool)
+        .await
+        .unwrap();
+    assert_eq!(downloads, 0, "a refused download is not counted");
+
+    client
+        .post(format!("/scripts/{SCRIPT_ID}/download"))
+        .body_json(&owner.download_body(SCRIPT_ID))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn private_script_source_url_is_404_for_non_owner_and_issued_for_owner() {
+    let state = setup().await;
+    let owner = RealKey::generate();
+    let stranger = RealKey::generate();
+    seed_account(&state, "owner", &owner).await;
+    seed_account(&state, "stranger", &stranger).await;
+    seed_private_script(&state).await;
+
+    let app = Route::new()
+        .at("/scripts/:id/source-url", post(issue_source_url))
+        .data(state.clone());
+    let client = TestClient::new(app);
+    let path = format!("/scripts/{SCRIPT_ID}/source-url");
+
+    let resp = client
+        .post(&path)
+        .body_json(&stranger.signed_fetch_body("source-url", SCRIPT_ID))
+        .send()
+        .await;
+    resp.assert_status(poem::http::StatusCode::NOT_FOUND);
+    let body: serde_json::Value = resp.0.into_body().into_json().await.unwrap();
+    assert_eq!(body["error"], "Script not found");
+
+    client
+        .post(&path)
+        .body_json(&owner.signed_fetch_body("source-url", SCRIPT_ID))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn private_script_preview_is_404_for_non_owner_and_200_for_owner() {
+    let state = setup().await;
+    let owner = RealKey::generate();
+    let stranger = RealKey::generate();
+    seed_account(&state, "owner", &owner).await;
+    seed_account(&state, "stranger", &stranger).await;
+    seed_private_script(&state).await;
+
+    let app = Route::new()
+        .at("/scripts/:id/preview", get(get_script_preview))
+        .data(state.clone());
+    let client = TestClient::new(app);
+    let path = format!("/scripts/{SCRIPT_ID}/preview");
+    let ts = chrono::Utc::now().timestamp();
+
+    let resp = client.get(&path).send().await;
+    resp.assert_status(poem::http::StatusCode::NOT_FOUND);
+    let body: serde_json::Value = resp.0.into_body().into_json().await.unwrap();
+    assert_eq!(body["error"], "Script not found");
+
+    client
+        .get(&path)
+        .header(PUBLIC_KEY_HEADER, &stranger.public_key_b64)
+        .header(SIGNATURE_HEADER, stranger.sign_get(&path, ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status(poem::http::StatusCode::NOT_FOUND);
+
+    client
+        .get(&path)
+        .header(PUBLIC_KEY_HEADER, &owner.public_key_b64)
+        .header(SIGNATURE_HEADER, owner.sign_get(&path, ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status_is_ok();
+}
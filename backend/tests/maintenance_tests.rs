@@ -0,0 +1,103 @@
+//! Maintenance mode: while the DB switch is on, writes get 503 with a
+//! `Retry-After` hint, reads and the admin API keep working, and
+//! `/api/v1/health` reports the state.
+
+use icp_marketplace_api::db::initialize_database;
+use icp_marketplace_api::handlers::health_check;
+use icp_marketplace_api::middleware::MaintenanceGuard;
+use icp_marketplace_api::models::{AdminMaintenanceRequest, AppState};
+use icp_marketplace_api::services::PasskeyService;
+use poem::http::StatusCode;
+use poem::test::TestClient;
+use poem::{get, handler, put, EndpointExt, Route};
+use sqlx::sqlite::SqlitePoolOptions;
+use std::sync::Arc;
+
+#[handler]
+fn ok() -> &'static str {
+    "ok"
+}
+
+async fn build_state(enabled: bool) -> Arc<AppState> {
+    let pool = SqlitePoolOptions::new()
+        .connect(":memory:")
+        .await
+        .expect("Failed to create test database");
+    initialize_database(&pool).await;
+    let passkey_service = PasskeyService::new(pool.clone(), "localhost", "http://localhost:58000")
+        .expect("Failed to create PasskeyService");
+    let state = icp_marketplace_api::test_support::app_state_stub(
+        pool,
+        passkey_service,
+        Arc::new(icp_marketplace_api::rate_limit::SlidingWindowRateLimiter::new(5, 15 * 60)),
+    );
+    state
+        .maintenance_service
+        .set(&AdminMaintenanceRequest {
+            enabled,
+            message: Some("Upgrading the database".to_string()),
+            retry_after_secs: Some(60),
+            reason: "test".to_string(),
+        })
+        .await
+        .expect("Failed to set maintenance mode");
+    Arc::new(state)
+}
+
+fn build_app(state: Arc<AppState>) -> impl poem::Endpoint {
+    Route::new()
+        .at("/api/v1/health", get(health_check))
+        .at("/api/v1/scripts", get(ok).post(ok))
+        .at("/api/v1/admin/maintenance", put(ok))
+        .with(MaintenanceGuard)
+        .data(state)
+}
+
+#[tokio::test]
+async fn writes_are_refused_with_retry_hint_during_maintenance() {
+    let client = TestClient::new(build_app(build_state(true).await));
+
+    let resp = client.post("/api/v1/scripts").send().await;
+    resp.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    resp.assert_header("retry-after", "60");
+    let json = resp.json().await.value().deserialize::<serde_json::Value>();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["error"], "Upgrading the database");
+    assert_eq!(json["retryAfterSecs"], 60);
+}
+
+#[tokio::test]
+async fn reads_and_admin_writes_are_served_during_maintenance() {
+    let client = TestClient::new(build_app(build_state(true).await));
+
+    client
+        .get("/api/v1/scripts")
+        .send()
+        .await
+        .assert_status_is_ok();
+    client
+        .put("/api/v1/admin/maintenance")
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let resp = client.get("/api/v1/health").send().await;
+    resp.assert_status_is_ok();
+    let json = resp.json().await.value().deserialize::<serde_json::Value>();
+    assert_eq!(json["status"], "maintenance");
+    assert_eq!(json["maintenance"]["enabled"], true);
+}
+
+#[tokio::test]
+async fn writes_pass_when_maintenance_is_off() {
+    let client = TestClient::new(build_app(build_state(false).await));
+
+    client
+        .post("/api/v1/scripts")
+        .send()
+        .await
+        .assert_status_is_ok();
+    let resp = client.get("/api/v1/health").send().await;
+    let json = resp.json().await.value().deserialize::<serde_json::Value>();
+    assert_eq!(json["status"], "ok");
+}
@@ -0,0 +1,169 @@
+//! Runs every vector of `tests/fixtures/xss_corpus.txt` through both
+//! markdown profiles and checks the HTML that comes out: no active tags, no
+//! event handler or styling attributes, and no link or image URL outside the
+//! allowed schemes.
+
+use icp_marketplace_api::markdown::{render, Profile, URL_SCHEMES};
+
+const FORBIDDEN_TAGS: &[&str] = &[
+    "script", "style", "iframe", "frame", "object", "embed", "form", "button", "svg", "math",
+    "base", "meta", "link", "body", "html", "textarea", "select",
+];
+
+const FORBIDDEN_ATTRIBUTES: &[&str] = &[
+    "style",
+    "class",
+    "srcdoc",
+    "action",
+    "formaction",
+    "xlink:href",
+    "autofocus",
+];
+
+fn corpus() -> Vec<String> {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/xss_corpus.txt");
+    let corpus: Vec<String> = std::fs::read_to_string(path)
+        .expect("read xss corpus")
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.replace("\\n", "\n"))
+        .collect();
+    assert!(corpus.len() >= 40, "xss corpus is missing vectors");
+    corpus
+}
+
+type Tag = (String, Vec<(String, String)>);
+
+/// The start tags of serialized HTML with their attributes. Enough for
+/// html5ever's output, which always double-quotes attribute values and
+/// escapes `<` in text.
+fn start_tags(html: &str) -> Vec<Tag> {
+    let bytes = html.as_bytes();
+    let mut tags = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'<' || matches!(bytes.get(i + 1), Some(b'/') | Some(b'!')) {
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let start = i;
+        while i < bytes.len() && !matches!(bytes[i], b' ' | b'>' | b'/') {
+            i += 1;
+        }
+        let name = html[start..i].to_ascii_lowercase();
+        let mut attributes = Vec::new();
+        loop {
+            while i < bytes.len() && matches!(bytes[i], b' ' | b'/') {
+                i += 1;
+            }
+            if i >= bytes.len() || bytes[i] == b'>' {
+                break;
+            }
+            let start = i;
+            while i < bytes.len() && !matches!(bytes[i], b'=' | b' ' | b'>') {
+                i += 1;
+            }
+            let attribute = html[start..i].to_ascii_lowercase();
+            let mut value = String::new();
+            if bytes.get(i) == Some(&b'=') && bytes.get(i + 1) == Some(&b'"') {
+                let start = i + 2;
+                let end = start + html[start..].find('"').expect("unterminated attribute");
+                value = html[start..end].to_string();
+                i = end + 1;
+            }
+            attributes.push((attribute, value));
+        }
+        tags.push((name, attributes));
+    }
+    tags
+}
+
+/// Relative, or absolute with an allowed scheme, read the way a browser
+/// reads it: surrounding spaces and controls and inner tabs and newlines are
+/// ignored, and a prefix that is not a valid scheme makes the URL relative.
+fn url_is_safe(url: &str) -> bool {
+    let url: String = url
+        .trim_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    let Some((scheme, _)) = url.split_once(':') else {
+        return true;
+    };
+    let is_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    !is_scheme || URL_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str())
+}
+
+fn assert_safe(vector: &str, profile: Profile, html: &str) {
+    for (tag, attributes) in start_tags(html) {
+        assert!(
+            !FORBIDDEN_TAGS.contains(&tag.as_str()),
+            "{profile:?} kept <{tag}> from {vector:?}: {html}"
+        );
+        for (attribute, value) in attributes {
+            assert!(
+                !attribute.starts_with("on") && !FORBIDDEN_ATTRIBUTES.contains(&attribute.as_str()),
+                "{profile:?} kept {attribute} on <{tag}> from {vector:?}: {html}"
+            );
+            if matches!(attribute.as_str(), "href" | "src" | "cite") {
+                assert!(
+                    url_is_safe(&value),
+                    "{profile:?} kept {attribute}={value:?} from {vector:?}: {html}"
+                );
+            }
+            if attribute == "type" {
+                assert_eq!(value, "checkbox", "{vector:?}: {html}");
+            }
+        }
+    }
+}
+
+#[test]
+fn corpus_renders_to_inert_html() {
+    for vector in corpus() {
+        for profile in [Profile::Document, Profile::Comment] {
+            assert_safe(&vector, profile, &render(&vector, profile));
+        }
+    }
+}
+
+#[test]
+fn links_in_rendered_output_carry_the_shared_rel() {
+    for profile in [Profile::Document, Profile::Comment] {
+        let html = render("[docs](https://example.com) <a href=/x>x</a>", profile);
+        let links: Vec<Tag> = start_tags(&html)
+            .into_iter()
+            .filter(|(tag, _)| tag == "a")
+            .collect();
+        assert_eq!(links.len(), 2, "{html}");
+        for (_, attributes) in links {
+            assert!(
+                attributes
+                    .iter()
+                    .any(|(name, value)| name == "rel" && value == "noopener noreferrer nofollow"),
+                "{html}"
+            );
+        }
+    }
+}
+
+#[test]
+fn parser_reads_attributes() {
+    let tags = start_tags(r#"<p><a href="https://x.test/?a=1&amp;b=2" rel="a b">x</a><br></p>"#);
+    assert_eq!(tags.len(), 3);
+    assert_eq!(tags[1].0, "a");
+    assert_eq!(
+        tags[1].1,
+        vec![
+            (
+                "href".to_string(),
+                "https://x.test/?a=1&amp;b=2".to_string()
+            ),
+            ("rel".to_string(), "a b".to_string()),
+        ]
+    );
+}
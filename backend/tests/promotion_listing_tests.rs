@@ -0,0 +1,104 @@
+//! A running promotion is surfaced in the browse lists and the detail view as
+//! `discounted_price_e8s` + `promotion_ends_at`, next to the untouched
+//! original `price_e8s`. Scripts without one carry `null` for both.
+
+use icp_marketplace_api::db::initialize_database;
+use icp_marketplace_api::handlers::{get_script, get_scripts};
+use icp_marketplace_api::models::AppState;
+use icp_marketplace_api::services::PasskeyService;
+use poem::http::StatusCode;
+use poem::test::TestClient;
+use poem::{get, EndpointExt, Route};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use std::sync::Arc;
+
+async fn insert_script(pool: &SqlitePool, id: &str, price_e8s: i64) {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO scripts (id, slug, title, description, category, bundle, version,
+                              price, price_e8s, is_public, created_at, updated_at)
+         VALUES (?1, ?1, ?1, 'd', 'utility', 'b', '1.0.0', ?2 / 100000000.0, ?2, 1, ?3, ?3)",
+    )
+    .bind(id)
+    .bind(price_e8s)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .expect("failed to insert script");
+}
+
+async fn insert_promotion(pool: &SqlitePool, script_id: &str, percent: i64, ends_at: &str) {
+    let now = chrono::Utc::now();
+    sqlx::query(
+        "INSERT INTO promotions (id, script_id, kind, amount, currency, starts_at, ends_at, created_at)
+         VALUES (?1, ?2, 'percent', ?3, 'ICP', ?4, ?5, ?4)",
+    )
+    .bind(format!("promo-{script_id}"))
+    .bind(script_id)
+    .bind(percent)
+    .bind((now - chrono::Duration::hours(1)).to_rfc3339())
+    .bind(ends_at)
+    .execute(pool)
+    .await
+    .expect("failed to insert promotion");
+}
+
+async fn build_state() -> (Arc<AppState>, String) {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("failed to create in-memory sqlite pool");
+    initialize_database(&pool).await;
+
+    let ends_at = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+    insert_script(&pool, "on-sale", 1_000_000_000).await;
+    insert_script(&pool, "full-price", 500_000_000).await;
+    insert_promotion(&pool, "on-sale", 40, &ends_at).await;
+
+    let passkey_service = PasskeyService::new(pool.clone(), "localhost", "http://localhost:58000")
+        .expect("Failed to create PasskeyService");
+    let state = icp_marketplace_api::test_support::app_state_stub(
+        pool,
+        passkey_service,
+        Arc::new(icp_marketplace_api::rate_limit::SlidingWindowRateLimiter::new(5, 15 * 60)),
+    );
+    (Arc::new(state), ends_at)
+}
+
+fn build_app(state: Arc<AppState>) -> impl poem::Endpoint {
+    Route::new()
+        .at("/api/v1/scripts", get(get_scripts))
+        .at("/api/v1/scripts/:id", get(get_script))
+        .data(state)
+}
+
+#[tokio::test]
+async fn list_shows_original_and_discounted_price() {
+    let (state, ends_at) = build_state().await;
+    let client = TestClient::new(build_app(state));
+    let resp = client.get("/api/v1/scripts").send().await;
+    resp.assert_status(StatusCode::OK);
+    let body = resp.json().await.value().deserialize::<serde_json::Value>();
+
+    let scripts = body["data"]["scripts"].as_array().unwrap();
+    let find = |id: &str| scripts.iter().find(|s| s["id"] == id).unwrap();
+    let on_sale = find("on-sale");
+    assert_eq!(on_sale["price_e8s"], 1_000_000_000);
+    assert_eq!(on_sale["discounted_price_e8s"], 600_000_000);
+    assert_eq!(on_sale["promotion_ends_at"], ends_at.as_str());
+    let full_price = find("full-price");
+    assert!(full_price["discounted_price_e8s"].is_null());
+    assert!(full_price["promotion_ends_at"].is_null());
+}
+
+#[tokio::test]
+async fn detail_shows_discounted_price() {
+    let (state, _) = build_state().await;
+    let client = TestClient::new(build_app(state));
+    let resp = client.get("/api/v1/scripts/on-sale").send().await;
+    resp.assert_status(StatusCode::OK);
+    let body = resp.json().await.value().deserialize::<serde_json::Value>();
+    assert_eq!(body["data"]["price_e8s"], 1_000_000_000);
+    assert_eq!(body["data"]["discounted_price_e8s"], 600_000_000);
+}
@@ -16,10 +16,12 @@
 
 use icp_marketplace_api::{
     db::initialize_database,
-    models::SearchRequest,
+    models::{ActivityKind, Script, SearchRequest, TrendingScope, TrendingSignal},
+    pricing::Price,
     repositories::{
-        AccountRepository, CreateAccountParams, ReviewRepository, ScriptRepository,
-        SignatureAuditParams, UpdateAccountParams,
+        weighted_rating, AccountRepository, ActivityRepository, CreateAccountParams,
+        FollowRepository, ReviewRepository, ScriptRepository, SignatureAuditParams,
+        UpdateAccountParams, MAX_RECORDED_FAILURES,
     },
 };
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
@@ -505,6 +507,37 @@ async fn account_record_signature_audit_distinct_nonces_both_succeed() {
     assert_eq!(count, 2);
 }
 
+#[tokio::test]
+async fn account_failures_keep_only_the_latest_per_account() {
+    let pool = setup().await;
+    let repo = AccountRepository::new(pool.clone());
+    create_account_full(&repo, "acc-failing", "failing").await;
+    create_account_full(&repo, "acc-other", "other").await;
+
+    repo.record_failure("acc-other", "vault:create", 401, "other", NOW)
+        .await
+        .unwrap();
+    for i in 0..MAX_RECORDED_FAILURES + 5 {
+        let at = format!("2026-07-11T00:{:02}:{:02}Z", i / 60, i % 60);
+        repo.record_failure("acc-failing", "vault:create", 401, &format!("#{i}"), &at)
+            .await
+            .unwrap();
+    }
+
+    let failures = repo.recent_failures("acc-failing", 100).await.unwrap();
+    assert_eq!(failures.len() as i64, MAX_RECORDED_FAILURES);
+    assert_eq!(
+        failures[0].reason,
+        format!("#{}", MAX_RECORDED_FAILURES + 4),
+        "newest first"
+    );
+    assert_eq!(failures.last().unwrap().reason, "#5");
+    assert_eq!(
+        repo.recent_failures("acc-other", 100).await.unwrap().len(),
+        1
+    );
+}
+
 // ===========================================================================
 // ScriptRepository
 // ===========================================================================
@@ -529,7 +562,7 @@ async fn create_script(
         Some("pk-author"),
         Some("sig-author"),
         "1.0.0",
-        0.0,
+        &Price::free(),
         is_public,
         Some(">=1.0"),
         Some(r#"["tag1","tag2"]"#),
@@ -641,17 +674,18 @@ async fn script_find_all_privacy_filter_excludes_private() {
     create_script(&repo, "s-2", "Utilities", false, "Priv").await;
 
     let public_only = repo
-        .find_all(100, 0, None, false)
+        .find_all(100, 0, None, None)
         .await
         .expect("find_all failed");
     assert_eq!(public_only.len(), 1);
     assert_eq!(public_only[0].id, "s-1");
 
+    // An ownerless private script is nobody's to list.
     let including_private = repo
-        .find_all(100, 0, None, true)
+        .find_all(100, 0, None, Some("acc-someone"))
         .await
         .expect("find_all failed");
-    assert_eq!(including_private.len(), 2);
+    assert_eq!(including_private.len(), 1);
 }
 
 #[tokio::test]
@@ -664,7 +698,7 @@ async fn script_find_all_category_filter() {
     create_script(&repo, "s-3", "Utilities", true, "C").await;
 
     let utils = repo
-        .find_all(100, 0, Some("Utilities".to_string()), false)
+        .find_all(100, 0, Some("Utilities".to_string()), None)
         .await
         .expect("find_all failed");
     assert_eq!(utils.len(), 2);
@@ -694,7 +728,7 @@ async fn script_find_all_category_filter_rejects_sql_injection() {
     // The injection: closes the quote, ORs a tautology, comments out the rest.
     let injection = "zzz' OR 1=1--".to_string();
     let leaked = repo
-        .find_all(100, 0, Some(injection), false)
+        .find_all(100, 0, Some(injection), None)
         .await
         .expect("find_all failed");
 
@@ -713,7 +747,7 @@ async fn script_find_all_category_filter_rejects_sql_injection() {
     // A legitimate category still filters correctly (regression guard for the
     // parameterisation itself — proves the bind matches real values).
     let utils = repo
-        .find_all(100, 0, Some("Utilities".to_string()), false)
+        .find_all(100, 0, Some("Utilities".to_string()), None)
         .await
         .expect("find_all failed");
     assert_eq!(utils.len(), 1, "only the public Utilities script matches");
@@ -737,7 +771,7 @@ async fn script_find_all_pagination() {
     }
 
     let page = repo
-        .find_all(2, 1, None, false)
+        .find_all(2, 1, None, None)
         .await
         .expect("find_all failed");
     assert_eq!(page.len(), 2);
@@ -796,7 +830,7 @@ async fn script_update_changes_specified_fields() {
         Some("Finance"),
         Some("new-bundle"),
         Some("2.0.0"),
-        Some(9.99),
+        Some(&Price::new(999_000_000, "ICP")),
         Some(false),
         Some(r#"["new"]"#),
         "2026-07-11T12:00:00Z",
@@ -811,6 +845,7 @@ async fn script_update_changes_specified_fields() {
     assert_eq!(s.bundle, "new-bundle");
     assert_eq!(s.version, "2.0.0");
     assert_eq!(s.price, 9.99);
+    assert_eq!(s.price_e8s, 999_000_000);
     assert!(!s.is_public);
     assert_eq!(s.tags.as_deref(), Some(r#"["new"]"#));
     assert_eq!(s.updated_at, "2026-07-11T12:00:00Z");
@@ -878,6 +913,32 @@ async fn script_update_stats_sets_rating_and_review_count() {
     let s = repo.find_by_id("s-1").await.unwrap().unwrap();
     assert_eq!(s.rating, 4.5);
     assert_eq!(s.review_count, 10);
+    assert_eq!(s.weighted_rating, weighted_rating(4.5, 10));
+}
+
+#[test]
+fn weighted_rating_pulls_few_reviews_towards_the_prior() {
+    assert_eq!(weighted_rating(5.0, 0), 0.0);
+    // One 5-star review: (5 + 5 * 3) / 6.
+    assert!((weighted_rating(5.0, 1) - 20.0 / 6.0).abs() < 1e-9);
+    // Many reviews converge on the plain average.
+    assert!((weighted_rating(4.6, 1000) - 4.6).abs() < 0.01);
+    assert!(weighted_rating(4.6, 50) > weighted_rating(5.0, 1));
+}
+
+#[tokio::test]
+async fn script_get_featured_ranks_by_weighted_rating() {
+    let pool = setup().await;
+    let repo = ScriptRepository::new(pool);
+
+    create_script(&repo, "s-one-review", "Utilities", true, "A").await;
+    create_script(&repo, "s-established", "Utilities", true, "B").await;
+    repo.update_stats("s-one-review", 5.0, 1).await.unwrap();
+    repo.update_stats("s-established", 4.6, 50).await.unwrap();
+
+    let featured = repo.get_featured(4.0, 0, 10).await.unwrap();
+    let ids: Vec<&str> = featured.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids, ["s-established", "s-one-review"]);
 }
 
 #[tokio::test]
@@ -934,12 +995,100 @@ async fn script_get_trending_orders_by_downloads_then_rating() {
     }
     repo.increment_downloads("s-high").await.unwrap(); // s-high = 2, s-mid = 3
 
-    let trending = repo.get_trending(3).await.expect("get_trending failed");
+    let trending = repo
+        .get_trending(3, TrendingSignal::Downloads)
+        .await
+        .expect("get_trending failed");
     assert_eq!(trending[0].id, "s-mid"); // 3 downloads
     assert_eq!(trending[1].id, "s-high"); // 2 downloads
     assert_eq!(trending[2].id, "s-low"); // 0 downloads
 }
 
+#[tokio::test]
+async fn script_get_trending_by_views_ranks_by_views() {
+    let pool = setup().await;
+    let repo = ScriptRepository::new(pool);
+
+    create_script(&repo, "s-downloaded", "Utilities", true, "Downloaded").await;
+    create_script(&repo, "s-viewed", "Utilities", true, "Viewed").await;
+
+    repo.increment_downloads("s-downloaded").await.unwrap();
+    for _ in 0..2 {
+        repo.increment_views("s-viewed").await.unwrap();
+    }
+
+    let trending = repo.get_trending(2, TrendingSignal::Views).await.unwrap();
+    assert_eq!(trending[0].id, "s-viewed");
+    assert_eq!(trending[0].views, 2);
+    assert_eq!(trending[1].id, "s-downloaded");
+    assert_eq!(repo.total_views().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn script_trending_rollups_rank_each_category_and_tag() {
+    let pool = setup().await;
+    let repo = ScriptRepository::new(pool.clone());
+
+    create_script(&repo, "s-util-low", "Utilities", true, "Low").await;
+    create_script(&repo, "s-util-high", "Utilities", true, "High").await;
+    create_script(&repo, "s-util-private", "Utilities", false, "Private").await;
+    create_script(&repo, "s-fin", "Finance", true, "Fin").await;
+    sqlx::query(r#"UPDATE scripts SET tags = '["swap","swap"]' WHERE id = 's-fin'"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE scripts SET tags = 'not json' WHERE id = 's-util-low'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        repo.increment_downloads("s-util-high").await.unwrap();
+    }
+    repo.increment_views("s-util-low").await.unwrap();
+
+    repo.rebuild_trending_rollups(20, NOW).await.unwrap();
+    let ids = |scripts: Vec<Script>| scripts.into_iter().map(|s| s.id).collect::<Vec<_>>();
+    let utilities = TrendingScope::Category("Utilities");
+    assert_eq!(
+        ids(repo
+            .get_trending_in(utilities, 20, TrendingSignal::Downloads)
+            .await
+            .unwrap()),
+        ["s-util-high", "s-util-low"]
+    );
+    assert_eq!(
+        ids(repo
+            .get_trending_in(utilities, 20, TrendingSignal::Views)
+            .await
+            .unwrap()),
+        ["s-util-low", "s-util-high"]
+    );
+    assert_eq!(
+        ids(repo
+            .get_trending_in(TrendingScope::Tag("swap"), 20, TrendingSignal::Downloads)
+            .await
+            .unwrap()),
+        ["s-fin"]
+    );
+    assert_eq!(
+        ids(repo
+            .get_trending_in(TrendingScope::Tag("tag1"), 1, TrendingSignal::Downloads)
+            .await
+            .unwrap()),
+        ["s-util-high"]
+    );
+
+    // A rebuild with a smaller size replaces the lists.
+    repo.rebuild_trending_rollups(1, NOW).await.unwrap();
+    assert_eq!(
+        ids(repo
+            .get_trending_in(utilities, 20, TrendingSignal::Downloads)
+            .await
+            .unwrap()),
+        ["s-util-high"]
+    );
+}
+
 #[tokio::test]
 async fn script_get_featured_filters_by_rating_and_downloads() {
     let pool = setup().await;
@@ -992,7 +1141,7 @@ async fn script_get_compatible_matches_and_includes_null_compatibility() {
         None,
         None,
         "1.0.0",
-        0.0,
+        &Price::free(),
         true,
         None, // NULL compatibility
         None,
@@ -1152,6 +1301,60 @@ async fn script_search_bad_sort_order_returns_bad_request() {
     assert_eq!(err.0, poem::http::StatusCode::BAD_REQUEST);
 }
 
+#[tokio::test]
+async fn script_merge_categories_moves_every_listed_category() {
+    let pool = setup().await;
+    let repo = ScriptRepository::new(pool);
+    create_script(&repo, "s-1", "defi", true, "One").await;
+    create_script(&repo, "s-2", "DeFi", false, "Two").await;
+    create_script(&repo, "s-3", "Utilities", true, "Three").await;
+
+    let updated = repo
+        .merge_categories(&["defi".to_string(), "DeFi".to_string()], "DeFi & Finance")
+        .await
+        .expect("merge failed");
+    assert_eq!(updated, 2);
+
+    for (id, expected) in [
+        ("s-1", "DeFi & Finance"),
+        ("s-2", "DeFi & Finance"),
+        ("s-3", "Utilities"),
+    ] {
+        let s = repo.find_by_id(id).await.unwrap().unwrap();
+        assert_eq!(s.category, expected, "{id}");
+    }
+}
+
+#[tokio::test]
+async fn script_rename_tag_rewrites_lists_without_duplicates() {
+    let pool = setup().await;
+    let repo = ScriptRepository::new(pool.clone());
+    create_script(&repo, "s-1", "Utilities", true, "One").await;
+    create_script(&repo, "s-2", "Utilities", true, "Two").await;
+    sqlx::query(r#"UPDATE scripts SET tags = '["tag1","tag3"]' WHERE id = 's-2'"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // s-1 has ["tag1","tag2"]: tag1 → tag2 collapses into one entry.
+    let updated = repo
+        .rename_tag("tag1", "tag2")
+        .await
+        .expect("rename failed");
+    assert_eq!(updated, 2);
+
+    let tags = |s: Script| s.tags.unwrap();
+    assert_eq!(
+        tags(repo.find_by_id("s-1").await.unwrap().unwrap()),
+        r#"["tag2"]"#
+    );
+    assert_eq!(
+        tags(repo.find_by_id("s-2").await.unwrap().unwrap()),
+        r#"["tag2","tag3"]"#
+    );
+    assert_eq!(repo.rename_tag("tag1", "tag2").await.unwrap(), 0);
+}
+
 // ===========================================================================
 // ReviewRepository
 // ===========================================================================
@@ -1205,7 +1408,7 @@ async fn review_create_and_find_by_script_ordered_desc() {
     .expect("create failed");
 
     let reviews = repo
-        .find_by_script("s-reviews", 100, 0)
+        .find_by_script("s-reviews", None, 100, 0)
         .await
         .expect("find_by_script failed");
     assert_eq!(reviews.len(), 3);
@@ -1239,7 +1442,7 @@ async fn review_find_by_script_pagination() {
 
     // Page 2 with limit=2, offset=2 (DESC order: r-5,r-4,r-3,r-2,r-1).
     let page = repo
-        .find_by_script("s-reviews", 2, 2)
+        .find_by_script("s-reviews", None, 2, 2)
         .await
         .expect("find_by_script failed");
     assert_eq!(page.len(), 2);
@@ -1254,7 +1457,7 @@ async fn review_find_by_script_empty_for_no_reviews() {
     let repo = ReviewRepository::new(pool);
 
     let reviews = repo
-        .find_by_script("s-reviews", 100, 0)
+        .find_by_script("s-reviews", None, 100, 0)
         .await
         .expect("find_by_script failed");
     assert!(reviews.is_empty());
@@ -1273,8 +1476,8 @@ async fn review_count_by_script() {
         .await
         .unwrap();
 
-    assert_eq!(repo.count_by_script("s-reviews").await.unwrap(), 2);
-    assert_eq!(repo.count_by_script("nope").await.unwrap(), 0);
+    assert_eq!(repo.count_by_script("s-reviews", None).await.unwrap(), 2);
+    assert_eq!(repo.count_by_script("nope", None).await.unwrap(), 0);
 }
 
 #[tokio::test]
@@ -1349,3 +1552,344 @@ async fn review_get_average_rating_returns_none_when_no_reviews() {
         .expect("get_average_rating failed");
     assert!(avg.is_none(), "AVG over zero rows should be NULL");
 }
+
+#[tokio::test]
+async fn review_refresh_script_stats_aggregates_visible_reviews_in_one_write() {
+    let pool = setup().await;
+    create_script_for_reviews(&pool).await;
+    let reviews = ReviewRepository::new(pool.clone());
+    let scripts = ScriptRepository::new(pool);
+
+    reviews.refresh_script_stats("s-reviews").await.unwrap();
+    let script = scripts.find_by_id("s-reviews").await.unwrap().unwrap();
+    assert_eq!((script.rating, script.review_count), (0.0, 0));
+    assert_eq!(script.weighted_rating, 0.0);
+
+    reviews
+        .create("r-1", "s-reviews", "user-a", 5, None, NOW)
+        .await
+        .unwrap();
+    reviews
+        .create("r-2", "s-reviews", "user-b", 4, None, NOW)
+        .await
+        .unwrap();
+    reviews
+        .create_quarantined("r-3", "s-reviews", "user-c", 1, None, NOW, "spam")
+        .await
+        .unwrap();
+    reviews.refresh_script_stats("s-reviews").await.unwrap();
+
+    let script = scripts.find_by_id("s-reviews").await.unwrap().unwrap();
+    assert_eq!((script.rating, script.review_count), (4.5, 2));
+    assert!((script.weighted_rating - weighted_rating(4.5, 2)).abs() < 1e-9);
+}
+
+// ===========================================================================
+// Shadow-ban filters
+// ===========================================================================
+
+#[tokio::test]
+async fn shadow_banned_owner_scripts_hidden_from_public_queries_only() {
+    let pool = setup().await;
+    let accounts = AccountRepository::new(pool.clone());
+    let repo = ScriptRepository::new(pool);
+    create_account_full(&accounts, "acc-banned", "spammer").await;
+    create_script(&repo, "s-open", "Utilities", true, "Open").await;
+    repo.create(
+        "s-banned",
+        "slug-s-banned",
+        Some("acc-banned"),
+        "Spam",
+        "A description",
+        "Utilities",
+        "bundle-bytes",
+        None,
+        None,
+        None,
+        "1.0.0",
+        &Price::free(),
+        true,
+        None,
+        None,
+        NOW,
+    )
+    .await
+    .unwrap();
+
+    accounts
+        .set_shadow_banned_at("acc-banned", Some(NOW))
+        .await
+        .unwrap();
+
+    let listed = repo.find_all(20, 0, None, None).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, "s-open", "ownerless scripts must stay listed");
+    assert_eq!(repo.count_public().await.unwrap(), 1);
+    assert_eq!(
+        repo.get_trending(10, TrendingSignal::Downloads)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        repo.get_by_category("Utilities", 10).await.unwrap().len(),
+        1
+    );
+    let search = repo
+        .search(&SearchRequest {
+            query: Some("Spam".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(search.total, 0);
+    assert_eq!(repo.get_marketplace_stats().await.unwrap().0, 1);
+
+    // Direct lookups and the owner's own listing still return it.
+    assert!(repo.find_by_id("s-banned").await.unwrap().is_some());
+    assert_eq!(
+        repo.find_all(20, 0, None, Some("acc-banned"))
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    accounts
+        .set_shadow_banned_at("acc-banned", None)
+        .await
+        .unwrap();
+    assert_eq!(repo.count_public().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn shadow_banned_reviewer_excluded_from_review_listing_and_aggregates() {
+    let pool = setup().await;
+    create_script_for_reviews(&pool).await;
+    let accounts = AccountRepository::new(pool.clone());
+    let repo = ReviewRepository::new(pool);
+    create_account_full(&accounts, "user-banned", "spammer").await;
+
+    repo.create("r-1", "s-reviews", "user-a", 4, None, NOW)
+        .await
+        .unwrap();
+    repo.create("r-2", "s-reviews", "user-banned", 1, None, NOW)
+        .await
+        .unwrap();
+    accounts
+        .set_shadow_banned_at("user-banned", Some(NOW))
+        .await
+        .unwrap();
+
+    let reviews = repo.find_by_script("s-reviews", None, 10, 0).await.unwrap();
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0].user_id, "user-a");
+    assert_eq!(repo.count_by_script("s-reviews", None).await.unwrap(), 1);
+    assert_eq!(
+        repo.get_average_rating("s-reviews").await.unwrap(),
+        Some(4.0)
+    );
+    assert_eq!(
+        repo.count_by_script_and_user("s-reviews", "user-banned")
+            .await
+            .unwrap(),
+        1,
+        "the duplicate-review guard must still see the hidden review"
+    );
+    assert_eq!(
+        repo.script_ids_reviewed_by("user-banned").await.unwrap(),
+        vec!["s-reviews".to_string()]
+    );
+}
+
+// ===========================================================================
+// ActivityRepository
+// ===========================================================================
+
+#[tokio::test]
+async fn activity_backfill_and_feed_hide_private_and_quarantined_entries() {
+    let pool = setup().await;
+    let accounts = AccountRepository::new(pool.clone());
+    let scripts = ScriptRepository::new(pool.clone());
+    let reviews = ReviewRepository::new(pool.clone());
+    let activity = ActivityRepository::new(pool);
+    create_account_full(&accounts, "acc-owner", "owner").await;
+    create_account_full(&accounts, "acc-other", "other").await;
+    // A user reviews a script once, so the held review goes on another
+    // author's script.
+    for (id, owner, is_public) in [
+        ("s-public", "acc-owner", true),
+        ("s-private", "acc-owner", false),
+        ("s-other", "acc-other", true),
+    ] {
+        scripts
+            .create(
+                id,
+                &format!("slug-{id}"),
+                Some(owner),
+                "Title",
+                "A description",
+                "Utilities",
+                "bundle-bytes",
+                None,
+                None,
+                None,
+                "1.0.0",
+                &Price::free(),
+                is_public,
+                None,
+                None,
+                NOW,
+            )
+            .await
+            .unwrap();
+    }
+    reviews
+        .create("r-ok", "s-public", "acc-owner", 4, Some("Nice"), NOW)
+        .await
+        .unwrap();
+    reviews
+        .create_quarantined("r-held", "s-other", "acc-owner", 1, None, NOW, "spam")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        activity.backfill().await.unwrap(),
+        4,
+        "2 public scripts + 2 reviews"
+    );
+    assert_eq!(
+        activity.backfill().await.unwrap(),
+        0,
+        "backfill is idempotent"
+    );
+
+    activity
+        .record(
+            "acc-owner",
+            ActivityKind::VersionReleased,
+            "s-public",
+            Some("1.1.0"),
+            None,
+            "2026-07-12T00:00:00Z",
+        )
+        .await
+        .unwrap();
+
+    let feed = activity.find_by_account("acc-owner", 10, 0).await.unwrap();
+    let kinds: Vec<&str> = feed.iter().map(|e| e.kind.as_str()).collect();
+    assert_eq!(
+        kinds,
+        ["version_released", "review_posted", "script_published"]
+    );
+    assert_eq!(feed[0].version.as_deref(), Some("1.1.0"));
+    assert_eq!(feed[1].review_id.as_deref(), Some("r-ok"));
+    assert_eq!(feed[1].rating, Some(4));
+    assert!(feed.iter().all(|e| e.script_id == "s-public"));
+
+    let page = activity.find_by_account("acc-owner", 1, 1).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].kind, "review_posted");
+    assert!(activity
+        .find_by_account("nobody", 10, 0)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn followed_feed_lists_script_activity_of_followed_authors_only() {
+    let pool = setup().await;
+    let accounts = AccountRepository::new(pool.clone());
+    let follows = FollowRepository::new(pool.clone());
+    let activity = ActivityRepository::new(pool.clone());
+    let scripts = ScriptRepository::new(pool);
+    for (id, username) in [
+        ("acc-fan", "fan"),
+        ("acc-a", "author-a"),
+        ("acc-b", "author-b"),
+    ] {
+        create_account_full(&accounts, id, username).await;
+    }
+    for (id, owner) in [("s-a", "acc-a"), ("s-b", "acc-b")] {
+        scripts
+            .create(
+                id,
+                &format!("slug-{id}"),
+                Some(owner),
+                "Title",
+                "A description",
+                "Utilities",
+                "bundle-bytes",
+                None,
+                None,
+                None,
+                "1.0.0",
+                &Price::free(),
+                true,
+                None,
+                None,
+                NOW,
+            )
+            .await
+            .unwrap();
+    }
+    activity.backfill().await.unwrap();
+    activity
+        .record(
+            "acc-a",
+            ActivityKind::ReviewPosted,
+            "s-b",
+            None,
+            Some("r-x"),
+            NOW,
+        )
+        .await
+        .unwrap();
+
+    assert!(follows.follow("acc-fan", "acc-a", NOW).await.unwrap());
+    assert!(!follows.follow("acc-fan", "acc-a", NOW).await.unwrap());
+    assert_eq!(follows.counts("acc-a").await.unwrap(), (1, 0));
+    assert_eq!(follows.counts("acc-fan").await.unwrap(), (0, 1));
+
+    let feed = activity.find_followed_by("acc-fan", 10, 0).await.unwrap();
+    assert_eq!(feed.len(), 1, "reviews and unfollowed authors stay out");
+    assert_eq!(feed[0].script_id, "s-a");
+    assert_eq!(feed[0].kind, "script_published");
+
+    assert!(follows.unfollow("acc-fan", "acc-a").await.unwrap());
+    assert!(!follows.unfollow("acc-fan", "acc-a").await.unwrap());
+    assert!(activity
+        .find_followed_by("acc-fan", 10, 0)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+// ===========================================================================
+// Schema indexes
+// ===========================================================================
+
+#[tokio::test]
+async fn schema_has_browse_indexes() {
+    let pool = setup().await;
+    let names: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'index'")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    for required in [
+        "idx_scripts_category_created",
+        "idx_scripts_created_at",
+        "idx_scripts_downloads",
+        "idx_scripts_rating",
+        "idx_scripts_owner_account_id",
+        "idx_reviews_script_id",
+    ] {
+        assert!(
+            names.iter().any(|n| n == required),
+            "missing index {required}"
+        );
+    }
+}
@@ -34,7 +34,7 @@ async fn run_marketplace_search(
     let sort_field = request.sort_by.as_deref().unwrap_or("createdAt");
     let sort_column = match sort_field {
         "createdAt" => "scripts.created_at",
-        "rating" => "scripts.rating",
+        "rating" => "scripts.weighted_rating",
         "downloads" => "scripts.downloads",
         "price" => "scripts.price",
         "title" => "scripts.title",
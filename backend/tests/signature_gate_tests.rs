@@ -9,6 +9,7 @@
 //! - **signed-by-non-owner** (key bound to account B, payload names account A) → 401
 //! - valid owner signature → Ok(resolved account_id)
 //! - replay (same nonce twice) → 401
+//! - rejections of a resolved key are stored against its account
 //!
 //! These are the security-property tests shared by every gated route
 //! (vault / passkey / recovery / review). Per-route HTTP-level coverage lives
@@ -201,6 +202,20 @@ async fn gate_rejects_signed_by_non_owner_with_401() {
     .expect_err("non-owner signature must be rejected");
     assert_eq!(err.status, poem::http::StatusCode::UNAUTHORIZED);
     assert_eq!(err.message, "Invalid signature");
+
+    // The rejection is kept for support, against the key's own account.
+    let failures = repo.recent_failures("acc-attacker-b", 10).await.unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].action, VAULT_CREATE_ACTION);
+    assert_eq!(failures[0].status, 401);
+    assert!(failures[0]
+        .reason
+        .starts_with("signature verification failed"));
+    assert!(repo
+        .recent_failures("acc-victim-a", 10)
+        .await
+        .unwrap()
+        .is_empty());
 }
 
 #[tokio::test]
@@ -294,4 +309,7 @@ async fn gate_rejects_replayed_nonce_with_401() {
         .expect_err("replay must be rejected");
     assert_eq!(err.status, poem::http::StatusCode::UNAUTHORIZED);
     assert_eq!(err.message, "Replay prevention failed");
+    let failures = repo.recent_failures("acc-replay", 10).await.unwrap();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].reason.starts_with("replay prevention failed"));
 }
@@ -226,3 +226,49 @@ fn verify_update_signature_accepts_fixture_payload() {
         "fixture payload signature should verify successfully"
     );
 }
+
+#[test]
+fn verify_update_signature_covers_readme() {
+    let signing_key = SigningKey::from_bytes(&[5u8; 32]);
+
+    let canonical_payload = serde_json::json!({
+        "action": "update",
+        "script_id": "script-123",
+        "timestamp": "2024-01-01T00:00:00Z",
+        "author_principal": "principal-1",
+        "readme": "# Usage\n\nRun it."
+    });
+
+    let canonical_json = create_canonical_payload(&canonical_payload);
+    let (signature_b64, public_key_b64) = sign_test_payload(&signing_key, &canonical_json);
+
+    let mut request_payload = canonical_payload
+        .as_object()
+        .expect("canonical payload must be an object")
+        .clone();
+    request_payload.insert(
+        "author_public_key".to_string(),
+        serde_json::Value::String(public_key_b64),
+    );
+    request_payload.insert(
+        "signature".to_string(),
+        serde_json::Value::String(signature_b64),
+    );
+
+    let request: UpdateScriptRequest =
+        serde_json::from_value(serde_json::Value::Object(request_payload.clone()))
+            .expect("valid update request json");
+    assert!(verify_script_update_signature(&request, "script-123").is_ok());
+
+    request_payload.insert(
+        "readme".to_string(),
+        serde_json::Value::String("# Usage\n\n<script>steal()</script>".to_string()),
+    );
+    let tampered: UpdateScriptRequest =
+        serde_json::from_value(serde_json::Value::Object(request_payload))
+            .expect("valid update request json");
+    assert!(
+        verify_script_update_signature(&tampered, "script-123").is_err(),
+        "a swapped readme must invalidate the signature"
+    );
+}
@@ -0,0 +1,238 @@
+//! Signed GET requests: the `SignedIdentity` / `OptionalSignedIdentity`
+//! extractors verify a header signature over `{METHOD}:{path_and_query}:{ts}`
+//! with REAL Ed25519 keys bound to an account in a REAL in-memory database.
+
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use icp_marketplace_api::db::initialize_database;
+use icp_marketplace_api::middleware::signed_identity::{
+    signed_read_message, PUBLIC_KEY_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use icp_marketplace_api::middleware::{OptionalSignedIdentity, SignedIdentity};
+use icp_marketplace_api::repositories::{AccountRepository, CreateAccountParams};
+use icp_marketplace_api::services::PasskeyService;
+use poem::http::StatusCode;
+use poem::test::TestClient;
+use poem::{get, handler, EndpointExt, Route};
+use rand::rngs::OsRng;
+use sqlx::sqlite::SqlitePoolOptions;
+use std::sync::Arc;
+
+const NOW: &str = "2026-07-14T00:00:00Z";
+
+#[handler]
+fn whoami(identity: SignedIdentity) -> String {
+    identity.account_id
+}
+
+#[handler]
+fn maybe_whoami(identity: OptionalSignedIdentity) -> String {
+    identity.account_id().unwrap_or("anonymous").to_string()
+}
+
+struct Key {
+    signing: SigningKey,
+    public_key_b64: String,
+}
+
+impl Key {
+    fn generate() -> Self {
+        let signing = SigningKey::generate(&mut OsRng);
+        let public_key_b64 =
+            base64::engine::general_purpose::STANDARD.encode(signing.verifying_key().as_bytes());
+        Self {
+            signing,
+            public_key_b64,
+        }
+    }
+
+    fn sign(&self, path_and_query: &str, timestamp: i64) -> String {
+        let message = signed_read_message("GET", path_and_query, timestamp);
+        base64::engine::general_purpose::STANDARD
+            .encode(self.signing.sign(message.as_bytes()).to_bytes())
+    }
+}
+
+async fn setup(key: &Key) -> (impl poem::Endpoint, AccountRepository) {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("pool");
+    initialize_database(&pool).await;
+    let accounts = AccountRepository::new(pool.clone());
+    accounts
+        .create_account(CreateAccountParams {
+            account_id: "acc-alice",
+            username: "alice",
+            display_name: "alice",
+            contact_email: None,
+            contact_telegram: None,
+            contact_discord: None,
+            contact_twitter: None,
+            website_url: None,
+            bio: None,
+            now: NOW,
+        })
+        .await
+        .expect("create_account");
+    let principal =
+        icp_marketplace_api::auth::derive_ic_principal(&key.public_key_b64).expect("derive");
+    accounts
+        .add_public_key(
+            "key-alice",
+            "acc-alice",
+            &key.public_key_b64,
+            &principal,
+            NOW,
+        )
+        .await
+        .expect("add_public_key");
+
+    let passkey_service = PasskeyService::new(pool.clone(), "localhost", "http://localhost:58000")
+        .expect("Failed to create PasskeyService");
+    let state = icp_marketplace_api::test_support::app_state_stub(
+        pool,
+        passkey_service,
+        Arc::new(icp_marketplace_api::rate_limit::SlidingWindowRateLimiter::new(5, 15 * 60)),
+    );
+    let app = Route::new()
+        .at("/private", get(whoami))
+        .at("/maybe", get(maybe_whoami))
+        .at(
+            "/api/v1/webhooks",
+            get(icp_marketplace_api::handlers::webhook_get),
+        )
+        .data(Arc::new(state));
+    (app, accounts)
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+#[tokio::test]
+async fn valid_signature_resolves_the_account() {
+    let key = Key::generate();
+    let (app, _) = setup(&key).await;
+    let client = TestClient::new(app);
+    let ts = now();
+
+    let resp = client
+        .get("/private?limit=5")
+        .header(PUBLIC_KEY_HEADER, &key.public_key_b64)
+        .header(SIGNATURE_HEADER, key.sign("/private?limit=5", ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("acc-alice").await;
+}
+
+#[tokio::test]
+async fn signature_over_another_path_or_query_is_rejected() {
+    let key = Key::generate();
+    let (app, _) = setup(&key).await;
+    let client = TestClient::new(app);
+    let ts = now();
+
+    client
+        .get("/private?limit=500")
+        .header(PUBLIC_KEY_HEADER, &key.public_key_b64)
+        .header(SIGNATURE_HEADER, key.sign("/private?limit=5", ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn stale_timestamp_unknown_and_disabled_keys_are_rejected() {
+    let key = Key::generate();
+    let (app, accounts) = setup(&key).await;
+    let client = TestClient::new(app);
+
+    let stale = now() - 3600;
+    client
+        .get("/private")
+        .header(PUBLIC_KEY_HEADER, &key.public_key_b64)
+        .header(SIGNATURE_HEADER, key.sign("/private", stale))
+        .header(TIMESTAMP_HEADER, stale.to_string())
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let stranger = Key::generate();
+    let ts = now();
+    client
+        .get("/private")
+        .header(PUBLIC_KEY_HEADER, &stranger.public_key_b64)
+        .header(SIGNATURE_HEADER, stranger.sign("/private", ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    accounts
+        .disable_key("key-alice", "key-alice", NOW)
+        .await
+        .expect("disable_key");
+    client
+        .get("/private")
+        .header(PUBLIC_KEY_HEADER, &key.public_key_b64)
+        .header(SIGNATURE_HEADER, key.sign("/private", ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn optional_identity_allows_anonymous_but_not_bad_signatures() {
+    let key = Key::generate();
+    let (app, _) = setup(&key).await;
+    let client = TestClient::new(app);
+
+    client
+        .get("/private")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let resp = client.get("/maybe").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_text("anonymous").await;
+
+    let ts = now();
+    client
+        .get("/maybe")
+        .header(PUBLIC_KEY_HEADER, &key.public_key_b64)
+        .header(SIGNATURE_HEADER, key.sign("/private", ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn webhook_read_is_a_signed_get() {
+    let key = Key::generate();
+    let (app, _) = setup(&key).await;
+    let client = TestClient::new(app);
+
+    client
+        .get("/api/v1/webhooks")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // Signed by the account, which has not registered a webhook yet.
+    let ts = now();
+    client
+        .get("/api/v1/webhooks")
+        .header(PUBLIC_KEY_HEADER, &key.public_key_b64)
+        .header(SIGNATURE_HEADER, key.sign("/api/v1/webhooks", ts))
+        .header(TIMESTAMP_HEADER, ts.to_string())
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,38 @@
+//! Generates `icp_core.h` from the `#[no_mangle]` exports in `src/ffi.rs`.
+//!
+//! The header goes to `OUT_DIR`, so a plain `cargo build` never touches the
+//! source tree. Set `ICP_CORE_HEADER_DIR` to also write it to a directory of
+//! your choice, e.g. `ICP_CORE_HEADER_DIR=include cargo build -p icp_core`
+//! (relative paths are resolved against this crate).
+
+use std::env;
+use std::path::PathBuf;
+
+const HEADER_DIR_ENV: &str = "ICP_CORE_HEADER_DIR";
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=src/ffi");
+    println!("cargo:rerun-if-changed=src/keystore/callback.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed={HEADER_DIR_ENV}");
+
+    // The C ABI (ffi.rs) is native-only.
+    if env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32") {
+        return;
+    }
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("Failed to read cbindgen.toml");
+    let bindings = cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate C header for icp_core FFI");
+    bindings.write_to_file(out_dir.join("icp_core.h"));
+    if let Some(dir) = env::var_os(HEADER_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        bindings.write_to_file(crate_dir.join(dir).join("icp_core.h"));
+    }
+}
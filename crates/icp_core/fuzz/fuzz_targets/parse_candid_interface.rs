@@ -0,0 +1,13 @@
+//! `parse_candid_interface` must reject malformed input with an error, never
+//! panic. Run with `just fuzz-candid`; the interfaces under
+//! `tests/fixtures/candid` seed the corpus.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = icp_core::canister_client::parse_candid_interface(source);
+    }
+});
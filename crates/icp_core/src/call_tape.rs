@@ -0,0 +1,567 @@
+//! Canister call recording and replay.
+//!
+//! While a tape is active in [`TapeMode::Record`], every successful call made
+//! through [`canister_client::call_anonymous`] and
+//! [`canister_client::call_authenticated`] is appended to a JSON Lines file.
+//! In [`TapeMode::Replay`] the same calls are answered from that file without
+//! touching the network, so scripts can be developed offline.
+//!
+//! [`replay_app`] drives a script's `init`/`update` loop from a tape: every
+//! `icp_call` / `icp_batch` effect is answered with the `effect/result`
+//! message the app host (`script_app_host.dart`) would deliver, which makes a
+//! script run deterministic enough to pin in a regression test.
+//!
+//! Calls are matched on canister, method, kind and arguments (normalized, so
+//! `(1:nat)` and `( 1 : nat )` match). A call recorded several times is
+//! replayed in recording order, repeating the last response once exhausted.
+//!
+//! Tapes never hold key material: the private key of an authenticated call
+//! is not part of the record, and any `signature`, `private_key` or
+//! `sender_*` member of the arguments or the response is replaced by
+//! [`SCRUBBED`] before it is written.
+
+use crate::candid_value;
+use crate::canister_client::{self, CanisterClientError, MethodKind};
+use crate::js_engine::{js_app_init, js_app_update, js_app_view};
+use crate::logging::{self, LogCategory, LogLevel};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use thiserror::Error;
+
+/// Stand-in for scrubbed secrets.
+pub const SCRUBBED: &str = "<scrubbed>";
+
+/// Upper bound on `update` calls in one [`replay_app`] run, so a script that
+/// keeps emitting effects cannot loop forever.
+pub const MAX_REPLAY_STEPS: usize = 64;
+
+#[derive(Debug, Error)]
+pub enum CallTapeError {
+    #[error("call tape i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid call tape line {line}: {reason}")]
+    Format { line: usize, reason: String },
+    #[error("script error: {0}")]
+    Script(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TapeMode {
+    Record,
+    Replay,
+}
+
+/// One recorded request/response pair, one line of a tape file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub canister_id: String,
+    pub method: String,
+    pub kind: MethodKind,
+    /// Arguments as normalized by [`normalize_args`].
+    pub args: String,
+    pub authenticated: bool,
+    /// What the call returned, e.g. `{"ok":true,"result":…}`.
+    pub response: Value,
+}
+
+impl RecordedCall {
+    pub fn new(
+        canister_id: &str,
+        method: &str,
+        kind: MethodKind,
+        args: &str,
+        authenticated: bool,
+        response: &str,
+    ) -> Self {
+        let mut response =
+            serde_json::from_str(response).unwrap_or_else(|_| Value::String(response.to_string()));
+        scrub(&mut response);
+        Self {
+            canister_id: canister_id.to_string(),
+            method: method.to_string(),
+            kind,
+            args: normalize_args(args),
+            authenticated,
+            response,
+        }
+    }
+
+    fn key(&self) -> String {
+        call_key(&self.canister_id, &self.method, self.kind, &self.args)
+    }
+}
+
+fn call_key(canister_id: &str, method: &str, kind: MethodKind, normalized_args: &str) -> String {
+    format!("{canister_id}\u{1f}{method}\u{1f}{kind:?}\u{1f}{normalized_args}")
+}
+
+/// Canonical form of call arguments: Candid text is reprinted, JSON is
+/// scrubbed and re-serialized with sorted keys, anything else (`base64:`) is
+/// kept as is. Arguments are classified the way the canister client does.
+pub fn normalize_args(args: &str) -> String {
+    let args = args.trim();
+    if canister_client::looks_like_textual_idl(args) {
+        return match candid_value::parse_args(args) {
+            Ok(parsed) => candid_value::format_args(&parsed),
+            Err(_) => args.to_string(),
+        };
+    }
+    match serde_json::from_str::<Value>(args) {
+        Ok(mut json) => {
+            scrub(&mut json);
+            json.to_string()
+        }
+        Err(_) => args.to_string(),
+    }
+}
+
+fn is_secret_member(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.contains("signature")
+        || key.contains("private_key")
+        || key.contains("privatekey")
+        || key.starts_with("sender_")
+}
+
+/// Replaces every secret-looking member of `value`, at any depth, with
+/// [`SCRUBBED`].
+pub fn scrub(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, member) in map.iter_mut() {
+                if is_secret_member(key) {
+                    *member = Value::String(SCRUBBED.to_string());
+                } else {
+                    scrub(member);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(scrub),
+        _ => {}
+    }
+}
+
+/// Recorded calls plus the replay position of each distinct call.
+#[derive(Debug, Default)]
+pub struct CallTape {
+    calls: Vec<RecordedCall>,
+    cursors: HashMap<String, usize>,
+}
+
+impl CallTape {
+    pub fn new(calls: Vec<RecordedCall>) -> Self {
+        Self {
+            calls,
+            cursors: HashMap::new(),
+        }
+    }
+
+    /// Reads a tape file; blank lines are skipped.
+    pub fn load(path: &Path) -> Result<Self, CallTapeError> {
+        let text = std::fs::read_to_string(path)?;
+        let mut calls = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let call = serde_json::from_str(line).map_err(|e| CallTapeError::Format {
+                line: i + 1,
+                reason: e.to_string(),
+            })?;
+            calls.push(call);
+        }
+        Ok(Self::new(calls))
+    }
+
+    /// Appends `call` to the tape file at `path`, creating it if needed.
+    pub fn append_to(path: &Path, call: &RecordedCall) -> Result<(), CallTapeError> {
+        let line = serde_json::to_string(call).map_err(std::io::Error::other)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    pub fn calls(&self) -> &[RecordedCall] {
+        &self.calls
+    }
+
+    pub fn push(&mut self, call: RecordedCall) {
+        self.calls.push(call);
+    }
+
+    /// The next recorded response to this call, if it was recorded at all.
+    pub fn replay(
+        &mut self,
+        canister_id: &str,
+        method: &str,
+        kind: MethodKind,
+        args: &str,
+    ) -> Option<&Value> {
+        let key = call_key(canister_id, method, kind, &normalize_args(args));
+        let matches: Vec<usize> = self
+            .calls
+            .iter()
+            .enumerate()
+            .filter(|(_, call)| call.key() == key)
+            .map(|(i, _)| i)
+            .collect();
+        let last = *matches.last()?;
+        let cursor = self.cursors.entry(key).or_insert(0);
+        let index = matches.get(*cursor).copied().unwrap_or(last);
+        *cursor += 1;
+        Some(&self.calls[index].response)
+    }
+
+    /// The `effect/result` messages the app host would deliver for
+    /// `effects` (the `effects` array of an `init`/`update` result), with
+    /// responses taken from the tape.
+    pub fn effect_results(&mut self, effects: &Value) -> Vec<Value> {
+        let Some(effects) = effects.as_array() else {
+            return Vec::new();
+        };
+        effects
+            .iter()
+            .map(|effect| self.effect_result(effect))
+            .collect()
+    }
+
+    fn effect_result(&mut self, effect: &Value) -> Value {
+        let Some(effect) = effect.as_object() else {
+            return json!({"type": "effect/result", "id": "invalid", "ok": false, "error": "invalid effect"});
+        };
+        let kind = effect
+            .get("kind")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .trim();
+        let id = effect.get("id").and_then(Value::as_str).unwrap_or(kind);
+        match kind {
+            "icp_call" => match self.replay_effect_call(effect) {
+                Ok(data) => json!({"type": "effect/result", "id": id, "ok": true, "data": data}),
+                Err(error) => {
+                    json!({"type": "effect/result", "id": id, "ok": false, "error": error})
+                }
+            },
+            "icp_batch" => {
+                let mut outputs = serde_json::Map::new();
+                let items = effect.get("items").and_then(Value::as_array);
+                for item in items.into_iter().flatten() {
+                    let Some(item) = item.as_object() else {
+                        continue;
+                    };
+                    let method = item.get("method").and_then(Value::as_str).unwrap_or("");
+                    let label = item
+                        .get("label")
+                        .and_then(Value::as_str)
+                        .unwrap_or(method)
+                        .trim();
+                    let output = self
+                        .replay_effect_call(item)
+                        .unwrap_or_else(|error| json!({"ok": false, "error": error}));
+                    let key = if label.is_empty() {
+                        method.trim()
+                    } else {
+                        label
+                    };
+                    outputs.insert(key.to_string(), output);
+                }
+                json!({"type": "effect/result", "id": id, "ok": true, "data": outputs})
+            }
+            _ => {
+                json!({"type": "effect/result", "id": id, "ok": false, "error": "unsupported effect"})
+            }
+        }
+    }
+
+    fn replay_effect_call(
+        &mut self,
+        call: &serde_json::Map<String, Value>,
+    ) -> Result<Value, String> {
+        let member = |key: &str| call.get(key).and_then(Value::as_str).unwrap_or("").trim();
+        let kind = match call.get("mode").and_then(Value::as_i64) {
+            Some(2) => MethodKind::CompositeQuery,
+            Some(1) => MethodKind::Update,
+            _ => MethodKind::Query,
+        };
+        let args = call.get("args").and_then(Value::as_str).unwrap_or("()");
+        let (canister_id, method) = (member("canister_id"), member("method"));
+        self.replay(canister_id, method, kind, args)
+            .cloned()
+            .ok_or_else(|| format!("no recorded response for {canister_id}.{method}"))
+    }
+}
+
+struct ActiveTape {
+    mode: TapeMode,
+    path: PathBuf,
+    tape: CallTape,
+}
+
+static ACTIVE: Mutex<Option<ActiveTape>> = Mutex::new(None);
+
+fn active() -> std::sync::MutexGuard<'static, Option<ActiveTape>> {
+    ACTIVE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Routes canister calls through the tape at `path` until [`stop`].
+/// Recording appends to an existing file. Returns the number of calls
+/// already on the tape.
+pub fn start(mode: TapeMode, path: &Path) -> Result<usize, CallTapeError> {
+    let tape = match mode {
+        TapeMode::Replay => CallTape::load(path)?,
+        TapeMode::Record if path.exists() => CallTape::load(path)?,
+        TapeMode::Record => CallTape::default(),
+    };
+    let count = tape.calls().len();
+    *active() = Some(ActiveTape {
+        mode,
+        path: path.to_path_buf(),
+        tape,
+    });
+    Ok(count)
+}
+
+/// Detaches the active tape, if any, and returns its mode.
+pub fn stop() -> Option<TapeMode> {
+    active().take().map(|t| t.mode)
+}
+
+/// Runs `live` (the real call) unless a replay tape answers it, and records
+/// its result when recording.
+pub(crate) fn through_tape(
+    canister_id: &str,
+    method: &str,
+    kind: MethodKind,
+    args: &str,
+    authenticated: bool,
+    live: impl FnOnce() -> Result<String, CanisterClientError>,
+) -> Result<String, CanisterClientError> {
+    if let Some(active) = active().as_mut() {
+        if active.mode == TapeMode::Replay {
+            return active
+                .tape
+                .replay(canister_id, method, kind, args)
+                .map(Value::to_string)
+                .ok_or_else(|| {
+                    CanisterClientError::Net(format!(
+                        "replay: no recorded response for {canister_id}.{method}"
+                    ))
+                });
+        }
+    }
+    // The lock is not held across the network call.
+    let out = live()?;
+    if let Some(active) = active().as_mut() {
+        if active.mode == TapeMode::Record {
+            let call = RecordedCall::new(canister_id, method, kind, args, authenticated, &out);
+            if let Err(e) = CallTape::append_to(&active.path, &call) {
+                logging::log(
+                    LogLevel::Warn,
+                    LogCategory::Canister,
+                    &format!("failed to record {canister_id}.{method} to the call tape: {e}"),
+                );
+            }
+            active.tape.push(call);
+        }
+    }
+    Ok(out)
+}
+
+/// The outcome of [`replay_app`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayRun {
+    pub state: Value,
+    pub ui: Value,
+    /// Every message delivered to `update`, in order.
+    pub messages: Vec<Value>,
+}
+
+fn script_step(out: &str) -> Result<Value, CallTapeError> {
+    let value: Value =
+        serde_json::from_str(out).map_err(|e| CallTapeError::Script(e.to_string()))?;
+    if value["ok"].as_bool() != Some(true) {
+        return Err(CallTapeError::Script(
+            value["error"]
+                .as_str()
+                .unwrap_or("script step failed")
+                .to_string(),
+        ));
+    }
+    Ok(value)
+}
+
+/// Runs `script` from `init` until it stops emitting effects, answering
+/// every effect from `tape`, and renders the final state.
+pub fn replay_app(
+    script: &str,
+    json_arg: Option<&str>,
+    tape: &mut CallTape,
+    budget_ms: u64,
+) -> Result<ReplayRun, CallTapeError> {
+    let init = script_step(&js_app_init(script, json_arg, budget_ms))?;
+    let mut state = init["state"].clone();
+    let mut queue: VecDeque<Value> = tape.effect_results(&init["effects"]).into();
+    let mut messages = Vec::new();
+    while let Some(msg) = queue.pop_front() {
+        if messages.len() == MAX_REPLAY_STEPS {
+            return Err(CallTapeError::Script(format!(
+                "replay did not settle within {MAX_REPLAY_STEPS} updates"
+            )));
+        }
+        let step = script_step(&js_app_update(
+            script,
+            &msg.to_string(),
+            &state.to_string(),
+            budget_ms,
+        ))?;
+        state = step["state"].clone();
+        queue.extend(tape.effect_results(&step["effects"]));
+        messages.push(msg);
+    }
+    let view = script_step(&js_app_view(script, &state.to_string(), budget_ms))?;
+    Ok(ReplayRun {
+        state,
+        ui: view["ui"].clone(),
+        messages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEDGER: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+
+    fn balance_call(response: &str) -> RecordedCall {
+        RecordedCall::new(
+            LEDGER,
+            "account_balance",
+            MethodKind::Query,
+            r#"(record { account = "abc" })"#,
+            false,
+            response,
+        )
+    }
+
+    #[test]
+    fn secrets_are_scrubbed_from_args_and_responses() {
+        let call = RecordedCall::new(
+            LEDGER,
+            "transfer",
+            MethodKind::Update,
+            r#"{"to":"abc","signature":"c2ln","auth":{"sender_pubkey":"cGs="}}"#,
+            true,
+            r#"{"ok":true,"result":{"height":"7","certificate_signature":"AAAA"}}"#,
+        );
+        assert_eq!(
+            call.args,
+            r#"{"auth":{"sender_pubkey":"<scrubbed>"},"signature":"<scrubbed>","to":"abc"}"#
+        );
+        assert_eq!(call.response["result"]["certificate_signature"], SCRUBBED);
+        assert_eq!(call.response["result"]["height"], "7");
+    }
+
+    #[test]
+    fn replay_matches_normalized_args_in_recording_order() {
+        let mut tape = CallTape::new(vec![
+            balance_call(r#"{"ok":true,"result":1}"#),
+            balance_call(r#"{"ok":true,"result":2}"#),
+        ]);
+        let args = r#"( record {account="abc"} )"#;
+        let kind = MethodKind::Query;
+        assert_eq!(
+            tape.replay(LEDGER, "account_balance", kind, args).unwrap()["result"],
+            1
+        );
+        assert_eq!(
+            tape.replay(LEDGER, "account_balance", kind, args).unwrap()["result"],
+            2
+        );
+        assert_eq!(
+            tape.replay(LEDGER, "account_balance", kind, args).unwrap()["result"],
+            2
+        );
+        assert!(tape
+            .replay(LEDGER, "account_balance", MethodKind::Update, args)
+            .is_none());
+    }
+
+    #[test]
+    fn tape_files_round_trip_and_active_tape_records_then_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calls.jsonl");
+
+        assert_eq!(start(TapeMode::Record, &path).unwrap(), 0);
+        let out = through_tape(LEDGER, "symbol", MethodKind::Query, "()", false, || {
+            Ok(r#"{"ok":true,"result":"ICP"}"#.to_string())
+        })
+        .unwrap();
+        assert_eq!(out, r#"{"ok":true,"result":"ICP"}"#);
+        assert_eq!(stop(), Some(TapeMode::Record));
+
+        let tape = CallTape::load(&path).unwrap();
+        assert_eq!(tape.calls().len(), 1);
+        assert_eq!(tape.calls()[0].method, "symbol");
+
+        assert_eq!(start(TapeMode::Replay, &path).unwrap(), 1);
+        let replayed = through_tape(LEDGER, "symbol", MethodKind::Query, "", false, || {
+            panic!("replay must not reach the network")
+        })
+        .unwrap();
+        assert_eq!(replayed, r#"{"ok":true,"result":"ICP"}"#);
+        let missing = through_tape(LEDGER, "decimals", MethodKind::Query, "()", false, || {
+            panic!("replay must not reach the network")
+        });
+        assert!(matches!(missing, Err(CanisterClientError::Net(_))));
+        assert_eq!(stop(), Some(TapeMode::Replay));
+    }
+
+    #[test]
+    fn replay_app_feeds_recorded_results_into_the_script() {
+        let script = r#"
+            function init(arg) {
+                return { state: { balance: null, symbol: null }, effects: [
+                    { kind: "icp_call", id: "balance", mode: 0, canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai",
+                      method: "account_balance", args: '(record { account = "abc" })' },
+                    { kind: "icp_batch", id: "meta", items: [
+                        { label: "symbol", mode: 0, canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai", method: "symbol", args: "()" },
+                        { label: "fee", mode: 0, canister_id: "ryjl3-tyaaa-aaaaa-aaaba-cai", method: "fee", args: "()" }
+                    ] }
+                ] };
+            }
+            function view(state) { return { type: "text", props: { text: String(state.balance) } }; }
+            function update(msg, state) {
+                if (msg.type === "effect/result" && msg.id === "balance") { state.balance = msg.data.result; }
+                if (msg.type === "effect/result" && msg.id === "meta") {
+                    state.symbol = msg.data.symbol.result;
+                    state.fee = msg.data.fee;
+                }
+                return { state: state, effects: [] };
+            }
+        "#;
+        let mut tape = CallTape::new(vec![
+            balance_call(r#"{"ok":true,"result":42}"#),
+            RecordedCall::new(
+                LEDGER,
+                "symbol",
+                MethodKind::Query,
+                "()",
+                false,
+                r#"{"ok":true,"result":"ICP"}"#,
+            ),
+        ]);
+        let run = replay_app(script, None, &mut tape, 500).unwrap();
+        assert_eq!(run.messages.len(), 2);
+        assert_eq!(run.state["balance"], 42);
+        assert_eq!(run.state["symbol"], "ICP");
+        assert_eq!(run.state["fee"]["ok"], false);
+        assert_eq!(run.ui["props"]["text"], "42");
+    }
+}
@@ -0,0 +1,87 @@
+//! Cooperative cancellation.
+//!
+//! A [`CancelToken`] is a shared flag. Long-running work does not take one as
+//! a parameter; it reads the token installed for the current thread with
+//! [`current`], so the existing synchronous entry points become cancellable
+//! without changing their signatures. The FFI async jobs run each job under
+//! its own token and `icp_async_cancel` trips it; then:
+//!
+//! - the script engine's interrupt handler stops the running script at its
+//!   next check, as it does when the time budget runs out;
+//! - a blocked canister call drops its in-flight agent request and returns
+//!   `CanisterClientError::Cancelled`;
+//! - static validation stops after the current stage.
+//!
+//! Work started outside a job sees a token that is never cancelled.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation. Idempotent; work notices at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// The token of the calling thread, or a fresh never-cancelled one outside
+/// [`with_token`].
+pub fn current() -> CancelToken {
+    CURRENT.with(|c| c.borrow().clone()).unwrap_or_default()
+}
+
+/// Runs `f` with `token` as the calling thread's token, restoring the
+/// previous one afterwards (also on panic).
+pub fn with_token<R>(token: &CancelToken, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<CancelToken>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(CURRENT.with(|c| c.borrow_mut().replace(token.clone())));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_the_flag() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[test]
+    fn current_token_is_scoped_to_with_token() {
+        assert!(!current().is_cancelled());
+        let outer = CancelToken::new();
+        outer.cancel();
+        with_token(&outer, || {
+            assert!(current().is_cancelled());
+            with_token(&CancelToken::new(), || assert!(!current().is_cancelled()));
+            assert!(current().is_cancelled());
+        });
+        assert!(!current().is_cancelled());
+    }
+}
@@ -0,0 +1,327 @@
+//! Candid textual values, as `dfx canister call` takes them.
+//!
+//! Users paste arguments like `(record { amount = 1_000 : nat }, "memo")`
+//! into the app's call forms and scripts. [`parse_args`] turns that text into
+//! [`IDLArgs`]; [`args_to_json`] / [`args_from_json`] map them to a tagged,
+//! lossless JSON form the editors can walk and modify; [`format_args`]
+//! prints them back as Candid text.
+//!
+//! Unlike the display JSON of canister call results, every value carries its
+//! Candid type, so `JSON -> text -> JSON` is an identity:
+//!
+//! ```json
+//! {"type":"record","fields":[{"name":"amount","value":{"type":"nat","value":"1000"}}]}
+//! ```
+//!
+//! | Candid | JSON |
+//! |---|---|
+//! | `null`, `reserved` | `{"type":"null"}`, `{"type":"reserved"}` |
+//! | `bool` | `{"type":"bool","value":true}` |
+//! | `text` | `{"type":"text","value":"…"}` |
+//! | `nat`, `int`, `nat64`, `int64` | `{"type":"nat","value":"1000"}` (string: no precision loss) |
+//! | unannotated number (`42`) | `{"type":"number","value":"42"}` |
+//! | `nat8`..`nat32`, `int8`..`int32`, `float32`, `float64` | `{"type":"nat8","value":7}` |
+//! | `principal`, `service` | `{"type":"principal","value":"aaaaa-aa"}` |
+//! | `func` | `{"type":"func","principal":"…","method":"…"}` |
+//! | `blob` | `{"type":"blob","value":"<hex>"}` |
+//! | `opt` | `{"type":"opt","value":…}`, no `value` for an absent option |
+//! | `vec` | `{"type":"vec","items":[…]}` |
+//! | `record` | `{"type":"record","fields":[{"name":"a","value":…}]}` |
+//! | `variant` | `{"type":"variant","name":"ok","index":0,"value":…}` |
+//!
+//! Field and case labels are `"name"` for named labels and `"id"` for
+//! numeric ones (`record { 1 = … }`); tuple fields (`record { …; … }`) have
+//! neither.
+
+use candid::types::value::{IDLField, IDLValue, VariantValue};
+use candid::types::Label;
+use candid::{IDLArgs, Int as CandidInt, Nat as CandidNat, Principal};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CandidValueError {
+    #[error("candid parse error: {0}")]
+    Parse(String),
+    #[error("invalid candid value at {path}: {reason}")]
+    Invalid { path: String, reason: String },
+}
+
+fn invalid(path: &str, reason: impl Into<String>) -> CandidValueError {
+    CandidValueError::Invalid {
+        path: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Parses Candid textual arguments. A blank string is the empty argument
+/// list; a single value may omit the surrounding parentheses
+/// (`record { a = 1 }` is `(record { a = 1 })`).
+pub fn parse_args(text: &str) -> Result<IDLArgs, CandidValueError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(IDLArgs::new(&[]));
+    }
+    match candid_parser::parse_idl_args(text) {
+        Ok(args) => Ok(args),
+        Err(e) if !text.starts_with('(') => candid_parser::parse_idl_args(&format!("({text})"))
+            .map_err(|_| CandidValueError::Parse(e.to_string())),
+        Err(e) => Err(CandidValueError::Parse(e.to_string())),
+    }
+}
+
+/// Candid text of `args`, e.g. `(record { amount = 1_000 : nat })`.
+pub fn format_args(args: &IDLArgs) -> String {
+    args.to_string()
+}
+
+/// The tagged JSON of every argument, in order.
+pub fn args_to_json(args: &IDLArgs) -> Value {
+    Value::Array(args.args.iter().map(value_to_json).collect())
+}
+
+/// Inverse of [`args_to_json`].
+pub fn args_from_json(json: &Value) -> Result<IDLArgs, CandidValueError> {
+    let items = json
+        .as_array()
+        .ok_or_else(|| invalid("args", "expected an array of arguments"))?;
+    let values = items
+        .iter()
+        .enumerate()
+        .map(|(i, v)| value_from_json(v, &format!("args[{i}]")))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(IDLArgs::new(&values))
+}
+
+fn tagged(ty: &str, value: Value) -> Value {
+    json!({ "type": ty, "value": value })
+}
+
+fn insert_label(map: &mut Map<String, Value>, label: &Label) {
+    match label {
+        Label::Named(name) => {
+            map.insert("name".into(), Value::String(name.to_string()));
+        }
+        Label::Id(id) => {
+            map.insert("id".into(), json!(id));
+        }
+        Label::Unnamed(_) => {}
+    }
+}
+
+pub fn value_to_json(value: &IDLValue) -> Value {
+    match value {
+        IDLValue::Null => json!({ "type": "null" }),
+        IDLValue::Reserved => json!({ "type": "reserved" }),
+        IDLValue::None => json!({ "type": "opt" }),
+        IDLValue::Bool(b) => tagged("bool", json!(b)),
+        IDLValue::Text(s) => tagged("text", json!(s)),
+        IDLValue::Number(s) => tagged("number", json!(s)),
+        IDLValue::Nat(n) => tagged("nat", json!(n.0.to_string())),
+        IDLValue::Int(i) => tagged("int", json!(i.0.to_string())),
+        IDLValue::Nat8(v) => tagged("nat8", json!(v)),
+        IDLValue::Nat16(v) => tagged("nat16", json!(v)),
+        IDLValue::Nat32(v) => tagged("nat32", json!(v)),
+        IDLValue::Nat64(v) => tagged("nat64", json!(v.to_string())),
+        IDLValue::Int8(v) => tagged("int8", json!(v)),
+        IDLValue::Int16(v) => tagged("int16", json!(v)),
+        IDLValue::Int32(v) => tagged("int32", json!(v)),
+        IDLValue::Int64(v) => tagged("int64", json!(v.to_string())),
+        IDLValue::Float32(v) => tagged("float32", json!(v)),
+        IDLValue::Float64(v) => tagged("float64", json!(v)),
+        IDLValue::Principal(p) => tagged("principal", json!(p.to_text())),
+        IDLValue::Service(p) => tagged("service", json!(p.to_text())),
+        IDLValue::Func(p, method) => {
+            json!({ "type": "func", "principal": p.to_text(), "method": method })
+        }
+        IDLValue::Blob(bytes) => tagged("blob", json!(hex::encode(bytes))),
+        IDLValue::Opt(inner) => tagged("opt", value_to_json(inner)),
+        IDLValue::Vec(items) => {
+            json!({ "type": "vec", "items": items.iter().map(value_to_json).collect::<Vec<_>>() })
+        }
+        IDLValue::Record(fields) => {
+            let fields: Vec<Value> = fields
+                .iter()
+                .map(|IDLField { id, val }| {
+                    let mut field = Map::new();
+                    insert_label(&mut field, id);
+                    field.insert("value".into(), value_to_json(val));
+                    Value::Object(field)
+                })
+                .collect();
+            json!({ "type": "record", "fields": fields })
+        }
+        IDLValue::Variant(VariantValue(field, index)) => {
+            let mut variant = Map::new();
+            variant.insert("type".into(), json!("variant"));
+            insert_label(&mut variant, &field.id);
+            variant.insert("index".into(), json!(index));
+            variant.insert("value".into(), value_to_json(&field.val));
+            Value::Object(variant)
+        }
+    }
+}
+
+fn label_from_json(
+    obj: &Map<String, Value>,
+    position: u32,
+    path: &str,
+) -> Result<Label, CandidValueError> {
+    match (obj.get("name"), obj.get("id")) {
+        (Some(Value::String(name)), None) => Ok(Label::Named(name.clone())),
+        (None, Some(id)) => id
+            .as_u64()
+            .and_then(|id| u32::try_from(id).ok())
+            .map(Label::Id)
+            .ok_or_else(|| invalid(path, "label id must be a 32-bit unsigned integer")),
+        (None, None) => Ok(Label::Unnamed(position)),
+        _ => Err(invalid(
+            path,
+            "expected either a string `name` or a numeric `id`",
+        )),
+    }
+}
+
+fn str_member<'a>(
+    obj: &'a Map<String, Value>,
+    key: &str,
+    path: &str,
+) -> Result<&'a str, CandidValueError> {
+    obj.get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid(path, format!("expected a string `{key}`")))
+}
+
+fn value_member<'a>(
+    obj: &'a Map<String, Value>,
+    path: &str,
+) -> Result<&'a Value, CandidValueError> {
+    obj.get("value")
+        .ok_or_else(|| invalid(path, "missing `value`"))
+}
+
+/// A JSON number, or a numeric string, as `T`.
+fn number_member<T: std::str::FromStr>(
+    obj: &Map<String, Value>,
+    ty: &str,
+    path: &str,
+) -> Result<T, CandidValueError> {
+    let raw = match value_member(obj, path)? {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.replace('_', ""),
+        _ => {
+            return Err(invalid(
+                path,
+                format!("expected a {ty} number or numeric string"),
+            ))
+        }
+    };
+    raw.parse::<T>()
+        .map_err(|_| invalid(path, format!("`{raw}` is not a valid {ty}")))
+}
+
+fn principal_member(
+    obj: &Map<String, Value>,
+    key: &str,
+    path: &str,
+) -> Result<Principal, CandidValueError> {
+    let text = str_member(obj, key, path)?;
+    Principal::from_text(text).map_err(|e| invalid(path, format!("invalid principal: {e}")))
+}
+
+/// Inverse of [`value_to_json`]; `path` locates the value in error messages.
+pub fn value_from_json(json: &Value, path: &str) -> Result<IDLValue, CandidValueError> {
+    let obj = json
+        .as_object()
+        .ok_or_else(|| invalid(path, "expected an object with a `type`"))?;
+    let ty = str_member(obj, "type", path)?;
+    Ok(match ty {
+        "null" => IDLValue::Null,
+        "reserved" => IDLValue::Reserved,
+        "bool" => IDLValue::Bool(
+            value_member(obj, path)?
+                .as_bool()
+                .ok_or_else(|| invalid(path, "expected a boolean `value`"))?,
+        ),
+        "text" => IDLValue::Text(str_member(obj, "value", path)?.to_string()),
+        "number" => {
+            let raw = str_member(obj, "value", path)?;
+            // Unannotated numbers are kept as written; check it parses as one.
+            raw.replace('_', "")
+                .parse::<CandidInt>()
+                .map_err(|_| invalid(path, format!("`{raw}` is not a number")))?;
+            IDLValue::Number(raw.to_string())
+        }
+        "nat" => IDLValue::Nat(number_member::<CandidNat>(obj, ty, path)?),
+        "int" => IDLValue::Int(number_member::<CandidInt>(obj, ty, path)?),
+        "nat8" => IDLValue::Nat8(number_member(obj, ty, path)?),
+        "nat16" => IDLValue::Nat16(number_member(obj, ty, path)?),
+        "nat32" => IDLValue::Nat32(number_member(obj, ty, path)?),
+        "nat64" => IDLValue::Nat64(number_member(obj, ty, path)?),
+        "int8" => IDLValue::Int8(number_member(obj, ty, path)?),
+        "int16" => IDLValue::Int16(number_member(obj, ty, path)?),
+        "int32" => IDLValue::Int32(number_member(obj, ty, path)?),
+        "int64" => IDLValue::Int64(number_member(obj, ty, path)?),
+        "float32" => IDLValue::Float32(number_member(obj, ty, path)?),
+        "float64" => IDLValue::Float64(number_member(obj, ty, path)?),
+        "principal" => IDLValue::Principal(principal_member(obj, "value", path)?),
+        "service" => IDLValue::Service(principal_member(obj, "value", path)?),
+        "func" => IDLValue::Func(
+            principal_member(obj, "principal", path)?,
+            str_member(obj, "method", path)?.to_string(),
+        ),
+        "blob" => IDLValue::Blob(
+            hex::decode(str_member(obj, "value", path)?)
+                .map_err(|e| invalid(path, format!("invalid blob hex: {e}")))?,
+        ),
+        "opt" => match obj.get("value") {
+            None | Some(Value::Null) => IDLValue::None,
+            Some(inner) => IDLValue::Opt(Box::new(value_from_json(inner, &format!("{path}?"))?)),
+        },
+        "vec" => {
+            let items = obj
+                .get("items")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid(path, "expected an `items` array"))?;
+            IDLValue::Vec(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| value_from_json(item, &format!("{path}[{i}]")))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        "record" => {
+            let fields = obj
+                .get("fields")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid(path, "expected a `fields` array"))?;
+            let mut out = Vec::with_capacity(fields.len());
+            for (i, field) in fields.iter().enumerate() {
+                let field_path = format!("{path}.fields[{i}]");
+                let field = field
+                    .as_object()
+                    .ok_or_else(|| invalid(&field_path, "expected a field object"))?;
+                let id = label_from_json(field, i as u32, &field_path)?;
+                let val = value_from_json(value_member(field, &field_path)?, &field_path)?;
+                out.push(IDLField { id, val });
+            }
+            IDLValue::Record(out)
+        }
+        "variant" => {
+            let id = label_from_json(obj, 0, path)?;
+            let index = match obj.get("index") {
+                None => 0,
+                Some(index) => index
+                    .as_u64()
+                    .ok_or_else(|| invalid(path, "variant `index` must be an unsigned integer"))?,
+            };
+            let val = match obj.get("value") {
+                None => IDLValue::Null,
+                Some(inner) => value_from_json(inner, &format!("{path}.value"))?,
+            };
+            IDLValue::Variant(VariantValue(Box::new(IDLField { id, val }), index))
+        }
+        other => return Err(invalid(path, format!("unknown candid type `{other}`"))),
+    })
+}
@@ -1,3 +1,6 @@
+use crate::call_tape;
+use crate::logging::{self, LogCategory, LogLevel};
+use crate::transfer_policy;
 use base64::Engine as _;
 use candid::types::value::{IDLField, IDLValue, VariantValue};
 use candid::types::Label;
@@ -28,7 +31,7 @@ pub const DEFAULT_IC_GATEWAY: &str = "https://ic0.app";
 /// so tests can inject a short bound without caching/teardown headaches). The
 /// 30s default mirrors the convention in
 /// `apps/autorun_flutter/lib/services/candid_service.dart`.
-fn canister_call_timeout() -> Duration {
+pub(crate) fn canister_call_timeout() -> Duration {
     const DEFAULT: Duration = Duration::from_secs(30);
     std::env::var("ICPCC_CANISTER_TIMEOUT_SECS")
         .ok()
@@ -58,6 +61,41 @@ fn shared_runtime() -> &'static tokio::runtime::Runtime {
     })
 }
 
+/// How often a blocked canister call checks its cancel token.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Why [`block_on_cancellable`] gave up on its future.
+pub(crate) enum Interrupted {
+    TimedOut,
+    Cancelled,
+}
+
+/// Drives `fut` on the shared runtime until it completes, `to` elapses, or
+/// the calling thread's cancel token (see [`crate::cancellation`]) is
+/// cancelled. Giving up drops `fut`, which aborts the in-flight agent request.
+pub(crate) fn block_on_cancellable<F: std::future::Future>(
+    fut: F,
+    to: Duration,
+) -> Result<F::Output, Interrupted> {
+    let token = crate::cancellation::current();
+    shared_runtime().block_on(async {
+        let mut fut = std::pin::pin!(fut);
+        let deadline = tokio::time::Instant::now() + to;
+        loop {
+            if token.is_cancelled() {
+                return Err(Interrupted::Cancelled);
+            }
+            let left = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if left.is_zero() {
+                return Err(Interrupted::TimedOut);
+            }
+            if let Ok(out) = timeout(left.min(CANCEL_POLL_INTERVAL), &mut fut).await {
+                return Ok(out);
+            }
+        }
+    })
+}
+
 #[derive(Debug, Error)]
 pub enum CanisterClientError {
     #[error("invalid canister id: {0}")]
@@ -66,6 +104,13 @@ pub enum CanisterClientError {
     CandidParse(String),
     #[error("network error: {0}")]
     Net(String),
+    /// The caller cancelled the call (`icp_async_cancel`) before it finished.
+    #[error("cancelled")]
+    Cancelled,
+    /// A script's transfer was above its auto-approval limits and was not
+    /// confirmed (see [`crate::transfer_policy`]).
+    #[error("transfer rejected: {0}")]
+    TransferRejected(String),
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -536,7 +581,7 @@ fn build_args_from_json(
 /// enumerate every JSON-leading char (`[`/`{`/`n`/`"`/`t`/`f`/digit/`-`).
 ///
 /// AUD-10: derive truth from structure, not a heuristic prefix list.
-fn looks_like_textual_idl(arg: &str) -> bool {
+pub(crate) fn looks_like_textual_idl(arg: &str) -> bool {
     let t = arg.trim_start();
     t.is_empty() || t.starts_with('(') || t.starts_with("base64:")
 }
@@ -589,13 +634,13 @@ pub fn fetch_candid(canister_id: &str, host: Option<&str>) -> Result<String, Can
             .await
     };
     let to = canister_call_timeout();
-    let rt = shared_runtime();
-    let bytes = match rt.block_on(async { timeout(to, fut).await }) {
+    let bytes = match block_on_cancellable(fut, to) {
         Ok(Ok(b)) => b,
         Ok(Err(e)) => {
             return Err(CanisterClientError::Net(format!("read_state: {e}")));
         }
-        Err(_) => {
+        Err(Interrupted::Cancelled) => return Err(CanisterClientError::Cancelled),
+        Err(Interrupted::TimedOut) => {
             return Err(CanisterClientError::Net(format!(
                 "canister call timeout ({}s): canister={canister_id} (fetch_candid)",
                 to.as_secs()
@@ -608,12 +653,79 @@ pub fn fetch_candid(canister_id: &str, host: Option<&str>) -> Result<String, Can
     Ok(candid_text)
 }
 
+/// Traces a canister call to the host log (see [`crate::logging`]): its
+/// start at debug level, its outcome and duration at info (or warn on
+/// failure). Arguments are not logged; they may carry user data.
+fn traced(
+    canister_id: &str,
+    method: &str,
+    kind: MethodKind,
+    call: impl FnOnce() -> Result<String, CanisterClientError>,
+) -> Result<String, CanisterClientError> {
+    logging::log(
+        LogLevel::Debug,
+        LogCategory::Canister,
+        &format!("{canister_id}.{method} ({kind:?}) started"),
+    );
+    let started = std::time::Instant::now();
+    let out = call();
+    let ms = started.elapsed().as_millis();
+    match &out {
+        Ok(_) => logging::log(
+            LogLevel::Info,
+            LogCategory::Canister,
+            &format!("{canister_id}.{method} ({kind:?}) ok in {ms} ms"),
+        ),
+        Err(e) => logging::log(
+            LogLevel::Warn,
+            LogCategory::Canister,
+            &format!("{canister_id}.{method} ({kind:?}) failed in {ms} ms: {e}"),
+        ),
+    }
+    out
+}
+
+/// Calls `method` as the anonymous principal. Goes through the active call
+/// tape, if any (see [`crate::call_tape`]).
+/// Consults [`transfer_policy`] before a live update call leaves the device;
+/// queries cannot move value.
+fn authorize_transfer(
+    canister_id: &str,
+    method: &str,
+    kind: MethodKind,
+    arg_candid: &str,
+) -> Result<(), CanisterClientError> {
+    if kind != MethodKind::Update {
+        return Ok(());
+    }
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    transfer_policy::authorize(canister_id, method, arg_candid, now_ms)
+        .map_err(CanisterClientError::TransferRejected)
+}
+
 pub fn call_anonymous(
     canister_id: &str,
     method: &str,
     kind: MethodKind,
     arg_candid: &str,
     host: Option<&str>,
+) -> Result<String, CanisterClientError> {
+    traced(canister_id, method, kind, || {
+        call_tape::through_tape(canister_id, method, kind, arg_candid, false, || {
+            authorize_transfer(canister_id, method, kind, arg_candid)?;
+            call_anonymous_live(canister_id, method, kind, arg_candid, host)
+        })
+    })
+}
+
+fn call_anonymous_live(
+    canister_id: &str,
+    method: &str,
+    kind: MethodKind,
+    arg_candid: &str,
+    host: Option<&str>,
 ) -> Result<String, CanisterClientError> {
     use ic_agent::Agent;
 
@@ -653,13 +765,13 @@ pub fn call_anonymous(
         }
     };
     let to = canister_call_timeout();
-    let rt = shared_runtime();
-    let out = match rt.block_on(async { timeout(to, fut).await }) {
+    let out = match block_on_cancellable(fut, to) {
         Ok(Ok(b)) => b,
         Ok(Err(e)) => {
             return Err(CanisterClientError::Net(format!("call: {e}")));
         }
-        Err(_) => {
+        Err(Interrupted::Cancelled) => return Err(CanisterClientError::Cancelled),
+        Err(Interrupted::TimedOut) => {
             return Err(CanisterClientError::Net(format!(
                 "canister call timeout ({}s): canister={canister_id} method={method}",
                 to.as_secs()
@@ -680,6 +792,8 @@ pub fn call_anonymous(
     Ok(response.to_string())
 }
 
+/// Calls `method` signed with the given Ed25519 key. Goes through the active
+/// call tape, if any; the key itself is never recorded.
 pub fn call_authenticated(
     canister_id: &str,
     method: &str,
@@ -687,6 +801,29 @@ pub fn call_authenticated(
     arg_candid: &str,
     ed25519_private_key_b64: &str,
     host: Option<&str>,
+) -> Result<String, CanisterClientError> {
+    traced(canister_id, method, kind, || {
+        call_tape::through_tape(canister_id, method, kind, arg_candid, true, || {
+            authorize_transfer(canister_id, method, kind, arg_candid)?;
+            call_authenticated_live(
+                canister_id,
+                method,
+                kind,
+                arg_candid,
+                ed25519_private_key_b64,
+                host,
+            )
+        })
+    })
+}
+
+fn call_authenticated_live(
+    canister_id: &str,
+    method: &str,
+    kind: MethodKind,
+    arg_candid: &str,
+    ed25519_private_key_b64: &str,
+    host: Option<&str>,
 ) -> Result<String, CanisterClientError> {
     use base64::Engine;
     use ic_agent::{identity::BasicIdentity, Agent};
@@ -737,13 +874,13 @@ pub fn call_authenticated(
         }
     };
     let to = canister_call_timeout();
-    let rt = shared_runtime();
-    let out = match rt.block_on(async { timeout(to, fut).await }) {
+    let out = match block_on_cancellable(fut, to) {
         Ok(Ok(b)) => b,
         Ok(Err(e)) => {
             return Err(CanisterClientError::Net(format!("call: {e}")));
         }
-        Err(_) => {
+        Err(Interrupted::Cancelled) => return Err(CanisterClientError::Cancelled),
+        Err(Interrupted::TimedOut) => {
             return Err(CanisterClientError::Net(format!(
                 "canister call timeout ({}s): canister={canister_id} method={method}",
                 to.as_secs()
@@ -0,0 +1,170 @@
+//! Connectivity diagnostics for an IC endpoint.
+//!
+//! [`probe_endpoint`] samples `/api/v2/status` of a boundary node or a local
+//! replica a few times and reports whether it answered, whether it reports
+//! itself healthy, whether its root key is the IC mainnet one, and latency
+//! percentiles. The app shows this before running a script, so a bad gateway
+//! URL or a stale local replica is named as such instead of surfacing as an
+//! opaque canister call failure.
+
+use crate::canister_client::{
+    block_on_cancellable, canister_call_timeout, Interrupted, DEFAULT_IC_GATEWAY,
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Samples taken when the caller does not say.
+pub const DEFAULT_PROBE_SAMPLES: u32 = 5;
+
+/// Upper bound on samples, so a probe stays a quick check.
+pub const MAX_PROBE_SAMPLES: u32 = 20;
+
+/// Latency of the successful samples, in milliseconds (nearest-rank
+/// percentiles).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencyStats {
+    pub min_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EndpointProbe {
+    pub url: String,
+    /// At least one status request succeeded.
+    pub reachable: bool,
+    /// `replica_health_status == "healthy"`; `None` if unreachable or not
+    /// reported.
+    pub healthy: Option<bool>,
+    /// The reported root key is the IC mainnet root key. Expected `false` on
+    /// a local replica; on a mainnet gateway `false` means the endpoint is
+    /// not the IC it claims to be. `None` if unreachable or not reported.
+    pub root_key_matches: Option<bool>,
+    pub samples: u32,
+    pub failures: u32,
+    /// `None` if every sample failed.
+    pub latency: Option<LatencyStats>,
+    /// Error of the last failed sample.
+    pub error: Option<String>,
+}
+
+/// The `p`-th percentile (0–100) of ascending `sorted` by nearest rank.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Summary of `samples`; `None` when empty.
+pub fn latency_stats(samples: &[Duration]) -> Option<LatencyStats> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+    ms.sort_by(f64::total_cmp);
+    Some(LatencyStats {
+        min_ms: ms[0],
+        p50_ms: percentile(&ms, 50.0),
+        p90_ms: percentile(&ms, 90.0),
+        p99_ms: percentile(&ms, 99.0),
+        max_ms: ms[ms.len() - 1],
+    })
+}
+
+/// Probes `url` (the default gateway when `None`) with `samples` status
+/// requests (at least 1, at most [`MAX_PROBE_SAMPLES`]). Each request is
+/// bounded by the canister call timeout. Never fails: an unusable URL is
+/// reported as unreachable with the reason in `error`. Cancelling the
+/// calling job stops sampling; `samples` then counts the ones taken.
+pub fn probe_endpoint(url: Option<&str>, samples: u32) -> EndpointProbe {
+    use ic_agent::Agent;
+
+    let url = url.unwrap_or(DEFAULT_IC_GATEWAY).to_string();
+    let samples = samples.clamp(1, MAX_PROBE_SAMPLES);
+    let mut probe = EndpointProbe {
+        url: url.clone(),
+        reachable: false,
+        healthy: None,
+        root_key_matches: None,
+        samples,
+        failures: samples,
+        latency: None,
+        error: None,
+    };
+    let agent = match Agent::builder().with_url(url.as_str()).build() {
+        Ok(agent) => agent,
+        Err(e) => {
+            probe.error = Some(format!("build agent: {e}"));
+            return probe;
+        }
+    };
+    // Until a key is fetched, the agent holds the mainnet root key.
+    let mainnet_root_key = agent.read_root_key();
+
+    let to = canister_call_timeout();
+    let mut latencies = Vec::with_capacity(samples as usize);
+    for taken in 0..samples {
+        let started = Instant::now();
+        match block_on_cancellable(agent.status(), to) {
+            Ok(Ok(status)) => {
+                latencies.push(started.elapsed());
+                if let Some(health) = status.replica_health_status.as_deref() {
+                    probe.healthy = Some(health == "healthy");
+                }
+                if let Some(root_key) = status.root_key.as_deref() {
+                    probe.root_key_matches = Some(root_key == mainnet_root_key.as_slice());
+                }
+            }
+            Ok(Err(e)) => probe.error = Some(format!("status: {e}")),
+            Err(Interrupted::TimedOut) => {
+                probe.error = Some(format!("status timeout ({}s)", to.as_secs()))
+            }
+            Err(Interrupted::Cancelled) => {
+                probe.samples = taken;
+                probe.error = Some("cancelled".to_string());
+                break;
+            }
+        }
+    }
+    probe.failures = probe.samples - latencies.len() as u32;
+    probe.reachable = !latencies.is_empty();
+    probe.latency = latency_stats(&latencies);
+    probe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let samples: Vec<Duration> = [40, 10, 30, 20, 50]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect();
+        let stats = latency_stats(&samples).unwrap();
+        assert_eq!(stats.min_ms, 10.0);
+        assert_eq!(stats.p50_ms, 30.0);
+        assert_eq!(stats.p90_ms, 50.0);
+        assert_eq!(stats.p99_ms, 50.0);
+        assert_eq!(stats.max_ms, 50.0);
+        assert_eq!(latency_stats(&[]), None);
+    }
+
+    #[test]
+    fn single_sample_is_every_percentile() {
+        let stats = latency_stats(&[Duration::from_millis(7)]).unwrap();
+        assert_eq!((stats.min_ms, stats.p50_ms, stats.max_ms), (7.0, 7.0, 7.0));
+    }
+
+    #[test]
+    fn unusable_url_is_reported_not_raised() {
+        let probe = probe_endpoint(Some("not a url"), 0);
+        assert!(!probe.reachable);
+        assert_eq!(probe.samples, 1);
+        assert_eq!(probe.failures, 1);
+        assert!(probe.latency.is_none());
+        assert!(probe.error.is_some());
+    }
+}
@@ -0,0 +1,472 @@
+//! Favorites: folders, per-entry notes/tags and smart lists.
+//!
+//! The app currently persists two flat lists (see
+//! `apps/autorun_flutter/lib/services/`): `script_favorites`, a JSON array of
+//! script ids, and `bookmarks`, a JSON array of
+//! `{canister_id, method, label?}`. [`Favorites::migrate_legacy`] folds both
+//! into the versioned document defined here; [`Favorites::from_json`] accepts
+//! either legacy shape as well as the current one, so callers can point it at
+//! whatever is on disk.
+//!
+//! Timestamps are unix milliseconds supplied by the caller.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const FAVORITES_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FavoriteTarget {
+    Script { script_id: String },
+    Method { canister_id: String, method: String },
+}
+
+impl FavoriteTarget {
+    pub fn canister_id(&self) -> Option<&str> {
+        match self {
+            FavoriteTarget::Method { canister_id, .. } => Some(canister_id),
+            FavoriteTarget::Script { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Favorite {
+    pub target: FavoriteTarget,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    /// `None` means the root (no folder).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    pub added_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<u64>,
+}
+
+impl Favorite {
+    pub fn new(target: FavoriteTarget, now: u64) -> Self {
+        Self {
+            target,
+            label: None,
+            note: None,
+            tags: BTreeSet::new(),
+            folder: None,
+            added_at: now,
+            last_used_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Folder {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+/// Computed views; nothing about them is stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SmartList {
+    /// Most recently used first; entries never used are excluded.
+    RecentlyUsed {
+        limit: usize,
+    },
+    ByCanister {
+        canister_id: String,
+    },
+    Tagged {
+        tag: String,
+    },
+    InFolder {
+        folder: Option<String>,
+    },
+    Scripts,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Favorites {
+    pub version: u32,
+    #[serde(default)]
+    pub folders: Vec<Folder>,
+    #[serde(default)]
+    pub entries: Vec<Favorite>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacyEntry {
+    ScriptId(String),
+    Bookmark {
+        canister_id: String,
+        method: String,
+        #[serde(default)]
+        label: Option<String>,
+    },
+}
+
+impl Favorites {
+    pub fn new() -> Self {
+        Self {
+            version: FAVORITES_FORMAT_VERSION,
+            ..Self::default()
+        }
+    }
+
+    /// Parses the current document or either legacy flat array. Legacy
+    /// entries get `added_at = now`.
+    pub fn from_json(json: &str, now: u64) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid favorites JSON: {}", e))?;
+        if value.is_array() {
+            let legacy: Vec<LegacyEntry> = serde_json::from_value(value)
+                .map_err(|e| format!("Invalid legacy favorites entry: {}", e))?;
+            let mut favorites = Self::new();
+            favorites.import_legacy(legacy, now);
+            return Ok(favorites);
+        }
+        let favorites: Self = serde_json::from_value(value)
+            .map_err(|e| format!("Invalid favorites document: {}", e))?;
+        if favorites.version > FAVORITES_FORMAT_VERSION {
+            return Err(format!(
+                "Favorites format version {} is newer than supported {}",
+                favorites.version, FAVORITES_FORMAT_VERSION
+            ));
+        }
+        Ok(Self {
+            version: FAVORITES_FORMAT_VERSION,
+            ..favorites
+        })
+    }
+
+    /// Merges the two legacy documents (`script_favorites`, `bookmarks`);
+    /// pass `None` for one that is absent.
+    pub fn migrate_legacy(
+        script_favorites_json: Option<&str>,
+        bookmarks_json: Option<&str>,
+        now: u64,
+    ) -> Result<Self, String> {
+        let mut favorites = Self::new();
+        for json in [script_favorites_json, bookmarks_json]
+            .into_iter()
+            .flatten()
+        {
+            let legacy: Vec<LegacyEntry> = serde_json::from_str(json)
+                .map_err(|e| format!("Invalid legacy favorites JSON: {}", e))?;
+            favorites.import_legacy(legacy, now);
+        }
+        Ok(favorites)
+    }
+
+    fn import_legacy(&mut self, legacy: Vec<LegacyEntry>, now: u64) {
+        for entry in legacy {
+            let (target, label) = match entry {
+                LegacyEntry::ScriptId(script_id) => (FavoriteTarget::Script { script_id }, None),
+                LegacyEntry::Bookmark {
+                    canister_id,
+                    method,
+                    label,
+                } => (
+                    FavoriteTarget::Method {
+                        canister_id,
+                        method,
+                    },
+                    label,
+                ),
+            };
+            let favorite = self.upsert(target, now);
+            if label.is_some() {
+                favorite.label = label;
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("favorites serialize infallibly")
+    }
+
+    pub fn get(&self, target: &FavoriteTarget) -> Option<&Favorite> {
+        self.entries.iter().find(|f| &f.target == target)
+    }
+
+    fn get_mut(&mut self, target: &FavoriteTarget) -> Result<&mut Favorite, String> {
+        self.entries
+            .iter_mut()
+            .find(|f| &f.target == target)
+            .ok_or_else(|| "Favorite not found".to_string())
+    }
+
+    /// Adds `target` if missing and returns the (possibly existing) entry.
+    pub fn upsert(&mut self, target: FavoriteTarget, now: u64) -> &mut Favorite {
+        match self.entries.iter().position(|f| f.target == target) {
+            Some(i) => &mut self.entries[i],
+            None => {
+                self.entries.push(Favorite::new(target, now));
+                self.entries.last_mut().expect("just pushed")
+            }
+        }
+    }
+
+    pub fn remove(&mut self, target: &FavoriteTarget) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|f| &f.target != target);
+        self.entries.len() != before
+    }
+
+    pub fn touch(&mut self, target: &FavoriteTarget, now: u64) -> Result<(), String> {
+        self.get_mut(target)?.last_used_at = Some(now);
+        Ok(())
+    }
+
+    pub fn set_note(
+        &mut self,
+        target: &FavoriteTarget,
+        note: Option<String>,
+    ) -> Result<(), String> {
+        self.get_mut(target)?.note = note.filter(|n| !n.trim().is_empty());
+        Ok(())
+    }
+
+    pub fn add_tag(&mut self, target: &FavoriteTarget, tag: &str) -> Result<(), String> {
+        let tag = normalize_tag(tag)?;
+        self.get_mut(target)?.tags.insert(tag);
+        Ok(())
+    }
+
+    pub fn remove_tag(&mut self, target: &FavoriteTarget, tag: &str) -> Result<bool, String> {
+        let tag = normalize_tag(tag)?;
+        Ok(self.get_mut(target)?.tags.remove(&tag))
+    }
+
+    /// Every tag in use, sorted.
+    pub fn tags(&self) -> BTreeSet<&str> {
+        self.entries
+            .iter()
+            .flat_map(|f| f.tags.iter().map(String::as_str))
+            .collect()
+    }
+
+    pub fn create_folder(&mut self, name: &str, parent: Option<&str>) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Folder name cannot be empty".to_string());
+        }
+        if self.folder(name).is_some() {
+            return Err(format!("Folder '{}' already exists", name));
+        }
+        if let Some(parent) = parent {
+            if self.folder(parent).is_none() {
+                return Err(format!("Parent folder '{}' does not exist", parent));
+            }
+        }
+        self.folders.push(Folder {
+            name: name.to_string(),
+            parent: parent.map(str::to_string),
+        });
+        Ok(())
+    }
+
+    fn folder(&self, name: &str) -> Option<&Folder> {
+        self.folders.iter().find(|f| f.name == name)
+    }
+
+    /// Deletes `name` and its subfolders; their entries move to the root.
+    pub fn delete_folder(&mut self, name: &str) -> Result<(), String> {
+        if self.folder(name).is_none() {
+            return Err(format!("Folder '{}' does not exist", name));
+        }
+        let mut doomed = BTreeSet::from([name.to_string()]);
+        loop {
+            let children: Vec<String> = self
+                .folders
+                .iter()
+                .filter(|f| f.parent.as_ref().is_some_and(|p| doomed.contains(p)))
+                .map(|f| f.name.clone())
+                .filter(|n| !doomed.contains(n))
+                .collect();
+            if children.is_empty() {
+                break;
+            }
+            doomed.extend(children);
+        }
+        self.folders.retain(|f| !doomed.contains(&f.name));
+        for entry in &mut self.entries {
+            if entry.folder.as_ref().is_some_and(|f| doomed.contains(f)) {
+                entry.folder = None;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn move_to_folder(
+        &mut self,
+        target: &FavoriteTarget,
+        folder: Option<&str>,
+    ) -> Result<(), String> {
+        if let Some(name) = folder {
+            if self.folder(name).is_none() {
+                return Err(format!("Folder '{}' does not exist", name));
+            }
+        }
+        self.get_mut(target)?.folder = folder.map(str::to_string);
+        Ok(())
+    }
+
+    pub fn smart_list(&self, list: &SmartList) -> Vec<&Favorite> {
+        let mut out: Vec<&Favorite> = match list {
+            SmartList::RecentlyUsed { .. } => self
+                .entries
+                .iter()
+                .filter(|f| f.last_used_at.is_some())
+                .collect(),
+            SmartList::ByCanister { canister_id } => self
+                .entries
+                .iter()
+                .filter(|f| f.target.canister_id() == Some(canister_id.as_str()))
+                .collect(),
+            SmartList::Tagged { tag } => match normalize_tag(tag) {
+                Ok(tag) => self
+                    .entries
+                    .iter()
+                    .filter(|f| f.tags.contains(&tag))
+                    .collect(),
+                Err(_) => Vec::new(),
+            },
+            SmartList::InFolder { folder } => self
+                .entries
+                .iter()
+                .filter(|f| &f.folder == folder)
+                .collect(),
+            SmartList::Scripts => self
+                .entries
+                .iter()
+                .filter(|f| matches!(f.target, FavoriteTarget::Script { .. }))
+                .collect(),
+        };
+        if let SmartList::RecentlyUsed { limit } = list {
+            out.sort_by(|a, b| b.last_used_at.cmp(&a.last_used_at));
+            out.truncate(*limit);
+        }
+        out
+    }
+}
+
+/// Tags are case-insensitive, trimmed and non-empty.
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn method(canister_id: &str, method: &str) -> FavoriteTarget {
+        FavoriteTarget::Method {
+            canister_id: canister_id.into(),
+            method: method.into(),
+        }
+    }
+
+    fn script(id: &str) -> FavoriteTarget {
+        FavoriteTarget::Script {
+            script_id: id.into(),
+        }
+    }
+
+    #[test]
+    fn test_migrates_both_legacy_documents() {
+        let favorites = Favorites::migrate_legacy(
+            Some(r#"["s1","s2","s1"]"#),
+            Some(r#"[{"canister_id":"ryjl3-tyaaa-aaaaa-aaaba-cai","method":"icrc1_balance_of","label":"ICP balance"}]"#),
+            7,
+        )
+        .unwrap();
+        assert_eq!(favorites.entries.len(), 3);
+        let bookmark = favorites
+            .get(&method("ryjl3-tyaaa-aaaaa-aaaba-cai", "icrc1_balance_of"))
+            .unwrap();
+        assert_eq!(bookmark.label.as_deref(), Some("ICP balance"));
+        assert_eq!(bookmark.added_at, 7);
+
+        let reparsed = Favorites::from_json(&favorites.to_json(), 99).unwrap();
+        assert_eq!(reparsed, favorites);
+        assert_eq!(
+            Favorites::from_json(r#"["s1"]"#, 1).unwrap().entries[0].target,
+            script("s1")
+        );
+    }
+
+    #[test]
+    fn test_rejects_newer_format() {
+        let err = Favorites::from_json(r#"{"version":3,"entries":[]}"#, 0).unwrap_err();
+        assert!(err.contains("newer"));
+        assert!(Favorites::from_json("{", 0).is_err());
+    }
+
+    #[test]
+    fn test_tags_notes_and_smart_lists() {
+        let mut favorites = Favorites::new();
+        let a = method("aaaaa-aa", "canister_status");
+        let b = method("ryjl3-tyaaa-aaaaa-aaaba-cai", "transfer");
+        favorites.upsert(a.clone(), 1);
+        favorites.upsert(b.clone(), 1);
+        favorites.upsert(script("s1"), 1);
+
+        favorites.add_tag(&a, " Admin ").unwrap();
+        favorites
+            .set_note(&a, Some("needs controller".into()))
+            .unwrap();
+        favorites.touch(&a, 10).unwrap();
+        favorites.touch(&b, 20).unwrap();
+        assert!(favorites.add_tag(&script("missing"), "x").is_err());
+
+        let tagged = favorites.smart_list(&SmartList::Tagged {
+            tag: "admin".into(),
+        });
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].note.as_deref(), Some("needs controller"));
+
+        let recent = favorites.smart_list(&SmartList::RecentlyUsed { limit: 1 });
+        assert_eq!(recent[0].target, b);
+        let by_canister = favorites.smart_list(&SmartList::ByCanister {
+            canister_id: "aaaaa-aa".into(),
+        });
+        assert_eq!(by_canister.len(), 1);
+        assert_eq!(favorites.smart_list(&SmartList::Scripts).len(), 1);
+        assert_eq!(favorites.tags().into_iter().collect::<Vec<_>>(), ["admin"]);
+    }
+
+    #[test]
+    fn test_deleting_folder_moves_entries_to_root() {
+        let mut favorites = Favorites::new();
+        favorites.create_folder("Ledger", None).unwrap();
+        favorites.create_folder("Test", Some("Ledger")).unwrap();
+        assert!(favorites.create_folder("Ledger", None).is_err());
+        assert!(favorites.create_folder("Orphan", Some("Nope")).is_err());
+
+        let target = script("s1");
+        favorites.upsert(target.clone(), 1);
+        favorites.move_to_folder(&target, Some("Test")).unwrap();
+        assert_eq!(
+            favorites
+                .smart_list(&SmartList::InFolder {
+                    folder: Some("Test".into())
+                })
+                .len(),
+            1
+        );
+
+        favorites.delete_folder("Ledger").unwrap();
+        assert!(favorites.folders.is_empty());
+        assert_eq!(favorites.get(&target).unwrap().folder, None);
+    }
+}
@@ -1,7 +1,12 @@
 use crate::{
+    call_tape::{self, CallTape, TapeMode},
+    candid_value,
     canister_client::{self, CanisterClientError, MethodKind},
-    generate_ed25519_keypair, generate_secp256k1_keypair, js_engine, principal_from_public_key,
-    sign_ed25519, sign_secp256k1,
+    endpoint_probe, generate_ed25519_keypair, generate_secp256k1_keypair, identity, js_engine,
+    keystore::{self, CallbackKeyStore},
+    logging::{self, LogLevel},
+    principal::{self, SUBACCOUNT_LEN},
+    principal_from_public_key, sign_ed25519, sign_secp256k1, transfer_policy,
     vault::{self, EncryptedVault},
     JsValidationContext,
 };
@@ -10,6 +15,16 @@ use serde_json::json;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
+mod async_jobs;
+
+/// Version of the C ABI exported by this module. Hosts compare it against the
+/// `ICP_FFI_ABI_VERSION` define in the generated `icp_core.h` they were built
+/// with (via `icp_ffi_abi_version`) before calling anything else.
+///
+/// Bump on any change to an exported signature or a callback typedef;
+/// `tests/ffi_abi.rs` fails until `ffi_abi.lock` is updated to match.
+pub const ICP_FFI_ABI_VERSION: u32 = 8;
+
 unsafe fn cstr_or_empty<'a>(p: *const c_char) -> &'a str {
     if p.is_null() {
         ""
@@ -70,20 +85,24 @@ fn canister_error_kind(e: &CanisterClientError) -> &'static str {
         CanisterClientError::InvalidCanisterId(_) => "invalid_canister_id",
         CanisterClientError::Net(_) => "net",
         CanisterClientError::CandidParse(_) => "candid",
+        CanisterClientError::Cancelled => "cancelled",
+        CanisterClientError::TransferRejected(_) => "transfer_rejected",
     }
 }
 
 /// Like [`err_ptr`] but adds a typed `"kind"` discriminator so the Dart host
 /// can match on the error variant instead of grepping the message string.
 fn canister_err_ptr(e: CanisterClientError) -> *mut c_char {
-    into_cstring_ptr(
-        json!({
-            "ok": false,
-            "kind": canister_error_kind(&e),
-            "error": e.to_string()
-        })
-        .to_string(),
-    )
+    into_cstring_ptr(canister_err_json(&e))
+}
+
+fn canister_err_json(e: &CanisterClientError) -> String {
+    json!({
+        "ok": false,
+        "kind": canister_error_kind(e),
+        "error": e.to_string()
+    })
+    .to_string()
 }
 
 fn method_kind(kind: i32) -> MethodKind {
@@ -98,6 +117,12 @@ fn null_c_string() -> *mut c_char {
     into_cstring_ptr(String::new())
 }
 
+/// ABI version of the loaded library; see [`ICP_FFI_ABI_VERSION`].
+#[no_mangle]
+pub extern "C" fn icp_ffi_abi_version() -> u32 {
+    ICP_FFI_ABI_VERSION
+}
+
 /// # Safety
 /// `mnemonic` must be either null or a valid, null-terminated C string pointer.
 #[no_mangle]
@@ -152,6 +177,93 @@ pub unsafe extern "C" fn icp_principal_from_public_key(
     into_cstring_ptr(principal)
 }
 
+/// Validates a textual principal and classifies it.
+///
+/// # Safety
+/// - `principal_text` must be null or a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success:
+///   {"ok":true,"principal":"...","kind":"self_authenticating|opaque|...","account_identifier":"<hex>"}
+/// - JSON format on error: {"ok":false,"error":"<precise reason>"}
+#[no_mangle]
+pub unsafe extern "C" fn icp_principal_info(principal_text: *const c_char) -> *mut c_char {
+    let text = match cstr_opt(principal_text) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid principal"),
+    };
+    let parsed = match principal::parse_principal_text(text) {
+        Ok(p) => p,
+        Err(e) => return err_ptr(e),
+    };
+    into_cstring_ptr(
+        json!({
+            "ok": true,
+            "principal": parsed.to_text(),
+            "kind": principal::classify_principal(&parsed),
+            "account_identifier": principal::account_identifier(&parsed, None),
+        })
+        .to_string(),
+    )
+}
+
+/// Derives the ICP ledger account identifier for a principal and subaccount.
+///
+/// # Safety
+/// - `principal_text` must be null or a valid, null-terminated C string.
+/// - `subaccount_hex` must be null (default subaccount) or a valid, null-terminated
+///   C string of 64 hex characters.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"account_identifier":"<hex>"}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_account_identifier(
+    principal_text: *const c_char,
+    subaccount_hex: *const c_char,
+) -> *mut c_char {
+    let text = match cstr_opt(principal_text) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid principal"),
+    };
+    let parsed = match principal::parse_principal_text(text) {
+        Ok(p) => p,
+        Err(e) => return err_ptr(e),
+    };
+    let subaccount: Option<[u8; SUBACCOUNT_LEN]> = match cstr_opt(subaccount_hex) {
+        None | Some("") => None,
+        Some(h) => match hex::decode(h).map(<[u8; SUBACCOUNT_LEN]>::try_from) {
+            Ok(Ok(sub)) => Some(sub),
+            _ => return err_ptr("Subaccount must be 32 bytes of hex"),
+        },
+    };
+    into_cstring_ptr(
+        json!({
+            "ok": true,
+            "account_identifier": principal::account_identifier(&parsed, subaccount.as_ref()),
+        })
+        .to_string(),
+    )
+}
+
+/// Checks length, hex encoding and CRC32 of an account identifier.
+///
+/// # Safety
+/// - `account_id_hex` must be null or a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format: {"ok":true} or {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_validate_account_identifier(
+    account_id_hex: *const c_char,
+) -> *mut c_char {
+    let text = match cstr_opt(account_id_hex) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid account identifier"),
+    };
+    match principal::validate_account_identifier(text) {
+        Ok(()) => into_cstring_ptr(json!({"ok": true}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
 /// Sign a message with a private key.
 ///
 /// # Safety
@@ -229,6 +341,30 @@ pub unsafe extern "C" fn icp_fetch_candid(
     }
 }
 
+/// Connectivity diagnostics for an IC endpoint: reachability, health, root
+/// key match and latency percentiles over `samples` status requests (0 means
+/// the default). Blocks for up to `samples` round trips; prefer
+/// `icp_probe_endpoint_start` from a UI thread.
+///
+/// # Safety
+/// - `url` must be null (the default gateway) or a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format: {"ok":true,"probe":{"url","reachable","healthy","root_key_matches",
+///   "samples","failures","latency":{"min_ms","p50_ms","p90_ms","p99_ms","max_ms"},"error"}}
+#[no_mangle]
+pub unsafe extern "C" fn icp_probe_endpoint(url: *const c_char, samples: u32) -> *mut c_char {
+    into_cstring_ptr(probe_json(cstr_opt_or_empty(url), samples))
+}
+
+fn probe_json(url: Option<&str>, samples: u32) -> String {
+    let url = url.filter(|u| !u.trim().is_empty());
+    let samples = match samples {
+        0 => endpoint_probe::DEFAULT_PROBE_SAMPLES,
+        n => n,
+    };
+    json!({"ok": true, "probe": endpoint_probe::probe_endpoint(url, samples)}).to_string()
+}
+
 /// # Safety
 /// - `candid_text` must be either null or a valid, null-terminated C string.
 /// - The returned pointer, when non-null, points to a heap-allocated C string owned by Rust
@@ -248,6 +384,50 @@ pub unsafe extern "C" fn icp_parse_candid(candid_text: *const c_char) -> *mut c_
     }
 }
 
+/// Parses Candid textual arguments (`(record { amount = 1_000 : nat })`) into
+/// the tagged JSON of [`candid_value`], for the call forms to edit.
+///
+/// # Safety
+/// - `args_text` must be null (no arguments) or a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"args":[...],"text":"<normalized Candid text>"}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_candid_parse_args(args_text: *const c_char) -> *mut c_char {
+    match candid_value::parse_args(cstr_or_empty(args_text)) {
+        Ok(args) => into_cstring_ptr(
+            json!({
+                "ok": true,
+                "args": candid_value::args_to_json(&args),
+                "text": candid_value::format_args(&args),
+            })
+            .to_string(),
+        ),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Prints the tagged JSON of [`candid_value`] back as Candid textual arguments.
+///
+/// # Safety
+/// - `args_json` must be null or a valid, null-terminated C string holding a JSON array.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"text":"(...)"}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_candid_format_args(args_json: *const c_char) -> *mut c_char {
+    let json: serde_json::Value = match serde_json::from_str(cstr_or_empty(args_json)) {
+        Ok(v) => v,
+        Err(e) => return err_ptr(format!("Invalid args JSON: {e}")),
+    };
+    match candid_value::args_from_json(&json) {
+        Ok(args) => into_cstring_ptr(
+            json!({"ok": true, "text": candid_value::format_args(&args)}).to_string(),
+        ),
+        Err(e) => err_ptr(e),
+    }
+}
+
 /// # Safety
 /// - `canister_id`, `method`, `arg_candid`, and `host` must be either null or valid,
 ///   null-terminated C strings.
@@ -434,6 +614,176 @@ pub unsafe extern "C" fn icp_js_app_update(
     into_cstring_ptr(out)
 }
 
+// ---- Logging FFI ----
+
+/// Host log callback: `level` is 0 trace … 4 error, `category` is `script`,
+/// `engine` or `canister`. Both strings are borrowed for the duration of the
+/// call only; the host copies what it keeps and must not free them.
+pub type LogCallback = extern "C" fn(level: i32, category: *const c_char, message: *const c_char);
+
+/// Registers (or clears, with null) the host log callback. Records below
+/// `min_level` (0 trace … 4 error) are not delivered. See [`logging`].
+///
+/// # Safety
+/// `cb` must be null or a function pointer that stays valid until it is
+/// replaced or cleared, and is safe to call from any thread.
+#[no_mangle]
+pub unsafe extern "C" fn icp_log_register_callback(cb: Option<LogCallback>, min_level: i32) {
+    let Some(cb) = cb else {
+        logging::clear_sink();
+        return;
+    };
+    logging::set_sink(
+        LogLevel::from_i32(min_level),
+        std::sync::Arc::new(move |level, category, message| {
+            // Infallible: category names are static ASCII, and interior NULs
+            // in the message are replaced first.
+            let category = CString::new(category.as_str()).unwrap_or_default();
+            let message = CString::new(message.replace('\0', "\u{fffd}")).unwrap_or_default();
+            cb(level as i32, category.as_ptr(), message.as_ptr());
+        }),
+    );
+}
+
+// ---- Transfer policy FFI ----
+
+/// Asks the host to confirm a script's transfer. `request_json` is
+/// `{"script_id","token","method","amount":"<decimal>"|null,"reason"}`,
+/// borrowed for the duration of the call. Return 1 to let the transfer go
+/// ahead, 0 to reject it. Called on the thread making the canister call,
+/// which stays blocked until it returns.
+pub type TransferConfirmCallback = extern "C" fn(request_json: *const c_char) -> i32;
+
+/// Replaces the transfer auto-approval limits. See [`transfer_policy`].
+///
+/// # Safety
+/// - `policy_json` must be a valid, null-terminated C string:
+///   {"tokens":{"<ledger id>":{"per_call":"<amount>","daily":"<amount>"}},
+///    "scripts":{"<script id>":{"<ledger id>":{...}}}}
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true}
+#[no_mangle]
+pub unsafe extern "C" fn icp_transfer_policy_set(policy_json: *const c_char) -> *mut c_char {
+    let policy_str = match cstr_opt(policy_json) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid policy"),
+    };
+    match serde_json::from_str::<transfer_policy::TransferPolicy>(policy_str) {
+        Ok(policy) => {
+            transfer_policy::set_policy(policy);
+            into_cstring_ptr(json!({"ok": true}).to_string())
+        }
+        Err(e) => err_ptr(format!("Invalid policy JSON: {}", e)),
+    }
+}
+
+/// Current transfer limits: {"ok":true,"policy":{...}}. Must be freed by
+/// `icp_free_string`.
+#[no_mangle]
+pub extern "C" fn icp_transfer_policy_get() -> *mut c_char {
+    into_cstring_ptr(json!({"ok": true, "policy": transfer_policy::policy()}).to_string())
+}
+
+/// Names the script whose canister calls follow, or clears it with null
+/// when the script stops. Only calls made while a script is named are
+/// subject to the transfer policy.
+///
+/// # Safety
+/// `script_id` must be null or a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn icp_transfer_policy_set_script(script_id: *const c_char) {
+    transfer_policy::set_active_script(cstr_opt(script_id).map(str::to_string));
+}
+
+/// Registers (or clears, with null) the transfer confirmation callback.
+/// Without one, transfers above the limits are rejected.
+///
+/// # Safety
+/// `cb` must be null or a function pointer that stays valid until it is
+/// replaced or cleared, and is safe to call from any thread.
+#[no_mangle]
+pub unsafe extern "C" fn icp_transfer_policy_register_confirm_callback(
+    cb: Option<TransferConfirmCallback>,
+) {
+    transfer_policy::set_confirm_handler(cb.map(|cb| -> transfer_policy::ConfirmHandler {
+        std::sync::Arc::new(move |request| {
+            let request_json = serde_json::to_string(request).unwrap_or_default();
+            // Infallible: serialized JSON escapes NUL.
+            let request_json = CString::new(request_json).unwrap_or_default();
+            cb(request_json.as_ptr()) == 1
+        })
+    }));
+}
+
+// ---- Call recording and replay FFI ----
+
+/// Routes every canister call through the tape file at `path`: `mode` 0
+/// records successful calls into it, 1 answers calls from it without the
+/// network. See [`call_tape`].
+///
+/// # Safety
+/// - `path` must be null or a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"calls":<calls already on the tape>}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_call_tape_open(mode: i32, path: *const c_char) -> *mut c_char {
+    let mode = match mode {
+        0 => TapeMode::Record,
+        1 => TapeMode::Replay,
+        _ => return err_ptr(format!("unknown tape mode {mode}")),
+    };
+    let path = match cstr_opt(path) {
+        Some(p) if !p.is_empty() => std::path::Path::new(p),
+        _ => return err_ptr("Missing tape path"),
+    };
+    match call_tape::start(mode, path) {
+        Ok(calls) => into_cstring_ptr(json!({"ok": true, "calls": calls}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Detaches the active call tape; canister calls reach the network again.
+/// Returns heap-allocated C string (JSON), to be freed by `icp_free_string`:
+/// {"ok":true,"stopped":<whether a tape was active>}
+#[no_mangle]
+pub extern "C" fn icp_call_tape_close() -> *mut c_char {
+    into_cstring_ptr(json!({"ok": true, "stopped": call_tape::stop().is_some()}).to_string())
+}
+
+/// Runs a script app from `init` with every canister effect answered from the
+/// tape file at `tape_path`, and returns the settled state and view.
+///
+/// # Safety
+/// - All pointers must be null or valid, null-terminated C strings.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"state":{...},"ui":{...},"messages":[...]}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_js_app_replay(
+    script: *const c_char,
+    json_arg: *const c_char,
+    tape_path: *const c_char,
+    budget_ms: u64,
+) -> *mut c_char {
+    let mut tape = match CallTape::load(std::path::Path::new(cstr_or_empty(tape_path))) {
+        Ok(tape) => tape,
+        Err(e) => return err_ptr(e),
+    };
+    match call_tape::replay_app(
+        cstr_or_empty(script),
+        cstr_opt_or_empty(json_arg),
+        &mut tape,
+        budget_ms,
+    ) {
+        Ok(run) => into_cstring_ptr(
+            json!({"ok": true, "state": run.state, "ui": run.ui, "messages": run.messages})
+                .to_string(),
+        ),
+        Err(e) => err_ptr(e),
+    }
+}
+
 // ---- Vault encryption FFI ----
 
 /// Encrypts data with AES-256-GCM using a password-derived key (Argon2id).
@@ -544,6 +894,620 @@ pub unsafe extern "C" fn icp_decrypt_vault(
     }
 }
 
+// ---- Encrypted identity FFI ----
+
+/// Encrypts an identity record into the versioned container format.
+///
+/// # Safety
+/// - `identity_json` and `password` must be null or valid, null-terminated C strings.
+/// - `identity_json` is a serialized `IdentityData`
+///   (`{"algorithm","public_key_b64","private_key_b64","principal_text"}`).
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"data":"<base64 container>"}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_export_identity_encrypted(
+    identity_json: *const c_char,
+    password: *const c_char,
+) -> *mut c_char {
+    if identity_json.is_null() || password.is_null() {
+        return err_ptr("Null parameters");
+    }
+    let identity_str = match cstr_opt(identity_json) {
+        Some(s) => s,
+        None => return err_ptr("Invalid identity encoding"),
+    };
+    let password_str = match cstr_opt(password) {
+        Some(s) => s,
+        None => return err_ptr("Invalid password encoding"),
+    };
+    let identity: identity::IdentityData = match serde_json::from_str(identity_str) {
+        Ok(i) => i,
+        Err(e) => return err_ptr(format!("Invalid identity JSON: {}", e)),
+    };
+
+    match identity::export_encrypted_identity(&identity, password_str) {
+        Ok(data) => into_cstring_ptr(json!({"ok": true, "data": B64.encode(data)}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Decrypts a container produced by `icp_export_identity_encrypted`.
+///
+/// # Safety
+/// - `data_b64` and `password` must be null or valid, null-terminated C strings.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"identity":{...}}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_import_identity_encrypted(
+    data_b64: *const c_char,
+    password: *const c_char,
+) -> *mut c_char {
+    if data_b64.is_null() || password.is_null() {
+        return err_ptr("Null parameters");
+    }
+    let password_str = match cstr_opt(password) {
+        Some(s) => s,
+        None => return err_ptr("Invalid password encoding"),
+    };
+    let data = match B64.decode(cstr_or_empty(data_b64)) {
+        Ok(b) => b,
+        Err(e) => return err_ptr(format!("Failed to decode data: {}", e)),
+    };
+
+    match identity::import_encrypted_identity(&data, password_str) {
+        Ok(identity) => into_cstring_ptr(json!({"ok": true, "identity": identity}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Parses a dfx/quill PEM identity file (Ed25519 PKCS#8 or secp256k1 SEC1/PKCS#8).
+///
+/// # Safety
+/// - `pem` must be null or a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"identity":{...}}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_import_identity_pem(pem: *const c_char) -> *mut c_char {
+    let pem_str = match cstr_opt(pem) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid PEM"),
+    };
+    match identity::import_pem_identity(pem_str) {
+        Ok(identity) => into_cstring_ptr(json!({"ok": true, "identity": identity}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Writes an identity as a dfx-compatible PEM file.
+///
+/// # Safety
+/// - `identity_json` must be null or a valid, null-terminated C string holding a
+///   serialized `IdentityData`.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"pem":"-----BEGIN ..."}
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_export_identity_pem(identity_json: *const c_char) -> *mut c_char {
+    let identity_str = match cstr_opt(identity_json) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid identity"),
+    };
+    let identity: identity::IdentityData = match serde_json::from_str(identity_str) {
+        Ok(i) => i,
+        Err(e) => return err_ptr(format!("Invalid identity JSON: {}", e)),
+    };
+    match identity::export_pem_identity(&identity) {
+        Ok(pem) => into_cstring_ptr(json!({"ok": true, "pem": pem}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+// ---- Keystore FFI ----
+
+/// Routes keystore operations to host-provided secure storage (Android
+/// Keystore / iOS Keychain). See `keystore::callback` for return conventions.
+///
+/// # Safety
+/// The callbacks must stay valid for the lifetime of the process and be safe
+/// to call from any thread.
+#[no_mangle]
+pub unsafe extern "C" fn icp_keystore_register_callbacks(
+    store_cb: keystore::callback::StoreCallback,
+    load_cb: keystore::callback::LoadCallback,
+    delete_cb: keystore::callback::DeleteCallback,
+) {
+    keystore::set_active_keystore(Box::new(CallbackKeyStore::new(
+        store_cb, load_cb, delete_cb,
+    )));
+}
+
+/// Generates an Ed25519 identity directly into the active keystore. The
+/// identity is not listed anywhere; hosts keeping more than one identity
+/// should use `icp_identity_profile_create` instead.
+///
+/// # Safety
+/// - `mnemonic` must be either null or a valid, null-terminated C string pointer.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success:
+///   {"ok":true,"identity":{"algorithm","public_key_b64","principal_text"}} (no private key)
+/// - JSON format on error: {"ok":false,"error":"..."}
+#[no_mangle]
+pub unsafe extern "C" fn icp_keystore_generate_ed25519(mnemonic: *const c_char) -> *mut c_char {
+    let mnemonic_opt = cstr_opt(mnemonic).map(str::to_string);
+    match keystore::with_active_keystore(|ks| keystore::generate_ed25519_identity(ks, mnemonic_opt))
+    {
+        Ok(stored) => into_cstring_ptr(json!({"ok": true, "identity": stored}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Moves an existing identity's private key into the active keystore.
+///
+/// # Safety
+/// - `identity_json` must be null or a valid, null-terminated C string holding a
+///   serialized `IdentityData`.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"identity":{...public fields...}}
+#[no_mangle]
+pub unsafe extern "C" fn icp_keystore_store_identity(identity_json: *const c_char) -> *mut c_char {
+    let identity_str = match cstr_opt(identity_json) {
+        Some(s) => s,
+        None => return err_ptr("Null or invalid identity"),
+    };
+    let identity: identity::IdentityData = match serde_json::from_str(identity_str) {
+        Ok(i) => i,
+        Err(e) => return err_ptr(format!("Invalid identity JSON: {}", e)),
+    };
+    match keystore::with_active_keystore(|ks| keystore::store_identity(ks, &identity)) {
+        Ok(stored) => into_cstring_ptr(json!({"ok": true, "identity": stored}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Signs with the key stored for `principal_text`.
+///
+/// # Safety
+/// - `principal_text` and `message_b64` must be valid, null-terminated C strings.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"signature":"<base64>"}
+#[no_mangle]
+pub unsafe extern "C" fn icp_keystore_sign(
+    principal_text: *const c_char,
+    message_b64: *const c_char,
+) -> *mut c_char {
+    let (principal, msg_b64) = match (cstr_opt(principal_text), cstr_opt(message_b64)) {
+        (Some(p), Some(m)) => (p, m),
+        _ => return err_ptr("Null parameters"),
+    };
+    let message = match B64.decode(msg_b64) {
+        Ok(b) => b,
+        Err(e) => return err_ptr(format!("Failed to decode message: {}", e)),
+    };
+    match keystore::with_active_keystore(|ks| {
+        keystore::sign_with_stored_key(ks, principal, &message)
+    }) {
+        Ok(sig) => into_cstring_ptr(json!({"ok": true, "signature": B64.encode(sig)}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Removes the key stored for `principal_text`.
+///
+/// # Safety
+/// - `principal_text` must be a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"deleted":true|false}
+#[no_mangle]
+pub unsafe extern "C" fn icp_keystore_delete(principal_text: *const c_char) -> *mut c_char {
+    let principal = match cstr_opt(principal_text) {
+        Some(p) => p,
+        None => return err_ptr("Null or invalid principal"),
+    };
+    match keystore::with_active_keystore(|ks| ks.delete(principal)) {
+        Ok(deleted) => into_cstring_ptr(json!({"ok": true, "deleted": deleted}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+// ---- Identity profiles FFI ----
+// Named identities in the active keystore; see `keystore::profiles`. Each
+// returns {"ok":false,"error":"..."} on failure.
+
+fn profile_ptr(result: Result<keystore::IdentityProfile, keystore::ProfileError>) -> *mut c_char {
+    match result {
+        Ok(profile) => into_cstring_ptr(json!({"ok": true, "profile": profile}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+unsafe fn profile_scope(account: *const c_char, network: *const c_char) -> keystore::ProfileScope {
+    keystore::ProfileScope {
+        account: cstr_opt(account).map(str::to_string),
+        network: cstr_opt(network).map(str::to_string),
+    }
+}
+
+/// Lists the identity profiles in the active keystore.
+///
+/// Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// JSON format on success:
+///   {"ok":true,"active":"<name>"|null,"profiles":[{"name","algorithm",
+///    "public_key_b64","principal_text","account"?,"network"?,"created_at"}]}
+#[no_mangle]
+pub extern "C" fn icp_identity_profiles_list() -> *mut c_char {
+    match keystore::with_active_keystore(keystore::profiles::load_profiles) {
+        Ok(set) => into_cstring_ptr(
+            json!({"ok": true, "active": set.active, "profiles": set.profiles}).to_string(),
+        ),
+        Err(e) => err_ptr(e),
+    }
+}
+
+/// Generates an identity into the active keystore and files it under `name`.
+/// The first profile becomes the active one.
+///
+/// # Safety
+/// - `name` must be a valid, null-terminated C string; `mnemonic`, `account`
+///   and `network` must be null or valid, null-terminated C strings.
+/// - `alg` is 0 (ed25519) or 1 (secp256k1); `now_ms` is unix milliseconds.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"profile":{...}} (no private key)
+#[no_mangle]
+pub unsafe extern "C" fn icp_identity_profile_create(
+    name: *const c_char,
+    alg: i32,
+    mnemonic: *const c_char,
+    account: *const c_char,
+    network: *const c_char,
+    now_ms: u64,
+) -> *mut c_char {
+    let name = match cstr_opt(name) {
+        Some(n) => n,
+        None => return err_ptr("Null or invalid profile name"),
+    };
+    let algorithm = match alg {
+        0 => identity::KeyAlgorithm::Ed25519,
+        1 => identity::KeyAlgorithm::Secp256k1,
+        _ => return err_ptr(format!("unsupported algorithm: {alg}")),
+    };
+    let mnemonic_opt = cstr_opt(mnemonic).map(str::to_string);
+    let scope = profile_scope(account, network);
+    profile_ptr(keystore::with_active_keystore(|ks| {
+        keystore::profiles::create_profile(ks, name, algorithm, mnemonic_opt, scope, now_ms)
+    }))
+}
+
+/// Moves an existing identity's key into the active keystore and files it
+/// under `name`.
+///
+/// # Safety
+/// - `name` and `identity_json` (a serialized `IdentityData`) must be valid,
+///   null-terminated C strings; `account` and `network` may be null.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"profile":{...}}
+#[no_mangle]
+pub unsafe extern "C" fn icp_identity_profile_import(
+    name: *const c_char,
+    identity_json: *const c_char,
+    account: *const c_char,
+    network: *const c_char,
+    now_ms: u64,
+) -> *mut c_char {
+    let (name, identity_str) = match (cstr_opt(name), cstr_opt(identity_json)) {
+        (Some(n), Some(i)) => (n, i),
+        _ => return err_ptr("Null parameters"),
+    };
+    let identity: identity::IdentityData = match serde_json::from_str(identity_str) {
+        Ok(i) => i,
+        Err(e) => return err_ptr(format!("Invalid identity JSON: {}", e)),
+    };
+    let scope = profile_scope(account, network);
+    profile_ptr(keystore::with_active_keystore(|ks| {
+        keystore::profiles::import_profile(ks, name, &identity, scope, now_ms)
+    }))
+}
+
+/// Makes `name` the active profile.
+///
+/// # Safety
+/// - `name` must be a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"profile":{...}}
+#[no_mangle]
+pub unsafe extern "C" fn icp_identity_profile_switch(name: *const c_char) -> *mut c_char {
+    let name = match cstr_opt(name) {
+        Some(n) => n,
+        None => return err_ptr("Null or invalid profile name"),
+    };
+    profile_ptr(keystore::with_active_keystore(|ks| {
+        keystore::profiles::switch_profile(ks, name)
+    }))
+}
+
+/// Renames a profile; the active selection follows it.
+///
+/// # Safety
+/// - `name` and `new_name` must be valid, null-terminated C strings.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"profile":{...}}
+#[no_mangle]
+pub unsafe extern "C" fn icp_identity_profile_rename(
+    name: *const c_char,
+    new_name: *const c_char,
+) -> *mut c_char {
+    let (from, to) = match (cstr_opt(name), cstr_opt(new_name)) {
+        (Some(f), Some(t)) => (f, t),
+        _ => return err_ptr("Null parameters"),
+    };
+    profile_ptr(keystore::with_active_keystore(|ks| {
+        keystore::profiles::rename_profile(ks, from, to)
+    }))
+}
+
+/// Removes a profile, and its private key unless another profile shares the
+/// principal. If it was active, the first remaining profile becomes active.
+///
+/// # Safety
+/// - `name` must be a valid, null-terminated C string.
+/// - Returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - JSON format on success: {"ok":true,"key_deleted":true|false}
+#[no_mangle]
+pub unsafe extern "C" fn icp_identity_profile_remove(name: *const c_char) -> *mut c_char {
+    let name = match cstr_opt(name) {
+        Some(n) => n,
+        None => return err_ptr("Null or invalid profile name"),
+    };
+    match keystore::with_active_keystore(|ks| keystore::profiles::remove_profile(ks, name)) {
+        Ok(deleted) => into_cstring_ptr(json!({"ok": true, "key_deleted": deleted}).to_string()),
+        Err(e) => err_ptr(e),
+    }
+}
+
+// ---- Async (handle-based) FFI ----
+// Non-blocking variants of the long-running calls above; see
+// `ffi/async_jobs.rs` for the delivery contract. Every `*_start` returns a
+// non-zero job handle, or 0 if the worker thread could not be started.
+
+/// Copies a borrowed C string so it can move onto a worker thread.
+unsafe fn owned_cstr(p: *const c_char) -> Option<CString> {
+    if p.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(p).to_owned())
+    }
+}
+
+fn opt_ptr(s: &Option<CString>) -> *const c_char {
+    s.as_ref().map_or(std::ptr::null(), |c| c.as_ptr())
+}
+
+/// Takes back a pointer produced by `into_cstring_ptr` as an owned String.
+fn take_string(ptr: *mut c_char) -> String {
+    // Sound: every synchronous entry point returns via `into_cstring_ptr`.
+    unsafe { CString::from_raw(ptr) }
+        .into_string()
+        .unwrap_or_else(|_| json!({"ok": false, "error": "invalid utf-8 result"}).to_string())
+}
+
+/// Registers (or clears, with null) the completion callback for async jobs.
+///
+/// # Safety
+/// `cb` must be null or a function pointer that stays valid for the lifetime
+/// of the process and is safe to call from any thread.
+#[no_mangle]
+pub unsafe extern "C" fn icp_async_register_callback(cb: Option<async_jobs::CompletionCallback>) {
+    async_jobs::set_callback(cb);
+}
+
+/// Collects the result of a finished job.
+///
+/// # Safety
+/// - Returns null while the job is still running.
+/// - Otherwise returns heap-allocated C string (JSON). Must be freed by `icp_free_string`.
+/// - Unknown, cancelled or already-collected handles yield {"ok":false,"error":"unknown async handle"}.
+#[no_mangle]
+pub unsafe extern "C" fn icp_async_poll(handle: u64) -> *mut c_char {
+    match async_jobs::poll(handle) {
+        async_jobs::Poll::Pending => std::ptr::null_mut(),
+        async_jobs::Poll::Done(result) => into_cstring_ptr(result),
+        async_jobs::Poll::Unknown => err_ptr("unknown async handle"),
+    }
+}
+
+/// Cancels a job and discards its result. A running script stops at its
+/// next interrupt check and an in-flight canister call is aborted, so the
+/// worker thread exits promptly. Returns 1 if the handle was live, 0
+/// otherwise.
+#[no_mangle]
+pub extern "C" fn icp_async_cancel(handle: u64) -> i32 {
+    i32::from(async_jobs::cancel(handle))
+}
+
+/// Async `icp_fetch_candid`. Result: {"ok":true,"candid":"..."} or a typed canister error.
+///
+/// # Safety
+/// `canister_id` and `host` must be either null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_fetch_candid_start(
+    canister_id: *const c_char,
+    host: *const c_char,
+) -> u64 {
+    let cid = cstr_or_empty(canister_id).to_string();
+    let host_opt = cstr_opt_or_empty(host).map(str::to_string);
+    async_jobs::spawn_job(
+        move || match canister_client::fetch_candid(&cid, host_opt.as_deref()) {
+            Ok(candid) => json!({"ok": true, "candid": candid}).to_string(),
+            Err(e) => canister_err_json(&e),
+        },
+    )
+}
+
+/// Async `icp_probe_endpoint`; same arguments and result JSON.
+///
+/// # Safety
+/// `url` must be either null or a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn icp_probe_endpoint_start(url: *const c_char, samples: u32) -> u64 {
+    let url = cstr_opt_or_empty(url).map(str::to_string);
+    async_jobs::spawn_job(move || probe_json(url.as_deref(), samples))
+}
+
+/// Async `icp_call_anonymous`; same arguments and result JSON.
+///
+/// # Safety
+/// All pointers must be either null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_call_anonymous_start(
+    canister_id: *const c_char,
+    method: *const c_char,
+    kind: i32,
+    arg_candid: *const c_char,
+    host: *const c_char,
+) -> u64 {
+    let (cid, m, a, h) = (
+        owned_cstr(canister_id),
+        owned_cstr(method),
+        owned_cstr(arg_candid),
+        owned_cstr(host),
+    );
+    async_jobs::spawn_job(move || {
+        take_string(icp_call_anonymous(
+            opt_ptr(&cid),
+            opt_ptr(&m),
+            kind,
+            opt_ptr(&a),
+            opt_ptr(&h),
+        ))
+    })
+}
+
+/// Async `icp_call_authenticated`; same arguments and result JSON.
+///
+/// # Safety
+/// All pointers must be either null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_call_authenticated_start(
+    canister_id: *const c_char,
+    method: *const c_char,
+    kind: i32,
+    arg_candid: *const c_char,
+    ed25519_private_key_b64: *const c_char,
+    host: *const c_char,
+) -> u64 {
+    let (cid, m, a, k, h) = (
+        owned_cstr(canister_id),
+        owned_cstr(method),
+        owned_cstr(arg_candid),
+        owned_cstr(ed25519_private_key_b64),
+        owned_cstr(host),
+    );
+    async_jobs::spawn_job(move || {
+        take_string(icp_call_authenticated(
+            opt_ptr(&cid),
+            opt_ptr(&m),
+            kind,
+            opt_ptr(&a),
+            opt_ptr(&k),
+            opt_ptr(&h),
+        ))
+    })
+}
+
+/// Async `icp_js_exec`; same arguments and result JSON.
+///
+/// # Safety
+/// `script` and `json_arg` must be null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_js_exec_start(script: *const c_char, json_arg: *const c_char) -> u64 {
+    let (s, a) = (owned_cstr(script), owned_cstr(json_arg));
+    async_jobs::spawn_job(move || take_string(icp_js_exec(opt_ptr(&s), opt_ptr(&a))))
+}
+
+/// Async `icp_js_app_init`; same arguments and result JSON. Cancelling the
+/// handle stops the script (`{"ok":false,"error":"execution cancelled"}`).
+///
+/// # Safety
+/// `script` and `json_arg` must be null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_js_app_init_start(
+    script: *const c_char,
+    json_arg: *const c_char,
+    budget_ms: u64,
+) -> u64 {
+    let (s, a) = (owned_cstr(script), owned_cstr(json_arg));
+    async_jobs::spawn_job(move || take_string(icp_js_app_init(opt_ptr(&s), opt_ptr(&a), budget_ms)))
+}
+
+/// Async `icp_js_app_update`; same arguments and result JSON. Cancelling the
+/// handle stops the script as for [`icp_js_app_init_start`].
+///
+/// # Safety
+/// All pointers must be null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_js_app_update_start(
+    script: *const c_char,
+    msg_json: *const c_char,
+    state_json: *const c_char,
+    budget_ms: u64,
+) -> u64 {
+    let (s, m, st) = (
+        owned_cstr(script),
+        owned_cstr(msg_json),
+        owned_cstr(state_json),
+    );
+    async_jobs::spawn_job(move || {
+        take_string(icp_js_app_update(
+            opt_ptr(&s),
+            opt_ptr(&m),
+            opt_ptr(&st),
+            budget_ms,
+        ))
+    })
+}
+
+/// Async `icp_encrypt_vault` (Argon2id takes ~1s on mobile); same result JSON.
+///
+/// # Safety
+/// `password` and `plaintext_b64` must be null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_encrypt_vault_start(
+    password: *const c_char,
+    plaintext_b64: *const c_char,
+) -> u64 {
+    let (p, t) = (owned_cstr(password), owned_cstr(plaintext_b64));
+    async_jobs::spawn_job(move || take_string(icp_encrypt_vault(opt_ptr(&p), opt_ptr(&t))))
+}
+
+/// Async `icp_decrypt_vault`; same arguments and result JSON.
+///
+/// # Safety
+/// All pointers must be null or valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn icp_decrypt_vault_start(
+    password: *const c_char,
+    encrypted_data_b64: *const c_char,
+    salt_b64: *const c_char,
+    nonce_b64: *const c_char,
+) -> u64 {
+    let (p, d, s, n) = (
+        owned_cstr(password),
+        owned_cstr(encrypted_data_b64),
+        owned_cstr(salt_b64),
+        owned_cstr(nonce_b64),
+    );
+    async_jobs::spawn_job(move || {
+        take_string(icp_decrypt_vault(
+            opt_ptr(&p),
+            opt_ptr(&d),
+            opt_ptr(&s),
+            opt_ptr(&n),
+        ))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::{canister_err_ptr, into_cstring_ptr};
@@ -596,6 +1560,11 @@ mod tests {
                 CanisterClientError::CandidParse("decode failed".into()),
                 "candid",
             ),
+            (CanisterClientError::Cancelled, "cancelled"),
+            (
+                CanisterClientError::TransferRejected("over the daily limit".into()),
+                "transfer_rejected",
+            ),
         ] {
             let ptr = canister_err_ptr(err);
             assert!(!ptr.is_null(), "kind={expected_kind} produced a null ptr");
@@ -619,4 +1588,53 @@ mod tests {
             );
         }
     }
+
+    /// The async variant must surface the same typed error JSON as the sync
+    /// call, delivered through `icp_async_poll` rather than a blocking return.
+    #[test]
+    fn fetch_candid_start_delivers_typed_error_via_poll() {
+        let cid = CString::new("not-a-principal").unwrap();
+        // Sound: `cid` is a valid C string; null host means default gateway.
+        let handle = unsafe { super::icp_fetch_candid_start(cid.as_ptr(), std::ptr::null()) };
+        assert_ne!(handle, 0);
+
+        let mut out = None;
+        for _ in 0..500 {
+            // Sound: poll returns null or a pointer from `into_cstring_ptr`.
+            let ptr = unsafe { super::icp_async_poll(handle) };
+            if !ptr.is_null() {
+                out = Some(unsafe { CString::from_raw(ptr) }.into_string().unwrap());
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let v: serde_json::Value = serde_json::from_str(&out.expect("job finished")).unwrap();
+        assert_eq!(v["ok"], false);
+        assert_eq!(v["kind"], "invalid_canister_id");
+        assert_eq!(
+            super::icp_async_cancel(handle),
+            0,
+            "collected handle is gone"
+        );
+    }
+
+    /// The stop button cancels a runaway `init`: the job is live until then,
+    /// and its result is not delivered afterwards.
+    #[test]
+    fn js_app_init_start_can_be_cancelled() {
+        let script = CString::new("function init(){ while (true) {} }").unwrap();
+        // Sound: `script` is a valid C string; null arg means no argument.
+        let handle =
+            unsafe { super::icp_js_app_init_start(script.as_ptr(), std::ptr::null(), 60_000) };
+        assert_ne!(handle, 0);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        // Sound: poll returns null while the job is pending.
+        assert!(unsafe { super::icp_async_poll(handle) }.is_null());
+        assert_eq!(super::icp_async_cancel(handle), 1);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        // Sound: unknown handles yield an owned error string.
+        let ptr = unsafe { super::icp_async_poll(handle) };
+        let out = unsafe { CString::from_raw(ptr) }.into_string().unwrap();
+        assert!(out.contains("unknown async handle"), "got: {out}");
+    }
 }
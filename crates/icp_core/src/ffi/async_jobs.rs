@@ -0,0 +1,234 @@
+//! Handle-based async jobs for the FFI layer.
+//!
+//! The synchronous entry points block the calling thread for the full
+//! duration of a canister call or Argon2 derivation, which freezes the Flutter
+//! UI when called from the main isolate. The `*_start` variants instead run
+//! the same work on a worker thread and return a job handle immediately.
+//!
+//! Completion is delivered one of two ways:
+//! - If the host registered a callback (`icp_async_register_callback`), it is
+//!   invoked from the worker thread with `(handle, result)`; for a job that
+//!   cannot start, from the starting thread before `*_start` returns. The
+//!   host owns `result` and must free it with `icp_free_string`. Dart hosts
+//!   should pass a `NativeCallable.listener` so the call is marshalled onto
+//!   the isolate.
+//! - Otherwise the result is kept until collected with `icp_async_poll`.
+//!
+//! Results are always the same JSON the synchronous entry point would return.
+//! A job that cannot start (no worker thread) or panics completes the same
+//! way with `{"ok": false, "error": ...}`.
+//!
+//! Each job runs under its own [`CancelToken`]; `icp_async_cancel` trips it,
+//! which stops a running script or aborts an in-flight canister call (see
+//! [`crate::cancellation`]).
+
+use super::into_cstring_ptr;
+use crate::cancellation::{self, CancelToken};
+use crate::logging::{self, LogCategory, LogLevel};
+use std::collections::HashMap;
+use std::os::raw::c_char;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+pub type CompletionCallback = extern "C" fn(handle: u64, result: *mut c_char);
+
+enum JobState {
+    Pending(CancelToken),
+    Done(String),
+    Cancelled,
+}
+
+pub(crate) enum Poll {
+    Pending,
+    Done(String),
+    Unknown,
+}
+
+struct Registry {
+    next_handle: AtomicU64,
+    jobs: Mutex<HashMap<u64, JobState>>,
+    callback: Mutex<Option<CompletionCallback>>,
+}
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| Registry {
+        // 0 is never handed out, so hosts can use it as "no job".
+        next_handle: AtomicU64::new(1),
+        jobs: Mutex::new(HashMap::new()),
+        callback: Mutex::new(None),
+    })
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // A panic inside a job never holds these locks, so recover rather than
+    // propagate poisoning across the FFI boundary.
+    m.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn set_callback(cb: Option<CompletionCallback>) {
+    *lock(&registry().callback) = cb;
+}
+
+/// Runs `job` on a worker thread and returns its handle. If the thread
+/// cannot be spawned the job completes at once with an error result, so the
+/// caller learns of it through its callback or `poll` like any other result.
+pub(crate) fn spawn_job<F>(job: F) -> u64
+where
+    F: FnOnce() -> String + Send + 'static,
+{
+    let reg = registry();
+    let handle = reg.next_handle.fetch_add(1, Ordering::Relaxed);
+    let token = CancelToken::new();
+    lock(&reg.jobs).insert(handle, JobState::Pending(token.clone()));
+
+    let spawned = std::thread::Builder::new()
+        .name(format!("icp-async-{handle}"))
+        .spawn(move || {
+            let job = || cancellation::with_token(&token, job);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job))
+                .unwrap_or_else(|_| {
+                    serde_json::json!({"ok": false, "error": "async job panicked"}).to_string()
+                });
+            complete(handle, result);
+        });
+    if let Err(e) = spawned {
+        start_failed(handle, &e);
+    }
+    handle
+}
+
+fn start_failed(handle: u64, error: &std::io::Error) {
+    logging::log(
+        LogLevel::Error,
+        LogCategory::Engine,
+        &format!("failed to spawn a thread for async job {handle}: {error}"),
+    );
+    let result = serde_json::json!({
+        "ok": false,
+        "error": format!("failed to start async job: {error}"),
+    });
+    complete(handle, result.to_string());
+}
+
+fn complete(handle: u64, result: String) {
+    let reg = registry();
+    let callback = *lock(&reg.callback);
+    let mut jobs = lock(&reg.jobs);
+    match jobs.remove(&handle) {
+        Some(JobState::Cancelled) | None => {}
+        Some(_) => match callback {
+            Some(cb) => {
+                drop(jobs);
+                cb(handle, into_cstring_ptr(result));
+            }
+            None => {
+                jobs.insert(handle, JobState::Done(result));
+            }
+        },
+    }
+}
+
+/// Collects a finished result (removing the job) or reports it still pending.
+pub(crate) fn poll(handle: u64) -> Poll {
+    let mut jobs = lock(&registry().jobs);
+    match jobs.get(&handle) {
+        Some(JobState::Pending(_)) => Poll::Pending,
+        Some(JobState::Done(_)) => match jobs.remove(&handle) {
+            Some(JobState::Done(result)) => Poll::Done(result),
+            _ => Poll::Unknown,
+        },
+        Some(JobState::Cancelled) | None => Poll::Unknown,
+    }
+}
+
+/// Cancels a job: trips its token so the work stops at its next check, and
+/// discards its result. Returns false for unknown or already-collected
+/// handles.
+pub(crate) fn cancel(handle: u64) -> bool {
+    let mut jobs = lock(&registry().jobs);
+    match jobs.get_mut(&handle) {
+        Some(state @ JobState::Pending(_)) => {
+            if let JobState::Pending(token) = std::mem::replace(state, JobState::Cancelled) {
+                token.cancel();
+            }
+            true
+        }
+        Some(JobState::Done(_)) => {
+            jobs.remove(&handle);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn wait_for(handle: u64) -> String {
+        for _ in 0..500 {
+            match poll(handle) {
+                Poll::Done(s) => return s,
+                Poll::Pending => std::thread::sleep(Duration::from_millis(5)),
+                Poll::Unknown => panic!("handle {handle} vanished"),
+            }
+        }
+        panic!("job {handle} did not finish");
+    }
+
+    #[test]
+    fn test_poll_returns_result_once() {
+        let handle = spawn_job(|| "done".to_string());
+        assert_ne!(handle, 0);
+        assert_eq!(wait_for(handle), "done");
+        assert!(matches!(poll(handle), Poll::Unknown));
+    }
+
+    #[test]
+    fn test_cancelled_job_result_is_dropped() {
+        let (tx, rx) = mpsc::channel::<()>();
+        let handle = spawn_job(move || {
+            rx.recv().unwrap();
+            "late".to_string()
+        });
+        assert!(matches!(poll(handle), Poll::Pending));
+        assert!(cancel(handle));
+        tx.send(()).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(matches!(poll(handle), Poll::Unknown));
+        assert!(!cancel(handle));
+    }
+
+    #[test]
+    fn test_cancel_trips_the_job_token() {
+        let (tx, rx) = mpsc::channel::<bool>();
+        let handle = spawn_job(move || {
+            while !cancellation::current().is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            tx.send(true).unwrap();
+            "stopped".to_string()
+        });
+        assert!(cancel(handle));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok(true));
+    }
+
+    #[test]
+    fn test_job_that_cannot_start_reports_error() {
+        let handle = registry().next_handle.fetch_add(1, Ordering::Relaxed);
+        lock(&registry().jobs).insert(handle, JobState::Pending(CancelToken::new()));
+        start_failed(handle, &std::io::Error::other("no threads left"));
+        let out = wait_for(handle);
+        assert!(out.contains("no threads left"), "got: {out}");
+    }
+
+    #[test]
+    fn test_panicking_job_reports_error() {
+        let handle = spawn_job(|| panic!("boom"));
+        let out = wait_for(handle);
+        assert!(out.contains("panicked"), "got: {out}");
+    }
+}
@@ -0,0 +1,91 @@
+//! Identity persistence helpers.
+//!
+//! An [`IdentityData`] is the serializable form of a keypair together with the
+//! algorithm it belongs to. The app keeps identities on disk only in the
+//! encrypted container produced by [`export_encrypted_identity`]; PEM
+//! import/export exists so developers can reuse their dfx identities.
+
+use crate::keypair::KeypairData;
+use crate::principal::principal_from_public_key;
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use serde::{Deserialize, Serialize};
+
+pub mod delegation;
+pub mod encrypted;
+pub mod ledger;
+pub mod pem;
+pub mod signer;
+
+pub use delegation::{
+    create_delegation, create_delegation_chain, Delegation, DelegationChain, SignedDelegation,
+};
+pub use encrypted::{
+    export_encrypted_identity, import_encrypted_identity, IDENTITY_FORMAT_MAGIC,
+    IDENTITY_FORMAT_VERSION,
+};
+pub use pem::{export_pem_identity, import_pem_identity};
+pub use signer::Signer;
+
+/// Key algorithm of a stored identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyAlgorithm {
+    Ed25519,
+    Secp256k1,
+}
+
+impl KeyAlgorithm {
+    /// Algorithm name as accepted by [`principal_from_public_key`].
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeyAlgorithm::Ed25519 => "ed25519",
+            KeyAlgorithm::Secp256k1 => "secp256k1",
+        }
+    }
+
+    pub fn parse(alg: &str) -> Result<Self, String> {
+        match alg {
+            "ed25519" => Ok(KeyAlgorithm::Ed25519),
+            "secp256k1" => Ok(KeyAlgorithm::Secp256k1),
+            _ => Err(format!("unsupported algorithm: {alg}")),
+        }
+    }
+}
+
+/// A keypair plus its algorithm, in the base64 form shared with Dart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityData {
+    pub algorithm: KeyAlgorithm,
+    pub public_key_b64: String,
+    pub private_key_b64: String,
+    pub principal_text: String,
+}
+
+impl IdentityData {
+    pub fn from_keypair(algorithm: KeyAlgorithm, keypair: &KeypairData) -> Self {
+        Self {
+            algorithm,
+            public_key_b64: keypair.public_key_b64.clone(),
+            private_key_b64: keypair.private_key_b64.clone(),
+            principal_text: keypair.principal_text.clone(),
+        }
+    }
+
+    /// Checks that `principal_text` is the self-authenticating principal of
+    /// `public_key_b64`. Every import path runs this so a tampered or
+    /// mismatched record is rejected instead of silently loaded.
+    pub fn validate(&self) -> Result<(), String> {
+        let public_key = B64
+            .decode(&self.public_key_b64)
+            .map_err(|e| format!("Invalid base64 public key: {}", e))?;
+        let derived = principal_from_public_key(self.algorithm.as_str(), &public_key)
+            .ok_or_else(|| format!("Invalid {} public key", self.algorithm.as_str()))?;
+        if derived != self.principal_text {
+            return Err(format!(
+                "Principal mismatch: record says {}, public key derives {}",
+                self.principal_text, derived
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,516 @@
+//! Delegation chains (Internet Identity style).
+//!
+//! A chain starts at a root public key (e.g. the II anchor's canister
+//! signature key), and each link delegates signing authority to the next
+//! public key until an expiration time, optionally restricted to a set of
+//! target canisters. The last link delegates to the session key that signs
+//! the actual requests; the sender principal is derived from the root key.
+//!
+//! Signatures over a delegation follow the IC interface spec: the signed
+//! message is `"\x1Aic-request-auth-delegation" || representation_independent_hash(delegation)`.
+//!
+//! The JSON shape matches agent-js `DelegationChain.toJSON()` (hex-encoded
+//! blobs, hex expiration, targets as hex principal bytes), so chains
+//! produced by II in a browser can be handed to icp_core unchanged.
+
+use super::signer::Signer;
+use crate::principal::{der_encode_public_key, principal_from_der};
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const DELEGATION_DOMAIN_SEPARATOR: &[u8] = b"\x1Aic-request-auth-delegation";
+
+/// The replica rejects chains longer than this.
+pub const MAX_DELEGATION_CHAIN_LEN: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegation {
+    /// DER-encoded public key the authority is delegated to.
+    #[serde(with = "hex_bytes")]
+    pub pubkey: Vec<u8>,
+    /// Expiration in nanoseconds since the Unix epoch.
+    #[serde(with = "hex_u64")]
+    pub expiration: u64,
+    /// Canisters the delegation is restricted to; `None` means any.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "hex_principals"
+    )]
+    pub targets: Option<Vec<Principal>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedDelegation {
+    pub delegation: Delegation,
+    #[serde(with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationChain {
+    pub delegations: Vec<SignedDelegation>,
+    /// DER-encoded root public key; the sender principal derives from this.
+    #[serde(with = "hex_bytes")]
+    pub public_key: Vec<u8>,
+}
+
+impl Delegation {
+    /// Representation-independent hash of the delegation map
+    /// (`pubkey`, `expiration`, optional `targets`).
+    pub fn hash(&self) -> Result<[u8; 32], String> {
+        let mut fields: Vec<Vec<u8>> = vec![
+            field_hash("pubkey", &sha256(&self.pubkey)),
+            field_hash("expiration", &sha256(&leb128(self.expiration))),
+        ];
+        if let Some(targets) = &self.targets {
+            let mut concat = Vec::with_capacity(targets.len() * 32);
+            for target in targets {
+                concat.extend_from_slice(&sha256(target.as_slice()));
+            }
+            fields.push(field_hash("targets", &sha256(&concat)));
+        }
+        fields.sort();
+        Ok(sha256(&fields.concat()))
+    }
+
+    /// Bytes that the delegating key signs.
+    pub fn signing_message(&self) -> Result<Vec<u8>, String> {
+        let mut msg = DELEGATION_DOMAIN_SEPARATOR.to_vec();
+        msg.extend_from_slice(&self.hash()?);
+        Ok(msg)
+    }
+
+    pub fn is_expired(&self, now_ns: u64) -> bool {
+        self.expiration <= now_ns
+    }
+}
+
+impl DelegationChain {
+    /// Principal on whose behalf the session key acts.
+    pub fn sender_principal(&self) -> String {
+        principal_from_der(&self.public_key)
+    }
+
+    /// Earliest expiration across all links; the chain is unusable after it.
+    pub fn expiration(&self) -> Option<u64> {
+        self.delegations
+            .iter()
+            .map(|d| d.delegation.expiration)
+            .min()
+    }
+
+    /// DER public key of the session key at the end of the chain.
+    pub fn session_public_key(&self) -> Option<&[u8]> {
+        self.delegations
+            .last()
+            .map(|d| d.delegation.pubkey.as_slice())
+    }
+
+    /// Whether the chain permits calls to `canister_id`: every link that
+    /// restricts targets must list it. Targets compare as principal bytes,
+    /// so an unparsable `canister_id` is never permitted by a restricted link.
+    pub fn permits_target(&self, canister_id: &str) -> bool {
+        let canister = Principal::from_text(canister_id).ok();
+        self.delegations
+            .iter()
+            .all(|d| match &d.delegation.targets {
+                Some(targets) => canister.is_some_and(|c| targets.contains(&c)),
+                None => true,
+            })
+    }
+
+    /// Verifies expiry, chain length, and every link's signature, using the
+    /// built-in Ed25519/secp256k1 verifier.
+    pub fn verify(&self, now_ns: u64) -> Result<(), String> {
+        self.verify_with(now_ns, verify_der_signature)
+    }
+
+    /// Like [`DelegationChain::verify`] but with a caller-supplied signature
+    /// verifier `(der_public_key, message, signature)`. II roots sign with
+    /// canister signatures, which need certificate verification that this
+    /// crate does not implement; callers that accept II chains plug it in here.
+    pub fn verify_with<F>(&self, now_ns: u64, verifier: F) -> Result<(), String>
+    where
+        F: Fn(&[u8], &[u8], &[u8]) -> Result<(), String>,
+    {
+        if self.delegations.is_empty() {
+            return Err("Delegation chain is empty".to_string());
+        }
+        if self.delegations.len() > MAX_DELEGATION_CHAIN_LEN {
+            return Err(format!(
+                "Delegation chain too long: {} links (max {})",
+                self.delegations.len(),
+                MAX_DELEGATION_CHAIN_LEN
+            ));
+        }
+        let mut signer = self.public_key.as_slice();
+        for (i, link) in self.delegations.iter().enumerate() {
+            if link.delegation.is_expired(now_ns) {
+                return Err(format!(
+                    "Delegation {} expired at {} (now {})",
+                    i, link.delegation.expiration, now_ns
+                ));
+            }
+            let msg = link.delegation.signing_message()?;
+            verifier(signer, &msg, &link.signature)
+                .map_err(|e| format!("Delegation {} signature invalid: {}", i, e))?;
+            signer = &link.delegation.pubkey;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a delegation from `delegator` to `session_public_key_der`.
+/// `delegator` is any [`Signer`]: an in-memory identity or a hardware wallet.
+pub fn create_delegation(
+    delegator: &dyn Signer,
+    session_public_key_der: &[u8],
+    expiration_ns: u64,
+    targets: Option<Vec<Principal>>,
+) -> Result<SignedDelegation, String> {
+    let delegation = Delegation {
+        pubkey: session_public_key_der.to_vec(),
+        expiration: expiration_ns,
+        targets,
+    };
+    let signature = delegator.sign(&delegation.signing_message()?)?;
+    Ok(SignedDelegation {
+        delegation,
+        signature,
+    })
+}
+
+/// Starts a single-link chain rooted at `delegator`.
+pub fn create_delegation_chain(
+    delegator: &dyn Signer,
+    session_public_key_der: &[u8],
+    expiration_ns: u64,
+    targets: Option<Vec<Principal>>,
+) -> Result<DelegationChain, String> {
+    let root_der = der_encode_public_key(delegator.algorithm().as_str(), &delegator.public_key()?)?;
+    let link = create_delegation(delegator, session_public_key_der, expiration_ns, targets)?;
+    Ok(DelegationChain {
+        delegations: vec![link],
+        public_key: root_der,
+    })
+}
+
+/// Verifies `signature` over `message` for a DER-encoded Ed25519 or
+/// secp256k1 public key (the algorithms `create_delegation` signs with).
+pub fn verify_der_signature(
+    public_key_der: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), String> {
+    use ed25519_dalek::pkcs8::DecodePublicKey as _;
+    use k256::ecdsa::signature::Verifier as _;
+
+    if let Ok(key) = ed25519_dalek::VerifyingKey::from_public_key_der(public_key_der) {
+        let sig = ed25519_dalek::Signature::from_slice(signature)
+            .map_err(|e| format!("Invalid Ed25519 signature: {}", e))?;
+        return key
+            .verify_strict(message, &sig)
+            .map_err(|_| "Ed25519 signature verification failed".to_string());
+    }
+    if let Ok(key) = k256::ecdsa::VerifyingKey::from_public_key_der(public_key_der) {
+        let sig = k256::ecdsa::Signature::from_slice(signature)
+            .map_err(|e| format!("Invalid secp256k1 signature: {}", e))?;
+        return key
+            .verify(message, &sig)
+            .map_err(|_| "secp256k1 signature verification failed".to_string());
+    }
+    Err(
+        "Unsupported public key type (only Ed25519 and secp256k1 are verified natively)"
+            .to_string(),
+    )
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+fn field_hash(key: &str, value_hash: &[u8; 32]) -> Vec<u8> {
+    let mut out = sha256(key.as_bytes()).to_vec();
+    out.extend_from_slice(value_hash);
+    out
+}
+
+fn leb128(mut n: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(d)?;
+        hex::decode(s).map_err(serde::de::Error::custom)
+    }
+}
+
+mod hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(n: &u64, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&format!("{:x}", n))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(d)?;
+        u64::from_str_radix(&s, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+/// agent-js writes targets with `Principal.toHex()` (upper-case hex of the
+/// principal bytes); either case is accepted on input.
+mod hex_principals {
+    use candid::Principal;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        targets: &Option<Vec<Principal>>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        match targets {
+            Some(targets) => s.collect_seq(targets.iter().map(|p| hex::encode_upper(p.as_slice()))),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<Vec<Principal>>, D::Error> {
+        let Some(hexes) = Option::<Vec<String>>::deserialize(d)? else {
+            return Ok(None);
+        };
+        hexes
+            .iter()
+            .map(|h| {
+                let bytes = hex::decode(h).map_err(serde::de::Error::custom)?;
+                Principal::try_from_slice(&bytes).map_err(serde::de::Error::custom)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::{IdentityData, KeyAlgorithm};
+    use crate::{generate_ed25519_keypair, generate_secp256k1_keypair};
+    use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+    use std::cell::Cell;
+
+    const NOW: u64 = 1_700_000_000_000_000_000;
+    const HOUR_NS: u64 = 3_600_000_000_000;
+
+    fn identity(alg: KeyAlgorithm, mnemonic: &str) -> IdentityData {
+        let keypair = match alg {
+            KeyAlgorithm::Ed25519 => generate_ed25519_keypair(Some(mnemonic.to_string())),
+            KeyAlgorithm::Secp256k1 => generate_secp256k1_keypair(Some(mnemonic.to_string())),
+        };
+        IdentityData::from_keypair(alg, &keypair)
+    }
+
+    fn root() -> IdentityData {
+        identity(
+            KeyAlgorithm::Ed25519,
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        )
+    }
+
+    fn session() -> IdentityData {
+        identity(
+            KeyAlgorithm::Ed25519,
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+        )
+    }
+
+    fn der(identity: &IdentityData) -> Vec<u8> {
+        der_encode_public_key(
+            identity.algorithm.as_str(),
+            &B64.decode(&identity.public_key_b64).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_leb128_encoding() {
+        assert_eq!(leb128(0), vec![0x00]);
+        assert_eq!(leb128(127), vec![0x7f]);
+        assert_eq!(leb128(624_485), vec![0xe5, 0x8e, 0x26]);
+    }
+
+    #[test]
+    fn test_chain_roundtrip_and_verify() {
+        let root = root();
+        let chain = create_delegation_chain(&root, &der(&session()), NOW + HOUR_NS, None).unwrap();
+
+        assert_eq!(chain.sender_principal(), root.principal_text);
+        assert_eq!(chain.session_public_key(), Some(der(&session()).as_slice()));
+        chain.verify(NOW).unwrap();
+
+        let json = serde_json::to_string(&chain).unwrap();
+        assert!(json.contains("\"publicKey\""), "got: {json}");
+        let parsed: DelegationChain = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, chain);
+        parsed.verify(NOW).unwrap();
+    }
+
+    #[test]
+    fn test_secp256k1_root_verifies() {
+        let root = identity(
+            KeyAlgorithm::Secp256k1,
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        );
+        let chain = create_delegation_chain(&root, &der(&session()), NOW + HOUR_NS, None).unwrap();
+        chain.verify(NOW).unwrap();
+    }
+
+    #[test]
+    fn test_expired_chain_rejected() {
+        let chain =
+            create_delegation_chain(&root(), &der(&session()), NOW + HOUR_NS, None).unwrap();
+        let err = chain.verify(NOW + HOUR_NS).unwrap_err();
+        assert!(err.contains("expired"), "got: {err}");
+    }
+
+    #[test]
+    fn test_tampered_delegation_rejected() {
+        let mut chain =
+            create_delegation_chain(&root(), &der(&session()), NOW + HOUR_NS, None).unwrap();
+        chain.delegations[0].delegation.expiration += 1;
+        let err = chain.verify(NOW).unwrap_err();
+        assert!(err.contains("signature invalid"), "got: {err}");
+    }
+
+    #[test]
+    fn test_two_link_chain_requires_correct_signer() {
+        let root = root();
+        let middle = session();
+        let leaf = identity(
+            KeyAlgorithm::Ed25519,
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+        );
+        let mut chain =
+            create_delegation_chain(&root, &der(&middle), NOW + 2 * HOUR_NS, None).unwrap();
+        chain
+            .delegations
+            .push(create_delegation(&middle, &der(&leaf), NOW + HOUR_NS, None).unwrap());
+        chain.verify(NOW).unwrap();
+        assert_eq!(chain.expiration(), Some(NOW + HOUR_NS));
+
+        // Second link signed by the root instead of the delegated middle key.
+        chain.delegations[1] = create_delegation(&root, &der(&leaf), NOW + HOUR_NS, None).unwrap();
+        assert!(chain.verify(NOW).is_err());
+    }
+
+    #[test]
+    fn test_targets_restrict_and_are_signed() {
+        let targets = Some(vec![
+            Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap()
+        ]);
+        let chain =
+            create_delegation_chain(&root(), &der(&session()), NOW + HOUR_NS, targets).unwrap();
+        chain.verify(NOW).unwrap();
+        assert!(chain.permits_target("ryjl3-tyaaa-aaaaa-aaaba-cai"));
+        assert!(!chain.permits_target("rrkah-fqaaa-aaaaa-aaaaq-cai"));
+
+        let mut widened = chain.clone();
+        widened.delegations[0].delegation.targets = None;
+        assert!(widened.verify(NOW).is_err());
+    }
+
+    /// `DelegationChain.toJSON()` output from agent-js for a root → session
+    /// delegation restricted to the ICP ledger canister.
+    const AGENT_JS_CHAIN_WITH_TARGETS: &str = r#"{
+        "delegations": [{
+            "delegation": {
+                "expiration": "1797a04466e2a000",
+                "pubkey": "302a300506032b6570032100f0ca10c39e1e06b25f42d654a0d490b79799f4b784b1e1f144a62fdb3872cb9f",
+                "targets": ["00000000000000020101"]
+            },
+            "signature": "2fce7cd10a8bdd084e71b93bb878d082eca42004ede0375454f4d591da951430f607da798c34145c67669329b6313456cd37d8a1baa27c22127a767c17789c0a"
+        }],
+        "publicKey": "302a300506032b6570032100c6f2ac5598970c79633714d3eb5c34d7bfc3e92da58c7354b37996d9a4af3ab2"
+    }"#;
+
+    #[test]
+    fn test_agent_js_targets_roundtrip() {
+        let chain: DelegationChain = serde_json::from_str(AGENT_JS_CHAIN_WITH_TARGETS).unwrap();
+        chain.verify(NOW).unwrap();
+        assert!(chain.permits_target("ryjl3-tyaaa-aaaaa-aaaba-cai"));
+        assert!(!chain.permits_target("rrkah-fqaaa-aaaaa-aaaaq-cai"));
+        assert!(!chain.permits_target("not a principal"));
+
+        let ledger = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let ours =
+            create_delegation_chain(&root(), &der(&session()), NOW + HOUR_NS, Some(vec![ledger]))
+                .unwrap();
+        assert_eq!(ours, chain);
+
+        let fixture: serde_json::Value = serde_json::from_str(AGENT_JS_CHAIN_WITH_TARGETS).unwrap();
+        assert_eq!(serde_json::to_value(&chain).unwrap(), fixture);
+    }
+
+    #[test]
+    fn test_empty_chain_rejected() {
+        let chain = DelegationChain {
+            delegations: vec![],
+            public_key: der(&root()),
+        };
+        assert!(chain.verify(NOW).is_err());
+    }
+
+    /// A signer that is not an `IdentityData`, standing in for a hardware
+    /// wallet; counts the signatures it makes.
+    struct CountingSigner {
+        inner: IdentityData,
+        signed: Cell<usize>,
+    }
+
+    impl Signer for CountingSigner {
+        fn algorithm(&self) -> KeyAlgorithm {
+            self.inner.algorithm()
+        }
+
+        fn public_key(&self) -> Result<Vec<u8>, String> {
+            self.inner.public_key()
+        }
+
+        fn sign(&self, message: &[u8]) -> Result<Vec<u8>, String> {
+            self.signed.set(self.signed.get() + 1);
+            self.inner.sign(message)
+        }
+    }
+
+    #[test]
+    fn test_chain_from_any_signer_verifies() {
+        let signer = CountingSigner {
+            inner: root(),
+            signed: Cell::new(0),
+        };
+        let chain =
+            create_delegation_chain(&signer, &der(&session()), NOW + HOUR_NS, None).unwrap();
+        assert_eq!(signer.signed.get(), 1);
+        assert_eq!(chain.sender_principal(), root().principal_text);
+        chain.verify(NOW).unwrap();
+    }
+}
@@ -0,0 +1,176 @@
+//! Password-encrypted identity container.
+//!
+//! Layout (all fields fixed-size except the ciphertext):
+//!
+//! ```text
+//! magic "ICPID" | version u8 | salt [16] | nonce [12] | AES-256-GCM ciphertext
+//! ```
+//!
+//! The key is derived with the same Argon2id parameters as the vault
+//! (see [`crate::vault::derive_key`]). The whole header is bound as AES-GCM
+//! associated data, so a flipped version byte or swapped salt fails
+//! authentication rather than decrypting with the wrong parameters.
+
+use super::IdentityData;
+use crate::vault::{derive_key, generate_nonce, generate_salt, NONCE_LEN, SALT_LEN};
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+
+pub const IDENTITY_FORMAT_MAGIC: &[u8; 5] = b"ICPID";
+pub const IDENTITY_FORMAT_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = IDENTITY_FORMAT_MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+/// Serializes `identity` and encrypts it under `password`.
+pub fn export_encrypted_identity(
+    identity: &IdentityData,
+    password: &str,
+) -> Result<Vec<u8>, String> {
+    if password.is_empty() {
+        return Err("Password must not be empty".to_string());
+    }
+    identity.validate()?;
+
+    let salt = generate_salt();
+    let nonce_bytes = generate_nonce();
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(IDENTITY_FORMAT_MAGIC);
+    header.push(IDENTITY_FORMAT_VERSION);
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce_bytes);
+
+    let plaintext = serde_json::to_vec(identity)
+        .map_err(|e| format!("Identity serialization failed: {}", e))?;
+
+    let key = derive_key(password, &salt)?;
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Cipher init failed: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce_bytes),
+            Payload {
+                msg: &plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|e| format!("Encryption failed: {}", e))?;
+
+    let mut out = header;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypts a container produced by [`export_encrypted_identity`].
+pub fn import_encrypted_identity(data: &[u8], password: &str) -> Result<IdentityData, String> {
+    if data.len() <= HEADER_LEN {
+        return Err("Encrypted identity is truncated".to_string());
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    let (magic, rest) = header.split_at(IDENTITY_FORMAT_MAGIC.len());
+    if magic != IDENTITY_FORMAT_MAGIC {
+        return Err("Not an encrypted identity (bad magic)".to_string());
+    }
+    let version = rest[0];
+    if version != IDENTITY_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported encrypted identity version: {} (expected {})",
+            version, IDENTITY_FORMAT_VERSION
+        ));
+    }
+    let salt = &rest[1..1 + SALT_LEN];
+    let nonce_bytes = &rest[1 + SALT_LEN..];
+
+    let key = derive_key(password, salt)?;
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Cipher init failed: {}", e))?;
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce_bytes),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| "Decryption failed: invalid password or corrupted data".to_string())?;
+
+    let identity: IdentityData = serde_json::from_slice(&plaintext)
+        .map_err(|e| format!("Invalid identity payload: {}", e))?;
+    identity.validate()?;
+    Ok(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::KeyAlgorithm;
+    use crate::{generate_ed25519_keypair, generate_secp256k1_keypair};
+
+    fn ed25519_identity() -> IdentityData {
+        IdentityData::from_keypair(KeyAlgorithm::Ed25519, &generate_ed25519_keypair(None))
+    }
+
+    #[test]
+    fn test_roundtrip_both_algorithms() {
+        for identity in [
+            ed25519_identity(),
+            IdentityData::from_keypair(KeyAlgorithm::Secp256k1, &generate_secp256k1_keypair(None)),
+        ] {
+            let blob = export_encrypted_identity(&identity, "pw").unwrap();
+            assert_eq!(&blob[..5], IDENTITY_FORMAT_MAGIC);
+            assert_eq!(blob[5], IDENTITY_FORMAT_VERSION);
+            let restored = import_encrypted_identity(&blob, "pw").unwrap();
+            assert_eq!(restored, identity);
+        }
+    }
+
+    #[test]
+    fn test_wrong_password_fails() {
+        let blob = export_encrypted_identity(&ed25519_identity(), "right").unwrap();
+        let err = import_encrypted_identity(&blob, "wrong").unwrap_err();
+        assert!(err.contains("invalid password"), "got: {err}");
+    }
+
+    #[test]
+    fn test_header_is_authenticated() {
+        let mut blob = export_encrypted_identity(&ed25519_identity(), "pw").unwrap();
+        // Flip a salt byte: the header is AAD, so this must not decrypt.
+        blob[6] ^= 0x01;
+        assert!(import_encrypted_identity(&blob, "pw").is_err());
+    }
+
+    #[test]
+    fn test_rejects_unknown_version_and_magic() {
+        let blob = export_encrypted_identity(&ed25519_identity(), "pw").unwrap();
+
+        let mut bad_version = blob.clone();
+        bad_version[5] = 99;
+        let err = import_encrypted_identity(&bad_version, "pw").unwrap_err();
+        assert!(
+            err.contains("Unsupported encrypted identity version"),
+            "got: {err}"
+        );
+
+        let mut bad_magic = blob;
+        bad_magic[0] = b'X';
+        let err = import_encrypted_identity(&bad_magic, "pw").unwrap_err();
+        assert!(err.contains("bad magic"), "got: {err}");
+
+        assert!(import_encrypted_identity(b"ICPID", "pw").is_err());
+    }
+
+    #[test]
+    fn test_export_rejects_mismatched_principal() {
+        let mut identity = ed25519_identity();
+        identity.principal_text = "aaaaa-aa".to_string();
+        let err = export_encrypted_identity(&identity, "pw").unwrap_err();
+        assert!(err.contains("Principal mismatch"), "got: {err}");
+    }
+
+    #[test]
+    fn test_export_rejects_empty_password() {
+        assert!(export_encrypted_identity(&ed25519_identity(), "").is_err());
+    }
+}
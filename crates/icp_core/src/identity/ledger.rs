@@ -0,0 +1,344 @@
+//! Ledger hardware wallet signer (ICP app by Zondax).
+//!
+//! The APDU and HID framing below has no dependencies so it is always built
+//! and unit-tested; only [`HidTransport`], which talks to a real device via
+//! `hidapi`, sits behind the `ledger` feature.
+//!
+//! The ICP app only signs secp256k1, at `m/44'/223'/0'/0/<index>`, and it
+//! parses what it signs: it shows a canister call on screen before the user
+//! approves. Payloads the app cannot parse are rejected by the device with a
+//! non-`0x9000` status, which surfaces here as an error.
+
+use super::signer::Signer;
+use super::KeyAlgorithm;
+
+const CLA_ICP: u8 = 0x11;
+const INS_GET_ADDR: u8 = 0x01;
+const INS_SIGN: u8 = 0x02;
+const P1_SIGN_INIT: u8 = 0x00;
+const P1_SIGN_ADD: u8 = 0x01;
+const P1_SIGN_LAST: u8 = 0x02;
+const SIGN_CHUNK_LEN: usize = 250;
+const SW_OK: u16 = 0x9000;
+const HARDENED: u32 = 0x8000_0000;
+
+pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+const HID_PACKET_LEN: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+
+/// Moves one APDU to the device and returns the response including the
+/// trailing two status-word bytes.
+pub trait LedgerTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+pub struct LedgerSigner<T: LedgerTransport> {
+    transport: T,
+    account_index: u32,
+}
+
+impl<T: LedgerTransport> LedgerSigner<T> {
+    pub fn new(transport: T, account_index: u32) -> Self {
+        Self {
+            transport,
+            account_index,
+        }
+    }
+
+    fn derivation_path(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(20);
+        for component in [
+            44 | HARDENED,
+            223 | HARDENED,
+            HARDENED,
+            0,
+            self.account_index,
+        ] {
+            out.extend_from_slice(&component.to_le_bytes());
+        }
+        out
+    }
+
+    fn send(&self, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>, String> {
+        let apdu = encode_apdu(ins, p1, p2, data)?;
+        let response = self.transport.exchange(&apdu)?;
+        split_status(response)
+    }
+}
+
+impl<T: LedgerTransport> Signer for LedgerSigner<T> {
+    fn algorithm(&self) -> KeyAlgorithm {
+        KeyAlgorithm::Secp256k1
+    }
+
+    fn public_key(&self) -> Result<Vec<u8>, String> {
+        let response = self.send(INS_GET_ADDR, 0x00, 0x00, &self.derivation_path())?;
+        // 65-byte uncompressed key, then principal and address bytes.
+        if response.len() < 65 || response[0] != 0x04 {
+            return Err("Ledger returned a malformed public key".to_string());
+        }
+        Ok(response[..65].to_vec())
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, String> {
+        self.send(INS_SIGN, P1_SIGN_INIT, 0x00, &self.derivation_path())?;
+        let chunks: Vec<&[u8]> = message.chunks(SIGN_CHUNK_LEN).collect();
+        if chunks.is_empty() {
+            return Err("Refusing to sign an empty message".to_string());
+        }
+        let mut response = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let p1 = if i + 1 == chunks.len() {
+                P1_SIGN_LAST
+            } else {
+                P1_SIGN_ADD
+            };
+            response = self.send(INS_SIGN, p1, 0x00, chunk)?;
+        }
+        // 32-byte pre-sign hash, 64-byte r||s, then the DER signature.
+        if response.len() < 96 {
+            return Err(format!(
+                "Ledger signature response too short: {} bytes",
+                response.len()
+            ));
+        }
+        Ok(response[32..96].to_vec())
+    }
+}
+
+fn encode_apdu(ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>, String> {
+    let lc = u8::try_from(data.len())
+        .map_err(|_| format!("APDU data too long: {} bytes", data.len()))?;
+    let mut apdu = vec![CLA_ICP, ins, p1, p2, lc];
+    apdu.extend_from_slice(data);
+    Ok(apdu)
+}
+
+fn split_status(mut response: Vec<u8>) -> Result<Vec<u8>, String> {
+    if response.len() < 2 {
+        return Err("Ledger response missing status word".to_string());
+    }
+    let sw_bytes = response.split_off(response.len() - 2);
+    let sw = u16::from_be_bytes([sw_bytes[0], sw_bytes[1]]);
+    if sw != SW_OK {
+        return Err(format!("Ledger error: {}", status_message(sw)));
+    }
+    Ok(response)
+}
+
+fn status_message(sw: u16) -> String {
+    match sw {
+        0x6986 => "transaction rejected on device".to_string(),
+        0x6984 => "data is invalid (the ICP app could not parse the payload)".to_string(),
+        0x6e00 | 0x6e01 => "ICP app is not open on the device".to_string(),
+        0x5515 => "device is locked".to_string(),
+        other => format!("status 0x{:04x}", other),
+    }
+}
+
+/// Splits an APDU into 64-byte HID report payloads.
+pub fn hid_frame(apdu: &[u8]) -> Vec<[u8; HID_PACKET_LEN]> {
+    let mut stream = Vec::with_capacity(apdu.len() + 2);
+    stream.extend_from_slice(&(apdu.len() as u16).to_be_bytes());
+    stream.extend_from_slice(apdu);
+
+    let mut packets = Vec::new();
+    for (seq, chunk) in (0u16..).zip(stream.chunks(HID_PACKET_LEN - 5)) {
+        let mut packet = [0u8; HID_PACKET_LEN];
+        packet[..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+        packet[2] = HID_TAG_APDU;
+        packet[3..5].copy_from_slice(&seq.to_be_bytes());
+        packet[5..5 + chunk.len()].copy_from_slice(chunk);
+        packets.push(packet);
+    }
+    packets
+}
+
+/// Reassembles a response from HID reports; `read` yields one report per call.
+pub fn hid_unframe<F>(mut read: F) -> Result<Vec<u8>, String>
+where
+    F: FnMut() -> Result<[u8; HID_PACKET_LEN], String>,
+{
+    let mut expected_len: Option<usize> = None;
+    let mut out = Vec::new();
+    let mut seq: u16 = 0;
+    loop {
+        let packet = read()?;
+        if packet[..2] != HID_CHANNEL.to_be_bytes() || packet[2] != HID_TAG_APDU {
+            return Err("Unexpected HID packet header".to_string());
+        }
+        if u16::from_be_bytes([packet[3], packet[4]]) != seq {
+            return Err("HID packet out of sequence".to_string());
+        }
+        let payload = if seq == 0 {
+            expected_len = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+            &packet[7..]
+        } else {
+            &packet[5..]
+        };
+        out.extend_from_slice(payload);
+        let total = expected_len.unwrap_or(0);
+        if out.len() >= total {
+            out.truncate(total);
+            return Ok(out);
+        }
+        seq += 1;
+    }
+}
+
+#[cfg(feature = "ledger")]
+pub use hid::HidTransport;
+
+#[cfg(feature = "ledger")]
+mod hid {
+    use super::{hid_frame, hid_unframe, LedgerTransport, HID_PACKET_LEN, LEDGER_VENDOR_ID};
+
+    const READ_TIMEOUT_MS: i32 = 60_000;
+
+    /// USB HID transport to the first connected Ledger device.
+    pub struct HidTransport {
+        device: hidapi::HidDevice,
+    }
+
+    impl HidTransport {
+        pub fn open_first() -> Result<Self, String> {
+            let api = hidapi::HidApi::new().map_err(|e| format!("HID init failed: {}", e))?;
+            let info = api
+                .device_list()
+                .find(|d| d.vendor_id() == LEDGER_VENDOR_ID)
+                .ok_or_else(|| "No Ledger device connected".to_string())?;
+            let device = info
+                .open_device(&api)
+                .map_err(|e| format!("Failed to open Ledger device: {}", e))?;
+            Ok(Self { device })
+        }
+    }
+
+    impl LedgerTransport for HidTransport {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, String> {
+            for packet in hid_frame(apdu) {
+                // Leading 0x00 is the HID report id.
+                let mut report = Vec::with_capacity(HID_PACKET_LEN + 1);
+                report.push(0x00);
+                report.extend_from_slice(&packet);
+                self.device
+                    .write(&report)
+                    .map_err(|e| format!("HID write failed: {}", e))?;
+            }
+            hid_unframe(|| {
+                let mut buf = [0u8; HID_PACKET_LEN];
+                let n = self
+                    .device
+                    .read_timeout(&mut buf, READ_TIMEOUT_MS)
+                    .map_err(|e| format!("HID read failed: {}", e))?;
+                if n == 0 {
+                    return Err("Timed out waiting for Ledger (confirm on device?)".to_string());
+                }
+                Ok(buf)
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Replays canned responses and records the APDUs it was sent.
+    struct MockTransport {
+        responses: RefCell<Vec<Vec<u8>>>,
+        sent: RefCell<Vec<Vec<u8>>>,
+    }
+
+    impl MockTransport {
+        fn new(mut responses: Vec<Vec<u8>>) -> Self {
+            responses.reverse();
+            Self {
+                responses: RefCell::new(responses),
+                sent: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl LedgerTransport for &MockTransport {
+        fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, String> {
+            self.sent.borrow_mut().push(apdu.to_vec());
+            self.responses
+                .borrow_mut()
+                .pop()
+                .ok_or_else(|| "no canned response".to_string())
+        }
+    }
+
+    fn ok(mut data: Vec<u8>) -> Vec<u8> {
+        data.extend_from_slice(&[0x90, 0x00]);
+        data
+    }
+
+    #[test]
+    fn test_public_key_apdu_and_parse() {
+        let mut key = vec![0x04];
+        key.extend_from_slice(&[7u8; 64]);
+        let mut response = key.clone();
+        response.extend_from_slice(&[1u8; 29]);
+        let mock = MockTransport::new(vec![ok(response)]);
+        let signer = LedgerSigner::new(&mock, 3);
+
+        assert_eq!(signer.public_key().unwrap(), key);
+        let sent = mock.sent.borrow();
+        assert_eq!(&sent[0][..5], &[CLA_ICP, INS_GET_ADDR, 0, 0, 20]);
+        assert_eq!(&sent[0][5..9], &(44 | HARDENED).to_le_bytes());
+        assert_eq!(&sent[0][21..25], &3u32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_sign_chunks_and_extracts_rs() {
+        let mut response = vec![0xAAu8; 32];
+        response.extend_from_slice(&[0x55u8; 64]);
+        response.extend_from_slice(&[0x30, 0x44]);
+        let mock = MockTransport::new(vec![ok(vec![]), ok(vec![]), ok(response)]);
+        let signer = LedgerSigner::new(&mock, 0);
+
+        let message = vec![9u8; SIGN_CHUNK_LEN + 10];
+        assert_eq!(signer.sign(&message).unwrap(), vec![0x55u8; 64]);
+
+        let sent = mock.sent.borrow();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[0][2], P1_SIGN_INIT);
+        assert_eq!(sent[1][2], P1_SIGN_ADD);
+        assert_eq!(sent[1][4] as usize, SIGN_CHUNK_LEN);
+        assert_eq!(sent[2][2], P1_SIGN_LAST);
+        assert_eq!(sent[2][4], 10);
+    }
+
+    #[test]
+    fn test_device_rejection_is_reported() {
+        let mock = MockTransport::new(vec![ok(vec![]), vec![0x69, 0x86]]);
+        let signer = LedgerSigner::new(&mock, 0);
+        let err = signer.sign(b"payload").unwrap_err();
+        assert!(err.contains("rejected on device"), "got: {err}");
+    }
+
+    #[test]
+    fn test_hid_frame_roundtrip() {
+        let apdu: Vec<u8> = (0..200u16).map(|b| b as u8).collect();
+        let packets = hid_frame(&apdu);
+        assert_eq!(packets.len(), 4);
+        assert_eq!(&packets[0][..5], &[0x01, 0x01, 0x05, 0x00, 0x00]);
+        assert_eq!(&packets[0][5..7], &200u16.to_be_bytes());
+
+        let mut iter = packets.into_iter();
+        let restored = hid_unframe(|| iter.next().ok_or_else(|| "eof".to_string())).unwrap();
+        assert_eq!(restored, apdu);
+    }
+
+    #[test]
+    fn test_hid_unframe_rejects_out_of_sequence() {
+        let mut packets = hid_frame(&[0u8; 100]);
+        packets.swap(0, 1);
+        let mut iter = packets.into_iter();
+        assert!(hid_unframe(|| iter.next().ok_or_else(|| "eof".to_string())).is_err());
+    }
+}
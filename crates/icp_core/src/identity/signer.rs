@@ -0,0 +1,76 @@
+//! Pluggable signing backends.
+//!
+//! Delegation links ([`super::create_delegation`]) are signed through
+//! [`Signer`], so a hardware wallet such as [`super::ledger::LedgerSigner`]
+//! can stand in for an in-memory key without the caller knowing which one it
+//! has. Marketplace uploads and canister calls still sign with the raw
+//! private key (see TODO.md).
+
+use super::{IdentityData, KeyAlgorithm};
+use crate::keypair::{sign_ed25519, sign_secp256k1};
+use crate::principal::principal_from_public_key;
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+
+pub trait Signer {
+    fn algorithm(&self) -> KeyAlgorithm;
+
+    /// Raw public key: 32 bytes for Ed25519, 65-byte uncompressed SEC1 for
+    /// secp256k1 (same encoding as [`crate::KeypairData`]).
+    fn public_key(&self) -> Result<Vec<u8>, String>;
+
+    /// Signs `message`: Ed25519 over the raw message, secp256k1 ECDSA over
+    /// SHA-256(message). Returns the 64-byte signature.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, String>;
+
+    fn principal_text(&self) -> Result<String, String> {
+        let public_key = self.public_key()?;
+        principal_from_public_key(self.algorithm().as_str(), &public_key)
+            .ok_or_else(|| format!("Invalid {} public key", self.algorithm().as_str()))
+    }
+}
+
+/// Software signer backed by a private key held in memory.
+impl Signer for IdentityData {
+    fn algorithm(&self) -> KeyAlgorithm {
+        self.algorithm
+    }
+
+    fn public_key(&self) -> Result<Vec<u8>, String> {
+        B64.decode(&self.public_key_b64)
+            .map_err(|e| format!("Invalid base64 public key: {}", e))
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, String> {
+        let signature_b64 = match self.algorithm {
+            KeyAlgorithm::Ed25519 => sign_ed25519(message, &self.private_key_b64)?,
+            KeyAlgorithm::Secp256k1 => sign_secp256k1(message, &self.private_key_b64)?,
+        };
+        B64.decode(signature_b64)
+            .map_err(|e| format!("Invalid signature encoding: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::delegation::verify_der_signature;
+    use crate::principal::der_encode_public_key;
+    use crate::{generate_ed25519_keypair, generate_secp256k1_keypair};
+
+    #[test]
+    fn test_software_signer_signatures_verify() {
+        for (alg, keypair) in [
+            (KeyAlgorithm::Ed25519, generate_ed25519_keypair(None)),
+            (KeyAlgorithm::Secp256k1, generate_secp256k1_keypair(None)),
+        ] {
+            let identity = IdentityData::from_keypair(alg, &keypair);
+            let signer: &dyn Signer = &identity;
+            assert_eq!(signer.principal_text().unwrap(), keypair.principal_text);
+
+            let signature = signer.sign(b"upload payload").unwrap();
+            assert_eq!(signature.len(), 64);
+            let der = der_encode_public_key(alg.as_str(), &signer.public_key().unwrap()).unwrap();
+            verify_der_signature(&der, b"upload payload", &signature).unwrap();
+        }
+    }
+}
@@ -25,6 +25,11 @@ pub struct JsValidationResult {
 pub mod static_analysis {
     use super::{JsValidationContext, JsValidationResult};
 
+    /// Version of the validation rules. Bump it whenever a stage starts
+    /// rejecting scripts it used to accept: the marketplace re-validates
+    /// every published script when it changes.
+    pub const RULES_VERSION: u32 = 1;
+
     pub fn fresh_result(script: &str) -> JsValidationResult {
         JsValidationResult {
             is_valid: true,
@@ -614,21 +619,47 @@ pub mod static_analysis {
         }
     }
 
+    type Stage = fn(&str, &JsValidationContext, &mut JsValidationResult);
+
+    /// Static stages in execution order, named for progress reporting.
+    pub const STAGES: &[(&str, Stage)] = &[
+        ("basic", |s, _, r| validate_basic(s, r)),
+        ("event_handlers", |s, _, r| validate_event_handlers(s, r)),
+        ("security", validate_security_patterns),
+        ("esm_format", |s, _, r| validate_esm_format(s, r)),
+        ("intl", |s, _, r| validate_intl(s, r)),
+        ("icp_integration", validate_icp_integration),
+        ("performance", validate_performance_patterns),
+        ("data_structures", validate_data_structures),
+        ("ui_nodes", |s, _, r| validate_ui_nodes(s, r)),
+    ];
+
     pub fn run_static_stages(
         script: &str,
         context: Option<JsValidationContext>,
+    ) -> JsValidationResult {
+        run_static_stages_with_progress(script, context, |_, _, _| {})
+    }
+
+    /// Like [`run_static_stages`], calling `on_stage(name, completed, total)`
+    /// after each stage so long validations can report progress. Stops after
+    /// the current stage once the thread's cancel token is cancelled (see
+    /// [`crate::cancellation`]); the result then covers the stages run.
+    pub fn run_static_stages_with_progress(
+        script: &str,
+        context: Option<JsValidationContext>,
+        mut on_stage: impl FnMut(&str, usize, usize),
     ) -> JsValidationResult {
         let ctx = context.unwrap_or_else(|| default_context(script));
         let mut result = fresh_result(script);
-        validate_basic(script, &mut result);
-        validate_event_handlers(script, &mut result);
-        validate_security_patterns(script, &ctx, &mut result);
-        validate_esm_format(script, &mut result);
-        validate_intl(script, &mut result);
-        validate_icp_integration(script, &ctx, &mut result);
-        validate_performance_patterns(script, &ctx, &mut result);
-        validate_data_structures(script, &ctx, &mut result);
-        validate_ui_nodes(script, &mut result);
+        let token = crate::cancellation::current();
+        for (i, (name, stage)) in STAGES.iter().enumerate() {
+            stage(script, &ctx, &mut result);
+            on_stage(name, i + 1, STAGES.len());
+            if token.is_cancelled() {
+                break;
+            }
+        }
         result.is_valid = result.syntax_errors.is_empty();
         result
     }
@@ -688,6 +719,24 @@ mod tests {
         assert!(res.is_err(), "expected infinite loop to be interrupted");
     }
 
+    #[test]
+    fn cancelled_token_stops_script_before_budget() {
+        let token = crate::cancellation::CancelToken::new();
+        let canceller = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            canceller.cancel();
+        });
+        let started = Instant::now();
+        let out = crate::cancellation::with_token(&token, || {
+            js_app_init("function init(){ while (true) {} }", None, 10_000)
+        });
+        let v: JsonValue = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["ok"], false);
+        assert_eq!(v["error"], "execution cancelled");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
     #[test]
     fn os_and_require_disabled() {
         let (_rt, ctx) = create_sandboxed_js(8 * 1024 * 1024, far_deadline()).unwrap();
@@ -1463,6 +1512,36 @@ mod tests {
         assert!(result.syntax_errors.iter().any(|e| e.contains("eval")));
     }
 
+    #[test]
+    fn static_analysis_reports_each_stage() {
+        let mut seen = Vec::new();
+        let script = "eval('1'); function init(){} function view(){} function update(){}";
+        let result =
+            static_analysis::run_static_stages_with_progress(script, None, |name, done, total| {
+                seen.push((name.to_string(), done, total));
+            });
+        assert_eq!(seen.len(), static_analysis::STAGES.len());
+        assert_eq!(seen.first().unwrap().0, "basic");
+        assert_eq!(seen.last().unwrap().1, seen.last().unwrap().2);
+        assert_eq!(
+            result.syntax_errors,
+            static_analysis::run_static_stages(script, None).syntax_errors
+        );
+    }
+
+    #[test]
+    fn static_analysis_stops_when_cancelled() {
+        let token = crate::cancellation::CancelToken::new();
+        let mut seen = 0;
+        crate::cancellation::with_token(&token, || {
+            static_analysis::run_static_stages_with_progress("let x = 1;", None, |_, _, _| {
+                seen += 1;
+                token.cancel();
+            })
+        });
+        assert_eq!(seen, 1);
+    }
+
     #[test]
     fn static_analysis_context_detection() {
         assert!(static_analysis::is_example_script("// Example script"));
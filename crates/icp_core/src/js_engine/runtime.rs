@@ -1,5 +1,7 @@
 use super::static_analysis;
 use super::{JsExecError, JsValidationContext, JsValidationResult};
+use crate::cancellation;
+use crate::logging::{self, LogCategory, LogLevel};
 use rquickjs::{Context, Ctx, Error, Function, Runtime, Value};
 use serde_json::{json, Value as JsonValue};
 use std::time::{Duration, Instant};
@@ -22,7 +24,11 @@ pub(super) fn create_sandboxed_js(
     let rt = Runtime::new()?;
     rt.set_memory_limit(memory_limit);
     rt.set_max_stack_size(STACK_LIMIT);
-    rt.set_interrupt_handler(Some(Box::new(move || Instant::now() > deadline)));
+    // The handler runs very often; read the thread's token once up front.
+    let token = cancellation::current();
+    rt.set_interrupt_handler(Some(Box::new(move || {
+        Instant::now() > deadline || token.is_cancelled()
+    })));
     let ctx = Context::full(&rt)?;
     Ok((rt, ctx))
 }
@@ -63,7 +69,9 @@ fn set_arg_global<'js>(
 
 const HOST_BOOTSTRAP_JS: &str = r#"
 var __icp_messages = [];
-function icp_log(msg){ __icp_messages.push(String(msg)); }
+function icp_log(msg){ __icp_messages.push(String(msg)); __icp_host_log("info", String(msg)); }
+function __icp_console(level){ return function(){ var parts = []; for (var i = 0; i < arguments.length; i++) { var a = arguments[i]; if (typeof a === "string") { parts.push(a); } else { try { parts.push(JSON.stringify(a)); } catch (e) { parts.push(String(a)); } } } __icp_host_log(level, parts.join(" ")); }; }
+if (typeof console === "undefined") { globalThis.console = { log: __icp_console("log"), info: __icp_console("info"), debug: __icp_console("debug"), warn: __icp_console("warn"), error: __icp_console("error") }; }
 function get_arg(){ return arg; }
 
 function icp_call(spec){ spec = spec || {}; spec.action = "call"; return spec; }
@@ -89,11 +97,42 @@ globalThis.eval = function(){ throw new Error('eval is disabled in sandbox'); };
 globalThis.Function = function(){ throw new Error('Function constructor is disabled in sandbox'); };
 "#;
 
+/// `__icp_host_log(level, message)`: forwards `icp_log` and `console.*`
+/// output to the host's log sink (see [`crate::logging`]).
+fn install_log_hook<'js>(ctx: &Ctx<'js>) -> rquickjs::Result<()> {
+    let hook = Function::new(ctx.clone(), |level: String, message: String| {
+        let level = LogLevel::parse(&level).unwrap_or(LogLevel::Info);
+        logging::log(level, LogCategory::Script, &message);
+    })?;
+    ctx.globals().set("__icp_host_log", hook)
+}
+
+/// What to report for a failed `init`/`view`/`update`: an interrupted
+/// script surfaces as a generic exception, so name the cause instead.
+fn failure_message(deadline: Instant, error: String) -> String {
+    if cancellation::current().is_cancelled() {
+        "execution cancelled".to_string()
+    } else if Instant::now() > deadline {
+        "execution timeout".to_string()
+    } else {
+        error
+    }
+}
+
+fn log_engine_failure(entry: &str, error: &str) {
+    logging::log(
+        LogLevel::Warn,
+        LogCategory::Engine,
+        &format!("{entry} failed: {error}"),
+    );
+}
+
 pub(super) fn install_host_globals<'js>(
     ctx: &Ctx<'js>,
     json_arg: Option<&str>,
 ) -> std::result::Result<(), JsExecError> {
     set_arg_global(ctx, json_arg)?;
+    install_log_hook(ctx).map_err(|e| JsExecError::Js(js_error_string(e)))?;
     ctx.eval::<(), _>(HOST_BOOTSTRAP_JS)
         .map_err(|e| JsExecError::Js(js_error_string(e)))?;
     ctx.eval::<(), _>(NEUTRALIZE_EVAL_JS)
@@ -153,7 +192,13 @@ pub fn execute_js_json(
     drop(ctx);
     drop(rt);
 
-    let (result_json, messages_json) = outcome?;
+    let (result_json, messages_json) = outcome.map_err(|e| {
+        if cancellation::current().is_cancelled() {
+            JsExecError::Js("execution cancelled".to_string())
+        } else {
+            e
+        }
+    })?;
     let result_value: JsonValue =
         serde_json::from_str(&result_json).map_err(|e| JsExecError::Js(e.to_string()))?;
     let messages: Vec<String> =
@@ -303,11 +348,8 @@ pub fn js_app_init(script: &str, json_arg: Option<&str>, budget_ms: u64) -> Stri
     match outcome {
         Ok((state, effects)) => json!({"ok": true, "state": state, "effects": effects}).to_string(),
         Err(e) => {
-            let msg = if Instant::now() > deadline {
-                "execution timeout".to_string()
-            } else {
-                e
-            };
+            let msg = failure_message(deadline, e);
+            log_engine_failure("init", &msg);
             json!({"ok": false, "error": msg}).to_string()
         }
     }
@@ -353,11 +395,8 @@ pub fn js_app_view(script: &str, state_json: &str, budget_ms: u64) -> String {
     match outcome {
         Ok(ui) => json!({"ok": true, "ui": ui}).to_string(),
         Err(e) => {
-            let msg = if Instant::now() > deadline {
-                "execution timeout".to_string()
-            } else {
-                e
-            };
+            let msg = failure_message(deadline, e);
+            log_engine_failure("view", &msg);
             json!({"ok": false, "error": msg}).to_string()
         }
     }
@@ -421,11 +460,8 @@ pub fn js_app_update(script: &str, msg_json: &str, state_json: &str, budget_ms:
     match outcome {
         Ok((state, effects)) => json!({"ok": true, "state": state, "effects": effects}).to_string(),
         Err(e) => {
-            let msg = if Instant::now() > deadline {
-                "execution timeout".to_string()
-            } else {
-                e
-            };
+            let msg = failure_message(deadline, e);
+            log_engine_failure("update", &msg);
             json!({"ok": false, "error": msg}).to_string()
         }
     }
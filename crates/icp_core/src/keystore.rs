@@ -0,0 +1,244 @@
+//! Private key storage outside of plaintext app storage.
+//!
+//! Backends:
+//! - [`OsKeyStore`] (feature `os-keystore`): macOS Keychain, Windows Credential
+//!   Manager (DPAPI-protected), Linux Secret Service.
+//! - [`CallbackKeyStore`]: Android Keystore / iOS Keychain, reached through
+//!   callbacks the host app registers over FFI (`icp_keystore_register_callbacks`).
+//! - [`MemoryKeyStore`]: tests and ephemeral sessions.
+//!
+//! Secrets are keyed by principal text. [`generate_ed25519_identity`] writes
+//! the private key straight into the store and hands back only the public
+//! half, so the key never has to round-trip through Dart. [`profiles`] files
+//! stored identities under names so several can be kept and switched.
+
+use crate::identity::{IdentityData, KeyAlgorithm};
+use crate::keypair::{generate_ed25519_keypair, sign_ed25519, sign_secp256k1};
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+pub mod callback;
+#[cfg(feature = "os-keystore")]
+pub mod os;
+pub mod profiles;
+
+pub use callback::CallbackKeyStore;
+#[cfg(feature = "os-keystore")]
+pub use os::OsKeyStore;
+pub use profiles::{IdentityProfile, ProfileError, ProfileScope, ProfileSet};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum KeystoreError {
+    #[error("no key stored for {0}")]
+    NotFound(String),
+    #[error("no keystore backend configured")]
+    Unavailable,
+    #[error("keystore backend error: {0}")]
+    Backend(String),
+}
+
+pub trait KeyStore: Send + Sync {
+    fn store(&self, id: &str, secret: &[u8]) -> Result<(), KeystoreError>;
+    fn load(&self, id: &str) -> Result<Vec<u8>, KeystoreError>;
+    /// Returns `false` when nothing was stored under `id`.
+    fn delete(&self, id: &str) -> Result<bool, KeystoreError>;
+}
+
+/// Public half of an identity whose private key lives in a [`KeyStore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredIdentity {
+    pub algorithm: KeyAlgorithm,
+    pub public_key_b64: String,
+    pub principal_text: String,
+}
+
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    secrets: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyStore for MemoryKeyStore {
+    fn store(&self, id: &str, secret: &[u8]) -> Result<(), KeystoreError> {
+        self.secrets
+            .lock()
+            .map_err(|_| KeystoreError::Backend("memory keystore poisoned".into()))?
+            .insert(id.to_string(), secret.to_vec());
+        Ok(())
+    }
+
+    fn load(&self, id: &str) -> Result<Vec<u8>, KeystoreError> {
+        self.secrets
+            .lock()
+            .map_err(|_| KeystoreError::Backend("memory keystore poisoned".into()))?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| KeystoreError::NotFound(id.to_string()))
+    }
+
+    fn delete(&self, id: &str) -> Result<bool, KeystoreError> {
+        Ok(self
+            .secrets
+            .lock()
+            .map_err(|_| KeystoreError::Backend("memory keystore poisoned".into()))?
+            .remove(id)
+            .is_some())
+    }
+}
+
+/// Stored record: algorithm tag byte followed by the raw private key.
+fn encode_secret(algorithm: KeyAlgorithm, private_key: &[u8]) -> Vec<u8> {
+    let tag = match algorithm {
+        KeyAlgorithm::Ed25519 => 0u8,
+        KeyAlgorithm::Secp256k1 => 1u8,
+    };
+    let mut out = vec![tag];
+    out.extend_from_slice(private_key);
+    out
+}
+
+fn decode_secret(id: &str, secret: &[u8]) -> Result<(KeyAlgorithm, &[u8]), KeystoreError> {
+    match secret.split_first() {
+        Some((&0, key)) => Ok((KeyAlgorithm::Ed25519, key)),
+        Some((&1, key)) => Ok((KeyAlgorithm::Secp256k1, key)),
+        _ => Err(KeystoreError::Backend(format!(
+            "corrupt keystore record for {id}"
+        ))),
+    }
+}
+
+/// Moves the private key of `identity` into `store`, keyed by principal.
+pub fn store_identity(
+    store: &dyn KeyStore,
+    identity: &IdentityData,
+) -> Result<StoredIdentity, KeystoreError> {
+    identity.validate().map_err(KeystoreError::Backend)?;
+    let private_key = B64
+        .decode(&identity.private_key_b64)
+        .map_err(|e| KeystoreError::Backend(format!("invalid base64 private key: {e}")))?;
+    store.store(
+        &identity.principal_text,
+        &encode_secret(identity.algorithm, &private_key),
+    )?;
+    Ok(StoredIdentity {
+        algorithm: identity.algorithm,
+        public_key_b64: identity.public_key_b64.clone(),
+        principal_text: identity.principal_text.clone(),
+    })
+}
+
+/// Generates an Ed25519 identity directly into `store`.
+pub fn generate_ed25519_identity(
+    store: &dyn KeyStore,
+    mnemonic: Option<String>,
+) -> Result<StoredIdentity, KeystoreError> {
+    let keypair = generate_ed25519_keypair(mnemonic);
+    store_identity(
+        store,
+        &IdentityData::from_keypair(KeyAlgorithm::Ed25519, &keypair),
+    )
+}
+
+/// Signs with the stored key for `principal_text`; returns the raw signature.
+pub fn sign_with_stored_key(
+    store: &dyn KeyStore,
+    principal_text: &str,
+    message: &[u8],
+) -> Result<Vec<u8>, KeystoreError> {
+    let secret = store.load(principal_text)?;
+    let (algorithm, private_key) = decode_secret(principal_text, &secret)?;
+    let private_b64 = B64.encode(private_key);
+    let signature_b64 = match algorithm {
+        KeyAlgorithm::Ed25519 => sign_ed25519(message, &private_b64),
+        KeyAlgorithm::Secp256k1 => sign_secp256k1(message, &private_b64),
+    }
+    .map_err(KeystoreError::Backend)?;
+    B64.decode(signature_b64)
+        .map_err(|e| KeystoreError::Backend(format!("invalid signature encoding: {e}")))
+}
+
+static ACTIVE: RwLock<Option<Box<dyn KeyStore>>> = RwLock::new(None);
+
+/// Installs the process-wide keystore used by the FFI entry points.
+pub fn set_active_keystore(store: Box<dyn KeyStore>) {
+    match ACTIVE.write() {
+        Ok(mut guard) => *guard = Some(store),
+        Err(poisoned) => *poisoned.into_inner() = Some(store),
+    }
+}
+
+/// Runs `f` against the active keystore. Falls back to the OS keystore when
+/// that feature is built and nothing else was installed.
+pub fn with_active_keystore<R, E: From<KeystoreError>>(
+    f: impl FnOnce(&dyn KeyStore) -> Result<R, E>,
+) -> Result<R, E> {
+    let guard = ACTIVE
+        .read()
+        .map_err(|_| KeystoreError::Backend("keystore lock poisoned".into()))?;
+    match guard.as_deref() {
+        Some(store) => f(store),
+        #[cfg(feature = "os-keystore")]
+        None => f(&OsKeyStore::default()),
+        #[cfg(not(feature = "os-keystore"))]
+        None => Err(KeystoreError::Unavailable.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_secp256k1_keypair;
+    use crate::identity::delegation::verify_der_signature;
+    use crate::principal::der_encode_public_key;
+
+    #[test]
+    fn test_generated_identity_signs_from_store() {
+        let store = MemoryKeyStore::new();
+        let stored = generate_ed25519_identity(&store, None).unwrap();
+
+        let signature = sign_with_stored_key(&store, &stored.principal_text, b"msg").unwrap();
+        let der =
+            der_encode_public_key("ed25519", &B64.decode(&stored.public_key_b64).unwrap()).unwrap();
+        verify_der_signature(&der, b"msg", &signature).unwrap();
+    }
+
+    #[test]
+    fn test_secp256k1_identity_roundtrip() {
+        let store = MemoryKeyStore::new();
+        let identity =
+            IdentityData::from_keypair(KeyAlgorithm::Secp256k1, &generate_secp256k1_keypair(None));
+        let stored = store_identity(&store, &identity).unwrap();
+        assert_eq!(stored.algorithm, KeyAlgorithm::Secp256k1);
+        let signature = sign_with_stored_key(&store, &stored.principal_text, b"msg").unwrap();
+        assert_eq!(signature.len(), 64);
+    }
+
+    #[test]
+    fn test_missing_and_deleted_keys() {
+        let store = MemoryKeyStore::new();
+        let stored = generate_ed25519_identity(&store, None).unwrap();
+        assert!(store.delete(&stored.principal_text).unwrap());
+        assert!(!store.delete(&stored.principal_text).unwrap());
+        assert_eq!(
+            sign_with_stored_key(&store, &stored.principal_text, b"msg"),
+            Err(KeystoreError::NotFound(stored.principal_text.clone()))
+        );
+    }
+
+    #[test]
+    fn test_corrupt_record_is_rejected() {
+        let store = MemoryKeyStore::new();
+        store.store("p", &[9, 1, 2, 3]).unwrap();
+        assert!(matches!(
+            sign_with_stored_key(&store, "p", b"msg"),
+            Err(KeystoreError::Backend(_))
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+//! Keystore backed by host callbacks (Android Keystore, iOS Keychain).
+//!
+//! The mobile platforms only expose their secure storage to Kotlin/Swift, so
+//! the Flutter host registers three C callbacks and Rust drives them.
+//!
+//! Return conventions:
+//! - `store`: `0` on success, negative on error.
+//! - `load`: secret length on success (if larger than `out_cap`, nothing is
+//!   written and Rust retries with a buffer of that size), `-1` when no secret
+//!   exists, other negative values on error.
+//! - `delete`: `1` deleted, `0` nothing stored, negative on error.
+
+use super::{KeyStore, KeystoreError};
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+pub type StoreCallback =
+    unsafe extern "C" fn(id: *const c_char, secret: *const u8, secret_len: usize) -> i32;
+pub type LoadCallback =
+    unsafe extern "C" fn(id: *const c_char, out: *mut u8, out_cap: usize) -> isize;
+pub type DeleteCallback = unsafe extern "C" fn(id: *const c_char) -> i32;
+
+const LOAD_NOT_FOUND: isize = -1;
+const INITIAL_LOAD_CAP: usize = 128;
+
+#[derive(Clone, Copy)]
+pub struct CallbackKeyStore {
+    store_cb: StoreCallback,
+    load_cb: LoadCallback,
+    delete_cb: DeleteCallback,
+}
+
+impl CallbackKeyStore {
+    pub fn new(store_cb: StoreCallback, load_cb: LoadCallback, delete_cb: DeleteCallback) -> Self {
+        Self {
+            store_cb,
+            load_cb,
+            delete_cb,
+        }
+    }
+}
+
+fn c_id(id: &str) -> Result<CString, KeystoreError> {
+    CString::new(id).map_err(|_| KeystoreError::Backend("key id contains a nul byte".into()))
+}
+
+impl KeyStore for CallbackKeyStore {
+    fn store(&self, id: &str, secret: &[u8]) -> Result<(), KeystoreError> {
+        let cid = c_id(id)?;
+        // Sound: both pointers stay valid for the duration of the call.
+        let rc = unsafe { (self.store_cb)(cid.as_ptr(), secret.as_ptr(), secret.len()) };
+        if rc < 0 {
+            return Err(KeystoreError::Backend(format!("host store failed ({rc})")));
+        }
+        Ok(())
+    }
+
+    fn load(&self, id: &str) -> Result<Vec<u8>, KeystoreError> {
+        let cid = c_id(id)?;
+        let mut buf = vec![0u8; INITIAL_LOAD_CAP];
+        // At most one retry: the host reports the exact size it needs.
+        for _ in 0..2 {
+            // Sound: `buf` is writable for `buf.len()` bytes.
+            let rc = unsafe { (self.load_cb)(cid.as_ptr(), buf.as_mut_ptr(), buf.len()) };
+            if rc == LOAD_NOT_FOUND {
+                return Err(KeystoreError::NotFound(id.to_string()));
+            }
+            if rc < 0 {
+                return Err(KeystoreError::Backend(format!("host load failed ({rc})")));
+            }
+            let len = rc as usize;
+            if len <= buf.len() {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            buf = vec![0u8; len];
+        }
+        Err(KeystoreError::Backend(
+            "host load kept growing the secret".into(),
+        ))
+    }
+
+    fn delete(&self, id: &str) -> Result<bool, KeystoreError> {
+        let cid = c_id(id)?;
+        // Sound: `cid` stays valid for the duration of the call.
+        match unsafe { (self.delete_cb)(cid.as_ptr()) } {
+            1 => Ok(true),
+            0 => Ok(false),
+            rc => Err(KeystoreError::Backend(format!("host delete failed ({rc})"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::ffi::CStr;
+    use std::sync::Mutex;
+
+    static HOST: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+
+    unsafe extern "C" fn host_store(id: *const c_char, secret: *const u8, len: usize) -> i32 {
+        let id = CStr::from_ptr(id).to_str().unwrap().to_string();
+        let secret = std::slice::from_raw_parts(secret, len).to_vec();
+        HOST.lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(id, secret);
+        0
+    }
+
+    unsafe extern "C" fn host_load(id: *const c_char, out: *mut u8, cap: usize) -> isize {
+        let id = CStr::from_ptr(id).to_str().unwrap();
+        let guard = HOST.lock().unwrap();
+        match guard.as_ref().and_then(|m| m.get(id)) {
+            None => -1,
+            Some(secret) => {
+                if secret.len() <= cap {
+                    std::ptr::copy_nonoverlapping(secret.as_ptr(), out, secret.len());
+                }
+                secret.len() as isize
+            }
+        }
+    }
+
+    unsafe extern "C" fn host_delete(id: *const c_char) -> i32 {
+        let id = CStr::from_ptr(id).to_str().unwrap();
+        let removed = HOST
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|m| m.remove(id))
+            .is_some();
+        i32::from(removed)
+    }
+
+    #[test]
+    fn test_callbacks_roundtrip_including_large_secret() {
+        let ks = CallbackKeyStore::new(host_store, host_load, host_delete);
+        let big = vec![7u8; INITIAL_LOAD_CAP * 3];
+        ks.store("cb-big", &big).unwrap();
+        assert_eq!(ks.load("cb-big").unwrap(), big);
+
+        ks.store("cb-small", b"abc").unwrap();
+        assert_eq!(ks.load("cb-small").unwrap(), b"abc");
+
+        assert!(ks.delete("cb-small").unwrap());
+        assert_eq!(
+            ks.load("cb-small"),
+            Err(KeystoreError::NotFound("cb-small".into()))
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Desktop OS credential stores via the `keyring` crate: macOS Keychain,
+//! Windows Credential Manager (DPAPI), Linux Secret Service.
+
+use super::{KeyStore, KeystoreError};
+
+const DEFAULT_SERVICE: &str = "icp-cc.identity";
+
+pub struct OsKeyStore {
+    service: String,
+}
+
+impl Default for OsKeyStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_SERVICE)
+    }
+}
+
+impl OsKeyStore {
+    pub fn new(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+        }
+    }
+
+    fn entry(&self, id: &str) -> Result<keyring::Entry, KeystoreError> {
+        keyring::Entry::new(&self.service, id).map_err(|e| KeystoreError::Backend(e.to_string()))
+    }
+}
+
+impl KeyStore for OsKeyStore {
+    fn store(&self, id: &str, secret: &[u8]) -> Result<(), KeystoreError> {
+        self.entry(id)?
+            .set_secret(secret)
+            .map_err(|e| KeystoreError::Backend(e.to_string()))
+    }
+
+    fn load(&self, id: &str) -> Result<Vec<u8>, KeystoreError> {
+        match self.entry(id)?.get_secret() {
+            Ok(secret) => Ok(secret),
+            Err(keyring::Error::NoEntry) => Err(KeystoreError::NotFound(id.to_string())),
+            Err(e) => Err(KeystoreError::Backend(e.to_string())),
+        }
+    }
+
+    fn delete(&self, id: &str) -> Result<bool, KeystoreError> {
+        match self.entry(id)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(KeystoreError::Backend(e.to_string())),
+        }
+    }
+}
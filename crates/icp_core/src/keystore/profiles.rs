@@ -0,0 +1,383 @@
+//! Named identity profiles.
+//!
+//! A profile gives a stored identity a name and, optionally, the marketplace
+//! account and network it is meant for, so a user can keep e.g. a mainnet
+//! identity and a local-replica identity side by side and switch between
+//! them. Private keys stay in the [`KeyStore`] under their principal as
+//! before; the profile list itself (public data only) is kept in the same
+//! store under [`PROFILES_RECORD_ID`], so it follows the keys wherever the
+//! host keeps them.
+//!
+//! Timestamps are unix milliseconds supplied by the caller.
+
+use super::{store_identity, KeyStore, KeystoreError};
+use crate::identity::{IdentityData, KeyAlgorithm};
+use crate::keypair::{generate_ed25519_keypair, generate_secp256k1_keypair};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// Keystore id of the serialized [`ProfileSet`].
+pub const PROFILES_RECORD_ID: &str = "icp_core.identity_profiles";
+
+pub const PROFILES_FORMAT_VERSION: u32 = 1;
+
+/// Longest profile name, in characters.
+pub const MAX_PROFILE_NAME_CHARS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProfileError {
+    #[error(transparent)]
+    Keystore(#[from] KeystoreError),
+    #[error("invalid profile name: {0}")]
+    InvalidName(String),
+    #[error("profile already exists: {0}")]
+    Duplicate(String),
+    #[error("no such profile: {0}")]
+    NotFound(String),
+    #[error("corrupt profile list: {0}")]
+    Corrupt(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityProfile {
+    pub name: String,
+    pub algorithm: KeyAlgorithm,
+    pub public_key_b64: String,
+    pub principal_text: String,
+    /// Marketplace account the identity signs for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Network the identity is meant for (`"ic"`, `"local"` or a gateway URL).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    pub created_at: u64,
+}
+
+/// Where a new profile is meant to be used; both parts are optional labels.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileScope {
+    pub account: Option<String>,
+    pub network: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileSet {
+    pub version: u32,
+    /// Name of the profile in use; `None` only when there are no profiles.
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: Vec<IdentityProfile>,
+}
+
+impl Default for ProfileSet {
+    fn default() -> Self {
+        Self {
+            version: PROFILES_FORMAT_VERSION,
+            active: None,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+impl ProfileSet {
+    pub fn get(&self, name: &str) -> Option<&IdentityProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    pub fn active_profile(&self) -> Option<&IdentityProfile> {
+        self.active.as_deref().and_then(|name| self.get(name))
+    }
+
+    fn position(&self, name: &str) -> Result<usize, ProfileError> {
+        self.profiles
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| ProfileError::NotFound(name.to_string()))
+    }
+
+    /// Adds a profile for `identity`; the first profile becomes active.
+    fn add(
+        &mut self,
+        name: &str,
+        identity: &IdentityData,
+        scope: ProfileScope,
+        now: u64,
+    ) -> Result<&IdentityProfile, ProfileError> {
+        let name = self.check_new_name(name)?;
+        self.profiles.push(IdentityProfile {
+            name: name.clone(),
+            algorithm: identity.algorithm,
+            public_key_b64: identity.public_key_b64.clone(),
+            principal_text: identity.principal_text.clone(),
+            account: scope.account,
+            network: scope.network,
+            created_at: now,
+        });
+        self.active.get_or_insert(name);
+        Ok(self.profiles.last().expect("just pushed"))
+    }
+
+    /// The trimmed `name` if it is valid and not taken (case-insensitively).
+    fn check_new_name(&self, name: &str) -> Result<String, ProfileError> {
+        let name = name.trim();
+        if name.is_empty()
+            || name.chars().count() > MAX_PROFILE_NAME_CHARS
+            || name.chars().any(char::is_control)
+        {
+            return Err(ProfileError::InvalidName(name.to_string()));
+        }
+        if self
+            .profiles
+            .iter()
+            .any(|p| p.name.to_lowercase() == name.to_lowercase())
+        {
+            return Err(ProfileError::Duplicate(name.to_string()));
+        }
+        Ok(name.to_string())
+    }
+
+    pub fn switch(&mut self, name: &str) -> Result<&IdentityProfile, ProfileError> {
+        let i = self.position(name)?;
+        self.active = Some(name.to_string());
+        Ok(&self.profiles[i])
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<&IdentityProfile, ProfileError> {
+        let i = self.position(from)?;
+        let removed = self.profiles.remove(i);
+        let checked = self.check_new_name(to);
+        self.profiles.insert(i, removed);
+        let to = checked?;
+        if self.active.as_deref() == Some(from) {
+            self.active = Some(to.clone());
+        }
+        self.profiles[i].name = to;
+        Ok(&self.profiles[i])
+    }
+
+    /// Removes a profile; the first remaining one becomes active if it was.
+    fn remove(&mut self, name: &str) -> Result<IdentityProfile, ProfileError> {
+        let removed = self.profiles.remove(self.position(name)?);
+        if self.active.as_deref() == Some(name) {
+            self.active = self.profiles.first().map(|p| p.name.clone());
+        }
+        Ok(removed)
+    }
+}
+
+/// Serializes load-modify-save cycles on the profile record.
+static PROFILES_LOCK: Mutex<()> = Mutex::new(());
+
+/// Reads the profile list; an empty one when nothing was saved yet.
+pub fn load_profiles(store: &dyn KeyStore) -> Result<ProfileSet, ProfileError> {
+    let bytes = match store.load(PROFILES_RECORD_ID) {
+        Ok(bytes) => bytes,
+        Err(KeystoreError::NotFound(_)) => return Ok(ProfileSet::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let set: ProfileSet =
+        serde_json::from_slice(&bytes).map_err(|e| ProfileError::Corrupt(e.to_string()))?;
+    if set.version > PROFILES_FORMAT_VERSION {
+        return Err(ProfileError::Corrupt(format!(
+            "format version {} is newer than supported {}",
+            set.version, PROFILES_FORMAT_VERSION
+        )));
+    }
+    Ok(set)
+}
+
+fn update_profiles<R>(
+    store: &dyn KeyStore,
+    f: impl FnOnce(&dyn KeyStore, &mut ProfileSet) -> Result<R, ProfileError>,
+) -> Result<R, ProfileError> {
+    let _guard = PROFILES_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut set = load_profiles(store)?;
+    let out = f(store, &mut set)?;
+    let bytes = serde_json::to_vec(&set).map_err(|e| ProfileError::Corrupt(e.to_string()))?;
+    store.store(PROFILES_RECORD_ID, &bytes)?;
+    Ok(out)
+}
+
+/// Generates a new identity into `store` and files it under `name`.
+pub fn create_profile(
+    store: &dyn KeyStore,
+    name: &str,
+    algorithm: KeyAlgorithm,
+    mnemonic: Option<String>,
+    scope: ProfileScope,
+    now: u64,
+) -> Result<IdentityProfile, ProfileError> {
+    let keypair = match algorithm {
+        KeyAlgorithm::Ed25519 => generate_ed25519_keypair(mnemonic),
+        KeyAlgorithm::Secp256k1 => generate_secp256k1_keypair(mnemonic),
+    };
+    import_profile(
+        store,
+        name,
+        &IdentityData::from_keypair(algorithm, &keypair),
+        scope,
+        now,
+    )
+}
+
+/// Moves an existing identity's key into `store` and files it under `name`.
+pub fn import_profile(
+    store: &dyn KeyStore,
+    name: &str,
+    identity: &IdentityData,
+    scope: ProfileScope,
+    now: u64,
+) -> Result<IdentityProfile, ProfileError> {
+    update_profiles(store, |store, set| {
+        // Check the name first so a rejected profile leaves no stray key.
+        set.check_new_name(name)?;
+        store_identity(store, identity)?;
+        set.add(name, identity, scope, now).cloned()
+    })
+}
+
+pub fn switch_profile(store: &dyn KeyStore, name: &str) -> Result<IdentityProfile, ProfileError> {
+    update_profiles(store, |_, set| set.switch(name).cloned())
+}
+
+pub fn rename_profile(
+    store: &dyn KeyStore,
+    from: &str,
+    to: &str,
+) -> Result<IdentityProfile, ProfileError> {
+    update_profiles(store, |_, set| set.rename(from, to).cloned())
+}
+
+/// Removes a profile. Its private key is deleted too unless another profile
+/// uses the same principal; returns whether it was.
+pub fn remove_profile(store: &dyn KeyStore, name: &str) -> Result<bool, ProfileError> {
+    update_profiles(store, |store, set| {
+        let removed = set.remove(name)?;
+        let shared = set
+            .profiles
+            .iter()
+            .any(|p| p.principal_text == removed.principal_text);
+        if shared {
+            return Ok(false);
+        }
+        Ok(store.delete(&removed.principal_text)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore::{sign_with_stored_key, MemoryKeyStore};
+
+    fn local() -> ProfileScope {
+        ProfileScope {
+            account: None,
+            network: Some("local".into()),
+        }
+    }
+
+    #[test]
+    fn first_profile_is_active_and_switching_persists() {
+        let store = MemoryKeyStore::new();
+        assert_eq!(load_profiles(&store).unwrap(), ProfileSet::default());
+
+        let main = create_profile(
+            &store,
+            " main ",
+            KeyAlgorithm::Ed25519,
+            None,
+            ProfileScope::default(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(main.name, "main");
+        let dev = create_profile(&store, "dev", KeyAlgorithm::Secp256k1, None, local(), 2).unwrap();
+        assert_eq!(dev.network.as_deref(), Some("local"));
+
+        let set = load_profiles(&store).unwrap();
+        assert_eq!(set.profiles.len(), 2);
+        assert_eq!(set.active_profile(), Some(&main));
+
+        switch_profile(&store, "dev").unwrap();
+        let active = load_profiles(&store).unwrap().active_profile().cloned();
+        assert_eq!(active, Some(dev.clone()));
+        sign_with_stored_key(&store, &dev.principal_text, b"msg").unwrap();
+    }
+
+    #[test]
+    fn names_are_validated_and_unique() {
+        let store = MemoryKeyStore::new();
+        let scope = ProfileScope::default;
+        create_profile(&store, "Main", KeyAlgorithm::Ed25519, None, scope(), 1).unwrap();
+        for (name, expected) in [
+            ("main", ProfileError::Duplicate("main".into())),
+            ("  ", ProfileError::InvalidName("".into())),
+            ("a\nb", ProfileError::InvalidName("a\nb".into())),
+        ] {
+            assert_eq!(
+                create_profile(&store, name, KeyAlgorithm::Ed25519, None, scope(), 2),
+                Err(expected)
+            );
+        }
+        assert_eq!(
+            switch_profile(&store, "nope"),
+            Err(ProfileError::NotFound("nope".into()))
+        );
+        assert_eq!(load_profiles(&store).unwrap().profiles.len(), 1);
+    }
+
+    #[test]
+    fn rename_keeps_the_active_selection() {
+        let store = MemoryKeyStore::new();
+        let scope = ProfileScope::default;
+        create_profile(&store, "a", KeyAlgorithm::Ed25519, None, scope(), 1).unwrap();
+        create_profile(&store, "b", KeyAlgorithm::Ed25519, None, scope(), 2).unwrap();
+        assert_eq!(
+            rename_profile(&store, "a", "B"),
+            Err(ProfileError::Duplicate("B".into()))
+        );
+        assert_eq!(rename_profile(&store, "a", "A").unwrap().name, "A");
+        let set = load_profiles(&store).unwrap();
+        assert_eq!(set.active.as_deref(), Some("A"));
+        assert_eq!(set.profiles[0].name, "A");
+    }
+
+    #[test]
+    fn removing_deletes_unshared_keys_and_moves_active() {
+        let store = MemoryKeyStore::new();
+        let identity =
+            IdentityData::from_keypair(KeyAlgorithm::Ed25519, &generate_ed25519_keypair(None));
+        import_profile(&store, "mainnet", &identity, ProfileScope::default(), 1).unwrap();
+        import_profile(&store, "local", &identity, local(), 2).unwrap();
+        let other = create_profile(
+            &store,
+            "other",
+            KeyAlgorithm::Ed25519,
+            None,
+            ProfileScope::default(),
+            3,
+        )
+        .unwrap();
+
+        assert_eq!(remove_profile(&store, "mainnet"), Ok(false));
+        assert_eq!(
+            load_profiles(&store).unwrap().active.as_deref(),
+            Some("local")
+        );
+        sign_with_stored_key(&store, &identity.principal_text, b"msg").unwrap();
+
+        assert_eq!(remove_profile(&store, "local"), Ok(true));
+        assert!(sign_with_stored_key(&store, &identity.principal_text, b"msg").is_err());
+        assert_eq!(
+            load_profiles(&store).unwrap().active_profile(),
+            Some(&other)
+        );
+        assert_eq!(
+            remove_profile(&store, "local"),
+            Err(ProfileError::NotFound("local".into()))
+        );
+    }
+}
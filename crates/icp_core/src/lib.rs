@@ -1,21 +1,41 @@
 #[cfg(not(target_arch = "wasm32"))]
+pub mod call_tape;
+pub mod cancellation;
+pub mod candid_value;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod canister_client;
 pub mod contract;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod endpoint_probe;
 
+pub mod favorites;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod ffi;
+pub mod identity;
 pub mod js_engine;
 pub mod keypair;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod keystore;
+pub mod logging;
+pub mod marketplace_auth;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod marketplace_cache;
 pub mod principal;
+pub mod transfer_policy;
 pub mod vault;
 
 // Include Wasm exports when target is wasm32
 #[cfg(target_arch = "wasm32")]
 pub mod wasm_exports;
 
+pub use candid_value::{args_from_json, args_to_json, format_args, parse_args, CandidValueError};
 #[cfg(not(target_arch = "wasm32"))]
 pub use canister_client::{MethodInfo, MethodKind, ParsedInterface, DEFAULT_IC_GATEWAY};
 pub use contract::SDK_CONTRACT_VERSION;
+pub use identity::{
+    export_encrypted_identity, export_pem_identity, import_encrypted_identity, import_pem_identity,
+    IdentityData, KeyAlgorithm,
+};
 #[cfg(not(target_arch = "wasm32"))]
 pub use js_engine::{
     execute_js_json, js_app_init, js_app_update, js_app_view, lint_js, validate_js_comprehensive,
@@ -24,7 +44,11 @@ pub use js_engine::{JsExecError, JsValidationContext, JsValidationResult};
 pub use keypair::{
     generate_ed25519_keypair, generate_secp256k1_keypair, sign_ed25519, sign_secp256k1, KeypairData,
 };
-pub use principal::{der_encode_public_key, principal_from_der, principal_from_public_key};
+pub use principal::{
+    account_identifier, classify_principal, der_encode_public_key, parse_principal_text,
+    principal_from_der, principal_from_public_key, subaccount_from_index,
+    validate_account_identifier, PrincipalKind, PrincipalTextError,
+};
 pub use vault::{
     decrypt_vault, derive_key, encrypt_vault, generate_nonce, generate_salt, EncryptedVault,
 };
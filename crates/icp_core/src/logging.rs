@@ -0,0 +1,169 @@
+//! Structured log hook.
+//!
+//! Script output (`icp_log`, `console.*`), script engine failures and
+//! canister call traces go through [`log`], which hands them to the sink the
+//! host registered (`icp_log_register_callback` over FFI). On mobile stdout
+//! goes nowhere, so without a sink the records are dropped rather than
+//! printed.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Longest message handed to the sink, in bytes; longer ones are cut at a
+/// character boundary and marked with `…`.
+pub const MAX_MESSAGE_BYTES: usize = 4096;
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace = 0,
+    Debug = 1,
+    Info = 2,
+    Warn = 3,
+    Error = 4,
+}
+
+impl LogLevel {
+    /// The level for an FFI integer; out-of-range values clamp.
+    pub fn from_i32(level: i32) -> Self {
+        match level {
+            i32::MIN..=0 => Self::Trace,
+            1 => Self::Debug,
+            2 => Self::Info,
+            3 => Self::Warn,
+            _ => Self::Error,
+        }
+    }
+
+    /// The level of a `console` method name (`log` is `info`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "log" | "info" => Some(Self::Info),
+            "warn" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogCategory {
+    /// Output of the script itself.
+    Script,
+    /// The script engine: failed or timed out `init`/`view`/`update`.
+    Engine,
+    /// Canister call traces.
+    Canister,
+}
+
+impl LogCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Script => "script",
+            Self::Engine => "engine",
+            Self::Canister => "canister",
+        }
+    }
+}
+
+pub type LogSink = Arc<dyn Fn(LogLevel, LogCategory, &str) + Send + Sync>;
+
+static SINK: RwLock<Option<(LogLevel, LogSink)>> = RwLock::new(None);
+
+/// Sends every record at `min_level` or above to `sink`, replacing any
+/// previous sink. `sink` may be called from any thread.
+pub fn set_sink(min_level: LogLevel, sink: LogSink) {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = Some((min_level, sink));
+}
+
+pub fn clear_sink() {
+    *SINK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Whether a record at `level` would reach a sink, to skip building
+/// expensive messages.
+pub fn enabled(level: LogLevel) -> bool {
+    SINK.read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .is_some_and(|(min, _)| level >= *min)
+}
+
+fn truncate(message: &str) -> std::borrow::Cow<'_, str> {
+    if message.len() <= MAX_MESSAGE_BYTES {
+        return message.into();
+    }
+    let mut end = MAX_MESSAGE_BYTES;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &message[..end]).into()
+}
+
+pub fn log(level: LogLevel, category: LogCategory, message: &str) {
+    // Clone the sink out so it runs without the lock held (it may log too).
+    let sink = match SINK.read().unwrap_or_else(PoisonError::into_inner).as_ref() {
+        Some((min, sink)) if level >= *min => Arc::clone(sink),
+        _ => return,
+    };
+    sink(level, category, &truncate(message));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn levels_map_from_ffi_integers_and_console_names() {
+        assert_eq!(LogLevel::from_i32(-5), LogLevel::Trace);
+        assert_eq!(LogLevel::from_i32(3), LogLevel::Warn);
+        assert_eq!(LogLevel::from_i32(99), LogLevel::Error);
+        assert_eq!(LogLevel::parse("log"), Some(LogLevel::Info));
+        assert_eq!(LogLevel::parse("warn"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("table"), None);
+    }
+
+    #[test]
+    fn sink_receives_records_at_or_above_its_level() {
+        let seen: Arc<Mutex<Vec<(LogLevel, LogCategory, String)>>> = Arc::default();
+        let sink_seen = Arc::clone(&seen);
+        set_sink(
+            LogLevel::Info,
+            Arc::new(move |level, category, message| {
+                if message.starts_with("logging-test") {
+                    sink_seen
+                        .lock()
+                        .unwrap()
+                        .push((level, category, message.to_string()));
+                }
+            }),
+        );
+        log(LogLevel::Debug, LogCategory::Script, "logging-test debug");
+        log(LogLevel::Warn, LogCategory::Engine, "logging-test warn");
+        let long = format!("logging-test {}", "é".repeat(MAX_MESSAGE_BYTES));
+        log(LogLevel::Info, LogCategory::Script, &long);
+        assert!(enabled(LogLevel::Error));
+        assert!(!enabled(LogLevel::Trace));
+        clear_sink();
+        log(
+            LogLevel::Error,
+            LogCategory::Canister,
+            "logging-test after clear",
+        );
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(
+            seen[0],
+            (
+                LogLevel::Warn,
+                LogCategory::Engine,
+                "logging-test warn".into()
+            )
+        );
+        assert!(seen[1].2.ends_with('…'));
+        assert!(seen[1].2.len() <= MAX_MESSAGE_BYTES + '…'.len_utf8());
+    }
+}
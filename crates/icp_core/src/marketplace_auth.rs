@@ -0,0 +1,323 @@
+//! Marketplace request signing.
+//!
+//! Mirrors `backend/src/auth.rs::create_canonical_payload` and the payload
+//! builders in `backend/src/middleware/auth.rs` byte for byte, so any client
+//! (Flutter over FFI, the web frontend over `wasm_exports`) produces the exact
+//! string the backend re-derives before verifying. If the backend changes the
+//! set of signed fields, this module must change with it.
+
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde_json::{Map, Value};
+
+/// Version the backend assumes when an upload omits `version`.
+pub const DEFAULT_UPLOAD_VERSION: &str = "1.0.0";
+
+/// Serializes `value` with object keys sorted at every level and no whitespace.
+pub fn canonical_payload(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let mut out = String::from("{");
+            for (i, key) in keys.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                // Keys are written verbatim, exactly as the backend does.
+                out.push('"');
+                out.push_str(key);
+                out.push_str("\":");
+                out.push_str(&canonical_payload(&map[*key]));
+            }
+            out.push('}');
+            out
+        }
+        other => other.to_string(),
+    }
+}
+
+fn field<'a>(request: &'a Value, key: &str) -> Option<&'a Value> {
+    request.get(key).filter(|v| !v.is_null())
+}
+
+fn string_field(request: &Value, key: &str) -> Result<Option<String>, String> {
+    match field(request, key) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(format!("Field '{}' must be a string", key)),
+    }
+}
+
+fn required_string(request: &Value, key: &str) -> Result<String, String> {
+    string_field(request, key)?.ok_or_else(|| format!("Missing {}", key))
+}
+
+fn sorted_tags(request: &Value) -> Result<Option<Value>, String> {
+    let Some(tags) = field(request, "tags") else {
+        return Ok(None);
+    };
+    let mut tags = tags
+        .as_array()
+        .ok_or("Field 'tags' must be an array")?
+        .iter()
+        .map(|t| {
+            t.as_str()
+                .map(str::to_string)
+                .ok_or("Field 'tags' must contain only strings")
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    tags.sort();
+    Ok(Some(Value::from(tags)))
+}
+
+/// `dependencies` as signed: `slug@version` of each entry, sorted.
+fn signed_dependencies(request: &Value) -> Result<Option<Value>, String> {
+    let Some(dependencies) = field(request, "dependencies") else {
+        return Ok(None);
+    };
+    let mut signed = dependencies
+        .as_array()
+        .ok_or("Field 'dependencies' must be an array")?
+        .iter()
+        .map(|dependency| {
+            let part = |key: &str| {
+                dependency
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .ok_or("Field 'dependencies' must contain slug/version strings")
+            };
+            Ok(format!("{}@{}", part("slug")?, part("version")?))
+        })
+        .collect::<Result<Vec<_>, &str>>()?;
+    signed.sort();
+    Ok(Some(Value::from(signed)))
+}
+
+/// Signed fields of `POST /api/v1/scripts`.
+pub fn upload_payload(request: &Value) -> Result<Value, String> {
+    let mut payload = Map::new();
+    payload.insert("action".into(), "upload".into());
+    for key in ["title", "description", "category", "bundle"] {
+        payload.insert(key.into(), required_string(request, key)?.into());
+    }
+    payload.insert(
+        "version".into(),
+        string_field(request, "version")?
+            .unwrap_or_else(|| DEFAULT_UPLOAD_VERSION.to_string())
+            .into(),
+    );
+    payload.insert(
+        "author_principal".into(),
+        required_string(request, "author_principal")?.into(),
+    );
+    if let Some(timestamp) = string_field(request, "timestamp")? {
+        payload.insert("timestamp".into(), timestamp.into());
+    }
+    if let Some(tags) = sorted_tags(request)? {
+        payload.insert("tags".into(), tags);
+    }
+    if let Some(compatibility) = string_field(request, "compatibility")? {
+        payload.insert("compatibility".into(), compatibility.into());
+    }
+    if let Some(dependencies) = signed_dependencies(request)? {
+        payload.insert("dependencies".into(), dependencies);
+    }
+    Ok(Value::Object(payload))
+}
+
+/// Signed fields of `PUT /api/v1/scripts/{script_id}`.
+pub fn update_payload(request: &Value, script_id: &str) -> Result<Value, String> {
+    if let Some(body_id) = string_field(request, "script_id")? {
+        if body_id != script_id {
+            return Err("Signed script_id does not match request path".to_string());
+        }
+    }
+    if let Some(action) = string_field(request, "action")? {
+        if action != "update" {
+            return Err(format!("Invalid action '{}' for script update", action));
+        }
+    }
+
+    let mut payload = Map::new();
+    payload.insert("action".into(), "update".into());
+    payload.insert("script_id".into(), script_id.into());
+    payload.insert(
+        "author_principal".into(),
+        required_string(request, "author_principal")?.into(),
+    );
+    for key in [
+        "timestamp",
+        "title",
+        "description",
+        "category",
+        "bundle",
+        "version",
+        "publish_at",
+        "channel",
+    ] {
+        if let Some(value) = string_field(request, key)? {
+            payload.insert(key.into(), value.into());
+        }
+    }
+    if let Some(tags) = sorted_tags(request)? {
+        payload.insert("tags".into(), tags);
+    }
+    if let Some(dependencies) = signed_dependencies(request)? {
+        payload.insert("dependencies".into(), dependencies);
+    }
+    if let Some(price) = field(request, "price") {
+        // The backend deserializes price as f64, so `1` is signed as `1.0`.
+        let price = price
+            .as_f64()
+            .and_then(serde_json::Number::from_f64)
+            .ok_or("Field 'price' must be a finite number")?;
+        payload.insert("price".into(), Value::Number(price));
+    }
+    if let Some(is_public) = field(request, "is_public") {
+        let is_public = is_public
+            .as_bool()
+            .ok_or("Field 'is_public' must be a boolean")?;
+        payload.insert("is_public".into(), is_public.into());
+    }
+    Ok(Value::Object(payload))
+}
+
+/// Signed fields of `DELETE /api/v1/scripts/{script_id}`.
+pub fn delete_payload(request: &Value, script_id: &str) -> Result<Value, String> {
+    let mut payload = Map::new();
+    payload.insert("action".into(), "delete".into());
+    payload.insert("script_id".into(), script_id.into());
+    payload.insert(
+        "author_principal".into(),
+        required_string(request, "author_principal")?.into(),
+    );
+    if let Some(timestamp) = string_field(request, "timestamp")? {
+        payload.insert("timestamp".into(), timestamp.into());
+    }
+    Ok(Value::Object(payload))
+}
+
+/// Builds the signed payload for `action` (`upload`, `update`, `delete`).
+pub fn payload_for_action(
+    action: &str,
+    request: &Value,
+    script_id: Option<&str>,
+) -> Result<Value, String> {
+    let script_id = || script_id.ok_or_else(|| format!("{} requires a script_id", action));
+    match action {
+        "upload" => upload_payload(request),
+        "update" => update_payload(request, script_id()?),
+        "delete" => delete_payload(request, script_id()?),
+        other => Err(format!("Unknown action '{}'", other)),
+    }
+}
+
+/// Verifies a base64 Ed25519 signature over `message`, as the backend does.
+pub fn verify_ed25519(
+    message: &[u8],
+    signature_b64: &str,
+    public_key_b64: &str,
+) -> Result<(), String> {
+    let signature = B64
+        .decode(signature_b64)
+        .map_err(|e| format!("Invalid Ed25519 signature encoding: {}", e))?;
+    let signature = Signature::from_slice(&signature)
+        .map_err(|e| format!("Invalid Ed25519 signature format: {}", e))?;
+    let public_key = B64
+        .decode(public_key_b64)
+        .map_err(|e| format!("Invalid Ed25519 public key encoding: {}", e))?;
+    let public_key: [u8; 32] = public_key
+        .as_slice()
+        .try_into()
+        .map_err(|_| "Invalid Ed25519 public key length".to_string())?;
+    VerifyingKey::from_bytes(&public_key)
+        .map_err(|e| format!("Invalid Ed25519 public key: {}", e))?
+        .verify(message, &signature)
+        .map_err(|e| format!("Ed25519 signature verification failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keypair::sign_ed25519;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_payload_sorts_nested_keys() {
+        let value = json!({"b": 1, "a": {"z": true, "y": [3, "x"]}, "c": "q\"n\n"});
+        assert_eq!(
+            canonical_payload(&value),
+            r#"{"a":{"y":[3,"x"],"z":true},"b":1,"c":"q\"n\n"}"#
+        );
+    }
+
+    #[test]
+    fn test_upload_payload_defaults_version_and_sorts_tags() {
+        let request = json!({
+            "title": "T", "description": "D", "category": "C", "bundle": "B",
+            "author_principal": "p", "tags": ["z", "a"], "signature": "ignored",
+        });
+        assert_eq!(
+            canonical_payload(&upload_payload(&request).unwrap()),
+            r#"{"action":"upload","author_principal":"p","bundle":"B","category":"C","description":"D","tags":["a","z"],"title":"T","version":"1.0.0"}"#
+        );
+        assert!(upload_payload(&json!({"title": "T"})).is_err());
+    }
+
+    #[test]
+    fn test_update_payload_matches_backend_rules() {
+        let request = json!({"author_principal": "p", "price": 1, "is_public": true});
+        assert_eq!(
+            canonical_payload(&update_payload(&request, "s1").unwrap()),
+            r#"{"action":"update","author_principal":"p","is_public":true,"price":1.0,"script_id":"s1"}"#
+        );
+        let with_dependencies = json!({
+            "author_principal": "p",
+            "dependencies": [{"slug": "z-lib", "version": "^1"}, {"slug": "a-lib", "version": "~2.1"}],
+        });
+        assert_eq!(
+            canonical_payload(&update_payload(&with_dependencies, "s1").unwrap()),
+            r#"{"action":"update","author_principal":"p","dependencies":["a-lib@~2.1","z-lib@^1"],"script_id":"s1"}"#
+        );
+        let mismatched = json!({"author_principal": "p", "script_id": "other"});
+        assert!(update_payload(&mismatched, "s1").is_err());
+        assert!(payload_for_action("delete", &request, None).is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let secret = B64.encode([7u8; 32]);
+        let public = B64.encode(
+            ed25519_dalek::SigningKey::from_bytes(&[7u8; 32])
+                .verifying_key()
+                .as_bytes(),
+        );
+        let signature = sign_ed25519(b"payload", &secret).unwrap();
+        verify_ed25519(b"payload", &signature, &public).unwrap();
+        assert!(verify_ed25519(b"tampered", &signature, &public).is_err());
+    }
+
+    #[test]
+    fn test_backend_update_fixture_signature() {
+        // backend/tests/signature_tests.rs: dart_generated_update_signature_verifies
+        let request = json!({
+            "author_principal": "yhnve-5y5qy-svqjc-aiobw-3a53m-n2gzt-xlrvn-s7kld-r5xid-td2ef-iae",
+            "timestamp": "2025-11-06T13:36:31.766449Z",
+            "title": "Updated Title",
+            "price": 0.0,
+            "is_public": true,
+            "tags": ["test", "unit"],
+        });
+        let payload = update_payload(&request, "41935708-8561-4424-a42f-cba44e26785a").unwrap();
+        let signature = sign_ed25519(
+            canonical_payload(&payload).as_bytes(),
+            &B64.encode([11u8; 32]),
+        )
+        .unwrap();
+        assert_eq!(
+            signature,
+            "2umMyrRT5PEnn4d8FiHV2oWhc3oPuRVw9fOWrra4yqhWU4MPlto5HBUneH6fG7rxD+yBXO/iPOIsYbpvW9ntCQ=="
+        );
+    }
+}
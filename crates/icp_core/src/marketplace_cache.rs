@@ -0,0 +1,546 @@
+//! Offline-first cache of marketplace listings and script bodies.
+//!
+//! Listings from `GET /api/v1/scripts` and bundles from
+//! `GET /api/v1/scripts/:id` are kept in a local SQLite file so the app can
+//! browse and run previously seen scripts without a network, and so startup
+//! only asks the backend for what changed.
+//!
+//! Delta sync sends the last seen `ETag` as `If-None-Match` and the newest
+//! `updated_at` already cached as `updated_since`. A `304` ends the sync; a
+//! server that ignores `updated_since` simply returns the full catalog, which
+//! is upserted by id, so the result is the same either way. Listings carrying
+//! a `deleted_at` are dropped together with their cached body.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Page size used when walking the catalog during a sync.
+pub const SYNC_PAGE_SIZE: u32 = 100;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS listings (
+    id TEXT PRIMARY KEY,
+    listing_json TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bodies (
+    script_id TEXT PRIMARY KEY,
+    version TEXT NOT NULL,
+    bundle TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("cache database error: {0}")]
+    Db(#[from] rusqlite::Error),
+    #[error("network error: {0}")]
+    Net(String),
+    #[error("unexpected backend response: {0}")]
+    Protocol(String),
+}
+
+/// One page of `GET /api/v1/scripts`, or a `304`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogPage {
+    NotModified,
+    Page {
+        scripts: Vec<Value>,
+        has_more: bool,
+        etag: Option<String>,
+    },
+}
+
+/// Where listings and bodies come from; [`HttpCatalog`] in the app.
+pub trait CatalogSource {
+    fn fetch_page(
+        &self,
+        offset: u32,
+        limit: u32,
+        updated_since: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Result<CatalogPage, CacheError>;
+
+    /// Returns the script detail object (including `bundle`).
+    fn fetch_script(&self, script_id: &str) -> Result<Value, CacheError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedBody {
+    pub script_id: String,
+    pub version: String,
+    pub bundle: String,
+    pub fetched_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SyncReport {
+    pub upserted: usize,
+    pub removed: usize,
+    pub not_modified: bool,
+}
+
+pub struct MarketplaceCache {
+    conn: Mutex<Connection>,
+}
+
+impl MarketplaceCache {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CacheError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, CacheError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, CacheError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // A panic while holding the lock cannot leave SQLite inconsistent
+        // (statements are atomic), so a poisoned guard is still usable.
+        self.conn.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    fn state(&self, key: &str) -> Result<Option<String>, CacheError> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn set_state(conn: &Connection, key: &str, value: &str) -> Result<(), CacheError> {
+        conn.execute(
+            "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Unix ms of the last successful sync, if any.
+    pub fn last_sync_at(&self) -> Result<Option<u64>, CacheError> {
+        Ok(self.state("last_sync_at")?.and_then(|v| v.parse().ok()))
+    }
+
+    /// True when there was never a sync or the last one is older than `max_age`.
+    pub fn is_stale(&self, now: u64, max_age: Duration) -> Result<bool, CacheError> {
+        Ok(match self.last_sync_at()? {
+            Some(at) => now.saturating_sub(at) > max_age.as_millis() as u64,
+            None => true,
+        })
+    }
+
+    /// Upserts listings and drops deleted ones. Returns `(upserted, removed)`.
+    pub fn apply_listings(
+        &self,
+        scripts: &[Value],
+        now: u64,
+    ) -> Result<(usize, usize), CacheError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let (mut upserted, mut removed) = (0, 0);
+        for script in scripts {
+            let id = script
+                .get("id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| CacheError::Protocol("listing without id".into()))?;
+            if script.get("deleted_at").is_some_and(|d| !d.is_null()) {
+                tx.execute("DELETE FROM listings WHERE id = ?1", params![id])?;
+                tx.execute("DELETE FROM bodies WHERE script_id = ?1", params![id])?;
+                removed += 1;
+                continue;
+            }
+            let updated_at = script
+                .get("updated_at")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            tx.execute(
+                "INSERT INTO listings (id, listing_json, updated_at, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(id) DO UPDATE SET listing_json = excluded.listing_json,
+                     updated_at = excluded.updated_at, fetched_at = excluded.fetched_at",
+                params![id, script.to_string(), updated_at, now as i64],
+            )?;
+            upserted += 1;
+        }
+        tx.commit()?;
+        Ok((upserted, removed))
+    }
+
+    /// Cached listings, most recently updated first.
+    pub fn listings(&self) -> Result<Vec<Value>, CacheError> {
+        let conn = self.conn();
+        let mut stmt =
+            conn.prepare("SELECT listing_json FROM listings ORDER BY updated_at DESC, id")?;
+        let mut listings = Vec::new();
+        for json in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            listings.push(
+                serde_json::from_str(&json?).map_err(|e| CacheError::Protocol(e.to_string()))?,
+            );
+        }
+        Ok(listings)
+    }
+
+    pub fn listing(&self, script_id: &str) -> Result<Option<Value>, CacheError> {
+        let json: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT listing_json FROM listings WHERE id = ?1",
+                params![script_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        json.map(|j| serde_json::from_str(&j).map_err(|e| CacheError::Protocol(e.to_string())))
+            .transpose()
+    }
+
+    pub fn store_body(
+        &self,
+        script_id: &str,
+        version: &str,
+        bundle: &str,
+        now: u64,
+    ) -> Result<(), CacheError> {
+        self.conn().execute(
+            "INSERT INTO bodies (script_id, version, bundle, fetched_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(script_id) DO UPDATE SET version = excluded.version,
+                 bundle = excluded.bundle, fetched_at = excluded.fetched_at",
+            params![script_id, version, bundle, now as i64],
+        )?;
+        Ok(())
+    }
+
+    pub fn body(&self, script_id: &str) -> Result<Option<CachedBody>, CacheError> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT script_id, version, bundle, fetched_at FROM bodies WHERE script_id = ?1",
+                params![script_id],
+                |row| {
+                    Ok(CachedBody {
+                        script_id: row.get(0)?,
+                        version: row.get(1)?,
+                        bundle: row.get(2)?,
+                        fetched_at: row.get::<_, i64>(3)? as u64,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Cached body if it matches the listing's current version, otherwise
+    /// fetched from `source` and cached. Falls back to a stale body when the
+    /// fetch fails, so offline runs keep working.
+    pub fn body_or_fetch(
+        &self,
+        source: &dyn CatalogSource,
+        script_id: &str,
+        now: u64,
+    ) -> Result<CachedBody, CacheError> {
+        let cached = self.body(script_id)?;
+        let listed_version = self
+            .listing(script_id)?
+            .and_then(|l| l.get("version").and_then(Value::as_str).map(str::to_string));
+        if let Some(body) = &cached {
+            if listed_version.as_deref().is_none_or(|v| v == body.version) {
+                return Ok(body.clone());
+            }
+        }
+        let fetched = match source.fetch_script(script_id) {
+            Ok(detail) => detail,
+            Err(e) => return cached.ok_or(e),
+        };
+        let field = |key: &str| {
+            fetched
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| CacheError::Protocol(format!("script detail without {}", key)))
+        };
+        let body = CachedBody {
+            script_id: script_id.to_string(),
+            version: field("version")?,
+            bundle: field("bundle")?,
+            fetched_at: now,
+        };
+        self.store_body(script_id, &body.version, &body.bundle, now)?;
+        Ok(body)
+    }
+
+    /// Pulls everything changed since the last sync from `source`.
+    pub fn sync(&self, source: &dyn CatalogSource, now: u64) -> Result<SyncReport, CacheError> {
+        let etag = self.state("etag")?;
+        let since: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT MAX(updated_at) FROM listings WHERE updated_at != ''",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        let mut report = SyncReport::default();
+        let mut offset = 0;
+        let mut new_etag = None;
+        loop {
+            // The ETag describes the first page; later pages are only fetched
+            // after it changed, so they never carry one.
+            let if_none_match = if offset == 0 { etag.as_deref() } else { None };
+            match source.fetch_page(offset, SYNC_PAGE_SIZE, since.as_deref(), if_none_match)? {
+                CatalogPage::NotModified => {
+                    report.not_modified = true;
+                    break;
+                }
+                CatalogPage::Page {
+                    scripts,
+                    has_more,
+                    etag,
+                } => {
+                    if offset == 0 {
+                        new_etag = etag;
+                    }
+                    let (upserted, removed) = self.apply_listings(&scripts, now)?;
+                    report.upserted += upserted;
+                    report.removed += removed;
+                    if !has_more || scripts.is_empty() {
+                        break;
+                    }
+                    offset += scripts.len() as u32;
+                }
+            }
+        }
+
+        let conn = self.conn();
+        if let Some(etag) = new_etag {
+            Self::set_state(&conn, "etag", &etag)?;
+        }
+        Self::set_state(&conn, "last_sync_at", &now.to_string())?;
+        Ok(report)
+    }
+}
+
+/// [`CatalogSource`] backed by the marketplace HTTP API.
+pub struct HttpCatalog {
+    base_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpCatalog {
+    /// `base_url` is the API origin, e.g. `https://marketplace.example.com`.
+    pub fn new(base_url: &str) -> Result<Self, CacheError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| CacheError::Net(e.to_string()))?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    fn data(response: reqwest::blocking::Response) -> Result<Value, CacheError> {
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .map_err(|e| CacheError::Protocol(format!("invalid JSON ({}): {}", status, e)))?;
+        if !status.is_success() || body.get("success") != Some(&Value::Bool(true)) {
+            return Err(CacheError::Protocol(format!(
+                "{}: {}",
+                status,
+                body.get("error")
+                    .and_then(Value::as_str)
+                    .unwrap_or("request failed")
+            )));
+        }
+        body.get("data")
+            .cloned()
+            .ok_or_else(|| CacheError::Protocol("response without data".into()))
+    }
+}
+
+impl CatalogSource for HttpCatalog {
+    fn fetch_page(
+        &self,
+        offset: u32,
+        limit: u32,
+        updated_since: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Result<CatalogPage, CacheError> {
+        let mut query = vec![("limit", limit.to_string()), ("offset", offset.to_string())];
+        if let Some(since) = updated_since {
+            query.push(("updated_since", since.to_string()));
+        }
+        let mut request = self
+            .client
+            .get(format!("{}/api/v1/scripts", self.base_url))
+            .query(&query);
+        if let Some(etag) = if_none_match {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        let response = request.send().map_err(|e| CacheError::Net(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(CatalogPage::NotModified);
+        }
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let data = Self::data(response)?;
+        let scripts = data
+            .get("scripts")
+            .and_then(Value::as_array)
+            .cloned()
+            .ok_or_else(|| CacheError::Protocol("listing without scripts".into()))?;
+        Ok(CatalogPage::Page {
+            has_more: data
+                .get("hasMore")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            scripts,
+            etag,
+        })
+    }
+
+    fn fetch_script(&self, script_id: &str) -> Result<Value, CacheError> {
+        let response = self
+            .client
+            .get(format!("{}/api/v1/scripts/{}", self.base_url, script_id))
+            .send()
+            .map_err(|e| CacheError::Net(e.to_string()))?;
+        Self::data(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    /// Serves fixed pages and records the conditional headers it was sent.
+    #[derive(Default)]
+    struct FakeCatalog {
+        pages: RefCell<Vec<CatalogPage>>,
+        seen: RefCell<Vec<(u32, Option<String>, Option<String>)>>,
+        detail: Option<Value>,
+    }
+
+    impl CatalogSource for FakeCatalog {
+        fn fetch_page(
+            &self,
+            offset: u32,
+            _limit: u32,
+            updated_since: Option<&str>,
+            if_none_match: Option<&str>,
+        ) -> Result<CatalogPage, CacheError> {
+            self.seen.borrow_mut().push((
+                offset,
+                updated_since.map(str::to_string),
+                if_none_match.map(str::to_string),
+            ));
+            Ok(self.pages.borrow_mut().remove(0))
+        }
+
+        fn fetch_script(&self, _script_id: &str) -> Result<Value, CacheError> {
+            self.detail
+                .clone()
+                .ok_or_else(|| CacheError::Net("offline".into()))
+        }
+    }
+
+    fn listing(id: &str, updated_at: &str, version: &str) -> Value {
+        json!({"id": id, "title": id, "updated_at": updated_at, "version": version})
+    }
+
+    #[test]
+    fn test_sync_pages_then_sends_conditional_headers() {
+        let cache = MarketplaceCache::open_in_memory().unwrap();
+        assert!(cache.is_stale(0, Duration::from_secs(60)).unwrap());
+
+        let source = FakeCatalog::default();
+        source.pages.borrow_mut().extend([
+            CatalogPage::Page {
+                scripts: vec![listing("a", "2025-01-01", "1.0.0")],
+                has_more: true,
+                etag: Some("\"v1\"".into()),
+            },
+            CatalogPage::Page {
+                scripts: vec![listing("b", "2025-01-02", "1.0.0")],
+                has_more: false,
+                etag: None,
+            },
+        ]);
+        let report = cache.sync(&source, 1_000).unwrap();
+        assert_eq!(report.upserted, 2);
+        assert_eq!(cache.listings().unwrap()[0]["id"], "b");
+        assert!(!cache.is_stale(1_500, Duration::from_secs(60)).unwrap());
+
+        source.pages.borrow_mut().push(CatalogPage::NotModified);
+        assert!(cache.sync(&source, 2_000).unwrap().not_modified);
+        let last = source.seen.borrow().last().cloned().unwrap();
+        assert_eq!(last, (0, Some("2025-01-02".into()), Some("\"v1\"".into())));
+        assert_eq!(cache.last_sync_at().unwrap(), Some(2_000));
+    }
+
+    #[test]
+    fn test_deleted_listing_drops_body() {
+        let cache = MarketplaceCache::open_in_memory().unwrap();
+        cache
+            .apply_listings(&[listing("a", "2025-01-01", "1.0.0")], 1)
+            .unwrap();
+        cache.store_body("a", "1.0.0", "export {}", 1).unwrap();
+        let mut deleted = listing("a", "2025-01-03", "1.0.0");
+        deleted["deleted_at"] = json!("2025-01-03");
+        assert_eq!(cache.apply_listings(&[deleted], 2).unwrap(), (0, 1));
+        assert!(cache.listing("a").unwrap().is_none());
+        assert!(cache.body("a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_body_refetched_on_version_change_and_served_offline() {
+        let cache = MarketplaceCache::open_in_memory().unwrap();
+        cache
+            .apply_listings(&[listing("a", "2025-01-01", "2.0.0")], 1)
+            .unwrap();
+        cache.store_body("a", "1.0.0", "old", 1).unwrap();
+
+        let offline = FakeCatalog::default();
+        assert_eq!(cache.body_or_fetch(&offline, "a", 5).unwrap().bundle, "old");
+
+        let online = FakeCatalog {
+            detail: Some(json!({"id": "a", "version": "2.0.0", "bundle": "new"})),
+            ..Default::default()
+        };
+        let body = cache.body_or_fetch(&online, "a", 6).unwrap();
+        assert_eq!(
+            (body.version.as_str(), body.bundle.as_str()),
+            ("2.0.0", "new")
+        );
+        assert_eq!(cache.body("a").unwrap().unwrap().fetched_at, 6);
+        assert!(matches!(
+            cache.body_or_fetch(&offline, "missing", 7),
+            Err(CacheError::Net(_))
+        ));
+    }
+}
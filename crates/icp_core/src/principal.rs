@@ -49,3 +49,150 @@ pub fn principal_from_public_key(alg: &str, public_key: &[u8]) -> Option<String>
     let der = der_encode_public_key(alg, public_key).ok()?;
     Some(principal_from_der(&der))
 }
+
+/// Length of an ICP ledger subaccount.
+pub const SUBACCOUNT_LEN: usize = 32;
+
+const ACCOUNT_DOMAIN_SEPARATOR: &[u8] = b"\x0Aaccount-id";
+const MAX_PRINCIPAL_BYTES: usize = 29;
+
+/// Why a textual principal was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrincipalTextError {
+    #[error("principal text is empty")]
+    Empty,
+    #[error("principal text must be lowercase")]
+    NotLowercase,
+    #[error("invalid character {0:?} in principal text")]
+    InvalidCharacter(char),
+    #[error("principal text is not valid base32")]
+    InvalidBase32,
+    #[error("principal text is too short to contain a checksum")]
+    TooShort,
+    #[error("principal is {0} bytes; at most 29 are allowed")]
+    TooLong(usize),
+    #[error("checksum mismatch: expected {expected:08x}, found {found:08x}")]
+    ChecksumMismatch { expected: u32, found: u32 },
+    #[error("principal is not in canonical dash-separated form (expected {0})")]
+    NotCanonical(String),
+}
+
+/// Principal class, from the trailing type byte (IC interface spec, "Principals").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalKind {
+    /// Zero-length principal: the management canister `aaaaa-aa`.
+    ManagementCanister,
+    /// Canister ids and other system-assigned principals (suffix 0x01).
+    Opaque,
+    /// Derived from a user public key (suffix 0x02).
+    SelfAuthenticating,
+    /// Derived from another principal (suffix 0x03).
+    Derived,
+    /// The anonymous principal `2vxsx-fae` (suffix 0x04).
+    Anonymous,
+    /// Reserved class (suffix 0x7f).
+    Reserved,
+    Unknown,
+}
+
+/// Parses a textual principal, reporting exactly which check failed.
+pub fn parse_principal_text(text: &str) -> Result<Principal, PrincipalTextError> {
+    if text.is_empty() {
+        return Err(PrincipalTextError::Empty);
+    }
+    if let Some(c) = text
+        .chars()
+        .find(|c| !c.is_ascii_lowercase() && !c.is_ascii_digit() && *c != '-')
+    {
+        return Err(if c.is_ascii_uppercase() {
+            PrincipalTextError::NotLowercase
+        } else {
+            PrincipalTextError::InvalidCharacter(c)
+        });
+    }
+    let compact: String = text.chars().filter(|c| *c != '-').collect();
+    let decoded = data_encoding::BASE32_NOPAD
+        .decode(compact.to_ascii_uppercase().as_bytes())
+        .map_err(|_| PrincipalTextError::InvalidBase32)?;
+    if decoded.len() < 4 {
+        return Err(PrincipalTextError::TooShort);
+    }
+    let (checksum, bytes) = decoded.split_at(4);
+    if bytes.len() > MAX_PRINCIPAL_BYTES {
+        return Err(PrincipalTextError::TooLong(bytes.len()));
+    }
+    let found = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    let expected = crc32fast::hash(bytes);
+    if found != expected {
+        return Err(PrincipalTextError::ChecksumMismatch { expected, found });
+    }
+    let principal = Principal::from_slice(bytes);
+    let canonical = principal.to_text();
+    if canonical != text {
+        return Err(PrincipalTextError::NotCanonical(canonical));
+    }
+    Ok(principal)
+}
+
+pub fn classify_principal(principal: &Principal) -> PrincipalKind {
+    match principal.as_slice().last() {
+        None => PrincipalKind::ManagementCanister,
+        Some(0x01) => PrincipalKind::Opaque,
+        Some(0x02) => PrincipalKind::SelfAuthenticating,
+        Some(0x03) => PrincipalKind::Derived,
+        Some(0x04) => PrincipalKind::Anonymous,
+        Some(0x7f) => PrincipalKind::Reserved,
+        Some(_) => PrincipalKind::Unknown,
+    }
+}
+
+/// Subaccount whose last 8 bytes are `index` big-endian (the convention used
+/// by the NNS dapp and dfx `--subaccount`).
+pub fn subaccount_from_index(index: u64) -> [u8; SUBACCOUNT_LEN] {
+    let mut sub = [0u8; SUBACCOUNT_LEN];
+    sub[SUBACCOUNT_LEN - 8..].copy_from_slice(&index.to_be_bytes());
+    sub
+}
+
+/// ICP ledger account identifier as 64 lowercase hex characters:
+/// `crc32(h) || h` where `h = sha224("\x0Aaccount-id" || principal || subaccount)`.
+/// `None` means the default (all-zero) subaccount.
+pub fn account_identifier(
+    principal: &Principal,
+    subaccount: Option<&[u8; SUBACCOUNT_LEN]>,
+) -> String {
+    use sha2::{Digest, Sha224};
+
+    let mut hasher = Sha224::new();
+    hasher.update(ACCOUNT_DOMAIN_SEPARATOR);
+    hasher.update(principal.as_slice());
+    hasher.update(subaccount.unwrap_or(&[0u8; SUBACCOUNT_LEN]));
+    let hash = hasher.finalize();
+
+    let mut out = crc32fast::hash(&hash).to_be_bytes().to_vec();
+    out.extend_from_slice(&hash);
+    hex::encode(out)
+}
+
+/// Checks length, hex encoding and the CRC32 prefix of an account identifier.
+pub fn validate_account_identifier(account_id_hex: &str) -> Result<(), String> {
+    if account_id_hex.len() != 64 {
+        return Err(format!(
+            "account identifier must be 64 hex characters, got {}",
+            account_id_hex.len()
+        ));
+    }
+    let bytes = hex::decode(account_id_hex)
+        .map_err(|e| format!("account identifier is not valid hex: {}", e))?;
+    let (checksum, hash) = bytes.split_at(4);
+    let found = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    let expected = crc32fast::hash(hash);
+    if found != expected {
+        return Err(format!(
+            "account identifier checksum mismatch: expected {:08x}, found {:08x}",
+            expected, found
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,465 @@
+//! Auto-approval policy for value-transferring canister calls made by
+//! scripts.
+//!
+//! While a script is running (the host names it with [`set_active_script`]),
+//! every live canister call is checked with [`authorize`] before it goes out.
+//! Calls to a ledger transfer method (see [`TRANSFER_METHODS`]) are approved
+//! silently only while they stay within the script's limits for that token:
+//! a per-call amount and a total per UTC day. Anything above, or a transfer
+//! whose amount cannot be read from the arguments, is put to the host's
+//! confirmation handler; without a handler it is rejected.
+//!
+//! Tokens are identified by their ledger canister id and amounts are in the
+//! ledger's base unit (e8s for ICP). Calls made outside a script, e.g. from
+//! the canister explorer, are not subject to the policy.
+
+use crate::candid_value;
+use candid::types::value::{IDLField, IDLValue};
+use candid::IDLArgs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Methods treated as moving value: ICRC-1/2 and the ICP ledger's own.
+pub const TRANSFER_METHODS: &[&str] = &[
+    "icrc1_transfer",
+    "icrc2_approve",
+    "icrc2_transfer_from",
+    "transfer",
+    "send_dfx",
+];
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Amounts a script may move without asking, per token. In JSON they are
+/// decimal strings (numbers are accepted too).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenLimits {
+    /// Largest single transfer approved without confirmation.
+    #[serde(with = "amount_string")]
+    pub per_call: u128,
+    /// Total approved without confirmation per UTC day.
+    #[serde(with = "amount_string")]
+    pub daily: u128,
+}
+
+// JSON numbers lose precision above 2^53 in most hosts.
+mod amount_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(amount: &u128, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&amount.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<u128, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Amount {
+            Text(String),
+            Number(u64),
+        }
+        match Amount::deserialize(d)? {
+            Amount::Text(s) => s
+                .parse()
+                .map_err(|_| D::Error::custom(format!("`{s}` is not a valid amount"))),
+            Amount::Number(n) => Ok(u128::from(n)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferPolicy {
+    /// Limits for every script, keyed by ledger canister id. Tokens not
+    /// listed always need confirmation.
+    #[serde(default)]
+    pub tokens: BTreeMap<String, TokenLimits>,
+    /// Per-script limits keyed by script id, then ledger canister id. They
+    /// replace the default limits of that token for that script.
+    #[serde(default)]
+    pub scripts: BTreeMap<String, BTreeMap<String, TokenLimits>>,
+}
+
+impl TransferPolicy {
+    pub fn limits(&self, script_id: &str, token: &str) -> TokenLimits {
+        self.scripts
+            .get(script_id)
+            .and_then(|tokens| tokens.get(token))
+            .or_else(|| self.tokens.get(token))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// A value-transferring call, as shown to the host for confirmation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferRequest {
+    pub script_id: String,
+    /// Ledger canister id.
+    pub token: String,
+    pub method: String,
+    /// `None` when the amount could not be read from the arguments.
+    #[serde(serialize_with = "amount_as_string")]
+    pub amount: Option<u128>,
+    /// Why the call was not approved automatically.
+    pub reason: String,
+}
+
+fn amount_as_string<S: serde::Serializer>(amount: &Option<u128>, s: S) -> Result<S::Ok, S::Error> {
+    match amount {
+        Some(v) => s.serialize_str(&v.to_string()),
+        None => s.serialize_none(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Within limits; counted against the daily allowance.
+    Approved,
+    /// Above limits or of unknown amount; ask the host.
+    NeedsConfirmation(TransferRequest),
+}
+
+/// The amount moved by a call of `method` with `args` (Candid text,
+/// `base64:` bytes or JSON, as accepted by the canister call entry points);
+/// `None` if `method` is not a transfer method. The inner `None` means a
+/// transfer of unknown amount.
+pub fn transfer_amount(method: &str, args: &str) -> Option<Option<u128>> {
+    if !TRANSFER_METHODS.contains(&method) {
+        return None;
+    }
+    Some(amount_of_args(args))
+}
+
+fn amount_of_args(args: &str) -> Option<u128> {
+    let t = args.trim_start();
+    if let Some(rest) = t.strip_prefix("base64:") {
+        use base64::Engine as _;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(rest.trim())
+            .ok()?;
+        return idl_amount(&IDLArgs::from_bytes(&bytes).ok()?);
+    }
+    if t.starts_with('(') {
+        return idl_amount(&candid_value::parse_args(t).ok()?);
+    }
+    let json: Value = serde_json::from_str(t).ok()?;
+    let first = match &json {
+        Value::Array(items) => items.first()?,
+        other => other,
+    };
+    json_amount(first.get("amount")?)
+}
+
+fn idl_amount(args: &IDLArgs) -> Option<u128> {
+    match args.args.first()? {
+        IDLValue::Record(fields) => idl_number(field(fields, "amount")?),
+        _ => None,
+    }
+}
+
+fn field<'a>(fields: &'a [IDLField], name: &str) -> Option<&'a IDLValue> {
+    // Decoded bytes carry hashed labels, parsed text carries names.
+    let id = candid::idl_hash(name);
+    fields.iter().find(|f| f.id.get_id() == id).map(|f| &f.val)
+}
+
+fn idl_number(value: &IDLValue) -> Option<u128> {
+    match value {
+        IDLValue::Nat(n) => n.0.to_string().parse().ok(),
+        IDLValue::Nat64(v) => Some(u128::from(*v)),
+        IDLValue::Nat32(v) => Some(u128::from(*v)),
+        IDLValue::Number(s) => s.replace('_', "").parse().ok(),
+        // ICP ledger `Tokens`: record { e8s : nat64 }.
+        IDLValue::Record(fields) => idl_number(field(fields, "e8s")?),
+        _ => None,
+    }
+}
+
+fn json_amount(value: &Value) -> Option<u128> {
+    match value {
+        Value::Number(n) => n.as_u64().map(u128::from),
+        Value::String(s) => s.replace('_', "").parse().ok(),
+        Value::Object(obj) => json_amount(obj.get("e8s")?),
+        _ => None,
+    }
+}
+
+/// Policy plus the amounts auto-approved so far, per script and token.
+#[derive(Debug, Default)]
+pub struct PolicyEngine {
+    policy: TransferPolicy,
+    /// (script, token) -> (UTC day, total approved that day).
+    spent: HashMap<(String, String), (u64, u128)>,
+}
+
+impl PolicyEngine {
+    pub fn new(policy: TransferPolicy) -> Self {
+        Self {
+            policy,
+            spent: HashMap::new(),
+        }
+    }
+
+    pub fn policy(&self) -> &TransferPolicy {
+        &self.policy
+    }
+
+    /// Replaces the limits; amounts already approved today still count.
+    pub fn set_policy(&mut self, policy: TransferPolicy) {
+        self.policy = policy;
+    }
+
+    /// Amount auto-approved today for `script_id` on `token`.
+    pub fn spent_today(&self, script_id: &str, token: &str, now_ms: u64) -> u128 {
+        match self.spent.get(&(script_id.to_string(), token.to_string())) {
+            Some((day, total)) if *day == now_ms / DAY_MS => *total,
+            _ => 0,
+        }
+    }
+
+    /// Decides on a transfer of `amount` and, when approved, counts it
+    /// against today's allowance. Transfers the host confirms are not
+    /// counted: the allowance only bounds what moves without asking.
+    pub fn evaluate(
+        &mut self,
+        script_id: &str,
+        token: &str,
+        method: &str,
+        amount: Option<u128>,
+        now_ms: u64,
+    ) -> Decision {
+        let limits = self.policy.limits(script_id, token);
+        let spent = self.spent_today(script_id, token, now_ms);
+        let reason = match amount {
+            None => Some("amount could not be determined".to_string()),
+            Some(a) if a > limits.per_call => Some(format!(
+                "{a} exceeds the per-call limit of {}",
+                limits.per_call
+            )),
+            Some(a) if spent.saturating_add(a) > limits.daily => Some(format!(
+                "{a} would exceed the daily limit of {} ({spent} already approved today)",
+                limits.daily
+            )),
+            Some(_) => None,
+        };
+        match (reason, amount) {
+            (None, Some(a)) => {
+                self.spent.insert(
+                    (script_id.to_string(), token.to_string()),
+                    (now_ms / DAY_MS, spent + a),
+                );
+                Decision::Approved
+            }
+            (reason, amount) => Decision::NeedsConfirmation(TransferRequest {
+                script_id: script_id.to_string(),
+                token: token.to_string(),
+                method: method.to_string(),
+                amount,
+                reason: reason.unwrap_or_default(),
+            }),
+        }
+    }
+}
+
+/// Host confirmation; returns whether the transfer may go ahead. It runs on
+/// the thread making the call and blocks it until the user answers.
+pub type ConfirmHandler = Arc<dyn Fn(&TransferRequest) -> bool + Send + Sync>;
+
+struct State {
+    engine: PolicyEngine,
+    script: Option<String>,
+    confirm: Option<ConfirmHandler>,
+}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    let mut guard = STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    f(guard.get_or_insert_with(|| State {
+        engine: PolicyEngine::default(),
+        script: None,
+        confirm: None,
+    }))
+}
+
+pub fn set_policy(policy: TransferPolicy) {
+    with_state(|s| s.engine.set_policy(policy));
+}
+
+pub fn policy() -> TransferPolicy {
+    with_state(|s| s.engine.policy().clone())
+}
+
+/// Names the script whose calls follow; `None` when no script is running.
+pub fn set_active_script(script_id: Option<String>) {
+    with_state(|s| s.script = script_id);
+}
+
+pub fn set_confirm_handler(handler: Option<ConfirmHandler>) {
+    with_state(|s| s.confirm = handler);
+}
+
+/// Checks a live canister call against the policy. `Err` carries the reason
+/// a transfer was rejected.
+pub fn authorize(canister_id: &str, method: &str, args: &str, now_ms: u64) -> Result<(), String> {
+    let amount = match transfer_amount(method, args) {
+        Some(amount) => amount,
+        None => return Ok(()),
+    };
+    // Decide under the lock, but ask the host without it: confirmation
+    // waits on the user.
+    let (decision, confirm) = with_state(|s| {
+        let script = match s.script.clone() {
+            Some(script) => script,
+            None => return (Decision::Approved, None),
+        };
+        let decision = s
+            .engine
+            .evaluate(&script, canister_id, method, amount, now_ms);
+        (decision, s.confirm.clone())
+    });
+    match decision {
+        Decision::Approved => Ok(()),
+        Decision::NeedsConfirmation(request) => match confirm {
+            Some(confirm) if confirm(&request) => Ok(()),
+            Some(_) => Err(format!("declined by user: {}", request.reason)),
+            None => Err(format!(
+                "{}; no confirmation handler registered",
+                request.reason
+            )),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEDGER: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+    const NOON: u64 = 12 * 60 * 60 * 1000;
+
+    fn engine() -> PolicyEngine {
+        let limits = TokenLimits {
+            per_call: 100,
+            daily: 250,
+        };
+        let mut policy = TransferPolicy::default();
+        policy.tokens.insert(LEDGER.into(), limits);
+        policy.scripts.insert(
+            "trusted".into(),
+            BTreeMap::from([(
+                LEDGER.to_string(),
+                TokenLimits {
+                    per_call: 1_000,
+                    daily: 1_000,
+                },
+            )]),
+        );
+        PolicyEngine::new(policy)
+    }
+
+    #[test]
+    fn reads_amounts_from_candid_text_json_and_bytes() {
+        let text =
+            r#"(record { to = record { owner = principal "aaaaa-aa" }; amount = 1_500 : nat })"#;
+        assert_eq!(transfer_amount("icrc1_transfer", text), Some(Some(1_500)));
+        assert_eq!(
+            transfer_amount(
+                "transfer",
+                "(record { amount = record { e8s = 42 : nat64 } })"
+            ),
+            Some(Some(42))
+        );
+        assert_eq!(
+            transfer_amount("icrc2_approve", r#"[{"amount": "9000000000000000000000"}]"#),
+            Some(Some(9_000_000_000_000_000_000_000))
+        );
+        assert_eq!(
+            transfer_amount("send_dfx", r#"{"amount": {"e8s": 7}}"#),
+            Some(Some(7))
+        );
+
+        use base64::Engine as _;
+        let bytes = candid_value::parse_args("(record { amount = 5 : nat })")
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+        let b64 = format!(
+            "base64:{}",
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        );
+        assert_eq!(transfer_amount("icrc1_transfer", &b64), Some(Some(5)));
+
+        assert_eq!(transfer_amount("icrc1_transfer", "(42)"), Some(None));
+        assert_eq!(transfer_amount("icrc1_balance_of", text), None);
+    }
+
+    #[test]
+    fn limits_apply_per_call_and_per_day() {
+        let mut engine = engine();
+        let eval = |e: &mut PolicyEngine, amount, now| {
+            e.evaluate("s", LEDGER, "icrc1_transfer", Some(amount), now)
+        };
+        assert_eq!(eval(&mut engine, 100, NOON), Decision::Approved);
+        assert_eq!(eval(&mut engine, 100, NOON), Decision::Approved);
+        match eval(&mut engine, 100, NOON) {
+            Decision::NeedsConfirmation(req) => assert!(req.reason.contains("daily")),
+            other => panic!("expected confirmation, got {other:?}"),
+        }
+        match eval(&mut engine, 101, NOON) {
+            Decision::NeedsConfirmation(req) => assert!(req.reason.contains("per-call")),
+            other => panic!("expected confirmation, got {other:?}"),
+        }
+        assert_eq!(engine.spent_today("s", LEDGER, NOON), 200);
+        // A new UTC day resets the allowance.
+        assert_eq!(eval(&mut engine, 100, NOON + DAY_MS), Decision::Approved);
+        assert_eq!(engine.spent_today("s", LEDGER, NOON + DAY_MS), 100);
+    }
+
+    #[test]
+    fn script_overrides_and_unknown_tokens() {
+        let mut engine = engine();
+        assert_eq!(
+            engine.evaluate("trusted", LEDGER, "transfer", Some(900), NOON),
+            Decision::Approved
+        );
+        assert!(matches!(
+            engine.evaluate(
+                "s",
+                "mxzaz-hqaaa-aaaar-qaada-cai",
+                "icrc1_transfer",
+                Some(1),
+                NOON
+            ),
+            Decision::NeedsConfirmation(_)
+        ));
+        assert!(matches!(
+            engine.evaluate("s", LEDGER, "icrc1_transfer", None, NOON),
+            Decision::NeedsConfirmation(TransferRequest { amount: None, .. })
+        ));
+    }
+
+    #[test]
+    fn requests_serialize_amounts_as_strings() {
+        let request = TransferRequest {
+            script_id: "s".into(),
+            token: LEDGER.into(),
+            method: "icrc1_transfer".into(),
+            amount: Some(u128::MAX),
+            reason: "r".into(),
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["amount"], u128::MAX.to_string());
+
+        let policy: TransferPolicy = serde_json::from_str(
+            r#"{"tokens": {"t": {"per_call": 5, "daily": "340282366920938463463374607431768211455"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(policy.limits("any", "t").daily, u128::MAX);
+        assert_eq!(policy.limits("any", "other"), TokenLimits::default());
+        let back: TransferPolicy =
+            serde_json::from_str(&serde_json::to_string(&policy).unwrap()).unwrap();
+        assert_eq!(back, policy);
+    }
+}
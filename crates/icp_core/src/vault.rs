@@ -20,8 +20,8 @@ const ARGON2_TIME_COST: u32 = 3;
 const ARGON2_MEMORY_COST: u32 = 65536; // 64 MB
 const ARGON2_PARALLELISM: u32 = 4;
 const ARGON2_OUTPUT_LEN: usize = 32;
-const SALT_LEN: usize = 16;
-const NONCE_LEN: usize = 12;
+pub(crate) const SALT_LEN: usize = 16;
+pub(crate) const NONCE_LEN: usize = 12;
 
 /// Encrypted vault data with all components needed for decryption
 #[derive(Debug, Clone)]
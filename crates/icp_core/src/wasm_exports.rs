@@ -1,8 +1,12 @@
 //! Wasm-compatible exports for use in Cloudflare Workers and other JavaScript environments
 #![cfg(target_arch = "wasm32")]
 
-use crate::{js_engine::static_analysis, JsValidationContext};
-use serde_json::json;
+use crate::cancellation::{self, CancelToken};
+use crate::candid_value;
+use crate::identity::{self, IdentityData, KeyAlgorithm};
+use crate::principal::{self, SUBACCOUNT_LEN};
+use crate::{js_engine::static_analysis, keypair, marketplace_auth, JsValidationContext};
+use serde_json::{json, Value};
 use wasm_bindgen::prelude::*;
 
 /// Wasm-compatible JavaScript/TypeScript validation using PURE-RUST static
@@ -23,6 +27,10 @@ pub fn validate_js_script_wasm(
         is_production,
     };
     let result = static_analysis::run_static_stages(script, Some(context));
+    validation_json(&result).to_string()
+}
+
+fn validation_json(result: &crate::JsValidationResult) -> Value {
     json!({
         "is_valid": result.is_valid,
         "syntax_errors": result.syntax_errors,
@@ -30,7 +38,103 @@ pub fn validate_js_script_wasm(
         "line_count": result.line_count,
         "character_count": result.character_count
     })
-    .to_string()
+}
+
+/// Incremental counterpart of [`validate_js_script_wasm`] for large scripts.
+///
+/// A web worker feeds the file in `Uint8Array` slices via [`push_chunk`],
+/// so no single multi-megabyte string has to cross the JS boundary, then
+/// calls [`finish`] once. Chunks may split UTF-8 sequences; decoding happens
+/// on the assembled bytes.
+///
+/// [`push_chunk`]: StreamingValidator::push_chunk
+/// [`finish`]: StreamingValidator::finish
+#[wasm_bindgen]
+pub struct StreamingValidator {
+    buffer: Vec<u8>,
+    expected_len: Option<usize>,
+}
+
+#[wasm_bindgen]
+impl StreamingValidator {
+    /// `expected_len` is the total byte size if known (e.g. `File.size`);
+    /// it only drives the progress fraction.
+    #[wasm_bindgen(constructor)]
+    pub fn new(expected_len: Option<u32>) -> StreamingValidator {
+        let expected_len = expected_len.map(|n| n as usize);
+        StreamingValidator {
+            buffer: Vec::with_capacity(expected_len.unwrap_or(0)),
+            expected_len,
+        }
+    }
+
+    /// Appends a chunk and returns upload progress in `[0, 1]`, or `-1` when
+    /// the total size was not given.
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> f64 {
+        self.buffer.extend_from_slice(chunk);
+        self.received_fraction()
+    }
+
+    pub fn bytes_received(&self) -> u32 {
+        self.buffer.len() as u32
+    }
+
+    fn received_fraction(&self) -> f64 {
+        match self.expected_len {
+            Some(0) => 1.0,
+            Some(total) => (self.buffer.len() as f64 / total as f64).min(1.0),
+            None => -1.0,
+        }
+    }
+
+    /// Runs every static stage over the assembled script. `on_progress`, if
+    /// given, is called as `on_progress(stage, completed, total)` after each
+    /// stage; returning `false` from it cancels the remaining stages. Returns
+    /// the same JSON as [`validate_js_script_wasm`], or `{ ok: false, error }`
+    /// when the bytes are not valid UTF-8 or validation was cancelled
+    /// (`error: "cancelled"`).
+    pub fn finish(
+        self,
+        is_example: bool,
+        is_test: bool,
+        is_production: bool,
+        on_progress: Option<js_sys::Function>,
+    ) -> String {
+        let script = match String::from_utf8(self.buffer) {
+            Ok(script) => script,
+            Err(e) => return error_json(format!("Script is not valid UTF-8: {}", e)),
+        };
+        let context = JsValidationContext {
+            is_example,
+            is_test,
+            is_production,
+        };
+        let token = CancelToken::new();
+        let result = cancellation::with_token(&token, || {
+            static_analysis::run_static_stages_with_progress(
+                &script,
+                Some(context),
+                |stage, done, total| {
+                    if let Some(callback) = &on_progress {
+                        // A throwing progress callback must not abort validation.
+                        let returned = callback.call3(
+                            &JsValue::NULL,
+                            &JsValue::from_str(stage),
+                            &JsValue::from(done as u32),
+                            &JsValue::from(total as u32),
+                        );
+                        if returned.is_ok_and(|v| v.as_bool() == Some(false)) {
+                            token.cancel();
+                        }
+                    }
+                },
+            )
+        });
+        if token.is_cancelled() {
+            return error_json("cancelled".to_string());
+        }
+        validation_json(&result).to_string()
+    }
 }
 
 /// Wasm-compatible JavaScript/TypeScript lint via PURE-RUST static analysis.
@@ -52,6 +156,164 @@ pub fn check_js_syntax_wasm(script: &str) -> String {
     .to_string()
 }
 
+fn error_json(error: String) -> String {
+    json!({ "ok": false, "error": error }).to_string()
+}
+
+/// Canonical (sorted-key, compact) serialization of an arbitrary JSON value,
+/// identical to the backend's `create_canonical_payload`.
+/// Returns `{ ok, canonical }`.
+#[wasm_bindgen]
+pub fn canonical_payload_wasm(value_json: &str) -> String {
+    match serde_json::from_str::<Value>(value_json) {
+        Ok(value) => json!({
+            "ok": true,
+            "canonical": marketplace_auth::canonical_payload(&value),
+        })
+        .to_string(),
+        Err(e) => error_json(format!("Invalid JSON: {}", e)),
+    }
+}
+
+/// Builds the signed payload for a marketplace `action` (`upload`, `update`,
+/// `delete`) from the request body the client is about to send. `script_id`
+/// is the path id for update/delete. Returns `{ ok, payload, canonical }`.
+#[wasm_bindgen]
+pub fn build_signing_payload_wasm(
+    action: &str,
+    request_json: &str,
+    script_id: Option<String>,
+) -> String {
+    let request = match serde_json::from_str::<Value>(request_json) {
+        Ok(request) => request,
+        Err(e) => return error_json(format!("Invalid request JSON: {}", e)),
+    };
+    match marketplace_auth::payload_for_action(action, &request, script_id.as_deref()) {
+        Ok(payload) => json!({
+            "ok": true,
+            "canonical": marketplace_auth::canonical_payload(&payload),
+            "payload": payload,
+        })
+        .to_string(),
+        Err(e) => error_json(e),
+    }
+}
+
+/// Ed25519 signature (base64) over the UTF-8 bytes of `message`.
+/// Returns `{ ok, signature }`.
+#[wasm_bindgen]
+pub fn sign_ed25519_wasm(message: &str, private_key_b64: &str) -> String {
+    match keypair::sign_ed25519(message.as_bytes(), private_key_b64) {
+        Ok(signature) => json!({ "ok": true, "signature": signature }).to_string(),
+        Err(e) => error_json(e),
+    }
+}
+
+/// Verifies a base64 Ed25519 signature over the UTF-8 bytes of `message`.
+/// Returns `{ ok }` or `{ ok: false, error }`.
+#[wasm_bindgen]
+pub fn verify_ed25519_wasm(message: &str, signature_b64: &str, public_key_b64: &str) -> String {
+    match marketplace_auth::verify_ed25519(message.as_bytes(), signature_b64, public_key_b64) {
+        Ok(()) => json!({ "ok": true }).to_string(),
+        Err(e) => error_json(e),
+    }
+}
+
+/// Principal text validation and classification.
+/// Returns `{ ok, principal, kind, account_identifier }`.
+#[wasm_bindgen]
+pub fn principal_info_wasm(principal_text: &str) -> String {
+    match principal::parse_principal_text(principal_text) {
+        Ok(parsed) => json!({
+            "ok": true,
+            "principal": parsed.to_text(),
+            "kind": principal::classify_principal(&parsed),
+            "account_identifier": principal::account_identifier(&parsed, None),
+        })
+        .to_string(),
+        Err(e) => error_json(e.to_string()),
+    }
+}
+
+/// Ledger account identifier for a principal and optional 32-byte hex
+/// subaccount. Returns `{ ok, account_identifier }`.
+#[wasm_bindgen]
+pub fn account_identifier_wasm(principal_text: &str, subaccount_hex: Option<String>) -> String {
+    let parsed = match principal::parse_principal_text(principal_text) {
+        Ok(parsed) => parsed,
+        Err(e) => return error_json(e.to_string()),
+    };
+    let subaccount: Option<[u8; SUBACCOUNT_LEN]> = match subaccount_hex.as_deref() {
+        None | Some("") => None,
+        Some(h) => match hex::decode(h).map(<[u8; SUBACCOUNT_LEN]>::try_from) {
+            Ok(Ok(sub)) => Some(sub),
+            _ => return error_json("Subaccount must be 32 bytes of hex".to_string()),
+        },
+    };
+    json!({
+        "ok": true,
+        "account_identifier": principal::account_identifier(&parsed, subaccount.as_ref()),
+    })
+    .to_string()
+}
+
+/// Derives an identity from a BIP39 mnemonic, the same way the app does.
+/// `algorithm` is `ed25519` or `secp256k1`. Returns `{ ok, identity }`.
+#[wasm_bindgen]
+pub fn identity_from_mnemonic_wasm(algorithm: &str, mnemonic: &str) -> String {
+    let algorithm = match KeyAlgorithm::parse(algorithm) {
+        Ok(algorithm) => algorithm,
+        Err(e) => return error_json(e),
+    };
+    if bip39::Mnemonic::parse_in(bip39::Language::English, mnemonic).is_err() {
+        return error_json("Invalid BIP39 mnemonic".to_string());
+    }
+    let keypair = match algorithm {
+        KeyAlgorithm::Ed25519 => keypair::generate_ed25519_keypair(Some(mnemonic.to_string())),
+        KeyAlgorithm::Secp256k1 => keypair::generate_secp256k1_keypair(Some(mnemonic.to_string())),
+    };
+    json!({ "ok": true, "identity": IdentityData::from_keypair(algorithm, &keypair) }).to_string()
+}
+
+/// Imports a dfx-style PEM identity. Returns `{ ok, identity }`.
+#[wasm_bindgen]
+pub fn import_identity_pem_wasm(pem: &str) -> String {
+    match identity::import_pem_identity(pem) {
+        Ok(identity) => json!({ "ok": true, "identity": identity }).to_string(),
+        Err(e) => error_json(e),
+    }
+}
+
+/// Parses Candid textual arguments (`(record { amount = 1_000 : nat })`).
+/// Returns `{ ok, args, text }`: the tagged JSON of `candid_value` and the
+/// normalized Candid text.
+#[wasm_bindgen]
+pub fn candid_parse_args_wasm(args_text: &str) -> String {
+    match candid_value::parse_args(args_text) {
+        Ok(args) => json!({
+            "ok": true,
+            "args": candid_value::args_to_json(&args),
+            "text": candid_value::format_args(&args),
+        })
+        .to_string(),
+        Err(e) => error_json(e.to_string()),
+    }
+}
+
+/// Prints the tagged JSON of `candid_value` back as Candid textual
+/// arguments. Returns `{ ok, text }`.
+#[wasm_bindgen]
+pub fn candid_format_args_wasm(args_json: &str) -> String {
+    let json = match serde_json::from_str::<Value>(args_json) {
+        Ok(json) => json,
+        Err(e) => return error_json(format!("Invalid args JSON: {}", e)),
+    };
+    match candid_value::args_from_json(&json) {
+        Ok(args) => json!({ "ok": true, "text": candid_value::format_args(&args) }).to_string(),
+        Err(e) => error_json(e.to_string()),
+    }
+}
+
 /// Initialize the Wasm module (called once when loading)
 #[wasm_bindgen(start)]
 pub fn main() {
@@ -0,0 +1,101 @@
+//! Golden tests for `parse_candid_interface` over real-world interfaces.
+//!
+//! Each `tests/fixtures/candid/<name>.did` (excerpts of the ICP ledger, NNS
+//! governance, Internet Identity and the cycles minting canister) has a
+//! `<name>.golden` next to it: one line per method, sorted, as
+//! `<name> <kind> (<args>) -> (<rets>)`. A parser change that alters any of
+//! them fails here.
+//!
+//! To accept an intentional change, run
+//! `ICP_CORE_BLESS_CANDID=1 cargo test -p icp_core --test candid_golden_tests`
+//! and review the diff of the `.golden` files.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use icp_core::canister_client::{parse_candid_interface, MethodKind, ParsedInterface};
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/candid")
+}
+
+fn fixtures() -> Vec<PathBuf> {
+    let mut dids: Vec<PathBuf> = std::fs::read_dir(fixtures_dir())
+        .expect("read fixtures dir")
+        .map(|entry| entry.expect("dir entry").path())
+        .filter(|p| p.extension().is_some_and(|e| e == "did"))
+        .collect();
+    dids.sort();
+    assert!(dids.len() >= 4, "candid fixtures missing: {dids:?}");
+    dids
+}
+
+fn render(parsed: &ParsedInterface) -> String {
+    let mut lines: Vec<String> = parsed
+        .methods
+        .iter()
+        .map(|m| {
+            let kind = match m.kind {
+                MethodKind::Query => "query",
+                MethodKind::Update => "update",
+                MethodKind::CompositeQuery => "composite_query",
+            };
+            format!(
+                "{} {kind} ({}) -> ({})",
+                m.name,
+                m.args.join(", "),
+                m.rets.join(", ")
+            )
+        })
+        .collect();
+    lines.sort();
+    lines.join("\n") + "\n"
+}
+
+fn name_of(did: &Path) -> String {
+    did.file_stem().unwrap().to_string_lossy().into_owned()
+}
+
+#[test]
+fn parsed_interfaces_match_goldens() {
+    let bless = std::env::var("ICP_CORE_BLESS_CANDID").is_ok();
+    let mut mismatched = Vec::new();
+    for did in fixtures() {
+        let source = std::fs::read_to_string(&did).expect("read .did");
+        let parsed = parse_candid_interface(&source)
+            .unwrap_or_else(|e| panic!("{} failed to parse: {e}", name_of(&did)));
+        let rendered = render(&parsed);
+        let golden_path = did.with_extension("golden");
+        if bless {
+            std::fs::write(&golden_path, &rendered).expect("write golden");
+            continue;
+        }
+        let golden = std::fs::read_to_string(&golden_path).unwrap_or_default();
+        if golden != rendered {
+            mismatched.push(format!(
+                "{}:\n--- golden\n{golden}--- parsed\n{rendered}",
+                name_of(&did)
+            ));
+        }
+    }
+    assert!(
+        mismatched.is_empty(),
+        "parsed interfaces differ from goldens (bless with ICP_CORE_BLESS_CANDID=1):\n{}",
+        mismatched.join("\n")
+    );
+}
+
+/// Every truncation of a real interface is an error or a smaller interface,
+/// never a panic: a cheap, deterministic stand-in for the fuzz target in
+/// `fuzz/fuzz_targets/parse_candid_interface.rs`.
+#[test]
+fn truncated_interfaces_never_panic() {
+    for did in fixtures() {
+        let source = std::fs::read_to_string(&did).expect("read .did");
+        for end in (0..source.len()).step_by(7) {
+            if source.is_char_boundary(end) {
+                let _ = parse_candid_interface(&source[..end]);
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use icp_core::candid_value::{
+    args_from_json, args_to_json, format_args, parse_args, CandidValueError,
+};
+use serde_json::json;
+
+#[test]
+fn parses_dfx_style_arguments_into_tagged_json() {
+    let args = parse_args(r#"(record { amount = 1_000 : nat; memo = opt "hi" }, 7 : nat8)"#)
+        .expect("parse ok");
+    let json = args_to_json(&args);
+    assert_eq!(json[0]["type"], "record");
+    let fields = json[0]["fields"].as_array().unwrap();
+    let amount = fields.iter().find(|f| f["name"] == "amount").unwrap();
+    assert_eq!(amount["value"], json!({"type": "nat", "value": "1000"}));
+    let memo = fields.iter().find(|f| f["name"] == "memo").unwrap();
+    assert_eq!(
+        memo["value"],
+        json!({"type": "opt", "value": {"type": "text", "value": "hi"}})
+    );
+    assert_eq!(json[1], json!({"type": "nat8", "value": 7}));
+}
+
+#[test]
+fn text_and_json_round_trip() {
+    for text in [
+        r#"(record { amount = 1_000 : nat; to = principal "aaaaa-aa" })"#,
+        r#"(variant { Ok = vec { 1 : int64; -2 : int64 } }, null, true)"#,
+        r#"(blob "\01\02\ff", record { 1 = "one"; 2 = 3.5 : float64 })"#,
+        r#"(opt (5 : nat8), record { "a"; 42 })"#,
+    ] {
+        let args = parse_args(text).expect(text);
+        let json = args_to_json(&args);
+        let back = args_from_json(&json).expect("json back");
+        assert_eq!(back, args, "JSON round trip of {text}");
+        let reparsed = parse_args(&format_args(&args)).expect("reparse printed text");
+        assert_eq!(reparsed, args, "text round trip of {text}");
+    }
+}
+
+#[test]
+fn big_integers_keep_full_precision() {
+    let args =
+        parse_args("(340282366920938463463374607431768211456 : nat, 18446744073709551615 : nat64)")
+            .unwrap();
+    let json = args_to_json(&args);
+    assert_eq!(json[0]["value"], "340282366920938463463374607431768211456");
+    assert_eq!(json[1]["value"], "18446744073709551615");
+    assert_eq!(parse_args(&format_args(&args)).unwrap(), args);
+}
+
+#[test]
+fn single_values_may_omit_parentheses_and_blank_is_no_args() {
+    let bare = parse_args("record { amount = 5 : nat }").unwrap();
+    let wrapped = parse_args("(record { amount = 5 : nat })").unwrap();
+    assert_eq!(bare, wrapped);
+    assert!(parse_args("   ").unwrap().args.is_empty());
+    assert_eq!(format_args(&parse_args("").unwrap()), "()");
+}
+
+#[test]
+fn errors_are_precise() {
+    assert!(matches!(
+        parse_args("(record { amount = })"),
+        Err(CandidValueError::Parse(_))
+    ));
+
+    let err = args_from_json(&json!([
+        {"type": "record", "fields": [{"name": "amount", "value": {"type": "nat", "value": "-1"}}]}
+    ]))
+    .unwrap_err();
+    assert_eq!(
+        err,
+        CandidValueError::Invalid {
+            path: "args[0].fields[0]".into(),
+            reason: "`-1` is not a valid nat".into(),
+        }
+    );
+
+    assert!(args_from_json(&json!({"type": "nat"})).is_err());
+    assert!(args_from_json(&json!([{"type": "decimal", "value": "1"}])).is_err());
+    assert!(args_from_json(&json!([{"type": "nat8", "value": 300}])).is_err());
+}
@@ -0,0 +1,156 @@
+//! Guards the C ABI against silent changes.
+//!
+//! `ffi_abi.lock` records every `#[no_mangle]` signature in `src/ffi.rs`,
+//! and every `extern "C"` callback typedef the host implements (see
+//! [`ABI_SOURCES`]), together with the ABI version they belong to. Changing
+//! an export or a callback without
+//! updating the lock fails here; updating the lock requires bumping
+//! `ICP_FFI_ABI_VERSION` so hosts built against the old header refuse to
+//! load the new library.
+//!
+//! To accept an intentional change: bump `ICP_FFI_ABI_VERSION`, then run
+//! `ICP_CORE_BLESS_ABI=1 cargo test -p icp_core --test ffi_abi`.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use icp_core::ffi::{icp_ffi_abi_version, ICP_FFI_ABI_VERSION};
+use std::path::PathBuf;
+
+const LOCK_HEADER: &str = "# icp_core C ABI lock -- see tests/ffi_abi.rs";
+
+/// Where the exports and the callback typedefs they take are declared.
+const ABI_SOURCES: &[&str] = &[
+    "src/ffi.rs",
+    "src/ffi/async_jobs.rs",
+    "src/keystore/callback.rs",
+];
+
+fn manifest_path(rel: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(rel)
+}
+
+/// The code of `lines` up to the first `end`, comments dropped, as one
+/// normalized line (whitespace collapsed, trailing commas removed), so
+/// rustfmt reflows don't count as ABI changes. Returns the line and the
+/// index of the line holding `end`.
+fn collect_until(lines: &[&str], start: usize, end: char) -> (String, usize) {
+    let mut parts = Vec::new();
+    let mut j = start;
+    while j < lines.len() {
+        let code = lines[j].split("//").next().unwrap_or("");
+        if let Some(idx) = code.find(end) {
+            parts.push(code[..idx].to_string());
+            break;
+        }
+        parts.push(code.to_string());
+        j += 1;
+    }
+    let joined = parts
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let normalized = joined
+        .replace(", )", ")")
+        .replace("( ", "(")
+        .replace(" )", ")");
+    (normalized, j)
+}
+
+/// One normalized line per export and per `extern "C"` callback typedef.
+fn exported_signatures(src: &str) -> Vec<String> {
+    let lines: Vec<&str> = src.lines().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line == "#[no_mangle]" {
+            let (signature, j) = collect_until(&lines, i + 1, '{');
+            out.push(signature);
+            i = j;
+        } else if line.starts_with("pub type ") {
+            let (typedef, j) = collect_until(&lines, i, ';');
+            if typedef.contains("extern \"C\" fn") {
+                out.push(typedef);
+            }
+            i = j;
+        }
+        i += 1;
+    }
+    out.sort();
+    out
+}
+
+fn parse_lock(lock: &str) -> (u32, Vec<String>) {
+    let mut version = None;
+    let mut signatures = Vec::new();
+    for line in lock.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if let Some(v) = line.strip_prefix("abi_version = ") {
+            version = Some(v.trim().parse().expect("abi_version must be an integer"));
+        } else {
+            signatures.push(line.to_string());
+        }
+    }
+    (
+        version.expect("ffi_abi.lock has no abi_version line"),
+        signatures,
+    )
+}
+
+#[test]
+fn runtime_version_matches_constant() {
+    assert_eq!(icp_ffi_abi_version(), ICP_FFI_ABI_VERSION);
+}
+
+#[test]
+fn exported_abi_matches_lock() {
+    let src = ABI_SOURCES
+        .iter()
+        .map(|rel| std::fs::read_to_string(manifest_path(rel)).expect(rel))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let current = exported_signatures(&src);
+    assert!(
+        current.iter().any(|s| s.contains("fn icp_free_string(")),
+        "signature extraction found no exports; parser out of date?"
+    );
+    assert!(
+        current
+            .iter()
+            .any(|s| s.starts_with("pub type CompletionCallback ")),
+        "signature extraction found no callback typedefs; parser out of date?"
+    );
+
+    let lock_path = manifest_path("ffi_abi.lock");
+    let lock = std::fs::read_to_string(&lock_path).expect("read ffi_abi.lock");
+    let (locked_version, locked) = parse_lock(&lock);
+
+    if std::env::var("ICP_CORE_BLESS_ABI").is_ok() && current != locked {
+        assert!(
+            ICP_FFI_ABI_VERSION > locked_version,
+            "ABI changed: bump ICP_FFI_ABI_VERSION above {locked_version} before blessing"
+        );
+        let body = format!(
+            "{LOCK_HEADER}\nabi_version = {ICP_FFI_ABI_VERSION}\n{}\n",
+            current.join("\n")
+        );
+        std::fs::write(&lock_path, body).expect("write ffi_abi.lock");
+        return;
+    }
+
+    assert_eq!(
+        locked_version, ICP_FFI_ABI_VERSION,
+        "ICP_FFI_ABI_VERSION changed without re-blessing ffi_abi.lock"
+    );
+    if current != locked {
+        let added: Vec<_> = current.iter().filter(|s| !locked.contains(s)).collect();
+        let removed: Vec<_> = locked.iter().filter(|s| !current.contains(s)).collect();
+        panic!(
+            "C ABI changed without a version bump.\n  added/changed: {added:#?}\n  removed/changed: {removed:#?}\n\
+             Bump ICP_FFI_ABI_VERSION and run ICP_CORE_BLESS_ABI=1 cargo test -p icp_core --test ffi_abi"
+        );
+    }
+}
@@ -21,3 +21,66 @@ fn secp256k1_principal_text_matches_dart() {
     let p2 = principal_from_public_key("secp256k1", &public).unwrap();
     assert_eq!(p2, id.principal_text);
 }
+
+#[test]
+fn principal_text_errors_are_precise() {
+    use icp_core::{parse_principal_text, PrincipalTextError};
+
+    assert!(parse_principal_text(common::ED25519_PRINCIPAL).is_ok());
+    assert_eq!(parse_principal_text(""), Err(PrincipalTextError::Empty));
+    assert_eq!(
+        parse_principal_text(&common::ED25519_PRINCIPAL.to_uppercase()),
+        Err(PrincipalTextError::NotLowercase)
+    );
+    assert_eq!(
+        parse_principal_text("aaaaa_aa"),
+        Err(PrincipalTextError::InvalidCharacter('_'))
+    );
+    assert!(matches!(
+        parse_principal_text("ryjl3-tyaaa-aaaaa-aaaba-caa"),
+        Err(PrincipalTextError::ChecksumMismatch { .. })
+    ));
+    assert!(matches!(
+        parse_principal_text("ryjl3tyaaa-aaaaa-aaaba-cai"),
+        Err(PrincipalTextError::NotCanonical(_))
+    ));
+}
+
+#[test]
+fn principals_are_classified_by_type_byte() {
+    use icp_core::{classify_principal, parse_principal_text, PrincipalKind};
+
+    let kind = |t: &str| classify_principal(&parse_principal_text(t).unwrap());
+    assert_eq!(
+        kind(common::ED25519_PRINCIPAL),
+        PrincipalKind::SelfAuthenticating
+    );
+    assert_eq!(kind("ryjl3-tyaaa-aaaaa-aaaba-cai"), PrincipalKind::Opaque);
+    assert_eq!(kind("2vxsx-fae"), PrincipalKind::Anonymous);
+    assert_eq!(kind("aaaaa-aa"), PrincipalKind::ManagementCanister);
+}
+
+#[test]
+fn account_identifier_matches_known_vector() {
+    use candid::Principal;
+    use icp_core::{account_identifier, subaccount_from_index, validate_account_identifier};
+
+    // Anonymous principal, default subaccount (as shown by `dfx ledger account-id`).
+    let anonymous = Principal::anonymous();
+    let id = account_identifier(&anonymous, None);
+    assert_eq!(
+        id,
+        "1c7a48ba6a562aa9eaa2481a9049cdf0433b9738c992d698c31d8abf89cadc79"
+    );
+    validate_account_identifier(&id).unwrap();
+
+    let sub = account_identifier(&anonymous, Some(&subaccount_from_index(1)));
+    assert_ne!(sub, id);
+    validate_account_identifier(&sub).unwrap();
+
+    let mut corrupted = id.into_bytes();
+    corrupted[0] = if corrupted[0] == b'0' { b'1' } else { b'0' };
+    let err = validate_account_identifier(std::str::from_utf8(&corrupted).unwrap()).unwrap_err();
+    assert!(err.contains("checksum mismatch"), "got: {err}");
+    assert!(validate_account_identifier("abc").is_err());
+}
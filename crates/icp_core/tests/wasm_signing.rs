@@ -0,0 +1,77 @@
+//! wasm-bindgen tests for the signing exports. Run with
+//! `wasm-pack test --node crates/icp_core -- --test wasm_signing`.
+//!
+//! The fixture is `dart_generated_update_signature_verifies` from
+//! `backend/tests/signature_tests.rs` (secret key `[11u8; 32]`), trimmed to
+//! the fields the web client sends.
+#![cfg(target_arch = "wasm32")]
+
+use icp_core::wasm_exports::{
+    build_signing_payload_wasm, canonical_payload_wasm, sign_ed25519_wasm, verify_ed25519_wasm,
+};
+use serde_json::{json, Value};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const SECRET_B64: &str = "CwsLCwsLCwsLCwsLCwsLCwsLCwsLCwsLCwsLCwsLCws=";
+const PUBLIC_B64: &str = "Zr5+Myx6RTMyvZ0Kf32wVfXF7xoGraZtmLOftoEMRzo=";
+const SCRIPT_ID: &str = "41935708-8561-4424-a42f-cba44e26785a";
+const CANONICAL: &str = r#"{"action":"update","author_principal":"yhnve-5y5qy-svqjc-aiobw-3a53m-n2gzt-xlrvn-s7kld-r5xid-td2ef-iae","is_public":true,"price":0.0,"script_id":"41935708-8561-4424-a42f-cba44e26785a","tags":["test","unit"],"timestamp":"2025-11-06T13:36:31.766449Z","title":"Updated Title"}"#;
+const SIGNATURE_B64: &str =
+    "2umMyrRT5PEnn4d8FiHV2oWhc3oPuRVw9fOWrra4yqhWU4MPlto5HBUneH6fG7rxD+yBXO/iPOIsYbpvW9ntCQ==";
+
+fn parse(out: String) -> Value {
+    serde_json::from_str(&out).unwrap()
+}
+
+fn update_request() -> String {
+    json!({
+        "script_id": SCRIPT_ID,
+        "timestamp": "2025-11-06T13:36:31.766449Z",
+        "author_principal": "yhnve-5y5qy-svqjc-aiobw-3a53m-n2gzt-xlrvn-s7kld-r5xid-td2ef-iae",
+        "title": "Updated Title",
+        "price": 0,
+        "is_public": true,
+        "tags": ["unit", "test"],
+        "author_public_key": PUBLIC_B64,
+    })
+    .to_string()
+}
+
+#[wasm_bindgen_test]
+fn update_payload_matches_backend_fixture() {
+    let out = parse(build_signing_payload_wasm(
+        "update",
+        &update_request(),
+        Some(SCRIPT_ID.to_string()),
+    ));
+    assert_eq!(out["ok"], true);
+    assert_eq!(out["canonical"], CANONICAL);
+
+    let again = parse(canonical_payload_wasm(&out["payload"].to_string()));
+    assert_eq!(again["canonical"], CANONICAL);
+}
+
+#[wasm_bindgen_test]
+fn signature_matches_backend_fixture() {
+    let out = parse(sign_ed25519_wasm(CANONICAL, SECRET_B64));
+    assert_eq!(out["signature"], SIGNATURE_B64);
+    assert_eq!(
+        parse(verify_ed25519_wasm(CANONICAL, SIGNATURE_B64, PUBLIC_B64))["ok"],
+        true
+    );
+    let tampered = CANONICAL.replace("Updated", "Changed");
+    assert_eq!(
+        parse(verify_ed25519_wasm(&tampered, SIGNATURE_B64, PUBLIC_B64))["ok"],
+        false
+    );
+}
+
+#[wasm_bindgen_test]
+fn invalid_input_is_reported_not_thrown() {
+    assert_eq!(parse(canonical_payload_wasm("{"))["ok"], false);
+    assert_eq!(
+        parse(build_signing_payload_wasm("delete", "{}", None))["ok"],
+        false
+    );
+    assert_eq!(parse(sign_ed25519_wasm("m", "not base64"))["ok"], false);
+}
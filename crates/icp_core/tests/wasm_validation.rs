@@ -0,0 +1,38 @@
+//! wasm-bindgen tests for the streaming validator. Run with
+//! `wasm-pack test --node crates/icp_core -- --test wasm_validation`.
+#![cfg(target_arch = "wasm32")]
+
+use icp_core::wasm_exports::{validate_js_script_wasm, StreamingValidator};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const SCRIPT: &str = "// é\nexport function init(arg) { return { state: {}, effects: [] }; }\nexport function view(state) { return { type: \"text\", props: { text: \"hi\" } }; }\nexport function update(msg, state) { return { state: state, effects: [] }; }\n";
+
+#[wasm_bindgen_test]
+fn chunked_result_matches_one_shot() {
+    let bytes = SCRIPT.as_bytes();
+    let mut validator = StreamingValidator::new(Some(bytes.len() as u32));
+    let mut last = 0.0;
+    // 4-byte chunks split the two-byte 'é' across a boundary.
+    for chunk in bytes.chunks(4) {
+        let progress = validator.push_chunk(chunk);
+        assert!(progress >= last);
+        last = progress;
+    }
+    assert_eq!(last, 1.0);
+    assert_eq!(validator.bytes_received() as usize, bytes.len());
+
+    let streamed = validator.finish(false, false, true, None);
+    assert_eq!(
+        streamed,
+        validate_js_script_wasm(SCRIPT, false, false, true)
+    );
+}
+
+#[wasm_bindgen_test]
+fn invalid_utf8_is_reported() {
+    let mut validator = StreamingValidator::new(None);
+    assert_eq!(validator.push_chunk(&[0xff, 0xfe]), -1.0);
+    assert!(validator
+        .finish(false, false, true, None)
+        .contains("not valid UTF-8"));
+}
@@ -0,0 +1,463 @@
+//! `icp-script`: lint, sign and publish marketplace scripts.
+//!
+//! ```text
+//! icp-script lint dist/app.js
+//! icp-script publish dist/app.js --slug hello --title Hello \
+//!     --description "Says hello" --category Utility --dfx-identity ci
+//! icp-script update <script-id> dist/app.js --version 1.1.0 --identity key.pem
+//! icp-script smoke --api-url https://staging.example.org
+//! ```
+//!
+//! Exit codes: 0 success, 1 lint errors, 2 usage/identity errors, 3 API errors.
+//! With `--output json` a single JSON object is written to stdout
+//! (`{ok, exit_code, error?, lint?, steps, result?}`) for CI to gate on.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use icp_core::{generate_ed25519_keypair, IdentityData, KeyAlgorithm};
+use icp_script::{
+    build_delete_request, build_update_request, build_upload_request, dfx_identity_path,
+    format_lint, lint, lint_report_json, load_identity, timestamp_now, ScriptChanges,
+    ScriptMetadata, SMOKE_TEST_BUNDLE,
+};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const DEFAULT_API_URL: &str = "https://icp-mp.kalaj.org";
+
+#[derive(Parser)]
+#[command(
+    name = "icp-script",
+    version,
+    about = "Lint, sign and publish marketplace scripts"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Output format; `json` prints one machine-readable result object.
+    #[arg(long, global = true, value_enum, default_value_t = Output::Text)]
+    output: Output,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validate a script bundle with the same rules the app applies.
+    Lint { file: PathBuf },
+    /// Upload a new script.
+    Publish {
+        file: PathBuf,
+        #[arg(long)]
+        slug: String,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        description: String,
+        #[arg(long)]
+        category: String,
+        #[arg(long)]
+        version: Option<String>,
+        /// Comma-separated tags.
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+        #[arg(long)]
+        compatibility: Option<String>,
+        /// Upload as a private draft.
+        #[arg(long)]
+        private: bool,
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+    /// Replace the bundle and/or metadata of an existing script.
+    Update {
+        script_id: String,
+        file: Option<PathBuf>,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        category: Option<String>,
+        #[arg(long)]
+        version: Option<String>,
+        #[arg(long, value_delimiter = ',')]
+        tags: Option<Vec<String>>,
+        #[arg(long)]
+        public: Option<bool>,
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+    /// Exercise the signed write path (upload, update, publish, delete) with a
+    /// throwaway identity, verifying signatures are accepted by the target.
+    Smoke {
+        #[arg(long, env = "PUBLIC_API_ENDPOINT", default_value = DEFAULT_API_URL)]
+        api_url: String,
+    },
+}
+
+#[derive(Args)]
+struct CommonArgs {
+    /// PEM (dfx) or identity JSON file to sign with.
+    #[arg(long, conflicts_with = "dfx_identity")]
+    identity: Option<PathBuf>,
+    /// Name of a dfx identity under ~/.config/dfx/identity.
+    #[arg(long)]
+    dfx_identity: Option<String>,
+    #[arg(long, env = "PUBLIC_API_ENDPOINT", default_value = DEFAULT_API_URL)]
+    api_url: String,
+    /// Print the signed request instead of sending it.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+enum Failure {
+    Lint,
+    Usage(String),
+    Api(String),
+}
+
+impl Failure {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Failure::Lint => 1,
+            Failure::Usage(_) => 2,
+            Failure::Api(_) => 3,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Failure::Lint => "lint failed",
+            Failure::Usage(e) | Failure::Api(e) => e,
+        }
+    }
+}
+
+/// Collects what a command did. In text mode everything is printed as it
+/// happens; in JSON mode it is emitted once at exit.
+struct Report {
+    output: Output,
+    lint: Option<Value>,
+    steps: Vec<String>,
+    result: Option<Value>,
+}
+
+impl Report {
+    fn new(output: Output) -> Self {
+        Report {
+            output,
+            lint: None,
+            steps: Vec::new(),
+            result: None,
+        }
+    }
+
+    fn lint(&mut self, path: &Path, result: &icp_core::JsValidationResult) {
+        let path = path.display().to_string();
+        if self.output == Output::Text {
+            eprint!("{}", format_lint(&path, result));
+        }
+        self.lint = Some(lint_report_json(&path, result));
+    }
+
+    fn step(&mut self, name: &str, detail: &str) {
+        if self.output == Output::Text {
+            eprintln!("ok  {:<9}{}", name, detail);
+        }
+        self.steps.push(name.to_string());
+    }
+
+    fn result(&mut self, value: Value) {
+        if self.output == Output::Text {
+            match &value {
+                Value::String(text) => println!("{}", text),
+                other => println!("{}", other),
+            }
+        }
+        self.result = Some(value);
+    }
+
+    fn finish(self, outcome: &Result<(), Failure>) {
+        if self.output == Output::Text {
+            if let Err(failure @ (Failure::Usage(_) | Failure::Api(_))) = outcome {
+                eprintln!("error: {}", failure.message());
+            }
+            return;
+        }
+        let mut out = json!({
+            "ok": outcome.is_ok(),
+            "exit_code": outcome.as_ref().err().map_or(0, Failure::exit_code),
+            "steps": self.steps,
+        });
+        if let Err(failure) = outcome {
+            out["error"] = failure.message().into();
+        }
+        if let Some(lint) = self.lint {
+            out["lint"] = lint;
+        }
+        if let Some(result) = self.result {
+            out["result"] = result;
+        }
+        println!("{}", out);
+    }
+}
+
+fn read_script(path: &Path) -> Result<String, Failure> {
+    std::fs::read_to_string(path)
+        .map_err(|e| Failure::Usage(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn lint_or_fail(report: &mut Report, path: &Path, script: &str) -> Result<(), Failure> {
+    let result = lint(script);
+    report.lint(path, &result);
+    if result.is_valid {
+        Ok(())
+    } else {
+        Err(Failure::Lint)
+    }
+}
+
+fn identity(common: &CommonArgs) -> Result<IdentityData, Failure> {
+    let path = match (&common.identity, &common.dfx_identity) {
+        (Some(path), _) => path.clone(),
+        (None, Some(name)) => dfx_identity_path(name).map_err(Failure::Usage)?,
+        (None, None) => {
+            return Err(Failure::Usage(
+                "Pass --identity <file> or --dfx-identity <name>".to_string(),
+            ))
+        }
+    };
+    load_identity(&path).map_err(Failure::Usage)
+}
+
+fn send(
+    report: &mut Report,
+    common: &CommonArgs,
+    method: reqwest::Method,
+    path: &str,
+    body: &Value,
+) -> Result<(), Failure> {
+    if common.dry_run {
+        report.result(match report.output {
+            Output::Text => format!(
+                "{} {}\n{}",
+                method,
+                path,
+                serde_json::to_string_pretty(body).unwrap_or_default()
+            )
+            .into(),
+            Output::Json => json!({ "method": method.as_str(), "path": path, "body": body }),
+        });
+        return Ok(());
+    }
+    let data = request(&common.api_url, method, path, Some(body))?;
+    report.result(data);
+    Ok(())
+}
+
+/// Sends one API request and returns the `data` field of a successful reply.
+fn request(
+    api_url: &str,
+    method: reqwest::Method,
+    path: &str,
+    body: Option<&Value>,
+) -> Result<Value, Failure> {
+    let url = format!("{}{}", api_url.trim_end_matches('/'), path);
+    let mut builder = reqwest::blocking::Client::new().request(method, &url);
+    if let Some(body) = body {
+        builder = builder.json(body);
+    }
+    let response = builder
+        .send()
+        .map_err(|e| Failure::Api(format!("{}: {}", url, e)))?;
+    let status = response.status();
+    let reply: Value = response
+        .json()
+        .map_err(|e| Failure::Api(format!("{} returned invalid JSON: {}", status, e)))?;
+    if !status.is_success() || reply.get("success") != Some(&Value::Bool(true)) {
+        let error = reply
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("request failed");
+        return Err(Failure::Api(format!("{} {}: {}", status, path, error)));
+    }
+    Ok(reply.get("data").cloned().unwrap_or(Value::Null))
+}
+
+fn smoke(report: &mut Report, api_url: &str) -> Result<(), Failure> {
+    let identity =
+        IdentityData::from_keypair(KeyAlgorithm::Ed25519, &generate_ed25519_keypair(None));
+    let meta = ScriptMetadata {
+        slug: format!("icp-script-smoke-{}", chrono::Utc::now().timestamp_millis()),
+        title: "icp-script smoke test".to_string(),
+        description: "Temporary script created and deleted by `icp-script smoke`".to_string(),
+        category: "utility".to_string(),
+        is_public: false,
+        ..Default::default()
+    };
+    let body = build_upload_request(&meta, SMOKE_TEST_BUNDLE, &identity, &timestamp_now())
+        .map_err(Failure::Usage)?;
+    let created = request(
+        api_url,
+        reqwest::Method::POST,
+        "/api/v1/scripts",
+        Some(&body),
+    )?;
+    let script_id = created
+        .get("id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Failure::Api("upload reply has no script id".to_string()))?
+        .to_string();
+    report.step("upload", &script_id);
+
+    let result = smoke_steps(report, api_url, &identity, &script_id);
+    // Always try to remove the fixture, even when a middle step failed.
+    let path = format!("/api/v1/scripts/{}", script_id);
+    let body =
+        build_delete_request(&script_id, &identity, &timestamp_now()).map_err(Failure::Usage)?;
+    let deleted = request(api_url, reqwest::Method::DELETE, &path, Some(&body));
+    result?;
+    deleted?;
+    report.step("delete", "");
+    Ok(())
+}
+
+fn smoke_steps(
+    report: &mut Report,
+    api_url: &str,
+    identity: &IdentityData,
+    script_id: &str,
+) -> Result<(), Failure> {
+    let path = format!("/api/v1/scripts/{}", script_id);
+    let changes = ScriptChanges {
+        version: Some("1.0.1".to_string()),
+        ..Default::default()
+    };
+    let body = build_update_request(script_id, &changes, identity, &timestamp_now())
+        .map_err(Failure::Usage)?;
+    request(api_url, reqwest::Method::PUT, &path, Some(&body))?;
+    report.step("update", "");
+
+    // Publishing signs the same payload as an `is_public: true` update.
+    let changes = ScriptChanges {
+        is_public: Some(true),
+        ..Default::default()
+    };
+    let body = build_update_request(script_id, &changes, identity, &timestamp_now())
+        .map_err(Failure::Usage)?;
+    request(
+        api_url,
+        reqwest::Method::POST,
+        &format!("{}/publish", path),
+        Some(&body),
+    )?;
+    report.step("publish", "");
+
+    let script = request(api_url, reqwest::Method::GET, &path, None)?;
+    if script.get("version") != Some(&Value::from("1.0.1"))
+        || script.get("is_public") != Some(&Value::Bool(true))
+    {
+        return Err(Failure::Api(format!(
+            "read-back mismatch: version={} is_public={}",
+            script.get("version").unwrap_or(&Value::Null),
+            script.get("is_public").unwrap_or(&Value::Null)
+        )));
+    }
+    report.step("read-back", "");
+    Ok(())
+}
+
+fn run(command: Command, report: &mut Report) -> Result<(), Failure> {
+    match command {
+        Command::Lint { file } => lint_or_fail(report, &file, &read_script(&file)?),
+        Command::Publish {
+            file,
+            slug,
+            title,
+            description,
+            category,
+            version,
+            tags,
+            compatibility,
+            private,
+            common,
+        } => {
+            let bundle = read_script(&file)?;
+            lint_or_fail(report, &file, &bundle)?;
+            let identity = identity(&common)?;
+            let meta = ScriptMetadata {
+                slug,
+                title,
+                description,
+                category,
+                version,
+                tags,
+                compatibility,
+                is_public: !private,
+            };
+            let body = build_upload_request(&meta, &bundle, &identity, &timestamp_now())
+                .map_err(Failure::Usage)?;
+            send(
+                report,
+                &common,
+                reqwest::Method::POST,
+                "/api/v1/scripts",
+                &body,
+            )
+        }
+        Command::Update {
+            script_id,
+            file,
+            title,
+            description,
+            category,
+            version,
+            tags,
+            public,
+            common,
+        } => {
+            let bundle = match &file {
+                Some(file) => {
+                    let bundle = read_script(file)?;
+                    lint_or_fail(report, file, &bundle)?;
+                    Some(bundle)
+                }
+                None => None,
+            };
+            let identity = identity(&common)?;
+            let changes = ScriptChanges {
+                title,
+                description,
+                category,
+                bundle,
+                version,
+                tags,
+                is_public: public,
+            };
+            let body = build_update_request(&script_id, &changes, &identity, &timestamp_now())
+                .map_err(Failure::Usage)?;
+            send(
+                report,
+                &common,
+                reqwest::Method::PUT,
+                &format!("/api/v1/scripts/{}", script_id),
+                &body,
+            )
+        }
+        Command::Smoke { api_url } => smoke(report, &api_url),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut report = Report::new(cli.output);
+    let outcome = run(cli.command, &mut report);
+    let code = outcome.as_ref().err().map_or(0, Failure::exit_code);
+    report.finish(&outcome);
+    ExitCode::from(code)
+}
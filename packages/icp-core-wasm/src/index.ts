@@ -0,0 +1,224 @@
+// Promise-based wrapper over the wasm-bindgen exports of crates/icp_core
+// (src/wasm_exports.rs). The raw exports return JSON strings shaped
+// `{ ok: true, ... } | { ok: false, error }`; this module loads the wasm once,
+// parses those strings into typed values and turns `ok: false` into a thrown
+// IcpCoreError. The untouched bindings are available as
+// "@icp-cc/icp-core-wasm/raw".
+
+import initWasm, * as raw from "../pkg/icp_core.js";
+
+export type InitInput = Parameters<typeof initWasm>[0];
+
+let ready: Promise<void> | undefined;
+
+/**
+ * Loads and instantiates the wasm module. Called implicitly by every helper;
+ * call it explicitly to pass a custom URL/bytes (e.g. inside a worker).
+ */
+export function init(input?: InitInput): Promise<void> {
+  ready ??= initWasm(input === undefined ? undefined : { module_or_path: input }).then(
+    () => undefined,
+  );
+  return ready;
+}
+
+export class IcpCoreError extends Error {
+  override name = "IcpCoreError";
+}
+
+type Envelope<T> = ({ ok: true } & T) | { ok: false; error: string };
+
+function unwrap<T>(json: string): T {
+  const parsed = JSON.parse(json) as Envelope<T>;
+  if (!parsed.ok) throw new IcpCoreError(parsed.error);
+  return parsed;
+}
+
+// ---- script validation ----
+
+export interface ValidationContext {
+  isExample?: boolean;
+  isTest?: boolean;
+  isProduction?: boolean;
+}
+
+export interface ValidationResult {
+  is_valid: boolean;
+  syntax_errors: string[];
+  warnings: string[];
+  line_count: number;
+  character_count: number;
+}
+
+export type ValidationProgress =
+  | { phase: "reading"; bytesReceived: number; fraction: number | null }
+  | { phase: "validating"; stage: string; completed: number; total: number };
+
+function contextArgs(ctx: ValidationContext): [boolean, boolean, boolean] {
+  const isExample = ctx.isExample ?? false;
+  const isTest = ctx.isTest ?? false;
+  return [isExample, isTest, ctx.isProduction ?? (!isExample && !isTest)];
+}
+
+export async function validateScript(
+  script: string,
+  ctx: ValidationContext = {},
+): Promise<ValidationResult> {
+  await init();
+  return JSON.parse(raw.validate_js_script_wasm(script, ...contextArgs(ctx))) as ValidationResult;
+}
+
+/**
+ * Validates a script delivered as a Blob/File or byte stream, feeding it to
+ * the wasm side chunk by chunk instead of as one large string. Aborting
+ * `signal` stops reading, or skips the remaining validation stages, and
+ * rejects with an `IcpCoreError("cancelled")`.
+ */
+export async function validateScriptStream(
+  source: Blob | ReadableStream<Uint8Array>,
+  ctx: ValidationContext = {},
+  onProgress?: (progress: ValidationProgress) => void,
+  signal?: AbortSignal,
+): Promise<ValidationResult> {
+  await init();
+  const stream = source instanceof Blob ? source.stream() : source;
+  const expected = source instanceof Blob ? source.size : undefined;
+  const validator = new raw.StreamingValidator(expected);
+  const reader = stream.getReader();
+  try {
+    for (;;) {
+      if (signal?.aborted) throw new IcpCoreError("cancelled");
+      const { done, value } = await reader.read();
+      if (done) break;
+      const fraction = validator.push_chunk(value);
+      onProgress?.({
+        phase: "reading",
+        bytesReceived: validator.bytes_received(),
+        fraction: fraction < 0 ? null : fraction,
+      });
+    }
+  } catch (e) {
+    validator.free();
+    throw e;
+  } finally {
+    reader.releaseLock();
+  }
+  // finish() consumes the validator on the Rust side; returning false from
+  // the progress callback cancels the remaining stages.
+  const out = validator.finish(
+    ...contextArgs(ctx),
+    onProgress || signal
+      ? (stage: string, completed: number, total: number) => {
+          onProgress?.({ phase: "validating", stage, completed, total });
+          return !signal?.aborted;
+        }
+      : undefined,
+  );
+  const parsed = JSON.parse(out) as ValidationResult | { ok: false; error: string };
+  if ("ok" in parsed) throw new IcpCoreError(parsed.error);
+  return parsed;
+}
+
+// ---- marketplace signing ----
+
+export type SigningAction = "upload" | "update" | "delete";
+
+export interface SigningPayload {
+  payload: Record<string, unknown>;
+  /** Exact bytes (as UTF-8) the backend verifies the signature over. */
+  canonical: string;
+}
+
+export async function canonicalPayload(value: unknown): Promise<string> {
+  await init();
+  return unwrap<{ canonical: string }>(raw.canonical_payload_wasm(JSON.stringify(value))).canonical;
+}
+
+export async function buildSigningPayload(
+  action: SigningAction,
+  request: Record<string, unknown>,
+  scriptId?: string,
+): Promise<SigningPayload> {
+  await init();
+  const { payload, canonical } = unwrap<SigningPayload>(
+    raw.build_signing_payload_wasm(action, JSON.stringify(request), scriptId),
+  );
+  return { payload, canonical };
+}
+
+export async function signEd25519(message: string, privateKeyB64: string): Promise<string> {
+  await init();
+  return unwrap<{ signature: string }>(raw.sign_ed25519_wasm(message, privateKeyB64)).signature;
+}
+
+export async function verifyEd25519(
+  message: string,
+  signatureB64: string,
+  publicKeyB64: string,
+): Promise<boolean> {
+  await init();
+  const out = JSON.parse(raw.verify_ed25519_wasm(message, signatureB64, publicKeyB64)) as {
+    ok: boolean;
+  };
+  return out.ok;
+}
+
+// ---- principals ----
+
+export type PrincipalKind =
+  | "management_canister"
+  | "opaque"
+  | "self_authenticating"
+  | "derived"
+  | "anonymous"
+  | "reserved"
+  | "unknown";
+
+export interface PrincipalInfo {
+  principal: string;
+  kind: PrincipalKind;
+  account_identifier: string;
+}
+
+export async function principalInfo(principalText: string): Promise<PrincipalInfo> {
+  await init();
+  const { principal, kind, account_identifier } = unwrap<PrincipalInfo>(
+    raw.principal_info_wasm(principalText),
+  );
+  return { principal, kind, account_identifier };
+}
+
+export async function accountIdentifier(
+  principalText: string,
+  subaccountHex?: string,
+): Promise<string> {
+  await init();
+  return unwrap<{ account_identifier: string }>(
+    raw.account_identifier_wasm(principalText, subaccountHex),
+  ).account_identifier;
+}
+
+// ---- identities ----
+
+export type KeyAlgorithm = "ed25519" | "secp256k1";
+
+export interface Identity {
+  algorithm: KeyAlgorithm;
+  public_key_b64: string;
+  private_key_b64: string;
+  principal_text: string;
+}
+
+export async function identityFromMnemonic(
+  algorithm: KeyAlgorithm,
+  mnemonic: string,
+): Promise<Identity> {
+  await init();
+  return unwrap<{ identity: Identity }>(raw.identity_from_mnemonic_wasm(algorithm, mnemonic))
+    .identity;
+}
+
+export async function importIdentityPem(pem: string): Promise<Identity> {
+  await init();
+  return unwrap<{ identity: Identity }>(raw.import_identity_pem_wasm(pem)).identity;
+}
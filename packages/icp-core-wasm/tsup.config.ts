@@ -0,0 +1,15 @@
+import { defineConfig } from "tsup";
+
+export default defineConfig({
+  entry: ["src/index.ts"],
+  format: ["esm"],
+  dts: true,
+  sourcemap: true,
+  clean: true,
+  treeshake: true,
+  target: "es2022",
+  platform: "neutral",
+  // The wasm-bindgen glue resolves icp_core_bg.wasm relative to its own URL,
+  // so it must stay in pkg/ rather than being inlined into dist/.
+  external: [/\.\.\/pkg\//],
+});